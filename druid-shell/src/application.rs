@@ -42,6 +42,34 @@ pub trait AppHandler {
     fn command(&mut self, id: u32) {}
 }
 
+/// Keeps the display awake while it exists.
+///
+/// This is returned by [`Application::inhibit_screensaver`]; the screen saver is
+/// allowed to start again when all tokens have been dropped.
+#[must_use = "the screen saver is only inhibited until the token is dropped"]
+pub struct InhibitToken {
+    // Dropping the inhibitor releases the inhibition.
+    inhibitor: Option<backend::ScreensaverInhibitor>,
+}
+
+impl InhibitToken {
+    /// Returns `true` if the platform honoured the request.
+    ///
+    /// This is `false` if the platform does not support inhibiting the screen saver,
+    /// or if the request failed.
+    pub fn is_active(&self) -> bool {
+        self.inhibitor.is_some()
+    }
+}
+
+impl std::fmt::Debug for InhibitToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("InhibitToken")
+            .field("active", &self.is_active())
+            .finish()
+    }
+}
+
 /// The top level application object.
 ///
 /// This can be thought of as a reference and it can be safely cloned.
@@ -170,6 +198,26 @@ impl Application {
         self.backend_app.quit()
     }
 
+    /// Prevents the display from sleeping and the screen saver from starting,
+    /// until the returned token is dropped.
+    ///
+    /// This is intended for applications such as media players and presentations,
+    /// which are in use without receiving any input. The `reason` may be shown to
+    /// the user by the platform.
+    ///
+    /// # Platform support
+    ///
+    /// This is supported on Windows, macOS, GTK, and X11 servers that provide the
+    /// MIT-SCREEN-SAVER extension. Elsewhere the returned token is not
+    /// [active](InhibitToken::is_active).
+    pub fn inhibit_screensaver(&self, reason: &str) -> InhibitToken {
+        let inhibitor = self.backend_app.inhibit_screensaver(reason);
+        if inhibitor.is_none() {
+            tracing::warn!("Failed to inhibit the screen saver");
+        }
+        InhibitToken { inhibitor }
+    }
+
     /// Returns a handle to the system clipboard.
     pub fn clipboard(&self) -> Clipboard {
         self.backend_app.clipboard().into()
@@ -183,4 +231,11 @@ impl Application {
     pub fn get_locale() -> String {
         backend::Application::get_locale()
     }
+
+    /// Open `url` with the user's preferred application, such as a web browser.
+    ///
+    /// This returns immediately; failures are logged.
+    pub fn open_url(url: &str) {
+        backend::Application::open_url(url)
+    }
 }
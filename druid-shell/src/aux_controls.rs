@@ -0,0 +1,161 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Controls shown by the platform outside of a window.
+
+use std::ops::RangeInclusive;
+
+use crate::piet::ImageBuf;
+
+/// A control shown by the platform on behalf of a window, outside of its content.
+///
+/// A window declares its auxiliary controls with [`WindowHandle::set_aux_controls`],
+/// and is notified through [`WinHandler::aux_control`] when one of them is used.
+///
+/// On macOS, the controls are shown in the Touch Bar while the window is key.
+/// On Windows, buttons are shown in the thumbnail toolbar of the taskbar preview
+/// of the window; at most seven buttons are supported, and sliders are ignored.
+/// Thumbnail toolbar buttons only show an [`icon`], with the title as tooltip.
+/// On other platforms, auxiliary controls are ignored.
+///
+/// [`WindowHandle::set_aux_controls`]: crate::WindowHandle::set_aux_controls
+/// [`WinHandler::aux_control`]: crate::WinHandler::aux_control
+/// [`icon`]: AuxControl::icon
+#[derive(Debug, Clone)]
+pub struct AuxControl {
+    pub(crate) id: u32,
+    pub(crate) title: String,
+    pub(crate) icon: Option<ImageBuf>,
+    pub(crate) enabled: bool,
+    pub(crate) kind: AuxControlKind,
+}
+
+/// The kind of an [`AuxControl`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuxControlKind {
+    /// A button.
+    Button,
+    /// A slider.
+    Slider {
+        /// The smallest value of the slider.
+        min: f64,
+        /// The largest value of the slider.
+        max: f64,
+        /// The current value of the slider.
+        value: f64,
+    },
+}
+
+impl AuxControl {
+    /// Create a new button.
+    ///
+    /// The `id` is passed to [`WinHandler::aux_control`] when the button is pressed.
+    ///
+    /// [`WinHandler::aux_control`]: crate::WinHandler::aux_control
+    pub fn button(id: u32, title: impl Into<String>) -> AuxControl {
+        AuxControl {
+            id,
+            title: title.into(),
+            icon: None,
+            enabled: true,
+            kind: AuxControlKind::Button,
+        }
+    }
+
+    /// Create a new slider with the provided range and initial value.
+    ///
+    /// The `id` and the new value are passed to [`WinHandler::aux_control`] when the
+    /// slider is moved.
+    ///
+    /// The value is clamped to the range. A reversed range is swapped, and a range
+    /// with a NaN bound is replaced by `0.0..=1.0`.
+    ///
+    /// [`WinHandler::aux_control`]: crate::WinHandler::aux_control
+    pub fn slider(
+        id: u32,
+        title: impl Into<String>,
+        range: RangeInclusive<f64>,
+        value: f64,
+    ) -> AuxControl {
+        let (mut min, mut max) = range.into_inner();
+        if min.is_nan() || max.is_nan() {
+            tracing::warn!("Slider range {min}..={max} is not valid, using 0.0..=1.0");
+            (min, max) = (0.0, 1.0);
+        } else if min > max {
+            tracing::warn!("Slider range {min}..={max} is reversed");
+            std::mem::swap(&mut min, &mut max);
+        }
+        let value = if value.is_nan() {
+            min
+        } else {
+            value.clamp(min, max)
+        };
+        AuxControl {
+            id,
+            title: title.into(),
+            icon: None,
+            enabled: true,
+            kind: AuxControlKind::Slider { min, max, value },
+        }
+    }
+
+    /// Builder-style method to set the icon of the control.
+    ///
+    /// On macOS, the icon replaces the title of a button.
+    pub fn icon(mut self, icon: ImageBuf) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Builder-style method to set whether the control can be used.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// The id of the control.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The title of the control.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The kind of the control.
+    pub fn kind(&self) -> &AuxControlKind {
+        &self.kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slider(range: RangeInclusive<f64>, value: f64) -> AuxControlKind {
+        AuxControl::slider(0, "", range, value).kind
+    }
+
+    fn expected(min: f64, max: f64, value: f64) -> AuxControlKind {
+        AuxControlKind::Slider { min, max, value }
+    }
+
+    #[test]
+    fn slider_range_is_validated() {
+        assert_eq!(slider(1.0..=0.0, 2.0), expected(0.0, 1.0, 1.0));
+        assert_eq!(slider(f64::NAN..=5.0, 3.0), expected(0.0, 1.0, 1.0));
+        assert_eq!(slider(0.0..=5.0, f64::NAN), expected(0.0, 5.0, 0.0));
+    }
+}
@@ -16,11 +16,14 @@
 
 use gtk::gio::prelude::ApplicationExtManual;
 use gtk::gio::{ApplicationFlags, Cancellable};
-use gtk::Application as GtkApplication;
+use gtk::{Application as GtkApplication, ApplicationInhibitFlags};
 
 use gtk::prelude::{ApplicationExt, GtkApplicationExt};
 
 use crate::application::AppHandler;
+use crate::backend::shared::linux;
+use crate::color_scheme::ColorScheme;
+use crate::system_status::SystemStatus;
 
 use super::clipboard::Clipboard;
 use super::error::Error;
@@ -75,12 +78,33 @@ impl Application {
         }
     }
 
+    pub fn inhibit_screensaver(&self, reason: &str) -> Option<ScreensaverInhibitor> {
+        let cookie = self.gtk_app.inhibit(
+            None::<&gtk::Window>,
+            ApplicationInhibitFlags::IDLE,
+            Some(reason),
+        );
+        // A cookie of 0 means the request failed.
+        (cookie != 0).then(|| ScreensaverInhibitor {
+            gtk_app: self.gtk_app.clone(),
+            cookie,
+        })
+    }
+
     pub fn clipboard(&self) -> Clipboard {
         Clipboard {
             selection: gtk::gdk::SELECTION_CLIPBOARD,
         }
     }
 
+    pub fn system_status() -> SystemStatus {
+        linux::status::system_status()
+    }
+
+    pub fn color_scheme() -> ColorScheme {
+        linux::appearance::color_scheme()
+    }
+
     pub fn get_locale() -> String {
         let mut locale: String = gtk::glib::language_names()[0].as_str().into();
         // This is done because the locale parsing library we use expects an unicode locale, but these vars have an ISO locale
@@ -89,6 +113,21 @@ impl Application {
         }
         locale
     }
+
+    pub fn open_url(url: &str) {
+        linux::open::open_url(url)
+    }
+}
+
+pub(crate) struct ScreensaverInhibitor {
+    gtk_app: GtkApplication,
+    cookie: u32,
+}
+
+impl Drop for ScreensaverInhibitor {
+    fn drop(&mut self) {
+        self.gtk_app.uninhibit(self.cookie);
+    }
 }
 
 impl crate::platform::linux::ApplicationExt for crate::Application {
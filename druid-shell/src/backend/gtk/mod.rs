@@ -18,6 +18,8 @@ pub mod application;
 pub mod clipboard;
 pub mod dialog;
 pub mod error;
+#[cfg(feature = "keychain")]
+pub(crate) use crate::backend::shared::linux::keychain;
 pub mod keycodes;
 pub mod menu;
 pub mod screen;
@@ -37,7 +37,8 @@ use gdk_sys::GdkKeymapKey;
 use anyhow::anyhow;
 use cairo::Surface;
 use gtk::gdk::{
-    EventKey, EventMask, EventType, ModifierType, ScrollDirection, Window, WindowTypeHint,
+    EventKey, EventMask, EventType, GrabStatus, ModifierType, ScrollDirection, Seat,
+    SeatCapabilities, Window, WindowTypeHint,
 };
 
 use instant::Duration;
@@ -46,6 +47,7 @@ use tracing::{error, warn};
 #[cfg(feature = "raw-win-handle")]
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle, XcbWindowHandle};
 
+use crate::aux_controls::AuxControl;
 use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
 use crate::piet::{Piet, PietText, RenderContext};
 
@@ -1306,6 +1308,19 @@ impl WindowHandle {
             .get())
     }
 
+    pub fn set_aux_controls(&self, _controls: Vec<AuxControl>) {
+        // Auxiliary controls have no equivalent on this platform.
+    }
+
+    pub(crate) fn begin_secure_input(&self) -> Option<SecureInput> {
+        // Grabbing the keyboard keeps other X clients from reading it. Under Wayland
+        // the compositor already does this, and the grab does no harm.
+        let window = self.state.upgrade()?.window.window()?;
+        let seat = window.display().default_seat()?;
+        let status = seat.grab(&window, SeatCapabilities::KEYBOARD, true, None, None, None);
+        (status == GrabStatus::Success).then_some(SecureInput { seat })
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         if let Some(state) = self.state.upgrade() {
             let window = &state.window;
@@ -1345,6 +1360,17 @@ impl WindowHandle {
 unsafe impl Send for WindowState {}
 unsafe impl Sync for WindowState {}
 
+/// A keyboard grab, released on drop.
+pub(crate) struct SecureInput {
+    seat: Seat,
+}
+
+impl Drop for SecureInput {
+    fn drop(&mut self) {
+        self.seat.ungrab();
+    }
+}
+
 impl IdleHandle {
     /// Add an idle handler, which is called (once) when the message loop
     /// is empty. The idle handler will be run from the main UI thread, and
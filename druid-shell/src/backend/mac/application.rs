@@ -21,7 +21,7 @@ use std::ffi::c_void;
 use std::rc::Rc;
 
 use cocoa::appkit::{NSApp, NSApplication, NSApplicationActivationPolicyRegular};
-use cocoa::base::{id, nil, NO, YES};
+use cocoa::base::{id, nil, BOOL, NO, YES};
 use cocoa::foundation::{NSArray, NSAutoreleasePool};
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
@@ -29,6 +29,8 @@ use objc::{class, msg_send, sel, sel_impl};
 use once_cell::sync::Lazy;
 
 use crate::application::AppHandler;
+use crate::color_scheme::ColorScheme;
+use crate::system_status::SystemStatus;
 
 use super::clipboard::Clipboard;
 use super::error::Error;
@@ -46,6 +48,24 @@ struct State {
     quitting: bool,
 }
 
+// From NSProcessInfo.h
+const NSActivityIdleDisplaySleepDisabled: u64 = 1 << 40;
+const NSActivityUserInitiated: u64 = 0x00FF_FFFF | (1 << 20);
+
+pub(crate) struct ScreensaverInhibitor {
+    activity: id,
+}
+
+impl Drop for ScreensaverInhibitor {
+    fn drop(&mut self) {
+        unsafe {
+            let process_info: id = msg_send![class!(NSProcessInfo), processInfo];
+            let () = msg_send![process_info, endActivity: self.activity];
+            let () = msg_send![self.activity, release];
+        }
+    }
+}
+
 impl Application {
     pub fn new() -> Result<Application, Error> {
         // macOS demands that we run not just on one thread,
@@ -101,10 +121,46 @@ impl Application {
         }
     }
 
+    pub fn inhibit_screensaver(&self, reason: &str) -> Option<ScreensaverInhibitor> {
+        unsafe {
+            let process_info: id = msg_send![class!(NSProcessInfo), processInfo];
+            let options = NSActivityIdleDisplaySleepDisabled | NSActivityUserInitiated;
+            let activity: id = msg_send![process_info,
+                beginActivityWithOptions: options
+                reason: util::make_nsstring(reason)];
+            if activity == nil {
+                return None;
+            }
+            // The activity is autoreleased, but must stay alive until it is ended.
+            let activity: id = msg_send![activity, retain];
+            Some(ScreensaverInhibitor { activity })
+        }
+    }
+
     pub fn clipboard(&self) -> Clipboard {
         Clipboard
     }
 
+    pub fn system_status() -> SystemStatus {
+        // TODO: the power status is available from IOKit (`IOPSCopyPowerSourcesInfo`),
+        // which we don't link yet. Until then, everything is reported as unknown.
+        SystemStatus::default()
+    }
+
+    pub fn color_scheme() -> ColorScheme {
+        unsafe {
+            // only set while the system is in dark mode
+            let defaults: id = msg_send![class!(NSUserDefaults), standardUserDefaults];
+            let style: id =
+                msg_send![defaults, stringForKey: util::make_nsstring("AppleInterfaceStyle")];
+            if style != nil && util::from_nsstring(style) == "Dark" {
+                ColorScheme::Dark
+            } else {
+                ColorScheme::Light
+            }
+        }
+    }
+
     pub fn get_locale() -> String {
         unsafe {
             let nslocale_class = class!(NSLocale);
@@ -118,6 +174,21 @@ impl Application {
             locale
         }
     }
+
+    pub fn open_url(url: &str) {
+        unsafe {
+            let ns_url: id = msg_send![class!(NSURL), URLWithString: util::make_nsstring(url)];
+            if ns_url == nil {
+                tracing::warn!("not a valid URL: {}", url);
+                return;
+            }
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let opened: BOOL = msg_send![workspace, openURL: ns_url];
+            if opened == NO {
+                tracing::warn!("failed to open {}", url);
+            }
+        }
+    }
 }
 
 impl crate::platform::mac::ApplicationExt for crate::Application {
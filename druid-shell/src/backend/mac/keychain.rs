@@ -0,0 +1,170 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Secrets in the macOS Keychain, as generic passwords.
+
+use std::ffi::c_void;
+use std::os::raw::c_char;
+use std::ptr::{null, null_mut};
+use std::slice;
+
+use anyhow::anyhow;
+
+use crate::error::Error;
+
+type OSStatus = i32;
+type SecKeychainItemRef = *mut c_void;
+
+const ERR_SEC_SUCCESS: OSStatus = 0;
+const ERR_SEC_ITEM_NOT_FOUND: OSStatus = -25300;
+
+#[link(name = "Security", kind = "framework")]
+extern "C" {
+    fn SecKeychainAddGenericPassword(
+        keychain: *mut c_void,
+        service_name_length: u32,
+        service_name: *const c_char,
+        account_name_length: u32,
+        account_name: *const c_char,
+        password_length: u32,
+        password_data: *const c_void,
+        item_ref: *mut SecKeychainItemRef,
+    ) -> OSStatus;
+    fn SecKeychainFindGenericPassword(
+        keychain_or_array: *const c_void,
+        service_name_length: u32,
+        service_name: *const c_char,
+        account_name_length: u32,
+        account_name: *const c_char,
+        password_length: *mut u32,
+        password_data: *mut *mut c_void,
+        item_ref: *mut SecKeychainItemRef,
+    ) -> OSStatus;
+    fn SecKeychainItemModifyAttributesAndData(
+        item_ref: SecKeychainItemRef,
+        attr_list: *const c_void,
+        length: u32,
+        data: *const c_void,
+    ) -> OSStatus;
+    fn SecKeychainItemDelete(item_ref: SecKeychainItemRef) -> OSStatus;
+    fn SecKeychainItemFreeContent(attr_list: *mut c_void, data: *mut c_void) -> OSStatus;
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFRelease(cf: *const c_void);
+}
+
+fn keychain_error(action: &str, status: OSStatus) -> Error {
+    anyhow!("failed to {} the password: OSStatus {}", action, status).into()
+}
+
+/// Look up the item for `account` of `service`, optionally copying out its password.
+///
+/// The returned item must be released with `CFRelease`.
+unsafe fn find_item(
+    service: &str,
+    account: &str,
+    password: Option<&mut Vec<u8>>,
+) -> Result<Option<SecKeychainItemRef>, Error> {
+    let mut item: SecKeychainItemRef = null_mut();
+    let mut length = 0u32;
+    let mut data: *mut c_void = null_mut();
+    let want_password = password.is_some();
+    let status = SecKeychainFindGenericPassword(
+        null(),
+        service.len() as u32,
+        service.as_ptr() as *const c_char,
+        account.len() as u32,
+        account.as_ptr() as *const c_char,
+        if want_password {
+            &mut length
+        } else {
+            null_mut()
+        },
+        if want_password { &mut data } else { null_mut() },
+        &mut item,
+    );
+    match status {
+        ERR_SEC_SUCCESS => {
+            if let Some(password) = password {
+                *password = slice::from_raw_parts(data as *const u8, length as usize).to_vec();
+                SecKeychainItemFreeContent(null_mut(), data);
+            }
+            Ok(Some(item))
+        }
+        ERR_SEC_ITEM_NOT_FOUND => Ok(None),
+        status => Err(keychain_error("look up", status)),
+    }
+}
+
+pub(crate) fn set_password(service: &str, account: &str, password: &str) -> Result<(), Error> {
+    unsafe {
+        let status = match find_item(service, account, None)? {
+            Some(item) => {
+                let status = SecKeychainItemModifyAttributesAndData(
+                    item,
+                    null(),
+                    password.len() as u32,
+                    password.as_ptr() as *const c_void,
+                );
+                CFRelease(item);
+                status
+            }
+            None => SecKeychainAddGenericPassword(
+                null_mut(),
+                service.len() as u32,
+                service.as_ptr() as *const c_char,
+                account.len() as u32,
+                account.as_ptr() as *const c_char,
+                password.len() as u32,
+                password.as_ptr() as *const c_void,
+                null_mut(),
+            ),
+        };
+        match status {
+            ERR_SEC_SUCCESS => Ok(()),
+            status => Err(keychain_error("store", status)),
+        }
+    }
+}
+
+pub(crate) fn get_password(service: &str, account: &str) -> Result<Option<String>, Error> {
+    let mut password = Vec::new();
+    match unsafe { find_item(service, account, Some(&mut password))? } {
+        Some(item) => {
+            unsafe { CFRelease(item) };
+            let password = String::from_utf8(password)
+                .map_err(|_| anyhow!("the stored password is not valid UTF-8"))?;
+            Ok(Some(password))
+        }
+        None => Ok(None),
+    }
+}
+
+pub(crate) fn delete_password(service: &str, account: &str) -> Result<bool, Error> {
+    unsafe {
+        match find_item(service, account, None)? {
+            Some(item) => {
+                let status = SecKeychainItemDelete(item);
+                CFRelease(item);
+                match status {
+                    ERR_SEC_SUCCESS => Ok(true),
+                    status => Err(keychain_error("delete", status)),
+                }
+            }
+            None => Ok(false),
+        }
+    }
+}
@@ -22,8 +22,11 @@ pub mod clipboard;
 pub mod dialog;
 pub mod error;
 mod keyboard;
+#[cfg(feature = "keychain")]
+pub mod keychain;
 pub mod menu;
 pub mod screen;
 pub mod text_input;
+mod touch_bar;
 pub mod util;
 pub mod window;
@@ -236,7 +236,12 @@ pub extern "C" fn first_rect_for_character_range(
             let line_range = edit_lock.line_range(range.start, Affinity::Downstream);
             range.end = usize::min(range.end, line_range.end);
         }
-        let rect = match edit_lock.slice_bounding_box(range.clone()) {
+        // The system asks for the rect of the marked text to place the
+        // candidate window; the field may want it somewhere else.
+        let anchor = edit_lock
+            .composition_range()
+            .and_then(|_| edit_lock.candidate_anchor());
+        let rect = match anchor.or_else(|| edit_lock.slice_bounding_box(range.clone())) {
             Some(v) => v,
             None => return NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(0.0, 0.0)),
         };
@@ -0,0 +1,117 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Auxiliary controls in the Touch Bar.
+
+use cocoa::base::{id, nil, NO, YES};
+use cocoa::foundation::NSSize;
+use objc::runtime::Class;
+use objc::{class, msg_send, sel, sel_impl};
+
+use super::util::make_nsstring;
+use crate::aux_controls::{AuxControl, AuxControlKind};
+use crate::piet::ImageBuf;
+
+/// Create a Touch Bar showing `controls`, whose actions are sent to `target`.
+///
+/// Returns `nil` if the Touch Bar is not supported by this version of macOS.
+pub(crate) unsafe fn make_touch_bar(target: id, controls: &[AuxControl]) -> id {
+    let touch_bar_class = match Class::get("NSTouchBar") {
+        Some(class) => class,
+        None => return nil,
+    };
+    let identifiers: id = msg_send![class!(NSMutableArray), array];
+    let items: id = msg_send![class!(NSMutableSet), set];
+    for control in controls {
+        let identifier = make_nsstring(&format!("org.linebender.druid.aux-control.{}", control.id));
+        let title = make_nsstring(&control.title);
+        let enabled = if control.enabled { YES } else { NO };
+        let item: id = match control.kind {
+            AuxControlKind::Button => {
+                let item: id = msg_send![class!(NSCustomTouchBarItem), alloc];
+                let item: id = msg_send![item, initWithIdentifier: identifier];
+                let button: id = match &control.icon {
+                    Some(icon) => {
+                        let image = make_nsimage(icon);
+                        msg_send![class!(NSButton), buttonWithImage: image target: target action: sel!(handleAuxControl:)]
+                    }
+                    None => {
+                        msg_send![class!(NSButton), buttonWithTitle: title target: target action: sel!(handleAuxControl:)]
+                    }
+                };
+                let () = msg_send![button, setTag: control.id as isize];
+                let () = msg_send![button, setEnabled: enabled];
+                let () = msg_send![item, setView: button];
+                let () = msg_send![item, setCustomizationLabel: title];
+                item
+            }
+            AuxControlKind::Slider { min, max, value } => {
+                let item: id = msg_send![class!(NSSliderTouchBarItem), alloc];
+                let item: id = msg_send![item, initWithIdentifier: identifier];
+                let () = msg_send![item, setLabel: title];
+                let slider: id = msg_send![item, slider];
+                let () = msg_send![slider, setMinValue: min];
+                let () = msg_send![slider, setMaxValue: max];
+                let () = msg_send![slider, setDoubleValue: value];
+                let () = msg_send![slider, setTag: control.id as isize];
+                let () = msg_send![slider, setEnabled: enabled];
+                let () = msg_send![slider, setContinuous: YES];
+                let () = msg_send![slider, setTarget: target];
+                let () = msg_send![slider, setAction: sel!(handleAuxControl:)];
+                item
+            }
+        };
+        let () = msg_send![items, addObject: item];
+        let () = msg_send![item, release];
+        let () = msg_send![identifiers, addObject: identifier];
+    }
+
+    let touch_bar: id = msg_send![touch_bar_class, alloc];
+    let touch_bar: id = msg_send![touch_bar, init];
+    let () = msg_send![touch_bar, setDefaultItemIdentifiers: identifiers];
+    let () = msg_send![touch_bar, setTemplateItems: items];
+    msg_send![touch_bar, autorelease]
+}
+
+/// Create an autoreleased `NSImage` from an image.
+unsafe fn make_nsimage(image: &ImageBuf) -> id {
+    let (width, height) = (image.width(), image.height());
+    let rep: id = msg_send![class!(NSBitmapImageRep), alloc];
+    let rep: id = msg_send![rep,
+        initWithBitmapDataPlanes: std::ptr::null_mut::<*mut u8>()
+        pixelsWide: width as isize
+        pixelsHigh: height as isize
+        bitsPerSample: 8isize
+        samplesPerPixel: 4isize
+        hasAlpha: YES
+        isPlanar: NO
+        colorSpaceName: make_nsstring("NSDeviceRGBColorSpace")
+        bytesPerRow: (width * 4) as isize
+        bitsPerPixel: 32isize
+    ];
+    // The default bitmap format has premultiplied alpha, with alpha last.
+    let data: *mut u8 = msg_send![rep, bitmapData];
+    let data = std::slice::from_raw_parts_mut(data, width * height * 4);
+    for (pixel, color) in data.chunks_exact_mut(4).zip(image.pixel_colors().flatten()) {
+        let (r, g, b, a) = color.as_rgba8();
+        let premul = |c: u8| (c as u16 * a as u16 / 255) as u8;
+        pixel.copy_from_slice(&[premul(r), premul(g), premul(b), a]);
+    }
+
+    let nsimage: id = msg_send![class!(NSImage), alloc];
+    let nsimage: id = msg_send![nsimage, initWithSize: NSSize::new(width as f64, height as f64)];
+    let () = msg_send![nsimage, addRepresentation: rep];
+    let () = msg_send![rep, release];
+    msg_send![nsimage, autorelease]
+}
@@ -55,7 +55,9 @@ use super::dialog;
 use super::keyboard::{make_modifiers, KeyboardState};
 use super::menu::Menu;
 use super::text_input::NSRange;
+use super::touch_bar::make_touch_bar;
 use super::util::{assert_main_thread, make_nsstring};
+use crate::aux_controls::AuxControl;
 use crate::common_util::IdleCallback;
 use crate::dialog::{FileDialogOptions, FileDialogType};
 use crate::keyboard_types::KeyState;
@@ -98,6 +100,23 @@ mod levels {
     }
 }
 
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn EnableSecureEventInput() -> i32;
+    fn DisableSecureEventInput() -> i32;
+}
+
+/// Secure event input, which is counted by the system and disabled on drop.
+pub(crate) struct SecureInput(());
+
+impl Drop for SecureInput {
+    fn drop(&mut self) {
+        unsafe {
+            DisableSecureEventInput();
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct WindowHandle {
     /// This is an NSView, as our concept of "window" is more the top-level container holding
@@ -492,6 +511,10 @@ static VIEW_CLASS: Lazy<ViewClass> = Lazy::new(|| unsafe {
         sel!(handleMenuItem:),
         handle_menu_item as extern "C" fn(&mut Object, Sel, id),
     );
+    decl.add_method(
+        sel!(handleAuxControl:),
+        handle_aux_control as extern "C" fn(&mut Object, Sel, id),
+    );
     decl.add_method(
         sel!(showContextMenu:),
         show_context_menu as extern "C" fn(&mut Object, Sel, id),
@@ -984,6 +1007,22 @@ extern "C" fn handle_menu_item(this: &mut Object, _: Sel, item: id) {
     }
 }
 
+extern "C" fn handle_aux_control(this: &mut Object, _: Sel, sender: id) {
+    unsafe {
+        let tag: isize = msg_send![sender, tag];
+        let is_slider: BOOL = msg_send![sender, isKindOfClass: class!(NSSlider)];
+        let value = if is_slider == YES {
+            let value: f64 = msg_send![sender, doubleValue];
+            Some(value)
+        } else {
+            None
+        };
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        view_state.handler.aux_control(tag as u32, value);
+    }
+}
+
 extern "C" fn show_context_menu(this: &mut Object, _: Sel, item: id) {
     unsafe {
         let window: id = msg_send![this as *const _, window];
@@ -1423,6 +1462,26 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_aux_controls(&self, controls: Vec<AuxControl>) {
+        unsafe {
+            let view = *self.nsview.load();
+            if view.is_null() {
+                return;
+            }
+            let touch_bar = make_touch_bar(view, &controls);
+            if touch_bar != nil {
+                let () = msg_send![view, setTouchBar: touch_bar];
+            }
+        }
+    }
+
+    pub(crate) fn begin_secure_input(&self) -> Option<SecureInput> {
+        // Secure event input keeps event taps from seeing keystrokes. The system only
+        // applies it while the app is active, so there is nothing to do on focus changes.
+        let status = unsafe { EnableSecureEventInput() };
+        (status == 0).then_some(SecureInput(()))
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         unsafe {
             NSApp().setMainMenu_(menu.menu);
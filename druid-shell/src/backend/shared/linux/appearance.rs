@@ -0,0 +1,54 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The preferred color scheme, from the desktop settings.
+
+use std::process::{Command, Stdio};
+
+use crate::ColorScheme;
+
+const GSETTINGS: &str = "gsettings";
+
+pub(crate) fn color_scheme() -> ColorScheme {
+    gsettings_color_scheme().unwrap_or_else(|| {
+        // GTK_THEME is `name` or `name:variant`
+        match std::env::var("GTK_THEME") {
+            Ok(theme) if theme.to_lowercase().ends_with("dark") => ColorScheme::Dark,
+            Ok(theme) if !theme.is_empty() => ColorScheme::Light,
+            _ => ColorScheme::NoPreference,
+        }
+    })
+}
+
+/// The `color-scheme` setting of GNOME and other desktops that follow it.
+fn gsettings_color_scheme() -> Option<ColorScheme> {
+    let output = Command::new(GSETTINGS)
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // the value is quoted, as in `'prefer-dark'`
+    match String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .trim_matches('\'')
+    {
+        "prefer-dark" => Some(ColorScheme::Dark),
+        "prefer-light" => Some(ColorScheme::Light),
+        _ => None,
+    }
+}
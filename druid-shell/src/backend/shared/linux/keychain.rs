@@ -0,0 +1,111 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Secret Service access through `secret-tool`.
+//!
+//! Using the command line tool avoids a D-Bus dependency. The password is passed on
+//! standard input and output, so it never shows up in the arguments of a process.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::anyhow;
+
+use crate::error::Error;
+
+const SECRET_TOOL: &str = "secret-tool";
+
+fn secret_tool(args: &[&str], service: &str, account: &str) -> Command {
+    let mut command = Command::new(SECRET_TOOL);
+    command
+        .args(args)
+        .args(["service", service, "account", account])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    command
+}
+
+fn spawn_error(err: std::io::Error) -> Error {
+    anyhow!("failed to run {}: {}", SECRET_TOOL, err).into()
+}
+
+pub(crate) fn set_password(service: &str, account: &str, password: &str) -> Result<(), Error> {
+    let label = format!("--label={service} ({account})");
+    let mut child = secret_tool(&["store", &label], service, account)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(spawn_error)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(password.as_bytes())
+            .map_err(|err| anyhow!("failed to pass the password to {}: {}", SECRET_TOOL, err))?;
+    }
+    let output = child.wait_with_output().map_err(spawn_error)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(anyhow!(
+            "{} failed to store the password: {}",
+            SECRET_TOOL,
+            stderr.trim()
+        )
+        .into())
+    }
+}
+
+pub(crate) fn get_password(service: &str, account: &str) -> Result<Option<String>, Error> {
+    let output = secret_tool(&["lookup"], service, account)
+        .stdout(Stdio::piped())
+        .output()
+        .map_err(spawn_error)?;
+    // `secret-tool` fails without a message if there is no matching secret.
+    if !output.status.success() {
+        if output.stderr.is_empty() {
+            return Ok(None);
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "{} failed to look up the password: {}",
+            SECRET_TOOL,
+            stderr.trim()
+        )
+        .into());
+    }
+    let password = String::from_utf8(output.stdout)
+        .map_err(|_| anyhow!("the stored password is not valid UTF-8"))?;
+    Ok(Some(password))
+}
+
+pub(crate) fn delete_password(service: &str, account: &str) -> Result<bool, Error> {
+    let existed = get_password(service, account)?.is_some();
+    if !existed {
+        return Ok(false);
+    }
+    let output = secret_tool(&["clear"], service, account)
+        .output()
+        .map_err(spawn_error)?;
+    if output.status.success() {
+        Ok(true)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(anyhow!(
+            "{} failed to delete the password: {}",
+            SECRET_TOOL,
+            stderr.trim()
+        )
+        .into())
+    }
+}
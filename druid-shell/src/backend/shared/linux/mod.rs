@@ -13,4 +13,14 @@
 // limitations under the License.
 
 // environment based utilities
+#[cfg(any(feature = "x11", feature = "wayland"))]
 pub mod env;
+// the preferred color scheme, through gsettings
+pub mod appearance;
+// opening URLs, through xdg-open
+pub mod open;
+// power and network status, from sysfs
+pub mod status;
+// secrets, through secret-tool
+#[cfg(feature = "keychain")]
+pub mod keychain;
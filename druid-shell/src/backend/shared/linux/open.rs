@@ -0,0 +1,36 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Opening URLs with the desktop's preferred application, through `xdg-open`.
+
+use std::process::{Command, Stdio};
+
+const XDG_OPEN: &str = "xdg-open";
+
+/// Open `url` without waiting for the application to start.
+pub(crate) fn open_url(url: &str) {
+    let spawned = Command::new(XDG_OPEN)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        // Wait on another thread, so that the process doesn't linger as a zombie.
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(err) => tracing::warn!("failed to run {}: {}", XDG_OPEN, err),
+    }
+}
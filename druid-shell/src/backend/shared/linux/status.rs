@@ -0,0 +1,88 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reading the power and network status from sysfs.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+use crate::system_status::{PowerSource, SystemStatus};
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+const NET_DIR: &str = "/sys/class/net";
+
+pub fn system_status() -> SystemStatus {
+    let mut status = SystemStatus {
+        online: network_online(),
+        ..Default::default()
+    };
+
+    let mut on_mains = None;
+    let mut discharging = false;
+    let (mut charge, mut batteries) = (0.0, 0);
+    for supply in dir_entries(POWER_SUPPLY_DIR) {
+        match read_attr(&supply, "type").as_deref() {
+            Some("Mains") => {
+                let online = read_attr(&supply, "online").as_deref() == Some("1");
+                on_mains = Some(on_mains.unwrap_or(false) || online);
+            }
+            // Devices such as wireless mice report their batteries with a scope of "Device".
+            Some("Battery") if read_attr(&supply, "scope").as_deref() != Some("Device") => {
+                discharging |= read_attr(&supply, "status").as_deref() == Some("Discharging");
+                if let Some(capacity) = read_attr(&supply, "capacity").and_then(|c| c.parse().ok())
+                {
+                    charge += f64::min(capacity, 100.0) / 100.0;
+                    batteries += 1;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    if batteries > 0 {
+        status.battery_level = Some(charge / batteries as f64);
+    }
+    status.power_source = match on_mains {
+        Some(true) => PowerSource::Ac,
+        Some(false) => PowerSource::Battery,
+        // Not every system reports its mains adapter.
+        None if discharging => PowerSource::Battery,
+        None if batteries > 0 => PowerSource::Ac,
+        None => PowerSource::Unknown,
+    };
+    status
+}
+
+/// Returns `true` if any interface other than loopback is up.
+fn network_online() -> Option<bool> {
+    let mut interfaces = dir_entries(NET_DIR)
+        .filter(|iface| iface.file_name() != Some(OsStr::new("lo")))
+        .peekable();
+    interfaces.peek()?;
+    Some(interfaces.any(|iface| read_attr(&iface, "operstate").as_deref() == Some("up")))
+}
+
+fn dir_entries(dir: &str) -> impl Iterator<Item = std::path::PathBuf> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+}
+
+fn read_attr(device: &Path, attr: &str) -> Option<String> {
+    fs::read_to_string(device.join(attr))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
@@ -25,6 +25,10 @@ cfg_if::cfg_if! {
         mod timer;
         pub(crate) use timer::*;
         pub(crate) mod xkb;
+    }
+}
+cfg_if::cfg_if! {
+    if #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "openbsd"))] {
         pub(crate) mod linux;
     }
 }
@@ -31,6 +31,8 @@ use std::{
 };
 
 use crate::backend::shared::linux;
+use crate::color_scheme::ColorScheme;
+use crate::system_status::SystemStatus;
 use wayland_client::protocol::wl_keyboard::WlKeyboard;
 use wayland_client::protocol::wl_registry;
 use wayland_client::{
@@ -402,15 +404,35 @@ impl Application {
         self.data.shutdown.set(true);
     }
 
+    pub fn inhibit_screensaver(&self, _reason: &str) -> Option<ScreensaverInhibitor> {
+        // TODO: this could use the idle-inhibit protocol, which requires a surface.
+        None
+    }
+
     pub fn clipboard(&self) -> clipboard::Clipboard {
         clipboard::Clipboard::from(&self.data.clipboard)
     }
 
+    pub fn system_status() -> SystemStatus {
+        linux::status::system_status()
+    }
+
+    pub fn color_scheme() -> ColorScheme {
+        linux::appearance::color_scheme()
+    }
+
     pub fn get_locale() -> String {
         linux::env::locale()
     }
+
+    pub fn open_url(url: &str) {
+        linux::open::open_url(url)
+    }
 }
 
+/// Inhibiting the screen saver is not supported on Wayland yet.
+pub(crate) enum ScreensaverInhibitor {}
+
 impl surfaces::Compositor for Data {
     fn output(&self, id: u32) -> Option<outputs::Meta> {
         self.outputs.borrow().get(&id).cloned()
@@ -18,6 +18,8 @@ pub mod application;
 pub mod clipboard;
 mod display;
 pub mod error;
+#[cfg(feature = "keychain")]
+pub(crate) use crate::backend::shared::linux::keychain;
 mod events;
 pub mod keyboard;
 pub mod menu;
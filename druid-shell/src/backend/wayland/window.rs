@@ -25,6 +25,7 @@ use raw_window_handle::{HasRawWindowHandle, RawWindowHandle, WaylandWindowHandle
 use super::application::{self, Timer};
 use super::{error::Error, menu::Menu, outputs, surfaces};
 
+use crate::aux_controls::AuxControl;
 use crate::Region;
 use crate::{
     dialog::FileDialogOptions,
@@ -50,6 +51,9 @@ struct Inner {
     pub(super) appdata: std::sync::Weak<application::Data>,
 }
 
+/// Secure text entry is not needed on Wayland, where clients cannot read each other's input.
+pub(crate) enum SecureInput {}
+
 #[derive(Clone)]
 pub struct WindowHandle {
     inner: std::sync::Arc<Inner>,
@@ -282,6 +286,15 @@ impl WindowHandle {
         Ok(self.inner.surface.get_scale())
     }
 
+    pub fn set_aux_controls(&self, _controls: Vec<AuxControl>) {
+        // Auxiliary controls have no equivalent on this platform.
+    }
+
+    pub(crate) fn begin_secure_input(&self) -> Option<SecureInput> {
+        // The compositor never shows keyboard input to other clients.
+        None
+    }
+
     pub fn set_menu(&self, _menu: Menu) {
         tracing::warn!("set_menu not implement for wayland");
     }
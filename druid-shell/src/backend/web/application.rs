@@ -15,6 +15,8 @@
 //! Web implementation of features at the application scope.
 
 use crate::application::AppHandler;
+use crate::color_scheme::ColorScheme;
+use crate::system_status::SystemStatus;
 
 use super::clipboard::Clipboard;
 use super::error::Error;
@@ -22,6 +24,9 @@ use super::error::Error;
 #[derive(Clone)]
 pub(crate) struct Application;
 
+/// Inhibiting the screen saver is not supported on the web yet.
+pub(crate) enum ScreensaverInhibitor {}
+
 impl Application {
     pub fn new() -> Result<Application, Error> {
         Ok(Application)
@@ -31,13 +36,50 @@ impl Application {
 
     pub fn quit(&self) {}
 
+    pub fn inhibit_screensaver(&self, _reason: &str) -> Option<ScreensaverInhibitor> {
+        None
+    }
+
     pub fn clipboard(&self) -> Clipboard {
         Clipboard
     }
 
+    pub fn system_status() -> SystemStatus {
+        SystemStatus {
+            online: web_sys::window().map(|w| w.navigator().on_line()),
+            ..Default::default()
+        }
+    }
+
+    pub fn color_scheme() -> ColorScheme {
+        let prefers = |scheme: &str| {
+            web_sys::window()
+                .and_then(|w| {
+                    w.match_media(&format!("(prefers-color-scheme: {scheme})"))
+                        .ok()
+                        .flatten()
+                })
+                .is_some_and(|query| query.matches())
+        };
+        if prefers("dark") {
+            ColorScheme::Dark
+        } else if prefers("light") {
+            ColorScheme::Light
+        } else {
+            ColorScheme::NoPreference
+        }
+    }
+
     pub fn get_locale() -> String {
         web_sys::window()
             .and_then(|w| w.navigator().language())
             .unwrap_or_else(|| "en-US".into())
     }
+
+    pub fn open_url(url: &str) {
+        let opened = web_sys::window().and_then(|w| w.open_with_url_and_target(url, "_blank").ok());
+        if opened.is_none() {
+            tracing::warn!("failed to open {}", url);
+        }
+    }
 }
@@ -0,0 +1,33 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! There is no credential store available to web pages.
+
+use crate::error::Error;
+
+fn unsupported() -> Error {
+    anyhow::anyhow!("storing secrets is not supported on the web").into()
+}
+
+pub(crate) fn set_password(_service: &str, _account: &str, _password: &str) -> Result<(), Error> {
+    Err(unsupported())
+}
+
+pub(crate) fn get_password(_service: &str, _account: &str) -> Result<Option<String>, Error> {
+    Err(unsupported())
+}
+
+pub(crate) fn delete_password(_service: &str, _account: &str) -> Result<bool, Error> {
+    Err(unsupported())
+}
@@ -17,6 +17,8 @@
 pub mod application;
 pub mod clipboard;
 pub mod error;
+#[cfg(feature = "keychain")]
+pub mod keychain;
 pub mod keycodes;
 pub mod menu;
 pub mod screen;
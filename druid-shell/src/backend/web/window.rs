@@ -27,6 +27,7 @@ use wasm_bindgen::JsCast;
 #[cfg(feature = "raw-win-handle")]
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle, WebWindowHandle};
 
+use crate::aux_controls::AuxControl;
 use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
 
 use crate::piet::{PietText, RenderContext};
@@ -77,6 +78,9 @@ pub(crate) struct WindowBuilder {
     menu: Option<Menu>,
 }
 
+/// Secure text entry is not supported on the web.
+pub(crate) enum SecureInput {}
+
 #[derive(Clone, Default)]
 pub struct WindowHandle(Weak<WindowState>);
 impl PartialEq for WindowHandle {
@@ -707,6 +711,15 @@ impl WindowHandle {
             .get())
     }
 
+    pub fn set_aux_controls(&self, _controls: Vec<AuxControl>) {
+        // Auxiliary controls have no equivalent on this platform.
+    }
+
+    pub(crate) fn begin_secure_input(&self) -> Option<SecureInput> {
+        // Secure text entry has no equivalent on this platform.
+        None
+    }
+
     pub fn set_menu(&self, _menu: Menu) {
         warn!("set_menu unimplemented for web");
     }
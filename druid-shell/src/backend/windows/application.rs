@@ -27,18 +27,25 @@ use winapi::shared::windef::{DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, HCURSOR
 use winapi::shared::winerror::HRESULT_FROM_WIN32;
 use winapi::um::errhandlingapi::GetLastError;
 use winapi::um::libloaderapi::GetModuleHandleW;
+use winapi::um::shellapi::ShellExecuteW;
 use winapi::um::shellscalingapi::PROCESS_PER_MONITOR_DPI_AWARE;
+use winapi::um::winbase::{GetSystemPowerStatus, SetThreadExecutionState, SYSTEM_POWER_STATUS};
+use winapi::um::wininet::InternetGetConnectedState;
 use winapi::um::winnls::GetUserDefaultLocaleName;
-use winapi::um::winnt::LOCALE_NAME_MAX_LENGTH;
+use winapi::um::winnt::{
+    ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED, LOCALE_NAME_MAX_LENGTH,
+};
 use winapi::um::winuser::{
     DispatchMessageW, GetAncestor, GetMessageW, LoadIconW, PeekMessageW, PostMessageW,
     PostQuitMessage, RegisterClassW, TranslateAcceleratorW, TranslateMessage, GA_ROOT,
-    MAKEINTRESOURCEW, MSG, PM_NOREMOVE, WM_TIMER, WNDCLASSW,
+    MAKEINTRESOURCEW, MSG, PM_NOREMOVE, SW_SHOWNORMAL, WM_TIMER, WNDCLASSW,
 };
 
 use piet_common::D2DLoadedFonts;
 
 use crate::application::AppHandler;
+use crate::color_scheme::ColorScheme;
+use crate::system_status::{PowerSource, SystemStatus};
 
 use super::accels;
 use super::clipboard::Clipboard;
@@ -55,6 +62,23 @@ pub(crate) struct Application {
 struct State {
     quitting: bool,
     windows: HashSet<HWND>,
+    /// The number of live screen saver inhibitors.
+    screensaver_inhibitors: usize,
+}
+
+pub(crate) struct ScreensaverInhibitor {
+    state: Rc<RefCell<State>>,
+}
+
+impl Drop for ScreensaverInhibitor {
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.screensaver_inhibitors -= 1;
+        if state.screensaver_inhibitors == 0 {
+            // Clear the requirements set in `inhibit_screensaver`.
+            unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+        }
+    }
 }
 
 /// Used to ensure the window class is registered only once per process.
@@ -66,6 +90,7 @@ impl Application {
         let state = Rc::new(RefCell::new(State {
             quitting: false,
             windows: HashSet::new(),
+            screensaver_inhibitors: 0,
         }));
         let fonts = D2DLoadedFonts::default();
         Ok(Application { state, fonts })
@@ -190,10 +215,54 @@ impl Application {
         }
     }
 
+    pub fn inhibit_screensaver(&self, _reason: &str) -> Option<ScreensaverInhibitor> {
+        let mut state = self.state.borrow_mut();
+        if state.screensaver_inhibitors == 0 {
+            // The execution state is per thread, and stays in effect until it is cleared.
+            let flags = ES_CONTINUOUS | ES_DISPLAY_REQUIRED | ES_SYSTEM_REQUIRED;
+            if unsafe { SetThreadExecutionState(flags) } == 0 {
+                return None;
+            }
+        }
+        state.screensaver_inhibitors += 1;
+        Some(ScreensaverInhibitor {
+            state: self.state.clone(),
+        })
+    }
+
     pub fn clipboard(&self) -> Clipboard {
         Clipboard
     }
 
+    pub fn system_status() -> SystemStatus {
+        let mut status = SystemStatus::default();
+        unsafe {
+            let mut power: SYSTEM_POWER_STATUS = mem::zeroed();
+            if GetSystemPowerStatus(&mut power) != FALSE {
+                status.power_source = match power.ACLineStatus {
+                    0 => PowerSource::Battery,
+                    1 => PowerSource::Ac,
+                    _ => PowerSource::Unknown,
+                };
+                // 128 means there is no battery, and 255 that its status is unknown.
+                if power.BatteryFlag & 128 == 0 && power.BatteryLifePercent <= 100 {
+                    status.battery_level = Some(power.BatteryLifePercent as f64 / 100.0);
+                }
+            }
+            let mut flags = 0;
+            status.online = Some(InternetGetConnectedState(&mut flags, 0) != FALSE);
+        }
+        status
+    }
+
+    pub fn color_scheme() -> ColorScheme {
+        if super::window::should_use_dark_theme() {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    }
+
     pub fn get_locale() -> String {
         let mut buf = [0u16; LOCALE_NAME_MAX_LENGTH];
         let len_with_null =
@@ -208,4 +277,21 @@ impl Application {
             "en-US".into()
         })
     }
+
+    pub fn open_url(url: &str) {
+        let result = unsafe {
+            ShellExecuteW(
+                ptr::null_mut(),
+                "open".to_wide().as_ptr(),
+                url.to_wide().as_ptr(),
+                ptr::null(),
+                ptr::null(),
+                SW_SHOWNORMAL,
+            )
+        };
+        // Values of 32 or less are errors.
+        if result as usize <= 32 {
+            tracing::warn!("failed to open {}", url);
+        }
+    }
 }
@@ -0,0 +1,94 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Secrets in the Windows Credential Manager.
+
+use std::ptr::null_mut;
+use std::slice;
+
+use winapi::shared::minwindef::{DWORD, FALSE, LPBYTE};
+use winapi::shared::winerror::{ERROR_NOT_FOUND, HRESULT_FROM_WIN32};
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::wincred::{
+    CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE,
+    CRED_TYPE_GENERIC, PCREDENTIALW,
+};
+
+use super::error::Error as WinError;
+use super::util::ToWide;
+use crate::error::Error;
+
+/// The name the credential is stored under, which is what the Credential Manager shows.
+fn target_name(service: &str, account: &str) -> Vec<u16> {
+    format!("{service}:{account}").to_wide()
+}
+
+fn last_error() -> Error {
+    unsafe { WinError::Hr(HRESULT_FROM_WIN32(GetLastError())).into() }
+}
+
+pub(crate) fn set_password(service: &str, account: &str, password: &str) -> Result<(), Error> {
+    let mut target = target_name(service, account);
+    let mut user = account.to_wide();
+    let mut blob = password.as_bytes().to_vec();
+    unsafe {
+        let mut credential: CREDENTIALW = std::mem::zeroed();
+        credential.Type = CRED_TYPE_GENERIC;
+        credential.TargetName = target.as_mut_ptr();
+        credential.UserName = user.as_mut_ptr();
+        credential.CredentialBlobSize = blob.len() as DWORD;
+        credential.CredentialBlob = blob.as_mut_ptr() as LPBYTE;
+        credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+        if CredWriteW(&mut credential, 0) == FALSE {
+            return Err(last_error());
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn get_password(service: &str, account: &str) -> Result<Option<String>, Error> {
+    let target = target_name(service, account);
+    unsafe {
+        let mut credential: PCREDENTIALW = null_mut();
+        if CredReadW(target.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == FALSE {
+            return match GetLastError() {
+                ERROR_NOT_FOUND => Ok(None),
+                err => Err(WinError::Hr(HRESULT_FROM_WIN32(err)).into()),
+            };
+        }
+        let blob = slice::from_raw_parts(
+            (*credential).CredentialBlob,
+            (*credential).CredentialBlobSize as usize,
+        );
+        let password = String::from_utf8(blob.to_vec());
+        CredFree(credential as _);
+        match password {
+            Ok(password) => Ok(Some(password)),
+            Err(_) => Err(anyhow::anyhow!("the stored password is not valid UTF-8").into()),
+        }
+    }
+}
+
+pub(crate) fn delete_password(service: &str, account: &str) -> Result<bool, Error> {
+    let target = target_name(service, account);
+    unsafe {
+        if CredDeleteW(target.as_ptr(), CRED_TYPE_GENERIC, 0) == FALSE {
+            return match GetLastError() {
+                ERROR_NOT_FOUND => Ok(false),
+                err => Err(WinError::Hr(HRESULT_FROM_WIN32(err)).into()),
+            };
+        }
+    }
+    Ok(true)
+}
@@ -21,9 +21,12 @@ pub mod dcomp;
 pub mod dialog;
 pub mod error;
 mod keyboard;
+#[cfg(feature = "keychain")]
+pub mod keychain;
 pub mod menu;
 pub mod paint;
 pub mod screen;
+mod thumb_bar;
 mod timers;
 pub mod util;
 pub mod window;
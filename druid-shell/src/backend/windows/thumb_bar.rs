@@ -0,0 +1,185 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Thumbnail toolbar buttons in the taskbar preview of a window.
+
+use std::ptr::null_mut;
+
+use once_cell::sync::Lazy;
+use tracing::warn;
+use winapi::shared::minwindef::*;
+use winapi::shared::windef::*;
+use winapi::shared::winerror::SUCCEEDED;
+use winapi::shared::wtypesbase::CLSCTX_INPROC_SERVER;
+use winapi::um::combaseapi::*;
+use winapi::um::shobjidl_core::*;
+use winapi::um::winuser::{DestroyIcon, RegisterWindowMessageW};
+use winapi::Interface;
+use wio::com::ComPtr;
+
+use super::util::ToWide;
+use super::window::create_icon;
+use crate::aux_controls::{AuxControl, AuxControlKind};
+
+/// The taskbar only supports this many buttons per window.
+const MAX_BUTTONS: usize = 7;
+
+/// The message sent to a window once its taskbar button exists.
+static TASKBAR_BUTTON_CREATED: Lazy<UINT> =
+    Lazy::new(|| unsafe { RegisterWindowMessageW("TaskbarButtonCreated".to_wide().as_ptr()) });
+
+/// Returns `true` if `msg` announces that the taskbar button of a window was created.
+///
+/// This is also sent again when Explorer restarts, in which case the buttons must be re-added.
+pub(crate) fn is_taskbar_button_created(msg: UINT) -> bool {
+    msg != 0 && msg == *TASKBAR_BUTTON_CREATED
+}
+
+/// The thumbnail toolbar of a window.
+///
+/// Buttons can only be added to the toolbar once, so we always add [`MAX_BUTTONS`]
+/// slots and hide the ones that are not used.
+#[derive(Default)]
+pub(crate) struct ThumbBar {
+    taskbar: Option<ComPtr<ITaskbarList3>>,
+    /// The ids of the controls in each slot.
+    ids: Vec<u32>,
+    buttons: Vec<AuxControl>,
+    icons: Vec<HICON>,
+}
+
+impl ThumbBar {
+    /// Set the buttons of the toolbar. Sliders are ignored.
+    pub(crate) fn set_controls(&mut self, hwnd: HWND, controls: Vec<AuxControl>) {
+        let mut buttons = controls
+            .into_iter()
+            .filter(|control| control.kind == AuxControlKind::Button)
+            .collect::<Vec<_>>();
+        if buttons.len() > MAX_BUTTONS {
+            warn!(
+                "The thumbnail toolbar supports at most {} buttons",
+                MAX_BUTTONS
+            );
+            buttons.truncate(MAX_BUTTONS);
+        }
+        self.buttons = buttons;
+        if self.taskbar.is_some() {
+            self.update(hwnd, false);
+        }
+    }
+
+    /// Create the toolbar, once the taskbar button of the window exists.
+    pub(crate) fn taskbar_button_created(&mut self, hwnd: HWND) {
+        let mut taskbar: *mut ITaskbarList3 = null_mut();
+        let created = unsafe {
+            CoCreateInstance(
+                &CLSID_TaskbarList,
+                null_mut(),
+                CLSCTX_INPROC_SERVER,
+                &ITaskbarList3::uuidof(),
+                &mut taskbar as *mut *mut ITaskbarList3 as *mut LPVOID,
+            )
+        };
+        if !SUCCEEDED(created) || taskbar.is_null() {
+            warn!("failed to create taskbar list: {:#x}", created);
+            return;
+        }
+        let taskbar = unsafe { ComPtr::from_raw(taskbar) };
+        if !SUCCEEDED(unsafe { taskbar.HrInit() }) {
+            warn!("failed to initialize taskbar list");
+            return;
+        }
+        self.taskbar = Some(taskbar);
+        self.update(hwnd, true);
+    }
+
+    /// The id of the control in a slot, when its button is clicked.
+    pub(crate) fn control_id(&self, slot: u32) -> Option<u32> {
+        self.ids.get(slot as usize).copied()
+    }
+
+    fn update(&mut self, hwnd: HWND, add: bool) {
+        if self.taskbar.is_none() {
+            return;
+        }
+        self.destroy_icons();
+        self.ids = self.buttons.iter().map(|button| button.id).collect();
+
+        let mut icons = Vec::new();
+
+        let mut slots = (0..MAX_BUTTONS)
+            .map(|slot| {
+                let mut button = THUMBBUTTON {
+                    dwMask: THB_FLAGS | THB_TOOLTIP | THB_ICON,
+                    iId: slot as UINT,
+                    iBitmap: 0,
+                    hIcon: null_mut(),
+                    szTip: [0; 260],
+                    dwFlags: THBF_HIDDEN,
+                };
+                if let Some(control) = self.buttons.get(slot) {
+                    for (dst, src) in button.szTip[..259]
+                        .iter_mut()
+                        .zip(control.title.encode_utf16())
+                    {
+                        *dst = src;
+                    }
+                    button.dwFlags = if control.enabled {
+                        THBF_ENABLED
+                    } else {
+                        THBF_DISABLED
+                    };
+                    if let Some(icon) = &control.icon {
+                        button.hIcon =
+                            unsafe { create_icon(hwnd, icon, None) }.unwrap_or(null_mut());
+                        if !button.hIcon.is_null() {
+                            icons.push(button.hIcon);
+                        }
+                    }
+                }
+                button
+            })
+            .collect::<Vec<_>>();
+        self.icons = icons;
+
+        let taskbar = match &self.taskbar {
+            Some(taskbar) => taskbar,
+            None => return,
+        };
+        let result = unsafe {
+            if add {
+                taskbar.ThumbBarAddButtons(hwnd, slots.len() as UINT, slots.as_mut_ptr())
+            } else {
+                taskbar.ThumbBarUpdateButtons(hwnd, slots.len() as UINT, slots.as_mut_ptr())
+            }
+        };
+        if !SUCCEEDED(result) {
+            warn!("failed to update thumbnail toolbar: {:#x}", result);
+        }
+    }
+
+    fn destroy_icons(&mut self) {
+        for icon in self.icons.drain(..) {
+            unsafe {
+                DestroyIcon(icon);
+            }
+        }
+    }
+}
+
+impl Drop for ThumbBar {
+    fn drop(&mut self) {
+        self.destroy_icons();
+    }
+}
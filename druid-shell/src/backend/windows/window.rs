@@ -38,6 +38,7 @@ use winapi::um::dcomp::{IDCompositionDevice, IDCompositionTarget, IDCompositionV
 use winapi::um::dwmapi::{DwmExtendFrameIntoClientArea, DwmSetWindowAttribute};
 use winapi::um::errhandlingapi::GetLastError;
 use winapi::um::shellscalingapi::MDT_EFFECTIVE_DPI;
+use winapi::um::shobjidl_core::THBN_CLICKED;
 use winapi::um::unknwnbase::*;
 use winapi::um::uxtheme::*;
 use winapi::um::wingdi::*;
@@ -54,7 +55,7 @@ use piet_common::d2d::{D2DFactory, DeviceContext};
 use piet_common::dwrite::DwriteFactory;
 
 use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
-use crate::piet::{Piet, PietText, RenderContext};
+use crate::piet::{ImageBuf, Piet, PietText, RenderContext};
 
 use super::accels::register_accel;
 use super::application::Application;
@@ -64,9 +65,11 @@ use super::error::Error;
 use super::keyboard::{self, KeyboardState};
 use super::menu::Menu;
 use super::paint;
+use super::thumb_bar::{self, ThumbBar};
 use super::timers::TimerSlots;
 use super::util::{self, as_result, FromWide, ToWide, OPTIONAL_FUNCTIONS};
 
+use crate::aux_controls::AuxControl;
 use crate::common_util::IdleCallback;
 use crate::dialog::{FileDialogOptions, FileDialogType, FileInfo};
 use crate::error::Error as ShellError;
@@ -169,6 +172,9 @@ enum DeferredOp {
     SetAlwaysOnTop(bool),
 }
 
+/// Secure text entry is not supported on Windows.
+pub(crate) enum SecureInput {}
+
 #[derive(Clone, Debug)]
 pub struct WindowHandle {
     text: PietText,
@@ -243,6 +249,7 @@ struct WindowState {
     is_focusable: bool,
     window_level: WindowLevel,
     is_always_on_top: Cell<bool>,
+    thumb_bar: RefCell<ThumbBar>,
 }
 
 impl std::fmt::Debug for WindowState {
@@ -921,6 +928,12 @@ impl WndProc for MyWndProc {
         lparam: LPARAM,
     ) -> Option<LRESULT> {
         //println!("wndproc msg: {}", msg);
+        if thumb_bar::is_taskbar_button_created(msg) {
+            if let Some(state) = self.handle.borrow().state.upgrade() {
+                state.thumb_bar.borrow_mut().taskbar_button_created(hwnd);
+            }
+            return Some(0);
+        }
         match msg {
             WM_CREATE => {
                 // Only supported on Windows 10, Could remove this as the 8.1 version below also works on 10..
@@ -1187,6 +1200,19 @@ impl WndProc for MyWndProc {
                 })
                 .map(|_| 0)
             },
+            WM_COMMAND if HIWORD(wparam as u32) == THBN_CLICKED => {
+                let slot = LOWORD(wparam as u32) as u32;
+                let id = self
+                    .handle
+                    .borrow()
+                    .state
+                    .upgrade()
+                    .and_then(|state| state.thumb_bar.borrow().control_id(slot));
+                if let Some(id) = id {
+                    self.with_wnd_state(|s| s.handler.aux_control(id, None));
+                }
+                Some(0)
+            }
             WM_COMMAND => {
                 self.with_wnd_state(|s| s.handler.command(LOWORD(wparam as u32) as u32));
                 Some(0)
@@ -1649,6 +1675,7 @@ impl WindowBuilder {
                 is_focusable: focusable,
                 window_level,
                 is_always_on_top: Cell::new(self.always_on_top),
+                thumb_bar: Default::default(),
             };
             let win = Rc::new(window);
             let handle = WindowHandle {
@@ -2303,6 +2330,19 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_aux_controls(&self, controls: Vec<AuxControl>) {
+        if let Some(w) = self.state.upgrade() {
+            w.thumb_bar
+                .borrow_mut()
+                .set_controls(w.hwnd.get(), controls);
+        }
+    }
+
+    pub(crate) fn begin_secure_input(&self) -> Option<SecureInput> {
+        // Secure text entry has no equivalent on this platform.
+        None
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         let accels = menu.accels();
         let hmenu = menu.into_hmenu();
@@ -2374,72 +2414,10 @@ impl WindowHandle {
     }
 
     pub fn make_cursor(&self, cursor_desc: &CursorDesc) -> Option<Cursor> {
-        if let Some(hwnd) = self.get_hwnd() {
-            unsafe {
-                let hdc = GetDC(hwnd);
-                if hdc.is_null() {
-                    return None;
-                }
-                defer!(ReleaseDC(null_mut(), hdc););
-
-                let mask_dc = CreateCompatibleDC(hdc);
-                if mask_dc.is_null() {
-                    return None;
-                }
-                defer!(DeleteDC(mask_dc););
-
-                let bmp_dc = CreateCompatibleDC(hdc);
-                if bmp_dc.is_null() {
-                    return None;
-                }
-                defer!(DeleteDC(bmp_dc););
-
-                let width = cursor_desc.image.width();
-                let height = cursor_desc.image.height();
-                let mask = CreateCompatibleBitmap(hdc, width as c_int, height as c_int);
-                if mask.is_null() {
-                    return None;
-                }
-                defer!(DeleteObject(mask as _););
-
-                let bmp = CreateCompatibleBitmap(hdc, width as c_int, height as c_int);
-                if bmp.is_null() {
-                    return None;
-                }
-                defer!(DeleteObject(bmp as _););
-
-                let old_mask = SelectObject(mask_dc, mask as *mut c_void);
-                let old_bmp = SelectObject(bmp_dc, bmp as *mut c_void);
-
-                for (row_idx, row) in cursor_desc.image.pixel_colors().enumerate() {
-                    for (col_idx, p) in row.enumerate() {
-                        let (r, g, b, a) = p.as_rgba8();
-                        // TODO: what's the story on partial transparency? I couldn't find documentation.
-                        let mask_px = RGB(255 - a, 255 - a, 255 - a);
-                        let bmp_px = RGB(r, g, b);
-                        SetPixel(mask_dc, col_idx as i32, row_idx as i32, mask_px);
-                        SetPixel(bmp_dc, col_idx as i32, row_idx as i32, bmp_px);
-                    }
-                }
-
-                SelectObject(mask_dc, old_mask);
-                SelectObject(bmp_dc, old_bmp);
-
-                let mut icon_info = ICONINFO {
-                    // 0 means it's a cursor, not an icon.
-                    fIcon: 0,
-                    xHotspot: cursor_desc.hot.x as DWORD,
-                    yHotspot: cursor_desc.hot.y as DWORD,
-                    hbmMask: mask,
-                    hbmColor: bmp,
-                };
-                let icon = CreateIconIndirect(&mut icon_info);
-
-                Some(Cursor::Custom(CustomCursor(Arc::new(HCursor(icon)))))
-            }
-        } else {
-            None
-        }
+        let hwnd = self.get_hwnd()?;
+        let hot = (cursor_desc.hot.x as DWORD, cursor_desc.hot.y as DWORD);
+        let icon = unsafe { create_icon(hwnd, &cursor_desc.image, Some(hot))? };
+        Some(Cursor::Custom(CustomCursor(Arc::new(HCursor(icon)))))
     }
 
     pub fn open_file(&mut self, options: FileDialogOptions) -> Option<FileDialogToken> {
@@ -2553,3 +2531,73 @@ impl Default for WindowHandle {
         }
     }
 }
+
+/// Create an icon, or a cursor with the provided hotspot, from an image.
+///
+/// The caller is responsible for destroying the returned handle.
+pub(crate) unsafe fn create_icon(
+    hwnd: HWND,
+    image: &ImageBuf,
+    cursor_hot: Option<(DWORD, DWORD)>,
+) -> Option<HICON> {
+    let hdc = GetDC(hwnd);
+    if hdc.is_null() {
+        return None;
+    }
+    defer!(ReleaseDC(null_mut(), hdc););
+
+    let mask_dc = CreateCompatibleDC(hdc);
+    if mask_dc.is_null() {
+        return None;
+    }
+    defer!(DeleteDC(mask_dc););
+
+    let bmp_dc = CreateCompatibleDC(hdc);
+    if bmp_dc.is_null() {
+        return None;
+    }
+    defer!(DeleteDC(bmp_dc););
+
+    let width = image.width();
+    let height = image.height();
+    let mask = CreateCompatibleBitmap(hdc, width as c_int, height as c_int);
+    if mask.is_null() {
+        return None;
+    }
+    defer!(DeleteObject(mask as _););
+
+    let bmp = CreateCompatibleBitmap(hdc, width as c_int, height as c_int);
+    if bmp.is_null() {
+        return None;
+    }
+    defer!(DeleteObject(bmp as _););
+
+    let old_mask = SelectObject(mask_dc, mask as *mut c_void);
+    let old_bmp = SelectObject(bmp_dc, bmp as *mut c_void);
+
+    for (row_idx, row) in image.pixel_colors().enumerate() {
+        for (col_idx, p) in row.enumerate() {
+            let (r, g, b, a) = p.as_rgba8();
+            // TODO: what's the story on partial transparency? I couldn't find documentation.
+            let mask_px = RGB(255 - a, 255 - a, 255 - a);
+            let bmp_px = RGB(r, g, b);
+            SetPixel(mask_dc, col_idx as i32, row_idx as i32, mask_px);
+            SetPixel(bmp_dc, col_idx as i32, row_idx as i32, bmp_px);
+        }
+    }
+
+    SelectObject(mask_dc, old_mask);
+    SelectObject(bmp_dc, old_bmp);
+
+    let (x_hot, y_hot) = cursor_hot.unwrap_or_default();
+    let mut icon_info = ICONINFO {
+        // 0 means it's a cursor, not an icon.
+        fIcon: cursor_hot.is_none() as BOOL,
+        xHotspot: x_hot,
+        yHotspot: y_hot,
+        hbmMask: mask,
+        hbmColor: bmp,
+    };
+    let icon = CreateIconIndirect(&mut icon_info);
+    (!icon.is_null()).then_some(icon)
+}
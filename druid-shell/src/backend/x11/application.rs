@@ -25,6 +25,7 @@ use anyhow::{anyhow, Context, Error};
 use x11rb::connection::{Connection, RequestConnection};
 use x11rb::protocol::present::ConnectionExt as _;
 use x11rb::protocol::render::{self, ConnectionExt as _, Pictformat};
+use x11rb::protocol::screensaver::{self, ConnectionExt as _};
 use x11rb::protocol::xfixes::ConnectionExt as _;
 use x11rb::protocol::xproto::{
     self, ConnectionExt, CreateWindowAux, EventMask, Timestamp, Visualtype, WindowClass,
@@ -36,6 +37,8 @@ use x11rb::resource_manager::{
 use x11rb::xcb_ffi::XCBConnection;
 
 use crate::application::AppHandler;
+use crate::color_scheme::ColorScheme;
+use crate::system_status::SystemStatus;
 
 use super::clipboard::Clipboard;
 use super::util;
@@ -190,6 +193,17 @@ struct State {
     xkb_state: xkb::State,
 }
 
+pub(crate) struct ScreensaverInhibitor {
+    connection: Rc<XCBConnection>,
+}
+
+impl Drop for ScreensaverInhibitor {
+    fn drop(&mut self) {
+        log_x11!(self.connection.screensaver_suspend(0));
+        log_x11!(self.connection.flush());
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct Cursors {
     pub default: Option<xproto::Cursor>,
@@ -821,14 +835,45 @@ impl Application {
         }
     }
 
+    pub fn inhibit_screensaver(&self, _reason: &str) -> Option<ScreensaverInhibitor> {
+        match self
+            .connection
+            .extension_information(screensaver::X11_EXTENSION_NAME)
+        {
+            Ok(Some(_)) => (),
+            _ => return None,
+        }
+        // Suspend requests nest, so every inhibitor can suspend and resume independently.
+        self.connection
+            .screensaver_suspend(1)
+            .and_then(|_| self.connection.flush())
+            .map_err(|e| tracing::error!("failed to suspend the screen saver: {}", e))
+            .ok()?;
+        Some(ScreensaverInhibitor {
+            connection: Rc::clone(&self.connection),
+        })
+    }
+
     pub fn clipboard(&self) -> Clipboard {
         self.clipboard.clone()
     }
 
+    pub fn system_status() -> SystemStatus {
+        linux::status::system_status()
+    }
+
+    pub fn color_scheme() -> ColorScheme {
+        linux::appearance::color_scheme()
+    }
+
     pub fn get_locale() -> String {
         linux::env::locale()
     }
 
+    pub fn open_url(url: &str) {
+        linux::open::open_url(url)
+    }
+
     pub(crate) fn idle_pipe(&self) -> RawFd {
         self.idle_write
     }
@@ -37,6 +37,8 @@ pub mod application;
 pub mod clipboard;
 pub mod dialog;
 pub mod error;
+#[cfg(feature = "keychain")]
+pub(crate) use crate::backend::shared::linux::keychain;
 pub mod menu;
 pub mod screen;
 pub mod window;
@@ -23,6 +23,7 @@ use std::rc::{Rc, Weak};
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
+use crate::aux_controls::AuxControl;
 use crate::scale::Scalable;
 use anyhow::{anyhow, Context, Error};
 use cairo::{XCBConnection as CairoXCBConnection, XCBDrawable, XCBSurface, XCBVisualType};
@@ -1555,6 +1556,18 @@ fn key_mods(mods: u16) -> Modifiers {
     ret
 }
 
+/// A keyboard grab, released on drop.
+pub(crate) struct SecureInput {
+    conn: Rc<XCBConnection>,
+}
+
+impl Drop for SecureInput {
+    fn drop(&mut self) {
+        log_x11!(self.conn.ungrab_keyboard(x11rb::CURRENT_TIME));
+        log_x11!(self.conn.flush());
+    }
+}
+
 /// A handle that can get used to schedule an idle handler. Note that
 /// this handle can be cloned and sent between threads.
 #[derive(Clone)]
@@ -1778,6 +1791,28 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_aux_controls(&self, _controls: Vec<AuxControl>) {
+        // Auxiliary controls have no equivalent on this platform.
+    }
+
+    pub(crate) fn begin_secure_input(&self) -> Option<SecureInput> {
+        // Grabbing the keyboard keeps other clients from reading it.
+        let w = self.window.upgrade()?;
+        let conn = w.app.connection();
+        let reply = conn
+            .grab_keyboard(
+                true,
+                w.id,
+                x11rb::CURRENT_TIME,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+            )
+            .ok()?
+            .reply()
+            .ok()?;
+        (reply.status == xproto::GrabStatus::SUCCESS).then(|| SecureInput { conn: conn.clone() })
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         if let Some(w) = self.window.upgrade() {
             w.set_menu(menu);
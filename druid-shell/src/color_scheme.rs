@@ -0,0 +1,48 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The user's preference for light or dark interfaces.
+
+use crate::backend::application as backend;
+
+/// Whether the user prefers light or dark interfaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColorScheme {
+    /// Dark text on light backgrounds.
+    Light,
+    /// Light text on dark backgrounds.
+    Dark,
+    /// The user has no preference, or it could not be determined.
+    #[default]
+    NoPreference,
+}
+
+impl ColorScheme {
+    /// Query the color scheme that the user currently prefers.
+    ///
+    /// This may do blocking I/O, and can be called from any thread.
+    ///
+    /// # Platform support
+    ///
+    /// On Linux the preference is read from the GNOME desktop settings with
+    /// `gsettings`, falling back to the `GTK_THEME` environment variable.
+    pub fn current() -> ColorScheme {
+        backend::Application::color_scheme()
+    }
+
+    /// Returns `true` if the user prefers dark interfaces.
+    pub fn is_dark(self) -> bool {
+        self == ColorScheme::Dark
+    }
+}
@@ -107,6 +107,16 @@ impl HotKey {
         self
     }
 
+    /// The modifiers of this hotkey.
+    pub fn mods(&self) -> RawMods {
+        self.mods
+    }
+
+    /// The non-modifier key of this hotkey.
+    pub fn key(&self) -> &KbKey {
+        &self.key
+    }
+
     /// Returns `true` if this [`KeyEvent`] matches this `HotKey`.
     ///
     /// [`KeyEvent`]: KeyEvent
@@ -0,0 +1,44 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storing secrets in the platform's credential store.
+//!
+//! Secrets are identified by a service name, usually the name of the application,
+//! and an account name. They are stored in the Keychain on macOS, the Credential
+//! Manager on Windows, and through the Secret Service on Linux and the BSDs, using
+//! the `secret-tool` program of libsecret. They are not supported on the web.
+//!
+//! This module is only available with the `keychain` feature.
+
+use crate::backend::keychain as backend;
+use crate::error::Error;
+
+/// Store `password` for `account` of `service`, replacing any existing password.
+pub fn set_password(service: &str, account: &str, password: &str) -> Result<(), Error> {
+    backend::set_password(service, account, password)
+}
+
+/// Retrieve the password for `account` of `service`.
+///
+/// Returns `None` if no password is stored.
+pub fn get_password(service: &str, account: &str) -> Result<Option<String>, Error> {
+    backend::get_password(service, account)
+}
+
+/// Delete the password for `account` of `service`.
+///
+/// Returns `false` if no password was stored.
+pub fn delete_password(service: &str, account: &str) -> Result<bool, Error> {
+    backend::delete_password(service, account)
+}
@@ -57,8 +57,10 @@ pub use raw_window_handle;
 mod util;
 
 mod application;
+mod aux_controls;
 mod backend;
 mod clipboard;
+mod color_scheme;
 mod common_util;
 mod dialog;
 mod error;
@@ -69,13 +71,18 @@ mod mouse;
 mod region;
 mod scale;
 mod screen;
+mod system_status;
 mod window;
 
+#[cfg(feature = "keychain")]
+pub mod keychain;
 pub mod platform;
 pub mod text;
 
-pub use application::{AppHandler, Application};
+pub use application::{AppHandler, Application, InhibitToken};
+pub use aux_controls::{AuxControl, AuxControlKind};
 pub use clipboard::{Clipboard, ClipboardFormat, FormatId};
+pub use color_scheme::ColorScheme;
 pub use common_util::Counter;
 pub use dialog::{FileDialogOptions, FileInfo, FileSpec};
 pub use error::Error;
@@ -86,9 +93,10 @@ pub use mouse::{Cursor, CursorDesc, MouseButton, MouseButtons, MouseEvent};
 pub use region::Region;
 pub use scale::{Scalable, Scale, ScaledArea};
 pub use screen::{Monitor, Screen};
+pub use system_status::{PowerSource, SystemStatus};
 pub use window::{
-    FileDialogToken, IdleHandle, IdleToken, TextFieldToken, TimerToken, WinHandler, WindowBuilder,
-    WindowHandle, WindowLevel, WindowState,
+    FileDialogToken, IdleHandle, IdleToken, SecureInputToken, TextFieldToken, TimerToken,
+    WinHandler, WindowBuilder, WindowHandle, WindowLevel, WindowState,
 };
 
 pub use keyboard_types;
@@ -13,9 +13,10 @@
 // limitations under the License.
 
 use crate::kurbo::{BezPath, Point, Rect, Shape, Vec2};
+use crate::piet::{ImageBuf, ImageFormat};
 
 /// A union of rectangles, useful for describing an area that needs to be repainted.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Region {
     rects: Vec<Rect>,
 }
@@ -108,6 +109,101 @@ impl Region {
         }
         self.rects.retain(|r| r.area() > 0.0)
     }
+
+    /// Approximates the area covered by `shape` with a set of rectangles.
+    ///
+    /// The shape is sampled on a grid with cells of `resolution` display points;
+    /// smaller values give a closer fit at the cost of more rectangles. This is
+    /// useful for creating non-rectangular windows with
+    /// [`WindowHandle::set_input_region`].
+    ///
+    /// [`WindowHandle::set_input_region`]: crate::WindowHandle::set_input_region
+    pub fn from_shape(shape: &impl Shape, resolution: f64) -> Region {
+        let resolution = resolution.max(0.1);
+        let bounds = shape.bounding_box();
+        let width = (bounds.width() / resolution).ceil() as usize;
+        let height = (bounds.height() / resolution).ceil() as usize;
+        let mut region = Region::from_mask(width, height, |x, y| {
+            shape.contains(Point::new(
+                bounds.x0 + (x as f64 + 0.5) * resolution,
+                bounds.y0 + (y as f64 + 0.5) * resolution,
+            ))
+        });
+        for r in &mut region.rects {
+            *r = Rect::new(
+                r.x0 * resolution,
+                r.y0 * resolution,
+                r.x1 * resolution,
+                r.y1 * resolution,
+            ) + bounds.origin().to_vec2();
+        }
+        region
+    }
+
+    /// Creates a region covering the opaque pixels of `image`.
+    ///
+    /// A pixel is included if its alpha is at least `threshold`. Each pixel of
+    /// the image is treated as one display point.
+    pub fn from_image_alpha(image: &ImageBuf, threshold: u8) -> Region {
+        let width = image.width();
+        let pixels = image.raw_pixels();
+        match image.format() {
+            ImageFormat::RgbaSeparate | ImageFormat::RgbaPremul => {
+                Region::from_mask(width, image.height(), |x, y| {
+                    pixels[(y * width + x) * 4 + 3] >= threshold
+                })
+            }
+            // Formats without alpha are fully opaque.
+            _ => Region::from(image.size().to_rect()),
+        }
+    }
+
+    /// Creates a region from a `width` by `height` grid of unit cells, covering
+    /// the cells for which `covered` returns `true`.
+    ///
+    /// Horizontal runs of covered cells are merged, as are identical runs
+    /// in consecutive rows, to keep the number of rectangles small.
+    pub fn from_mask(
+        width: usize,
+        height: usize,
+        covered: impl Fn(usize, usize) -> bool,
+    ) -> Region {
+        let mut rects: Vec<Rect> = Vec::new();
+        // Indices into `rects` of the runs in the previous row, which can be
+        // extended downwards if the current row has the same run.
+        let mut open: Vec<usize> = Vec::new();
+        for y in 0..height {
+            let mut next_open = Vec::new();
+            let mut x = 0;
+            while x < width {
+                if !covered(x, y) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < width && covered(x, y) {
+                    x += 1;
+                }
+                let (x0, x1) = (start as f64, x as f64);
+                let above = open
+                    .iter()
+                    .copied()
+                    .find(|&idx| rects[idx] == Rect::new(x0, rects[idx].y0, x1, y as f64));
+                match above {
+                    Some(idx) => {
+                        rects[idx].y1 += 1.0;
+                        next_open.push(idx);
+                    }
+                    None => {
+                        next_open.push(rects.len());
+                        rects.push(Rect::new(x0, y as f64, x1, y as f64 + 1.0));
+                    }
+                }
+            }
+            open = next_open;
+        }
+        Region { rects }
+    }
 }
 
 impl std::ops::AddAssign<Vec2> for Region {
@@ -131,3 +227,28 @@ impl From<Rect> for Region {
         Region { rects: vec![rect] }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kurbo::Circle;
+
+    #[test]
+    fn mask_merges_runs() {
+        // An L shape: a 2x3 column with an extra cell at the bottom right.
+        let region = Region::from_mask(3, 3, |x, y| x < 2 || y == 2);
+        assert_eq!(
+            region.rects(),
+            &[Rect::new(0.0, 0.0, 2.0, 2.0), Rect::new(0.0, 2.0, 3.0, 3.0)]
+        );
+    }
+
+    #[test]
+    fn shape_is_approximated() {
+        let circle = Circle::new((50.0, 50.0), 20.0);
+        let region = Region::from_shape(&circle, 1.0);
+        assert!(region.contains(Point::new(50.0, 50.0)));
+        assert!(!region.contains(Point::new(32.0, 32.0)));
+        assert!(region.bounding_box().area() <= 40.0 * 40.0);
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Power and network status of the system.
+
+use crate::backend::application as backend;
+
+/// Where the system is drawing its power from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerSource {
+    /// Mains power; this includes laptops that are plugged in.
+    Ac,
+    /// A battery.
+    Battery,
+    /// The power source could not be determined.
+    Unknown,
+}
+
+/// A snapshot of the power and network status of the system.
+///
+/// Any part of the status may be unknown, depending on the platform and hardware.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystemStatus {
+    /// Where the system is drawing its power from.
+    pub power_source: PowerSource,
+    /// The remaining battery charge, from `0.0` to `1.0`, if the system has a battery.
+    pub battery_level: Option<f64>,
+    /// Whether the system has a network connection.
+    ///
+    /// This only reflects whether any network interface is connected,
+    /// not whether a particular host can be reached.
+    pub online: Option<bool>,
+}
+
+impl SystemStatus {
+    /// Query the current status of the system.
+    ///
+    /// This may do blocking I/O, and can be called from any thread.
+    ///
+    /// # Platform support
+    ///
+    /// The power status is available on Windows and Linux, and the network status
+    /// is available on Windows, Linux and the web. Everything else is reported as unknown.
+    ///
+    /// macOS is not supported yet, and always reports the [`Default`] status.
+    pub fn current() -> SystemStatus {
+        backend::Application::system_status()
+    }
+
+    /// Returns `true` if the system is known to be running on battery power.
+    pub fn on_battery(&self) -> bool {
+        self.power_source == PowerSource::Battery
+    }
+}
+
+impl Default for SystemStatus {
+    fn default() -> Self {
+        SystemStatus {
+            power_source: PowerSource::Unknown,
+            battery_level: None,
+            online: None,
+        }
+    }
+}
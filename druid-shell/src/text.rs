@@ -440,6 +440,15 @@ pub trait InputHandler {
     /// `slice_bounding_box` may panic.
     fn slice_bounding_box(&self, range: Range<usize>) -> Option<Rect>;
 
+    /// Returns the rect, in window coordinates, that the platform's candidate
+    /// window should be placed against while text is being composed.
+    ///
+    /// If this returns `None` (the default) the candidate window is placed
+    /// against the composition, as reported by `slice_bounding_box`.
+    fn candidate_anchor(&self) -> Option<Rect> {
+        None
+    }
+
     /// Applies an [`Action`] to the text field.
     ///
     /// Requires a mutable lock.
@@ -18,6 +18,7 @@ use std::any::Any;
 use std::time::Duration;
 
 use crate::application::Application;
+use crate::aux_controls::AuxControl;
 use crate::backend::window as backend;
 use crate::common_util::Counter;
 use crate::dialog::{FileDialogOptions, FileInfo};
@@ -145,6 +146,33 @@ impl FileDialogToken {
     }
 }
 
+/// Keeps secure text entry enabled while it exists.
+///
+/// This is returned by [`WindowHandle::begin_secure_input`].
+#[must_use = "secure text entry is only enabled until the token is dropped"]
+pub struct SecureInputToken {
+    // Dropping the backend guard ends secure text entry.
+    secure_input: Option<backend::SecureInput>,
+}
+
+impl SecureInputToken {
+    /// Returns `true` if the platform honoured the request.
+    ///
+    /// This is `false` if the platform has no secure text entry mode, or if the
+    /// request failed.
+    pub fn is_active(&self) -> bool {
+        self.secure_input.is_some()
+    }
+}
+
+impl std::fmt::Debug for SecureInputToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SecureInputToken")
+            .field("active", &self.is_active())
+            .finish()
+    }
+}
+
 /// Levels in the window system - Z order for display purposes.
 /// Describes the purpose of a window and should be mapped appropriately to match platform
 /// conventions.
@@ -340,6 +368,35 @@ impl WindowHandle {
         self.0.set_menu(menu.into_inner())
     }
 
+    /// Set the auxiliary controls for this window, replacing any previous ones.
+    ///
+    /// See [`AuxControl`] for how these are shown on each platform.
+    pub fn set_aux_controls(&self, controls: Vec<AuxControl>) {
+        self.0.set_aux_controls(controls)
+    }
+
+    /// Enables secure text entry until the returned token is dropped.
+    ///
+    /// While secure text entry is enabled, other applications are prevented from
+    /// observing keyboard input, for example through event taps or keyboard grabs.
+    /// This is intended for password fields, and should only be enabled while such a
+    /// field is focused, since it also disables global hotkeys and some accessibility
+    /// features.
+    ///
+    /// # Platform support
+    ///
+    /// This is supported on macOS, GTK, and X11, where it grabs the keyboard for the
+    /// window. Wayland compositors already keep input from other clients, and
+    /// Windows and the web have no equivalent; there the returned token is not
+    /// [active](SecureInputToken::is_active).
+    pub fn begin_secure_input(&self) -> SecureInputToken {
+        let secure_input = self.0.begin_secure_input();
+        if secure_input.is_none() {
+            tracing::debug!("Secure text entry is not available");
+        }
+        SecureInputToken { secure_input }
+    }
+
     /// Get access to a type that can perform text layout.
     pub fn text(&self) -> PietText {
         self.0.text()
@@ -606,6 +663,14 @@ pub trait WinHandler {
     #[allow(unused_variables)]
     fn command(&mut self, id: u32) {}
 
+    /// Called when one of the window's [auxiliary controls] is used.
+    ///
+    /// `value` is the new value of a slider, and `None` for buttons.
+    ///
+    /// [auxiliary controls]: WindowHandle::set_aux_controls
+    #[allow(unused_variables)]
+    fn aux_control(&mut self, id: u32, value: Option<f64>) {}
+
     /// Called when a "Save As" dialog is closed.
     ///
     /// `token` is the value returned by [`WindowHandle::save_as`]. `file` contains the information
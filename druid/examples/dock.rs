@@ -0,0 +1,58 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This example demonstrates the `Dock` widget, with panels that can be
+//! rearranged by dragging their tabs.
+
+// On Windows platform, don't show a console when opening the app.
+#![windows_subsystem = "windows"]
+
+use druid::widget::{Align, Dock, DockEdge, DockLayout, Flex, Label, TextBox};
+use druid::{AppLauncher, Widget, WidgetExt, WindowDesc};
+
+fn build_app() -> impl Widget<String> {
+    let layout = DockLayout::new()
+        .with_panel("files", DockEdge::Left)
+        .with_panel("search", DockEdge::Left)
+        .with_panel("output", DockEdge::Bottom);
+
+    let editor = TextBox::multiline().expand().padding(8.0);
+    Dock::new(editor)
+        .with_panel("files", "Files", || {
+            Flex::column()
+                .with_child(Label::new("main.rs"))
+                .with_child(Label::new("lib.rs"))
+                .padding(8.0)
+        })
+        .with_panel("search", "Search", || {
+            TextBox::new()
+                .with_placeholder("Search")
+                .expand_width()
+                .padding(8.0)
+        })
+        .with_panel("output", "Output", || {
+            Align::centered(Label::new(|text: &String, _: &_| {
+                format!("{} characters", text.chars().count())
+            }))
+        })
+        .with_layout(layout)
+}
+
+pub fn main() {
+    let window = WindowDesc::new(build_app()).title("Dock");
+    AppLauncher::with_window(window)
+        .log_to_console()
+        .launch("Drag the tabs to another edge, or into the middle to float them.".to_string())
+        .expect("launch failed");
+}
@@ -55,7 +55,9 @@ fn build_app() -> impl Widget<u32> {
             .split_point(0.5)
             .draggable(true)
             .solid_bar(true)
-            .min_size(60.0, 60.0),
+            .min_size(60.0, 60.0)
+            .collapsible(true)
+            .snap_points(vec![0.25, 0.5, 0.75], 8.0),
         )
         .border(Color::WHITE, 1.0),
     );
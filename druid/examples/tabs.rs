@@ -29,6 +29,7 @@ use instant::Duration;
 struct DynamicTabData {
     highest_tab: usize,
     removed_tabs: usize,
+    moved_tabs: usize,
     tab_labels: Vector<usize>,
 }
 
@@ -37,6 +38,7 @@ impl DynamicTabData {
         DynamicTabData {
             highest_tab,
             removed_tabs: 0,
+            moved_tabs: 0,
             tab_labels: (1..=highest_tab).collect(),
         }
     }
@@ -55,9 +57,19 @@ impl DynamicTabData {
         }
     }
 
+    fn move_tab(&mut self, idx: usize, to: usize) {
+        if idx >= self.tab_labels.len() || to >= self.tab_labels.len() {
+            tracing::warn!("Attempt to move tab {} to invalid index {}", idx, to)
+        } else {
+            self.moved_tabs += 1;
+            let tab = self.tab_labels.remove(idx);
+            self.tab_labels.insert(to, tab);
+        }
+    }
+
     // This provides a key that will monotonically increase as interactions occur.
-    fn tabs_key(&self) -> (usize, usize) {
-        (self.highest_tab, self.removed_tabs)
+    fn tabs_key(&self) -> (usize, usize, usize) {
+        (self.highest_tab, self.removed_tabs, self.moved_tabs)
     }
 }
 
@@ -195,6 +207,16 @@ impl TabsPolicy for NumberedTabs {
         }
     }
 
+    fn can_move_tab(&self, _key: Self::Key, _data: &DynamicTabData) -> bool {
+        true
+    }
+
+    fn move_tab(&self, key: Self::Key, to: usize, data: &mut DynamicTabData) {
+        if let Some(idx) = data.tab_labels.index_of(&key) {
+            data.move_tab(idx, to)
+        }
+    }
+
     fn tab_label(
         &self,
         _key: Self::Key,
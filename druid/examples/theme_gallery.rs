@@ -0,0 +1,115 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shows the built-in widgets in each of the theme presets, which can be switched
+//! while the app is running.
+
+// On Windows platform, don't show a console when opening the app.
+#![windows_subsystem = "windows"]
+
+use druid::theme::ThemePreset;
+use druid::widget::{
+    Button, Checkbox, CrossAxisAlignment, Flex, Label, ProgressBar, RadioGroup, Scroll,
+    SegmentedControl, Slider, Switch, TextBox,
+};
+use druid::{AppLauncher, Data, Lens, Widget, WidgetExt, WindowDesc};
+
+#[derive(Clone, Copy, PartialEq, Data)]
+enum Size {
+    Small,
+    Medium,
+    Large,
+}
+
+#[derive(Clone, Data, Lens)]
+struct GalleryState {
+    theme: ThemePreset,
+    text: String,
+    enabled: bool,
+    value: f64,
+    size: Size,
+}
+
+pub fn main() {
+    let window = WindowDesc::new(build_root_widget())
+        .title("Theme gallery")
+        .window_size((480.0, 520.0));
+
+    let state = GalleryState {
+        theme: ThemePreset::default(),
+        text: String::new(),
+        enabled: true,
+        value: 0.4,
+        size: Size::Medium,
+    };
+
+    AppLauncher::with_window(window)
+        .log_to_console()
+        .launch(state)
+        .expect("Failed to launch application");
+}
+
+fn build_root_widget() -> impl Widget<GalleryState> {
+    let theme_picker =
+        SegmentedControl::new(ThemePreset::ALL.map(|preset| (preset.name(), preset)))
+            .lens(GalleryState::theme);
+
+    let lines = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Scroll to see the scroll bar."))
+        .with_spacer(200.0)
+        .with_child(Label::new("The end."));
+
+    let gallery = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(theme_picker)
+        .with_spacer(16.0)
+        .with_child(
+            TextBox::new()
+                .with_placeholder("Type here; selected text and the caret follow the theme")
+                .expand_width()
+                .lens(GalleryState::text),
+        )
+        .with_default_spacer()
+        .with_child(
+            Flex::row()
+                .with_child(Checkbox::new("Enabled").lens(GalleryState::enabled))
+                .with_default_spacer()
+                .with_child(Switch::new().lens(GalleryState::enabled)),
+        )
+        .with_default_spacer()
+        .with_child(
+            Button::new("Reset")
+                .on_click(|_, data: &mut GalleryState, _| data.value = 0.4)
+                .disabled_if(|data, _| !data.enabled),
+        )
+        .with_default_spacer()
+        .with_child(Slider::new().expand_width().lens(GalleryState::value))
+        .with_default_spacer()
+        .with_child(ProgressBar::new().expand_width().lens(GalleryState::value))
+        .with_default_spacer()
+        .with_child(
+            RadioGroup::row([
+                ("Small", Size::Small),
+                ("Medium", Size::Medium),
+                ("Large", Size::Large),
+            ])
+            .lens(GalleryState::size),
+        )
+        .with_default_spacer()
+        .with_flex_child(Scroll::new(lines).vertical().expand_width(), 1.0)
+        .padding(16.0);
+
+    ThemePreset::scope(|data: &GalleryState| data.theme, gallery)
+}
@@ -14,14 +14,29 @@
 
 //! Window building and app lifecycle.
 
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
 use crate::ext_event::{ExtEventHost, ExtEventSink};
-use crate::kurbo::{Point, Size};
+use crate::kurbo::{Point, Rect, Size};
 use crate::menu::MenuManager;
-use crate::shell::{Application, Error as PlatformError, WindowBuilder, WindowHandle, WindowLevel};
+#[cfg(feature = "persistence")]
+use crate::persistence::{PersistDelegate, Persistence, SaveTimer};
+use crate::shell::{
+    Application, Error as PlatformError, Region, Screen, WindowBuilder, WindowHandle, WindowLevel,
+};
+use crate::subscription::{LensSubscriber, Subscriber};
+use crate::theme::{SystemThemes, Theme};
+#[cfg(feature = "theme-file")]
+use crate::theme_file::ThemeFile;
 use crate::widget::LabelText;
 use crate::win_handler::{AppHandler, AppState};
 use crate::window::WindowId;
-use crate::{AppDelegate, Data, Env, LocalizedString, Menu, Widget};
+use crate::{
+    AppDelegate, AuxControls, ColorScheme, Data, DelegateCtx, Env, Lens, LocalizedString, Menu,
+    Widget,
+};
 
 use tracing::warn;
 
@@ -37,6 +52,15 @@ pub struct AppLauncher<T> {
     l10n_resources: Option<(Vec<String>, String)>,
     delegate: Option<Box<dyn AppDelegate<T>>>,
     ext_event_host: ExtEventHost,
+    system_status_interval: Option<Duration>,
+    color_scheme_interval: Option<Duration>,
+    system_themes: Option<SystemThemes>,
+    fonts: Vec<Arc<[u8]>>,
+    subscribers: Vec<Box<dyn Subscriber<T>>>,
+    #[cfg(feature = "persistence")]
+    persistence: Option<Persistence<T>>,
+    #[cfg(feature = "theme-file")]
+    theme_file: Option<std::path::PathBuf>,
 }
 
 /// Defines how a windows size should be determined
@@ -65,6 +89,7 @@ pub struct WindowConfig {
     pub(crate) level: Option<WindowLevel>,
     pub(crate) always_on_top: Option<bool>,
     pub(crate) state: Option<WindowState>,
+    pub(crate) input_region: Option<Region>,
 }
 
 /// A description of a window to be instantiated.
@@ -91,8 +116,24 @@ pub struct PendingWindow<T> {
     pub(crate) title: LabelText<T>,
     pub(crate) transparent: bool,
     pub(crate) menu: Option<MenuManager<T>>,
-    pub(crate) size_policy: WindowSizePolicy, // This is copied over from the WindowConfig
-                                              // when the native window is constructed.
+    // This is copied over from the WindowConfig when the native window is constructed.
+    pub(crate) size_policy: WindowSizePolicy,
+    pub(crate) kiosk: Option<KioskMode>,
+    pub(crate) aux_controls: Option<AuxControls>,
+}
+
+/// Options for running a window in kiosk mode.
+///
+/// A kiosk window covers an entire monitor, has no titlebar and stays on top of
+/// other windows. This is intended for signage, point-of-sale terminals and similar
+/// deployments, where the application is the only thing the user should interact with.
+///
+/// Use with [`WindowDesc::kiosk`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KioskMode {
+    pub(crate) monitor: Option<usize>,
+    pub(crate) hide_cursor_after: Option<Duration>,
+    pub(crate) suppress_shortcuts: bool,
 }
 
 impl<T: Data> PendingWindow<T> {
@@ -108,6 +149,8 @@ impl<T: Data> PendingWindow<T> {
             menu: MenuManager::platform_default(),
             transparent: false,
             size_policy: WindowSizePolicy::User,
+            kiosk: None,
+            aux_controls: None,
         }
     }
 
@@ -139,6 +182,61 @@ impl<T: Data> PendingWindow<T> {
     }
 }
 
+impl KioskMode {
+    /// Create kiosk options that cover the primary monitor.
+    pub fn new() -> KioskMode {
+        KioskMode::default()
+    }
+
+    /// Builder-style method to cover the monitor at `index` in [`Screen::get_monitors`]
+    /// instead of the primary monitor.
+    ///
+    /// If there is no monitor at that index, the primary monitor is used.
+    pub fn on_monitor(mut self, index: usize) -> Self {
+        self.monitor = Some(index);
+        self
+    }
+
+    /// Builder-style method to hide the cursor when the mouse has not been used
+    /// for `delay`. The cursor is shown again as soon as the mouse moves.
+    pub fn hide_cursor_after(mut self, delay: Duration) -> Self {
+        self.hide_cursor_after = Some(delay);
+        self
+    }
+
+    /// Builder-style method to suppress shortcuts that would leave the application.
+    ///
+    /// The window gets no menu, so platform default shortcuts such as quitting
+    /// on macOS are not available, and requests from the system to close the
+    /// window, such as <kbd>Alt</kbd>+<kbd>F4</kbd>, are ignored. The window can
+    /// still be closed with [`CLOSE_WINDOW`].
+    ///
+    /// System-wide shortcuts such as <kbd>Alt</kbd>+<kbd>Tab</kbd> or
+    /// <kbd>Ctrl</kbd>+<kbd>Alt</kbd>+<kbd>Del</kbd> can not be intercepted by
+    /// applications; these have to be disabled in the configuration of the
+    /// operating system.
+    ///
+    /// [`CLOSE_WINDOW`]: crate::commands::CLOSE_WINDOW
+    pub fn suppress_shortcuts(mut self, suppress: bool) -> Self {
+        self.suppress_shortcuts = suppress;
+        self
+    }
+
+    /// The area of the monitor the window should cover, in [display points]
+    /// relative to the origin of the [virtual screen].
+    ///
+    /// [display points]: crate::Scale
+    /// [virtual screen]: crate::Screen
+    pub(crate) fn monitor_rect(&self) -> Option<Rect> {
+        let monitors = Screen::get_monitors();
+        self.monitor
+            .and_then(|idx| monitors.get(idx))
+            .or_else(|| monitors.iter().find(|m| m.is_primary()))
+            .or_else(|| monitors.first())
+            .map(|monitor| monitor.virtual_rect())
+    }
+}
+
 impl<T: Data> AppLauncher<T> {
     /// Create a new `AppLauncher` with the provided window.
     pub fn with_window(window: WindowDesc<T>) -> Self {
@@ -148,6 +246,15 @@ impl<T: Data> AppLauncher<T> {
             l10n_resources: None,
             delegate: None,
             ext_event_host: ExtEventHost::new(),
+            system_status_interval: None,
+            color_scheme_interval: None,
+            system_themes: None,
+            fonts: Vec::new(),
+            subscribers: Vec::new(),
+            #[cfg(feature = "persistence")]
+            persistence: None,
+            #[cfg(feature = "theme-file")]
+            theme_file: None,
         }
     }
 
@@ -166,6 +273,169 @@ impl<T: Data> AppLauncher<T> {
         self
     }
 
+    /// Check the power and network status of the system every `interval`, and send
+    /// [`SYSTEM_STATUS_CHANGED`] when it changes.
+    ///
+    /// The status is checked on a background thread, so this is not available on the web.
+    ///
+    /// [`SYSTEM_STATUS_CHANGED`]: crate::commands::SYSTEM_STATUS_CHANGED
+    pub fn watch_system_status(mut self, interval: Duration) -> Self {
+        self.system_status_interval = Some(interval);
+        self
+    }
+
+    /// Check the user's preferred [`ColorScheme`] every `interval`, and send
+    /// [`COLOR_SCHEME_CHANGED`] when it changes.
+    ///
+    /// The color scheme is checked on a background thread, so this is not
+    /// available on the web.
+    ///
+    /// [`ColorScheme`]: crate::ColorScheme
+    /// [`COLOR_SCHEME_CHANGED`]: crate::commands::COLOR_SCHEME_CHANGED
+    pub fn watch_color_scheme(mut self, interval: Duration) -> Self {
+        self.color_scheme_interval = Some(interval);
+        self
+    }
+
+    /// Switch the whole app between a light and a dark [`Theme`], following
+    /// the user's preferred [`ColorScheme`].
+    ///
+    /// The theme for the current color scheme is applied before
+    /// [`configure_env`], and the color scheme is then checked every second,
+    /// unless another interval is set with [`watch_color_scheme`]. When there
+    /// is no preference, the light theme is used.
+    ///
+    /// ```no_run
+    /// # use druid::{AppLauncher, WindowDesc};
+    /// # use druid::widget::Label;
+    /// use druid::theme::ThemePreset;
+    ///
+    /// # let window = WindowDesc::new(Label::<()>::new("hello"));
+    /// AppLauncher::with_window(window).follow_system_theme(ThemePreset::Light, ThemePreset::Dark);
+    /// ```
+    ///
+    /// [`Theme`]: crate::theme::Theme
+    /// [`ColorScheme`]: crate::ColorScheme
+    /// [`configure_env`]: AppLauncher::configure_env
+    /// [`watch_color_scheme`]: AppLauncher::watch_color_scheme
+    pub fn follow_system_theme(mut self, light: impl Into<Theme>, dark: impl Into<Theme>) -> Self {
+        self.system_themes = Some(SystemThemes {
+            light: light.into(),
+            dark: dark.into(),
+        });
+        self.color_scheme_interval
+            .get_or_insert(Duration::from_secs(1));
+        self
+    }
+
+    /// Load a font from the bytes of a font file (such as a `.ttf` or `.otf`),
+    /// so that it can be used without being installed.
+    ///
+    /// The font is loaded into each window as it is created, and is used by
+    /// giving its family name to a [`FontDescriptor`]. Not every platform can
+    /// load fonts this way; where it is unsupported, a warning is logged.
+    ///
+    /// ```no_run
+    /// # use druid::{AppLauncher, FontDescriptor, FontFamily, WindowDesc};
+    /// # use druid::widget::Label;
+    /// # let window = WindowDesc::new(Label::<()>::new("hello"));
+    /// const FONT: &[u8] = &[]; // include_bytes!("../assets/Inter.ttf");
+    ///
+    /// let font = FontDescriptor::new(FontFamily::new_unchecked("Inter"));
+    /// AppLauncher::with_window(window)
+    ///     .load_font(FONT)
+    ///     .configure_env(move |env, _| env.set(druid::theme::UI_FONT, font.clone()));
+    /// ```
+    ///
+    /// [`FontDescriptor`]: crate::FontDescriptor
+    pub fn load_font(mut self, data: impl Into<Arc<[u8]>>) -> Self {
+        self.fonts.push(data.into());
+        self
+    }
+
+    /// Load a font from a font file, like [`load_font`].
+    ///
+    /// If the file cannot be read a warning is logged, and text that asks for
+    /// the font will use a fallback.
+    ///
+    /// [`load_font`]: AppLauncher::load_font
+    pub fn load_font_file(self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        match std::fs::read(path) {
+            Ok(data) => self.load_font(data),
+            Err(e) => {
+                warn!("failed to read font file {}: {}", path.display(), e);
+                self
+            }
+        }
+    }
+
+    /// Call `callback` whenever the part of the data selected by `lens` changes.
+    ///
+    /// This lets code outside the widget tree, such as a logger or a
+    /// background service, react to changes of the data. The callback is run
+    /// on the UI thread after each update, with the old and the new value;
+    /// it can submit commands through the [`DelegateCtx`].
+    ///
+    /// ```
+    /// # use druid::{AppLauncher, Data, Lens, WindowDesc};
+    /// # use druid::widget::Label;
+    /// #[derive(Clone, Data, Lens)]
+    /// struct AppState {
+    ///     volume: f64,
+    /// }
+    ///
+    /// # let window = WindowDesc::new(Label::new("hello"));
+    /// AppLauncher::with_window(window).subscribe(AppState::volume, |old, new, _ctx| {
+    ///     println!("volume changed from {old} to {new}");
+    /// });
+    /// ```
+    pub fn subscribe<U: Data>(
+        mut self,
+        lens: impl Lens<T, U> + 'static,
+        callback: impl FnMut(&U, &U, &mut DelegateCtx) + 'static,
+    ) -> Self {
+        self.subscribers
+            .push(Box::new(LensSubscriber::new(lens, callback)));
+        self
+    }
+
+    /// Save part or all of the app data between runs.
+    ///
+    /// The saved data is restored into the data given to [`launch`], before
+    /// the environment is configured. It is saved again whenever a window
+    /// closes and when the application quits. Errors are logged as warnings.
+    ///
+    /// See the [`persistence`] module for details.
+    ///
+    /// [`launch`]: AppLauncher::launch
+    /// [`persistence`]: crate::persistence
+    #[cfg(feature = "persistence")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "persistence")))]
+    pub fn persist(mut self, persistence: Persistence<T>) -> Self {
+        self.persistence = Some(persistence);
+        self
+    }
+
+    /// Apply the theme file at `path` to the whole app.
+    ///
+    /// The theme is applied on top of the [system theme], before
+    /// [`configure_env`]. In debug builds, the file is also checked twice a
+    /// second, and applied again whenever it changes. Errors in the file are
+    /// logged as warnings.
+    ///
+    /// See the [`theme_file`] module for the format of the file.
+    ///
+    /// [system theme]: AppLauncher::follow_system_theme
+    /// [`configure_env`]: AppLauncher::configure_env
+    /// [`theme_file`]: crate::theme_file
+    #[cfg(feature = "theme-file")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "theme-file")))]
+    pub fn theme_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.theme_file = Some(path.into());
+        self
+    }
+
     /// Initialize a minimal logger with DEBUG max level for printing logs out to stderr.
     ///
     /// This is meant for use during development only.
@@ -254,34 +524,173 @@ impl<T: Data> AppLauncher<T> {
     pub fn launch(mut self, data: T) -> Result<(), PlatformError> {
         let app = Application::new()?;
 
+        #[cfg(feature = "persistence")]
+        let mut data = data;
+        #[cfg(feature = "persistence")]
+        let persist_sink = self.ext_event_host.make_sink();
+        #[cfg(feature = "persistence")]
+        let persistence = self.persistence.take().map(|persistence| {
+            if let Err(e) = persistence.restore(&mut data) {
+                warn!("failed to restore app data: {}", e);
+            }
+            let persistence = std::rc::Rc::new(persistence);
+            let delegate = PersistDelegate::new(persistence.clone(), self.delegate.take());
+            self.delegate = Some(Box::new(delegate));
+            persistence
+        });
+
         let mut env = self
             .l10n_resources
             .map(|it| Env::with_i10n(it.0, &it.1))
             .unwrap_or_else(Env::with_default_i10n);
 
+        if let Some(themes) = &self.system_themes {
+            themes.for_scheme(ColorScheme::current()).apply(&mut env);
+        }
+
+        #[cfg(feature = "theme-file")]
+        let theme_file = self.theme_file.take().map(|path| {
+            let file = ThemeFile::load(path);
+            file.theme().apply(&mut env);
+            file
+        });
+
         if let Some(f) = self.env_setup.take() {
             f(&mut env, &data);
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        let sink = self.ext_event_host.make_sink();
         let mut state = AppState::new(
             app.clone(),
             data,
             env,
             self.delegate.take(),
             self.ext_event_host,
+            self.fonts,
+            self.subscribers,
+            self.system_themes,
         );
 
+        #[cfg(all(feature = "theme-file", not(target_arch = "wasm32")))]
+        let _theme_file_watcher =
+            theme_file
+                .as_ref()
+                .filter(|_| cfg!(debug_assertions))
+                .map(|file| {
+                    let path = file.path().to_owned();
+                    Watcher::spawn(
+                        sink.clone(),
+                        Duration::from_millis(500),
+                        crate::theme_file::THEME_FILE_CHANGED,
+                        move || ThemeFile::modified(&path),
+                    )
+                });
+        #[cfg(feature = "theme-file")]
+        if let Some(file) = theme_file {
+            state.set_theme_file(file);
+        }
+
         for desc in self.windows {
             let window = desc.build_native(&mut state)?;
             window.show();
         }
 
+        #[cfg(not(target_arch = "wasm32"))]
+        let _watchers = (
+            self.system_status_interval.map(|interval| {
+                Watcher::spawn(
+                    sink.clone(),
+                    interval,
+                    crate::commands::SYSTEM_STATUS_CHANGED,
+                    crate::shell::SystemStatus::current,
+                )
+            }),
+            self.color_scheme_interval.map(|interval| {
+                Watcher::spawn(
+                    sink,
+                    interval,
+                    crate::commands::COLOR_SCHEME_CHANGED,
+                    ColorScheme::current,
+                )
+            }),
+        );
+        #[cfg(target_arch = "wasm32")]
+        if self.system_status_interval.is_some() {
+            warn!("Watching the system status is not supported on the web");
+        }
+        #[cfg(target_arch = "wasm32")]
+        if self.color_scheme_interval.is_some() {
+            warn!("Watching the color scheme is not supported on the web");
+        }
+
+        #[cfg(feature = "persistence")]
+        let _save_timer = persistence
+            .as_ref()
+            .and_then(|persistence| persistence.interval())
+            .map(|interval| SaveTimer::spawn(persist_sink, interval));
+
+        #[cfg(feature = "persistence")]
+        let final_state = state.clone();
         let handler = AppHandler::new(state);
         app.run(Some(Box::new(handler)));
+        #[cfg(feature = "persistence")]
+        if let Some(persistence) = persistence {
+            persistence.save_or_warn(&final_state.data());
+        }
         Ok(())
     }
 }
 
+/// Polls a setting on a background thread, and submits it whenever
+/// it changes, until it is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+struct Watcher {
+    stop: std::sync::mpsc::Sender<()>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Watcher {
+    fn spawn<V: Copy + PartialEq + Send + 'static>(
+        sink: ExtEventSink,
+        interval: Duration,
+        selector: crate::Selector<V>,
+        current: impl Fn() -> V + Send + 'static,
+    ) -> Watcher {
+        use crate::Target;
+        use std::sync::mpsc::{channel, RecvTimeoutError};
+
+        let (stop, stopped) = channel();
+        std::thread::spawn(move || {
+            let mut last = None;
+            loop {
+                let value = current();
+                if last != Some(value) {
+                    last = Some(value);
+                    if sink
+                        .submit_command(selector, value, Target::Global)
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+                // Either a stop message, or the watcher being dropped, ends the thread.
+                if stopped.recv_timeout(interval) != Err(RecvTimeoutError::Timeout) {
+                    break;
+                }
+            }
+        });
+        Watcher { stop }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+    }
+}
+
 impl Default for WindowConfig {
     fn default() -> Self {
         WindowConfig {
@@ -295,6 +704,7 @@ impl Default for WindowConfig {
             level: None,
             always_on_top: None,
             state: None,
+            input_region: None,
         }
     }
 }
@@ -399,6 +809,24 @@ impl WindowConfig {
         self
     }
 
+    /// Sets the shape of the window, as the region in which it receives input.
+    ///
+    /// Input outside of the region passes through to whatever is below the
+    /// window. On some platforms, the window is also only drawn inside the
+    /// region; for a consistent look, combine this with a [`transparent`]
+    /// window without a titlebar. [`Region::from_shape`] and
+    /// [`Region::from_image_alpha`] can be used to create a region from a
+    /// path or an image mask.
+    ///
+    /// See [`WindowHandle::set_input_region`] for platform support.
+    ///
+    /// [`transparent`]: WindowConfig::transparent
+    /// [`WindowHandle::set_input_region`]: crate::WindowHandle::set_input_region
+    pub fn set_input_region(mut self, region: Region) -> Self {
+        self.input_region = Some(region);
+        self
+    }
+
     /// Apply this window configuration to the passed in WindowBuilder
     pub fn apply_to_builder(&self, builder: &mut WindowBuilder) {
         if let Some(resizable) = self.resizable {
@@ -468,6 +896,10 @@ impl WindowConfig {
         if let Some(state) = self.state {
             win_handle.set_window_state(state);
         }
+
+        if let Some(region) = &self.input_region {
+            win_handle.set_input_region(Some(region.clone()));
+        }
     }
 }
 
@@ -610,6 +1042,40 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Sets the shape of the window, as the region in which it receives input.
+    ///
+    /// See [`WindowConfig::set_input_region`] for more information.
+    pub fn set_input_region(mut self, region: Region) -> Self {
+        self.config = self.config.set_input_region(region);
+        self
+    }
+
+    /// Run the window in kiosk mode.
+    ///
+    /// The window covers the monitor selected in `kiosk`, without a titlebar,
+    /// and stays on top of other windows. See [`KioskMode`] for the other options.
+    pub fn kiosk(mut self, kiosk: KioskMode) -> Self {
+        self.config = self
+            .config
+            .show_titlebar(false)
+            .resizable(false)
+            .set_always_on_top(true);
+        if kiosk.suppress_shortcuts {
+            self.pending.menu = None;
+        }
+        self.pending.kiosk = Some(kiosk);
+        self
+    }
+
+    /// Set the controls that the platform shows for this window outside of its content,
+    /// such as in the Touch Bar on macOS.
+    ///
+    /// See [`AuxControls`] for more information.
+    pub fn aux_controls(mut self, controls: AuxControls) -> Self {
+        self.pending.aux_controls = Some(controls);
+        self
+    }
+
     /// Set the [`WindowConfig`] of window.
     pub fn with_config(mut self, config: WindowConfig) -> Self {
         self.config = config;
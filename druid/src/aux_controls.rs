@@ -0,0 +1,119 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Controls shown by the platform outside of a window.
+
+use std::ops::RangeInclusive;
+
+use crate::shell::AuxControl;
+use crate::{Command, ImageBuf, Selector};
+
+/// A small set of buttons and sliders that the platform shows outside of a window.
+///
+/// On macOS these are shown in the Touch Bar, and on Windows the buttons are shown in the
+/// thumbnail toolbar of the window's taskbar preview. Other platforms ignore them. See
+/// [`druid_shell::AuxControl`] for the limitations of each platform.
+///
+/// Each control dispatches a command when it is used. Commands with [`Target::Auto`] are
+/// sent to the window the controls belong to.
+///
+/// Set the controls of a window with [`WindowDesc::aux_controls`], or change them later with
+/// the [`SET_AUX_CONTROLS`] command.
+///
+/// [`druid_shell::AuxControl`]: crate::shell::AuxControl
+/// [`Target::Auto`]: crate::Target::Auto
+/// [`WindowDesc::aux_controls`]: crate::WindowDesc::aux_controls
+/// [`SET_AUX_CONTROLS`]: crate::commands::SET_AUX_CONTROLS
+#[derive(Debug, Clone, Default)]
+pub struct AuxControls {
+    items: Vec<(AuxControl, AuxAction)>,
+}
+
+/// What happens when an auxiliary control is used.
+#[derive(Debug, Clone)]
+enum AuxAction {
+    Command(Command),
+    Slider(Selector<f64>),
+}
+
+impl AuxControls {
+    /// Create an empty set of controls.
+    pub fn new() -> AuxControls {
+        AuxControls::default()
+    }
+
+    /// Builder-style method to add a button that submits `command` when pressed.
+    pub fn with_button(self, title: impl Into<String>, command: impl Into<Command>) -> Self {
+        let control = AuxControl::button(self.next_id(), title);
+        self.with_item(control, AuxAction::Command(command.into()))
+    }
+
+    /// Builder-style method to add a button with an icon.
+    ///
+    /// The title is used as a tooltip or label, depending on the platform.
+    pub fn with_icon_button(
+        self,
+        title: impl Into<String>,
+        icon: ImageBuf,
+        command: impl Into<Command>,
+    ) -> Self {
+        let control = AuxControl::button(self.next_id(), title).icon(icon);
+        self.with_item(control, AuxAction::Command(command.into()))
+    }
+
+    /// Builder-style method to add a slider, which submits `selector` with the new value
+    /// whenever it is moved.
+    pub fn with_slider(
+        self,
+        title: impl Into<String>,
+        range: RangeInclusive<f64>,
+        value: f64,
+        selector: Selector<f64>,
+    ) -> Self {
+        let control = AuxControl::slider(self.next_id(), title, range, value);
+        self.with_item(control, AuxAction::Slider(selector))
+    }
+
+    /// Returns `true` if there are no controls.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn next_id(&self) -> u32 {
+        self.items.len() as u32
+    }
+
+    fn with_item(mut self, control: AuxControl, action: AuxAction) -> Self {
+        self.items.push((control, action));
+        self
+    }
+
+    /// The controls to pass to the platform.
+    pub(crate) fn platform_controls(&self) -> Vec<AuxControl> {
+        self.items
+            .iter()
+            .map(|(control, _)| control.clone())
+            .collect()
+    }
+
+    /// The command for a control that was used, with the new value for sliders.
+    pub(crate) fn command(&self, id: u32, value: Option<f64>) -> Option<Command> {
+        let (_, action) = self.items.get(id as usize)?;
+        match (action, value) {
+            (AuxAction::Command(command), _) => Some(command.clone()),
+            (AuxAction::Slider(selector), Some(value)) => Some(selector.with(value)),
+            (AuxAction::Slider(_), None) => None,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simulating color vision deficiencies, to check that a UI remains usable.
+
+use crate::{Color, Env};
+
+/// A type of color vision deficiency that can be simulated with the
+/// [`SIMULATE_COLOR_BLINDNESS`] command.
+///
+/// The simulation uses the model of Machado, Oliveira and Fernandes (2009) at full
+/// severity, applied to linear RGB.
+///
+/// [`SIMULATE_COLOR_BLINDNESS`]: crate::commands::SIMULATE_COLOR_BLINDNESS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorBlindness {
+    /// Missing long-wavelength (red) cones.
+    Protanopia,
+    /// Missing medium-wavelength (green) cones.
+    Deuteranopia,
+    /// Missing short-wavelength (blue) cones.
+    Tritanopia,
+}
+
+impl ColorBlindness {
+    /// Returns how `color` appears to someone with this deficiency.
+    ///
+    /// The alpha channel is left unchanged.
+    pub fn simulate(self, color: &Color) -> Color {
+        let m = self.matrix();
+        let (r, g, b, a) = color.as_rgba();
+        let (r, g, b) = (to_linear(r), to_linear(g), to_linear(b));
+        let channel = |row: [f64; 3]| to_srgb(row[0] * r + row[1] * g + row[2] * b);
+        Color::rgba(channel(m[0]), channel(m[1]), channel(m[2]), a)
+    }
+
+    /// Returns a copy of `env` with every color replaced by its simulated appearance.
+    pub(crate) fn filter_env(self, env: &Env) -> Env {
+        env.map_colors(|color| self.simulate(color))
+    }
+
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            ColorBlindness::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorBlindness::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorBlindness::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+}
+
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_srgb(c: f64) -> f64 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grays_are_unchanged() {
+        for kind in [
+            ColorBlindness::Protanopia,
+            ColorBlindness::Deuteranopia,
+            ColorBlindness::Tritanopia,
+        ] {
+            for gray in [0, 64, 128, 255] {
+                let color = Color::rgba8(gray, gray, gray, 100);
+                assert_eq!(kind.simulate(&color).as_rgba8(), (gray, gray, gray, 100));
+            }
+        }
+    }
+
+    #[test]
+    fn red_and_green_are_confused() {
+        let red = ColorBlindness::Deuteranopia.simulate(&Color::rgb8(200, 40, 40));
+        let green = ColorBlindness::Deuteranopia.simulate(&Color::rgb8(40, 160, 40));
+        let (rr, rg, _, _) = red.as_rgba();
+        let (gr, gg, _, _) = green.as_rgba();
+        assert!((rr - rg).abs() < 0.25);
+        assert!((gr - gg).abs() < 0.25);
+    }
+}
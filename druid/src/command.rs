@@ -170,7 +170,9 @@ pub mod sys {
     use super::Selector;
     use crate::{
         sub_window::{SubWindowDesc, SubWindowUpdate},
-        FileDialogOptions, FileInfo, Rect, SingleUse, WidgetId, WindowConfig,
+        widget::{DockLayout, SplitPane},
+        ArcStr, AuxControls, ColorBlindness, ColorScheme, FileDialogOptions, FileInfo, Rect,
+        SingleUse, SystemStatus, WidgetId, WindowConfig,
     };
 
     /// Quit the running application. This command is handled by the Druid library.
@@ -248,6 +250,15 @@ pub mod sys {
     /// Show the application's "about" window.
     pub const SHOW_ABOUT: Selector = Selector::new("druid-builtin.menu-show-about");
 
+    /// Open a URL with the user's preferred application, such as a web browser.
+    ///
+    /// This command is handled by the Druid library. Links in [`RichText`]
+    /// made with [`AttributesAdder::link_url`] submit it when clicked.
+    ///
+    /// [`RichText`]: crate::text::RichText
+    /// [`AttributesAdder::link_url`]: crate::text::AttributesAdder::link_url
+    pub const OPEN_URL: Selector<String> = Selector::new("druid-builtin.open-url");
+
     /// Show all applications.
     pub const SHOW_ALL: Selector = Selector::new("druid-builtin.menu-show-all");
 
@@ -353,6 +364,103 @@ pub mod sys {
     /// [`Viewport::default_scroll_to_view_handling`]: crate::widget::Viewport::default_scroll_to_view_handling()
     pub const SCROLL_TO_VIEW: Selector<Rect> = Selector::new("druid-builtin.scroll-to");
 
+    /// Scroll the [`Anchor`] with this name into view.
+    ///
+    /// This should be sent to the window containing the anchor; the first anchor
+    /// with a matching name handles it.
+    ///
+    /// [`Anchor`]: crate::widget::Anchor
+    pub const NAVIGATE_TO_ANCHOR: Selector<ArcStr> =
+        Selector::new("druid-builtin.navigate-to-anchor");
+
+    /// Sent to the [`AppDelegate`] and all windows when the power or network status
+    /// of the system changes.
+    ///
+    /// This is only sent if the application was launched with
+    /// [`AppLauncher::watch_system_status`]; it is also sent once when the application starts.
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`AppLauncher::watch_system_status`]: crate::AppLauncher::watch_system_status
+    pub const SYSTEM_STATUS_CHANGED: Selector<SystemStatus> =
+        Selector::new("druid-builtin.system-status-changed");
+
+    /// Sent to the [`AppDelegate`] and all windows when the user's preferred
+    /// [`ColorScheme`] changes.
+    ///
+    /// This is only sent if the application was launched with
+    /// [`AppLauncher::watch_color_scheme`] or [`AppLauncher::follow_system_theme`];
+    /// it is also sent once when the application starts.
+    ///
+    /// [`AppDelegate`]: crate::AppDelegate
+    /// [`AppLauncher::watch_color_scheme`]: crate::AppLauncher::watch_color_scheme
+    /// [`AppLauncher::follow_system_theme`]: crate::AppLauncher::follow_system_theme
+    pub const COLOR_SCHEME_CHANGED: Selector<ColorScheme> =
+        Selector::new("druid-builtin.color-scheme-changed");
+
+    /// Sets the split point of a [`Split`], as a fraction of the split axis.
+    ///
+    /// This also expands a collapsed pane.
+    ///
+    /// [`Split`]: crate::widget::Split
+    pub const SET_SPLIT_POINT: Selector<f64> = Selector::new("druid-builtin.split-set-point");
+
+    /// Collapses the provided pane of a [`Split`], or expands it again with `None`.
+    ///
+    /// [`Split`]: crate::widget::Split
+    pub const COLLAPSE_SPLIT_PANE: Selector<Option<SplitPane>> =
+        Selector::new("druid-builtin.split-collapse-pane");
+
+    /// A notification sent by a [`Split`] when the user has finished dragging the splitter bar.
+    ///
+    /// The payload is the new split point, which can be persisted and restored with
+    /// [`SET_SPLIT_POINT`] or [`Split::split_point`].
+    ///
+    /// [`Split`]: crate::widget::Split
+    /// [`Split::split_point`]: crate::widget::Split::split_point
+    pub const SPLIT_POINT_CHANGED: Selector<f64> =
+        Selector::new("druid-builtin.split-point-changed");
+
+    /// A notification sent by a [`Split`] when a pane is collapsed or expanded.
+    ///
+    /// [`Split`]: crate::widget::Split
+    pub const SPLIT_PANE_COLLAPSED: Selector<Option<SplitPane>> =
+        Selector::new("druid-builtin.split-pane-collapsed");
+
+    /// Replaces the [auxiliary controls] of a window.
+    ///
+    /// This command must target a window.
+    ///
+    /// [auxiliary controls]: crate::AuxControls
+    pub const SET_AUX_CONTROLS: Selector<AuxControls> =
+        Selector::new("druid-builtin.set-aux-controls");
+
+    /// Replaces the layout of the panels of a [`Dock`].
+    ///
+    /// [`Dock`]: crate::widget::Dock
+    pub const SET_DOCK_LAYOUT: Selector<DockLayout> =
+        Selector::new("druid-builtin.dock-set-layout");
+
+    /// A notification sent by a [`Dock`] when the user changes the layout of its panels.
+    ///
+    /// The payload can be persisted, and restored with [`SET_DOCK_LAYOUT`] or
+    /// [`Dock::with_layout`].
+    ///
+    /// [`Dock`]: crate::widget::Dock
+    /// [`Dock::with_layout`]: crate::widget::Dock::with_layout
+    pub const DOCK_LAYOUT_CHANGED: Selector<DockLayout> =
+        Selector::new("druid-builtin.dock-layout-changed");
+
+    /// Simulates a color vision deficiency in all windows, or turns the simulation off.
+    ///
+    /// This is a debugging aid for checking that a UI remains usable for color-blind users.
+    /// Every color in the [`Env`] is replaced with how it appears to someone with the given
+    /// [`ColorBlindness`]; colors that are not taken from the `Env`, such as images and
+    /// hard-coded colors, are not affected.
+    ///
+    /// [`Env`]: crate::Env
+    pub const SIMULATE_COLOR_BLINDNESS: Selector<Option<ColorBlindness>> =
+        Selector::new("druid-builtin.simulate-color-blindness");
+
     /// A change that has occurred to text state, and needs to be
     /// communicated to the platform.
     pub(crate) struct ImeInvalidation {
@@ -26,13 +26,14 @@ use tracing::{error, trace, warn};
 use crate::commands::SCROLL_TO_VIEW;
 use crate::core::{CommandQueue, CursorChange, FocusChange, WidgetState};
 use crate::env::KeyLike;
+use crate::kurbo::Shape;
 use crate::menu::ContextMenu;
 use crate::piet::{Piet, PietText, RenderContext};
 use crate::shell::text::Event as ImeInvalidation;
 use crate::shell::Region;
 use crate::text::{ImeHandlerRef, TextFieldRegistration};
 use crate::{
-    commands, sub_window::SubWindowDesc, widget::Widget, Affine, Command, Cursor, Data, Env,
+    commands, sub_window::SubWindowDesc, theme, widget::Widget, Affine, Command, Cursor, Data, Env,
     ExtEventSink, Insets, Menu, Notification, Point, Rect, Scale, SingleUse, Size, Target,
     TimerToken, Vec2, WidgetId, WindowConfig, WindowDesc, WindowHandle, WindowId,
 };
@@ -1064,6 +1065,22 @@ impl<'a, 'b> LayoutCtx<'a, 'b> {
         self.widget_state.paint_insets = insets.nonnegative();
     }
 
+    /// Set explicit hit [`Insets`] for this widget.
+    ///
+    /// Mouse events are only delivered to a widget if they are inside its
+    /// layout bounds. If you position a child outside of your bounds and want
+    /// it to stay interactive, the argument should be an [`Insets`] struct that
+    /// indicates how far beyond your bounds mouse events should be accepted.
+    ///
+    /// For more information, see [`WidgetPod::hit_insets`].
+    ///
+    /// [`WidgetPod::hit_insets`]: crate::WidgetPod::hit_insets
+    pub fn set_hit_insets(&mut self, insets: impl Into<Insets>) {
+        let insets = insets.into();
+        trace!("set_hit_insets {:?}", insets);
+        self.widget_state.hit_insets = insets.nonnegative();
+    }
+
     /// Set an explicit baseline position for this widget.
     ///
     /// The baseline position is used to align widgets that contain text,
@@ -1167,6 +1184,39 @@ impl PaintCtx<'_, '_, '_> {
             transform: current_transform,
         })
     }
+
+    /// Paint the selection decoration for `shape`.
+    ///
+    /// The shape is filled with [`theme::SELECTION_FILL_COLOR`] and outlined
+    /// with [`theme::SELECTION_STROKE_COLOR`], so custom widgets can draw
+    /// selected items that match the active theme.
+    ///
+    /// [`theme::SELECTION_FILL_COLOR`]: crate::theme::SELECTION_FILL_COLOR
+    /// [`theme::SELECTION_STROKE_COLOR`]: crate::theme::SELECTION_STROKE_COLOR
+    pub fn paint_selection_decoration(&mut self, shape: impl Shape, env: &Env) {
+        self.render_ctx
+            .fill(&shape, &env.get(theme::SELECTION_FILL_COLOR));
+        self.render_ctx.stroke(
+            &shape,
+            &env.get(theme::SELECTION_STROKE_COLOR),
+            env.get(theme::SELECTION_OUTLINE_WIDTH),
+        );
+    }
+
+    /// Paint the keyboard focus decoration for `shape`.
+    ///
+    /// The shape is outlined with [`theme::FOCUS_COLOR`] at
+    /// [`theme::FOCUS_OUTLINE_WIDTH`].
+    ///
+    /// [`theme::FOCUS_COLOR`]: crate::theme::FOCUS_COLOR
+    /// [`theme::FOCUS_OUTLINE_WIDTH`]: crate::theme::FOCUS_OUTLINE_WIDTH
+    pub fn paint_focus_decoration(&mut self, shape: impl Shape, env: &Env) {
+        self.render_ctx.stroke(
+            &shape,
+            &env.get(theme::FOCUS_COLOR),
+            env.get(theme::FOCUS_OUTLINE_WIDTH),
+        );
+    }
 }
 
 impl<'a> ContextState<'a> {
@@ -22,6 +22,7 @@ use crate::command::sys::{CLOSE_WINDOW, SUB_WINDOW_HOST_TO_PARENT, SUB_WINDOW_PA
 use crate::commands::SCROLL_TO_VIEW;
 use crate::contexts::{ChangeCtx, ContextState};
 use crate::kurbo::{Affine, Insets, Point, Rect, Shape, Size};
+use crate::profiling::{self, Pass};
 use crate::sub_window::SubWindowUpdate;
 use crate::{
     ArcStr, BoxConstraints, Color, Command, Cursor, Data, Env, Event, EventCtx, InternalEvent,
@@ -90,6 +91,10 @@ pub struct WidgetState {
     /// In general, these will be zero; the exception is for things like
     /// drop shadows or overflowing text.
     pub(crate) paint_insets: Insets,
+    /// The insets applied to the layout rect to generate the hit rect, the
+    /// area in which the widget receives mouse events. These are zero unless
+    /// the widget positions a child outside of its bounds.
+    pub(crate) hit_insets: Insets,
 
     /// The offset of the baseline relative to the bottom of the widget.
     ///
@@ -363,12 +368,49 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
         union_pant_rect - parent_bounds
     }
 
+    /// Get the widget's hit [`Rect`].
+    ///
+    /// This is the area in which the widget receives mouse events; it is the
+    /// [`layout_rect`] with the [`hit_insets`] applied.
+    ///
+    /// [`layout_rect`]: #method.layout_rect
+    /// [`hit_insets`]: #method.hit_insets
+    pub fn hit_rect(&self) -> Rect {
+        self.state.hit_rect()
+    }
+
+    /// Return the hit [`Insets`] for this widget.
+    ///
+    /// These are generally zero; the exception is widgets that position a child
+    /// partially or fully outside of their own bounds, and want that child to
+    /// still receive mouse events.
+    ///
+    /// A widget can set its insets by calling [`set_hit_insets`] during its
+    /// [`layout`] method.
+    ///
+    /// [`set_hit_insets`]: LayoutCtx::set_hit_insets
+    /// [`layout`]: Widget::layout
+    pub fn hit_insets(&self) -> Insets {
+        self.state.hit_insets
+    }
+
+    /// Given a parents layout size, determine the appropriate hit [`Insets`]
+    /// for the parent.
+    ///
+    /// This is the hit-testing equivalent of [`compute_parent_paint_insets`].
+    ///
+    /// [`compute_parent_paint_insets`]: #method.compute_parent_paint_insets
+    pub fn compute_parent_hit_insets(&self, parent_size: Size) -> Insets {
+        let parent_bounds = Rect::ZERO.with_size(parent_size);
+        self.hit_rect().union(parent_bounds) - parent_bounds
+    }
+
     /// The distance from the bottom of this widget to the baseline.
     pub fn baseline_offset(&self) -> f64 {
         self.state.baseline_offset
     }
 
-    /// Determines if the provided `mouse_pos` is inside the widget's `layout_rect`
+    /// Determines if the provided `mouse_pos` is inside the widget's `hit_rect`
     /// and if so updates the hot state and sends [`LifeCycle::HotChanged`].
     ///
     /// Returns `true` if the hot state changed.
@@ -381,7 +423,7 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
         data: &T,
         env: &Env,
     ) -> bool {
-        let rect = self.layout_rect();
+        let rect = self.hit_rect();
         let had_hot = self.state.is_hot;
         self.state.is_hot = match mouse_pos {
             Some(pos) => rect.winding(pos) != 0,
@@ -438,7 +480,9 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             widget_state: &mut self.state,
             depth: ctx.depth,
         };
+        let timer = profiling::time_widget(Pass::Paint, self.inner.short_type_name());
         self.inner.paint(&mut inner_ctx, data, env);
+        drop(timer);
 
         ctx.z_ops.append(&mut inner_ctx.z_ops);
 
@@ -567,7 +611,9 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             state: ctx.state,
         };
 
+        let timer = profiling::time_widget(Pass::Layout, self.inner.short_type_name());
         let new_size = self.inner.layout(&mut child_ctx, bc, data, env);
+        drop(timer);
         if new_size != prev_size {
             let mut child_ctx = LifeCycleCtx {
                 widget_state: child_ctx.widget_state,
@@ -829,7 +875,9 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     ctx.is_handled = true;
                 }
                 _ => {
+                    let timer = profiling::time_widget(Pass::Event, self.inner.short_type_name());
                     self.inner.event(&mut inner_ctx, inner_event, data, env);
+                    drop(timer);
 
                     inner_ctx.widget_state.has_active |= inner_ctx.widget_state.is_active;
                     ctx.is_handled |= inner_ctx.is_handled;
@@ -875,7 +923,9 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             // skip notifications that were submitted by our child
             if notification.source() != self_id {
                 let event = Event::Notification(notification);
+                let timer = profiling::time_widget(Pass::Event, self.inner.short_type_name());
                 self.inner.event(&mut inner_ctx, &event, data, env);
+                drop(timer);
                 if inner_ctx.is_handled {
                     inner_ctx.is_handled = false;
                 } else if let Event::Notification(notification) = event {
@@ -1083,6 +1133,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             widget_state: &mut self.state,
         };
 
+        let timer = profiling::time_widget(Pass::Lifecycle, self.inner.short_type_name());
         if recurse {
             self.inner.lifecycle(&mut child_ctx, event, data, env);
         }
@@ -1090,6 +1141,7 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
         if let Some(event) = extra_event.as_ref() {
             self.inner.lifecycle(&mut child_ctx, event, data, env);
         }
+        drop(timer);
 
         // Sync our state with our parent's state after the event!
 
@@ -1194,8 +1246,10 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             env,
         };
 
+        let timer = profiling::time_widget(Pass::Update, self.inner.short_type_name());
         self.inner
             .update(&mut child_ctx, self.old_data.as_ref().unwrap(), data, env);
+        drop(timer);
         self.old_data = Some(data.clone());
         self.env = Some(env.clone());
 
@@ -1235,6 +1289,7 @@ impl WidgetState {
             size: size.unwrap_or_default(),
             is_expecting_set_origin_call: true,
             paint_insets: Insets::ZERO,
+            hit_insets: Insets::ZERO,
             invalid: Region::EMPTY,
             children_disabled_changed: false,
             ancestor_disabled: false,
@@ -1344,6 +1399,13 @@ impl WidgetState {
         self.layout_rect() + self.paint_insets
     }
 
+    /// The region in which this widget receives mouse events.
+    ///
+    /// For more information, see [`WidgetPod::hit_rect`].
+    pub(crate) fn hit_rect(&self) -> Rect {
+        self.layout_rect() + self.hit_insets
+    }
+
     /// The rectangle used when calculating layout with other widgets
     pub fn layout_rect(&self) -> Rect {
         Rect::from_origin_size(self.origin, self.size)
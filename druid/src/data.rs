@@ -70,8 +70,10 @@ use piet::ImageBuf;
 ///
 /// ## Collection types
 ///
-/// `Data` is not implemented for `std` collection types, because comparing them
-/// can be expensive. To use collection types with Druid, there are two easy options:
+/// `Data` is implemented for `VecDeque`, `BTreeMap`, `BTreeSet` and `HashSet`
+/// from `std`, but these have to be compared element by element, which can
+/// be expensive for large collections; `Vec` and `HashMap` do not implement
+/// `Data` at all. For large collections there are two easy options:
 /// either wrap the collection in an `Arc`, or build `druid` with the `im` feature,
 /// which adds `Data` implementations to the collections from the [`im` crate],
 /// a set of immutable data structures that fit nicely with Druid.
@@ -305,6 +307,68 @@ impl<T0: Data, T1: Data, T2: Data, T3: Data, T4: Data, T5: Data> Data for (T0, T
     }
 }
 
+macro_rules! impl_data_for_tuple {
+    ($($T:ident, $i:tt),*) => {
+        impl<$($T: Data,)*> Data for ($($T,)*) {
+            fn same(&self, other: &Self) -> bool {
+                $(self.$i.same(&other.$i))&&*
+            }
+        }
+    };
+}
+
+impl_data_for_tuple!(T0, 0, T1, 1, T2, 2, T3, 3, T4, 4, T5, 5, T6, 6);
+impl_data_for_tuple!(T0, 0, T1, 1, T2, 2, T3, 3, T4, 4, T5, 5, T6, 6, T7, 7);
+impl_data_for_tuple!(T0, 0, T1, 1, T2, 2, T3, 3, T4, 4, T5, 5, T6, 6, T7, 7, T8, 8);
+impl_data_for_tuple!(T0, 0, T1, 1, T2, 2, T3, 3, T4, 4, T5, 5, T6, 6, T7, 7, T8, 8, T9, 9);
+impl_data_for_tuple!(T0, 0, T1, 1, T2, 2, T3, 3, T4, 4, T5, 5, T6, 6, T7, 7, T8, 8, T9, 9, T10, 10);
+impl_data_for_tuple!(
+    T0, 0, T1, 1, T2, 2, T3, 3, T4, 4, T5, 5, T6, 6, T7, 7, T8, 8, T9, 9, T10, 10, T11, 11
+);
+
+/// Compares the strings, not their addresses.
+impl Data for std::borrow::Cow<'static, str> {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// Compares the elements in order with [`Data::same`].
+impl<T: Data> Data for std::collections::VecDeque<T> {
+    fn same(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a.same(b))
+    }
+}
+
+/// Compares the keys with `==` and the values with [`Data::same`].
+impl<K: Ord + Clone + 'static, V: Data> Data for std::collections::BTreeMap<K, V> {
+    fn same(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|((k1, v1), (k2, v2))| k1 == k2 && v1.same(v2))
+    }
+}
+
+/// Compares the sets with `==`.
+impl<T: Ord + Clone + 'static> Data for std::collections::BTreeSet<T> {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// Compares the sets with `==`.
+impl<T, S> Data for std::collections::HashSet<T, S>
+where
+    T: Eq + std::hash::Hash + Clone + 'static,
+    S: std::hash::BuildHasher + Clone + 'static,
+{
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
 impl<T: 'static + ?Sized> Data for std::marker::PhantomData<T> {
     fn same(&self, _other: &Self) -> bool {
         // zero-sized types
@@ -633,7 +697,7 @@ mod test {
     #[cfg(feature = "im")]
     fn im_data() {
         for len in 8..256 {
-            let input = std::iter::repeat(0_u8).take(len).collect::<im::Vector<_>>();
+            let input = std::iter::repeat_n(0_u8, len).collect::<im::Vector<_>>();
             let mut inp2 = input.clone();
             assert!(input.same(&inp2));
             inp2.set(len - 1, 98);
@@ -644,11 +708,28 @@ mod test {
     #[test]
     #[cfg(feature = "im")]
     fn im_vec_different_length() {
-        let one = std::iter::repeat(0_u8).take(9).collect::<im::Vector<_>>();
-        let two = std::iter::repeat(0_u8).take(10).collect::<im::Vector<_>>();
+        let one = std::iter::repeat_n(0_u8, 9).collect::<im::Vector<_>>();
+        let two = std::iter::repeat_n(0_u8, 10).collect::<im::Vector<_>>();
         assert!(!one.same(&two));
     }
 
+    #[test]
+    fn std_collections() {
+        use std::collections::{BTreeMap, VecDeque};
+
+        let deque: VecDeque<_> = [1.0, 2.0].into_iter().collect();
+        let mut other = deque.clone();
+        assert!(deque.same(&other));
+        other.push_back(3.0);
+        assert!(!deque.same(&other));
+
+        let map: BTreeMap<_, _> = [(1, "a"), (2, "b")].into_iter().collect();
+        let mut other = map.clone();
+        assert!(map.same(&other));
+        other.insert(2, "c");
+        assert!(!map.same(&other));
+    }
+
     #[test]
     fn static_strings() {
         let first = "test";
@@ -0,0 +1,170 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Finding which parts of some data changed.
+
+use crate::{Data, Lens};
+
+type Check<T> = Box<dyn Fn(&T, &T, &str, &mut Vec<String>)>;
+
+/// Reports which named parts of some [`Data`] changed between two values.
+///
+/// This is a debugging aid for finding out why a widget is, or is not,
+/// updated. Each path is a [`Lens`] with a name, and is reported as changed
+/// when [`Data::same`] is `false` for the values it selects; this is the
+/// same comparison that decides whether widgets are updated.
+///
+/// Use it directly with [`changed`], or log the changes that reach a widget
+/// with [`WidgetExt::log_data_changes`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::{Data, DataDiff, Lens};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Shape {
+///     name: String,
+///     origin: Point,
+/// }
+///
+/// let diff = DataDiff::new().with_path("name", Shape::name).with_nested(
+///     "origin",
+///     Shape::origin,
+///     DataDiff::new()
+///         .with_path("x", Point::x)
+///         .with_path("y", Point::y),
+/// );
+///
+/// let old = Shape {
+///     name: "square".into(),
+///     origin: Point { x: 0.0, y: 0.0 },
+/// };
+/// let mut new = old.clone();
+/// new.origin.y = 2.0;
+/// assert_eq!(diff.changed(&old, &new), vec!["origin.y"]);
+/// ```
+///
+/// [`changed`]: DataDiff::changed
+/// [`WidgetExt::log_data_changes`]: crate::WidgetExt::log_data_changes
+pub struct DataDiff<T> {
+    paths: Vec<Check<T>>,
+}
+
+impl<T: Data> DataDiff<T> {
+    /// Create a `DataDiff` without any paths.
+    pub fn new() -> Self {
+        DataDiff { paths: Vec::new() }
+    }
+
+    /// Builder-style method to add a path called `name`, for the part of the
+    /// data selected by `lens`.
+    pub fn with_path<U: Data>(
+        mut self,
+        name: impl Into<String>,
+        lens: impl Lens<T, U> + 'static,
+    ) -> Self {
+        let name = name.into();
+        self.paths.push(Box::new(move |old, new, prefix, changed| {
+            let same = lens.with(old, |old| lens.with(new, |new| old.same(new)));
+            if !same {
+                changed.push(format!("{prefix}{name}"));
+            }
+        }));
+        self
+    }
+
+    /// Builder-style method to add the paths of `diff`, for the part of the
+    /// data selected by `lens`.
+    ///
+    /// The nested paths are reported as `name.path`. If the part changed but
+    /// none of the nested paths did, `name` itself is reported.
+    pub fn with_nested<U: Data>(
+        mut self,
+        name: impl Into<String>,
+        lens: impl Lens<T, U> + 'static,
+        diff: DataDiff<U>,
+    ) -> Self {
+        let name = name.into();
+        self.paths.push(Box::new(move |old, new, prefix, changed| {
+            lens.with(old, |old| {
+                lens.with(new, |new| {
+                    if !old.same(new) {
+                        let prefix = format!("{prefix}{name}");
+                        let count = changed.len();
+                        diff.collect(old, new, &format!("{prefix}."), changed);
+                        if changed.len() == count {
+                            changed.push(prefix);
+                        }
+                    }
+                })
+            })
+        }));
+        self
+    }
+
+    /// The names of the paths that changed between `old` and `new`, in the
+    /// order they were added.
+    ///
+    /// This is empty if `old` and `new` are the [`same`], but also if they
+    /// only differ in parts that are not covered by any path.
+    ///
+    /// [`same`]: Data::same
+    pub fn changed(&self, old: &T, new: &T) -> Vec<String> {
+        let mut changed = Vec::new();
+        self.collect(old, new, "", &mut changed);
+        changed
+    }
+
+    fn collect(&self, old: &T, new: &T, prefix: &str, changed: &mut Vec<String>) {
+        for check in &self.paths {
+            check(old, new, prefix, changed);
+        }
+    }
+}
+
+impl<T: Data> Default for DataDiff<T> {
+    fn default() -> Self {
+        DataDiff::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_paths() {
+        let diff = DataDiff::new()
+            .with_path("a", lens!((u32, (u32, u32)), 0))
+            .with_nested(
+                "b",
+                lens!((u32, (u32, u32)), 1),
+                DataDiff::new().with_path("first", lens!((u32, u32), 0)),
+            );
+        assert!(diff.changed(&(1, (2, 3)), &(1, (2, 3))).is_empty());
+        assert_eq!(
+            diff.changed(&(1, (2, 3)), &(0, (4, 3))),
+            vec!["a", "b.first"]
+        );
+        // A change that no nested path covers is reported for the parent.
+        assert_eq!(diff.changed(&(1, (2, 3)), &(1, (2, 5))), vec!["b"]);
+    }
+}
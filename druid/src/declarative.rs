@@ -0,0 +1,525 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Building widget trees from a description loaded at runtime.
+//!
+//! A UI description is a tree of JSON objects. Each object names the widget
+//! to create in its `widget` field, may have a list of `children`, and may
+//! have any other properties the widget understands:
+//!
+//! ```json
+//! {
+//!     "widget": "column",
+//!     "spacing": 8.0,
+//!     "children": [
+//!         { "widget": "label", "text": "Your name:" },
+//!         { "widget": "textbox", "bind": "name", "placeholder": "Name" },
+//!         { "widget": "button", "text": "Greet", "command": "greet" }
+//!     ]
+//! }
+//! ```
+//!
+//! Widgets are created by the builders in a [`UiRegistry`]. The `bind`
+//! property refers to a lens that was registered with
+//! [`UiRegistry::add_binding`], and `command` to a command registered with
+//! [`UiRegistry::add_command`]. Applications can register builders for their
+//! own widgets with [`UiRegistry::add_widget`].
+//!
+//! The [`Declarative`] widget shows a description and rebuilds its contents
+//! when it receives a new one with the [`Declarative::RELOAD`] command, for
+//! instance after the file it was loaded from changed.
+//!
+//! The builtin widgets are:
+//!
+//! | widget      | properties                            | children | binding  |
+//! |-------------|---------------------------------------|----------|----------|
+//! | `column`    | `spacing`                             | any      |          |
+//! | `row`       | `spacing`                             | any      |          |
+//! | `label`     | `text`, or a binding                  |          | `String` |
+//! | `button`    | `text`, `command`                     |          |          |
+//! | `textbox`   | `placeholder`                         |          | `String` |
+//! | `checkbox`  | `text`                                |          | `bool`   |
+//! | `slider`    | `min`, `max`                          |          | `f64`    |
+//! | `padding`   | `padding`                             | one      |          |
+//! | `sized_box` | `width`, `height`                     | optional |          |
+//! | `scroll`    |                                       | one      |          |
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde_json::{Map, Value};
+use tracing::{instrument, warn};
+
+use crate::widget::prelude::*;
+use crate::widget::{
+    Button, Checkbox, Flex, Label, LensWrap, Padding, Scroll, SizedBox, Slider, TextBox,
+};
+use crate::{Command, Lens, Point, Selector, WidgetPod};
+
+type WidgetBuilder<T> = Box<dyn Fn(&UiRegistry<T>, &Node) -> Result<Box<dyn Widget<T>>, Error>>;
+type Binding<T, U> = Rc<dyn Fn(Box<dyn Widget<U>>) -> Box<dyn Widget<T>>>;
+
+/// An error that occurs while building a widget tree from a description.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The description is not valid JSON.
+    Parse(serde_json::Error),
+    /// A node is not an object with a `widget` field, or has invalid children.
+    InvalidNode(String),
+    /// No builder is registered for this widget name.
+    UnknownWidget(String),
+    /// No binding is registered with this name.
+    UnknownBinding(String),
+    /// A binding exists but has a different type than the widget needs.
+    BindingType {
+        /// The name of the binding.
+        binding: String,
+        /// The type of data the widget needs.
+        expected: &'static str,
+    },
+    /// A property that the widget needs is missing or has the wrong type.
+    MissingProperty {
+        /// The name of the widget.
+        widget: String,
+        /// The name of the property.
+        property: &'static str,
+    },
+    /// No command is registered with this name.
+    UnknownCommand(String),
+}
+
+/// A node in a UI description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    /// The name of the widget builder that creates this node.
+    pub widget: String,
+    /// The properties of this node, other than `widget` and `children`.
+    pub properties: Map<String, Value>,
+    /// The children of this node.
+    pub children: Vec<Node>,
+}
+
+/// Widget builders, data bindings and commands that UI descriptions can refer to by name.
+pub struct UiRegistry<T> {
+    widgets: HashMap<String, WidgetBuilder<T>>,
+    bindings: HashMap<String, Box<dyn Any>>,
+    commands: HashMap<String, Command>,
+}
+
+/// A widget that displays a UI description, and can replace it at runtime.
+pub struct Declarative<T> {
+    registry: Rc<UiRegistry<T>>,
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+}
+
+impl Node {
+    /// Parse a node and its children from JSON.
+    pub fn parse(json: &str) -> Result<Node, Error> {
+        let value = serde_json::from_str(json).map_err(Error::Parse)?;
+        Node::from_value(value)
+    }
+
+    /// Create a node and its children from a JSON value.
+    pub fn from_value(value: Value) -> Result<Node, Error> {
+        let mut properties = match value {
+            Value::Object(map) => map,
+            other => {
+                return Err(Error::InvalidNode(format!(
+                    "expected an object, found {other}"
+                )))
+            }
+        };
+        let widget = match properties.remove("widget") {
+            Some(Value::String(widget)) => widget,
+            _ => return Err(Error::InvalidNode("missing 'widget' field".into())),
+        };
+        let children = match properties.remove("children") {
+            Some(Value::Array(children)) => children
+                .into_iter()
+                .map(Node::from_value)
+                .collect::<Result<_, _>>()?,
+            Some(_) => {
+                return Err(Error::InvalidNode(format!(
+                    "the children of '{widget}' are not a list"
+                )))
+            }
+            None => Vec::new(),
+        };
+        Ok(Node {
+            widget,
+            properties,
+            children,
+        })
+    }
+
+    /// The string property `name`.
+    pub fn str(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).and_then(Value::as_str)
+    }
+
+    /// The number property `name`.
+    pub fn f64(&self, name: &str) -> Option<f64> {
+        self.properties.get(name).and_then(Value::as_f64)
+    }
+
+    /// The boolean property `name`.
+    pub fn bool(&self, name: &str) -> Option<bool> {
+        self.properties.get(name).and_then(Value::as_bool)
+    }
+
+    /// The string property `name`, or an error if it is missing.
+    pub fn required_str(&self, name: &'static str) -> Result<&str, Error> {
+        self.str(name).ok_or_else(|| self.missing(name))
+    }
+
+    fn missing(&self, property: &'static str) -> Error {
+        Error::MissingProperty {
+            widget: self.widget.clone(),
+            property,
+        }
+    }
+}
+
+impl<T: Data> UiRegistry<T> {
+    /// Create a registry with the builtin widgets.
+    ///
+    /// See the [module level documentation](crate::declarative) for the list
+    /// of builtin widgets.
+    pub fn new() -> Self {
+        let mut registry = UiRegistry {
+            widgets: HashMap::new(),
+            bindings: HashMap::new(),
+            commands: HashMap::new(),
+        };
+        registry.add_builtin_widgets();
+        registry
+    }
+
+    /// Register a function that creates a widget from a node named `name`.
+    ///
+    /// This replaces any builder that was registered with the same name.
+    pub fn add_widget(
+        &mut self,
+        name: impl Into<String>,
+        builder: impl Fn(&UiRegistry<T>, &Node) -> Result<Box<dyn Widget<T>>, Error> + 'static,
+    ) {
+        self.widgets.insert(name.into(), Box::new(builder));
+    }
+
+    /// Register a lens that nodes can bind to with `"bind": "<name>"`.
+    pub fn add_binding<U: Data>(
+        &mut self,
+        name: impl Into<String>,
+        lens: impl Lens<T, U> + Clone + 'static,
+    ) {
+        let binding: Binding<T, U> =
+            Rc::new(move |widget| Box::new(LensWrap::new(widget, lens.clone())));
+        self.bindings.insert(name.into(), Box::new(binding));
+    }
+
+    /// Register a command that nodes can refer to by `name`.
+    pub fn add_command(&mut self, name: impl Into<String>, command: impl Into<Command>) {
+        self.commands.insert(name.into(), command.into());
+    }
+
+    /// Build the widget tree described by a JSON string.
+    pub fn build_from_str(&self, json: &str) -> Result<Box<dyn Widget<T>>, Error> {
+        self.build(&Node::parse(json)?)
+    }
+
+    /// Build the widget tree described by `node`.
+    pub fn build(&self, node: &Node) -> Result<Box<dyn Widget<T>>, Error> {
+        let builder = self
+            .widgets
+            .get(&node.widget)
+            .ok_or_else(|| Error::UnknownWidget(node.widget.clone()))?;
+        builder(self, node)
+    }
+
+    /// Build all children of `node`.
+    pub fn build_children(&self, node: &Node) -> Result<Vec<Box<dyn Widget<T>>>, Error> {
+        node.children
+            .iter()
+            .map(|child| self.build(child))
+            .collect()
+    }
+
+    /// Build the only child of `node`, or return an error if it does not have
+    /// exactly one child.
+    pub fn build_child(&self, node: &Node) -> Result<Box<dyn Widget<T>>, Error> {
+        match node.children.as_slice() {
+            [child] => self.build(child),
+            _ => Err(Error::InvalidNode(format!(
+                "'{}' needs exactly one child",
+                node.widget
+            ))),
+        }
+    }
+
+    /// Wrap `widget` in the binding named by the `bind` property of `node`.
+    pub fn bind<U: Data>(
+        &self,
+        node: &Node,
+        widget: impl Widget<U> + 'static,
+    ) -> Result<Box<dyn Widget<T>>, Error> {
+        let name = node.required_str("bind")?;
+        let binding = self
+            .bindings
+            .get(name)
+            .ok_or_else(|| Error::UnknownBinding(name.to_owned()))?;
+        let binding =
+            binding
+                .downcast_ref::<Binding<T, U>>()
+                .ok_or_else(|| Error::BindingType {
+                    binding: name.to_owned(),
+                    expected: std::any::type_name::<U>(),
+                })?;
+        Ok(binding(Box::new(widget)))
+    }
+
+    /// The command registered as `name`.
+    pub fn command(&self, name: &str) -> Result<Command, Error> {
+        self.commands
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownCommand(name.to_owned()))
+    }
+
+    fn add_builtin_widgets(&mut self) {
+        self.add_widget("column", |registry, node| {
+            build_flex(registry, node, Flex::column())
+        });
+        self.add_widget("row", |registry, node| {
+            build_flex(registry, node, Flex::row())
+        });
+        self.add_widget("label", |registry, node| match node.str("text") {
+            Some(text) => Ok(Box::new(Label::new(text.to_owned()))),
+            None if node.str("bind").is_some() => {
+                registry.bind(node, Label::dynamic(|text: &String, _| text.clone()))
+            }
+            None => Err(node.missing("text")),
+        });
+        self.add_widget("button", |registry, node| {
+            let button = Button::new(node.required_str("text")?.to_owned());
+            match node.str("command") {
+                Some(name) => {
+                    let command = registry.command(name)?;
+                    Ok(Box::new(button.on_click(move |ctx, _, _| {
+                        ctx.submit_command(command.clone())
+                    })))
+                }
+                None => Ok(Box::new(button)),
+            }
+        });
+        self.add_widget("textbox", |registry, node| {
+            let mut textbox = TextBox::<String>::new();
+            if let Some(placeholder) = node.str("placeholder") {
+                textbox = textbox.with_placeholder(placeholder.to_owned());
+            }
+            registry.bind(node, textbox)
+        });
+        self.add_widget("checkbox", |registry, node| {
+            let text = node.str("text").unwrap_or_default().to_owned();
+            registry.bind(node, Checkbox::new(text))
+        });
+        self.add_widget("slider", |registry, node| {
+            let min = node.f64("min").unwrap_or(0.0);
+            let max = node.f64("max").unwrap_or(1.0);
+            registry.bind(node, Slider::new().with_range(min, max))
+        });
+        self.add_widget("padding", |registry, node| {
+            let padding = node.f64("padding").ok_or_else(|| node.missing("padding"))?;
+            Ok(Box::new(Padding::new(padding, registry.build_child(node)?)))
+        });
+        self.add_widget("sized_box", |registry, node| {
+            let mut sized_box = match node.children.is_empty() {
+                true => SizedBox::empty(),
+                false => SizedBox::new(registry.build_child(node)?),
+            };
+            if let Some(width) = node.f64("width") {
+                sized_box = sized_box.width(width);
+            }
+            if let Some(height) = node.f64("height") {
+                sized_box = sized_box.height(height);
+            }
+            Ok(Box::new(sized_box))
+        });
+        self.add_widget("scroll", |registry, node| {
+            Ok(Box::new(
+                Scroll::new(registry.build_child(node)?).vertical(),
+            ))
+        });
+    }
+}
+
+fn build_flex<T: Data>(
+    registry: &UiRegistry<T>,
+    node: &Node,
+    mut flex: Flex<T>,
+) -> Result<Box<dyn Widget<T>>, Error> {
+    let spacing = node.f64("spacing");
+    for (i, child) in registry.build_children(node)?.into_iter().enumerate() {
+        if let (Some(spacing), true) = (spacing, i > 0) {
+            flex.add_spacer(spacing);
+        }
+        flex.add_child(child);
+    }
+    Ok(Box::new(flex))
+}
+
+impl<T: Data> Default for UiRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Declarative<T> {
+    /// Replace the displayed widgets with those built from the JSON description
+    /// in the payload.
+    ///
+    /// If the new description cannot be built, the error is logged and the
+    /// current widgets are kept.
+    pub const RELOAD: Selector<String> = Selector::new("druid-builtin.declarative-reload");
+
+    /// Create a widget that displays the JSON `description`.
+    ///
+    /// If the description cannot be built, the error is displayed instead.
+    pub fn new(registry: Rc<UiRegistry<T>>, description: &str) -> Self {
+        let child = registry
+            .build_from_str(description)
+            .unwrap_or_else(|err| Box::new(Label::new(err.to_string())));
+        Declarative {
+            registry,
+            child: WidgetPod::new(child),
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Declarative<T> {
+    #[instrument(
+        name = "Declarative",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(description) = cmd.get(Self::RELOAD) {
+                match self.registry.build_from_str(description) {
+                    Ok(child) => {
+                        self.child = WidgetPod::new(child);
+                        ctx.children_changed();
+                    }
+                    Err(err) => warn!("failed to reload UI description: {}", err),
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Declarative",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Declarative",
+        level = "trace",
+        skip(self, ctx, _old, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Declarative", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "Declarative", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "Invalid UI description: {err}"),
+            Error::InvalidNode(reason) => write!(f, "Invalid node: {reason}"),
+            Error::UnknownWidget(name) => write!(f, "Unknown widget: '{name}'"),
+            Error::UnknownBinding(name) => write!(f, "Unknown binding: '{name}'"),
+            Error::BindingType { binding, expected } => {
+                write!(f, "Binding '{binding}' does not have type {expected}")
+            }
+            Error::MissingProperty { widget, property } => {
+                write!(f, "'{widget}' is missing property '{property}'")
+            }
+            Error::UnknownCommand(name) => write!(f, "Unknown command: '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lens;
+
+    #[derive(Clone, Data, Lens)]
+    struct Form {
+        name: String,
+        subscribed: bool,
+    }
+
+    #[test]
+    fn build_description() {
+        let mut registry = UiRegistry::new();
+        registry.add_binding("name", Form::name);
+        registry.add_binding("subscribed", Form::subscribed);
+
+        let description = r#"{
+            "widget": "column",
+            "children": [
+                { "widget": "textbox", "bind": "name" },
+                { "widget": "checkbox", "text": "Subscribe", "bind": "subscribed" }
+            ]
+        }"#;
+        assert!(registry.build_from_str(description).is_ok());
+
+        let wrong_type = r#"{ "widget": "checkbox", "bind": "name" }"#;
+        assert!(matches!(
+            registry.build_from_str(wrong_type),
+            Err(Error::BindingType { .. })
+        ));
+        assert!(matches!(
+            registry.build_from_str(r#"{ "widget": "chart" }"#),
+            Err(Error::UnknownWidget(_))
+        ));
+        assert!(matches!(
+            registry.build_from_str(r#"{ "widget": "padding", "padding": 4.0 }"#),
+            Err(Error::InvalidNode(_))
+        ));
+    }
+}
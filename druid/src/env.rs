@@ -336,6 +336,29 @@ impl Env {
         Ok(())
     }
 
+    /// The value for the key named `key`, if there is one.
+    #[cfg(feature = "theme-file")]
+    pub(crate) fn get_raw(&self, key: &str) -> Option<&Value> {
+        self.0.map.get(key)
+    }
+
+    /// Set the value for the key named `key`, replacing a value of any type.
+    #[cfg(feature = "theme-file")]
+    pub(crate) fn set_raw(&mut self, key: &str, raw: Value) {
+        Arc::make_mut(&mut self.0).map.insert(key.into(), raw);
+    }
+
+    /// Returns a copy of this `Env` with every [`Color`] value passed through `f`.
+    pub(crate) fn map_colors(&self, f: impl Fn(&Color) -> Color) -> Env {
+        let mut env = self.clone();
+        for value in Arc::make_mut(&mut env.0).map.values_mut() {
+            if let Value::Color(color) = value {
+                *color = f(color);
+            }
+        }
+        env
+    }
+
     /// Returns a reference to the [`L10nManager`], which handles localization
     /// resources.
     ///
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::ops;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::Data;
@@ -209,6 +211,66 @@ pub trait LensExt<A: ?Sized, B: ?Sized>: Lens<A, B> {
         InArc::new(self)
     }
 
+    /// Adapt to operate on the contents of an `Rc` with efficient copy-on-write
+    /// semantics, like [`in_arc`].
+    ///
+    /// [`in_arc`]: LensExt::in_arc
+    fn in_rc(self) -> InRc<Self>
+    where
+        A: Clone,
+        B: Data,
+        Self: Sized,
+    {
+        InRc::new(self)
+    }
+
+    /// Access the contents of an `Arc` or `Rc`, copying them on write.
+    ///
+    /// The contents are only copied when a different value is written, so a
+    /// widget bound through this lens can edit data that is shared.
+    ///
+    /// ```
+    /// # use druid::*; use std::sync::Arc;
+    /// let lens = lens!((Arc<(u32, bool)>, u32), 0).make_mut();
+    /// let mut x = (Arc::new((1, true)), 0);
+    /// let original = x.0.clone();
+    /// lens.with_mut(&mut x, |inner| inner.0 = 1);
+    /// assert!(Arc::ptr_eq(&original, &x.0), "no-op writes don't cause a deep copy");
+    /// lens.with_mut(&mut x, |inner| inner.0 = 2);
+    /// assert_eq!(*x.0, (2, true));
+    /// assert_eq!(*original, (1, true));
+    /// ```
+    fn make_mut<C>(self) -> Then<Self, MakeMut, B>
+    where
+        MakeMut: Lens<B, C>,
+        Self: Sized,
+    {
+        self.then(MakeMut)
+    }
+
+    /// Access an `Arc<str>` or `Rc<str>` as a `String`, for use with widgets
+    /// like [`TextBox`].
+    ///
+    /// A new string is only allocated when a different value is written.
+    ///
+    /// ```
+    /// # use druid::*; use std::sync::Arc;
+    /// let lens = lens::Identity.as_string();
+    /// let mut text: Arc<str> = "hello".into();
+    /// lens.with_mut(&mut text, |s: &mut String| s.push('!'));
+    /// assert_eq!(&*text, "hello!");
+    /// ```
+    ///
+    /// [`TextBox`]: crate::widget::TextBox
+    #[allow(clippy::wrong_self_convention)]
+    fn as_string(self) -> Then<Self, AsString, B>
+    where
+        AsString: Lens<B, String>,
+        Self: Sized,
+    {
+        self.then(AsString)
+    }
+
     /// A lens that reverses a boolean value
     ///
     /// # Examples
@@ -236,6 +298,114 @@ pub trait LensExt<A: ?Sized, B: ?Sized>: Lens<A, B> {
     {
         self.then(Not)
     }
+
+    /// Access an entry of a container that may not exist, such as an index
+    /// past the end of a `Vec`.
+    ///
+    /// Unlike [`index`], this never panics: the lens yields `None` for a
+    /// missing entry. Writing `None`, or writing to a missing entry, does
+    /// nothing.
+    ///
+    /// ```
+    /// # use druid::*;
+    /// let lens = lens::Identity.try_index(1);
+    /// let mut data = vec![1, 2];
+    /// assert_eq!(lens.get(&data), Some(2));
+    /// lens.put(&mut data, Some(5));
+    /// assert_eq!(data, [1, 5]);
+    /// data.clear();
+    /// assert_eq!(lens.get(&data), None);
+    /// ```
+    ///
+    /// [`index`]: LensExt::index
+    fn try_index(self, index: usize) -> Then<Self, Entry<usize>, B>
+    where
+        B: Keyed<usize>,
+        Self: Sized,
+    {
+        self.then(Entry::new(index))
+    }
+
+    /// Access the entry for `key` in a map, which may not exist.
+    ///
+    /// The lens yields `None` if there is no entry for `key`. Writing `None`,
+    /// or writing to a missing entry, does nothing.
+    ///
+    /// ```
+    /// # use druid::*;
+    /// # use std::collections::HashMap;
+    /// let lens = lens::Identity.key("a");
+    /// let mut data = HashMap::new();
+    /// assert_eq!(lens.get(&data), None);
+    /// data.insert("a", 1);
+    /// assert_eq!(lens.get(&data), Some(1));
+    /// ```
+    fn key<K>(self, key: K) -> Then<Self, Entry<K>, B>
+    where
+        B: Keyed<K>,
+        Self: Sized,
+    {
+        self.then(Entry::new(key))
+    }
+
+    /// Continue a lens that yields an `Option` with a lens into the value
+    /// inside it.
+    ///
+    /// The combined lens yields `None` when the first lens does.
+    ///
+    /// ```
+    /// # use druid::*;
+    /// let lens = lens::Identity.then_some(lens!((u32, bool), 0));
+    /// assert_eq!(lens.get(&Some((3, true))), Some(3));
+    /// assert_eq!(lens.get(&None), None);
+    /// ```
+    fn then_some<Other, X, C>(self, other: Other) -> Then<Self, ThenSome<Other>, Option<X>>
+    where
+        Self: Lens<A, Option<X>> + Sized,
+        Other: Lens<X, C>,
+        C: Data,
+    {
+        Then::new(self, ThenSome::new(other))
+    }
+
+    /// Turn a lens that yields an `Option` into one that yields `default`
+    /// in place of `None`.
+    ///
+    /// See [`Defaulted`] for how writes are handled.
+    ///
+    /// ```
+    /// # use druid::*;
+    /// let lens = lens::Identity.try_index(5).defaulted(0);
+    /// assert_eq!(lens.get(&vec![1, 2]), 0);
+    /// ```
+    fn defaulted<X>(self, default: X) -> Then<Self, Defaulted<X>, Option<X>>
+    where
+        Self: Lens<A, Option<X>> + Sized,
+        X: Data,
+    {
+        Then::new(self, Defaulted::new(default))
+    }
+
+    /// Derive a value from the lensed data with `compute`, recomputing it
+    /// only when the lensed data changes.
+    ///
+    /// See [`Computed`] for details.
+    ///
+    /// ```
+    /// # use druid::*;
+    /// # use std::sync::Arc;
+    /// let lens = lens!((Arc<Vec<u32>>, bool), 0).computed(|values| values.iter().sum::<u32>());
+    /// assert_eq!(lens.get(&(Arc::new(vec![1, 2, 3]), true)), 6);
+    /// ```
+    fn computed<U, F>(self, compute: F) -> Then<Self, Computed<B, U, F>, B>
+    where
+        B: Data,
+        U: Clone,
+        F: Fn(&B) -> U,
+        Self: Sized,
+    {
+        self.then(Computed::new(compute))
+    }
 }
 
 impl<A: ?Sized, B: ?Sized, T: Lens<A, B>> LensExt<A, B> for T {}
@@ -515,6 +685,124 @@ where
     }
 }
 
+/// A `Lens` that exposes data within an `Rc` with copy-on-write semantics
+///
+/// A copy is only made in the event that a different value is written.
+#[derive(Debug, Copy, Clone)]
+pub struct InRc<L> {
+    inner: L,
+}
+
+impl<L> InRc<L> {
+    /// Adapt a lens to operate on an `Rc`
+    ///
+    /// See also `LensExt::in_rc`
+    pub fn new<A, B>(inner: L) -> Self
+    where
+        A: Clone,
+        B: Data,
+        L: Lens<A, B>,
+    {
+        Self { inner }
+    }
+}
+
+impl<A, B, L> Lens<Rc<A>, B> for InRc<L>
+where
+    A: Clone,
+    B: Data,
+    L: Lens<A, B>,
+{
+    fn with<V, F: FnOnce(&B) -> V>(&self, data: &Rc<A>, f: F) -> V {
+        self.inner.with(data, f)
+    }
+
+    fn with_mut<V, F: FnOnce(&mut B) -> V>(&self, data: &mut Rc<A>, f: F) -> V {
+        let mut temp = self.inner.with(data, |x| x.clone());
+        let v = f(&mut temp);
+        if self.inner.with(data, |x| !x.same(&temp)) {
+            self.inner.with_mut(Rc::make_mut(data), |x| *x = temp);
+        }
+        v
+    }
+}
+
+/// `Lens` for the contents of an `Arc` or `Rc`, with copy-on-write semantics
+///
+/// A copy is only made in the event that a different value is written.
+///
+/// See also `LensExt::make_mut`.
+#[derive(Debug, Copy, Clone)]
+pub struct MakeMut;
+
+impl<T: Data> Lens<Arc<T>, T> for MakeMut {
+    fn with<V, F: FnOnce(&T) -> V>(&self, data: &Arc<T>, f: F) -> V {
+        f(data)
+    }
+
+    fn with_mut<V, F: FnOnce(&mut T) -> V>(&self, data: &mut Arc<T>, f: F) -> V {
+        let mut temp = T::clone(data);
+        let v = f(&mut temp);
+        if !temp.same(data) {
+            *data = Arc::new(temp);
+        }
+        v
+    }
+}
+
+impl<T: Data> Lens<Rc<T>, T> for MakeMut {
+    fn with<V, F: FnOnce(&T) -> V>(&self, data: &Rc<T>, f: F) -> V {
+        f(data)
+    }
+
+    fn with_mut<V, F: FnOnce(&mut T) -> V>(&self, data: &mut Rc<T>, f: F) -> V {
+        let mut temp = T::clone(data);
+        let v = f(&mut temp);
+        if !temp.same(data) {
+            *data = Rc::new(temp);
+        }
+        v
+    }
+}
+
+/// `Lens` that exposes an `Arc<str>` or `Rc<str>` as a `String`
+///
+/// A new string is only allocated in the event that a different value is written.
+///
+/// See also `LensExt::as_string`.
+#[derive(Debug, Copy, Clone)]
+pub struct AsString;
+
+impl Lens<Arc<str>, String> for AsString {
+    fn with<V, F: FnOnce(&String) -> V>(&self, data: &Arc<str>, f: F) -> V {
+        f(&data.to_string())
+    }
+
+    fn with_mut<V, F: FnOnce(&mut String) -> V>(&self, data: &mut Arc<str>, f: F) -> V {
+        let mut temp = data.to_string();
+        let v = f(&mut temp);
+        if *temp != **data {
+            *data = temp.into();
+        }
+        v
+    }
+}
+
+impl Lens<Rc<str>, String> for AsString {
+    fn with<V, F: FnOnce(&String) -> V>(&self, data: &Rc<str>, f: F) -> V {
+        f(&data.to_string())
+    }
+
+    fn with_mut<V, F: FnOnce(&mut String) -> V>(&self, data: &mut Rc<str>, f: F) -> V {
+        let mut temp = data.to_string();
+        let v = f(&mut temp);
+        if *temp != **data {
+            *data = temp.into();
+        }
+        v
+    }
+}
+
 /// A `Lens` that always yields ().
 ///
 /// This is useful when you wish to have a display only widget, require a type-erased widget, or
@@ -568,6 +856,298 @@ impl<A, B: Clone> Lens<A, B> for Constant<B> {
     }
 }
 
+/// A container with entries that may be missing, for use with [`Entry`].
+pub trait Keyed<K> {
+    /// The type of the entries.
+    type Value: Data;
+
+    /// The entry for `key`, if there is one.
+    fn entry(&self, key: &K) -> Option<&Self::Value>;
+
+    /// The entry for `key`, if there is one.
+    fn entry_mut(&mut self, key: &K) -> Option<&mut Self::Value>;
+}
+
+impl<T: Data> Keyed<usize> for Vec<T> {
+    type Value = T;
+    fn entry(&self, key: &usize) -> Option<&T> {
+        self.get(*key)
+    }
+    fn entry_mut(&mut self, key: &usize) -> Option<&mut T> {
+        self.get_mut(*key)
+    }
+}
+
+impl<K: Ord, V: Data> Keyed<K> for std::collections::BTreeMap<K, V> {
+    type Value = V;
+    fn entry(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+    fn entry_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
+impl<K, V, S> Keyed<K> for std::collections::HashMap<K, V, S>
+where
+    K: Eq + std::hash::Hash,
+    V: Data,
+    S: std::hash::BuildHasher,
+{
+    type Value = V;
+    fn entry(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+    fn entry_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
+/// Copies the container on write, if it is shared.
+impl<K, C: Keyed<K> + Clone> Keyed<K> for Arc<C> {
+    type Value = C::Value;
+    fn entry(&self, key: &K) -> Option<&C::Value> {
+        C::entry(self, key)
+    }
+    fn entry_mut(&mut self, key: &K) -> Option<&mut C::Value> {
+        Arc::make_mut(self).entry_mut(key)
+    }
+}
+
+/// Copies the container on write, if it is shared.
+impl<K, C: Keyed<K> + Clone> Keyed<K> for Rc<C> {
+    type Value = C::Value;
+    fn entry(&self, key: &K) -> Option<&C::Value> {
+        C::entry(self, key)
+    }
+    fn entry_mut(&mut self, key: &K) -> Option<&mut C::Value> {
+        Rc::make_mut(self).entry_mut(key)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<T: Data> Keyed<usize> for im::Vector<T> {
+    type Value = T;
+    fn entry(&self, key: &usize) -> Option<&T> {
+        self.get(*key)
+    }
+    fn entry_mut(&mut self, key: &usize) -> Option<&mut T> {
+        self.get_mut(*key)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<K, V, S> Keyed<K> for im::HashMap<K, V, S>
+where
+    K: Clone + Eq + std::hash::Hash,
+    V: Data,
+    S: std::hash::BuildHasher,
+{
+    type Value = V;
+    fn entry(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+    fn entry_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<K: Clone + Ord, V: Data> Keyed<K> for im::OrdMap<K, V> {
+    type Value = V;
+    fn entry(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+    fn entry_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
+/// `Lens` for an entry of a container that may be missing
+///
+/// See also `LensExt::try_index` and `LensExt::key`.
+#[derive(Debug, Copy, Clone)]
+pub struct Entry<K> {
+    key: K,
+}
+
+impl<K> Entry<K> {
+    /// Construct a lens that accesses the entry for `key`
+    pub fn new(key: K) -> Self {
+        Self { key }
+    }
+}
+
+impl<T, K> Lens<T, Option<T::Value>> for Entry<K>
+where
+    T: ?Sized + Keyed<K>,
+{
+    fn with<V, F: FnOnce(&Option<T::Value>) -> V>(&self, data: &T, f: F) -> V {
+        f(&data.entry(&self.key).cloned())
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Option<T::Value>) -> V>(&self, data: &mut T, f: F) -> V {
+        let mut temp = data.entry(&self.key).cloned();
+        let v = f(&mut temp);
+        if let Some(temp) = temp {
+            // Only ask for a mutable entry when it changes, so that shared
+            // containers are not copied for nothing.
+            if data
+                .entry(&self.key)
+                .is_some_and(|entry| !entry.same(&temp))
+            {
+                if let Some(entry) = data.entry_mut(&self.key) {
+                    *entry = temp;
+                }
+            }
+        }
+        v
+    }
+}
+
+/// `Lens` that applies a lens to the value inside an `Option`
+///
+/// See also `LensExt::then_some`.
+#[derive(Debug, Copy, Clone)]
+pub struct ThenSome<L> {
+    inner: L,
+}
+
+impl<L> ThenSome<L> {
+    /// Adapt a lens to operate on an `Option`
+    pub fn new(inner: L) -> Self {
+        Self { inner }
+    }
+}
+
+impl<B, C, L> Lens<Option<B>, Option<C>> for ThenSome<L>
+where
+    C: Data,
+    L: Lens<B, C>,
+{
+    fn with<V, F: FnOnce(&Option<C>) -> V>(&self, data: &Option<B>, f: F) -> V {
+        match data {
+            Some(b) => self.inner.with(b, |c| f(&Some(c.clone()))),
+            None => f(&None),
+        }
+    }
+
+    fn with_mut<V, F: FnOnce(&mut Option<C>) -> V>(&self, data: &mut Option<B>, f: F) -> V {
+        match data {
+            Some(b) => self.inner.with_mut(b, |c| {
+                let mut temp = Some(c.clone());
+                let v = f(&mut temp);
+                if let Some(temp) = temp {
+                    if !c.same(&temp) {
+                        *c = temp;
+                    }
+                }
+                v
+            }),
+            None => f(&mut None),
+        }
+    }
+}
+
+/// `Lens` that derives a value from its input and caches it
+///
+/// The value is recomputed only when the input is not [`same`] as the input
+/// it was last computed from, so expensive derivations such as sorted or
+/// filtered lists don't run on every update pass. Narrow the input to the
+/// data the value depends on by putting another lens in front of this one,
+/// or use `LensExt::computed`.
+///
+/// The lens is read-only: changes made through it are discarded.
+///
+/// [`same`]: Data::same
+pub struct Computed<I, U, F> {
+    compute: F,
+    cache: RefCell<Option<(I, U)>>,
+}
+
+impl<I, U, F> Computed<I, U, F> {
+    /// Construct a lens that computes its value with `compute`
+    pub fn new(compute: F) -> Self
+    where
+        F: Fn(&I) -> U,
+    {
+        Self {
+            compute,
+            cache: RefCell::new(None),
+        }
+    }
+}
+
+impl<I: Data, U: Clone, F: Clone> Clone for Computed<I, U, F> {
+    fn clone(&self) -> Self {
+        Self {
+            compute: self.compute.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<I: Data, U: Clone, F: Fn(&I) -> U> Computed<I, U, F> {
+    fn value(&self, input: &I) -> U {
+        if let Some((cached_input, value)) = &*self.cache.borrow() {
+            if cached_input.same(input) {
+                return value.clone();
+            }
+        }
+        let value = (self.compute)(input);
+        *self.cache.borrow_mut() = Some((input.clone(), value.clone()));
+        value
+    }
+}
+
+impl<I: Data, U: Clone, F: Fn(&I) -> U> Lens<I, U> for Computed<I, U, F> {
+    fn with<V, G: FnOnce(&U) -> V>(&self, data: &I, f: G) -> V {
+        f(&self.value(data))
+    }
+
+    fn with_mut<V, G: FnOnce(&mut U) -> V>(&self, data: &mut I, f: G) -> V {
+        f(&mut self.value(data))
+    }
+}
+
+/// `Lens` that yields a default value in place of `None`
+///
+/// Writing a value other than the default to `None` replaces it with
+/// `Some`; otherwise `None` is left as it is.
+///
+/// See also `LensExt::defaulted`.
+#[derive(Debug, Copy, Clone)]
+pub struct Defaulted<T> {
+    default: T,
+}
+
+impl<T> Defaulted<T> {
+    /// Construct a lens that yields `default` in place of `None`
+    pub fn new(default: T) -> Self {
+        Self { default }
+    }
+}
+
+impl<T: Data> Lens<Option<T>, T> for Defaulted<T> {
+    fn with<V, F: FnOnce(&T) -> V>(&self, data: &Option<T>, f: F) -> V {
+        f(data.as_ref().unwrap_or(&self.default))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut T) -> V>(&self, data: &mut Option<T>, f: F) -> V {
+        match data {
+            Some(value) => f(value),
+            None => {
+                let mut temp = self.default.clone();
+                let v = f(&mut temp);
+                if !temp.same(&self.default) {
+                    *data = Some(temp);
+                }
+                v
+            }
+        }
+    }
+}
+
 macro_rules! impl_lens_for_tuple {
     ($(($Lens:ident, $B:ident, $i:tt)),*) => {
         #[allow(non_snake_case)]
@@ -53,5 +53,6 @@
 #[macro_use]
 mod lens;
 pub use lens::{
-    Constant, Deref, Field, Identity, InArc, Index, Lens, LensExt, Map, Ref, Then, Unit,
+    AsString, Computed, Constant, Defaulted, Deref, Entry, Field, Identity, InArc, InRc, Index,
+    Keyed, Lens, LensExt, MakeMut, Map, Ref, Then, ThenSome, Unit,
 };
@@ -99,6 +99,12 @@
 //!          which is made available via the [`im` module].
 //! * `svg` - Scalable Vector Graphics for icons and other scalable images using the [`usvg` crate].
 //! * `image` - Bitmap image support using the [`image` crate].
+//! * `markdown` - The `Markdown` widget, parsing CommonMark with the [`pulldown-cmark` crate].
+//! * `declarative` - Widget trees built from JSON at runtime, see the [`declarative` module].
+//! * `persistence` - Saving app data between runs with [`serde`], see the [`persistence` module].
+//! * `theme-file` - Loading and hot reloading the theme from a TOML file, see the [`theme_file` module].
+//! * `futures` - Feeding a `futures` `Stream` into the app data, see [`task::Feed`].
+//! * `constraint-layout` - The `ConstraintLayout` widget, using the [`cassowary` crate].
 //! * `x11` - Work-in-progress X11 backend instead of GTK.
 //! * `wayland` - Work-in-progress Wayland backend, very experimental.
 //! * `serde` - Serde support for some internal types (most Kurbo primitives).
@@ -142,6 +148,12 @@
 //! [`im` module]: im/index.html
 //! [`usvg` crate]: https://crates.io/crates/usvg
 //! [`image` crate]: https://crates.io/crates/image
+//! [`pulldown-cmark` crate]: https://crates.io/crates/pulldown-cmark
+//! [`cassowary` crate]: https://crates.io/crates/cassowary
+//! [`declarative` module]: declarative/index.html
+//! [`serde`]: https://crates.io/crates/serde
+//! [`persistence` module]: persistence/index.html
+//! [`theme_file` module]: theme_file/index.html
 
 #![deny(
     rustdoc::broken_intra_doc_links,
@@ -177,13 +189,19 @@ mod util;
 
 mod app;
 mod app_delegate;
+mod aux_controls;
 mod bloom;
 mod box_constraints;
+mod color_blindness;
 mod command;
 mod contexts;
 mod core;
 mod data;
+mod data_diff;
 pub mod debug_state;
+#[cfg(feature = "declarative")]
+#[cfg_attr(docsrs, doc(cfg(feature = "declarative")))]
+pub mod declarative;
 mod dialog;
 pub mod env;
 mod event;
@@ -191,12 +209,23 @@ mod ext_event;
 mod localization;
 pub mod menu;
 mod mouse;
+#[cfg(feature = "persistence")]
+#[cfg_attr(docsrs, doc(cfg(feature = "persistence")))]
+pub mod persistence;
+pub mod plugin;
+pub mod profiling;
+pub mod resources;
 pub mod scroll_component;
 mod sub_window;
+mod subscription;
+pub mod task;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tests;
 pub mod text;
 pub mod theme;
+#[cfg(feature = "theme-file")]
+#[cfg_attr(docsrs, doc(cfg(feature = "theme-file")))]
+pub mod theme_file;
 pub mod widget;
 mod win_handler;
 mod window;
@@ -209,23 +238,29 @@ pub use piet::{Color, ImageBuf, LinearGradient, RadialGradient, RenderContext, U
 #[cfg(feature = "image")]
 pub use shell::image;
 pub use shell::keyboard_types;
+#[cfg(feature = "keychain")]
+pub use shell::keychain;
 pub use shell::{
-    Application, Clipboard, ClipboardFormat, Code, Cursor, CursorDesc, Error as PlatformError,
-    FileInfo, FileSpec, FormatId, HotKey, KbKey, KeyEvent, Location, Modifiers, Monitor,
-    MouseButton, MouseButtons, RawMods, Region, Scalable, Scale, ScaledArea, Screen, SysMods,
-    TimerToken, WindowHandle, WindowLevel, WindowState,
+    Application, Clipboard, ClipboardFormat, Code, ColorScheme, Cursor, CursorDesc,
+    Error as PlatformError, FileInfo, FileSpec, FormatId, HotKey, InhibitToken, KbKey, KeyEvent,
+    Location, Modifiers, Monitor, MouseButton, MouseButtons, PowerSource, RawMods, Region,
+    Scalable, Scale, ScaledArea, Screen, SecureInputToken, SysMods, SystemStatus, TimerToken,
+    WindowHandle, WindowLevel, WindowState,
 };
 
 #[cfg(feature = "raw-win-handle")]
 pub use crate::shell::raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 
 pub use crate::core::{WidgetPod, WidgetState};
-pub use app::{AppLauncher, WindowConfig, WindowDesc, WindowSizePolicy};
+pub use app::{AppLauncher, KioskMode, WindowConfig, WindowDesc, WindowSizePolicy};
 pub use app_delegate::{AppDelegate, DelegateCtx};
+pub use aux_controls::AuxControls;
 pub use box_constraints::BoxConstraints;
+pub use color_blindness::ColorBlindness;
 pub use command::{sys as commands, Command, Notification, Selector, SingleUse, Target};
 pub use contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx};
 pub use data::*; // Wildcard because rustdoc has trouble inlining docs of two things called Data
+pub use data_diff::DataDiff;
 pub use dialog::FileDialogOptions;
 #[doc(inline)]
 pub use env::{Env, Key, KeyOrValue, Value, ValueType, ValueTypeError};
@@ -236,6 +271,7 @@ pub use localization::LocalizedString;
 #[doc(inline)]
 pub use menu::{sys as platform_menus, Menu, MenuItem};
 pub use mouse::MouseEvent;
+pub use task::TaskPool;
 pub use util::Handled;
 pub use widget::{Widget, WidgetExt, WidgetId};
 pub use win_handler::DruidHandler;
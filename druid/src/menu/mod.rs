@@ -216,6 +216,39 @@ impl<T: Data> MenuManager<T> {
         data: &T,
         env: &Env,
     ) -> Option<PlatformMenu> {
+        self.update_menu(window, data, env)
+            .then(|| self.refresh(data, env))
+    }
+
+    /// Build an initial menu from the application data, for drawing it inside a window.
+    pub fn initialize_nodes(
+        &mut self,
+        window: Option<WindowId>,
+        data: &T,
+        env: &Env,
+    ) -> Vec<MenuNode> {
+        if let Some(build) = &mut self.build {
+            self.menu = Some((build)(window, data, env));
+        }
+        self.old_data = Some(data.clone());
+        self.nodes(data, env)
+    }
+
+    /// Update the menu based on a change to the data, for drawing it inside a window.
+    ///
+    /// Returns the new entries if the menu has changed; returns `None` if it hasn't.
+    pub fn update_nodes(
+        &mut self,
+        window: Option<WindowId>,
+        data: &T,
+        env: &Env,
+    ) -> Option<Vec<MenuNode>> {
+        self.update_menu(window, data, env)
+            .then(|| self.nodes(data, env))
+    }
+
+    /// Rebuilds the menu if needed, and returns `true` if it has to be refreshed.
+    fn update_menu(&mut self, window: Option<WindowId>, data: &T, env: &Env) -> bool {
         if let (Some(menu), Some(old_data)) = (self.menu.as_mut(), self.old_data.as_ref()) {
             let ret = match menu.update(old_data, data, env) {
                 MenuUpdate::NeedsRebuild => {
@@ -224,16 +257,16 @@ impl<T: Data> MenuManager<T> {
                     } else {
                         tracing::warn!("tried to rebuild a context menu");
                     }
-                    Some(self.refresh(data, env))
+                    true
                 }
-                MenuUpdate::NeedsRefresh => Some(self.refresh(data, env)),
-                MenuUpdate::UpToDate => None,
+                MenuUpdate::NeedsRefresh => true,
+                MenuUpdate::UpToDate => false,
             };
             self.old_data = Some(data.clone());
             ret
         } else {
             tracing::error!("tried to update uninitialized menus");
-            None
+            false
         }
     }
 
@@ -245,12 +278,24 @@ impl<T: Data> MenuManager<T> {
         if let Some(menu) = self.menu.as_mut() {
             let mut ctx = MenuBuildCtx::new(self.popup);
             menu.refresh_children(&mut ctx, data, env);
-            ctx.current
+            ctx.current.unwrap_or_else(PlatformMenu::new)
         } else {
             tracing::error!("tried to refresh uninitialized menus");
             PlatformMenu::new()
         }
     }
+
+    /// Resolves the entries of the menu for the given data, for drawing it inside a window.
+    pub fn nodes(&mut self, data: &T, env: &Env) -> Vec<MenuNode> {
+        if let Some(menu) = self.menu.as_mut() {
+            let mut ctx = MenuBuildCtx::in_window();
+            menu.refresh_children(&mut ctx, data, env);
+            ctx.nodes
+        } else {
+            tracing::error!("tried to refresh uninitialized menus");
+            Vec::new()
+        }
+    }
 }
 
 /// This context is available to the callback that is called when a menu item is activated.
@@ -263,26 +308,65 @@ pub struct MenuEventCtx<'a> {
     queue: &'a mut CommandQueue,
 }
 
-/// This context helps menu items to build the platform menu.
+/// A resolved menu entry, for menus that are drawn inside a window instead of by the platform.
+#[derive(Debug, Clone)]
+pub(crate) enum MenuNode {
+    Item {
+        id: MenuItemId,
+        title: ArcStr,
+        hotkey: Option<HotKey>,
+        selected: Option<bool>,
+        enabled: bool,
+    },
+    Submenu {
+        title: ArcStr,
+        enabled: bool,
+        children: Vec<MenuNode>,
+    },
+    Separator,
+}
+
+/// This context helps menu items to build the platform menu, or the entries of a menu
+/// drawn inside a window.
 struct MenuBuildCtx {
-    current: PlatformMenu,
+    /// The platform menu, if one is being built.
+    current: Option<PlatformMenu>,
+    nodes: Vec<MenuNode>,
 }
 
 impl MenuBuildCtx {
     fn new(popup: bool) -> MenuBuildCtx {
         MenuBuildCtx {
-            current: if popup {
+            current: Some(if popup {
                 PlatformMenu::new_for_popup()
             } else {
                 PlatformMenu::new()
-            },
+            }),
+            nodes: Vec::new(),
+        }
+    }
+
+    fn in_window() -> MenuBuildCtx {
+        MenuBuildCtx {
+            current: None,
+            nodes: Vec::new(),
         }
     }
 
     fn with_submenu(&mut self, text: &str, enabled: bool, f: impl FnOnce(&mut MenuBuildCtx)) {
-        let mut child = MenuBuildCtx::new(false);
+        let mut child = match self.current {
+            Some(_) => MenuBuildCtx::new(false),
+            None => MenuBuildCtx::in_window(),
+        };
         f(&mut child);
-        self.current.add_dropdown(child.current, text, enabled);
+        if let (Some(current), Some(submenu)) = (&mut self.current, child.current) {
+            current.add_dropdown(submenu, text, enabled);
+        }
+        self.nodes.push(MenuNode::Submenu {
+            title: text.into(),
+            enabled,
+            children: child.nodes,
+        });
     }
 
     fn add_item(
@@ -293,11 +377,23 @@ impl MenuBuildCtx {
         selected: Option<bool>,
         enabled: bool,
     ) {
-        self.current.add_item(id, text, key, selected, enabled);
+        if let Some(current) = &mut self.current {
+            current.add_item(id, text, key, selected, enabled);
+        }
+        self.nodes.push(MenuNode::Item {
+            id: MenuItemId::new(id),
+            title: text.into(),
+            hotkey: key.cloned(),
+            selected,
+            enabled,
+        });
     }
 
     fn add_separator(&mut self) {
-        self.current.add_separator();
+        if let Some(current) = &mut self.current {
+            current.add_separator();
+        }
+        self.nodes.push(MenuNode::Separator);
     }
 }
 
@@ -0,0 +1,426 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Saving the application's data between runs.
+//!
+//! A [`Persistence`] describes what part of the root data to save, and where.
+//! Give it to [`AppLauncher::persist`]: the saved data is restored when the
+//! application launches, and saved again whenever a window closes, when the
+//! application quits, and optionally at a regular interval.
+//!
+//! The data is stored as JSON in the platform's configuration directory,
+//! together with a version number. When the format of the data changes,
+//! raise the version with [`Persistence::with_version`], and add a
+//! [`migration`] that turns data saved by an older version into the new
+//! format.
+//!
+//! ```ignore
+//! use druid::persistence::Persistence;
+//! use druid::widget::Label;
+//! use druid::{AppLauncher, Data, Lens, WindowDesc};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Data, Lens, Serialize, Deserialize)]
+//! struct Settings {
+//!     volume: f64,
+//! }
+//!
+//! #[derive(Clone, Data, Lens)]
+//! struct AppState {
+//!     settings: Settings,
+//!     status: String,
+//! }
+//!
+//! let window = WindowDesc::new(Label::new("Hello"));
+//! let state = AppState {
+//!     settings: Settings { volume: 0.5 },
+//!     status: String::new(),
+//! };
+//! AppLauncher::with_window(window)
+//!     .persist(Persistence::lensed("my-app", AppState::settings))
+//!     .launch(state)
+//!     .expect("launch failed");
+//! ```
+//!
+//! [`AppLauncher::persist`]: crate::AppLauncher::persist
+//! [`migration`]: Persistence::with_migration
+
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::{
+    AppDelegate, Command, DelegateCtx, Env, Event, ExtEventSink, Handled, Lens, Selector, Target,
+    WindowHandle, WindowId,
+};
+
+type SaveFn<T> = Box<dyn Fn(&T) -> Result<Value, serde_json::Error>>;
+type RestoreFn<T> = Box<dyn Fn(&mut T, Value) -> Result<(), serde_json::Error>>;
+type Migration = Box<dyn Fn(Value) -> Value>;
+
+/// Sent by the timer of [`Persistence::save_every`].
+const SAVE: Selector = Selector::new("druid-builtin.persistence-save");
+
+/// An error that occurs while saving or restoring data.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+    /// The saved data could not be converted to or from JSON.
+    Json(serde_json::Error),
+    /// The platform's configuration directory could not be found.
+    NoConfigDir,
+    /// The data was saved by a newer version of the application.
+    NewerVersion(u32),
+}
+
+/// What part of the application's data to save, and where.
+///
+/// See the [module documentation](self) for an overview.
+pub struct Persistence<T> {
+    app_name: String,
+    file_name: String,
+    path: Option<PathBuf>,
+    version: u32,
+    migrations: Vec<(u32, Migration)>,
+    interval: Option<Duration>,
+    save: SaveFn<T>,
+    restore: RestoreFn<T>,
+}
+
+impl<T: Serialize + DeserializeOwned + 'static> Persistence<T> {
+    /// Save all of the application's data.
+    ///
+    /// `app_name` names the directory the data is saved in, within the
+    /// platform's configuration directory.
+    pub fn new(app_name: impl Into<String>) -> Self {
+        Persistence::with_functions(
+            app_name.into(),
+            Box::new(|data: &T| serde_json::to_value(data)),
+            Box::new(|data, value| {
+                *data = serde_json::from_value(value)?;
+                Ok(())
+            }),
+        )
+    }
+}
+
+impl<T: 'static> Persistence<T> {
+    /// Save the part of the application's data selected by `lens`.
+    ///
+    /// `app_name` names the directory the data is saved in, within the
+    /// platform's configuration directory.
+    pub fn lensed<U, L>(app_name: impl Into<String>, lens: L) -> Self
+    where
+        U: Serialize + DeserializeOwned + 'static,
+        L: Lens<T, U> + Clone + 'static,
+    {
+        let restore_lens = lens.clone();
+        Persistence::with_functions(
+            app_name.into(),
+            Box::new(move |data| lens.with(data, |data: &U| serde_json::to_value(data))),
+            Box::new(move |data, value| {
+                let value = serde_json::from_value(value)?;
+                restore_lens.with_mut(data, |data| *data = value);
+                Ok(())
+            }),
+        )
+    }
+
+    fn with_functions(app_name: String, save: SaveFn<T>, restore: RestoreFn<T>) -> Self {
+        Persistence {
+            app_name,
+            file_name: "state.json".into(),
+            path: None,
+            version: 0,
+            migrations: Vec::new(),
+            interval: None,
+            save,
+            restore,
+        }
+    }
+
+    /// Builder-style method to set the name of the file in the application's
+    /// configuration directory. The default is `state.json`.
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = file_name.into();
+        self
+    }
+
+    /// Builder-style method to save to `path`, instead of to the platform's
+    /// configuration directory.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Builder-style method to set the version of the format of the saved data.
+    ///
+    /// The version starts at `0`. Data saved with a newer version than this
+    /// is not restored.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Builder-style method to add a migration from data saved with version
+    /// `from` to the format of version `from + 1`.
+    ///
+    /// When older data is restored, the migrations from its version up to the
+    /// current version are applied in order.
+    pub fn with_migration(mut self, from: u32, migrate: impl Fn(Value) -> Value + 'static) -> Self {
+        self.migrations.push((from, Box::new(migrate)));
+        self.migrations.sort_by_key(|(from, _)| *from);
+        self
+    }
+
+    /// Builder-style method to also save the data every `interval`, so that
+    /// less is lost if the application does not quit normally.
+    pub fn save_every(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// The file the data is saved to, or `None` if the platform's
+    /// configuration directory could not be found.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path
+            .clone()
+            .or_else(|| Some(config_dir()?.join(&self.app_name).join(&self.file_name)))
+    }
+
+    /// Save `data` now.
+    pub fn save(&self, data: &T) -> Result<(), Error> {
+        let path = self.path().ok_or(Error::NoConfigDir)?;
+        let value = (self.save)(data).map_err(Error::Json)?;
+        let file = json!({ "version": self.version, "data": value });
+        let text = serde_json::to_string_pretty(&file).map_err(Error::Json)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(Error::Io)?;
+        }
+        // Write to a temporary file first, so that a crash can't leave half a file.
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, text).map_err(Error::Io)?;
+        std::fs::rename(&temp, &path).map_err(Error::Io)
+    }
+
+    /// Restore the saved data into `data`.
+    ///
+    /// Returns `false` if nothing has been saved yet.
+    pub fn restore(&self, data: &mut T) -> Result<bool, Error> {
+        let path = self.path().ok_or(Error::NoConfigDir)?;
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(Error::Io(e)),
+        };
+        let file: Value = serde_json::from_str(&text).map_err(Error::Json)?;
+        let version = file["version"].as_u64().unwrap_or(0) as u32;
+        if version > self.version {
+            return Err(Error::NewerVersion(version));
+        }
+        let value = self.migrate(version, file["data"].clone());
+        (self.restore)(data, value).map_err(Error::Json)?;
+        Ok(true)
+    }
+
+    fn migrate(&self, version: u32, mut value: Value) -> Value {
+        for (from, migrate) in &self.migrations {
+            if *from >= version && *from < self.version {
+                value = migrate(value);
+            }
+        }
+        value
+    }
+
+    pub(crate) fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    pub(crate) fn save_or_warn(&self, data: &T) {
+        if let Err(e) = self.save(data) {
+            warn!("failed to save app data: {}", e);
+        }
+    }
+}
+
+/// The platform's directory for per-user configuration files.
+fn config_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        })
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+}
+
+/// Wraps the application's delegate, to save when windows close and when
+/// the save timer fires.
+pub(crate) struct PersistDelegate<T> {
+    persistence: Rc<Persistence<T>>,
+    inner: Option<Box<dyn AppDelegate<T>>>,
+}
+
+impl<T> PersistDelegate<T> {
+    pub(crate) fn new(
+        persistence: Rc<Persistence<T>>,
+        inner: Option<Box<dyn AppDelegate<T>>>,
+    ) -> Self {
+        PersistDelegate { persistence, inner }
+    }
+}
+
+impl<T: crate::Data> AppDelegate<T> for PersistDelegate<T> {
+    fn event(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        window_id: WindowId,
+        event: Event,
+        data: &mut T,
+        env: &Env,
+    ) -> Option<Event> {
+        match &mut self.inner {
+            Some(inner) => inner.event(ctx, window_id, event, data, env),
+            None => Some(event),
+        }
+    }
+
+    fn command(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        target: Target,
+        cmd: &Command,
+        data: &mut T,
+        env: &Env,
+    ) -> Handled {
+        if cmd.is(SAVE) {
+            self.persistence.save_or_warn(data);
+            return Handled::Yes;
+        }
+        match &mut self.inner {
+            Some(inner) => inner.command(ctx, target, cmd, data, env),
+            None => Handled::No,
+        }
+    }
+
+    fn window_added(
+        &mut self,
+        id: WindowId,
+        handle: WindowHandle,
+        data: &mut T,
+        env: &Env,
+        ctx: &mut DelegateCtx,
+    ) {
+        if let Some(inner) = &mut self.inner {
+            inner.window_added(id, handle, data, env, ctx);
+        }
+    }
+
+    fn window_removed(&mut self, id: WindowId, data: &mut T, env: &Env, ctx: &mut DelegateCtx) {
+        if let Some(inner) = &mut self.inner {
+            inner.window_removed(id, data, env, ctx);
+        }
+        self.persistence.save_or_warn(data);
+    }
+}
+
+/// Sends [`SAVE`] on a background thread, until it is dropped.
+pub(crate) struct SaveTimer {
+    stop: mpsc::Sender<()>,
+}
+
+impl SaveTimer {
+    pub(crate) fn spawn(sink: ExtEventSink, interval: Duration) -> SaveTimer {
+        let (stop, stopped) = mpsc::channel();
+        std::thread::spawn(move || {
+            while stopped.recv_timeout(interval) == Err(RecvTimeoutError::Timeout) {
+                if sink.submit_command(SAVE, (), Target::Global).is_err() {
+                    break;
+                }
+            }
+        });
+        SaveTimer { stop }
+    }
+}
+
+impl Drop for SaveTimer {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "Could not access the saved data: {err}"),
+            Error::Json(err) => write!(f, "Invalid saved data: {err}"),
+            Error::NoConfigDir => write!(f, "No configuration directory"),
+            Error::NewerVersion(version) => {
+                write!(f, "Data was saved by a newer version ({version})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restores_and_migrates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        let old = Persistence::<(u32, String)>::new("test").with_path(&path);
+        assert!(!old.restore(&mut (0, String::new())).unwrap());
+        old.save(&(7, "seven".into())).unwrap();
+
+        // Version 1 swapped the fields around.
+        let new = Persistence::<(String, u32)>::new("test")
+            .with_path(&path)
+            .with_version(1)
+            .with_migration(0, |value| json!([value[1], value[0]]));
+        let mut data = (String::new(), 0);
+        assert!(new.restore(&mut data).unwrap());
+        assert_eq!(data, ("seven".to_string(), 7));
+
+        new.save(&data).unwrap();
+        assert!(matches!(
+            old.restore(&mut (0, String::new())),
+            Err(Error::NewerVersion(1))
+        ));
+    }
+}
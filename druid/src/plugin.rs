@@ -0,0 +1,271 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A registry for extending an application with plugins.
+//!
+//! A host application creates a [`PluginRegistry`] at startup and passes it
+//! to each [`Plugin`] it wants to load, usually from other crates. Plugins
+//! contribute named widget factories, named commands, and items for the
+//! host's menus; the host then looks these up by name when building its
+//! windows.
+//!
+//! ```
+//! use druid::plugin::{Plugin, PluginRegistry};
+//! use druid::widget::Label;
+//! use druid::{MenuItem, Selector};
+//!
+//! const SAY_HELLO: Selector = Selector::new("hello-plugin.say-hello");
+//!
+//! struct HelloPlugin;
+//!
+//! impl Plugin<u32> for HelloPlugin {
+//!     fn name(&self) -> &str {
+//!         "hello"
+//!     }
+//!
+//!     fn register(&self, registry: &mut PluginRegistry<u32>) {
+//!         registry.add_widget("hello.greeting", || Label::new("Hello!"));
+//!         registry.add_command("hello.say-hello", "Say Hello", SAY_HELLO);
+//!         registry.add_menu_item("Help", || MenuItem::new("Say Hello").command(SAY_HELLO));
+//!     }
+//! }
+//!
+//! let mut registry = PluginRegistry::new();
+//! registry.register(&HelloPlugin);
+//!
+//! assert!(registry.widget("hello.greeting").is_some());
+//! let help_menu = registry.menu("Help");
+//! ```
+
+use std::collections::HashMap;
+
+use tracing::warn;
+
+use crate::menu::MenuEntry;
+use crate::{ArcStr, Command, Data, Menu, Widget};
+
+type WidgetFactory<T> = Box<dyn Fn() -> Box<dyn Widget<T>>>;
+type MenuFactory<T> = Box<dyn Fn() -> MenuEntry<T>>;
+
+/// An extension that contributes widgets, commands, and menu items to a
+/// host application.
+pub trait Plugin<T> {
+    /// A name that uniquely identifies this plugin.
+    fn name(&self) -> &str;
+
+    /// Add this plugin's contributions to the registry.
+    fn register(&self, registry: &mut PluginRegistry<T>);
+}
+
+/// A command contributed by a plugin.
+#[derive(Debug, Clone)]
+pub struct PluginCommand {
+    /// The name the command is registered under.
+    pub name: ArcStr,
+    /// A human readable title, for instance for display in a command palette.
+    pub title: ArcStr,
+    /// The command to submit when this is invoked.
+    pub command: Command,
+}
+
+/// The contributions of all loaded [`Plugin`]s.
+///
+/// See the [module level documentation](crate::plugin) for an example.
+pub struct PluginRegistry<T> {
+    plugins: Vec<ArcStr>,
+    widgets: HashMap<ArcStr, WidgetFactory<T>>,
+    commands: Vec<PluginCommand>,
+    menu_items: Vec<(ArcStr, MenuFactory<T>)>,
+}
+
+impl<T: Data> PluginRegistry<T> {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        PluginRegistry {
+            plugins: Vec::new(),
+            widgets: HashMap::new(),
+            commands: Vec::new(),
+            menu_items: Vec::new(),
+        }
+    }
+
+    /// Load a plugin, adding its contributions to this registry.
+    ///
+    /// A plugin with the same name as one already loaded is ignored.
+    pub fn register(&mut self, plugin: &dyn Plugin<T>) {
+        let name = plugin.name();
+        if self.is_registered(name) {
+            warn!("plugin '{}' is already registered", name);
+            return;
+        }
+        self.plugins.push(name.into());
+        plugin.register(self);
+    }
+
+    /// Returns `true` if a plugin with this name has been loaded.
+    pub fn is_registered(&self, plugin: &str) -> bool {
+        self.plugins.iter().any(|name| &**name == plugin)
+    }
+
+    /// The names of the loaded plugins, in the order they were registered.
+    pub fn plugins(&self) -> impl Iterator<Item = &str> {
+        self.plugins.iter().map(|name| &**name)
+    }
+
+    /// Register a function that creates a widget under `name`.
+    ///
+    /// If a widget was already registered under this name, it is replaced.
+    pub fn add_widget<W: Widget<T> + 'static>(
+        &mut self,
+        name: impl Into<ArcStr>,
+        factory: impl Fn() -> W + 'static,
+    ) {
+        let name = name.into();
+        let factory: WidgetFactory<T> = Box::new(move || Box::new(factory()));
+        if self.widgets.insert(name.clone(), factory).is_some() {
+            warn!("widget '{}' was registered more than once", name);
+        }
+    }
+
+    /// Create a new instance of the widget registered under `name`.
+    pub fn widget(&self, name: &str) -> Option<Box<dyn Widget<T>>> {
+        self.widgets.get(name).map(|factory| factory())
+    }
+
+    /// The names of all registered widgets, in no particular order.
+    pub fn widget_names(&self) -> impl Iterator<Item = &str> {
+        self.widgets.keys().map(|name| &**name)
+    }
+
+    /// Register a command under `name`, with a human readable `title`.
+    ///
+    /// If a command was already registered under this name, it is replaced.
+    pub fn add_command(
+        &mut self,
+        name: impl Into<ArcStr>,
+        title: impl Into<ArcStr>,
+        command: impl Into<Command>,
+    ) {
+        let name = name.into();
+        let command = PluginCommand {
+            name: name.clone(),
+            title: title.into(),
+            command: command.into(),
+        };
+        match self.commands.iter_mut().find(|cmd| cmd.name == name) {
+            Some(existing) => {
+                warn!("command '{}' was registered more than once", name);
+                *existing = command;
+            }
+            None => self.commands.push(command),
+        }
+    }
+
+    /// The command registered under `name`.
+    pub fn command(&self, name: &str) -> Option<Command> {
+        self.commands
+            .iter()
+            .find(|cmd| &*cmd.name == name)
+            .map(|cmd| cmd.command.clone())
+    }
+
+    /// All registered commands, in the order they were registered.
+    pub fn commands(&self) -> &[PluginCommand] {
+        &self.commands
+    }
+
+    /// Contribute an entry to the menu titled `menu`.
+    ///
+    /// Menus are rebuilt for each window, so an entry is added as a function
+    /// that creates it.
+    pub fn add_menu_item<E: Into<MenuEntry<T>>>(
+        &mut self,
+        menu: impl Into<ArcStr>,
+        entry: impl Fn() -> E + 'static,
+    ) {
+        self.menu_items
+            .push((menu.into(), Box::new(move || entry().into())));
+    }
+
+    /// The titles of all menus that have contributions, in the order they
+    /// were first contributed to.
+    pub fn menu_titles(&self) -> Vec<ArcStr> {
+        let mut titles: Vec<ArcStr> = Vec::new();
+        for (title, _) in &self.menu_items {
+            if !titles.contains(title) {
+                titles.push(title.clone());
+            }
+        }
+        titles
+    }
+
+    /// Add all entries contributed to the menu titled `menu` to an existing menu.
+    pub fn extend_menu(&self, menu: &str, mut existing: Menu<T>) -> Menu<T> {
+        for (_, entry) in self.menu_items.iter().filter(|(title, _)| &**title == menu) {
+            existing = existing.entry(entry());
+        }
+        existing
+    }
+
+    /// Create a menu titled `menu`, containing all entries contributed to it.
+    pub fn menu(&self, menu: &str) -> Menu<T> {
+        self.extend_menu(menu, Menu::new(menu.to_owned()))
+    }
+}
+
+impl<T: Data> Default for PluginRegistry<T> {
+    fn default() -> Self {
+        PluginRegistry::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::Label;
+    use crate::{MenuItem, Selector};
+
+    const FIRST: Selector = Selector::new("druid-test.plugin-first");
+    const SECOND: Selector = Selector::new("druid-test.plugin-second");
+
+    struct TestPlugin(&'static str, Selector);
+
+    impl Plugin<()> for TestPlugin {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn register(&self, registry: &mut PluginRegistry<()>) {
+            registry.add_widget("label", || Label::new("label"));
+            registry.add_command("run", "Run", self.1);
+            registry.add_menu_item("File", || MenuItem::new("Run").command(FIRST));
+        }
+    }
+
+    #[test]
+    fn registration() {
+        let mut registry = PluginRegistry::new();
+        registry.register(&TestPlugin("first", FIRST));
+        registry.register(&TestPlugin("first", SECOND));
+        assert_eq!(registry.plugins().collect::<Vec<_>>(), ["first"]);
+        assert!(registry.command("run").unwrap().is(FIRST));
+
+        registry.register(&TestPlugin("second", SECOND));
+        assert_eq!(registry.commands().len(), 1);
+        assert!(registry.command("run").unwrap().is(SECOND));
+        assert!(registry.widget("label").is_some());
+        assert!(registry.widget("missing").is_none());
+        assert_eq!(registry.menu_titles(), vec![ArcStr::from("File")]);
+    }
+}
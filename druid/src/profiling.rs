@@ -0,0 +1,261 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-widget timing, to find the widgets responsible for slow frames.
+//!
+//! Profiling is off by default, and is turned on with [`set_enabled`]. While it is on,
+//! every [`WidgetPod`] measures the time its widget spends in each pass, not counting
+//! the time spent in child pods, and attributes it to the widget's type. Widgets
+//! wrapped with [`WidgetExt::profiled`] are also reported under their name, with the
+//! time spent in their children included.
+//!
+//! The timings accumulate across passes until [`reset`] is called, and can be read at
+//! any time with [`report`].
+//!
+//! # Examples
+//!
+//! ```
+//! use druid::profiling;
+//!
+//! profiling::set_enabled(true);
+//! // ... run the app for a while, then:
+//! for timing in profiling::report().widget_types.iter().take(5) {
+//!     println!("{}: {:?}", timing.name, timing.total());
+//! }
+//! ```
+//!
+//! [`WidgetPod`]: crate::WidgetPod
+//! [`WidgetExt::profiled`]: crate::WidgetExt::profiled
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use instant::Instant;
+
+use crate::ArcStr;
+
+/// The passes a widget is timed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pass {
+    /// [`Widget::event`](crate::Widget::event).
+    Event,
+    /// [`Widget::lifecycle`](crate::Widget::lifecycle).
+    Lifecycle,
+    /// [`Widget::update`](crate::Widget::update).
+    Update,
+    /// [`Widget::layout`](crate::Widget::layout).
+    Layout,
+    /// [`Widget::paint`](crate::Widget::paint).
+    Paint,
+}
+
+/// The time spent in one pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PassTiming {
+    /// The number of times the pass ran.
+    pub calls: u64,
+    /// The cumulative time spent in the pass.
+    pub time: Duration,
+}
+
+/// The time spent by a widget type or a named widget.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WidgetTiming {
+    /// The widget's type, or the name it was given.
+    pub name: String,
+    passes: [PassTiming; 5],
+}
+
+/// The accumulated timings, each sorted from the most to the least time spent.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// The time spent by each widget type, not counting its children.
+    pub widget_types: Vec<WidgetTiming>,
+    /// The time spent by each widget named with [`WidgetExt::profiled`],
+    /// including its children.
+    ///
+    /// [`WidgetExt::profiled`]: crate::WidgetExt::profiled
+    pub named: Vec<WidgetTiming>,
+}
+
+impl WidgetTiming {
+    /// The time spent in `pass`.
+    pub fn pass(&self, pass: Pass) -> PassTiming {
+        self.passes[pass as usize]
+    }
+
+    /// The time spent in all passes.
+    pub fn total(&self) -> Duration {
+        self.passes.iter().map(|pass| pass.time).sum()
+    }
+
+    fn record(&mut self, pass: Pass, time: Duration) {
+        let timing = &mut self.passes[pass as usize];
+        timing.calls += 1;
+        timing.time += time;
+    }
+}
+
+#[derive(Default)]
+struct Profiler {
+    widget_types: HashMap<&'static str, WidgetTiming>,
+    named: HashMap<ArcStr, WidgetTiming>,
+    /// The time spent in the children of each widget being timed.
+    children_time: Vec<Duration>,
+}
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static PROFILER: RefCell<Profiler> = RefCell::new(Profiler::default());
+}
+
+/// Turn profiling on or off.
+///
+/// Timings collected so far are kept; see [`reset`].
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|cell| cell.set(enabled));
+}
+
+/// Whether profiling is on.
+pub fn is_enabled() -> bool {
+    ENABLED.with(|cell| cell.get())
+}
+
+/// The timings accumulated since profiling started, or since the last [`reset`].
+pub fn report() -> Report {
+    PROFILER.with(|profiler| {
+        let profiler = profiler.borrow();
+        let sorted = |timings: Vec<WidgetTiming>| {
+            let mut timings = timings;
+            timings.sort_by_key(|timing| std::cmp::Reverse(timing.total()));
+            timings
+        };
+        Report {
+            widget_types: sorted(profiler.widget_types.values().cloned().collect()),
+            named: sorted(profiler.named.values().cloned().collect()),
+        }
+    })
+}
+
+/// Discard the timings accumulated so far.
+pub fn reset() {
+    PROFILER.with(|profiler| {
+        let mut profiler = profiler.borrow_mut();
+        profiler.widget_types.clear();
+        profiler.named.clear();
+    })
+}
+
+/// Times a pass of a widget until dropped.
+pub(crate) struct WidgetTimer {
+    pass: Pass,
+    type_name: &'static str,
+    start: Instant,
+}
+
+/// Start timing `pass` of a widget of type `type_name`, if profiling is on.
+pub(crate) fn time_widget(pass: Pass, type_name: &'static str) -> Option<WidgetTimer> {
+    if !is_enabled() {
+        return None;
+    }
+    PROFILER.with(|profiler| profiler.borrow_mut().children_time.push(Duration::ZERO));
+    Some(WidgetTimer {
+        pass,
+        type_name,
+        start: Instant::now(),
+    })
+}
+
+impl Drop for WidgetTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        PROFILER.with(|profiler| {
+            let mut profiler = profiler.borrow_mut();
+            let children_time = profiler.children_time.pop().unwrap_or_default();
+            if let Some(parent_children_time) = profiler.children_time.last_mut() {
+                *parent_children_time += elapsed;
+            }
+            let name = self.type_name;
+            profiler
+                .widget_types
+                .entry(name)
+                .or_insert_with(|| WidgetTiming {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .record(self.pass, elapsed.saturating_sub(children_time));
+        })
+    }
+}
+
+/// Times a pass of a named widget, children included, until dropped.
+pub(crate) struct NamedTimer<'a> {
+    pass: Pass,
+    name: &'a ArcStr,
+    start: Instant,
+}
+
+/// Start timing `pass` of the widget named `name`, if profiling is on.
+pub(crate) fn time_named(pass: Pass, name: &ArcStr) -> Option<NamedTimer<'_>> {
+    is_enabled().then(|| NamedTimer {
+        pass,
+        name,
+        start: Instant::now(),
+    })
+}
+
+impl Drop for NamedTimer<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        PROFILER.with(|profiler| {
+            profiler
+                .borrow_mut()
+                .named
+                .entry(self.name.clone())
+                .or_insert_with(|| WidgetTiming {
+                    name: self.name.to_string(),
+                    ..Default::default()
+                })
+                .record(self.pass, elapsed);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn children_are_not_counted() {
+        set_enabled(true);
+        {
+            let _parent = time_widget(Pass::Layout, "Parent");
+            let _child = time_widget(Pass::Layout, "Child");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let report = report();
+        set_enabled(false);
+        reset();
+
+        let time = |name: &str| {
+            let timing = report.widget_types.iter().find(|t| t.name == name).unwrap();
+            assert_eq!(timing.pass(Pass::Layout).calls, 1);
+            timing.total()
+        };
+        assert!(time("Child") >= Duration::from_millis(20));
+        assert!(time("Parent") < Duration::from_millis(20));
+        assert_eq!(report.widget_types[0].name, "Child");
+    }
+}
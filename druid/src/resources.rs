@@ -0,0 +1,393 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracking and budgeting of memory held by widget caches.
+//!
+//! Widgets often cache expensive derived resources, such as the platform
+//! image created from an [`ImageBuf`] or a laid out block of text. In a
+//! long-running application with many such widgets this memory can grow
+//! without bound.
+//!
+//! A widget keeps a cache that can be rebuilt on demand in a
+//! [`ResourceCache`]. The cached value is held by the registry, and the
+//! widget only borrows it while using it. If a [`budget`] is set, the least
+//! recently used caches are dropped when the application is idle, until
+//! the total is back within budget; this frees their memory even if the
+//! widget that owns them is never painted again.
+//!
+//! Resources that can not be rebuilt on demand, such as text layouts, are
+//! registered with a [`ResourceHandle`]. They count towards the budget and
+//! show up in [`usage`], but are never evicted.
+//!
+//! [`ImageBuf`]: crate::ImageBuf
+//! [`budget`]: set_budget
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+use tracing::debug;
+
+/// Entries used more recently than this are never evicted, so that
+/// resources which are on screen are not rebuilt on every frame.
+const MIN_IDLE_AGE: Duration = Duration::from_secs(2);
+
+thread_local! {
+    static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+}
+
+/// The kind of resource a [`ResourceHandle`] or [`ResourceCache`] tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    /// A decoded image, or a platform image created from one.
+    Image,
+    /// A laid out block of text.
+    TextLayout,
+}
+
+/// A snapshot of the memory currently held by registered resources.
+///
+/// Sizes are in bytes, and are estimates provided by the owners of
+/// the resources.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// Memory held by images.
+    pub images: usize,
+    /// Memory held by text layouts.
+    pub text_layouts: usize,
+    /// The number of live (not evicted) entries.
+    pub entries: usize,
+    /// The number of entries evicted since the application started.
+    pub evictions: usize,
+    /// The current budget, if any.
+    pub budget: Option<usize>,
+}
+
+impl ResourceUsage {
+    /// The total memory held by all registered resources.
+    pub fn total(&self) -> usize {
+        self.images + self.text_layouts
+    }
+}
+
+/// A registration of a resource with the global budget.
+///
+/// The resource counts towards the budget, but is never evicted. The entry
+/// is removed when the handle is dropped.
+#[derive(Debug)]
+pub struct ResourceHandle {
+    id: u64,
+}
+
+/// A cached value that is dropped when it is evicted.
+///
+/// The value is held by the resource registry; [`get_or_insert_with`]
+/// returns a shared reference to it, which the owner should only keep for
+/// as long as it is using the value.
+///
+/// [`get_or_insert_with`]: ResourceCache::get_or_insert_with
+pub struct ResourceCache<V> {
+    kind: ResourceKind,
+    handle: Option<ResourceHandle>,
+    value: PhantomData<Rc<V>>,
+}
+
+struct Entry {
+    kind: ResourceKind,
+    bytes: usize,
+    last_used: Instant,
+    /// The cached value, for entries of a [`ResourceCache`].
+    value: Option<Rc<dyn Any>>,
+    evictable: bool,
+}
+
+#[derive(Default)]
+struct Registry {
+    entries: HashMap<u64, Entry>,
+    next_id: u64,
+    budget: Option<usize>,
+    evictions: usize,
+}
+
+impl ResourceHandle {
+    /// Register a new resource of `kind` holding approximately `bytes`.
+    pub fn new(kind: ResourceKind, bytes: usize) -> ResourceHandle {
+        ResourceHandle::register(kind, bytes, None)
+    }
+
+    fn register(kind: ResourceKind, bytes: usize, value: Option<Rc<dyn Any>>) -> ResourceHandle {
+        REGISTRY.with(|reg| {
+            let mut reg = reg.borrow_mut();
+            reg.next_id += 1;
+            let id = reg.next_id;
+            reg.entries.insert(
+                id,
+                Entry {
+                    kind,
+                    bytes,
+                    last_used: Instant::now(),
+                    evictable: value.is_some(),
+                    value,
+                },
+            );
+            ResourceHandle { id }
+        })
+    }
+
+    /// Mark the resource as used, moving it to the back of the eviction queue.
+    pub fn touch(&self) {
+        self.with_entry(|entry| entry.last_used = Instant::now());
+    }
+
+    /// Update the size of the resource.
+    pub fn set_size(&self, bytes: usize) {
+        self.with_entry(|entry| {
+            entry.bytes = bytes;
+            entry.last_used = Instant::now();
+        });
+    }
+
+    fn with_entry<R>(&self, f: impl FnOnce(&mut Entry) -> R) -> Option<R> {
+        REGISTRY.with(|reg| reg.borrow_mut().entries.get_mut(&self.id).map(f))
+    }
+}
+
+impl Clone for ResourceHandle {
+    fn clone(&self) -> Self {
+        REGISTRY
+            .with(|reg| {
+                let reg = reg.borrow();
+                reg.entries.get(&self.id).map(|e| (e.kind, e.bytes))
+            })
+            .map(|(kind, bytes)| ResourceHandle::new(kind, bytes))
+            .unwrap_or(ResourceHandle { id: 0 })
+    }
+}
+
+impl Drop for ResourceHandle {
+    fn drop(&mut self) {
+        // This can run during thread teardown, after the registry is gone.
+        let entry = REGISTRY.try_with(|reg| reg.borrow_mut().entries.remove(&self.id));
+        // The value is dropped here, outside of the borrow, in case it holds
+        // resources of its own.
+        drop(entry);
+    }
+}
+
+impl<V: 'static> ResourceCache<V> {
+    /// Create an empty cache for a resource of `kind`.
+    pub fn new(kind: ResourceKind) -> Self {
+        ResourceCache {
+            kind,
+            handle: None,
+            value: PhantomData,
+        }
+    }
+
+    /// Returns the cached value, marking it as used, or `None` if it was
+    /// never built, cleared, or evicted.
+    pub fn get(&self) -> Option<Rc<V>> {
+        let value = self.handle.as_ref()?.with_entry(|entry| {
+            entry.last_used = Instant::now();
+            entry.value.clone()
+        });
+        value.flatten()?.downcast().ok()
+    }
+
+    /// Returns the cached value, building it with `make` if needed.
+    ///
+    /// `bytes` is an estimate of the memory the value holds.
+    pub fn get_or_insert_with(&mut self, bytes: usize, make: impl FnOnce() -> V) -> Rc<V> {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        let value = Rc::new(make());
+        // Replacing the handle drops the entry of an evicted value.
+        self.handle = Some(ResourceHandle::register(
+            self.kind,
+            bytes,
+            Some(value.clone()),
+        ));
+        value
+    }
+
+    /// Drop the cached value, so that it is rebuilt on next use.
+    pub fn clear(&mut self) {
+        self.handle = None;
+    }
+}
+
+impl Registry {
+    fn usage(&self) -> ResourceUsage {
+        let mut usage = ResourceUsage {
+            evictions: self.evictions,
+            budget: self.budget,
+            ..Default::default()
+        };
+        for entry in self.entries.values().filter(|e| !e.is_evicted()) {
+            usage.entries += 1;
+            match entry.kind {
+                ResourceKind::Image => usage.images += entry.bytes,
+                ResourceKind::TextLayout => usage.text_layouts += entry.bytes,
+            }
+        }
+        usage
+    }
+
+    fn over_budget(&self) -> bool {
+        match self.budget {
+            Some(budget) => self.usage().total() > budget,
+            None => false,
+        }
+    }
+
+    /// Evict least recently used entries that are older than `cutoff`
+    /// until we are within budget. Returns the evicted values, which the
+    /// caller should drop once the registry is no longer borrowed.
+    fn collect(&mut self, cutoff: Instant) -> Vec<(usize, Rc<dyn Any>)> {
+        let budget = match self.budget {
+            Some(budget) => budget,
+            None => return Vec::new(),
+        };
+        let mut total = self.usage().total();
+        let mut candidates = self
+            .entries
+            .iter_mut()
+            .filter(|(_, e)| e.evictable && !e.is_evicted() && e.last_used <= cutoff)
+            .collect::<Vec<_>>();
+        // Ids are increasing, so they break ties in favour of newer entries.
+        candidates.sort_by_key(|(id, e)| (e.last_used, **id));
+
+        let mut evicted = Vec::new();
+        for (_, entry) in candidates {
+            if total <= budget {
+                break;
+            }
+            if let Some(value) = entry.value.take() {
+                total -= entry.bytes;
+                evicted.push((entry.bytes, value));
+            }
+        }
+        self.evictions += evicted.len();
+        evicted
+    }
+}
+
+impl Entry {
+    fn is_evicted(&self) -> bool {
+        self.evictable && self.value.is_none()
+    }
+}
+
+/// Set the total number of bytes registered resources should stay within.
+///
+/// Passing `None` (the default) disables eviction.
+pub fn set_budget(budget: impl Into<Option<usize>>) {
+    REGISTRY.with(|reg| reg.borrow_mut().budget = budget.into());
+}
+
+/// Returns the current resource usage, for diagnostics.
+pub fn usage() -> ResourceUsage {
+    REGISTRY.with(|reg| reg.borrow().usage())
+}
+
+/// Evict least recently used caches until the total is within budget.
+///
+/// Resources used within the last couple of seconds are never evicted.
+/// This is called automatically when the application is idle; it returns
+/// the number of bytes that were freed.
+pub fn collect_garbage() -> usize {
+    let cutoff = Instant::now()
+        .checked_sub(MIN_IDLE_AGE)
+        .unwrap_or_else(Instant::now);
+    let freed = collect(cutoff);
+    if freed > 0 {
+        debug!("Evicted {} bytes of cached resources", freed);
+    }
+    freed
+}
+
+/// Evict entries last used before `cutoff`, and drop their values.
+fn collect(cutoff: Instant) -> usize {
+    let evicted = REGISTRY.with(|reg| reg.borrow_mut().collect(cutoff));
+    evicted.into_iter().map(|(bytes, _)| bytes).sum()
+}
+
+/// Returns `true` if the registered resources exceed the budget.
+pub(crate) fn over_budget() -> bool {
+    REGISTRY.with(|reg| reg.borrow().over_budget())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    fn collect_all() -> usize {
+        collect(Instant::now())
+    }
+
+    #[test]
+    fn usage_by_kind() {
+        let mut image = ResourceCache::new(ResourceKind::Image);
+        image.get_or_insert_with(100, || ());
+        let _text = ResourceHandle::new(ResourceKind::TextLayout, 10);
+        let usage = usage();
+        assert_eq!(usage.images, 100);
+        assert_eq!(usage.text_layouts, 10);
+        assert_eq!(usage.entries, 2);
+        assert_eq!(usage.total(), 110);
+    }
+
+    #[test]
+    fn drop_unregisters() {
+        let text = ResourceHandle::new(ResourceKind::TextLayout, 100);
+        let copy = text.clone();
+        assert_eq!(usage().text_layouts, 200);
+        drop(text);
+        assert_eq!(usage().text_layouts, 100);
+        drop(copy);
+        assert_eq!(usage().entries, 0);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        set_budget(150);
+        let mut old = ResourceCache::new(ResourceKind::Image);
+        let old_value = Rc::downgrade(&old.get_or_insert_with(100, || 1));
+        let mut new = ResourceCache::new(ResourceKind::Image);
+        new.get_or_insert_with(100, || 2);
+        let _pinned = ResourceHandle::new(ResourceKind::TextLayout, 10);
+        new.get();
+        assert!(over_budget());
+
+        assert_eq!(collect_all(), 100);
+        // The evicted value is freed, even though its owner was not used.
+        assert!(old_value.upgrade().is_none());
+        assert!(old.get().is_none());
+        assert_eq!(new.get().as_deref(), Some(&2));
+        assert!(!over_budget());
+        assert_eq!(usage().evictions, 1);
+
+        assert_eq!(*old.get_or_insert_with(50, || 3), 3);
+        assert_eq!(usage().total(), 160);
+        set_budget(None);
+        assert_eq!(collect_all(), 0);
+    }
+}
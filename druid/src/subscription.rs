@@ -0,0 +1,97 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Watching the app data from outside the widget tree.
+
+use crate::{Data, DelegateCtx, Lens};
+
+/// Something that is told about changes to the app data after each update.
+pub(crate) trait Subscriber<T> {
+    /// Remember the initial data, without reporting a change.
+    fn init(&mut self, data: &T);
+
+    /// Report a change, if the watched part of `data` changed since the last check.
+    fn check(&mut self, data: &T, ctx: &mut DelegateCtx);
+}
+
+/// A [`Subscriber`] that watches the part of the data selected by a lens.
+pub(crate) struct LensSubscriber<L, U, F> {
+    lens: L,
+    last: Option<U>,
+    callback: F,
+}
+
+impl<L, U, F> LensSubscriber<L, U, F> {
+    pub(crate) fn new(lens: L, callback: F) -> Self {
+        LensSubscriber {
+            lens,
+            last: None,
+            callback,
+        }
+    }
+}
+
+impl<T, U, L, F> Subscriber<T> for LensSubscriber<L, U, F>
+where
+    U: Data,
+    L: Lens<T, U>,
+    F: FnMut(&U, &U, &mut DelegateCtx),
+{
+    fn init(&mut self, data: &T) {
+        self.last = Some(self.lens.with(data, U::clone));
+    }
+
+    fn check(&mut self, data: &T, ctx: &mut DelegateCtx) {
+        let changed = self.lens.with(data, |new| match &self.last {
+            Some(last) if last.same(new) => None,
+            _ => Some(new.clone()),
+        });
+        if let Some(new) = changed {
+            if let Some(old) = self.last.replace(new.clone()) {
+                (self.callback)(&old, &new, ctx);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::ext_event::ExtEventHost;
+
+    #[test]
+    fn reports_changes_of_lensed_data() {
+        let mut changes = Vec::new();
+        let mut subscriber = LensSubscriber::new(
+            lens!((u32, u32), 0),
+            |old: &u32, new: &u32, _: &mut DelegateCtx| changes.push((*old, *new)),
+        );
+        let mut command_queue = VecDeque::new();
+        let ext_event_host = ExtEventHost::new();
+        let mut ctx = DelegateCtx {
+            command_queue: &mut command_queue,
+            ext_event_host: &ext_event_host,
+            app_data_type: TypeId::of::<(u32, u32)>(),
+        };
+
+        subscriber.init(&(1, 1));
+        subscriber.check(&(1, 2), &mut ctx);
+        subscriber.check(&(3, 2), &mut ctx);
+        subscriber.check(&(3, 2), &mut ctx);
+        assert_eq!(changes, [(1, 3)]);
+    }
+}
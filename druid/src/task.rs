@@ -0,0 +1,516 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Running work off the UI thread.
+//!
+//! A [`TaskPool`] runs blocking closures and futures on a small set of
+//! worker threads, and delivers their results back to the application as
+//! [`Command`]s or as updates to the root [`Data`]. This replaces spawning a
+//! thread by hand and holding on to an [`ExtEventSink`].
+//!
+//! A [`Feed`] connects a channel, or a `futures` `Stream` with the `futures`
+//! feature, to a field of the application data, for things like log tails and
+//! progress feeds.
+//!
+//! [`Command`]: crate::Command
+
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+use tracing::warn;
+
+use crate::{Data, ExtEventSink, Lens, Selector, Target};
+
+type Job = Box<dyn FnOnce() + Send>;
+type Apply<T, I> = Arc<dyn Fn(&mut T, Vec<I>) + Send + Sync>;
+
+/// Identifies a task spawned on a [`TaskPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+/// The progress of a running task, as reported with [`TaskCtx::report_progress`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaskProgress {
+    /// The task that reported progress.
+    pub task: TaskId,
+    /// How far along the task is, between `0.0` and `1.0`.
+    pub fraction: f64,
+}
+
+/// A pool of worker threads that runs tasks for the application.
+///
+/// The pool is cheap to clone; clones share the same workers. Create one with
+/// an [`ExtEventSink`], for instance from [`EventCtx::get_external_handle`]
+/// or [`AppLauncher::get_external_handle`].
+///
+/// [`EventCtx::get_external_handle`]: crate::EventCtx::get_external_handle
+/// [`AppLauncher::get_external_handle`]: crate::AppLauncher::get_external_handle
+#[derive(Clone)]
+pub struct TaskPool {
+    sink: ExtEventSink,
+    jobs: Sender<Job>,
+    next_id: Arc<AtomicU64>,
+}
+
+/// Passed to a running task, to report progress and check for cancellation.
+#[derive(Clone)]
+pub struct TaskCtx {
+    id: TaskId,
+    sink: ExtEventSink,
+    target: Target,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// A handle to a spawned task, which can be used to cancel it.
+///
+/// Dropping the handle does not cancel the task.
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    id: TaskId,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Delivers the items of a channel or stream to a field of the app data.
+///
+/// Created with [`TaskPool::feed`], and started with [`connect_receiver`] or
+/// [`connect_stream`]. Feeds run on their own thread rather than on the
+/// pool's workers, since they usually last as long as the application.
+///
+/// [`connect_receiver`]: Feed::connect_receiver
+/// [`connect_stream`]: Feed::connect_stream
+pub struct Feed<T, I> {
+    sink: ExtEventSink,
+    id: TaskId,
+    cancelled: Arc<AtomicBool>,
+    apply: Apply<T, I>,
+    coalesce: bool,
+}
+
+impl TaskPool {
+    /// Sent to a task's target when it calls [`TaskCtx::report_progress`].
+    pub const PROGRESS: Selector<TaskProgress> = Selector::new("druid-builtin.task-progress");
+
+    /// Create a pool with one worker per available CPU.
+    pub fn new(sink: ExtEventSink) -> Self {
+        let threads = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4);
+        TaskPool::with_threads(sink, threads)
+    }
+
+    /// Create a pool with `threads` workers.
+    ///
+    /// The workers exit once every clone of the pool has been dropped and the
+    /// queued tasks have finished.
+    pub fn with_threads(sink: ExtEventSink, threads: usize) -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        for i in 0..threads.max(1) {
+            let queue = queue.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("druid-task-{i}"))
+                .spawn(move || worker(&queue));
+            if let Err(e) = spawned {
+                warn!("failed to spawn task worker: {}", e);
+            }
+        }
+        TaskPool {
+            sink,
+            jobs,
+            next_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Run `work` on a worker thread, and submit its result as `selector` to `target`.
+    ///
+    /// Nothing is submitted if the task was cancelled before it finished.
+    pub fn spawn_blocking<R: Any + Send>(
+        &self,
+        selector: Selector<R>,
+        target: impl Into<Target>,
+        work: impl FnOnce(&TaskCtx) -> R + Send + 'static,
+    ) -> TaskHandle {
+        let (ctx, handle) = self.task(target.into());
+        self.run(move || {
+            let result = work(&ctx);
+            ctx.deliver(selector, result);
+        });
+        handle
+    }
+
+    /// Run a future to completion on a worker thread, and submit its output
+    /// as `selector` to `target`.
+    ///
+    /// `make_future` is called with the task's [`TaskCtx`]. The future is
+    /// polled on a single worker, which is blocked while the future is
+    /// pending; if the task is cancelled the future is dropped the next time
+    /// it wakes.
+    pub fn spawn_async<R, F>(
+        &self,
+        selector: Selector<R>,
+        target: impl Into<Target>,
+        make_future: impl FnOnce(TaskCtx) -> F + Send + 'static,
+    ) -> TaskHandle
+    where
+        R: Any + Send,
+        F: Future<Output = R> + 'static,
+    {
+        let (ctx, handle) = self.task(target.into());
+        self.run(move || {
+            let future = make_future(ctx.clone());
+            if let Some(result) = block_on(future, &ctx.cancelled) {
+                ctx.deliver(selector, result);
+            }
+        });
+        handle
+    }
+
+    /// Run `work` on a worker thread, then call `apply` with its result and
+    /// the root data on the UI thread.
+    ///
+    /// `T` must be the application's root `Data` type. Progress reports are
+    /// sent to [`Target::Global`].
+    pub fn spawn_update<T: Data, R: Send + 'static>(
+        &self,
+        work: impl FnOnce(&TaskCtx) -> R + Send + 'static,
+        apply: impl FnOnce(&mut T, R) + Send + 'static,
+    ) -> TaskHandle {
+        let (ctx, handle) = self.task(Target::Global);
+        self.run(move || {
+            let result = work(&ctx);
+            if !ctx.is_cancelled() {
+                let cancelled = ctx.cancelled.clone();
+                ctx.sink.add_idle_callback(move |data: &mut T| {
+                    if !cancelled.load(Ordering::Acquire) {
+                        apply(data, result);
+                    }
+                });
+            }
+        });
+        handle
+    }
+
+    /// Create a [`Feed`] that calls `apply` with the field selected by `lens`
+    /// for each item it receives.
+    ///
+    /// `T` must be the application's root `Data` type.
+    ///
+    /// ```no_run
+    /// # use druid::{Data, Lens, task::TaskPool};
+    /// # use std::sync::{mpsc, Arc};
+    /// #[derive(Clone, Data, Lens)]
+    /// struct AppState {
+    ///     log: Arc<String>,
+    /// }
+    ///
+    /// # fn connect(pool: &TaskPool) {
+    /// let (tx, rx) = mpsc::channel::<String>();
+    /// pool.feed(AppState::log, |log: &mut Arc<String>, line: String| {
+    ///     Arc::make_mut(log).push_str(&line);
+    /// })
+    /// .with_coalescing(true)
+    /// .connect_receiver(rx);
+    /// # }
+    /// ```
+    pub fn feed<T, U, I>(
+        &self,
+        lens: impl Lens<T, U> + Send + Sync + 'static,
+        apply: impl Fn(&mut U, I) + Send + Sync + 'static,
+    ) -> Feed<T, I>
+    where
+        T: Data,
+        U: 'static,
+    {
+        let (_, handle) = self.task(Target::Global);
+        let apply = move |data: &mut T, items: Vec<I>| {
+            lens.with_mut(data, |field| {
+                for item in items {
+                    apply(field, item);
+                }
+            })
+        };
+        Feed {
+            sink: self.sink.clone(),
+            id: handle.id,
+            cancelled: handle.cancelled,
+            apply: Arc::new(apply),
+            coalesce: false,
+        }
+    }
+
+    fn task(&self, target: Target) -> (TaskCtx, TaskHandle) {
+        let id = TaskId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let ctx = TaskCtx {
+            id,
+            sink: self.sink.clone(),
+            target,
+            cancelled: cancelled.clone(),
+        };
+        (ctx, TaskHandle { id, cancelled })
+    }
+
+    fn run(&self, job: impl FnOnce() + Send + 'static) {
+        if self.jobs.send(Box::new(job)).is_err() {
+            warn!("task pool has no workers, task dropped");
+        }
+    }
+}
+
+impl TaskCtx {
+    /// The id of this task.
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Whether the task has been cancelled.
+    ///
+    /// Long running tasks should check this periodically and return early.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Send [`TaskPool::PROGRESS`] to the task's target.
+    ///
+    /// `fraction` is clamped to the range `0.0..=1.0`.
+    pub fn report_progress(&self, fraction: f64) {
+        let progress = TaskProgress {
+            task: self.id,
+            fraction: fraction.clamp(0.0, 1.0),
+        };
+        if self
+            .sink
+            .submit_command(TaskPool::PROGRESS, progress, self.target)
+            .is_err()
+        {
+            warn!("failed to report task progress");
+        }
+    }
+
+    fn deliver<R: Any + Send>(&self, selector: Selector<R>, result: R) {
+        if self.is_cancelled() {
+            return;
+        }
+        if self
+            .sink
+            .submit_command(selector, Box::new(result), self.target)
+            .is_err()
+        {
+            warn!("failed to deliver task result");
+        }
+    }
+}
+
+impl TaskHandle {
+    /// The id of the task.
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Cancel the task.
+    ///
+    /// A cancelled task's result is never delivered. Blocking tasks keep
+    /// running until they check [`TaskCtx::is_cancelled`].
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Whether [`cancel`] has been called.
+    ///
+    /// [`cancel`]: TaskHandle::cancel
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+impl<T: Data, I: Send + 'static> Feed<T, I> {
+    /// Builder-style method to set whether bursts of items are coalesced.
+    ///
+    /// When coalescing, items that arrive while an update is already pending
+    /// are applied together in that update, so the data changes at most once
+    /// per pass of the event loop. Otherwise every item is a separate update.
+    pub fn with_coalescing(mut self, coalesce: bool) -> Self {
+        self.coalesce = coalesce;
+        self
+    }
+
+    /// Start delivering items from `receiver`, until it disconnects or the
+    /// returned handle is cancelled.
+    pub fn connect_receiver(self, receiver: Receiver<I>) -> TaskHandle {
+        self.spawn(move |feed| {
+            for item in receiver {
+                if feed.cancelled.load(Ordering::Acquire) {
+                    break;
+                }
+                feed.deliver(item);
+            }
+        })
+    }
+
+    /// Start delivering items from `stream`, until it ends or the returned
+    /// handle is cancelled.
+    ///
+    /// The stream is polled on the feed's own thread.
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    pub fn connect_stream<S>(self, stream: S) -> TaskHandle
+    where
+        S: Stream<Item = I> + Send + 'static,
+    {
+        self.spawn(move |feed| {
+            let mut stream = Box::pin(stream);
+            loop {
+                let next = std::future::poll_fn(|cx| stream.as_mut().poll_next(cx));
+                let Some(Some(item)) = block_on(next, &feed.cancelled) else {
+                    break;
+                };
+                feed.deliver(item);
+            }
+        })
+    }
+
+    fn spawn(self, run: impl FnOnce(&FeedSender<T, I>) + Send + 'static) -> TaskHandle {
+        let handle = TaskHandle {
+            id: self.id,
+            cancelled: self.cancelled.clone(),
+        };
+        let sender = FeedSender {
+            sink: self.sink,
+            cancelled: self.cancelled,
+            apply: self.apply,
+            pending: self.coalesce.then(|| Arc::new(Mutex::new(Vec::new()))),
+        };
+        let spawned = thread::Builder::new()
+            .name(format!("druid-feed-{}", self.id.0))
+            .spawn(move || run(&sender));
+        if let Err(e) = spawned {
+            warn!("failed to spawn feed thread: {}", e);
+        }
+        handle
+    }
+}
+
+/// The part of a [`Feed`] that lives on its thread.
+struct FeedSender<T, I> {
+    sink: ExtEventSink,
+    cancelled: Arc<AtomicBool>,
+    apply: Apply<T, I>,
+    /// Items waiting for an update that has already been scheduled, when coalescing.
+    pending: Option<Arc<Mutex<Vec<I>>>>,
+}
+
+impl<T: Data, I: Send + 'static> FeedSender<T, I> {
+    fn deliver(&self, item: I) {
+        let apply = self.apply.clone();
+        let cancelled = self.cancelled.clone();
+        let Some(pending) = &self.pending else {
+            self.sink.add_idle_callback(move |data: &mut T| {
+                if !cancelled.load(Ordering::Acquire) {
+                    apply(data, vec![item]);
+                }
+            });
+            return;
+        };
+        let schedule = {
+            let mut pending = pending.lock().unwrap();
+            pending.push(item);
+            pending.len() == 1
+        };
+        if schedule {
+            let pending = pending.clone();
+            self.sink.add_idle_callback(move |data: &mut T| {
+                let items = std::mem::take(&mut *pending.lock().unwrap());
+                if !cancelled.load(Ordering::Acquire) && !items.is_empty() {
+                    apply(data, items);
+                }
+            });
+        }
+    }
+}
+
+fn worker(queue: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = match queue.lock() {
+            Ok(queue) => queue.recv(),
+            Err(_) => return,
+        };
+        match job {
+            Ok(job) => job(),
+            Err(_) => return,
+        }
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Poll `future` on this thread until it completes or `cancelled` is set.
+fn block_on<F: Future>(future: F, cancelled: &AtomicBool) -> Option<F::Output> {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if cancelled.load(Ordering::Acquire) {
+            return None;
+        }
+        if let Poll::Ready(output) = Pin::as_mut(&mut future).poll(&mut cx) {
+            return Some(output);
+        }
+        thread::park();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_on_wakes_from_other_thread() {
+        struct Flag(Arc<AtomicBool>);
+        impl Future for Flag {
+            type Output = u32;
+            fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<u32> {
+                if self.0.load(Ordering::Acquire) {
+                    return Poll::Ready(7);
+                }
+                let flag = self.0.clone();
+                let waker = cx.waker().clone();
+                thread::spawn(move || {
+                    flag.store(true, Ordering::Release);
+                    waker.wake();
+                });
+                Poll::Pending
+            }
+        }
+        let cancelled = AtomicBool::new(false);
+        let flag = Flag(Arc::new(AtomicBool::new(false)));
+        assert_eq!(block_on(flag, &cancelled), Some(7));
+
+        cancelled.store(true, Ordering::Release);
+        assert_eq!(block_on(async { 1 }, &cancelled), None);
+    }
+}
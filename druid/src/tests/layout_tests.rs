@@ -273,3 +273,136 @@ fn aspect_ratio() {
         assert_eq!(state.layout_rect().size(), Size::new(1000., 500.));
     });
 }
+
+#[test]
+fn flex_gap() {
+    let [flex_id, id1, id2, id3] = widget_ids();
+    let widget = Flex::row()
+        .with_gap(10.0)
+        .with_child(SizedBox::empty().width(20.0).with_id(id1))
+        .with_child(SizedBox::empty().width(20.0).with_id(id2))
+        .with_spacer(5.0)
+        .with_child(SizedBox::empty().width(20.0).with_id(id3))
+        .with_id(flex_id);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(id1).layout_rect().x0, 0.0);
+        assert_eq!(harness.get_state(id2).layout_rect().x0, 30.0);
+        // The spacer adds to the gap.
+        assert_eq!(harness.get_state(id3).layout_rect().x0, 65.0);
+        assert_eq!(harness.get_state(flex_id).layout_rect().width(), 85.0);
+    })
+}
+
+#[test]
+fn grid_auto_column_intrinsic_width() {
+    let [flex_id, other_id] = widget_ids();
+    // Laid out with loose constraints the flex row would take all the available
+    // width; its intrinsic width is only that of its child.
+    let widget = Grid::new()
+        .with_column(GridTrack::Auto)
+        .with_column(GridTrack::Auto)
+        .with_child(
+            Flex::row()
+                .with_flex_child(SizedBox::empty().width(30.0), 1.0)
+                .with_id(flex_id),
+            0,
+            0,
+        )
+        .with_child(SizedBox::empty().width(20.0).with_id(other_id), 0, 1);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.set_initial_size(Size::new(400., 400.));
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.get_state(flex_id).layout_rect().width(), 30.0);
+        assert_eq!(harness.get_state(other_id).layout_rect().x0, 30.0);
+    })
+}
+
+#[test]
+fn align_offset_out_of_bounds() {
+    let [child_id] = widget_ids();
+    let overlay = Align::new(
+        UnitPoint::TOP_LEFT,
+        SizedBox::empty().width(20.).height(20.).with_id(child_id),
+    )
+    .with_fractional_offset((-0.5, -0.5));
+    let widget = ZStack::new(SizedBox::empty())
+        .with_centered_child(overlay)
+        .padding(50.);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.set_initial_size(Size::new(400., 400.));
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(
+            harness.get_state(child_id).layout_rect(),
+            Rect::new(-10., -10., 10., 10.)
+        );
+
+        // The part of the child outside of the stack still receives mouse events.
+        harness.event(Event::MouseMove(move_mouse((45., 45.))));
+        assert!(harness.get_state(child_id).is_hot);
+        harness.event(Event::MouseMove(move_mouse((35., 35.))));
+        assert!(!harness.get_state(child_id).is_hot);
+    })
+}
+
+#[test]
+fn aspect_ratio_fit() {
+    let [letterbox_id, letterboxed_id, crop_id, cropped_id] = widget_ids();
+    let letterbox = AspectRatioBox::<()>::new(Label::new("hello!").with_id(letterboxed_id), 2.0)
+        .with_fit(AspectRatioFit::Letterbox)
+        .with_id(letterbox_id)
+        .fix_size(400., 400.);
+    let crop = AspectRatioBox::<()>::new(Label::new("hello!").with_id(cropped_id), 2.0)
+        .with_fit(AspectRatioFit::Crop)
+        .with_id(crop_id)
+        .fix_size(400., 400.);
+    let widget = Flex::row().with_child(letterbox).with_child(crop);
+
+    Harness::create_simple((), widget, |harness| {
+        harness.set_initial_size(Size::new(1000., 1000.));
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(
+            harness.get_state(letterbox_id).layout_rect().size(),
+            Size::new(400., 400.)
+        );
+        assert_eq!(
+            harness.get_state(letterboxed_id).layout_rect(),
+            Rect::new(0., 100., 400., 300.)
+        );
+        assert_eq!(
+            harness.get_state(crop_id).layout_rect().size(),
+            Size::new(400., 400.)
+        );
+        assert_eq!(
+            harness.get_state(cropped_id).layout_rect(),
+            Rect::new(-200., 0., 600., 400.)
+        );
+    });
+}
+
+#[test]
+fn rotated_label_swaps_axes() {
+    let [plain_id, rotated_id] = widget_ids();
+    let widget = Flex::row()
+        .with_child(Label::new("hello!").with_id(plain_id))
+        .with_child(
+            Label::new("hello!")
+                .with_rotation(TextRotation::Clockwise)
+                .with_id(rotated_id),
+        );
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let plain = harness.get_state(plain_id).layout_rect().size();
+        let rotated = harness.get_state(rotated_id).layout_rect().size();
+        assert_eq!(rotated, Size::new(plain.height, plain.width));
+    });
+}
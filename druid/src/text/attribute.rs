@@ -16,8 +16,8 @@
 
 use std::ops::Range;
 
-use crate::piet::{Color, FontFamily, FontStyle, FontWeight, TextAttribute as PietAttr};
-use crate::{Command, Env, FontDescriptor, KeyOrValue};
+use crate::piet::{Color, FontFamily, FontStyle, FontWeight, ImageBuf, TextAttribute as PietAttr};
+use crate::{ArcStr, Command, Env, FontDescriptor, KeyOrValue, Size};
 
 use super::EnvUpdateCtx;
 
@@ -30,6 +30,37 @@ pub struct Link {
     pub command: Command,
 }
 
+/// An image or widget embedded in the flow of text.
+///
+/// The attachment stands in place of a range of the text, which is laid out
+/// to be as wide as the attachment and about as tall; the line grows to fit
+/// it, and it wraps like a word. Attachments are added with
+/// [`RichTextBuilder::push_image`] and [`RichTextBuilder::push_widget`].
+///
+/// [`RichTextBuilder::push_image`]: super::RichTextBuilder::push_image
+/// [`RichTextBuilder::push_widget`]: super::RichTextBuilder::push_widget
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    /// The range of text the attachment stands in place of.
+    pub range: Range<usize>,
+    /// The size of the attachment. Its bottom edge sits on the baseline.
+    pub size: Size,
+    /// What is drawn in the attachment.
+    pub content: AttachmentContent,
+}
+
+/// What an [`Attachment`] shows.
+#[derive(Debug, Clone)]
+pub enum AttachmentContent {
+    /// An image, scaled to the size of the attachment.
+    Image(ImageBuf),
+    /// The inline widget with this key, added to the label showing the text
+    /// with [`RawLabel::with_inline_widget`].
+    ///
+    /// [`RawLabel::with_inline_widget`]: crate::widget::RawLabel::with_inline_widget
+    Widget(ArcStr),
+}
+
 /// A collection of spans of attributes of various kinds.
 #[derive(Debug, Clone, Default)]
 pub struct AttributeSpans {
@@ -114,6 +145,22 @@ impl Link {
     }
 }
 
+impl Attachment {
+    /// Create a new `Attachment`.
+    pub fn new(range: Range<usize>, size: Size, content: AttachmentContent) -> Self {
+        Self {
+            range,
+            size,
+            content,
+        }
+    }
+
+    /// Get this `Attachment`'s range.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
 impl AttributeSpans {
     /// Create a new, empty `AttributeSpans`.
     pub fn new() -> Self {
@@ -134,6 +181,63 @@ impl AttributeSpans {
         }
     }
 
+    /// Update the spans for an edit of the text, where `changed` was replaced by
+    /// `new_len` bytes of unstyled text.
+    pub(crate) fn edit(&mut self, changed: Range<usize>, new_len: usize) {
+        self.family.edit(changed.clone(), new_len);
+        self.size.edit(changed.clone(), new_len);
+        self.weight.edit(changed.clone(), new_len);
+        self.fg_color.edit(changed.clone(), new_len);
+        self.style.edit(changed.clone(), new_len);
+        self.underline.edit(changed.clone(), new_len);
+        self.strikethrough.edit(changed.clone(), new_len);
+        self.font_descriptor.edit(changed, new_len);
+    }
+
+    /// Remove the attributes of the same kind as `kind` from `range`; the value of
+    /// `kind` is ignored.
+    pub(crate) fn remove(&mut self, range: Range<usize>, kind: &Attribute) {
+        match kind {
+            Attribute::FontFamily(_) => self.family.remove(range),
+            Attribute::FontSize(_) => self.size.remove(range),
+            Attribute::Weight(_) => self.weight.remove(range),
+            Attribute::TextColor(_) => self.fg_color.remove(range),
+            Attribute::Style(_) => self.style.remove(range),
+            Attribute::Underline(_) => self.underline.remove(range),
+            Attribute::Strikethrough(_) => self.strikethrough.remove(range),
+            Attribute::Descriptor(_) => self.font_descriptor.remove(range),
+        }
+    }
+
+    /// The attributes that apply at `offset`.
+    pub(crate) fn attributes_at(&self, offset: usize) -> Vec<Attribute> {
+        let mut attrs = Vec::new();
+        attrs.extend(self.family.get(offset).cloned().map(Attribute::FontFamily));
+        attrs.extend(self.size.get(offset).cloned().map(Attribute::FontSize));
+        attrs.extend(self.weight.get(offset).cloned().map(Attribute::Weight));
+        attrs.extend(self.fg_color.get(offset).cloned().map(Attribute::TextColor));
+        attrs.extend(self.style.get(offset).cloned().map(Attribute::Style));
+        attrs.extend(
+            self.underline
+                .get(offset)
+                .cloned()
+                .map(Attribute::Underline),
+        );
+        attrs.extend(
+            self.strikethrough
+                .get(offset)
+                .cloned()
+                .map(Attribute::Strikethrough),
+        );
+        attrs.extend(
+            self.font_descriptor
+                .get(offset)
+                .cloned()
+                .map(Attribute::Descriptor),
+        );
+        attrs
+    }
+
     pub(crate) fn to_piet_attrs(&self, env: &Env) -> Vec<(Range<usize>, PietAttr)> {
         let mut items = Vec::new();
         for Span { range, attr } in self.font_descriptor.iter() {
@@ -206,6 +310,32 @@ impl<T: Clone> SpanSet<T> {
         self.spans.iter()
     }
 
+    /// The attribute of the span containing `offset`, if any.
+    fn get(&self, offset: usize) -> Option<&T> {
+        self.spans
+            .iter()
+            .find(|span| span.range.contains(&offset))
+            .map(|span| &span.attr)
+    }
+
+    /// Remove `range` from the spans, splitting spans that extend past it.
+    fn remove(&mut self, range: Range<usize>) {
+        let mut spans = Vec::with_capacity(self.spans.len() + 1);
+        for span in self.spans.drain(..) {
+            if span.range.end <= range.start || span.range.start >= range.end {
+                spans.push(span);
+                continue;
+            }
+            if span.range.start < range.start {
+                spans.push(Span::new(span.range.start..range.start, span.attr.clone()));
+            }
+            if span.range.end > range.end {
+                spans.push(Span::new(range.end..span.range.end, span.attr));
+            }
+        }
+        self.spans = spans;
+    }
+
     /// Add a `Span` to this `SpanSet`.
     ///
     /// Spans can be added in any order. existing spans will be updated
@@ -259,10 +389,8 @@ impl<T: Clone> SpanSet<T> {
     /// `new_len` is the length of the inserted text.
     //TODO: we could be smarter here about just extending the existing spans
     //as required for insertions in the interior of a span.
-    //TODO: this isn't currently used; it should be used if we use spans with
-    //some editable type.
     // the branches are much more readable without sharing code
-    #[allow(dead_code, clippy::branches_sharing_code)]
+    #[allow(clippy::branches_sharing_code)]
     fn edit(&mut self, changed: Range<usize>, new_len: usize) {
         let old_len = changed.len();
         let mut to_insert = None;
@@ -389,6 +517,23 @@ mod tests {
         assert_eq!(&spans.spans, &vec![Span::new(0..5, 3), Span::new(5..20, 4)]);
     }
 
+    #[test]
+    fn remove_spans() {
+        let mut spans = SpanSet::<u32>::default();
+        spans.add(Span::new(0..4, 1));
+        spans.add(Span::new(6..12, 2));
+        spans.remove(2..8);
+        assert_eq!(&spans.spans, &vec![Span::new(0..2, 1), Span::new(8..12, 2)]);
+
+        spans.remove(9..10);
+        assert_eq!(
+            &spans.spans,
+            &vec![Span::new(0..2, 1), Span::new(8..9, 2), Span::new(10..12, 2)]
+        );
+        assert_eq!(spans.get(8), Some(&2));
+        assert_eq!(spans.get(9), None);
+    }
+
     #[test]
     fn edit_spans() {
         let mut spans = SpanSet::<u32>::default();
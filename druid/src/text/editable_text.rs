@@ -99,13 +99,11 @@ impl EditableText for String {
     }
 
     fn prev_grapheme_offset(&self, from: usize) -> Option<usize> {
-        let mut c = GraphemeCursor::new(from, self.len(), true);
-        c.prev_boundary(self, 0).unwrap()
+        prev_grapheme_offset(self, from)
     }
 
     fn next_grapheme_offset(&self, from: usize) -> Option<usize> {
-        let mut c = GraphemeCursor::new(from, self.len(), true);
-        c.next_boundary(self, 0).unwrap()
+        next_grapheme_offset(self, from)
     }
 
     fn prev_codepoint_offset(&self, from: usize) -> Option<usize> {
@@ -123,33 +121,11 @@ impl EditableText for String {
     }
 
     fn prev_word_offset(&self, from: usize) -> Option<usize> {
-        let mut offset = from;
-        let mut passed_alphanumeric = false;
-        for prev_grapheme in self.get(0..from)?.graphemes(true).rev() {
-            let is_alphanumeric = prev_grapheme.chars().next()?.is_alphanumeric();
-            if is_alphanumeric {
-                passed_alphanumeric = true;
-            } else if passed_alphanumeric {
-                return Some(offset);
-            }
-            offset -= prev_grapheme.len();
-        }
-        None
+        prev_word_offset(self, from)
     }
 
     fn next_word_offset(&self, from: usize) -> Option<usize> {
-        let mut offset = from;
-        let mut passed_alphanumeric = false;
-        for next_grapheme in self.get(from..)?.graphemes(true) {
-            let is_alphanumeric = next_grapheme.chars().next()?.is_alphanumeric();
-            if is_alphanumeric {
-                passed_alphanumeric = true;
-            } else if passed_alphanumeric {
-                return Some(offset);
-            }
-            offset += next_grapheme.len();
-        }
-        Some(self.len())
+        next_word_offset(self, from)
     }
 
     fn is_empty(&self) -> bool {
@@ -161,30 +137,78 @@ impl EditableText for String {
     }
 
     fn preceding_line_break(&self, from: usize) -> usize {
-        let mut offset = from;
+        preceding_line_break(self, from)
+    }
 
-        for byte in self.get(0..from).unwrap_or("").bytes().rev() {
-            if byte == 0x0a {
-                return offset;
-            }
-            offset -= 1;
+    fn next_line_break(&self, from: usize) -> usize {
+        next_line_break(self, from)
+    }
+}
+
+// The navigation methods only need to read the text, so they are shared with
+// read-only text and other storage types through these functions.
+
+pub(crate) fn prev_grapheme_offset(text: &str, from: usize) -> Option<usize> {
+    let mut c = GraphemeCursor::new(from, text.len(), true);
+    c.prev_boundary(text, 0).unwrap()
+}
+
+pub(crate) fn next_grapheme_offset(text: &str, from: usize) -> Option<usize> {
+    let mut c = GraphemeCursor::new(from, text.len(), true);
+    c.next_boundary(text, 0).unwrap()
+}
+
+pub(crate) fn prev_word_offset(text: &str, from: usize) -> Option<usize> {
+    let mut offset = from;
+    let mut passed_alphanumeric = false;
+    for prev_grapheme in text.get(0..from)?.graphemes(true).rev() {
+        let is_alphanumeric = prev_grapheme.chars().next()?.is_alphanumeric();
+        if is_alphanumeric {
+            passed_alphanumeric = true;
+        } else if passed_alphanumeric {
+            return Some(offset);
         }
+        offset -= prev_grapheme.len();
+    }
+    None
+}
 
-        0
+pub(crate) fn next_word_offset(text: &str, from: usize) -> Option<usize> {
+    let mut offset = from;
+    let mut passed_alphanumeric = false;
+    for next_grapheme in text.get(from..)?.graphemes(true) {
+        let is_alphanumeric = next_grapheme.chars().next()?.is_alphanumeric();
+        if is_alphanumeric {
+            passed_alphanumeric = true;
+        } else if passed_alphanumeric {
+            return Some(offset);
+        }
+        offset += next_grapheme.len();
     }
+    Some(text.len())
+}
 
-    fn next_line_break(&self, from: usize) -> usize {
-        let mut offset = from;
+pub(crate) fn preceding_line_break(text: &str, from: usize) -> usize {
+    let mut offset = from;
 
-        for char in self.get(from..).unwrap_or("").bytes() {
-            if char == 0x0a {
-                return offset;
-            }
-            offset += 1;
+    for byte in text.get(0..from).unwrap_or("").bytes().rev() {
+        if byte == 0x0a {
+            return offset;
         }
+        offset -= 1;
+    }
 
-        self.len()
+    0
+}
+
+pub(crate) fn next_line_break(text: &str, from: usize) -> usize {
+    for (index, char) in text.get(from..).unwrap_or("").bytes().enumerate() {
+        if char == 0x0a {
+            return from + index;
+        }
     }
+
+    text.len()
 }
 
 impl EditableText for Arc<String> {
@@ -0,0 +1,130 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Restricting the text that can be entered.
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// Restricts the text that can be entered in a [`TextBox`].
+///
+/// Unlike a [`Formatter`], a filter does not change the type of the data
+/// being edited; it only rejects input. Characters that are not allowed are
+/// dropped, and text that would exceed the maximum length is truncated.
+///
+/// # Examples
+///
+/// ```
+/// # use druid::text::InputFilter;
+/// # use druid::widget::TextBox;
+/// // A five digit postal code.
+/// let text_box = TextBox::<String>::new()
+///     .with_input_filter(InputFilter::digits().max_length(5));
+/// ```
+///
+/// [`TextBox`]: crate::widget::TextBox
+/// [`Formatter`]: super::Formatter
+#[derive(Clone, Default)]
+pub struct InputFilter {
+    allowed: Option<Rc<dyn Fn(char) -> bool>>,
+    max_length: Option<usize>,
+}
+
+impl InputFilter {
+    /// Create a filter that accepts everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a filter that only accepts the ASCII digits `0` to `9`.
+    pub fn digits() -> Self {
+        Self::new().allow(|c| c.is_ascii_digit())
+    }
+
+    /// Builder-style method to only accept characters for which `predicate`
+    /// returns `true`.
+    pub fn allow(mut self, predicate: impl Fn(char) -> bool + 'static) -> Self {
+        self.allowed = Some(Rc::new(predicate));
+        self
+    }
+
+    /// Builder-style method to limit the text to `max_length` characters.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Returns the part of `text` that may replace `range` of `current`.
+    pub fn filter<'a>(&self, current: &str, range: Range<usize>, text: &'a str) -> Cow<'a, str> {
+        if self.is_unrestricted() {
+            return Cow::Borrowed(text);
+        }
+        let remaining = match self.max_length {
+            Some(max) => {
+                let kept = current.chars().count() - current[range].chars().count();
+                max.saturating_sub(kept)
+            }
+            None => usize::MAX,
+        };
+        let filtered: String = text
+            .chars()
+            .filter(|&c| self.allows(c))
+            .take(remaining)
+            .collect();
+        if filtered == text {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(filtered)
+        }
+    }
+
+    /// Returns `true` if `c` can be entered.
+    pub fn allows(&self, c: char) -> bool {
+        match &self.allowed {
+            Some(predicate) => predicate(c),
+            None => true,
+        }
+    }
+
+    pub(crate) fn is_unrestricted(&self) -> bool {
+        self.allowed.is_none() && self.max_length.is_none()
+    }
+}
+
+impl std::fmt::Debug for InputFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InputFilter")
+            .field(
+                "allowed",
+                &self.allowed.as_ref().map(|_| "Fn(char) -> bool"),
+            )
+            .field("max_length", &self.max_length)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_with_max_length() {
+        let filter = InputFilter::digits().max_length(4);
+        assert_eq!(filter.filter("12", 2..2, "3a4b5"), "34");
+        assert_eq!(filter.filter("1234", 1..3, "x9"), "9");
+        assert_eq!(filter.filter("1234", 4..4, "5"), "");
+        assert!(matches!(filter.filter("", 0..0, "12"), Cow::Borrowed(_)));
+    }
+}
@@ -14,6 +14,7 @@
 
 //! Font attributes
 
+use crate::piet::{PietText, Text as _};
 use crate::{Data, FontFamily, FontStyle, FontWeight};
 
 /// A collection of attributes that describe a font.
@@ -30,6 +31,12 @@ pub struct FontDescriptor {
     pub weight: FontWeight,
     /// The font's [`FontStyle`].
     pub style: FontStyle,
+    /// Families to use, in order, when `family` is not installed.
+    ///
+    /// The first installed family of the chain is used for the text; glyphs
+    /// missing from that font are found by the platform's own fallback.
+    #[data(eq)]
+    pub fallbacks: Vec<FontFamily>,
 }
 
 impl FontDescriptor {
@@ -40,6 +47,7 @@ impl FontDescriptor {
             size: crate::piet::util::DEFAULT_FONT_SIZE,
             weight: FontWeight::REGULAR,
             style: FontStyle::Regular,
+            fallbacks: Vec::new(),
         }
     }
 
@@ -60,6 +68,30 @@ impl FontDescriptor {
         self.style = style;
         self
     }
+
+    /// Buider-style method to add a family to the descriptor's fallbacks.
+    pub fn with_fallback(mut self, family: FontFamily) -> Self {
+        self.fallbacks.push(family);
+        self
+    }
+
+    /// The first family of the chain made of `family` and `fallbacks` that
+    /// is available, or `family` if none are.
+    pub(crate) fn resolve_family(&self, text: &mut PietText) -> FontFamily {
+        if self.fallbacks.is_empty() {
+            return self.family.clone();
+        }
+        std::iter::once(&self.family)
+            .chain(&self.fallbacks)
+            .find_map(|family| {
+                if family.is_generic() {
+                    Some(family.clone())
+                } else {
+                    text.font_family(family.name())
+                }
+            })
+            .unwrap_or_else(|| self.family.clone())
+    }
 }
 
 impl Default for FontDescriptor {
@@ -69,6 +101,7 @@ impl Default for FontDescriptor {
             weight: Default::default(),
             style: Default::default(),
             size: crate::piet::util::DEFAULT_FONT_SIZE,
+            fallbacks: Vec::new(),
         }
     }
 }
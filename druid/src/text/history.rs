@@ -0,0 +1,295 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Undo and redo for editable text.
+
+use std::ops::Range;
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+
+use super::{Selection, TextStorage};
+
+/// The maximum number of steps that can be undone.
+const MAX_UNDO_STEPS: usize = 100;
+
+/// Typing or deleting after a pause this long starts a new undo step.
+const COALESCE_PAUSE: Duration = Duration::from_millis(1000);
+
+/// The text and selection at some point in the edit history.
+#[derive(Debug, Clone)]
+pub(crate) struct Snapshot<T> {
+    pub text: T,
+    pub selection: Selection,
+}
+
+/// The undo and redo stacks of an [`EditSession`].
+///
+/// Consecutive typing and consecutive deletions are grouped into a single
+/// undo step, which ends at a word boundary or after a pause.
+///
+/// [`EditSession`]: super::EditSession
+#[derive(Debug, Clone)]
+pub(crate) struct EditHistory<T> {
+    undo: Vec<Snapshot<T>>,
+    redo: Vec<Snapshot<T>>,
+    /// The text after the last recorded edit, used to notice edits that
+    /// were made without going through the history.
+    current: Option<T>,
+    /// The undo step that the next edit can be added to, if any.
+    group: Option<EditGroup>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EditGroup {
+    kind: EditKind,
+    /// The position of the caret after the last edit in the group.
+    caret: usize,
+    /// Whether the last edit inserted whitespace.
+    after_whitespace: bool,
+    time: Instant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Typing,
+    Deleting,
+    Other,
+}
+
+impl<T: TextStorage> EditHistory<T> {
+    /// Record an edit that changed the text from `old` to `new`.
+    ///
+    /// `selection` is the selection before the edit.
+    pub fn record(&mut self, old: &T, selection: Selection, new: &T) {
+        self.record_at(old, selection, new, Instant::now())
+    }
+
+    fn record_at(&mut self, old: &T, selection: Selection, new: &T, now: Instant) {
+        if old.same(new) {
+            return;
+        }
+        let (removed, inserted) = changed_ranges(old.as_str(), new.as_str());
+        let inserted_text = &new.as_str()[inserted.clone()];
+        let kind = if removed.is_empty() && !inserted_text.contains('\n') {
+            EditKind::Typing
+        } else if inserted.is_empty() && selection.is_caret() {
+            EditKind::Deleting
+        } else {
+            EditKind::Other
+        };
+        let after_whitespace = inserted_text.ends_with(char::is_whitespace);
+        let starts_word = inserted_text.starts_with(|c: char| !c.is_whitespace());
+
+        let extends_group = match self.group {
+            Some(group) if group.kind == kind => {
+                now.duration_since(group.time) < COALESCE_PAUSE
+                    && match kind {
+                        EditKind::Typing => {
+                            removed.start == group.caret && !(group.after_whitespace && starts_word)
+                        }
+                        // Backspace deletes before the caret, delete after it.
+                        EditKind::Deleting => {
+                            removed.end == group.caret || removed.start == group.caret
+                        }
+                        EditKind::Other => false,
+                    }
+            }
+            _ => false,
+        };
+
+        if !extends_group {
+            self.undo.push(Snapshot {
+                text: old.clone(),
+                selection,
+            });
+            if self.undo.len() > MAX_UNDO_STEPS {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.current = Some(new.clone());
+        self.group = Some(EditGroup {
+            kind,
+            caret: inserted.end,
+            after_whitespace,
+            time: now,
+        });
+    }
+
+    /// Pop the last undo step, given the current text and selection.
+    pub fn undo(&mut self, text: &T, selection: Selection) -> Option<Snapshot<T>> {
+        let snapshot = self.undo.pop()?;
+        self.redo.push(Snapshot {
+            text: text.clone(),
+            selection,
+        });
+        self.restored(snapshot)
+    }
+
+    /// Pop the last redo step, given the current text and selection.
+    pub fn redo(&mut self, text: &T, selection: Selection) -> Option<Snapshot<T>> {
+        let snapshot = self.redo.pop()?;
+        self.undo.push(Snapshot {
+            text: text.clone(),
+            selection,
+        });
+        self.restored(snapshot)
+    }
+
+    fn restored(&mut self, snapshot: Snapshot<T>) -> Option<Snapshot<T>> {
+        self.current = Some(snapshot.text.clone());
+        self.group = None;
+        Some(snapshot)
+    }
+
+    /// Forget the history if the text was changed by something other than
+    /// a recorded edit, since the recorded states no longer apply.
+    pub fn sync(&mut self, text: &T) {
+        if let Some(current) = &self.current {
+            // an equal string in a new allocation is not an external change
+            if !current.same(text) && current.as_str() != text.as_str() {
+                self.clear();
+            }
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.current = None;
+        self.group = None;
+    }
+}
+
+impl<T> Default for EditHistory<T> {
+    fn default() -> Self {
+        EditHistory {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            current: None,
+            group: None,
+        }
+    }
+}
+
+/// The range of `old` that was replaced, and the range of `new` that replaced it.
+pub(crate) fn changed_ranges(old: &str, new: &str) -> (Range<usize>, Range<usize>) {
+    let mut prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let mut suffix = old
+        .bytes()
+        .rev()
+        .zip(new.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+    (prefix..old.len() - suffix, prefix..new.len() - suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArcStr;
+
+    fn history_with(edits: &[(&str, &str)], pause: Duration) -> EditHistory<ArcStr> {
+        let mut history = EditHistory::default();
+        let mut now = Instant::now();
+        for (old, new) in edits {
+            let old: ArcStr = (*old).into();
+            let caret = Selection::caret(old.len());
+            history.record_at(&old, caret, &(*new).into(), now);
+            now += pause;
+        }
+        history
+    }
+
+    fn undo_all(history: &mut EditHistory<ArcStr>, text: &str) -> Vec<String> {
+        let mut text: ArcStr = text.into();
+        let mut states = Vec::new();
+        while let Some(snapshot) = history.undo(&text, Selection::caret(0)) {
+            text = snapshot.text;
+            states.push(text.to_string());
+        }
+        states
+    }
+
+    #[test]
+    fn typing_is_grouped_by_word() {
+        let edits = [
+            ("", "a"),
+            ("a", "ab"),
+            ("ab", "ab "),
+            ("ab ", "ab c"),
+            ("ab c", "ab cd"),
+        ];
+        let mut history = history_with(&edits, Duration::from_millis(10));
+        assert_eq!(undo_all(&mut history, "ab cd"), ["ab ", ""]);
+    }
+
+    #[test]
+    fn pause_ends_group() {
+        let edits = [("", "a"), ("a", "ab")];
+        let mut history = history_with(&edits, COALESCE_PAUSE);
+        assert_eq!(undo_all(&mut history, "ab"), ["a", ""]);
+    }
+
+    #[test]
+    fn deleting_is_grouped() {
+        let edits = [("abc", "abcd"), ("abcd", "abc"), ("abc", "ab")];
+        let mut history = history_with(&edits, Duration::from_millis(10));
+        assert_eq!(undo_all(&mut history, "ab"), ["abcd", "abc"]);
+    }
+
+    #[test]
+    fn redo_and_external_change() {
+        let mut history = history_with(&[("", "a")], Duration::ZERO);
+        let undone = history.undo(&"a".into(), Selection::caret(1)).unwrap();
+        assert_eq!(&*undone.text, "");
+        assert!(history.can_redo());
+        let redone = history.redo(&undone.text, undone.selection).unwrap();
+        assert_eq!(&*redone.text, "a");
+        assert_eq!(redone.selection, Selection::caret(1));
+
+        history.sync(&"a".into());
+        assert!(history.can_undo());
+        history.sync(&"something else".into());
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn changed_ranges_multibyte() {
+        assert_eq!(changed_ranges("héllo", "hello"), (1..3, 1..2));
+        assert_eq!(changed_ranges("aa", "aaa"), (2..2, 2..3));
+    }
+}
@@ -20,9 +20,10 @@ use std::sync::{Arc, Weak};
 
 use tracing::instrument;
 
+use super::history::{changed_ranges, EditHistory};
 use super::{
-    EditableText, ImeHandlerRef, ImeInvalidation, InputHandler, Movement, Selection, TextAction,
-    TextLayout, TextStorage,
+    EditableText, ImeHandlerRef, ImeInvalidation, InputFilter, InputHandler, Movement, Selection,
+    TextAction, TextLayout, TextStorage,
 };
 use crate::kurbo::{Line, Point, Rect, Vec2};
 use crate::piet::TextLayout as _;
@@ -102,7 +103,14 @@ pub struct EditSession<T> {
     alignment_offset: f64,
     /// The portion of the text that is currently marked by the IME.
     composition_range: Option<Range<usize>>,
+    candidate_anchor: CandidateAnchor,
+    /// Ranges of the text drawn on the highlight color, such as search matches.
+    highlights: Vec<Range<usize>>,
     drag_granularity: DragGranularity,
+    history: EditHistory<T>,
+    input_filter: InputFilter,
+    /// The text before the input method started composing, if it is composing.
+    pre_composition: Option<T>,
     /// The origin of the textbox, relative to the origin of the window.
     pub origin: Point,
 }
@@ -126,6 +134,19 @@ struct EditSessionHandle<T> {
     inner: Arc<RefCell<EditSession<T>>>,
 }
 
+/// Where the platform's IME candidate window is placed while text is being
+/// composed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CandidateAnchor {
+    /// Against the text being composed, which is what the platform does
+    /// on its own.
+    #[default]
+    Composition,
+    /// Against the caret, following it as it moves through the composition
+    /// and as the text scrolls.
+    Caret,
+}
+
 /// When a drag follows a double- or triple-click, the behaviour of
 /// drag changes to only select whole words or whole paragraphs.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -342,8 +363,14 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
                 }
                 let text = self.borrow_mut().take_external_text_change();
                 let selection = self.borrow_mut().take_external_selection_change();
+                let (text, selection) = self
+                    .borrow_mut()
+                    .filter_external_change(data, text, selection);
                 if let Some(text) = text {
-                    self.borrow_mut().layout.set_text(text.clone());
+                    let mut session = self.borrow_mut();
+                    let old_selection = session.selection;
+                    session.history.record(data, old_selection, &text);
+                    session.layout.set_text(text.clone());
                     *data = text;
                 }
                 if let Some(selection) = selection {
@@ -455,6 +482,23 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
         let cursor_color = env.get(theme::CURSOR_COLOR);
         let text_offset = Vec2::new(self.borrow().alignment_offset, 0.0);
 
+        let highlight_color = env.get(theme::HIGHLIGHTED_TEXT_BACKGROUND_COLOR);
+        for range in self.borrow().highlights.iter() {
+            // highlights may be stale for a moment after the text changes
+            if range.end
+                > self
+                    .borrow()
+                    .layout
+                    .text()
+                    .map_or(0, |text| text.as_str().len())
+            {
+                continue;
+            }
+            for rect in self.borrow().layout.rects_for_range(range.clone()) {
+                ctx.fill(rect + text_offset, &highlight_color);
+            }
+        }
+
         let selection = self.borrow().selection();
         let composition = self.borrow().composition_range();
         let sel_rects = self.borrow().layout.rects_for_range(selection.range());
@@ -462,6 +506,10 @@ impl<T: TextStorage + EditableText> Widget<T> for TextComponent<T> {
             // I believe selection should always be contained in composition range while composing?
             assert!(composition.start <= selection.anchor && composition.end >= selection.active);
             let comp_rects = self.borrow().layout.rects_for_range(composition);
+            let comp_color = env.get(theme::COMPOSITION_BACKGROUND_COLOR);
+            for region in &comp_rects {
+                ctx.fill(*region + text_offset, &comp_color);
+            }
             for region in comp_rects {
                 let y = region.max_y().floor();
                 let line = Line::new((region.min_x(), y), (region.max_x(), y)) + text_offset;
@@ -515,6 +563,20 @@ impl<T> EditSession<T> {
         self.accepts_newlines = accepts_newlines;
     }
 
+    /// Set the [`InputFilter`] that restricts the text that can be entered.
+    ///
+    /// This does not affect the current text.
+    pub fn set_input_filter(&mut self, filter: InputFilter) {
+        self.input_filter = filter;
+    }
+
+    /// Sets whether or not this session will allow the insertion of tabs.
+    ///
+    /// If `false`, the tab key sends a [`TextComponent::TAB`] notification instead.
+    pub fn set_accepts_tabs(&mut self, accepts_tabs: bool) {
+        self.accepts_tabs = accepts_tabs;
+    }
+
     /// Set the text alignment.
     ///
     /// This is only meaningful for single-line text that does not fill
@@ -528,6 +590,19 @@ impl<T> EditSession<T> {
         self.alignment
     }
 
+    /// Set the ranges of the text to draw on [`HIGHLIGHTED_TEXT_BACKGROUND_COLOR`],
+    /// replacing any earlier ones.
+    ///
+    /// [`HIGHLIGHTED_TEXT_BACKGROUND_COLOR`]: theme::HIGHLIGHTED_TEXT_BACKGROUND_COLOR
+    pub fn set_highlights(&mut self, ranges: Vec<Range<usize>>) {
+        self.highlights = ranges;
+    }
+
+    /// Set where the IME candidate window is placed while text is composed.
+    pub fn set_candidate_anchor(&mut self, anchor: CandidateAnchor) {
+        self.candidate_anchor = anchor;
+    }
+
     /// Returns any invalidation action that should be passed to the platform.
     ///
     /// The user of this component *must* check this after calling `update`.
@@ -584,13 +659,70 @@ impl<T: TextStorage + EditableText> EditSession<T> {
     /// text state, by calling [`EventCtx::invalidate_text_input`].
     #[must_use]
     pub fn insert_text(&mut self, data: &mut T, new_text: &str) -> ImeInvalidation {
+        let new_text = self
+            .input_filter
+            .filter(data.as_str(), self.selection.range(), new_text);
+        let new_text = new_text.as_ref();
+        let old = data.clone();
         let new_cursor_pos = self.selection.min() + new_text.len();
         data.edit(self.selection.range(), new_text);
+        self.history.record(&old, self.selection, data);
         self.selection = Selection::caret(new_cursor_pos);
         self.scroll_to_selection_end(true);
         ImeInvalidation::Reset
     }
 
+    /// Revert the last edit, restoring the text and selection from before it.
+    ///
+    /// Consecutive typing is undone a word at a time, and consecutive
+    /// deletions all at once.
+    ///
+    /// Returns `None` if there is nothing to undo. Otherwise the caller is
+    /// responsible for notifying the platform of the change in text state, by
+    /// calling [`EventCtx::invalidate_text_input`].
+    #[must_use]
+    pub fn undo(&mut self, data: &mut T) -> Option<ImeInvalidation> {
+        let snapshot = self.history.undo(data, self.selection)?;
+        *data = snapshot.text;
+        self.selection = snapshot.selection;
+        self.scroll_to_selection_end(true);
+        Some(ImeInvalidation::Reset)
+    }
+
+    /// Reapply the last edit reverted with [`undo`].
+    ///
+    /// Returns `None` if there is nothing to redo. Otherwise the caller is
+    /// responsible for notifying the platform of the change in text state, by
+    /// calling [`EventCtx::invalidate_text_input`].
+    ///
+    /// [`undo`]: EditSession::undo
+    #[must_use]
+    pub fn redo(&mut self, data: &mut T) -> Option<ImeInvalidation> {
+        let snapshot = self.history.redo(data, self.selection)?;
+        *data = snapshot.text;
+        self.selection = snapshot.selection;
+        self.scroll_to_selection_end(true);
+        Some(ImeInvalidation::Reset)
+    }
+
+    /// Returns `true` if there is an edit that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// Returns `true` if there is an undone edit that can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Forget all edits, so that they can no longer be undone or redone.
+    ///
+    /// The history is also cleared whenever the text is changed from outside
+    /// of this session, for example by setting the data.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
     /// Sets the clipboard to the contents of the current selection.
     ///
     /// Returns `true` if the clipboard was set, and `false` if not (indicating)
@@ -609,6 +741,59 @@ impl<T: TextStorage + EditableText> EditSession<T> {
         false
     }
 
+    /// Apply the input filter to a change of the text and selection by the platform.
+    ///
+    /// Text that is being composed by an input method is left alone until the
+    /// composition ends, and then filtered as a whole.
+    fn filter_external_change(
+        &mut self,
+        data: &T,
+        text: Option<T>,
+        selection: Option<Selection>,
+    ) -> (Option<T>, Option<Selection>) {
+        if self.input_filter.is_unrestricted() {
+            return (text, selection);
+        }
+        if self.composition_range.is_some() {
+            if text.is_some() && self.pre_composition.is_none() {
+                self.pre_composition = Some(data.clone());
+            }
+            return (text, selection);
+        }
+        let base = self.pre_composition.take().unwrap_or_else(|| data.clone());
+        let new = match text {
+            Some(text) => text,
+            None if !base.same(data) => data.clone(),
+            None => return (None, selection),
+        };
+
+        let (removed, inserted) = changed_ranges(base.as_str(), new.as_str());
+        let inserted_text = &new.as_str()[inserted.clone()];
+        let filtered = self
+            .input_filter
+            .filter(base.as_str(), removed.clone(), inserted_text);
+        if filtered == inserted_text {
+            return (Some(new), selection);
+        }
+
+        let mut result = base;
+        result.edit(removed.clone(), filtered.as_ref());
+        let filtered_end = removed.start + filtered.len();
+        let map = |pos: usize| {
+            if pos <= inserted.start {
+                pos
+            } else if pos >= inserted.end {
+                pos - inserted.end + filtered_end
+            } else {
+                filtered_end
+            }
+        };
+        let selection = selection.unwrap_or_else(|| Selection::caret(inserted.end));
+        let selection = Selection::new(map(selection.anchor), map(selection.active));
+        self.update_pending_invalidation(ImeInvalidation::Reset);
+        (Some(result), Some(selection))
+    }
+
     fn scroll_to_selection_end(&mut self, after_edit: bool) {
         self.external_scroll_to = Some(after_edit);
     }
@@ -820,6 +1005,7 @@ impl<T: TextStorage + EditableText> EditSession<T> {
         {
             self.update_pending_invalidation(ImeInvalidation::Reset);
             self.layout.set_text(new_data.clone());
+            self.history.sync(new_data);
         }
         if self.layout.needs_rebuild_after_update(ctx) {
             ctx.request_layout();
@@ -910,7 +1096,18 @@ impl<T: TextStorage + EditableText> InputHandler for EditSessionHandle<T> {
         } else {
             layout.rects_for_range(range).first().copied()
         }
-        .map(|rect| rect + origin.to_vec2())
+        .map(|rect| rect + origin.to_vec2() + Vec2::new(self.inner.borrow().alignment_offset, 0.0))
+    }
+
+    fn candidate_anchor(&self) -> Option<Rect> {
+        let inner = self.inner.borrow();
+        match inner.candidate_anchor {
+            CandidateAnchor::Composition => None,
+            CandidateAnchor::Caret => {
+                let line = inner.cursor_line_for_text_position(inner.selection.active);
+                Some(Rect::from_points(line.p0, line.p1) + inner.origin.to_vec2())
+            }
+        }
     }
 
     fn handle_action(&mut self, action: TextAction) {
@@ -939,6 +1136,8 @@ impl<T> Default for TextComponent<T> {
             pending_ime_invalidation: None,
             selection: Selection::caret(0),
             composition_range: None,
+            candidate_anchor: CandidateAnchor::default(),
+            highlights: Vec::new(),
             send_notification_on_return: false,
             send_notification_on_cancel: false,
             accepts_newlines: false,
@@ -946,6 +1145,9 @@ impl<T> Default for TextComponent<T> {
             alignment: TextAlignment::Start,
             alignment_offset: 0.0,
             drag_granularity: DragGranularity::Grapheme,
+            history: EditHistory::default(),
+            input_filter: InputFilter::new(),
+            pre_composition: None,
             origin: Point::ZERO,
         };
 
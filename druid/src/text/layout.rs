@@ -17,14 +17,23 @@
 use std::ops::Range;
 use std::rc::Rc;
 
-use super::{EnvUpdateCtx, Link, TextStorage};
-use crate::kurbo::{Line, Point, Rect, Size};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{Attachment, EnvUpdateCtx, Link, ParagraphStyle, TextStorage};
+use crate::kurbo::{Affine, Line, Point, Rect, Size, Vec2};
 use crate::piet::{
-    Color, PietText, PietTextLayout, Text as _, TextAlignment, TextAttribute, TextLayout as _,
-    TextLayoutBuilder as _,
+    Color, FontFamily, PietText, PietTextLayout, Text as _, TextAlignment, TextAttribute,
+    TextLayout as _, TextLayoutBuilder as _,
 };
+use crate::resources::{ResourceHandle, ResourceKind};
 use crate::{Env, FontDescriptor, KeyOrValue, PaintCtx, RenderContext, UpdateCtx};
 
+/// Roughly how much memory a layout uses per byte of text, used for
+/// [`resources`] accounting.
+///
+/// [`resources`]: crate::resources
+const LAYOUT_BYTES_PER_BYTE: usize = 64;
+
 /// A component for displaying text on screen.
 ///
 /// This is a type intended to be used by other widgets that display text.
@@ -56,7 +65,14 @@ pub struct TextLayout<T> {
     wrap_width: f64,
     alignment: TextAlignment,
     links: Rc<[(Rect, usize)]>,
+    attachments: Rc<[Rect]>,
+    paragraph_style: ParagraphStyle,
+    /// How far each line is moved by the paragraph style; empty if none are.
+    line_offsets: Rc<[Vec2]>,
+    /// The size of the text with its lines moved.
+    spaced_size: Size,
     text_is_rtl: bool,
+    resource: Option<ResourceHandle>,
 }
 
 /// Metrics describing the layout text.
@@ -87,7 +103,12 @@ impl<T> TextLayout<T> {
             wrap_width: f64::INFINITY,
             alignment: Default::default(),
             links: Rc::new([]),
+            attachments: Rc::new([]),
+            paragraph_style: ParagraphStyle::new(),
+            line_offsets: Rc::new([]),
+            spaced_size: Size::ZERO,
             text_is_rtl: false,
+            resource: None,
         }
     }
 
@@ -149,6 +170,64 @@ impl<T> TextLayout<T> {
         }
     }
 
+    /// Set the [`ParagraphStyle`], for text that does not have its own.
+    ///
+    /// The inner piet layout returned by [`layout`] does not include the
+    /// spacing; use the methods on `TextLayout` to measure and hit-test.
+    ///
+    /// [`layout`]: TextLayout::layout
+    pub fn set_paragraph_style(&mut self, style: ParagraphStyle) {
+        if style != self.paragraph_style {
+            self.paragraph_style = style;
+            self.layout = None;
+        }
+    }
+
+    /// How far line `line` of the piet layout is moved by the paragraph style.
+    fn line_offset(&self, line: usize) -> Vec2 {
+        self.line_offsets.get(line).copied().unwrap_or(Vec2::ZERO)
+    }
+
+    /// How far the line at height `y` of the piet layout is moved.
+    fn offset_at_y(&self, layout: &PietTextLayout, y: f64) -> Vec2 {
+        if self.line_offsets.is_empty() {
+            return Vec2::ZERO;
+        }
+        let line = (0..layout.line_count())
+            .find(|i| {
+                layout
+                    .line_metric(*i)
+                    .map(|line| y < line.y_offset + line.height)
+                    .unwrap_or(false)
+            })
+            .unwrap_or_else(|| layout.line_count().saturating_sub(1));
+        self.line_offset(line)
+    }
+
+    /// The point in the piet layout that is drawn at `point`.
+    fn unspaced_point(&self, layout: &PietTextLayout, point: Point) -> Point {
+        if self.line_offsets.is_empty() {
+            return point;
+        }
+        // The line whose spaced band, reaching halfway to its neighbours, holds the point.
+        let mut line = 0;
+        for i in 1..layout.line_count() {
+            let (prev, next) = match (layout.line_metric(i - 1), layout.line_metric(i)) {
+                (Some(prev), Some(next)) => (prev, next),
+                _ => break,
+            };
+            let prev_bottom = prev.y_offset + prev.height + self.line_offset(i - 1).y;
+            let next_top = next.y_offset + self.line_offset(i).y;
+            if point.y < (prev_bottom + next_top) / 2.0 {
+                break;
+            }
+            line = i;
+        }
+        let metric = layout.line_metric(line).unwrap_or_default();
+        let offset = self.line_offset(line);
+        Point::new(point.x - offset.x, metric.y_offset + metric.height / 2.0)
+    }
+
     /// Returns `true` if this layout's text appears to be right-to-left.
     ///
     /// See [`piet::util::first_strong_rtl`] for more information.
@@ -206,10 +285,11 @@ impl<T: TextStorage> TextLayout<T> {
     ///
     /// [`rebuild_if_needed`]: TextLayout::rebuild_if_needed
     pub fn size(&self) -> Size {
-        self.layout
-            .as_ref()
-            .map(|layout| layout.size())
-            .unwrap_or_default()
+        match self.layout.as_ref() {
+            Some(_) if !self.line_offsets.is_empty() => self.spaced_size,
+            Some(layout) => layout.size(),
+            None => Size::ZERO,
+        }
     }
 
     /// Return the text's [`LayoutMetrics`].
@@ -225,8 +305,8 @@ impl<T: TextStorage> TextLayout<T> {
         );
 
         if let Some(layout) = self.layout.as_ref() {
-            let first_baseline = layout.line_metric(0).unwrap().baseline;
-            let size = layout.size();
+            let first_baseline = layout.line_metric(0).unwrap().baseline + self.line_offset(0).y;
+            let size = self.size();
             LayoutMetrics {
                 size,
                 first_baseline,
@@ -242,7 +322,11 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn text_position_for_point(&self, point: Point) -> usize {
         self.layout
             .as_ref()
-            .map(|layout| layout.hit_test_point(point).idx)
+            .map(|layout| {
+                layout
+                    .hit_test_point(self.unspaced_point(layout, point))
+                    .idx
+            })
             .unwrap_or_default()
     }
 
@@ -256,7 +340,10 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn point_for_text_position(&self, text_pos: usize) -> Point {
         self.layout
             .as_ref()
-            .map(|layout| layout.hit_test_text_position(text_pos).point)
+            .map(|layout| {
+                let hit = layout.hit_test_text_position(text_pos);
+                hit.point + self.line_offset(hit.line)
+            })
             .unwrap_or_default()
     }
 
@@ -269,7 +356,13 @@ impl<T: TextStorage> TextLayout<T> {
     pub fn rects_for_range(&self, range: Range<usize>) -> Vec<Rect> {
         self.layout
             .as_ref()
-            .map(|layout| layout.rects_for_range(range))
+            .map(|layout| {
+                let mut rects = layout.rects_for_range(range);
+                for rect in &mut rects {
+                    *rect = *rect + self.offset_at_y(layout, rect.center().y);
+                }
+                rects
+            })
             .unwrap_or_default()
     }
 
@@ -288,7 +381,7 @@ impl<T: TextStorage> TextLayout<T> {
                 let line_metric = layout.line_metric(p1.line).unwrap();
                 // heuristic; 1/5 of height is a rough guess at the descender pos?
                 let y_pos = line_metric.baseline + (line_metric.height / 5.0);
-                Line::new((p1.point.x, y_pos), (p2.point.x, y_pos))
+                Line::new((p1.point.x, y_pos), (p2.point.x, y_pos)) + self.line_offset(p1.line)
             })
             .unwrap_or_else(|| Line::new(Point::ZERO, Point::ZERO))
     }
@@ -303,7 +396,7 @@ impl<T: TextStorage> TextLayout<T> {
                 let line_metrics = layout.line_metric(pos.line).unwrap();
                 let p1 = (pos.point.x, line_metrics.y_offset);
                 let p2 = (pos.point.x, (line_metrics.y_offset + line_metrics.height));
-                Line::new(p1, p2)
+                Line::new(p1, p2) + self.line_offset(pos.line)
             })
             .unwrap_or_else(|| Line::new(Point::ZERO, Point::ZERO))
     }
@@ -325,6 +418,16 @@ impl<T: TextStorage> TextLayout<T> {
         text.links().get(*i)
     }
 
+    /// Returns the text's [`Attachment`]s, each with the rect (relative to the
+    /// layout's origin) it should be drawn in.
+    ///
+    /// The bottom of each rect is on the baseline of the line the attachment
+    /// starts on.
+    pub fn attachments(&self) -> impl Iterator<Item = (&Attachment, Rect)> + '_ {
+        let attachments = self.text().map(|text| text.attachments()).unwrap_or(&[]);
+        attachments.iter().zip(self.attachments.iter().copied())
+    }
+
     /// Called during the containing widget's [`update`] method; this text object
     /// will check to see if any used environment items have changed,
     /// and invalidate itself as needed.
@@ -378,15 +481,33 @@ impl<T: TextStorage> TextLayout<T> {
                     font
                 };
 
+                let style = text.paragraph_style().unwrap_or(self.paragraph_style);
+                let family = descriptor.resolve_family(factory);
                 let builder = factory
                     .new_text_layout(text.clone())
-                    .max_width(self.wrap_width)
+                    .max_width((self.wrap_width - style.first_line_indent).max(0.0))
                     .alignment(self.alignment)
-                    .font(descriptor.family.clone(), descriptor.size)
+                    .font(family, descriptor.size)
                     .default_attribute(descriptor.weight)
                     .default_attribute(descriptor.style)
                     .default_attribute(TextAttribute::TextColor(color));
-                let layout = text.add_attributes(builder, env).build().unwrap();
+                let mut builder = text.add_attributes(builder, env);
+                let emoji = emoji_ranges(text.as_str());
+                if let Some(family) = emoji.first().and_then(|_| emoji_family(factory)) {
+                    for range in emoji {
+                        builder = builder
+                            .range_attribute(range, TextAttribute::FontFamily(family.clone()));
+                    }
+                }
+                let layout = builder.build().unwrap();
+
+                if style.is_natural() {
+                    self.line_offsets = Rc::new([]);
+                } else {
+                    let (offsets, size) = spaced_lines(&layout, text.as_str(), &style);
+                    self.line_offsets = offsets.into();
+                    self.spaced_size = size;
+                }
 
                 self.links = text
                     .links()
@@ -396,10 +517,35 @@ impl<T: TextStorage> TextLayout<T> {
                         layout
                             .rects_for_range(link.range())
                             .into_iter()
+                            .map(|rect| rect + self.offset_at_y(&layout, rect.center().y))
                             .map(move |rect| (rect, i))
                     })
                     .collect();
 
+                self.attachments = text
+                    .attachments()
+                    .iter()
+                    .map(|attachment| {
+                        let pos = layout.hit_test_text_position(attachment.range.start);
+                        let baseline = layout
+                            .line_metric(pos.line)
+                            .map(|line| line.y_offset + line.baseline)
+                            .unwrap_or_default();
+                        let size = attachment.size;
+                        Rect::from_origin_size((pos.point.x, baseline - size.height), size)
+                            + self.line_offset(pos.line)
+                    })
+                    .collect();
+
+                // A rough estimate, the real cost depends on the platform.
+                let bytes = text.as_str().len() * LAYOUT_BYTES_PER_BYTE;
+                match &self.resource {
+                    Some(handle) => handle.set_size(bytes),
+                    None => {
+                        self.resource = Some(ResourceHandle::new(ResourceKind::TextLayout, bytes))
+                    }
+                }
+
                 self.layout = Some(layout);
             }
         }
@@ -422,10 +568,99 @@ impl<T: TextStorage> TextLayout<T> {
                 .map(|t| t.as_str())
                 .unwrap_or("layout is missing text")
         );
-        if let Some(layout) = self.layout.as_ref() {
+        let layout = match self.layout.as_ref() {
+            Some(layout) => layout,
+            None => return,
+        };
+        if self.line_offsets.is_empty() {
             ctx.draw_text(layout, point);
+            return;
+        }
+        // Piet can't space lines, so draw the layout once for each line,
+        // clipped to that line and moved into place.
+        let origin = point.into().to_vec2();
+        let width = layout.size().width;
+        for (i, offset) in self.line_offsets.iter().enumerate() {
+            if let Some(line) = layout.line_metric(i) {
+                // wide enough for any alignment, and for glyphs that overhang
+                let clip = Rect::new(
+                    -width,
+                    line.y_offset,
+                    2.0 * width,
+                    line.y_offset + line.height,
+                );
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate(origin + *offset));
+                    ctx.clip(clip);
+                    ctx.draw_text(layout, Point::ZERO);
+                });
+            }
+        }
+    }
+}
+
+/// How far each line of `layout` is moved by `style`, and the size of the
+/// spaced text.
+fn spaced_lines(layout: &PietTextLayout, text: &str, style: &ParagraphStyle) -> (Vec<Vec2>, Size) {
+    let mut offsets = Vec::with_capacity(layout.line_count());
+    let mut top = 0.0;
+    let mut starts_paragraph = true;
+    for i in 0..layout.line_count() {
+        let line = layout.line_metric(i).unwrap_or_default();
+        if starts_paragraph && i > 0 {
+            top += style.paragraph_spacing;
         }
+        let height = style.line_spacing.line_height(line.height);
+        let indent = if starts_paragraph {
+            style.first_line_indent
+        } else {
+            0.0
+        };
+        offsets.push(Vec2::new(
+            indent,
+            top + (height - line.height) / 2.0 - line.y_offset,
+        ));
+        top += height;
+        starts_paragraph = text[line.range()].ends_with(['\n', '\r']);
     }
+    let size = Size::new(layout.size().width + style.first_line_indent, top);
+    (offsets, size)
+}
+
+/// The family of the platform's color emoji font.
+fn emoji_family(factory: &mut PietText) -> Option<FontFamily> {
+    #[cfg(target_os = "macos")]
+    const NAME: &str = "Apple Color Emoji";
+    #[cfg(target_os = "windows")]
+    const NAME: &str = "Segoe UI Emoji";
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    const NAME: &str = "Noto Color Emoji";
+    factory.font_family(NAME)
+}
+
+/// The ranges of `text` that are emoji, which are drawn with the emoji font
+/// whatever the font of the text around them, so that they are in color.
+fn emoji_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    if text.is_ascii() {
+        return ranges;
+    }
+    for (start, cluster) in text.grapheme_indices(true) {
+        let is_emoji = cluster.chars().any(|c| {
+            // pictographs, emoticons and flags, or any character followed by
+            // the emoji presentation selector
+            matches!(c as u32, 0x1F000..=0x1FAFF | 0xFE0F)
+        });
+        if !is_emoji {
+            continue;
+        }
+        let end = start + cluster.len();
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+    ranges
 }
 
 impl<T> std::fmt::Debug for TextLayout<T> {
@@ -451,3 +686,22 @@ impl<T: TextStorage> Default for TextLayout<T> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emoji_ranges_cover_whole_clusters() {
+        assert!(emoji_ranges("plain text").is_empty());
+        assert!(emoji_ranges("caf\u{e9} \u{2764}").is_empty());
+        // a thumbs up with a skin tone, then a heart with the emoji selector
+        let text = "ok \u{1F44D}\u{1F3FD}\u{2764}\u{FE0F} done \u{1F1EB}\u{1F1F7}";
+        let ranges = emoji_ranges(text);
+        assert_eq!(ranges, vec![3..17, 23..31]);
+        assert_eq!(
+            &text[ranges[0].clone()],
+            "\u{1F44D}\u{1F3FD}\u{2764}\u{FE0F}"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Displaying text in a fixed pattern.
+
+use super::{Formatter, InputFilter, Selection, Validation, ValidationError};
+
+/// The character marking a slot in an [`InputMask`] pattern.
+const SLOT: char = '_';
+
+/// A [`Formatter`] that shows text in a fixed pattern, such as a phone number
+/// or a date, while the data only holds the characters that were entered.
+///
+/// The pattern is a string in which each `_` is a slot for one entered
+/// character; every other character is shown as is. Slots that have not been
+/// filled yet are shown as `_`.
+///
+/// Characters that appear in the pattern outside of slots can't be entered,
+/// so typing or pasting `12/03/1999` into a `__/__/____` mask gives the same
+/// result as `12031999`.
+///
+/// # Examples
+///
+/// ```
+/// # use druid::text::InputMask;
+/// # use druid::widget::TextBox;
+/// // The data is "12031999" while the text box shows "12/03/1999".
+/// let date = TextBox::new()
+///     .with_formatter(InputMask::new("__/__/____").allow(|c| c.is_ascii_digit()))
+///     .update_data_while_editing(true);
+/// ```
+#[derive(Debug, Clone)]
+pub struct InputMask {
+    pattern: String,
+    filter: InputFilter,
+}
+
+impl InputMask {
+    /// Create a mask from a pattern, in which each `_` is a slot for one
+    /// entered character.
+    pub fn new(pattern: impl Into<String>) -> Self {
+        InputMask {
+            pattern: pattern.into(),
+            filter: InputFilter::new(),
+        }
+    }
+
+    /// Builder-style method to only accept characters for which `predicate`
+    /// returns `true` in the slots.
+    pub fn allow(mut self, predicate: impl Fn(char) -> bool + 'static) -> Self {
+        self.filter = self.filter.allow(predicate);
+        self
+    }
+
+    /// The number of characters that can be entered.
+    pub fn slot_count(&self) -> usize {
+        self.pattern.chars().filter(|&c| c == SLOT).count()
+    }
+
+    /// Returns `true` if every slot of the mask is filled by `value`.
+    pub fn is_complete(&self, value: &str) -> bool {
+        value.chars().count() >= self.slot_count()
+    }
+
+    fn accepts(&self, c: char) -> bool {
+        c != SLOT && !self.pattern.contains(c) && self.filter.allows(c)
+    }
+
+    /// The entered characters in `input`, without the pattern.
+    fn entered(&self, input: &str) -> String {
+        input
+            .chars()
+            .filter(|&c| self.accepts(c))
+            .take(self.slot_count())
+            .collect()
+    }
+
+    /// The position in the formatted text after the `n`th slot, or before
+    /// the first slot if `n` is zero.
+    fn position_after_slot(&self, n: usize) -> usize {
+        let mut slots = self.pattern.match_indices(SLOT);
+        match n {
+            0 => slots.next().map(|(i, _)| i).unwrap_or(0),
+            n => slots
+                .nth(n - 1)
+                .map(|(i, _)| i + SLOT.len_utf8())
+                .unwrap_or(self.pattern.len()),
+        }
+    }
+}
+
+impl Formatter<String> for InputMask {
+    fn format(&self, value: &String) -> String {
+        let mut entered = value.chars();
+        self.pattern
+            .chars()
+            .map(|c| match c {
+                SLOT => entered.next().unwrap_or(SLOT),
+                c => c,
+            })
+            .collect()
+    }
+
+    fn validate_partial_input(&self, input: &str, sel: &Selection) -> Validation {
+        let entered = self.entered(input);
+        let text = self.format(&entered);
+        if text == input {
+            return Validation::success();
+        }
+        // Keep the caret after the same entered character it followed in the input.
+        let before_caret = input
+            .get(..sel.active)
+            .map(|before| before.chars().filter(|&c| self.accepts(c)).count())
+            .unwrap_or(0)
+            .min(entered.chars().count());
+        let caret = self.position_after_slot(before_caret);
+        Validation::success()
+            .change_text(text)
+            .change_selection(Selection::caret(caret))
+    }
+
+    fn value(&self, input: &str) -> Result<String, ValidationError> {
+        Ok(self.entered(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date() -> InputMask {
+        InputMask::new("__/__/____").allow(|c| c.is_ascii_digit())
+    }
+
+    #[test]
+    fn format_and_value() {
+        let mask = date();
+        assert_eq!(mask.format(&String::new()), "__/__/____");
+        assert_eq!(mask.format(&"1203".to_string()), "12/03/____");
+        assert_eq!(mask.value("12/03/1999").unwrap(), "12031999");
+        assert_eq!(mask.value("1x2/0").unwrap(), "120");
+        assert!(mask.is_complete("12031999"));
+    }
+
+    #[test]
+    fn typing_moves_caret() {
+        let mask = date();
+        // Typing "3" after "12/0".
+        let validation = mask.validate_partial_input("12/03_/____", &Selection::caret(5));
+        assert_eq!(validation.text_change.as_deref(), Some("12/03/____"));
+        assert_eq!(validation.selection_change, Some(Selection::caret(5)));
+
+        // Backspace after "12/0".
+        let validation = mask.validate_partial_input("12/_/____", &Selection::caret(3));
+        assert_eq!(validation.text_change.as_deref(), Some("12/__/____"));
+        assert_eq!(validation.selection_change, Some(Selection::caret(2)));
+
+        // Letters are rejected.
+        let validation = mask.validate_partial_input("a__/__/____", &Selection::caret(1));
+        assert_eq!(validation.text_change.as_deref(), Some("__/__/____"));
+        assert_eq!(validation.selection_change, Some(Selection::caret(0)));
+    }
+}
@@ -17,6 +17,7 @@
 mod attribute;
 mod backspace;
 mod editable_text;
+mod filter;
 mod font_descriptor;
 
 #[deprecated(since = "0.8.0", note = "use types from druid::text module instead")]
@@ -26,10 +27,13 @@ pub mod format;
 // format private
 #[path = "format.rs"]
 mod format_priv;
+mod history;
 mod input_component;
 mod input_methods;
 mod layout;
+mod mask;
 mod movement;
+mod paragraph_style;
 mod rich_text;
 mod storage;
 
@@ -39,16 +43,20 @@ pub use druid_shell::text::{
     Selection, VerticalMovement, WritingDirection,
 };
 
-pub use self::attribute::{Attribute, AttributeSpans, Link};
+pub use self::attribute::{Attachment, AttachmentContent, Attribute, AttributeSpans, Link};
 pub use self::backspace::offset_for_delete_backwards;
 pub use self::editable_text::{EditableText, EditableTextCursor, StringCursor};
+pub use self::filter::InputFilter;
 pub use self::font_descriptor::FontDescriptor;
 pub use self::format_priv::{Formatter, ParseFormatter, Validation, ValidationError};
 pub use self::layout::{LayoutMetrics, TextLayout};
+pub use self::mask::InputMask;
 pub use self::movement::movement;
-pub use input_component::{EditSession, TextComponent};
+pub use self::paragraph_style::{LineSpacing, ParagraphStyle};
+pub use input_component::{CandidateAnchor, EditSession, TextComponent};
 pub use input_methods::ImeHandlerRef;
 pub use rich_text::{AttributesAdder, RichText, RichTextBuilder};
 pub use storage::{ArcStr, EnvUpdateCtx, TextStorage};
 
 pub(crate) use input_methods::TextFieldRegistration;
+pub(crate) use movement::word_range_for_pos;
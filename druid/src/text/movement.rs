@@ -18,10 +18,14 @@ use std::ops::Range;
 
 use unicode_segmentation::UnicodeSegmentation;
 
+use super::editable_text::{
+    next_grapheme_offset, next_line_break, next_word_offset, preceding_line_break,
+    prev_grapheme_offset, prev_word_offset,
+};
 use crate::kurbo::Point;
 use crate::piet::TextLayout as _;
 use crate::text::{
-    EditableText, Movement, Selection, TextLayout, TextStorage, VerticalMovement, WritingDirection,
+    Movement, Selection, TextLayout, TextStorage, VerticalMovement, WritingDirection,
 };
 
 /// Compute the result of a [`Movement`] on a [`Selection`].
@@ -31,21 +35,21 @@ use crate::text::{
 /// If `modify` is true, only the 'active' edge (the `end`) of the selection
 /// should be changed; this is the case when the user moves with the shift
 /// key pressed.
-pub fn movement<T: EditableText + TextStorage>(
+pub fn movement<T: TextStorage>(
     m: Movement,
     s: Selection,
     layout: &TextLayout<T>,
     modify: bool,
 ) -> Selection {
     let (text, layout) = match (layout.text(), layout.layout()) {
-        (Some(text), Some(layout)) => (text, layout),
+        (Some(text), Some(layout)) => (text.as_str(), layout),
         _ => {
             debug_assert!(false, "movement() called before layout rebuild");
             return s;
         }
     };
 
-    let writing_direction = if crate::piet::util::first_strong_rtl(text.as_str()) {
+    let writing_direction = if crate::piet::util::first_strong_rtl(text) {
         WritingDirection::RightToLeft
     } else {
         WritingDirection::LeftToRight
@@ -54,7 +58,7 @@ pub fn movement<T: EditableText + TextStorage>(
     let (offset, h_pos) = match m {
         Movement::Grapheme(d) if d.is_upstream_for_direction(writing_direction) => {
             if s.is_caret() || modify {
-                text.prev_grapheme_offset(s.active)
+                prev_grapheme_offset(text, s.active)
                     .map(|off| (off, None))
                     .unwrap_or((0, s.h_pos))
             } else {
@@ -63,7 +67,7 @@ pub fn movement<T: EditableText + TextStorage>(
         }
         Movement::Grapheme(_) => {
             if s.is_caret() || modify {
-                text.next_grapheme_offset(s.active)
+                next_grapheme_offset(text, s.active)
                     .map(|off| (off, None))
                     .unwrap_or((s.active, s.h_pos))
             } else {
@@ -109,8 +113,8 @@ pub fn movement<T: EditableText + TextStorage>(
         Movement::Vertical(VerticalMovement::DocumentStart) => (0, None),
         Movement::Vertical(VerticalMovement::DocumentEnd) => (text.len(), None),
 
-        Movement::ParagraphStart => (text.preceding_line_break(s.active), None),
-        Movement::ParagraphEnd => (text.next_line_break(s.active), None),
+        Movement::ParagraphStart => (preceding_line_break(text, s.active), None),
+        Movement::ParagraphEnd => (next_line_break(text, s.active), None),
 
         Movement::Line(d) => {
             let hit = layout.hit_test_text_position(s.active);
@@ -124,7 +128,7 @@ pub fn movement<T: EditableText + TextStorage>(
         }
         Movement::Word(d) if d.is_upstream_for_direction(writing_direction) => {
             let offset = if s.is_caret() || modify {
-                text.prev_word_offset(s.active).unwrap_or(0)
+                prev_word_offset(text, s.active).unwrap_or(0)
             } else {
                 s.min()
             };
@@ -132,7 +136,7 @@ pub fn movement<T: EditableText + TextStorage>(
         }
         Movement::Word(_) => {
             let offset = if s.is_caret() || modify {
-                text.next_word_offset(s.active).unwrap_or(s.active)
+                next_word_offset(text, s.active).unwrap_or(s.active)
             } else {
                 s.max()
             };
@@ -0,0 +1,97 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The spacing of lines and paragraphs.
+
+use crate::Data;
+
+/// How far apart the lines of text are.
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub enum LineSpacing {
+    /// A multiple of each line's natural height; `1.0` leaves lines as the
+    /// font lays them out.
+    Proportional(f64),
+    /// The same height for every line, whatever the size of its text.
+    Exact(f64),
+}
+
+/// The spacing of the lines and paragraphs of a [`TextLayout`].
+///
+/// The extra space from [`line_spacing`] is split evenly above and below
+/// each line's text.
+///
+/// [`TextLayout`]: super::TextLayout
+/// [`line_spacing`]: ParagraphStyle::line_spacing
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub struct ParagraphStyle {
+    /// The height of each line.
+    pub line_spacing: LineSpacing,
+    /// Extra space after each paragraph, that is, after each hard line break.
+    pub paragraph_spacing: f64,
+    /// How far the first line of each paragraph is indented.
+    ///
+    /// Paragraphs are wrapped as if all of their lines were indented, so that
+    /// the first line always fits.
+    pub first_line_indent: f64,
+}
+
+impl LineSpacing {
+    /// The height of a line whose natural height is `natural`.
+    pub(crate) fn line_height(self, natural: f64) -> f64 {
+        match self {
+            LineSpacing::Proportional(factor) => natural * factor,
+            LineSpacing::Exact(height) => height,
+        }
+    }
+}
+
+impl ParagraphStyle {
+    /// Create a style that leaves text as the font lays it out.
+    pub const fn new() -> Self {
+        ParagraphStyle {
+            line_spacing: LineSpacing::Proportional(1.0),
+            paragraph_spacing: 0.0,
+            first_line_indent: 0.0,
+        }
+    }
+
+    /// Builder-style method to set the [`LineSpacing`].
+    pub const fn with_line_spacing(mut self, spacing: LineSpacing) -> Self {
+        self.line_spacing = spacing;
+        self
+    }
+
+    /// Builder-style method to set the space after each paragraph.
+    pub const fn with_paragraph_spacing(mut self, spacing: f64) -> Self {
+        self.paragraph_spacing = spacing;
+        self
+    }
+
+    /// Builder-style method to set the indent of the first line of each paragraph.
+    pub const fn with_first_line_indent(mut self, indent: f64) -> Self {
+        self.first_line_indent = indent;
+        self
+    }
+
+    /// Returns `true` if this style moves no lines.
+    pub(crate) fn is_natural(&self) -> bool {
+        *self == ParagraphStyle::new()
+    }
+}
+
+impl Default for ParagraphStyle {
+    fn default() -> Self {
+        ParagraphStyle::new()
+    }
+}
@@ -14,23 +14,37 @@
 
 //! Rich text with style spans.
 
+use std::borrow::Cow;
 use std::ops::{Range, RangeBounds};
+use std::rc::Rc;
 use std::sync::Arc;
 
-use super::attribute::Link;
-use super::{Attribute, AttributeSpans, EnvUpdateCtx, TextStorage};
+use super::attribute::{Attachment, AttachmentContent, Link};
+use super::editable_text::{
+    next_grapheme_offset, next_line_break, next_word_offset, preceding_line_break,
+    prev_grapheme_offset, prev_word_offset,
+};
+use super::{
+    Attribute, AttributeSpans, EditableText, EditableTextCursor, EnvUpdateCtx, ParagraphStyle,
+    StringCursor, TextStorage,
+};
 use crate::piet::{
-    util, Color, FontFamily, FontStyle, FontWeight, PietTextLayoutBuilder, TextLayoutBuilder,
-    TextStorage as PietTextStorage,
+    util, Color, FontFamily, FontStyle, FontWeight, PietTextLayoutBuilder, TextAttribute,
+    TextLayoutBuilder, TextStorage as PietTextStorage,
 };
-use crate::{ArcStr, Command, Data, Env, FontDescriptor, KeyOrValue};
+use crate::{theme, ArcStr, Command, Data, Env, FontDescriptor, ImageBuf, KeyOrValue, Size};
+
+/// The character attachments are made of; it is one em wide in most fonts.
+const ATTACHMENT_CHAR: char = '\u{2003}';
 
 /// Text with optional style spans.
 #[derive(Clone, Debug, Data)]
 pub struct RichText {
     buffer: ArcStr,
     attrs: Arc<AttributeSpans>,
-    links: Arc<[Link]>,
+    links: Rc<[Link]>,
+    attachments: Arc<[Attachment]>,
+    paragraph_style: Option<ParagraphStyle>,
 }
 
 impl RichText {
@@ -44,10 +58,18 @@ impl RichText {
         RichText {
             buffer,
             attrs: Arc::new(attributes),
-            links: Arc::new([]),
+            links: Rc::new([]),
+            attachments: Arc::new([]),
+            paragraph_style: None,
         }
     }
 
+    /// Builder-style method to set the [`ParagraphStyle`] of the whole text.
+    pub fn with_paragraph_style(mut self, style: ParagraphStyle) -> Self {
+        self.paragraph_style = Some(style);
+        self
+    }
+
     /// Builder-style method for adding an [`Attribute`] to a range of text.
     pub fn with_attribute(mut self, range: impl RangeBounds<usize>, attr: Attribute) -> Self {
         self.add_attribute(range, attr);
@@ -69,6 +91,110 @@ impl RichText {
         let range = util::resolve_range(range, self.buffer.len());
         Arc::make_mut(&mut self.attrs).add(range, attr);
     }
+
+    /// Remove attributes of the same kind as `kind` from the provided range of text.
+    pub(crate) fn remove_attributes(&mut self, range: Range<usize>, kind: &Attribute) {
+        Arc::make_mut(&mut self.attrs).remove(range, kind);
+    }
+
+    /// The attributes that apply at `offset`.
+    pub(crate) fn attributes_at(&self, offset: usize) -> Vec<Attribute> {
+        self.attrs.attributes_at(offset)
+    }
+}
+
+impl EditableText for RichText {
+    fn cursor(&self, position: usize) -> Option<StringCursor<'_>> {
+        let mut cursor = StringCursor::new(&self.buffer);
+        cursor.set(position);
+        cursor.is_boundary().then_some(cursor)
+    }
+
+    /// Replace `range` with `new`; the new text is unstyled, and links and
+    /// attachments that overlap the range are removed.
+    fn edit(&mut self, range: Range<usize>, new: impl Into<String>) {
+        let new = new.into();
+        if range.is_empty() && new.is_empty() {
+            return;
+        }
+        let mut buffer = self.buffer.to_string();
+        buffer.replace_range(range.clone(), &new);
+        self.buffer = buffer.into();
+        Arc::make_mut(&mut self.attrs).edit(range.clone(), new.len());
+        if !self.links.is_empty() {
+            self.links = self
+                .links
+                .iter()
+                .filter_map(|link| {
+                    let moved = range_after_edit(link.range(), &range, new.len())?;
+                    Some(Link::new(moved, link.command.clone()))
+                })
+                .collect();
+        }
+        if !self.attachments.is_empty() {
+            self.attachments = self
+                .attachments
+                .iter()
+                .filter_map(|attachment| {
+                    let moved = range_after_edit(attachment.range(), &range, new.len())?;
+                    Some(Attachment::new(
+                        moved,
+                        attachment.size,
+                        attachment.content.clone(),
+                    ))
+                })
+                .collect();
+        }
+    }
+
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        self.buffer.get(range).map(Cow::from)
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn prev_word_offset(&self, offset: usize) -> Option<usize> {
+        prev_word_offset(&self.buffer, offset)
+    }
+
+    fn next_word_offset(&self, offset: usize) -> Option<usize> {
+        next_word_offset(&self.buffer, offset)
+    }
+
+    fn prev_grapheme_offset(&self, offset: usize) -> Option<usize> {
+        prev_grapheme_offset(&self.buffer, offset)
+    }
+
+    fn next_grapheme_offset(&self, offset: usize) -> Option<usize> {
+        next_grapheme_offset(&self.buffer, offset)
+    }
+
+    fn prev_codepoint_offset(&self, offset: usize) -> Option<usize> {
+        self.cursor(offset)?.prev()
+    }
+
+    fn next_codepoint_offset(&self, offset: usize) -> Option<usize> {
+        let mut cursor = self.cursor(offset)?;
+        cursor.next().map(|_| cursor.pos())
+    }
+
+    fn preceding_line_break(&self, offset: usize) -> usize {
+        preceding_line_break(&self.buffer, offset)
+    }
+
+    fn next_line_break(&self, offset: usize) -> usize {
+        next_line_break(&self.buffer, offset)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    fn from_str(s: &str) -> Self {
+        RichText::new(s.into())
+    }
 }
 
 impl PietTextStorage for RichText {
@@ -83,6 +209,15 @@ impl TextStorage for RichText {
         mut builder: PietTextLayoutBuilder,
         env: &Env,
     ) -> PietTextLayoutBuilder {
+        // Links are styled first, so that the attributes of their text take precedence.
+        if !self.links.is_empty() {
+            let color = env.get(theme::LINK_COLOR);
+            for link in self.links.iter() {
+                builder = builder
+                    .range_attribute(link.range(), TextAttribute::TextColor(color))
+                    .range_attribute(link.range(), TextAttribute::Underline(true));
+            }
+        }
         for (range, attr) in self.attrs.to_piet_attrs(env) {
             builder = builder.range_attribute(range, attr);
         }
@@ -91,11 +226,38 @@ impl TextStorage for RichText {
 
     fn env_update(&self, ctx: &EnvUpdateCtx) -> bool {
         self.attrs.env_update(ctx)
+            || (!self.links.is_empty() && ctx.env_key_changed(&theme::LINK_COLOR))
     }
 
     fn links(&self) -> &[Link] {
         &self.links
     }
+
+    fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    fn paragraph_style(&self) -> Option<ParagraphStyle> {
+        self.paragraph_style
+    }
+}
+
+/// Where `span` is after `edit` is replaced with `new_len` bytes, or `None`
+/// if they overlap.
+fn range_after_edit(
+    span: Range<usize>,
+    edit: &Range<usize>,
+    new_len: usize,
+) -> Option<Range<usize>> {
+    if span.end <= edit.start {
+        Some(span)
+    } else if span.start >= edit.end {
+        let start = span.start - edit.len() + new_len;
+        let end = span.end - edit.len() + new_len;
+        Some(start..end)
+    } else {
+        None
+    }
 }
 
 /// A builder for creating [`RichText`] objects.
@@ -123,6 +285,8 @@ pub struct RichTextBuilder {
     buffer: String,
     attrs: AttributeSpans,
     links: Vec<Link>,
+    attachments: Vec<Attachment>,
+    paragraph_style: Option<ParagraphStyle>,
 }
 
 impl RichTextBuilder {
@@ -135,17 +299,60 @@ impl RichTextBuilder {
     ///
     /// This method returns a [`AttributesAdder`] that can be used to style the newly
     /// added string slice.
-    pub fn push(&mut self, string: &str) -> AttributesAdder {
+    pub fn push(&mut self, string: &str) -> AttributesAdder<'_> {
         let range = self.buffer.len()..(self.buffer.len() + string.len());
         self.buffer.push_str(string);
         self.add_attributes_for_range(range)
     }
 
+    /// Append an image to the end of the text, drawn at `size` with its
+    /// bottom edge on the baseline.
+    ///
+    /// The returned [`AttributesAdder`] can be used to make the image a link,
+    /// for instance. Setting its font size would change the space it takes up.
+    pub fn push_image(&mut self, image: ImageBuf, size: impl Into<Size>) -> AttributesAdder<'_> {
+        self.push_attachment(size.into(), AttachmentContent::Image(image))
+    }
+
+    /// Append space for a widget to the end of the text, at `size`.
+    ///
+    /// The widget is added to the label showing the text, with
+    /// [`RawLabel::with_inline_widget`] and the same `key`; its baseline is
+    /// aligned with the text's.
+    ///
+    /// [`RawLabel::with_inline_widget`]: crate::widget::RawLabel::with_inline_widget
+    pub fn push_widget(
+        &mut self,
+        key: impl Into<ArcStr>,
+        size: impl Into<Size>,
+    ) -> AttributesAdder<'_> {
+        self.push_attachment(size.into(), AttachmentContent::Widget(key.into()))
+    }
+
+    fn push_attachment(&mut self, size: Size, content: AttachmentContent) -> AttributesAdder<'_> {
+        // As many em spaces as fit across the attachment, each at least as tall as it.
+        let count = if size.height > 0.0 {
+            (size.width / size.height).floor().max(1.0) as usize
+        } else {
+            1
+        };
+        let start = self.buffer.len();
+        for _ in 0..count {
+            self.buffer.push(ATTACHMENT_CHAR);
+        }
+        let range = start..self.buffer.len();
+        self.attachments
+            .push(Attachment::new(range.clone(), size, content));
+        let mut adder = self.add_attributes_for_range(range);
+        adder.size(size.width / count as f64);
+        adder
+    }
+
     /// Glue for usage of the write! macro.
     ///
     /// This method should generally not be invoked manually, but rather through the write! macro itself.
     #[doc(hidden)]
-    pub fn write_fmt(&mut self, fmt: std::fmt::Arguments<'_>) -> AttributesAdder {
+    pub fn write_fmt(&mut self, fmt: std::fmt::Arguments<'_>) -> AttributesAdder<'_> {
         use std::fmt::Write;
         let start = self.buffer.len();
         self.buffer
@@ -157,7 +364,10 @@ impl RichTextBuilder {
     /// Get an [`AttributesAdder`] for the given range.
     ///
     /// This can be used to modify styles for a given range after it has been added.
-    pub fn add_attributes_for_range(&mut self, range: impl RangeBounds<usize>) -> AttributesAdder {
+    pub fn add_attributes_for_range(
+        &mut self,
+        range: impl RangeBounds<usize>,
+    ) -> AttributesAdder<'_> {
         let range = util::resolve_range(range, self.buffer.len());
         AttributesAdder {
             rich_text_builder: self,
@@ -165,12 +375,19 @@ impl RichTextBuilder {
         }
     }
 
+    /// Set the [`ParagraphStyle`] of the whole text.
+    pub fn set_paragraph_style(&mut self, style: ParagraphStyle) {
+        self.paragraph_style = Some(style);
+    }
+
     /// Build the `RichText`.
     pub fn build(self) -> RichText {
         RichText {
             buffer: self.buffer.into(),
             attrs: self.attrs.into(),
             links: self.links.into(),
+            attachments: self.attachments.into(),
+            paragraph_style: self.paragraph_style,
         }
     }
 }
@@ -238,13 +455,26 @@ impl AttributesAdder<'_> {
         self
     }
 
-    /// Add a [`Link`] attribute.
+    /// Add a [`Link`] attribute, which submits `command` when clicked.
+    ///
+    /// Links are underlined and drawn in [`LINK_COLOR`], unless other
+    /// attributes of the text say otherwise.
     ///
     /// [`Link`]: super::attribute::Link
+    /// [`LINK_COLOR`]: crate::theme::LINK_COLOR
     pub fn link(&mut self, command: impl Into<Command>) -> &mut Self {
         self.rich_text_builder
             .links
             .push(Link::new(self.range.clone(), command.into()));
         self
     }
+
+    /// Add a [`Link`] attribute that opens `url` when clicked, by submitting
+    /// [`OPEN_URL`].
+    ///
+    /// [`Link`]: super::attribute::Link
+    /// [`OPEN_URL`]: crate::commands::OPEN_URL
+    pub fn link_url(&mut self, url: impl Into<String>) -> &mut Self {
+        self.link(crate::commands::OPEN_URL.with(url.into()))
+    }
 }
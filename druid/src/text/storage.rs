@@ -20,7 +20,8 @@ use crate::env::KeyLike;
 use crate::piet::{PietTextLayoutBuilder, TextStorage as PietTextStorage};
 use crate::{Data, Env};
 
-use super::attribute::Link;
+use super::attribute::{Attachment, Link};
+use super::ParagraphStyle;
 use crate::UpdateCtx;
 
 /// A type that represents text that can be displayed.
@@ -52,6 +53,26 @@ pub trait TextStorage: PietTextStorage + Data {
     fn links(&self) -> &[Link] {
         &[]
     }
+
+    /// Any [`Attachment`]s embedded in this text.
+    ///
+    /// Like links, attachments are managed in Druid rather than in [`piet`]; the
+    /// text should also style the range of each attachment to take up its space.
+    ///
+    /// [`Attachment`]: super::attribute::Attachment
+    /// [`piet`]: crate::piet
+    fn attachments(&self) -> &[Attachment] {
+        &[]
+    }
+
+    /// The [`ParagraphStyle`] of this text, if it has one.
+    ///
+    /// This takes precedence over the style set on the [`TextLayout`].
+    ///
+    /// [`TextLayout`]: super::TextLayout
+    fn paragraph_style(&self) -> Option<ParagraphStyle> {
+        None
+    }
 }
 
 /// Provides information about keys change for more fine grained invalidation
@@ -16,11 +16,17 @@
 
 #![allow(missing_docs)]
 
+use std::sync::Arc;
+
 use crate::kurbo::RoundedRectRadii;
 
 use crate::piet::Color;
 
-use crate::{Env, FontDescriptor, FontFamily, FontStyle, FontWeight, Insets, Key};
+use crate::widget::{Container, EnvScope};
+use crate::{
+    ArcStr, ColorScheme, Data, Env, FontDescriptor, FontFamily, FontStyle, FontWeight, Insets, Key,
+    Selector, ValueType, Widget,
+};
 
 pub const WINDOW_BACKGROUND_COLOR: Key<Color> =
     Key::new("org.linebender.druid.theme.window_background_color");
@@ -55,6 +61,8 @@ pub const BUTTON_BORDER_RADIUS: Key<RoundedRectRadii> =
     Key::new("org.linebender.druid.theme.button_radius");
 pub const BUTTON_BORDER_WIDTH: Key<f64> =
     Key::new("org.linebender.druid.theme.button_border_width");
+/// The space between the border of a button and its label.
+pub const BUTTON_INSETS: Key<Insets> = Key::new("org.linebender.druid.theme.button_insets");
 pub const BORDER_DARK: Key<Color> = Key::new("org.linebender.druid.theme.border_dark");
 pub const BORDER_LIGHT: Key<Color> = Key::new("org.linebender.druid.theme.border_light");
 #[doc(hidden)]
@@ -67,6 +75,36 @@ pub const SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR: Key<Color> =
 pub const SELECTION_TEXT_COLOR: Key<Color> =
     Key::new("org.linebender.druid.theme.selection_text_color");
 pub const CURSOR_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.cursor_color");
+/// The color of links in [`RichText`], unless their text color is set.
+///
+/// [`RichText`]: crate::text::RichText
+pub const LINK_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.link_color");
+/// The background of highlighted text, such as the matches of a search.
+pub const HIGHLIGHTED_TEXT_BACKGROUND_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.highlighted_text_background_color");
+/// The background of text that is being composed with an input method.
+pub const COMPOSITION_BACKGROUND_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.composition_background_color");
+/// The color of the outline or highlight that shows which widget has keyboard focus.
+pub const FOCUS_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.focus_color");
+/// The width of the outline drawn by [`PaintCtx::paint_focus_decoration`].
+///
+/// [`PaintCtx::paint_focus_decoration`]: crate::PaintCtx::paint_focus_decoration
+pub const FOCUS_OUTLINE_WIDTH: Key<f64> =
+    Key::new("org.linebender.druid.theme.focus_outline_width");
+/// The fill of a selected item in a custom widget.
+///
+/// See [`PaintCtx::paint_selection_decoration`].
+///
+/// [`PaintCtx::paint_selection_decoration`]: crate::PaintCtx::paint_selection_decoration
+pub const SELECTION_FILL_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.selection_fill_color");
+/// The outline of a selected item in a custom widget.
+pub const SELECTION_STROKE_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.selection_stroke_color");
+/// The width of the outline of a selected item in a custom widget.
+pub const SELECTION_OUTLINE_WIDTH: Key<f64> =
+    Key::new("org.linebender.druid.theme.selection_outline_width");
 
 pub const TEXT_SIZE_NORMAL: Key<f64> = Key::new("org.linebender.druid.theme.text_size_normal");
 pub const TEXT_SIZE_LARGE: Key<f64> = Key::new("org.linebender.druid.theme.text_size_large");
@@ -93,6 +131,12 @@ pub const TEXTBOX_BORDER_RADIUS: Key<RoundedRectRadii> =
 pub const TEXTBOX_BORDER_WIDTH: Key<f64> =
     Key::new("org.linebender.druid.theme.textbox_border_width");
 pub const TEXTBOX_INSETS: Key<Insets> = Key::new("org.linebender.druid.theme.textbox_insets");
+/// The character that a [`PasswordBox`] shows in place of each character of
+/// its text. Only the first character of the string is used.
+///
+/// [`PasswordBox`]: crate::widget::PasswordBox
+pub const PASSWORD_MASK_CHAR: Key<ArcStr> =
+    Key::new("org.linebender.druid.theme.password_mask_char");
 
 /// The default horizontal spacing between widgets.
 pub const WIDGET_PADDING_HORIZONTAL: Key<f64> =
@@ -104,6 +148,59 @@ pub const WIDGET_PADDING_VERTICAL: Key<f64> =
 /// of a widget; for instance between a checkbox and its label.
 pub const WIDGET_CONTROL_COMPONENT_PADDING: Key<f64> =
     Key::new("org.linebender.druid.theme.widget-padding-control-label");
+/// Whether read-only text, such as a [`Label`], can be focused and navigated with a
+/// caret, to select and copy it with the keyboard. This is `false` by default.
+///
+/// [`Label`]: crate::widget::Label
+pub const CARET_BROWSING: Key<bool> = Key::new("org.linebender.druid.theme.caret_browsing");
+/// Whether animations that move content around should be skipped, for users who
+/// prefer reduced motion. This is `false` by default.
+///
+/// When this is set, transitions such as those of [`Tabs`], [`Collapsible`], [`Split`]
+/// and [`Switch`] jump straight to their end state. To keep a particular animation,
+/// set this back to `false` around it with an [`EnvScope`].
+///
+/// [`Tabs`]: crate::widget::Tabs
+/// [`Collapsible`]: crate::widget::Collapsible
+/// [`Split`]: crate::widget::Split
+/// [`Switch`]: crate::widget::Switch
+/// [`EnvScope`]: crate::widget::EnvScope
+pub const REDUCED_MOTION: Key<bool> = Key::new("org.linebender.druid.theme.reduced_motion");
+
+/// The default color of the data in a [`chart`](crate::widget::chart).
+pub const CHART_SERIES_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.chart_series_color");
+/// The color of the axes, ticks and labels of a chart.
+pub const CHART_AXIS_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.chart_axis_color");
+/// The color of the grid lines drawn behind the data of a chart.
+pub const CHART_GRID_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.chart_grid_color");
+/// The text size of chart labels and tooltips.
+pub const CHART_LABEL_SIZE: Key<f64> = Key::new("org.linebender.druid.theme.chart_label_size");
+
+/// The color of the error message shown below an invalid [`FormField`].
+///
+/// [`FormField`]: crate::widget::FormField
+pub const FORM_ERROR_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.form_error_color");
+/// The text size of the error message shown below an invalid form field.
+pub const FORM_ERROR_TEXT_SIZE: Key<f64> =
+    Key::new("org.linebender.druid.theme.form_error_text_size");
+
+/// The background of the selected segments of a [`SegmentedControl`].
+///
+/// [`SegmentedControl`]: crate::widget::SegmentedControl
+pub const SEGMENT_SELECTED_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.segment_selected_color");
+/// The background of a segment of a segmented control under the mouse.
+pub const SEGMENT_HOVER_COLOR: Key<Color> =
+    Key::new("org.linebender.druid.theme.segment_hover_color");
+/// The background of a [`Badged`] badge.
+///
+/// [`Badged`]: crate::widget::Badged
+pub const BADGE_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.badge_color");
+/// The text and icon color of a [`Badged`] badge.
+///
+/// [`Badged`]: crate::widget::Badged
+pub const BADGE_TEXT_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.badge_text_color");
 
 pub const SCROLLBAR_COLOR: Key<Color> = Key::new("org.linebender.druid.theme.scrollbar_color");
 pub const SCROLLBAR_BORDER_COLOR: Key<Color> =
@@ -122,36 +219,418 @@ pub const SCROLLBAR_EDGE_WIDTH: Key<f64> =
 /// scrollbar's primary axis.
 pub const SCROLLBAR_MIN_SIZE: Key<f64> = Key::new("org.linebender.theme.scrollbar_min_size");
 
+/// A complete set of colors for the built-in widgets.
+///
+/// The initial theme is [`ThemePreset::Dark`]. A preset can be applied to the whole
+/// app with [`AppLauncher::configure_env`], or to part of it with [`ThemePreset::scope`],
+/// which can switch presets while the app is running:
+///
+/// ```
+/// use druid::theme::ThemePreset;
+/// use druid::widget::{Flex, Label, SegmentedControl};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     theme: ThemePreset,
+/// }
+///
+/// fn ui() -> impl Widget<AppState> {
+///     let picker = SegmentedControl::new(ThemePreset::ALL.map(|preset| (preset.name(), preset)))
+///         .lens(AppState::theme);
+///     let content = Flex::column()
+///         .with_child(picker)
+///         .with_child(Label::new("Hello"));
+///     ThemePreset::scope(|data: &AppState| data.theme, content)
+/// }
+/// ```
+///
+/// [`AppLauncher::configure_env`]: crate::AppLauncher::configure_env
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Data)]
+pub enum ThemePreset {
+    /// Light text on dark grey; the initial theme.
+    #[default]
+    Dark,
+    /// Dark text on white and light grey.
+    Light,
+    /// White text on black, with bright borders and focus outlines.
+    HighContrast,
+    /// The dark variant of Ethan Schoonover's Solarized palette.
+    Solarized,
+}
+
+impl ThemePreset {
+    /// Every preset, for instance to offer them in a preferences dialog.
+    pub const ALL: [ThemePreset; 4] = [
+        ThemePreset::Dark,
+        ThemePreset::Light,
+        ThemePreset::HighContrast,
+        ThemePreset::Solarized,
+    ];
+
+    /// The name of the preset, to show to the user.
+    pub fn name(self) -> &'static str {
+        match self {
+            ThemePreset::Dark => "Dark",
+            ThemePreset::Light => "Light",
+            ThemePreset::HighContrast => "High contrast",
+            ThemePreset::Solarized => "Solarized",
+        }
+    }
+
+    /// Set every color of the theme in `env` to this preset's.
+    pub fn apply(self, env: &mut Env) {
+        for (key, color) in self.colors() {
+            env.set(key, color);
+        }
+    }
+
+    /// Apply the preset returned by `preset` to `child`, which is drawn on the
+    /// preset's [`WINDOW_BACKGROUND_COLOR`].
+    pub fn scope<T: Data, W: Widget<T> + 'static>(
+        preset: impl Fn(&T) -> ThemePreset + 'static,
+        child: W,
+    ) -> EnvScope<T, Container<T>> {
+        EnvScope::new(
+            move |env, data| preset(data).apply(env),
+            Container::new(child).background(WINDOW_BACKGROUND_COLOR),
+        )
+    }
+
+    fn colors(self) -> [(Key<Color>, Color); 38] {
+        match self {
+            ThemePreset::Dark => [
+                (WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29)),
+                (TEXT_COLOR, Color::rgb8(0xf0, 0xf0, 0xea)),
+                (DISABLED_TEXT_COLOR, Color::rgb8(0xa0, 0xa0, 0x9a)),
+                (PLACEHOLDER_COLOR, Color::rgb8(0x80, 0x80, 0x80)),
+                (PRIMARY_LIGHT, Color::rgb8(0x5c, 0xc4, 0xff)),
+                (PRIMARY_DARK, Color::rgb8(0x00, 0x8d, 0xdd)),
+                (BACKGROUND_LIGHT, Color::rgb8(0x3a, 0x3a, 0x3a)),
+                (BACKGROUND_DARK, Color::rgb8(0x31, 0x31, 0x31)),
+                (FOREGROUND_LIGHT, Color::rgb8(0xf9, 0xf9, 0xf9)),
+                (FOREGROUND_DARK, Color::rgb8(0xbf, 0xbf, 0xbf)),
+                (DISABLED_FOREGROUND_LIGHT, Color::rgb8(0x89, 0x89, 0x89)),
+                (DISABLED_FOREGROUND_DARK, Color::rgb8(0x6f, 0x6f, 0x6f)),
+                (BUTTON_DARK, Color::BLACK),
+                (BUTTON_LIGHT, Color::rgb8(0x21, 0x21, 0x21)),
+                (DISABLED_BUTTON_DARK, Color::grey8(0x28)),
+                (DISABLED_BUTTON_LIGHT, Color::grey8(0x38)),
+                (BORDER_DARK, Color::rgb8(0x3a, 0x3a, 0x3a)),
+                (BORDER_LIGHT, Color::rgb8(0xa1, 0xa1, 0xa1)),
+                (
+                    SELECTED_TEXT_BACKGROUND_COLOR,
+                    Color::rgb8(0x43, 0x70, 0xA8),
+                ),
+                (SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::grey8(0x74)),
+                (SELECTION_TEXT_COLOR, Color::rgb8(0x00, 0x00, 0x00)),
+                (CURSOR_COLOR, Color::WHITE),
+                (FOCUS_COLOR, Color::rgb8(0x5c, 0xc4, 0xff)),
+                (SELECTION_FILL_COLOR, Color::rgba8(0x5c, 0xc4, 0xff, 0x40)),
+                (SELECTION_STROKE_COLOR, Color::rgb8(0x5c, 0xc4, 0xff)),
+                (SCROLLBAR_COLOR, Color::rgb8(0xff, 0xff, 0xff)),
+                (SCROLLBAR_BORDER_COLOR, Color::rgb8(0x77, 0x77, 0x77)),
+                (CHART_SERIES_COLOR, Color::rgb8(0x5c, 0xc4, 0xff)),
+                (CHART_AXIS_COLOR, Color::rgb8(0xa0, 0xa0, 0x9a)),
+                (CHART_GRID_COLOR, Color::rgb8(0x3a, 0x3a, 0x3a)),
+                (FORM_ERROR_COLOR, Color::rgb8(0xf0, 0x5d, 0x5d)),
+                (BADGE_COLOR, Color::rgb8(0xe5, 0x39, 0x35)),
+                (BADGE_TEXT_COLOR, Color::WHITE),
+                (SEGMENT_SELECTED_COLOR, Color::rgb8(0x00, 0x8d, 0xdd)),
+                (SEGMENT_HOVER_COLOR, Color::grey8(0x3a)),
+                (LINK_COLOR, Color::rgb8(0x5c, 0xc4, 0xff)),
+                (
+                    HIGHLIGHTED_TEXT_BACKGROUND_COLOR,
+                    Color::rgba8(0xff, 0xd5, 0x4f, 0x50),
+                ),
+                (
+                    COMPOSITION_BACKGROUND_COLOR,
+                    Color::rgba8(0x5c, 0xc4, 0xff, 0x30),
+                ),
+            ],
+            ThemePreset::Light => [
+                (WINDOW_BACKGROUND_COLOR, Color::rgb8(0xf3, 0xf3, 0xf3)),
+                (TEXT_COLOR, Color::rgb8(0x1e, 0x1e, 0x1e)),
+                (DISABLED_TEXT_COLOR, Color::rgb8(0x8a, 0x8a, 0x8a)),
+                (PLACEHOLDER_COLOR, Color::rgb8(0x9a, 0x9a, 0x9a)),
+                (PRIMARY_LIGHT, Color::rgb8(0x33, 0x96, 0xe6)),
+                (PRIMARY_DARK, Color::rgb8(0x00, 0x5a, 0x9e)),
+                (BACKGROUND_LIGHT, Color::WHITE),
+                (BACKGROUND_DARK, Color::rgb8(0xe8, 0xe8, 0xe8)),
+                (FOREGROUND_LIGHT, Color::rgb8(0x2b, 0x2b, 0x2b)),
+                (FOREGROUND_DARK, Color::rgb8(0x4a, 0x4a, 0x4a)),
+                (DISABLED_FOREGROUND_LIGHT, Color::rgb8(0x9a, 0x9a, 0x9a)),
+                (DISABLED_FOREGROUND_DARK, Color::rgb8(0xb5, 0xb5, 0xb5)),
+                (BUTTON_DARK, Color::rgb8(0xdc, 0xdc, 0xdc)),
+                (BUTTON_LIGHT, Color::rgb8(0xfa, 0xfa, 0xfa)),
+                (DISABLED_BUTTON_DARK, Color::grey8(0xe6)),
+                (DISABLED_BUTTON_LIGHT, Color::grey8(0xf0)),
+                (BORDER_DARK, Color::rgb8(0xc8, 0xc8, 0xc8)),
+                (BORDER_LIGHT, Color::rgb8(0x7a, 0x7a, 0x7a)),
+                (
+                    SELECTED_TEXT_BACKGROUND_COLOR,
+                    Color::rgb8(0xad, 0xd6, 0xff),
+                ),
+                (SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::grey8(0xd4)),
+                (SELECTION_TEXT_COLOR, Color::rgb8(0x00, 0x00, 0x00)),
+                (CURSOR_COLOR, Color::BLACK),
+                (FOCUS_COLOR, Color::rgb8(0x00, 0x77, 0xd4)),
+                (SELECTION_FILL_COLOR, Color::rgba8(0x00, 0x77, 0xd4, 0x30)),
+                (SELECTION_STROKE_COLOR, Color::rgb8(0x00, 0x77, 0xd4)),
+                (SCROLLBAR_COLOR, Color::rgb8(0x30, 0x30, 0x30)),
+                (SCROLLBAR_BORDER_COLOR, Color::rgb8(0xc8, 0xc8, 0xc8)),
+                (CHART_SERIES_COLOR, Color::rgb8(0x00, 0x77, 0xd4)),
+                (CHART_AXIS_COLOR, Color::rgb8(0x6a, 0x6a, 0x6a)),
+                (CHART_GRID_COLOR, Color::rgb8(0xdc, 0xdc, 0xdc)),
+                (FORM_ERROR_COLOR, Color::rgb8(0xc6, 0x28, 0x28)),
+                (BADGE_COLOR, Color::rgb8(0xd3, 0x2f, 0x2f)),
+                (BADGE_TEXT_COLOR, Color::WHITE),
+                (SEGMENT_SELECTED_COLOR, Color::rgb8(0x9c, 0xc9, 0xf5)),
+                (SEGMENT_HOVER_COLOR, Color::grey8(0xe0)),
+                (LINK_COLOR, Color::rgb8(0x00, 0x5a, 0x9e)),
+                (
+                    HIGHLIGHTED_TEXT_BACKGROUND_COLOR,
+                    Color::rgba8(0xff, 0xd5, 0x4f, 0x90),
+                ),
+                (
+                    COMPOSITION_BACKGROUND_COLOR,
+                    Color::rgba8(0x00, 0x77, 0xd4, 0x20),
+                ),
+            ],
+            ThemePreset::HighContrast => [
+                (WINDOW_BACKGROUND_COLOR, Color::BLACK),
+                (TEXT_COLOR, Color::WHITE),
+                (DISABLED_TEXT_COLOR, Color::rgb8(0xc0, 0xc0, 0xc0)),
+                (PLACEHOLDER_COLOR, Color::rgb8(0xc0, 0xc0, 0xc0)),
+                (PRIMARY_LIGHT, Color::rgb8(0x1a, 0xeb, 0xff)),
+                (PRIMARY_DARK, Color::rgb8(0x00, 0x9e, 0xb0)),
+                (BACKGROUND_LIGHT, Color::BLACK),
+                (BACKGROUND_DARK, Color::BLACK),
+                (FOREGROUND_LIGHT, Color::WHITE),
+                (FOREGROUND_DARK, Color::WHITE),
+                (DISABLED_FOREGROUND_LIGHT, Color::rgb8(0xa0, 0xa0, 0xa0)),
+                (DISABLED_FOREGROUND_DARK, Color::rgb8(0x80, 0x80, 0x80)),
+                (BUTTON_DARK, Color::BLACK),
+                (BUTTON_LIGHT, Color::BLACK),
+                (DISABLED_BUTTON_DARK, Color::grey8(0x1a)),
+                (DISABLED_BUTTON_LIGHT, Color::grey8(0x1a)),
+                (BORDER_DARK, Color::WHITE),
+                (BORDER_LIGHT, Color::rgb8(0xff, 0xff, 0x00)),
+                (
+                    SELECTED_TEXT_BACKGROUND_COLOR,
+                    Color::rgb8(0x1a, 0xeb, 0xff),
+                ),
+                (SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::grey8(0x80)),
+                (SELECTION_TEXT_COLOR, Color::rgb8(0x00, 0x00, 0x00)),
+                (CURSOR_COLOR, Color::WHITE),
+                (FOCUS_COLOR, Color::rgb8(0xff, 0xff, 0x00)),
+                (SELECTION_FILL_COLOR, Color::rgba8(0x1a, 0xeb, 0xff, 0x50)),
+                (SELECTION_STROKE_COLOR, Color::rgb8(0x1a, 0xeb, 0xff)),
+                (SCROLLBAR_COLOR, Color::WHITE),
+                (SCROLLBAR_BORDER_COLOR, Color::BLACK),
+                (CHART_SERIES_COLOR, Color::rgb8(0x1a, 0xeb, 0xff)),
+                (CHART_AXIS_COLOR, Color::WHITE),
+                (CHART_GRID_COLOR, Color::rgb8(0x80, 0x80, 0x80)),
+                (FORM_ERROR_COLOR, Color::rgb8(0xff, 0x6b, 0x6b)),
+                (BADGE_COLOR, Color::rgb8(0xff, 0x00, 0x00)),
+                (BADGE_TEXT_COLOR, Color::WHITE),
+                (SEGMENT_SELECTED_COLOR, Color::rgb8(0x00, 0x50, 0xa0)),
+                (SEGMENT_HOVER_COLOR, Color::grey8(0x33)),
+                (LINK_COLOR, Color::rgb8(0x1a, 0xeb, 0xff)),
+                (
+                    HIGHLIGHTED_TEXT_BACKGROUND_COLOR,
+                    Color::rgba8(0xff, 0xff, 0x00, 0x70),
+                ),
+                (
+                    COMPOSITION_BACKGROUND_COLOR,
+                    Color::rgba8(0x1a, 0xeb, 0xff, 0x40),
+                ),
+            ],
+            ThemePreset::Solarized => [
+                (WINDOW_BACKGROUND_COLOR, Color::rgb8(0x00, 0x2b, 0x36)),
+                (TEXT_COLOR, Color::rgb8(0x93, 0xa1, 0xa1)),
+                (DISABLED_TEXT_COLOR, Color::rgb8(0x58, 0x6e, 0x75)),
+                (PLACEHOLDER_COLOR, Color::rgb8(0x58, 0x6e, 0x75)),
+                (PRIMARY_LIGHT, Color::rgb8(0x26, 0x8b, 0xd2)),
+                (PRIMARY_DARK, Color::rgb8(0x1b, 0x6a, 0xa3)),
+                (BACKGROUND_LIGHT, Color::rgb8(0x07, 0x36, 0x42)),
+                (BACKGROUND_DARK, Color::rgb8(0x00, 0x2b, 0x36)),
+                (FOREGROUND_LIGHT, Color::rgb8(0xee, 0xe8, 0xd5)),
+                (FOREGROUND_DARK, Color::rgb8(0x93, 0xa1, 0xa1)),
+                (DISABLED_FOREGROUND_LIGHT, Color::rgb8(0x65, 0x7b, 0x83)),
+                (DISABLED_FOREGROUND_DARK, Color::rgb8(0x58, 0x6e, 0x75)),
+                (BUTTON_DARK, Color::rgb8(0x00, 0x2b, 0x36)),
+                (BUTTON_LIGHT, Color::rgb8(0x07, 0x36, 0x42)),
+                (DISABLED_BUTTON_DARK, Color::rgb8(0x01, 0x31, 0x3d)),
+                (DISABLED_BUTTON_LIGHT, Color::rgb8(0x07, 0x36, 0x42)),
+                (BORDER_DARK, Color::rgb8(0x0a, 0x43, 0x52)),
+                (BORDER_LIGHT, Color::rgb8(0x58, 0x6e, 0x75)),
+                (
+                    SELECTED_TEXT_BACKGROUND_COLOR,
+                    Color::rgb8(0x2d, 0x5c, 0x69),
+                ),
+                (
+                    SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR,
+                    Color::rgb8(0x1b, 0x47, 0x52),
+                ),
+                (SELECTION_TEXT_COLOR, Color::rgb8(0xfd, 0xf6, 0xe3)),
+                (CURSOR_COLOR, Color::rgb8(0x93, 0xa1, 0xa1)),
+                (FOCUS_COLOR, Color::rgb8(0xb5, 0x89, 0x00)),
+                (SELECTION_FILL_COLOR, Color::rgba8(0x26, 0x8b, 0xd2, 0x40)),
+                (SELECTION_STROKE_COLOR, Color::rgb8(0x26, 0x8b, 0xd2)),
+                (SCROLLBAR_COLOR, Color::rgb8(0x93, 0xa1, 0xa1)),
+                (SCROLLBAR_BORDER_COLOR, Color::rgb8(0x00, 0x2b, 0x36)),
+                (CHART_SERIES_COLOR, Color::rgb8(0x26, 0x8b, 0xd2)),
+                (CHART_AXIS_COLOR, Color::rgb8(0x58, 0x6e, 0x75)),
+                (CHART_GRID_COLOR, Color::rgb8(0x07, 0x36, 0x42)),
+                (FORM_ERROR_COLOR, Color::rgb8(0xdc, 0x32, 0x2f)),
+                (BADGE_COLOR, Color::rgb8(0xdc, 0x32, 0x2f)),
+                (BADGE_TEXT_COLOR, Color::rgb8(0xfd, 0xf6, 0xe3)),
+                (SEGMENT_SELECTED_COLOR, Color::rgb8(0x26, 0x8b, 0xd2)),
+                (SEGMENT_HOVER_COLOR, Color::rgb8(0x0a, 0x41, 0x50)),
+                (LINK_COLOR, Color::rgb8(0x26, 0x8b, 0xd2)),
+                (
+                    HIGHLIGHTED_TEXT_BACKGROUND_COLOR,
+                    Color::rgba8(0xb5, 0x89, 0x00, 0x60),
+                ),
+                (
+                    COMPOSITION_BACKGROUND_COLOR,
+                    Color::rgba8(0x26, 0x8b, 0xd2, 0x30),
+                ),
+            ],
+        }
+    }
+}
+
+/// Switch every [`ThemeScope`] to a new [`Theme`].
+///
+/// Submit this to [`Target::Global`] to restyle every window.
+///
+/// [`ThemeScope`]: crate::widget::ThemeScope
+/// [`Target::Global`]: crate::Target::Global
+pub const SET_THEME: Selector<Theme> = Selector::new("druid-builtin.set-theme");
+
+type SetValue = Arc<dyn Fn(&mut Env)>;
+
+/// A set of theme values that can be switched while the app is running.
+///
+/// A `Theme` is applied by a [`ThemeScope`] to the widgets inside it, and
+/// swapped for another with the [`SET_THEME`] command. Colors can fade from
+/// the old theme to the new one; other values change at once.
+///
+/// ```
+/// use druid::theme::{self, Theme, ThemePreset};
+/// use druid::Color;
+///
+/// let theme = Theme::from(ThemePreset::Light)
+///     .with_color(theme::PRIMARY_LIGHT, Color::rgb8(0xe0, 0x60, 0x20))
+///     .with_value(theme::BUTTON_BORDER_RADIUS, 8.0);
+/// ```
+///
+/// [`ThemeScope`]: crate::widget::ThemeScope
+#[derive(Clone, Default)]
+pub struct Theme {
+    colors: Vec<(Key<Color>, Color)>,
+    values: Vec<SetValue>,
+}
+
+impl Theme {
+    /// Create a theme that changes nothing.
+    pub fn new() -> Self {
+        Theme::default()
+    }
+
+    /// Builder-style method to set the color for `key`.
+    pub fn with_color(mut self, key: Key<Color>, color: impl Into<Color>) -> Self {
+        let color = color.into();
+        match self.colors.iter_mut().find(|(k, _)| k == &key) {
+            Some(entry) => entry.1 = color,
+            None => self.colors.push((key, color)),
+        }
+        self
+    }
+
+    /// Builder-style method to set the value for `key`.
+    ///
+    /// Use [`with_color`] for colors, so that they can fade when the theme
+    /// is switched.
+    ///
+    /// [`with_color`]: Theme::with_color
+    pub fn with_value<V: ValueType + 'static>(mut self, key: Key<V>, value: impl Into<V>) -> Self {
+        let value = value.into();
+        self.values
+            .push(Arc::new(move |env| env.set(key.clone(), value.clone())));
+        self
+    }
+
+    /// Builder-style method to add a function that sets values in the `Env`.
+    #[cfg(feature = "theme-file")]
+    pub(crate) fn with_setter(mut self, set_values: impl Fn(&mut Env) + 'static) -> Self {
+        self.values.push(Arc::new(set_values));
+        self
+    }
+
+    /// Set every value of the theme in `env`.
+    pub fn apply(&self, env: &mut Env) {
+        for set_value in &self.values {
+            set_value(env);
+        }
+        for (key, color) in &self.colors {
+            env.set(key.clone(), *color);
+        }
+    }
+
+    /// The colors that the theme sets.
+    pub fn colors(&self) -> impl Iterator<Item = (&Key<Color>, &Color)> {
+        self.colors.iter().map(|(key, color)| (key, color))
+    }
+}
+
+impl From<ThemePreset> for Theme {
+    fn from(preset: ThemePreset) -> Self {
+        Theme {
+            colors: preset.colors().to_vec(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Theme")
+            .field("colors", &self.colors)
+            .field("values", &self.values.len())
+            .finish()
+    }
+}
+
+/// The themes that the app switches between with the system color scheme.
+///
+/// See [`AppLauncher::follow_system_theme`].
+///
+/// [`AppLauncher::follow_system_theme`]: crate::AppLauncher::follow_system_theme
+pub(crate) struct SystemThemes {
+    pub(crate) light: Theme,
+    pub(crate) dark: Theme,
+}
+
+impl SystemThemes {
+    /// The theme for `scheme`; the light theme if there is no preference.
+    pub(crate) fn for_scheme(&self, scheme: ColorScheme) -> &Theme {
+        match scheme {
+            ColorScheme::Dark => &self.dark,
+            ColorScheme::Light | ColorScheme::NoPreference => &self.light,
+        }
+    }
+}
+
 /// An initial theme.
 pub(crate) fn add_to_env(env: Env) -> Env {
-    env.adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29))
-        .adding(TEXT_COLOR, Color::rgb8(0xf0, 0xf0, 0xea))
-        .adding(DISABLED_TEXT_COLOR, Color::rgb8(0xa0, 0xa0, 0x9a))
-        .adding(PLACEHOLDER_COLOR, Color::rgb8(0x80, 0x80, 0x80))
-        .adding(PRIMARY_LIGHT, Color::rgb8(0x5c, 0xc4, 0xff))
-        .adding(PRIMARY_DARK, Color::rgb8(0x00, 0x8d, 0xdd))
+    let mut env = env
         .adding(PROGRESS_BAR_RADIUS, 4.)
-        .adding(BACKGROUND_LIGHT, Color::rgb8(0x3a, 0x3a, 0x3a))
-        .adding(BACKGROUND_DARK, Color::rgb8(0x31, 0x31, 0x31))
-        .adding(FOREGROUND_LIGHT, Color::rgb8(0xf9, 0xf9, 0xf9))
-        .adding(FOREGROUND_DARK, Color::rgb8(0xbf, 0xbf, 0xbf))
-        .adding(DISABLED_FOREGROUND_LIGHT, Color::rgb8(0x89, 0x89, 0x89))
-        .adding(DISABLED_FOREGROUND_DARK, Color::rgb8(0x6f, 0x6f, 0x6f))
-        .adding(BUTTON_DARK, Color::BLACK)
-        .adding(BUTTON_LIGHT, Color::rgb8(0x21, 0x21, 0x21))
-        .adding(DISABLED_BUTTON_DARK, Color::grey8(0x28))
-        .adding(DISABLED_BUTTON_LIGHT, Color::grey8(0x38))
         .adding(BUTTON_BORDER_RADIUS, 4.)
         .adding(BUTTON_BORDER_WIDTH, 2.)
-        .adding(BORDER_DARK, Color::rgb8(0x3a, 0x3a, 0x3a))
-        .adding(BORDER_LIGHT, Color::rgb8(0xa1, 0xa1, 0xa1))
-        .adding(
-            SELECTED_TEXT_BACKGROUND_COLOR,
-            Color::rgb8(0x43, 0x70, 0xA8),
-        )
-        .adding(SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::grey8(0x74))
-        .adding(SELECTION_TEXT_COLOR, Color::rgb8(0x00, 0x00, 0x00))
-        .adding(CURSOR_COLOR, Color::WHITE)
+        .adding(BUTTON_INSETS, Insets::uniform_xy(8., 2.))
         .adding(TEXT_SIZE_NORMAL, 15.0)
         .adding(TEXT_SIZE_LARGE, 24.0)
         .adding(BASIC_WIDGET_HEIGHT, 18.0)
@@ -159,9 +638,10 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(BORDERED_WIDGET_HEIGHT, 24.0)
         .adding(TEXTBOX_BORDER_RADIUS, 2.)
         .adding(TEXTBOX_BORDER_WIDTH, 1.)
+        .adding(FOCUS_OUTLINE_WIDTH, 2.)
+        .adding(SELECTION_OUTLINE_WIDTH, 1.)
         .adding(TEXTBOX_INSETS, Insets::new(4.0, 4.0, 4.0, 4.0))
-        .adding(SCROLLBAR_COLOR, Color::rgb8(0xff, 0xff, 0xff))
-        .adding(SCROLLBAR_BORDER_COLOR, Color::rgb8(0x77, 0x77, 0x77))
+        .adding(PASSWORD_MASK_CHAR, "\u{2022}")
         .adding(SCROLLBAR_MAX_OPACITY, 0.7)
         .adding(SCROLLBAR_FADE_DELAY, 1500u64)
         .adding(SCROLLBAR_WIDTH, 8.)
@@ -172,6 +652,10 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(WIDGET_PADDING_VERTICAL, 10.0)
         .adding(WIDGET_PADDING_HORIZONTAL, 8.0)
         .adding(WIDGET_CONTROL_COMPONENT_PADDING, 4.0)
+        .adding(CARET_BROWSING, false)
+        .adding(REDUCED_MOTION, false)
+        .adding(CHART_LABEL_SIZE, 12.0)
+        .adding(FORM_ERROR_TEXT_SIZE, 12.0)
         .adding(
             UI_FONT,
             FontDescriptor::new(FontFamily::SYSTEM_UI).with_size(15.0),
@@ -187,5 +671,7 @@ pub(crate) fn add_to_env(env: Env) -> Env {
             FontDescriptor::new(FontFamily::SYSTEM_UI)
                 .with_style(FontStyle::Italic)
                 .with_size(15.0),
-        )
+        );
+    ThemePreset::Dark.apply(&mut env);
+    env
 }
@@ -0,0 +1,359 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Loading the theme from a file, and reloading it while the app runs.
+//!
+//! A theme file is a [TOML] table that maps [`Env`] keys to values. Bare keys
+//! name the keys of the [`theme`] module, without their
+//! `org.linebender.druid.theme.` prefix; other keys are written out in full,
+//! in quotes.
+//!
+//! ```toml
+//! primary_light = "#e06020"
+//! button_border_radius = 8
+//! textbox_insets = [6, 4]
+//! ui-font = { family = "Inter", size = 14 }
+//! "com.example.app.accent" = "#3080ffcc"
+//! ```
+//!
+//! Values are read according to the type of their key:
+//!
+//! * colors are hex strings, such as `"#rgb"`, `"#rrggbb"` or `"#rrggbbaa"`;
+//! * numbers, `bool`s and strings are written as themselves;
+//! * [`Insets`] are a number, `[horizontal, vertical]`, or
+//!   `[left, top, right, bottom]`;
+//! * [`RoundedRectRadii`] are a number, or
+//!   `[top_left, top_right, bottom_right, bottom_left]`;
+//! * a [`Point`] is `[x, y]`, a [`Size`] is `[width, height]` and a [`Rect`]
+//!   is `[x0, y0, x1, y1]`;
+//! * fonts are tables with any of `family`, `size`, `weight` and `italic`,
+//!   which change the current font.
+//!
+//! A key that is not in the `Env` yet gets a color if its value starts with
+//! `#`, and otherwise a number, `bool` or string. A value that does not fit
+//! the type of its key is logged and skipped.
+//!
+//! Load a file with [`Theme::load`], or give it to [`AppLauncher::theme_file`]
+//! to apply it to the whole app. In debug builds the launcher also watches the
+//! file, and applies it again whenever it is saved, so colors, paddings and
+//! fonts can be tuned without recompiling.
+//!
+//! [TOML]: https://toml.io
+//! [`theme`]: crate::theme
+//! [`AppLauncher::theme_file`]: crate::AppLauncher::theme_file
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use tracing::warn;
+
+use crate::kurbo::RoundedRectRadii;
+use crate::theme::Theme;
+use crate::{
+    Color, FontDescriptor, FontFamily, FontStyle, FontWeight, Insets, Key, Point, Rect, Selector,
+    Size, Value,
+};
+
+/// The prefix of the keys in the [`theme`](crate::theme) module.
+const THEME_PREFIX: &str = "org.linebender.druid.theme.";
+
+/// Sent by the watcher of [`AppLauncher::theme_file`], with the time the file
+/// was last modified.
+///
+/// [`AppLauncher::theme_file`]: crate::AppLauncher::theme_file
+pub(crate) const THEME_FILE_CHANGED: Selector<Option<SystemTime>> =
+    Selector::new("druid-builtin.theme-file-changed");
+
+/// An error that occurs while loading a theme file.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not valid TOML.
+    Toml(toml::de::Error),
+    /// The value for a key can not be used in a theme.
+    InvalidValue(String),
+}
+
+impl Theme {
+    /// Create a theme from the contents of a theme file.
+    ///
+    /// See the [module documentation](self) for the format.
+    pub fn from_toml(source: &str) -> Result<Theme, Error> {
+        let table: toml::Table = source.parse().map_err(Error::Toml)?;
+        let mut theme = Theme::new();
+        for (key, value) in table {
+            let key = if key.contains('.') {
+                key
+            } else {
+                format!("{THEME_PREFIX}{key}")
+            };
+            theme = match value {
+                toml::Value::String(hex) if hex.starts_with('#') => {
+                    let color =
+                        Color::from_hex_str(&hex).map_err(|_| Error::InvalidValue(key.clone()))?;
+                    theme.with_color(Key::new(intern(key)), color)
+                }
+                toml::Value::Datetime(_) => return Err(Error::InvalidValue(key)),
+                value => {
+                    theme.with_setter(move |env| match to_env_value(env.get_raw(&key), &value) {
+                        Some(raw) => env.set_raw(&key, raw),
+                        None => warn!("invalid theme value for '{}': {}", key, value),
+                    })
+                }
+            };
+        }
+        Ok(theme)
+    }
+
+    /// Load a theme file.
+    ///
+    /// See the [module documentation](self) for the format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Theme, Error> {
+        let source = std::fs::read_to_string(path).map_err(Error::Io)?;
+        Theme::from_toml(&source)
+    }
+}
+
+/// The theme file of the app, and the theme last loaded from it.
+pub(crate) struct ThemeFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    theme: Theme,
+}
+
+impl ThemeFile {
+    /// Load the theme file at `path`.
+    ///
+    /// If it can't be loaded, the error is logged and the theme is empty.
+    pub(crate) fn load(path: PathBuf) -> ThemeFile {
+        let mut file = ThemeFile {
+            modified: ThemeFile::modified(&path),
+            path,
+            theme: Theme::new(),
+        };
+        match Theme::load(&file.path) {
+            Ok(theme) => file.theme = theme,
+            Err(e) => warn!("failed to load theme file {:?}: {}", file.path, e),
+        }
+        file
+    }
+
+    /// Load the file again if it was `modified` since it was last loaded.
+    ///
+    /// Returns `true` if there is a new theme. If the file can't be loaded,
+    /// the error is logged and the last theme is kept.
+    pub(crate) fn reload(&mut self, modified: Option<SystemTime>) -> bool {
+        if modified.is_none() || modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        match Theme::load(&self.path) {
+            Ok(theme) => {
+                self.theme = theme;
+                true
+            }
+            Err(e) => {
+                warn!("failed to reload theme file {:?}: {}", self.path, e);
+                false
+            }
+        }
+    }
+
+    /// The path of the file.
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The theme last loaded from the file.
+    pub(crate) fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// When the file at `path` was last modified, if it can be read.
+    pub(crate) fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+/// Convert `value` to the type of `current`, or guess a type if there is no
+/// current value.
+fn to_env_value(current: Option<&Value>, value: &toml::Value) -> Option<Value> {
+    let raw = match current {
+        Some(Value::Color(_)) => Value::Color(Color::from_hex_str(value.as_str()?).ok()?),
+        Some(Value::Float(_)) => Value::Float(number(value)?),
+        Some(Value::UnsignedInt(_)) => Value::UnsignedInt(value.as_integer()?.try_into().ok()?),
+        Some(Value::Bool(_)) => Value::Bool(value.as_bool()?),
+        Some(Value::String(_)) => Value::String(value.as_str()?.into()),
+        Some(Value::Insets(_)) => Value::Insets(match numbers(value)?[..] {
+            [all] => Insets::uniform(all),
+            [x, y] => Insets::uniform_xy(x, y),
+            [x0, y0, x1, y1] => Insets::new(x0, y0, x1, y1),
+            _ => return None,
+        }),
+        Some(Value::RoundedRectRadii(_)) => Value::RoundedRectRadii(match numbers(value)?[..] {
+            [all] => RoundedRectRadii::from_single_radius(all),
+            [top_left, top_right, bottom_right, bottom_left] => {
+                RoundedRectRadii::new(top_left, top_right, bottom_right, bottom_left)
+            }
+            _ => return None,
+        }),
+        Some(Value::Point(_)) => match numbers(value)?[..] {
+            [x, y] => Value::Point(Point::new(x, y)),
+            _ => return None,
+        },
+        Some(Value::Size(_)) => match numbers(value)?[..] {
+            [width, height] => Value::Size(Size::new(width, height)),
+            _ => return None,
+        },
+        Some(Value::Rect(_)) => match numbers(value)?[..] {
+            [x0, y0, x1, y1] => Value::Rect(Rect::new(x0, y0, x1, y1)),
+            _ => return None,
+        },
+        Some(Value::Font(current)) => Value::Font(font(current.clone(), value)?),
+        Some(Value::Other(_)) => return None,
+        None => match value {
+            toml::Value::Integer(_) | toml::Value::Float(_) => Value::Float(number(value)?),
+            toml::Value::Boolean(value) => Value::Bool(*value),
+            toml::Value::String(value) => Value::String(value.as_str().into()),
+            toml::Value::Table(_) => Value::Font(font(FontDescriptor::default(), value)?),
+            _ => return None,
+        },
+    };
+    Some(raw)
+}
+
+fn number(value: &toml::Value) -> Option<f64> {
+    match value {
+        toml::Value::Integer(value) => Some(*value as f64),
+        toml::Value::Float(value) => Some(*value),
+        _ => None,
+    }
+}
+
+/// A single number, or an array of numbers.
+fn numbers(value: &toml::Value) -> Option<Vec<f64>> {
+    match value {
+        toml::Value::Array(values) => values.iter().map(number).collect(),
+        value => Some(vec![number(value)?]),
+    }
+}
+
+/// `font`, changed by the fields of the table `value`.
+fn font(mut font: FontDescriptor, value: &toml::Value) -> Option<FontDescriptor> {
+    for (field, value) in value.as_table()? {
+        match field.as_str() {
+            "family" => {
+                font.family = match value.as_str()? {
+                    "system-ui" => FontFamily::SYSTEM_UI,
+                    "serif" => FontFamily::SERIF,
+                    "sans-serif" => FontFamily::SANS_SERIF,
+                    "monospace" => FontFamily::MONOSPACE,
+                    name => FontFamily::new_unchecked(name),
+                }
+            }
+            "size" => font.size = number(value)?,
+            "weight" => font.weight = FontWeight::new(value.as_integer()?.try_into().ok()?),
+            "italic" => {
+                font.style = if value.as_bool()? {
+                    FontStyle::Italic
+                } else {
+                    FontStyle::Regular
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some(font)
+}
+
+/// Leak `key`, so that it can name a [`Key`].
+///
+/// Each key is only leaked once, however often the file is reloaded.
+fn intern(key: String) -> &'static str {
+    static KEYS: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut keys = KEYS.lock().unwrap();
+    if let Some(key) = keys.get(key.as_str()) {
+        return key;
+    }
+    let key: &'static str = Box::leak(key.into_boxed_str());
+    keys.insert(key);
+    key
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "Could not read the theme file: {err}"),
+            Error::Toml(err) => write!(f, "Invalid theme file: {err}"),
+            Error::InvalidValue(key) => write!(f, "Invalid value for '{key}'"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{theme, Env};
+
+    #[test]
+    fn values_take_the_type_of_their_key() {
+        let source = r##"
+            primary_light = "#102030"
+            button_border_radius = [1, 2, 3, 4]
+            textbox_insets = [6, 4]
+            scrollbar_fade_delay = 300
+            ui-font = { family = "monospace", size = 20, italic = true }
+            "com.example.spacing" = 3
+        "##;
+        let theme = Theme::from_toml(source).unwrap();
+        let mut env = theme::add_to_env(Env::empty());
+        theme.apply(&mut env);
+
+        assert_eq!(env.get(theme::PRIMARY_LIGHT), Color::rgb8(0x10, 0x20, 0x30));
+        assert_eq!(
+            env.get(theme::BUTTON_BORDER_RADIUS),
+            RoundedRectRadii::new(1., 2., 3., 4.)
+        );
+        assert_eq!(env.get(theme::TEXTBOX_INSETS), Insets::uniform_xy(6., 4.));
+        assert_eq!(env.get(theme::SCROLLBAR_FADE_DELAY), 300);
+        let font = env.get(theme::UI_FONT);
+        assert_eq!(font.family, FontFamily::MONOSPACE);
+        assert_eq!(font.size, 20.);
+        assert_eq!(font.style, FontStyle::Italic);
+        assert_eq!(env.get(Key::<f64>::new("com.example.spacing")), 3.);
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(matches!(
+            Theme::from_toml("primary_light = \"#nope\""),
+            Err(Error::InvalidValue(_))
+        ));
+        assert!(matches!(Theme::from_toml("= 1"), Err(Error::Toml(_))));
+
+        // a value of the wrong type is skipped
+        let theme = Theme::from_toml("button_border_width = \"wide\"").unwrap();
+        let mut env = theme::add_to_env(Env::empty());
+        theme.apply(&mut env);
+        assert_eq!(env.get(theme::BUTTON_BORDER_WIDTH), 2.);
+    }
+}
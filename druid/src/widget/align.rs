@@ -16,15 +16,41 @@
 
 use crate::debug_state::DebugState;
 use crate::widget::prelude::*;
-use crate::{Data, Rect, Size, UnitPoint, WidgetPod};
+use crate::{Data, Rect, Size, UnitPoint, Vec2, WidgetPod};
 use tracing::{instrument, trace};
 
 /// A widget that aligns its child.
+///
+/// After alignment the child can be moved by an offset in pixels, with
+/// [`with_offset`], and by an offset relative to its own size, with
+/// [`with_fractional_offset`]. The child may end up partially or fully
+/// outside of the `Align`; it is still painted and receives mouse events
+/// there, as long as they reach the `Align`. [`ZStack`] passes on mouse events
+/// outside of its bounds in the same way.
+///
+/// # Examples
+///
+/// A button centered on the top right corner of a card:
+///
+/// ```
+/// # use druid::widget::{Align, Button, Label, ZStack};
+/// # use druid::{UnitPoint, WidgetExt};
+/// let card = ZStack::new(Label::new("Card").padding(20.0)).with_centered_child(
+///     Align::new(UnitPoint::TOP_RIGHT, Button::new("x")).with_fractional_offset((0.5, -0.5)),
+/// );
+/// # let _: ZStack<()> = card;
+/// ```
+///
+/// [`ZStack`]: crate::widget::ZStack
+/// [`with_offset`]: Align::with_offset
+/// [`with_fractional_offset`]: Align::with_fractional_offset
 pub struct Align<T> {
     align: UnitPoint,
     child: WidgetPod<T, Box<dyn Widget<T>>>,
     width_factor: Option<f64>,
     height_factor: Option<f64>,
+    offset: Vec2,
+    fractional_offset: Vec2,
 }
 
 impl<T> Align<T> {
@@ -39,6 +65,8 @@ impl<T> Align<T> {
             child: WidgetPod::new(child).boxed(),
             width_factor: None,
             height_factor: None,
+            offset: Vec2::ZERO,
+            fractional_offset: Vec2::ZERO,
         }
     }
 
@@ -64,6 +92,8 @@ impl<T> Align<T> {
             child: WidgetPod::new(child).boxed(),
             width_factor: None,
             height_factor: Some(1.0),
+            offset: Vec2::ZERO,
+            fractional_offset: Vec2::ZERO,
         }
     }
 
@@ -74,8 +104,39 @@ impl<T> Align<T> {
             child: WidgetPod::new(child).boxed(),
             width_factor: Some(1.0),
             height_factor: None,
+            offset: Vec2::ZERO,
+            fractional_offset: Vec2::ZERO,
         }
     }
+
+    /// Builder-style method to move the child by `offset` pixels after aligning it.
+    ///
+    /// Negative values move the child left and up.
+    pub fn with_offset(mut self, offset: impl Into<Vec2>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
+    /// Builder-style method to move the child by a fraction of its own size
+    /// after aligning it.
+    ///
+    /// For example, `(0.5, -0.5)` moves the child half its width to the right
+    /// and half its height up.
+    pub fn with_fractional_offset(mut self, offset: impl Into<Vec2>) -> Self {
+        self.fractional_offset = offset.into();
+        self
+    }
+
+    /// Set the offset in pixels by which the child is moved after aligning it.
+    pub fn set_offset(&mut self, offset: impl Into<Vec2>) {
+        self.offset = offset.into();
+    }
+
+    /// Set the offset, as a fraction of the child's size, by which the child
+    /// is moved after aligning it.
+    pub fn set_fractional_offset(&mut self, offset: impl Into<Vec2>) {
+        self.fractional_offset = offset.into();
+    }
 }
 
 impl<T: Data> Widget<T> for Align<T> {
@@ -121,18 +182,26 @@ impl<T: Data> Widget<T> for Align<T> {
         my_size = bc.constrain(my_size);
         let extra_width = (my_size.width - size.width).max(0.);
         let extra_height = (my_size.height - size.height).max(0.);
-        let origin = self
+        let offset = self.offset
+            + Vec2::new(
+                self.fractional_offset.x * size.width,
+                self.fractional_offset.y * size.height,
+            );
+        let origin = (self
             .align
             .resolve(Rect::new(0., 0., extra_width, extra_height))
+            + offset)
             .expand();
         self.child.set_origin(ctx, origin);
 
         let my_insets = self.child.compute_parent_paint_insets(my_size);
         ctx.set_paint_insets(my_insets);
+        // The child can be moved outside of our bounds; keep it interactive there.
+        ctx.set_hit_insets(self.child.compute_parent_hit_insets(my_size));
         if self.height_factor.is_some() {
             let baseline_offset = self.child.baseline_offset();
             if baseline_offset > 0f64 {
-                ctx.set_baseline_offset(baseline_offset + extra_height / 2.0);
+                ctx.set_baseline_offset(my_size.height - origin.y - size.height + baseline_offset);
             }
         }
 
@@ -0,0 +1,212 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A named position that can be navigated to.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::commands::NAVIGATE_TO_ANCHOR;
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::WidgetWrapper;
+use crate::{theme, ArcStr, Data, Point, WidgetPod};
+
+/// How long the highlight takes to fade out.
+const HIGHLIGHT_DURATION: Duration = Duration::from_millis(1000);
+/// The opacity of the highlight when it appears.
+const HIGHLIGHT_OPACITY: f64 = 0.4;
+
+thread_local! {
+    /// The number of anchors with each name.
+    static ANCHORS: RefCell<HashMap<ArcStr, usize>> = RefCell::new(HashMap::new());
+}
+
+/// A widget that marks its child as a named place in a document.
+///
+/// Submitting [`NAVIGATE_TO_ANCHOR`] with the name of an anchor to its window
+/// scrolls any [`Scroll`] containing the anchor so that it is visible. If the
+/// anchor was created [`with_highlight`], it then briefly flashes.
+///
+/// The names of all anchors in the application are available from [`Anchor::names`],
+/// for instance to build a table of contents.
+///
+/// # Examples
+///
+/// ```
+/// use druid::commands::NAVIGATE_TO_ANCHOR;
+/// use druid::widget::{Button, Flex, Label, Scroll};
+/// use druid::{Widget, WidgetExt};
+///
+/// fn settings() -> impl Widget<()> {
+///     let sections = Flex::column()
+///         .with_child(Label::new("General").anchor("general"))
+///         .with_spacer(1000.0)
+///         .with_child(Label::new("Advanced").anchor("advanced"));
+///     Flex::column()
+///         .with_child(Button::new("Advanced").on_click(|ctx, _, _| {
+///             ctx.submit_command(NAVIGATE_TO_ANCHOR.with("advanced".into()))
+///         }))
+///         .with_flex_child(Scroll::new(sections).vertical(), 1.0)
+/// }
+/// ```
+///
+/// [`NAVIGATE_TO_ANCHOR`]: crate::commands::NAVIGATE_TO_ANCHOR
+/// [`Scroll`]: super::Scroll
+/// [`with_highlight`]: Anchor::with_highlight
+pub struct Anchor<T, W> {
+    name: ArcStr,
+    highlight: bool,
+    /// The time left until the highlight has faded, if it is showing.
+    highlight_left: Option<Duration>,
+    registered: bool,
+    child: WidgetPod<T, W>,
+}
+
+impl<T, W: Widget<T>> Anchor<T, W> {
+    /// Create an anchor named `name` around `child`.
+    ///
+    /// This is available as [`WidgetExt::anchor`] for convenience.
+    ///
+    /// [`WidgetExt::anchor`]: super::WidgetExt::anchor
+    pub fn new(name: impl Into<ArcStr>, child: W) -> Self {
+        Anchor {
+            name: name.into(),
+            highlight: false,
+            highlight_left: None,
+            registered: false,
+            child: WidgetPod::new(child),
+        }
+    }
+
+    /// Builder-style method for whether the anchor flashes when it is navigated to.
+    pub fn with_highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// The name of this anchor.
+    pub fn name(&self) -> &ArcStr {
+        &self.name
+    }
+}
+
+impl Anchor<(), ()> {
+    /// The names of all anchors currently in the widget tree, in no particular order.
+    pub fn names() -> Vec<ArcStr> {
+        ANCHORS.with(|anchors| anchors.borrow().keys().cloned().collect())
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Anchor<T, W> {
+    #[instrument(name = "Anchor", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.get(NAVIGATE_TO_ANCHOR) == Some(&self.name) => {
+                trace!("navigating to anchor '{}'", self.name);
+                ctx.scroll_to_view();
+                if self.highlight {
+                    self.highlight_left = Some(HIGHLIGHT_DURATION);
+                    ctx.request_anim_frame();
+                    ctx.request_paint();
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::AnimFrame(interval) => {
+                if let Some(left) = self.highlight_left {
+                    self.highlight_left = left.checked_sub(Duration::from_nanos(*interval));
+                    if self.highlight_left.is_some() {
+                        ctx.request_anim_frame();
+                    }
+                    ctx.request_paint();
+                }
+            }
+            _ => {}
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Anchor", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            if !self.registered {
+                ANCHORS.with(|anchors| {
+                    *anchors.borrow_mut().entry(self.name.clone()).or_default() += 1
+                });
+                self.registered = true;
+            }
+        }
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Anchor", level = "trace", skip(self, ctx, _old, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Anchor", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Anchor");
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "Anchor", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if let Some(left) = self.highlight_left {
+            let fraction = left.as_secs_f64() / HIGHLIGHT_DURATION.as_secs_f64();
+            let color = env
+                .get(theme::PRIMARY_LIGHT)
+                .with_alpha(HIGHLIGHT_OPACITY * fraction);
+            let rect = ctx.size().to_rect();
+            ctx.fill(rect, &color);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: self.name.to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, W> Drop for Anchor<T, W> {
+    fn drop(&mut self) {
+        if !self.registered {
+            return;
+        }
+        ANCHORS.with(|anchors| {
+            let mut anchors = anchors.borrow_mut();
+            if let Some(count) = anchors.get_mut(&self.name) {
+                *count -= 1;
+                if *count == 0 {
+                    anchors.remove(&self.name);
+                }
+            }
+        });
+    }
+}
+
+impl<T, W: Widget<T>> WidgetWrapper for Anchor<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
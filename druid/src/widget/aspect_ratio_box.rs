@@ -15,10 +15,27 @@
 use crate::debug_state::DebugState;
 
 use crate::widget::Axis;
+use crate::{Point, WidgetPod};
 use druid::widget::prelude::*;
 use druid::Data;
 use tracing::{instrument, warn};
 
+/// How an [`AspectRatioBox`] fits its child into space of a different aspect ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum AspectRatioFit {
+    /// The box takes the largest size with the aspect ratio that fits the constraints.
+    ///
+    /// This is the default.
+    Shrink,
+    /// The box fills the available space, and the child is the largest size with the
+    /// aspect ratio that fits inside, centered. The space around it is left empty.
+    Letterbox,
+    /// The box fills the available space, and the child is the smallest size with the
+    /// aspect ratio that covers it, centered. The parts of the child outside the box
+    /// are clipped.
+    Crop,
+}
+
 /// A widget that preserves the aspect ratio given to it.
 ///
 /// If given a child, this widget forces the child to have a width and height that preserves
@@ -26,9 +43,14 @@ use tracing::{instrument, warn};
 ///
 /// If not given a child, The box will try to size itself  as large or small as possible
 /// to preserve the aspect ratio.
+///
+/// By default the box itself has the aspect ratio. To fill the available space instead,
+/// and either letterbox or crop the child, set an [`AspectRatioFit`]. This is useful for
+/// thumbnails, video and map tiles. Both only apply when the available space is bounded.
 pub struct AspectRatioBox<T> {
-    child: Box<dyn Widget<T>>,
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
     ratio: f64,
+    fit: AspectRatioFit,
 }
 
 impl<T> AspectRatioBox<T> {
@@ -39,11 +61,23 @@ impl<T> AspectRatioBox<T> {
     /// If aspect ratio <= 0.0, the ratio will be set to 1.0
     pub fn new(child: impl Widget<T> + 'static, ratio: f64) -> Self {
         Self {
-            child: Box::new(child),
+            child: WidgetPod::new(Box::new(child)),
             ratio: clamp_ratio(ratio),
+            fit: AspectRatioFit::Shrink,
         }
     }
 
+    /// Builder-style method to set how the child is fit into the available space.
+    pub fn with_fit(mut self, fit: AspectRatioFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Set how the child is fit into the available space.
+    pub fn set_fit(&mut self, fit: AspectRatioFit) {
+        self.fit = fit;
+    }
+
     /// Set the ratio of the box.
     ///
     /// The ratio has to be a value between 0 and f64::MAX, excluding 0. It will be clamped
@@ -88,6 +122,18 @@ impl<T> AspectRatioBox<T> {
 
         BoxConstraints::tight(bc.constrain(Size::new(new_width, new_height)))
     }
+
+    /// The size of the child when it is letterboxed or cropped in `size`.
+    fn fit_size(&self, size: Size) -> Size {
+        let wider = size.width > size.height * self.ratio;
+        // When letterboxing, the child touches the shorter sides, relative to the ratio;
+        // when cropping, it touches the longer sides.
+        if wider == (self.fit == AspectRatioFit::Crop) {
+            Size::new(size.width, size.width / self.ratio)
+        } else {
+            Size::new(size.height * self.ratio, size.height)
+        }
+    }
 }
 
 /// Clamps the ratio between 0.0 and f64::MAX
@@ -125,10 +171,10 @@ impl<T: Data> Widget<T> for AspectRatioBox<T> {
     #[instrument(
         name = "AspectRatioBox",
         level = "trace",
-        skip(self, ctx, old_data, data, env)
+        skip(self, ctx, _old_data, data, env)
     )]
-    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
-        self.child.update(ctx, old_data, data, env);
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
     }
 
     #[instrument(
@@ -139,35 +185,52 @@ impl<T: Data> Widget<T> for AspectRatioBox<T> {
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("AspectRatioBox");
 
-        if bc.max() == bc.min() {
-            warn!("Box constraints are tight. Aspect ratio box will not be able to preserve aspect ratio.");
-
-            return self.child.layout(ctx, bc, data, env);
+        if self.fit != AspectRatioFit::Shrink && bc.is_width_bounded() && bc.is_height_bounded() {
+            let size = bc.max();
+            let child_size = self.fit_size(size);
+            self.child
+                .layout(ctx, &BoxConstraints::tight(child_size), data, env);
+            let origin = Point::new(
+                (size.width - child_size.width) / 2.0,
+                (size.height - child_size.height) / 2.0,
+            );
+            self.child.set_origin(ctx, origin);
+            return size;
         }
-        if bc.max().width == f64::INFINITY && bc.max().height == f64::INFINITY {
-            warn!("Box constraints are INFINITE. Aspect ratio box won't be able to choose a size because the constraints given by the parent widget are INFINITE.");
 
-            return self.child.layout(ctx, bc, data, env);
-        }
+        let size = if bc.max() == bc.min() {
+            warn!("Box constraints are tight. Aspect ratio box will not be able to preserve aspect ratio.");
 
-        let bc = self.generate_constraints(bc);
+            self.child.layout(ctx, bc, data, env)
+        } else if bc.max().width == f64::INFINITY && bc.max().height == f64::INFINITY {
+            warn!("Box constraints are INFINITE. Aspect ratio box won't be able to choose a size because the constraints given by the parent widget are INFINITE.");
 
-        self.child.layout(ctx, &bc, data, env)
+            self.child.layout(ctx, bc, data, env)
+        } else {
+            let bc = self.generate_constraints(bc);
+            self.child.layout(ctx, &bc, data, env)
+        };
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
     }
 
     #[instrument(name = "AspectRatioBox", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        self.child.paint(ctx, data, env);
-    }
-
-    fn id(&self) -> Option<WidgetId> {
-        self.child.id()
+        if self.fit == AspectRatioFit::Crop {
+            let clip_rect = ctx.size().to_rect();
+            ctx.with_save(|ctx| {
+                ctx.clip(clip_rect);
+                self.child.paint(ctx, data, env);
+            });
+        } else {
+            self.child.paint(ctx, data, env);
+        }
     }
 
     fn debug_state(&self, data: &T) -> DebugState {
         DebugState {
             display_name: self.short_type_name().to_string(),
-            children: vec![self.child.debug_state(data)],
+            children: vec![self.child.widget().debug_state(data)],
             ..Default::default()
         }
     }
@@ -185,14 +248,18 @@ impl<T: Data> Widget<T> for AspectRatioBox<T> {
                 if bc.is_height_bounded() {
                     bc.max().height * self.ratio
                 } else {
-                    self.child.compute_max_intrinsic(axis, ctx, bc, data, env)
+                    self.child
+                        .widget_mut()
+                        .compute_max_intrinsic(axis, ctx, bc, data, env)
                 }
             }
             Axis::Vertical => {
                 if bc.is_width_bounded() {
                     bc.max().width / self.ratio
                 } else {
-                    self.child.compute_max_intrinsic(axis, ctx, bc, data, env)
+                    self.child
+                        .widget_mut()
+                        .compute_max_intrinsic(axis, ctx, bc, data, env)
                 }
             }
         }
@@ -0,0 +1,237 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An image that is loaded in the background.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+#[cfg(feature = "image")]
+use std::path::PathBuf;
+
+use tracing::{instrument, trace, warn};
+
+use crate::piet::{ImageBuf, InterpolationMode};
+use crate::widget::prelude::*;
+use crate::widget::{FillStrat, Image, Spinner};
+use crate::{ArcStr, Data, ExtEventSink, Point, Selector, Target, WidgetPod};
+
+type LoadError = Box<dyn Error + Send + Sync>;
+type Loader = Box<dyn FnOnce() -> Result<ImageBuf, LoadError> + Send>;
+
+/// Sent to the widget that started loading an image, once it is loaded.
+const IMAGE_LOADED: Selector<Result<ImageBuf, String>> =
+    Selector::new("druid-builtin.async-image-loaded");
+
+/// Where an image was loaded from, used as the key of the cache.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Source {
+    #[cfg(feature = "image")]
+    Path(PathBuf),
+    Key(ArcStr),
+}
+
+thread_local! {
+    static CACHE: RefCell<HashMap<Source, ImageBuf>> = RefCell::new(HashMap::new());
+}
+
+/// An [`Image`] that is decoded on a background thread.
+///
+/// A placeholder widget is shown until the image has loaded. Decoded images
+/// are cached by their source, so other widgets showing the same image
+/// don't load it again; see [`Image::clear_cache`].
+///
+/// This is created with [`Image::from_path`] or [`Image::load_with`].
+pub struct AsyncImage<T> {
+    source: Source,
+    loader: Option<Loader>,
+    image: Image,
+    placeholder: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+}
+
+impl<T: Data> AsyncImage<T> {
+    fn new(source: Source, loader: Loader) -> Self {
+        AsyncImage {
+            source,
+            loader: Some(loader),
+            image: Image::new(ImageBuf::empty()),
+            placeholder: Some(WidgetPod::new(Box::new(Spinner::new()))),
+        }
+    }
+
+    /// Builder-style method for the widget shown while the image is loading.
+    ///
+    /// The default is a [`Spinner`].
+    pub fn with_placeholder(mut self, placeholder: impl Widget<T> + 'static) -> Self {
+        self.placeholder = Some(WidgetPod::new(Box::new(placeholder)));
+        self
+    }
+
+    /// Builder-style method for specifying the fill strategy.
+    pub fn fill_mode(mut self, mode: FillStrat) -> Self {
+        self.image.set_fill_mode(mode);
+        self
+    }
+
+    /// Builder-style method for specifying the interpolation strategy.
+    pub fn interpolation_mode(mut self, interpolation: InterpolationMode) -> Self {
+        self.image.set_interpolation_mode(interpolation);
+        self
+    }
+
+    /// Returns `true` once the image has loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.placeholder.is_none()
+    }
+
+    fn start_loading(&mut self, sink: ExtEventSink, target: WidgetId) {
+        let loader = match self.loader.take() {
+            Some(loader) => loader,
+            None => return,
+        };
+        let load = move || {
+            let result = loader().map_err(|err| err.to_string());
+            if sink
+                .submit_command(IMAGE_LOADED, Box::new(result), Target::Widget(target))
+                .is_err()
+            {
+                trace!("application closed before the image loaded");
+            }
+        };
+        // There are no threads on the web, so load the image right away.
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(load);
+        #[cfg(target_arch = "wasm32")]
+        load();
+    }
+
+    fn finish_loading(&mut self, image: ImageBuf) {
+        self.image.set_image_data(image);
+        self.placeholder = None;
+    }
+}
+
+impl Image {
+    /// Create an image widget that decodes the image file at `path` in the background.
+    ///
+    /// See [`AsyncImage`] for details.
+    #[cfg(feature = "image")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+    pub fn from_path<T: Data>(path: impl Into<PathBuf>) -> AsyncImage<T> {
+        let path = path.into();
+        let source = Source::Path(path.clone());
+        AsyncImage::new(source, Box::new(move || ImageBuf::from_file(path)))
+    }
+
+    /// Create an image widget that gets its image by calling `loader` in the background.
+    ///
+    /// This can be used to load images from other sources, such as over the
+    /// network. `key` identifies the image in the cache, for instance its URL.
+    ///
+    /// See [`AsyncImage`] for details.
+    pub fn load_with<T: Data>(
+        key: impl Into<ArcStr>,
+        loader: impl FnOnce() -> Result<ImageBuf, LoadError> + Send + 'static,
+    ) -> AsyncImage<T> {
+        AsyncImage::new(Source::Key(key.into()), Box::new(loader))
+    }
+
+    /// Drop all images cached by [`AsyncImage`]s.
+    ///
+    /// Widgets that already show an image keep it; new widgets load it again.
+    pub fn clear_cache() {
+        CACHE.with(|cache| cache.borrow_mut().clear());
+    }
+}
+
+impl<T: Data> Widget<T> for AsyncImage<T> {
+    #[instrument(
+        name = "AsyncImage",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(result) = cmd.get(IMAGE_LOADED) {
+                match result {
+                    Ok(image) => {
+                        CACHE.with(|cache| {
+                            cache
+                                .borrow_mut()
+                                .insert(self.source.clone(), image.clone())
+                        });
+                        self.finish_loading(image.clone());
+                        ctx.children_changed();
+                    }
+                    Err(err) => warn!("failed to load image {:?}: {}", self.source, err),
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        if let Some(placeholder) = &mut self.placeholder {
+            placeholder.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "AsyncImage",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let cached = CACHE.with(|cache| cache.borrow().get(&self.source).cloned());
+            match cached {
+                Some(image) => self.finish_loading(image),
+                None => self.start_loading(ctx.get_external_handle(), ctx.widget_id()),
+            }
+        }
+        if let Some(placeholder) = &mut self.placeholder {
+            placeholder.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "AsyncImage",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if let Some(placeholder) = &mut self.placeholder {
+            placeholder.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "AsyncImage", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("AsyncImage");
+        match &mut self.placeholder {
+            Some(placeholder) => {
+                let size = placeholder.layout(ctx, bc, data, env);
+                placeholder.set_origin(ctx, Point::ORIGIN);
+                size
+            }
+            None => self.image.layout(ctx, bc, data, env),
+        }
+    }
+
+    #[instrument(name = "AsyncImage", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        match &mut self.placeholder {
+            Some(placeholder) => placeholder.paint(ctx, data, env),
+            None => self.image.paint(ctx, data, env),
+        }
+    }
+}
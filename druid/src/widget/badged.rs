@@ -0,0 +1,295 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small badge on the corner of a widget.
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::Circle;
+use crate::widget::prelude::*;
+use crate::widget::{Icon, IconData, WidgetWrapper};
+use crate::{
+    theme, ArcStr, Color, Data, FontDescriptor, FontFamily, FontWeight, KeyOrValue, Point, Rect,
+    TextLayout, UnitPoint, WidgetPod,
+};
+
+/// The diameter of a dot badge.
+const DOT_SIZE: f64 = 8.0;
+/// The smallest height of a badge with content.
+const MIN_HEIGHT: f64 = 16.0;
+const TEXT_SIZE: f64 = 11.0;
+const ICON_SIZE: f64 = 10.0;
+/// The space to the left and right of the text of a badge.
+const TEXT_PADDING: f64 = 5.0;
+
+type BadgeFn<T> = Box<dyn Fn(&T, &Env) -> Badge>;
+
+/// What a [`Badged`] widget shows in its badge.
+#[derive(Debug, Clone, Default)]
+pub enum Badge {
+    /// No badge.
+    #[default]
+    None,
+    /// A small dot, without content.
+    Dot,
+    /// A number; a count of zero hides the badge.
+    Count(usize),
+    /// A short text; an empty text hides the badge.
+    Text(ArcStr),
+    /// An icon.
+    Icon(IconData),
+}
+
+/// A widget that draws a small badge on a corner of its child.
+///
+/// The badge comes from the data, and it's hidden when it's [`Badge::None`], a
+/// zero count, or an empty text. It's centered on the corner, so it sticks out of
+/// the child and is not clipped along with the child's contents.
+///
+/// The colors default to [`theme::BADGE_COLOR`] and [`theme::BADGE_TEXT_COLOR`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Badged, Button};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Inbox {
+///     unread: usize,
+/// }
+///
+/// fn inbox_button() -> impl Widget<Inbox> {
+///     Badged::count(Button::new("Inbox"), |inbox: &Inbox| inbox.unread).with_max_count(99)
+/// }
+/// ```
+///
+/// [`theme::BADGE_COLOR`]: crate::theme::BADGE_COLOR
+/// [`theme::BADGE_TEXT_COLOR`]: crate::theme::BADGE_TEXT_COLOR
+pub struct Badged<T, W> {
+    child: WidgetPod<T, W>,
+    badge_fn: BadgeFn<T>,
+    badge: Badge,
+    text: TextLayout<ArcStr>,
+    icon: Option<Icon>,
+    corner: UnitPoint,
+    color: KeyOrValue<Color>,
+    text_color: KeyOrValue<Color>,
+    max_count: usize,
+    badge_rect: Rect,
+}
+
+impl<T, W: Widget<T>> Badged<T, W> {
+    /// Create a badge on `child`, which shows what `badge` returns for the data.
+    pub fn new(child: W, badge: impl Fn(&T, &Env) -> Badge + 'static) -> Self {
+        let mut text = TextLayout::new();
+        text.set_font(
+            FontDescriptor::new(FontFamily::SYSTEM_UI)
+                .with_weight(FontWeight::BOLD)
+                .with_size(TEXT_SIZE),
+        );
+        text.set_text_color(theme::BADGE_TEXT_COLOR);
+        Badged {
+            child: WidgetPod::new(child),
+            badge_fn: Box::new(badge),
+            badge: Badge::None,
+            text,
+            icon: None,
+            corner: UnitPoint::TOP_RIGHT,
+            color: theme::BADGE_COLOR.into(),
+            text_color: theme::BADGE_TEXT_COLOR.into(),
+            max_count: usize::MAX,
+            badge_rect: Rect::ZERO,
+        }
+    }
+
+    /// Create a badge on `child` that shows a count, and is hidden when it's zero.
+    pub fn count(child: W, count: impl Fn(&T) -> usize + 'static) -> Self {
+        Self::new(child, move |data, _| Badge::Count(count(data)))
+    }
+
+    /// Create a badge on `child` that shows a dot while `shown` returns `true`.
+    pub fn dot(child: W, shown: impl Fn(&T) -> bool + 'static) -> Self {
+        Self::new(child, move |data, _| match shown(data) {
+            true => Badge::Dot,
+            false => Badge::None,
+        })
+    }
+
+    /// Builder-style method to set the corner the badge is centered on.
+    ///
+    /// The default is [`UnitPoint::TOP_RIGHT`].
+    pub fn with_corner(mut self, corner: UnitPoint) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    /// Builder-style method to set the background color of the badge.
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Builder-style method to set the color of the text or icon of the badge.
+    pub fn with_text_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.text_color = color.into();
+        self.text.set_text_color(self.text_color.clone());
+        self
+    }
+
+    /// Builder-style method to set the largest count that is shown; larger counts
+    /// are shown as this count followed by a `+`.
+    pub fn with_max_count(mut self, max_count: usize) -> Self {
+        self.max_count = max_count;
+        self
+    }
+
+    fn set_badge(&mut self, badge: Badge) {
+        match &badge {
+            Badge::Count(count) if *count > self.max_count => {
+                self.text.set_text(format!("{}+", self.max_count).into())
+            }
+            Badge::Count(count) => self.text.set_text(count.to_string().into()),
+            Badge::Text(text) => self.text.set_text(text.clone()),
+            _ => (),
+        }
+        self.icon = match &badge {
+            Badge::Icon(icon) => Some(
+                Icon::new(icon.clone())
+                    .with_size(ICON_SIZE)
+                    .with_color(self.text_color.clone()),
+            ),
+            _ => None,
+        };
+        self.badge = badge;
+    }
+
+    /// The size of the badge, or `None` if it's hidden.
+    fn badge_size(&self) -> Option<Size> {
+        match &self.badge {
+            Badge::None | Badge::Count(0) => None,
+            Badge::Text(text) if text.is_empty() => None,
+            Badge::Dot => Some(Size::new(DOT_SIZE, DOT_SIZE)),
+            Badge::Icon(_) => Some(Size::new(MIN_HEIGHT, MIN_HEIGHT)),
+            Badge::Count(_) | Badge::Text(_) => {
+                let text_size = self.text.size();
+                let height = (text_size.height + 2.0).max(MIN_HEIGHT);
+                let width = (text_size.width + 2.0 * TEXT_PADDING).max(height);
+                Some(Size::new(width, height))
+            }
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Badged<T, W> {
+    #[instrument(name = "Badged", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Badged", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let badge = (self.badge_fn)(data, env);
+            self.set_badge(badge);
+        }
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Badged",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let badge = (self.badge_fn)(data, env);
+        self.set_badge(badge);
+        // The text and the paint insets are recomputed in layout, whether the badge
+        // or the env changed.
+        self.text.needs_rebuild_after_update(ctx);
+        ctx.request_layout();
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Badged", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Badged");
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        self.text.rebuild_if_needed(ctx.text(), env);
+
+        let mut paint_rect = self.child.paint_rect();
+        self.badge_rect = match self.badge_size() {
+            Some(badge_size) => {
+                let center = self.corner.resolve(size.to_rect());
+                let badge_rect = Rect::from_center_size(center, badge_size);
+                paint_rect = paint_rect.union(badge_rect);
+                badge_rect
+            }
+            None => Rect::ZERO,
+        };
+        ctx.set_paint_insets(paint_rect - size.to_rect());
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Badged", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if self.badge_size().is_none() {
+            return;
+        }
+
+        let rect = self.badge_rect;
+        let color = self.color.resolve(env);
+        let radius = rect.height() / 2.0;
+        if rect.width() > rect.height() {
+            ctx.fill(rect.to_rounded_rect(radius), &color);
+        } else {
+            ctx.fill(Circle::new(rect.center(), radius), &color);
+        }
+
+        if let Some(icon) = &self.icon {
+            let icon_size = icon.resolved_size(env);
+            icon.draw(ctx, rect.center() - icon_size.to_vec2() / 2.0, env);
+        } else if matches!(self.badge, Badge::Count(_) | Badge::Text(_)) {
+            let text_size = self.text.size();
+            self.text
+                .draw(ctx, rect.center() - text_size.to_vec2() / 2.0);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let main_value = match &self.badge {
+            Badge::None => String::new(),
+            Badge::Dot => "dot".to_string(),
+            Badge::Icon(_) => "icon".to_string(),
+            Badge::Count(_) | Badge::Text(_) => self
+                .text
+                .text()
+                .map_or_else(String::new, |text| text.to_string()),
+        };
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value,
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, W: Widget<T>> WidgetWrapper for Badged<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
@@ -0,0 +1,168 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Keeping a widget's own state in step with the app data.
+
+use tracing::instrument;
+
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::Lens;
+
+type Getter<W, U> = Box<dyn Fn(&W) -> U>;
+type Setter<W, U> = Box<dyn Fn(&mut W, &mut UpdateCtx, &U)>;
+
+/// A [`Controller`] that binds a piece of a widget's internal state, such as
+/// the offset of a [`Scroll`], to a field of the data in both directions.
+///
+/// When the widget's state changes while handling an event, the new value is
+/// written to the field selected by the lens. When the field is changed by
+/// anything else, the widget's state is set from it. Values are compared with
+/// [`Data::same`], and each side is only written when it differs from the
+/// other, so the two can't keep updating each other.
+///
+/// To mirror the state of one widget into another, bind both widgets to the
+/// same field.
+///
+/// The widget's state is first set from the data on the first update after
+/// the widget is added.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Binding, Flex, Label, Scroll};
+/// use druid::{Data, Lens, Vec2, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct AppState {
+///     offset: f64,
+/// }
+///
+/// fn build_ui() -> impl Widget<AppState> {
+///     // Two scroll areas that always show the same part of their contents.
+///     let scroll = || {
+///         Scroll::new(Label::new("A long text\n".repeat(100)))
+///             .vertical()
+///             .controller(Binding::new(
+///                 AppState::offset,
+///                 |scroll: &Scroll<_, _>| scroll.offset().y,
+///                 |scroll, ctx, y| {
+///                     let delta = Vec2::new(0.0, *y - scroll.offset().y);
+///                     scroll.scroll_by(ctx, delta);
+///                 },
+///             ))
+///     };
+///     Flex::row()
+///         .with_flex_child(scroll(), 1.0)
+///         .with_flex_child(scroll(), 1.0)
+/// }
+/// ```
+///
+/// [`Scroll`]: crate::widget::Scroll
+pub struct Binding<L, U, W> {
+    lens: L,
+    get: Getter<W, U>,
+    set: Setter<W, U>,
+    synced: bool,
+}
+
+impl<L, U: Data, W> Binding<L, U, W> {
+    /// Create a binding between the field selected by `lens` and the widget
+    /// state that is read with `get` and written with `set`.
+    ///
+    /// `set` should request a layout or paint if the widget needs one.
+    pub fn new(
+        lens: L,
+        get: impl Fn(&W) -> U + 'static,
+        set: impl Fn(&mut W, &mut UpdateCtx, &U) + 'static,
+    ) -> Self {
+        Binding {
+            lens,
+            get: Box::new(get),
+            set: Box::new(set),
+            synced: false,
+        }
+    }
+
+    /// Set the widget's state from `data`, if they differ.
+    fn push<T>(&self, child: &mut W, ctx: &mut UpdateCtx, data: &T)
+    where
+        L: Lens<T, U>,
+    {
+        let current = (self.get)(child);
+        self.lens.with(data, |value| {
+            if !value.same(&current) {
+                (self.set)(child, ctx, value);
+            }
+        });
+    }
+}
+
+impl<T: Data, U: Data, L: Lens<T, U>, W: Widget<T>> Controller<T, W> for Binding<L, U, W> {
+    #[instrument(
+        name = "Binding",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if !self.synced {
+            ctx.request_update();
+        }
+        let before = (self.get)(child);
+        child.event(ctx, event, data, env);
+        let after = (self.get)(child);
+        if self.synced && !before.same(&after) {
+            self.lens.with_mut(data, |value| {
+                if !value.same(&after) {
+                    *value = after;
+                }
+            });
+        }
+    }
+
+    #[instrument(
+        name = "Binding",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.synced = false;
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "Binding",
+        level = "trace",
+        skip(self, child, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        child.update(ctx, old_data, data, env);
+        let changed = !self
+            .lens
+            .with(old_data, |old| self.lens.with(data, |new| old.same(new)));
+        if changed || !self.synced {
+            self.synced = true;
+            self.push(child, ctx, data);
+        }
+    }
+}
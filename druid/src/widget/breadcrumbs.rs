@@ -0,0 +1,479 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A breadcrumb navigation widget.
+
+use tracing::{instrument, trace};
+
+use crate::commands::CLOSE_WINDOW;
+use crate::debug_state::DebugState;
+use crate::kurbo::BezPath;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::widget::ListIter;
+use crate::{theme, Point, Rect, Selector, WindowConfig, WindowId, WindowLevel, WindowSizePolicy};
+
+/// The horizontal padding around the text of each segment.
+const SEGMENT_PADDING: f64 = 6.0;
+/// The vertical padding around the text of each segment.
+const SEGMENT_PADDING_VERTICAL: f64 = 3.0;
+/// The default width of the separator between segments.
+const DEFAULT_SEPARATOR_WIDTH: f64 = 12.0;
+/// The text of the segment standing in for the segments that do not fit.
+const OVERFLOW_TEXT: &str = "…";
+
+/// Sent by the overflow popup with the hidden segment that was selected, or `None` when
+/// the popup is dismissed.
+const OVERFLOW_SELECTED: Selector<Option<usize>> =
+    Selector::new("druid-builtin.breadcrumbs-overflow");
+
+type ClickCallback<C> = Box<dyn Fn(&mut EventCtx, &mut C, usize, &Env)>;
+
+/// A segment of a [`Breadcrumbs`], as laid out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Crumb {
+    Segment(usize),
+    Overflow,
+}
+
+/// A path of clickable segments, such as the folders leading to a file.
+///
+/// The segments are the items of a collection implementing [`ListIter`], such as a
+/// `Vector<String>`. If the path is too wide to fit, segments in the middle are replaced
+/// by an overflow segment, which shows the hidden segments in a popup when clicked. The
+/// popup closes when a segment is chosen, or when the mouse leaves it.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::Breadcrumbs;
+///
+/// let breadcrumbs = Breadcrumbs::new(|folder: &String, _env: &_| folder.clone())
+///     .on_click(|_ctx, path: &mut Arc<Vec<String>>, idx, _env| {
+///         Arc::make_mut(path).truncate(idx + 1)
+///     });
+/// ```
+pub struct Breadcrumbs<C, T> {
+    text: Box<dyn Fn(&T, &Env) -> String>,
+    on_click: Option<ClickCallback<C>>,
+    separator: Box<dyn Fn(&mut PaintCtx, Rect, &Env)>,
+    separator_width: f64,
+    segments: Vec<TextLayout<String>>,
+    overflow: TextLayout<String>,
+    /// The visible segments and their rects, as of the last layout.
+    crumbs: Vec<(Crumb, Rect)>,
+    hot: Option<Crumb>,
+    pressed: Option<Crumb>,
+    popup: Option<WindowId>,
+}
+
+/// The root widget of the popup listing the hidden segments.
+struct OverflowPopup {
+    breadcrumbs: WidgetId,
+    items: Vec<(usize, TextLayout<String>)>,
+    item_height: f64,
+    hot: Option<usize>,
+}
+
+impl<C: Data + ListIter<T>, T: Data> Breadcrumbs<C, T> {
+    /// Create new breadcrumbs, with a closure that returns the text of a segment.
+    pub fn new(text: impl Fn(&T, &Env) -> String + 'static) -> Self {
+        Breadcrumbs {
+            text: Box::new(text),
+            on_click: None,
+            separator: Box::new(paint_chevron),
+            separator_width: DEFAULT_SEPARATOR_WIDTH,
+            segments: Vec::new(),
+            overflow: TextLayout::from_text(OVERFLOW_TEXT.to_string()),
+            crumbs: Vec::new(),
+            hot: None,
+            pressed: None,
+            popup: None,
+        }
+    }
+
+    /// Builder-style method to provide a closure that is called with the index of a
+    /// segment when it is clicked.
+    pub fn on_click(
+        mut self,
+        on_click: impl Fn(&mut EventCtx, &mut C, usize, &Env) + 'static,
+    ) -> Self {
+        self.on_click = Some(Box::new(on_click));
+        self
+    }
+
+    /// Builder-style method to set how the separator between segments is painted.
+    ///
+    /// The closure is called with the rect the separator occupies, which is `width`
+    /// wide and as high as the widget.
+    pub fn with_separator(
+        mut self,
+        width: f64,
+        paint: impl Fn(&mut PaintCtx, Rect, &Env) + 'static,
+    ) -> Self {
+        self.separator = Box::new(paint);
+        self.separator_width = width;
+        self
+    }
+
+    fn rebuild_segments(&mut self, data: &C, env: &Env) {
+        let mut segments = std::mem::take(&mut self.segments);
+        segments.truncate(data.data_len());
+        data.for_each(|item, idx| {
+            let text = (self.text)(item, env);
+            match segments.get_mut(idx) {
+                Some(layout) => layout.set_text(text),
+                None => segments.push(TextLayout::from_text(text)),
+            }
+        });
+        self.segments = segments;
+    }
+
+    fn crumb_width(&self, crumb: Crumb) -> f64 {
+        let text = match crumb {
+            Crumb::Segment(idx) => &self.segments[idx],
+            Crumb::Overflow => &self.overflow,
+        };
+        text.size().width + 2.0 * SEGMENT_PADDING
+    }
+
+    /// Choose which segments are shown in `max_width`.
+    ///
+    /// If not all of them fit, the first segment is followed by the overflow segment and
+    /// as many of the last segments as fit. The last segment is always shown.
+    fn visible_crumbs(&self, max_width: f64) -> Vec<Crumb> {
+        let count = self.segments.len();
+        let all = (0..count).map(Crumb::Segment).collect::<Vec<_>>();
+        if count <= 1 || self.total_width(&all) <= max_width {
+            return all;
+        }
+
+        let mut tail = vec![Crumb::Segment(count - 1)];
+        for idx in (1..count - 1).rev() {
+            let mut candidate = vec![Crumb::Segment(0), Crumb::Overflow, Crumb::Segment(idx)];
+            candidate.extend(&tail);
+            if self.total_width(&candidate) > max_width {
+                break;
+            }
+            tail.insert(0, Crumb::Segment(idx));
+        }
+        let mut crumbs = vec![Crumb::Segment(0), Crumb::Overflow];
+        crumbs.extend(&tail);
+        if self.total_width(&crumbs) > max_width {
+            // Not even the first and last segments fit; drop the first.
+            crumbs.remove(0);
+        }
+        crumbs
+    }
+
+    fn total_width(&self, crumbs: &[Crumb]) -> f64 {
+        let separators = crumbs.len().saturating_sub(1) as f64 * self.separator_width;
+        crumbs.iter().map(|c| self.crumb_width(*c)).sum::<f64>() + separators
+    }
+
+    fn crumb_at(&self, pos: Point) -> Option<Crumb> {
+        self.crumbs
+            .iter()
+            .find(|(_, rect)| rect.contains(pos))
+            .map(|(crumb, _)| *crumb)
+    }
+
+    /// The segments replaced by the overflow segment.
+    fn hidden_segments(&self) -> Vec<usize> {
+        let shown = self
+            .crumbs
+            .iter()
+            .filter_map(|(crumb, _)| match crumb {
+                Crumb::Segment(idx) => Some(*idx),
+                Crumb::Overflow => None,
+            })
+            .collect::<Vec<_>>();
+        (0..self.segments.len())
+            .filter(|idx| !shown.contains(idx))
+            .collect()
+    }
+
+    fn show_popup(&mut self, ctx: &mut EventCtx, anchor: Rect, data: &C, env: &Env) {
+        let items = self
+            .hidden_segments()
+            .into_iter()
+            .map(|idx| {
+                let text = self.segments[idx].text().cloned().unwrap_or_default();
+                (idx, TextLayout::from_text(text))
+            })
+            .collect();
+        let popup = OverflowPopup {
+            breadcrumbs: ctx.widget_id(),
+            items,
+            item_height: 0.0,
+            hot: None,
+        };
+        let position = ctx.to_window(Point::new(anchor.x0, anchor.y1));
+        let window = ctx.new_sub_window(
+            WindowConfig::default()
+                .show_titlebar(false)
+                .window_size_policy(WindowSizePolicy::Content)
+                .set_level(WindowLevel::DropDown(ctx.window().clone()))
+                .set_position(position),
+            popup,
+            data.clone(),
+            env.clone(),
+        );
+        self.popup = Some(window);
+    }
+
+    fn close_popup(&mut self, ctx: &mut EventCtx) {
+        if let Some(window) = self.popup.take() {
+            ctx.submit_command(CLOSE_WINDOW.to(window));
+        }
+    }
+
+    fn clicked(&mut self, ctx: &mut EventCtx, data: &mut C, idx: usize, env: &Env) {
+        trace!("Breadcrumb {} clicked", idx);
+        if let Some(on_click) = &self.on_click {
+            on_click(ctx, data, idx, env);
+        }
+    }
+}
+
+/// Paint a chevron pointing right, the default separator.
+fn paint_chevron(ctx: &mut PaintCtx, rect: Rect, env: &Env) {
+    let center = rect.center();
+    let half = (rect.width().min(rect.height()) / 4.0).max(1.0);
+    let mut path = BezPath::new();
+    path.move_to((center.x - half / 2.0, center.y - half));
+    path.line_to((center.x + half / 2.0, center.y));
+    path.line_to((center.x - half / 2.0, center.y + half));
+    ctx.stroke(path, &env.get(theme::PLACEHOLDER_COLOR), 1.5);
+}
+
+impl<C: Data + ListIter<T>, T: Data> Widget<C> for Breadcrumbs<C, T> {
+    #[instrument(
+        name = "Breadcrumbs",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut C, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(OVERFLOW_SELECTED) => {
+                ctx.set_handled();
+                self.close_popup(ctx);
+                match *cmd.get_unchecked(OVERFLOW_SELECTED) {
+                    Some(idx) if idx < self.segments.len() => self.clicked(ctx, data, idx, env),
+                    _ => (),
+                }
+            }
+            Event::MouseMove(mouse) => {
+                let hot = if ctx.is_hot() {
+                    self.crumb_at(mouse.pos)
+                } else {
+                    None
+                };
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                self.pressed = self.crumb_at(mouse.pos);
+                if self.pressed.is_some() {
+                    ctx.set_active(true);
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() && ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.request_paint();
+                let pressed = self.pressed.take();
+                if pressed != self.crumb_at(mouse.pos) {
+                    return;
+                }
+                match pressed {
+                    Some(Crumb::Segment(idx)) => self.clicked(ctx, data, idx, env),
+                    Some(Crumb::Overflow) if self.popup.is_some() => self.close_popup(ctx),
+                    Some(Crumb::Overflow) => {
+                        let anchor = self
+                            .crumbs
+                            .iter()
+                            .find(|(crumb, _)| *crumb == Crumb::Overflow)
+                            .map(|(_, rect)| *rect)
+                            .unwrap_or_default();
+                        self.show_popup(ctx, anchor, data, env);
+                    }
+                    None => (),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "Breadcrumbs",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &C, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => self.rebuild_segments(data, env),
+            LifeCycle::HotChanged(false) if self.hot.is_some() => {
+                self.hot = None;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "Breadcrumbs",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &C, data: &C, env: &Env) {
+        if !old_data.same(data) || ctx.env_changed() {
+            self.rebuild_segments(data, env);
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Breadcrumbs", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &C, env: &Env) -> Size {
+        bc.debug_check("Breadcrumbs");
+        self.overflow.rebuild_if_needed(ctx.text(), env);
+        let mut text_height = self.overflow.size().height;
+        for segment in &mut self.segments {
+            segment.rebuild_if_needed(ctx.text(), env);
+            text_height = text_height.max(segment.size().height);
+        }
+
+        let height = text_height + 2.0 * SEGMENT_PADDING_VERTICAL;
+        let mut x = 0.0;
+        self.crumbs = Vec::new();
+        for crumb in self.visible_crumbs(bc.max().width) {
+            if !self.crumbs.is_empty() {
+                x += self.separator_width;
+            }
+            let width = self.crumb_width(crumb);
+            self.crumbs
+                .push((crumb, Rect::new(x, 0.0, x + width, height)));
+            x += width;
+        }
+
+        let size = bc.constrain((x, height));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "Breadcrumbs", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &C, env: &Env) {
+        let clip = ctx.size().to_rect();
+        ctx.clip(clip);
+        for (i, &(crumb, rect)) in self.crumbs.iter().enumerate() {
+            if i > 0 {
+                let sep = Rect::new(rect.x0 - self.separator_width, rect.y0, rect.x0, rect.y1);
+                (self.separator)(ctx, sep, env);
+            }
+            if self.hot == Some(crumb) {
+                let color = if self.pressed == Some(crumb) {
+                    env.get(theme::BACKGROUND_DARK)
+                } else {
+                    env.get(theme::BACKGROUND_LIGHT)
+                };
+                ctx.fill(rect.to_rounded_rect(3.0), &color);
+            }
+            let text = match crumb {
+                Crumb::Segment(idx) => &self.segments[idx],
+                Crumb::Overflow => &self.overflow,
+            };
+            let y = rect.y0 + (rect.height() - text.size().height) / 2.0;
+            text.draw(ctx, (rect.x0 + SEGMENT_PADDING, y));
+        }
+    }
+
+    fn debug_state(&self, _data: &C) -> DebugState {
+        let path = self
+            .segments
+            .iter()
+            .filter_map(|segment| segment.text().cloned())
+            .collect::<Vec<_>>();
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: path.join(" / "),
+            ..Default::default()
+        }
+    }
+}
+
+impl OverflowPopup {
+    fn item_at(&self, pos: Point) -> Option<usize> {
+        let row = (pos.y / self.item_height).floor();
+        (row >= 0.0 && (row as usize) < self.items.len()).then_some(row as usize)
+    }
+}
+
+impl<C: Data> Widget<C> for OverflowPopup {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut C, _env: &Env) {
+        match event {
+            Event::MouseMove(mouse) => {
+                let hot = self.item_at(mouse.pos);
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() => {
+                if let Some(row) = self.item_at(mouse.pos) {
+                    let idx = self.items[row].0;
+                    ctx.submit_command(OVERFLOW_SELECTED.with(Some(idx)).to(self.breadcrumbs));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &C, _env: &Env) {
+        // The popup is dismissed when the mouse leaves it.
+        if let LifeCycle::HotChanged(false) = event {
+            ctx.submit_command(OVERFLOW_SELECTED.with(None).to(self.breadcrumbs));
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &C, _data: &C, _env: &Env) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &C, env: &Env) -> Size {
+        let mut width: f64 = 0.0;
+        let mut text_height: f64 = 0.0;
+        for (_, text) in &mut self.items {
+            text.rebuild_if_needed(ctx.text(), env);
+            width = width.max(text.size().width);
+            text_height = text_height.max(text.size().height);
+        }
+        self.item_height = text_height + 2.0 * SEGMENT_PADDING_VERTICAL;
+        let height = self.item_height * self.items.len() as f64;
+        bc.constrain((width + 2.0 * SEGMENT_PADDING, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &C, env: &Env) {
+        let size = ctx.size();
+        ctx.fill(size.to_rect(), &env.get(theme::BACKGROUND_DARK));
+        for (row, (_, text)) in self.items.iter().enumerate() {
+            let y = row as f64 * self.item_height;
+            if self.hot == Some(row) {
+                let rect = Rect::new(0.0, y, size.width, y + self.item_height);
+                ctx.fill(rect, &env.get(theme::PRIMARY_DARK));
+            }
+            text.draw(ctx, (SEGMENT_PADDING, y + SEGMENT_PADDING_VERTICAL));
+        }
+    }
+}
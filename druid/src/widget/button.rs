@@ -16,19 +16,24 @@
 
 use crate::debug_state::DebugState;
 use crate::widget::prelude::*;
-use crate::widget::{Click, ControllerHost, Label, LabelText};
-use crate::{theme, Affine, Data, Insets, LinearGradient, UnitPoint};
+use crate::widget::{Click, ControllerHost, Icon, IconData, Label, LabelText};
+use crate::{theme, Affine, Data, LinearGradient, Point, UnitPoint};
 use tracing::{instrument, trace};
 
 // the minimum padding added to a button.
 // NOTE: these values are chosen to match the existing look of TextBox; these
 // should be reevaluated at some point.
-const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
 
-/// A button with a text label.
+/// A button with a text label, and optionally an icon.
 pub struct Button<T> {
     label: Label<T>,
     label_size: Size,
+    icon: Option<Icon>,
+    icon_after_label: bool,
+    /// The size of the label and icon together, and where each of them goes.
+    content_size: Size,
+    label_origin: Point,
+    icon_origin: Point,
 }
 
 impl<T: Data> Button<T> {
@@ -69,10 +74,16 @@ impl<T: Data> Button<T> {
     /// ```
     ///
     /// [`on_click`]: #method.on_click
-    pub fn from_label(label: Label<T>) -> Button<T> {
+    pub fn from_label(mut label: Label<T>) -> Button<T> {
+        label.set_browsable(false);
         Button {
             label,
             label_size: Size::ZERO,
+            icon: None,
+            icon_after_label: false,
+            content_size: Size::ZERO,
+            label_origin: Point::ORIGIN,
+            icon_origin: Point::ORIGIN,
         }
     }
 
@@ -101,6 +112,31 @@ impl<T: Data> Button<T> {
         Button::new(text)
     }
 
+    /// Builder-style method for showing an icon before the label.
+    ///
+    /// The icon is lined up with the label's text, and is tinted like the text
+    /// while the button is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::{Button, IconData};
+    ///
+    /// let button: Button<()> = Button::new("Delete").with_icon(IconData::DELETE);
+    /// ```
+    pub fn with_icon(mut self, icon: IconData) -> Self {
+        self.icon = Some(Icon::new(icon));
+        self.icon_after_label = false;
+        self
+    }
+
+    /// Builder-style method for showing an icon after the label.
+    pub fn with_icon_after(mut self, icon: IconData) -> Self {
+        self.icon = Some(Icon::new(icon));
+        self.icon_after_label = true;
+        self
+    }
+
     /// Provide a closure to be called when this button is clicked.
     pub fn on_click(
         self,
@@ -142,24 +178,58 @@ impl<T: Data> Widget<T> for Button<T> {
 
     #[instrument(name = "Button", level = "trace", skip(self, ctx, old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if let Some(icon) = &mut self.icon {
+            icon.update(ctx, old_data, data, env);
+        }
         self.label.update(ctx, old_data, data, env)
     }
 
     #[instrument(name = "Button", level = "trace", skip(self, ctx, bc, data, env))]
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("Button");
-        let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
-        let label_bc = bc.shrink(padding).loosen();
+        let insets = env.get(theme::BUTTON_INSETS);
+        let padding = Size::new(insets.x_value(), insets.y_value());
+        let icon_size = match &self.icon {
+            Some(icon) => icon.resolved_size(env),
+            None => Size::ZERO,
+        };
+        let icon_space = match &self.icon {
+            Some(_) => icon_size.width + env.get(theme::WIDGET_CONTROL_COMPONENT_PADDING),
+            None => 0.0,
+        };
+        let label_bc = bc
+            .shrink((padding.width + icon_space, padding.height))
+            .loosen();
         self.label_size = self.label.layout(ctx, &label_bc, data, env);
+
+        // Line up the label's baseline with the icon's.
+        let label_descent = self.label.baseline_offset();
+        let label_ascent = self.label_size.height - label_descent;
+        let (icon_ascent, icon_descent) = match &self.icon {
+            Some(icon) => {
+                let descent = icon.baseline_offset(env);
+                (icon_size.height - descent, descent)
+            }
+            None => (0.0, 0.0),
+        };
+        let ascent = label_ascent.max(icon_ascent);
+        let descent = label_descent.max(icon_descent);
+        let (label_x, icon_x) = match self.icon_after_label {
+            true => (0.0, self.label_size.width + icon_space - icon_size.width),
+            false => (icon_space, 0.0),
+        };
+        self.label_origin = Point::new(label_x, ascent - label_ascent);
+        self.icon_origin = Point::new(icon_x, ascent - icon_ascent);
+        self.content_size = Size::new(self.label_size.width + icon_space, ascent + descent);
+
         // HACK: to make sure we look okay at default sizes when beside a textbox,
         // we make sure we will have at least the same height as the default textbox.
         let min_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
-        let baseline = self.label.baseline_offset();
-        ctx.set_baseline_offset(baseline + LABEL_INSETS.y1);
+        ctx.set_baseline_offset(descent + insets.y1);
 
         let button_size = bc.constrain(Size::new(
-            self.label_size.width + padding.width,
-            (self.label_size.height + padding.height).max(min_height),
+            self.content_size.width + padding.width,
+            (self.content_size.height + padding.height).max(min_height),
         ));
         trace!("Computed button size: {}", button_size);
         button_size
@@ -210,12 +280,16 @@ impl<T: Data> Widget<T> for Button<T> {
 
         ctx.fill(rounded_rect, &bg_gradient);
 
-        let label_offset = (size.to_vec2() - self.label_size.to_vec2()) / 2.0;
+        let content_offset = (size.to_vec2() - self.content_size.to_vec2()) / 2.0;
+        let label_offset = content_offset + self.label_origin.to_vec2();
 
         ctx.with_save(|ctx| {
             ctx.transform(Affine::translate(label_offset));
             self.label.paint(ctx, data, env);
         });
+        if let Some(icon) = &self.icon {
+            icon.draw(ctx, self.icon_origin + content_offset, env);
+        }
     }
 
     fn debug_state(&self, _data: &T) -> DebugState {
@@ -0,0 +1,424 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that draws a retained scene of shapes and images.
+
+use crate::kurbo::{BezPath, ParamCurveNearest, Shape};
+use crate::piet::{ImageBuf, InterpolationMode, PaintBrush, PietImage};
+use crate::widget::prelude::*;
+use crate::{Affine, Data, Point, Rect};
+use tracing::{instrument, trace};
+
+/// The accuracy used when hit testing strokes and converting shapes to paths.
+const TOLERANCE: f64 = 0.1;
+
+type HoverCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, Option<usize>, &Env)>;
+type ClickCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, usize, &Env)>;
+
+/// A shape or image in a [`Scene`].
+#[derive(Clone)]
+pub struct SceneItem {
+    kind: ItemKind,
+    fill: Option<PaintBrush>,
+    stroke: Option<(PaintBrush, f64)>,
+    transform: Affine,
+    hit_testable: bool,
+}
+
+#[derive(Clone)]
+enum ItemKind {
+    Path(BezPath),
+    Image(ImageBuf, Rect),
+}
+
+/// A list of [`SceneItem`]s, painted back to front.
+///
+/// Items are identified by their index, which is what the [`Canvas`] passes
+/// to its hover and click callbacks.
+#[derive(Clone, Default)]
+pub struct Scene {
+    items: Vec<SceneItem>,
+    bounds: Vec<Rect>,
+}
+
+/// A widget that draws a [`Scene`] built from its data.
+///
+/// The scene is only rebuilt when the data or the [`Env`] changes, and each
+/// item can be hit tested, which is used to report which item the mouse is
+/// over and which item was clicked.
+///
+/// The canvas asks for enough space to contain the whole scene, so it can be
+/// wrapped in a [`ClipBox`] or [`Scroll`] to pan around a large scene; only the
+/// items in the visible region are painted.
+///
+/// # Examples
+///
+/// ```
+/// use druid::kurbo::Circle;
+/// use druid::widget::{Canvas, Scene, SceneItem};
+/// use druid::Color;
+///
+/// let canvas = Canvas::new(|data: &Vec<(f64, f64)>, _env| {
+///     let mut scene = Scene::new();
+///     for &(x, y) in data {
+///         scene.push(SceneItem::shape(Circle::new((x, y), 10.0)).fill(Color::BLUE));
+///     }
+///     scene
+/// })
+/// .on_click(|_ctx, data, item, _env| {
+///     data.remove(item);
+/// });
+/// ```
+///
+/// [`ClipBox`]: super::ClipBox
+/// [`Scroll`]: super::Scroll
+pub struct Canvas<T> {
+    build_scene: Box<dyn Fn(&T, &Env) -> Scene>,
+    scene: Scene,
+    images: Vec<Option<PietImage>>,
+    hovered: Option<usize>,
+    pressed: Option<usize>,
+    on_hover: Option<HoverCallback<T>>,
+    on_click: Option<ClickCallback<T>>,
+}
+
+impl SceneItem {
+    /// Create an item from a shape.
+    ///
+    /// The shape is not painted until it is given a [`fill`] or a [`stroke`].
+    ///
+    /// [`fill`]: SceneItem::fill
+    /// [`stroke`]: SceneItem::stroke
+    pub fn shape(shape: impl Shape) -> Self {
+        Self::new(ItemKind::Path(shape.into_path(TOLERANCE)))
+    }
+
+    /// Create an item that draws an image, scaled to fill `rect`.
+    pub fn image(image: ImageBuf, rect: Rect) -> Self {
+        Self::new(ItemKind::Image(image, rect))
+    }
+
+    fn new(kind: ItemKind) -> Self {
+        SceneItem {
+            kind,
+            fill: None,
+            stroke: None,
+            transform: Affine::IDENTITY,
+            hit_testable: true,
+        }
+    }
+
+    /// Builder-style method for filling the shape with a brush.
+    pub fn fill(mut self, brush: impl Into<PaintBrush>) -> Self {
+        self.fill = Some(brush.into());
+        self
+    }
+
+    /// Builder-style method for stroking the outline of the shape.
+    pub fn stroke(mut self, brush: impl Into<PaintBrush>, width: f64) -> Self {
+        self.stroke = Some((brush.into(), width));
+        self
+    }
+
+    /// Builder-style method for setting a transform, applied to the item
+    /// both when painting and when hit testing.
+    pub fn transform(mut self, transform: Affine) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Builder-style method for setting whether this item responds to the mouse.
+    ///
+    /// Items that are not hit testable are ignored when looking for the item
+    /// under the mouse, so the items behind them can still be clicked.
+    pub fn hit_testable(mut self, hit_testable: bool) -> Self {
+        self.hit_testable = hit_testable;
+        self
+    }
+
+    /// The area covered by this item, in the canvas' coordinate space.
+    pub fn bounding_box(&self) -> Rect {
+        let local = match &self.kind {
+            ItemKind::Path(path) => {
+                let half_width = self.stroke.as_ref().map(|s| s.1 / 2.0).unwrap_or(0.0);
+                path.bounding_box().inflate(half_width, half_width)
+            }
+            ItemKind::Image(_, rect) => *rect,
+        };
+        self.transform.transform_rect_bbox(local)
+    }
+
+    /// Returns `true` if `point`, in the canvas' coordinate space, is inside
+    /// the filled area of this item or on its stroke.
+    pub fn hit_test(&self, point: Point) -> bool {
+        let point = self.transform.inverse() * point;
+        match &self.kind {
+            ItemKind::Path(path) => {
+                if self.fill.is_some() && path.contains(point) {
+                    return true;
+                }
+                match &self.stroke {
+                    Some((_, width)) => {
+                        let max_dist_sq = (width / 2.0).powi(2);
+                        path.segments()
+                            .any(|seg| seg.nearest(point, TOLERANCE).distance_sq <= max_dist_sq)
+                    }
+                    None => false,
+                }
+            }
+            ItemKind::Image(_, rect) => rect.contains(point),
+        }
+    }
+
+    fn paint(&self, ctx: &mut PaintCtx, image: &mut Option<PietImage>) {
+        ctx.with_save(|ctx| {
+            ctx.transform(self.transform);
+            match &self.kind {
+                ItemKind::Path(path) => {
+                    if let Some(brush) = &self.fill {
+                        ctx.fill(path, brush);
+                    }
+                    if let Some((brush, width)) = &self.stroke {
+                        ctx.stroke(path, brush, *width);
+                    }
+                }
+                ItemKind::Image(image_buf, rect) => {
+                    let image = image.get_or_insert_with(|| image_buf.to_image(ctx.render_ctx));
+                    ctx.draw_image(image, *rect, InterpolationMode::Bilinear);
+                }
+            }
+        });
+    }
+}
+
+impl Scene {
+    /// Create an empty scene.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item on top of the existing items, returning its index.
+    pub fn push(&mut self, item: SceneItem) -> usize {
+        self.bounds.push(item.bounding_box());
+        self.items.push(item);
+        self.items.len() - 1
+    }
+
+    /// Builder-style method to add an item on top of the existing items.
+    pub fn with_item(mut self, item: SceneItem) -> Self {
+        self.push(item);
+        self
+    }
+
+    /// The number of items in the scene.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the scene has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The item at `index`.
+    pub fn get(&self, index: usize) -> Option<&SceneItem> {
+        self.items.get(index)
+    }
+
+    /// The smallest rectangle containing all items.
+    pub fn bounding_box(&self) -> Rect {
+        self.bounds
+            .iter()
+            .copied()
+            .reduce(|acc, rect| acc.union(rect))
+            .unwrap_or(Rect::ZERO)
+    }
+
+    /// The index of the topmost hit testable item at `point`.
+    pub fn hit_test(&self, point: Point) -> Option<usize> {
+        self.items
+            .iter()
+            .zip(&self.bounds)
+            .enumerate()
+            .rev()
+            .find(|(_, (item, bounds))| {
+                item.hit_testable && bounds.contains(point) && item.hit_test(point)
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+impl<T: Data> Canvas<T> {
+    /// Create a canvas that draws the scene returned by `build_scene`.
+    ///
+    /// `build_scene` is called again whenever the data or the [`Env`] changes.
+    pub fn new(build_scene: impl Fn(&T, &Env) -> Scene + 'static) -> Self {
+        Canvas {
+            build_scene: Box::new(build_scene),
+            scene: Scene::new(),
+            images: Vec::new(),
+            hovered: None,
+            pressed: None,
+            on_hover: None,
+            on_click: None,
+        }
+    }
+
+    /// Builder-style method to provide a closure that is called when the
+    /// item under the mouse changes.
+    ///
+    /// The closure is passed the index of the new item, or `None` if the mouse
+    /// is not over any hit testable item.
+    pub fn on_hover(
+        mut self,
+        f: impl Fn(&mut EventCtx, &mut T, Option<usize>, &Env) + 'static,
+    ) -> Self {
+        self.on_hover = Some(Box::new(f));
+        self
+    }
+
+    /// Builder-style method to provide a closure that is called when an item
+    /// is clicked, with the index of that item.
+    pub fn on_click(mut self, f: impl Fn(&mut EventCtx, &mut T, usize, &Env) + 'static) -> Self {
+        self.on_click = Some(Box::new(f));
+        self
+    }
+
+    /// The scene currently displayed.
+    pub fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    /// The index of the item currently under the mouse.
+    pub fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    fn rebuild(&mut self, data: &T, env: &Env) {
+        self.scene = (self.build_scene)(data, env);
+        self.images.clear();
+        self.images.resize_with(self.scene.len(), || None);
+        self.pressed = None;
+        if matches!(self.hovered, Some(index) if index >= self.scene.len()) {
+            self.hovered = None;
+        }
+    }
+
+    fn set_hovered(&mut self, ctx: &mut EventCtx, data: &mut T, hovered: Option<usize>, env: &Env) {
+        if self.hovered != hovered {
+            self.hovered = hovered;
+            if let Some(on_hover) = &self.on_hover {
+                on_hover(ctx, data, hovered, env);
+            }
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Canvas<T> {
+    #[instrument(name = "Canvas", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(mouse) => {
+                // We also get a move when the mouse leaves the widget.
+                let hovered = if ctx.is_hot() {
+                    self.scene.hit_test(mouse.pos)
+                } else {
+                    None
+                };
+                self.set_hovered(ctx, data, hovered, env);
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                self.pressed = self.scene.hit_test(mouse.pos);
+                if self.pressed.is_some() {
+                    ctx.set_active(true);
+                    ctx.set_handled();
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() && ctx.is_active() => {
+                ctx.set_active(false);
+                let pressed = self.pressed.take();
+                if pressed.is_some() && pressed == self.scene.hit_test(mouse.pos) {
+                    if let (Some(on_click), Some(index)) = (&self.on_click, pressed) {
+                        on_click(ctx, data, index, env);
+                    }
+                }
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    #[instrument(name = "Canvas", level = "trace", skip(self, _ctx, event, data, env))]
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rebuild(data, env);
+        }
+    }
+
+    #[instrument(name = "Canvas", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) || ctx.env_changed() {
+            self.rebuild(data, env);
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Canvas", level = "trace", skip(self, _ctx, bc, _data, _env))]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.debug_check("Canvas");
+        let bounds = self.scene.bounding_box();
+        let size = bc.constrain(Size::new(bounds.x1.max(0.0), bounds.y1.max(0.0)));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Canvas", level = "trace", skip(self, ctx, _data, _env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, _env: &Env) {
+        let clip_rect = ctx.size().to_rect();
+        ctx.clip(clip_rect);
+        for (index, item) in self.scene.items.iter().enumerate() {
+            if ctx.region().intersects(self.scene.bounds[index]) {
+                item.paint(ctx, &mut self.images[index]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kurbo::{Circle, Line};
+    use crate::Color;
+
+    #[test]
+    fn hit_testing() {
+        let scene = Scene::new()
+            .with_item(SceneItem::shape(Rect::new(0., 0., 100., 100.)).fill(Color::RED))
+            .with_item(SceneItem::shape(Circle::new((50., 50.), 10.)).fill(Color::BLUE))
+            .with_item(
+                SceneItem::shape(Line::new((0., 200.), (100., 200.))).stroke(Color::BLACK, 4.),
+            )
+            .with_item(
+                SceneItem::shape(Rect::new(0., 0., 10., 10.))
+                    .fill(Color::WHITE)
+                    .hit_testable(false),
+            );
+
+        assert_eq!(scene.hit_test(Point::new(50., 50.)), Some(1));
+        assert_eq!(scene.hit_test(Point::new(5., 5.)), Some(0));
+        assert_eq!(scene.hit_test(Point::new(50., 201.)), Some(2));
+        assert_eq!(scene.hit_test(Point::new(50., 205.)), None);
+        assert_eq!(scene.bounding_box(), Rect::new(-2., 0., 102., 202.));
+    }
+}
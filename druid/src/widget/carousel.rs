@@ -0,0 +1,515 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows one of its pages at a time, and slides between them.
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::contexts::RequestCtx;
+use crate::debug_state::DebugState;
+use crate::kurbo::Circle;
+use crate::widget::prelude::*;
+use crate::{
+    theme, Affine, Data, KbKey, Lens, LensExt, Point, Rect, Selector, TimerToken, WidgetPod,
+};
+
+/// The default duration of the slide to another page.
+const DEFAULT_DURATION: Duration = Duration::from_millis(300);
+/// How far the mouse has to move before a press turns into a drag.
+const DRAG_SLOP: f64 = 8.0;
+/// The fraction of the width a drag has to cover to move to the next page.
+const DRAG_PAGE_FRACTION: f64 = 0.2;
+/// The height of the area at the bottom that holds the page indicator.
+const INDICATOR_HEIGHT: f64 = 24.0;
+const DOT_RADIUS: f64 = 3.5;
+const DOT_SPACING: f64 = 14.0;
+
+type Page<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+
+/// A running slide from one page to another.
+struct Slide {
+    /// The position the slide started at, in pages.
+    from: f64,
+    elapsed: u64,
+    duration: u64,
+}
+
+/// A mouse press that may turn into a drag.
+struct Drag {
+    start_x: f64,
+    start_position: f64,
+    dragging: bool,
+}
+
+enum PageRequest {
+    Next,
+    Previous,
+    Show(usize),
+}
+
+/// A widget that shows one page at a time, such as a set of onboarding screens
+/// or a gallery.
+///
+/// The user moves between pages by dragging them sideways, with the left and right
+/// arrow keys while the carousel has focus, or by clicking the dots of the page
+/// indicator. [`Carousel::NEXT_PAGE`], [`Carousel::PREVIOUS_PAGE`] and
+/// [`Carousel::SHOW_PAGE`] change the page as well; pages can send them as
+/// notifications, or they can be sent to the carousel as commands.
+///
+/// Pages slide into view, unless [`theme::REDUCED_MOTION`] is set. With
+/// [`with_auto_advance`], the carousel moves to the next page on a timer, wrapping
+/// around after the last one, and pausing while the mouse is over it.
+///
+/// The index of the current page can be kept in the data with [`with_index_lens`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use druid::widget::{Carousel, Label};
+/// use druid::{Data, Lens, Widget};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Tour {
+///     page: usize,
+/// }
+///
+/// fn tour() -> impl Widget<Tour> {
+///     Carousel::new()
+///         .with_page(Label::new("Welcome"))
+///         .with_page(Label::new("Organize your files"))
+///         .with_page(Label::new("Share them with friends"))
+///         .with_auto_advance(Duration::from_secs(5))
+///         .with_index_lens(Tour::page)
+/// }
+/// ```
+///
+/// [`theme::REDUCED_MOTION`]: crate::theme::REDUCED_MOTION
+/// [`with_auto_advance`]: Carousel::with_auto_advance
+/// [`with_index_lens`]: Carousel::with_index_lens
+pub struct Carousel<T> {
+    pages: Vec<Page<T>>,
+    current: usize,
+    /// The page that is shown, which is fractional while the pages move.
+    position: f64,
+    slide: Option<Slide>,
+    drag: Option<Drag>,
+    duration: Duration,
+    auto_advance: Option<Duration>,
+    timer: TimerToken,
+    show_indicator: bool,
+    get_index: Option<Box<dyn Fn(&T) -> usize>>,
+    put_index: Option<Box<dyn Fn(&mut T, usize)>>,
+}
+
+impl Carousel<()> {
+    /// Move to the next page.
+    pub const NEXT_PAGE: Selector = Selector::new("druid-builtin.carousel-next-page");
+
+    /// Move to the previous page.
+    pub const PREVIOUS_PAGE: Selector = Selector::new("druid-builtin.carousel-previous-page");
+
+    /// Move to the page with this index.
+    pub const SHOW_PAGE: Selector<usize> = Selector::new("druid-builtin.carousel-show-page");
+}
+
+impl<T: Data> Carousel<T> {
+    /// Create a carousel without any pages.
+    pub fn new() -> Self {
+        Carousel {
+            pages: Vec::new(),
+            current: 0,
+            position: 0.0,
+            slide: None,
+            drag: None,
+            duration: DEFAULT_DURATION,
+            auto_advance: None,
+            timer: TimerToken::INVALID,
+            show_indicator: true,
+            get_index: None,
+            put_index: None,
+        }
+    }
+
+    /// Builder-style method for adding a page.
+    pub fn with_page(mut self, page: impl Widget<T> + 'static) -> Self {
+        self.pages.push(WidgetPod::new(Box::new(page)));
+        self
+    }
+
+    /// Builder-style method for setting how long the slide to another page takes.
+    ///
+    /// The default is 300 milliseconds.
+    pub fn with_transition_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Builder-style method for moving to the next page every `interval`.
+    pub fn with_auto_advance(mut self, interval: Duration) -> Self {
+        self.auto_advance = Some(interval);
+        self
+    }
+
+    /// Builder-style method for setting whether the page indicator dots are shown.
+    ///
+    /// The default is `true`.
+    pub fn with_indicator(mut self, show: bool) -> Self {
+        self.show_indicator = show;
+        self
+    }
+
+    /// Builder-style method for keeping the index of the current page in the data.
+    ///
+    /// When the index in the data changes, the carousel slides to that page.
+    pub fn with_index_lens(mut self, lens: impl Lens<T, usize> + 'static) -> Self {
+        let lens = Rc::new(lens);
+        let put_lens = lens.clone();
+        self.get_index = Some(Box::new(move |data| lens.get(data)));
+        self.put_index = Some(Box::new(move |data, index| put_lens.put(data, index)));
+        self
+    }
+
+    /// The index of the current page.
+    pub fn current_page(&self) -> usize {
+        self.current
+    }
+
+    fn last_page(&self) -> usize {
+        self.pages.len().saturating_sub(1)
+    }
+
+    /// Slides from the current position to `index`.
+    fn go_to(&mut self, ctx: &mut impl RequestCtx, index: usize, env: &Env) {
+        let index = index.min(self.last_page());
+        let distance = (index as f64 - self.position).abs();
+        if index != self.current {
+            self.current = index;
+            ctx.children_changed();
+        }
+        let duration = (self.duration.as_nanos() as f64 * distance.min(1.0)) as u64;
+        if env.get(theme::REDUCED_MOTION) || duration == 0 {
+            self.slide = None;
+            self.position = index as f64;
+        } else {
+            self.slide = Some(Slide {
+                from: self.position,
+                elapsed: 0,
+                duration,
+            });
+            ctx.request_anim_frame();
+        }
+        self.restart_timer(ctx);
+        ctx.request_paint();
+    }
+
+    /// Moves to `index` because the user asked for it, and writes it to the data.
+    fn navigate(&mut self, ctx: &mut EventCtx, index: usize, data: &mut T, env: &Env) {
+        self.go_to(ctx, index, env);
+        if let Some(put_index) = &self.put_index {
+            put_index(data, self.current);
+        }
+    }
+
+    fn restart_timer(&mut self, ctx: &mut impl RequestCtx) {
+        if let Some(interval) = self.auto_advance {
+            self.timer = ctx.request_timer(interval);
+        }
+    }
+
+    fn is_moving(&self) -> bool {
+        self.slide.is_some() || matches!(self.drag, Some(Drag { dragging: true, .. }))
+    }
+
+    /// The index of the dot at `pos`.
+    fn dot_at(&self, pos: Point, size: Size) -> Option<usize> {
+        if !self.show_indicator || pos.y < size.height - INDICATOR_HEIGHT {
+            return None;
+        }
+        let first = self.dot_center(0, size).x;
+        let index = ((pos.x - first) / DOT_SPACING).round();
+        (index >= 0.0 && (index as usize) < self.pages.len()).then_some(index as usize)
+    }
+
+    fn dot_center(&self, index: usize, size: Size) -> Point {
+        let width = self.last_page() as f64 * DOT_SPACING;
+        Point::new(
+            (size.width - width) / 2.0 + index as f64 * DOT_SPACING,
+            size.height - INDICATOR_HEIGHT / 2.0,
+        )
+    }
+}
+
+impl<T: Data> Default for Carousel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Carousel<T> {
+    #[instrument(name = "Carousel", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Some(request) = page_request(event) {
+            let index = match request {
+                PageRequest::Next => (self.current + 1).min(self.last_page()),
+                PageRequest::Previous => self.current.saturating_sub(1),
+                PageRequest::Show(index) => index,
+            };
+            self.navigate(ctx, index, data, env);
+            ctx.set_handled();
+            return;
+        }
+
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                if ctx.is_hot() || self.drag.is_some() {
+                    self.restart_timer(ctx);
+                } else {
+                    let next = match self.current == self.last_page() {
+                        true => 0,
+                        false => self.current + 1,
+                    };
+                    self.navigate(ctx, next, data, env);
+                }
+                return;
+            }
+            Event::AnimFrame(interval) => {
+                if let Some(slide) = &mut self.slide {
+                    // We can get a high interval on the first frame due to other widgets or old animations.
+                    slide.elapsed += if slide.elapsed == 0 { 1 } else { *interval };
+                    let t = (slide.elapsed as f64 / slide.duration as f64).min(1.0);
+                    let eased = 1.0 - (1.0 - t).powi(3);
+                    let to = self.current as f64;
+                    self.position = slide.from + (to - slide.from) * eased;
+                    if t < 1.0 {
+                        ctx.request_anim_frame();
+                    } else {
+                        self.slide = None;
+                    }
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() && !ctx.is_disabled() => {
+                ctx.request_focus();
+                if let Some(index) = self.dot_at(mouse.pos, ctx.size()) {
+                    self.navigate(ctx, index, data, env);
+                    ctx.set_handled();
+                    return;
+                }
+                self.drag = Some(Drag {
+                    start_x: mouse.pos.x,
+                    start_position: self.position,
+                    dragging: false,
+                });
+            }
+            Event::MouseMove(mouse) => {
+                let width = ctx.size().width.max(1.0);
+                let last = self.last_page() as f64;
+                if let Some(drag) = &mut self.drag {
+                    let dx = mouse.pos.x - drag.start_x;
+                    if !drag.dragging && dx.abs() > DRAG_SLOP && self.pages.len() > 1 {
+                        drag.dragging = true;
+                        self.slide = None;
+                        ctx.set_active(true);
+                        ctx.children_changed();
+                    }
+                    if drag.dragging {
+                        self.position = (drag.start_position - dx / width).clamp(0.0, last);
+                        ctx.request_paint();
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+            }
+            Event::MouseUp(_) => {
+                if let Some(drag) = self.drag.take() {
+                    if drag.dragging {
+                        ctx.set_active(false);
+                        let moved = self.position - self.current as f64;
+                        let index = if moved > DRAG_PAGE_FRACTION {
+                            self.current + 1
+                        } else if moved < -DRAG_PAGE_FRACTION {
+                            self.current.saturating_sub(1)
+                        } else {
+                            self.current
+                        };
+                        self.navigate(ctx, index, data, env);
+                        ctx.children_changed();
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() && !self.is_moving() => {
+                let index = match key.key {
+                    KbKey::ArrowLeft => self.current.saturating_sub(1),
+                    KbKey::ArrowRight => (self.current + 1).min(self.last_page()),
+                    KbKey::Home => 0,
+                    KbKey::End => self.last_page(),
+                    _ => self.current,
+                };
+                if index != self.current {
+                    self.navigate(ctx, index, data, env);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            _ => (),
+        }
+
+        if event.should_propagate_to_hidden() {
+            for page in &mut self.pages {
+                page.event(ctx, event, data, env);
+            }
+        } else if !self.is_moving() {
+            if let Some(page) = self.pages.get_mut(self.current) {
+                page.event(ctx, event, data, env);
+            }
+        }
+    }
+
+    #[instrument(name = "Carousel", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.register_for_focus();
+            if let Some(get_index) = &self.get_index {
+                self.current = get_index(data).min(self.last_page());
+                self.position = self.current as f64;
+            }
+            self.restart_timer(ctx);
+        }
+        if let LifeCycle::FocusChanged(_) = event {
+            ctx.request_paint();
+        }
+        if event.should_propagate_to_hidden() {
+            for page in &mut self.pages {
+                page.lifecycle(ctx, event, data, env);
+            }
+        } else if let Some(page) = self.pages.get_mut(self.current) {
+            page.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Carousel",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if let Some(get_index) = &self.get_index {
+            let index = get_index(data).min(self.last_page());
+            if index != self.current {
+                self.go_to(ctx, index, env);
+            }
+        }
+        // Hidden pages are updated too, so they are current when they slide into view.
+        for page in &mut self.pages {
+            page.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Carousel", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Carousel");
+        let mut size = Size::ZERO;
+        for page in &mut self.pages {
+            let page_size = page.layout(ctx, bc, data, env);
+            page.set_origin(ctx, Point::ORIGIN);
+            size = Size::new(
+                size.width.max(page_size.width),
+                size.height.max(page_size.height),
+            );
+        }
+        let size = bc.constrain(size);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Carousel", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        ctx.clip(size.to_rect());
+
+        let first = self.position.floor().max(0.0) as usize;
+        let last = (self.position.ceil() as usize).min(self.last_page());
+        for index in first..=last {
+            if let Some(page) = self.pages.get_mut(index) {
+                let x = (index as f64 - self.position) * size.width;
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate((x, 0.0)));
+                    page.paint_raw(ctx, data, env);
+                });
+            }
+        }
+
+        if self.show_indicator && self.pages.len() > 1 {
+            let active = env.get(theme::PRIMARY_LIGHT);
+            let inactive = env.get(theme::BORDER_LIGHT);
+            for index in 0..self.pages.len() {
+                // The dots of the pages on either side of the position blend between the colors.
+                let closeness = 1.0 - (index as f64 - self.position).abs().min(1.0);
+                let dot = Circle::new(self.dot_center(index, size), DOT_RADIUS);
+                ctx.fill(dot, &inactive);
+                if closeness > 0.0 {
+                    ctx.fill(dot, &active.with_alpha(closeness));
+                }
+            }
+        }
+
+        if ctx.is_focused() {
+            let rect = Rect::from_origin_size(Point::ORIGIN, size).inset(-1.0);
+            ctx.paint_focus_decoration(rect, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("page {} of {}", self.current + 1, self.pages.len()),
+            children: self
+                .pages
+                .get(self.current)
+                .map(|page| page.widget().debug_state(data))
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+fn page_request(event: &Event) -> Option<PageRequest> {
+    let (next, previous, show) = match event {
+        Event::Notification(note) => (
+            note.is(Carousel::NEXT_PAGE),
+            note.is(Carousel::PREVIOUS_PAGE),
+            note.get(Carousel::SHOW_PAGE),
+        ),
+        Event::Command(cmd) => (
+            cmd.is(Carousel::NEXT_PAGE),
+            cmd.is(Carousel::PREVIOUS_PAGE),
+            cmd.get(Carousel::SHOW_PAGE),
+        ),
+        _ => return None,
+    };
+    match (next, previous, show) {
+        (true, _, _) => Some(PageRequest::Next),
+        (_, true, _) => Some(PageRequest::Previous),
+        (_, _, Some(&index)) => Some(PageRequest::Show(index)),
+        _ => None,
+    }
+}
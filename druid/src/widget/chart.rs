@@ -0,0 +1,799 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simple charts of list data.
+//!
+//! [`LineChart`] and [`ScatterChart`] plot a list of [`Point`]s, and
+//! [`BarChart`] plots a list of `f64` values, one bar per value. Axes and
+//! their labels are chosen automatically to fit the data, and hovering over
+//! a data point shows its value in a tooltip.
+//!
+//! The colors used are taken from the [`theme`] keys starting with `CHART_`.
+//! When data is only appended to, or only its newest point changes, and the
+//! axes stay the same, only the affected part of the chart is repainted.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use druid::widget::chart::LineChart;
+//! use druid::{Color, Point, Widget};
+//!
+//! fn build_chart() -> impl Widget<Arc<Vec<Point>>> {
+//!     LineChart::new().with_color(Color::rgb8(0xdd, 0x44, 0x44))
+//! }
+//! ```
+//!
+//! [`theme`]: crate::theme
+
+use crate::kurbo::{BezPath, Circle, Line};
+use crate::widget::prelude::*;
+use crate::widget::ListIter;
+use crate::{theme, ArcStr, Color, KeyOrValue, Point, Rect, TextLayout, Vec2};
+use tracing::{instrument, trace};
+
+const DEFAULT_SIZE: Size = Size::new(300.0, 200.0);
+/// The number of ticks we aim for on each axis.
+const TICK_COUNT: usize = 5;
+const TICK_LENGTH: f64 = 4.0;
+const LABEL_PADDING: f64 = 4.0;
+const PLOT_PADDING: f64 = 8.0;
+const LINE_WIDTH: f64 = 2.0;
+const POINT_RADIUS: f64 = 3.0;
+const HOVER_RADIUS: f64 = 8.0;
+/// The fraction of the space available to each bar that it fills.
+const BAR_FILL: f64 = 0.8;
+const TOOLTIP_PADDING: f64 = 4.0;
+
+/// A chart that connects a list of points with lines.
+pub struct LineChart {
+    chart: Chart,
+}
+
+/// A chart that draws a list of points as dots.
+pub struct ScatterChart {
+    chart: Chart,
+}
+
+/// A chart that draws a list of values as vertical bars.
+pub struct BarChart {
+    chart: Chart,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Line,
+    Scatter,
+    Bar,
+}
+
+/// What needs to be repainted after the data changed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Repaint {
+    None,
+    Rect(Rect),
+    All,
+}
+
+struct Tick {
+    value: f64,
+    label: TextLayout<ArcStr>,
+}
+
+#[derive(Default)]
+struct Axis {
+    min: f64,
+    max: f64,
+    step: f64,
+    ticks: Vec<Tick>,
+}
+
+/// The state shared by all chart types.
+struct Chart {
+    kind: Kind,
+    color: KeyOrValue<Color>,
+    points: Vec<Point>,
+    x_axis: Axis,
+    y_axis: Axis,
+    plot: Rect,
+    hovered: Option<usize>,
+    tooltip: TextLayout<String>,
+}
+
+impl LineChart {
+    /// Create a new line chart.
+    pub fn new() -> Self {
+        LineChart {
+            chart: Chart::new(Kind::Line),
+        }
+    }
+
+    /// Builder-style method to set the color of the line.
+    ///
+    /// The default is [`theme::CHART_SERIES_COLOR`].
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.chart.color = color.into();
+        self
+    }
+}
+
+impl ScatterChart {
+    /// Create a new scatter chart.
+    pub fn new() -> Self {
+        ScatterChart {
+            chart: Chart::new(Kind::Scatter),
+        }
+    }
+
+    /// Builder-style method to set the color of the points.
+    ///
+    /// The default is [`theme::CHART_SERIES_COLOR`].
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.chart.color = color.into();
+        self
+    }
+}
+
+impl BarChart {
+    /// Create a new bar chart.
+    pub fn new() -> Self {
+        BarChart {
+            chart: Chart::new(Kind::Bar),
+        }
+    }
+
+    /// Builder-style method to set the color of the bars.
+    ///
+    /// The default is [`theme::CHART_SERIES_COLOR`].
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.chart.color = color.into();
+        self
+    }
+}
+
+impl Default for LineChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for ScatterChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for BarChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn collect_points(data: &impl ListIter<Point>) -> Vec<Point> {
+    let mut points = Vec::with_capacity(data.data_len());
+    data.for_each(|point, _| points.push(*point));
+    points
+}
+
+fn collect_bars(data: &impl ListIter<f64>) -> Vec<Point> {
+    let mut points = Vec::with_capacity(data.data_len());
+    data.for_each(|value, i| points.push(Point::new(i as f64, *value)));
+    points
+}
+
+impl<T: ListIter<Point>> Widget<T> for LineChart {
+    #[instrument(
+        name = "LineChart",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        self.chart.event(ctx, event);
+    }
+
+    #[instrument(
+        name = "LineChart",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.chart.set_points(collect_points(data));
+        }
+        self.chart.lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "LineChart",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) {
+            let repaint = self.chart.set_points(collect_points(data));
+            self.chart.request_repaint(ctx, repaint);
+        }
+        self.chart.update_env(ctx);
+    }
+
+    #[instrument(name = "LineChart", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("LineChart");
+        self.chart.layout(ctx, bc, env)
+    }
+
+    #[instrument(name = "LineChart", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        self.chart.paint(ctx, env);
+    }
+}
+
+impl<T: ListIter<Point>> Widget<T> for ScatterChart {
+    #[instrument(
+        name = "ScatterChart",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        self.chart.event(ctx, event);
+    }
+
+    #[instrument(
+        name = "ScatterChart",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.chart.set_points(collect_points(data));
+        }
+        self.chart.lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "ScatterChart",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) {
+            let repaint = self.chart.set_points(collect_points(data));
+            self.chart.request_repaint(ctx, repaint);
+        }
+        self.chart.update_env(ctx);
+    }
+
+    #[instrument(
+        name = "ScatterChart",
+        level = "trace",
+        skip(self, ctx, bc, _data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("ScatterChart");
+        self.chart.layout(ctx, bc, env)
+    }
+
+    #[instrument(name = "ScatterChart", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        self.chart.paint(ctx, env);
+    }
+}
+
+impl<T: ListIter<f64>> Widget<T> for BarChart {
+    #[instrument(
+        name = "BarChart",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        self.chart.event(ctx, event);
+    }
+
+    #[instrument(name = "BarChart", level = "trace", skip(self, ctx, event, data, _env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.chart.set_points(collect_bars(data));
+        }
+        self.chart.lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "BarChart",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) {
+            let repaint = self.chart.set_points(collect_bars(data));
+            self.chart.request_repaint(ctx, repaint);
+        }
+        self.chart.update_env(ctx);
+    }
+
+    #[instrument(name = "BarChart", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("BarChart");
+        self.chart.layout(ctx, bc, env)
+    }
+
+    #[instrument(name = "BarChart", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        self.chart.paint(ctx, env);
+    }
+}
+
+impl Axis {
+    /// Choose the range and ticks of this axis so that they cover `min..=max`.
+    ///
+    /// Returns `true` if the axis changed.
+    fn fit(&mut self, min: f64, max: f64) -> bool {
+        let (min, max, step) = nice_range(min, max, TICK_COUNT);
+        self.set(min, max, step, min)
+    }
+
+    /// Set up this axis for `count` bars, centered on whole numbers.
+    fn fit_bars(&mut self, count: usize) -> bool {
+        let step = nice_number(count as f64 / TICK_COUNT as f64).max(1.0);
+        self.set(-0.5, count.max(1) as f64 - 0.5, step, 0.0)
+    }
+
+    fn set(&mut self, min: f64, max: f64, step: f64, first_tick: f64) -> bool {
+        if (min, max, step) == (self.min, self.max, self.step) && !self.ticks.is_empty() {
+            return false;
+        }
+        self.min = min;
+        self.max = max;
+        self.step = step;
+        let first = min + (first_tick - min).rem_euclid(step);
+        let count = ((max - first) / step + 1e-9).floor() as usize;
+        self.ticks = (0..=count)
+            .map(|i| {
+                let value = first + i as f64 * step;
+                let mut label = TextLayout::from_text(format_value(value, step));
+                label.set_text_size(theme::CHART_LABEL_SIZE);
+                label.set_text_color(theme::CHART_AXIS_COLOR);
+                Tick { value, label }
+            })
+            .collect();
+        true
+    }
+
+    /// The position of `value` along this axis, from `0.0` to `1.0`.
+    fn fraction(&self, value: f64) -> f64 {
+        (value - self.min) / (self.max - self.min)
+    }
+}
+
+impl Chart {
+    fn new(kind: Kind) -> Self {
+        let mut tooltip = TextLayout::new();
+        tooltip.set_text_size(theme::CHART_LABEL_SIZE);
+        Chart {
+            kind,
+            color: theme::CHART_SERIES_COLOR.into(),
+            points: Vec::new(),
+            x_axis: Axis::default(),
+            y_axis: Axis::default(),
+            plot: Rect::ZERO,
+            hovered: None,
+            tooltip,
+        }
+    }
+
+    /// Replace the points being displayed.
+    fn set_points(&mut self, points: Vec<Point>) -> Repaint {
+        if points == self.points && !self.x_axis.ticks.is_empty() {
+            return Repaint::None;
+        }
+        let axes_changed = self.fit_axes(&points);
+
+        // The newest point may have been updated in place; everything before it
+        // has to be the same for a partial repaint.
+        let old_len = self.points.len();
+        let stable = old_len.saturating_sub(1);
+        let appended = points.len() >= old_len && points[..stable] == self.points[..stable];
+        let same_bars = self.kind != Kind::Bar || points.len() == old_len;
+        let partial = !axes_changed && appended && same_bars && self.hovered.is_none();
+
+        let repaint = if partial {
+            let first_changed = self
+                .points
+                .iter()
+                .zip(&points)
+                .position(|(old, new)| old != new)
+                .unwrap_or(old_len);
+            // Lines also connect to the point before the first one that changed.
+            let from = first_changed.saturating_sub(1);
+            let dirty = self.points[from..]
+                .iter()
+                .chain(&points[from..])
+                .map(|point| self.point_rect(*point))
+                .reduce(|acc, rect| acc.union(rect));
+            match dirty {
+                Some(rect) => Repaint::Rect(rect),
+                None => Repaint::None,
+            }
+        } else {
+            Repaint::All
+        };
+
+        self.points = points;
+        if matches!(self.hovered, Some(i) if i >= self.points.len()) {
+            self.hovered = None;
+        }
+        repaint
+    }
+
+    fn fit_axes(&mut self, points: &[Point]) -> bool {
+        let (mut y_min, mut y_max) = min_max(points.iter().map(|p| p.y));
+        let x_changed = if self.kind == Kind::Bar {
+            y_min = y_min.min(0.0);
+            y_max = y_max.max(0.0);
+            self.x_axis.fit_bars(points.len())
+        } else {
+            let (x_min, x_max) = min_max(points.iter().map(|p| p.x));
+            self.x_axis.fit(x_min, x_max)
+        };
+        let y_changed = self.y_axis.fit(y_min, y_max);
+        x_changed || y_changed
+    }
+
+    /// The area that a data point may be painted in, including the full height
+    /// of its column, so that it also covers where the point used to be.
+    fn point_rect(&self, point: Point) -> Rect {
+        let x = self.to_screen(point).x;
+        let half_width = if self.kind == Kind::Bar {
+            self.bar_width() / 2.0
+        } else {
+            HOVER_RADIUS
+        };
+        Rect::new(x - half_width, self.plot.y0, x + half_width, self.plot.y1)
+            .inflate(LINE_WIDTH, POINT_RADIUS + LINE_WIDTH)
+    }
+
+    fn request_repaint(&self, ctx: &mut UpdateCtx, repaint: Repaint) {
+        match repaint {
+            Repaint::None => {}
+            Repaint::Rect(rect) => ctx.request_paint_rect(rect),
+            Repaint::All => ctx.request_layout(),
+        }
+    }
+
+    fn update_env(&mut self, ctx: &mut UpdateCtx) {
+        let mut relayout = self.tooltip.needs_rebuild_after_update(ctx);
+        for tick in self.x_axis.ticks.iter_mut().chain(&mut self.y_axis.ticks) {
+            relayout |= tick.label.needs_rebuild_after_update(ctx);
+        }
+        if relayout {
+            ctx.request_layout();
+        } else if ctx.env_changed() {
+            ctx.request_paint();
+        }
+    }
+
+    fn to_screen(&self, point: Point) -> Point {
+        Point::new(
+            self.plot.x0 + self.x_axis.fraction(point.x) * self.plot.width(),
+            self.plot.y1 - self.y_axis.fraction(point.y) * self.plot.height(),
+        )
+    }
+
+    fn bar_width(&self) -> f64 {
+        self.plot.width() / (self.x_axis.max - self.x_axis.min) * BAR_FILL
+    }
+
+    fn bar_rect(&self, point: Point) -> Rect {
+        let top = self.to_screen(point);
+        let base = self.to_screen(Point::new(point.x, 0.0)).y;
+        let half_width = self.bar_width() / 2.0;
+        Rect::new(top.x - half_width, top.y, top.x + half_width, base).abs()
+    }
+
+    /// The index of the data point at `pos`.
+    fn hit_test(&self, pos: Point) -> Option<usize> {
+        if self.kind == Kind::Bar {
+            return self
+                .points
+                .iter()
+                .position(|point| self.bar_rect(*point).contains(pos));
+        }
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| (i, (self.to_screen(*point) - pos).hypot()))
+            .filter(|(_, distance)| *distance <= HOVER_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    fn set_hovered(&mut self, hovered: Option<usize>) -> bool {
+        if self.hovered == hovered {
+            return false;
+        }
+        self.hovered = hovered;
+        if let Some(point) = hovered.map(|i| self.points[i]) {
+            let text = match self.kind {
+                Kind::Bar => format_value(point.y, self.y_axis.step / 100.0),
+                _ => format!(
+                    "{}, {}",
+                    format_value(point.x, self.x_axis.step / 100.0),
+                    format_value(point.y, self.y_axis.step / 100.0)
+                ),
+            };
+            self.tooltip.set_text(text);
+        }
+        true
+    }
+
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event) {
+        if let Event::MouseMove(mouse) = event {
+            let hovered = if ctx.is_hot() {
+                self.hit_test(mouse.pos)
+            } else {
+                None
+            };
+            if self.set_hovered(hovered) {
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(false) = event {
+            if self.set_hovered(None) {
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        self.tooltip.rebuild_if_needed(ctx.text(), env);
+        let mut label_size = Size::ZERO;
+        for tick in &mut self.x_axis.ticks {
+            tick.label.rebuild_if_needed(ctx.text(), env);
+            label_size.height = label_size.height.max(tick.label.size().height);
+        }
+        for tick in &mut self.y_axis.ticks {
+            tick.label.rebuild_if_needed(ctx.text(), env);
+            label_size.width = label_size.width.max(tick.label.size().width);
+        }
+
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            DEFAULT_SIZE.width
+        };
+        let height = if bc.is_height_bounded() {
+            bc.max().height
+        } else {
+            DEFAULT_SIZE.height
+        };
+        let size = bc.constrain(Size::new(width, height));
+
+        let x0 = label_size.width + TICK_LENGTH + LABEL_PADDING;
+        let y1 = size.height - label_size.height - TICK_LENGTH - LABEL_PADDING;
+        self.plot = Rect::new(
+            x0,
+            PLOT_PADDING,
+            (size.width - PLOT_PADDING).max(x0),
+            y1.max(PLOT_PADDING),
+        );
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env) {
+        let axis_color = env.get(theme::CHART_AXIS_COLOR);
+        let grid_color = env.get(theme::CHART_GRID_COLOR);
+        let color = self.color.resolve(env);
+        let plot = self.plot;
+
+        for tick in &self.y_axis.ticks {
+            let y = self.to_screen(Point::new(self.x_axis.min, tick.value)).y;
+            ctx.stroke(Line::new((plot.x0, y), (plot.x1, y)), &grid_color, 1.0);
+            ctx.stroke(
+                Line::new((plot.x0 - TICK_LENGTH, y), (plot.x0, y)),
+                &axis_color,
+                1.0,
+            );
+            let size = tick.label.size();
+            let origin = (
+                plot.x0 - TICK_LENGTH - LABEL_PADDING - size.width,
+                y - size.height / 2.0,
+            );
+            tick.label.draw(ctx, origin);
+        }
+        for tick in &self.x_axis.ticks {
+            let x = self.to_screen(Point::new(tick.value, self.y_axis.min)).x;
+            ctx.stroke(
+                Line::new((x, plot.y1), (x, plot.y1 + TICK_LENGTH)),
+                &axis_color,
+                1.0,
+            );
+            let size = tick.label.size();
+            let origin = (x - size.width / 2.0, plot.y1 + TICK_LENGTH + LABEL_PADDING);
+            tick.label.draw(ctx, origin);
+        }
+        let mut axes = BezPath::new();
+        axes.move_to((plot.x0, plot.y0));
+        axes.line_to((plot.x0, plot.y1));
+        axes.line_to((plot.x1, plot.y1));
+        ctx.stroke(axes, &axis_color, 1.0);
+
+        ctx.with_save(|ctx| {
+            ctx.clip(plot.inflate(HOVER_RADIUS, HOVER_RADIUS));
+            match self.kind {
+                Kind::Line => {
+                    let mut line = BezPath::new();
+                    for (i, point) in self.points.iter().enumerate() {
+                        let point = self.to_screen(*point);
+                        if i == 0 {
+                            line.move_to(point);
+                        } else {
+                            line.line_to(point);
+                        }
+                    }
+                    ctx.stroke(line, &color, LINE_WIDTH);
+                    if let Some(i) = self.hovered {
+                        let point = self.to_screen(self.points[i]);
+                        ctx.fill(Circle::new(point, POINT_RADIUS * 1.5), &color);
+                    }
+                }
+                Kind::Scatter => {
+                    for (i, point) in self.points.iter().enumerate() {
+                        let radius = if self.hovered == Some(i) {
+                            POINT_RADIUS * 1.5
+                        } else {
+                            POINT_RADIUS
+                        };
+                        ctx.fill(Circle::new(self.to_screen(*point), radius), &color);
+                    }
+                }
+                Kind::Bar => {
+                    for (i, point) in self.points.iter().enumerate() {
+                        let rect = self.bar_rect(*point);
+                        ctx.fill(rect, &color);
+                        if self.hovered == Some(i) {
+                            ctx.stroke(rect, &axis_color, 1.5);
+                        }
+                    }
+                }
+            }
+        });
+
+        if let Some(i) = self.hovered {
+            self.paint_tooltip(ctx, env, self.points[i]);
+        }
+    }
+
+    fn paint_tooltip(&mut self, ctx: &mut PaintCtx, env: &Env, point: Point) {
+        self.tooltip.rebuild_if_needed(ctx.text(), env);
+        let anchor = match self.kind {
+            Kind::Bar => {
+                let rect = self.bar_rect(point);
+                Point::new(rect.center().x, rect.y0)
+            }
+            _ => self.to_screen(point),
+        };
+        let size = self.tooltip.size() + Size::new(TOOLTIP_PADDING, TOOLTIP_PADDING) * 2.0;
+        let bounds = ctx.size().to_rect();
+        let mut origin = Point::new(
+            anchor.x - size.width / 2.0,
+            anchor.y - size.height - HOVER_RADIUS,
+        );
+        origin.x = origin.x.min(bounds.x1 - size.width).max(bounds.x0);
+        if origin.y < bounds.y0 {
+            origin.y = anchor.y + HOVER_RADIUS;
+        }
+        let rect = Rect::from_origin_size(origin, size).to_rounded_rect(3.0);
+        ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(rect, &env.get(theme::BORDER_DARK), 1.0);
+        self.tooltip
+            .draw(ctx, origin + Vec2::new(TOOLTIP_PADDING, TOOLTIP_PADDING));
+    }
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values
+        .filter(|value| value.is_finite())
+        .fold(None, |acc: Option<(f64, f64)>, value| match acc {
+            Some((min, max)) => Some((min.min(value), max.max(value))),
+            None => Some((value, value)),
+        })
+        .unwrap_or((0.0, 1.0))
+}
+
+/// Round `value` to the nearest of 1, 2 or 5 times a power of ten.
+fn nice_number(value: f64) -> f64 {
+    let magnitude = 10f64.powf(value.log10().floor());
+    let fraction = value / magnitude;
+    let nice = if fraction < 1.5 {
+        1.0
+    } else if fraction < 3.0 {
+        2.0
+    } else if fraction < 7.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// Extend `min..max` to multiples of a round step, so that it is divided into
+/// roughly `count` ticks. Returns the new range and the step.
+fn nice_range(min: f64, max: f64, count: usize) -> (f64, f64, f64) {
+    let (min, max) = if max - min < f64::EPSILON * max.abs().max(1.0) {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    };
+    let step = nice_number((max - min) / (count - 1) as f64);
+    (
+        (min / step).floor() * step,
+        (max / step).ceil() * step,
+        step,
+    )
+}
+
+/// Format `value` with as many decimals as `precision` needs.
+fn format_value(value: f64, precision: f64) -> String {
+    let decimals = if precision >= 1.0 {
+        0
+    } else {
+        (-precision.log10()).ceil() as usize
+    };
+    let text = format!("{:.*}", decimals, value);
+    if decimals > 0 && text.contains('.') {
+        let trimmed = text.trim_end_matches('0').trim_end_matches('.');
+        if trimmed == "-0" {
+            return "0".into();
+        }
+        return trimmed.into();
+    }
+    if text == "-0" {
+        "0".into()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticks() {
+        assert_eq!(nice_range(0.0, 97.0, 5), (0.0, 100.0, 20.0));
+        assert_eq!(nice_range(-3.0, 3.0, 5), (-4.0, 4.0, 2.0));
+        assert_eq!(nice_range(5.0, 5.0, 5), (4.0, 6.0, 0.5));
+        assert_eq!(format_value(0.5, 0.5), "0.5");
+        assert_eq!(format_value(-0.0, 1.0), "0");
+        assert_eq!(format_value(12.0, 0.01), "12");
+    }
+
+    #[test]
+    fn incremental_repaint() {
+        let mut chart = Chart::new(Kind::Line);
+        chart.plot = Rect::new(0., 0., 100., 100.);
+        let points = vec![Point::new(0., 0.), Point::new(1., 5.), Point::new(2., 3.)];
+        assert_eq!(chart.set_points(points.clone()), Repaint::All);
+        assert_eq!(chart.set_points(points.clone()), Repaint::None);
+
+        let mut moved = points.clone();
+        moved[2].y = 4.0;
+        assert!(matches!(chart.set_points(moved), Repaint::Rect(_)));
+
+        let mut changed = points;
+        changed[0].y = 1.0;
+        assert_eq!(chart.set_points(changed), Repaint::All);
+    }
+}
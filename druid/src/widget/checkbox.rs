@@ -33,7 +33,8 @@ impl Checkbox {
     }
 
     /// Create a new `Checkbox` with the provided [`Label`].
-    pub fn from_label(label: Label<bool>) -> Checkbox {
+    pub fn from_label(mut label: Label<bool>) -> Checkbox {
+        label.set_browsable(false);
         Checkbox { child_label: label }
     }
 
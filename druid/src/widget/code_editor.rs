@@ -0,0 +1,555 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A text editor for source code.
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::piet::{
+    PietTextLayoutBuilder, Text, TextLayout as _, TextLayoutBuilder as _,
+    TextStorage as PietTextStorage,
+};
+use crate::text::{
+    Attribute, AttributeSpans, EditableText, EnvUpdateCtx, Link, Selection, StringCursor,
+    TextStorage,
+};
+use crate::widget::prelude::*;
+use crate::widget::{Findable, TextBox};
+use crate::{theme, FontDescriptor, FontFamily, KeyOrValue, Point, Rect, Vec2, WidgetPod};
+
+/// The horizontal padding on each side of the line numbers.
+const GUTTER_PADDING: f64 = 8.0;
+/// The fewest digits the gutter makes room for, so it doesn't resize on the first lines.
+const GUTTER_MIN_DIGITS: usize = 2;
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+/// A source of syntax highlighting for a [`CodeEditor`].
+///
+/// This is implemented for closures with the same signature as [`Highlighter::highlight`],
+/// and can be implemented to wire in a highlighting library such as `syntect`.
+pub trait Highlighter {
+    /// Compute the styles for `text`.
+    ///
+    /// This is called with the whole text whenever it changes. The returned ranges
+    /// are byte ranges into `text`.
+    fn highlight(&mut self, text: &str) -> Vec<(Range<usize>, Attribute)>;
+}
+
+impl<F: FnMut(&str) -> Vec<(Range<usize>, Attribute)>> Highlighter for F {
+    fn highlight(&mut self, text: &str) -> Vec<(Range<usize>, Attribute)> {
+        (self)(text)
+    }
+}
+
+/// A multi-line text editor for source code.
+///
+/// The text is laid out in a monospace font, with a gutter of line numbers, and
+/// the bracket matching the one next to the caret is outlined. Syntax highlighting
+/// is provided by a [`Highlighter`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::text::Attribute;
+/// use druid::widget::CodeEditor;
+/// use druid::{Color, Widget};
+///
+/// fn editor() -> impl Widget<String> {
+///     // highlight comments
+///     CodeEditor::new().with_highlighter(|text: &str| {
+///         let mut spans = Vec::new();
+///         let mut start = 0;
+///         for line in text.split_inclusive('\n') {
+///             if let Some(comment) = line.find("//") {
+///                 let comment_color = Attribute::text_color(Color::grey(0.5));
+///                 spans.push((start + comment..start + line.len(), comment_color));
+///             }
+///             start += line.len();
+///         }
+///         spans
+///     })
+/// }
+/// ```
+pub struct CodeEditor {
+    textbox: WidgetPod<CodeText, TextBox<CodeText>>,
+    highlighter: Option<Box<dyn Highlighter>>,
+    font: KeyOrValue<FontDescriptor>,
+    line_numbers: bool,
+    gutter_width: f64,
+    /// The text, as edited by the text box.
+    buffer: CodeText,
+    /// The text as of the text box's last update.
+    shown: CodeText,
+}
+
+/// The text of a [`CodeEditor`], with its highlighting.
+#[derive(Clone, Data)]
+struct CodeText {
+    text: String,
+    attrs: Arc<AttributeSpans>,
+}
+
+impl CodeEditor {
+    /// Create a new `CodeEditor`.
+    pub fn new() -> Self {
+        let font = FontDescriptor::new(FontFamily::MONOSPACE);
+        let mut textbox = TextBox::multiline()
+            .with_line_wrapping(false)
+            .with_font(font.clone());
+        textbox.text_mut().borrow_mut().set_accepts_tabs(true);
+        CodeEditor {
+            textbox: WidgetPod::new(textbox),
+            highlighter: None,
+            font: font.into(),
+            line_numbers: true,
+            gutter_width: 0.0,
+            buffer: CodeText::default(),
+            shown: CodeText::default(),
+        }
+    }
+
+    /// Builder-style method to set the [`Highlighter`].
+    pub fn with_highlighter(mut self, highlighter: impl Highlighter + 'static) -> Self {
+        self.highlighter = Some(Box::new(highlighter));
+        self
+    }
+
+    /// Builder-style method to set the font.
+    ///
+    /// The default is the system's monospace font.
+    pub fn with_font(mut self, font: impl Into<KeyOrValue<FontDescriptor>>) -> Self {
+        self.set_font(font);
+        self
+    }
+
+    /// Builder-style method to set whether long lines are wrapped.
+    ///
+    /// By default lines are not wrapped, and the editor scrolls horizontally.
+    pub fn with_soft_wrap(mut self, soft_wrap: bool) -> Self {
+        self.set_soft_wrap(soft_wrap);
+        self
+    }
+
+    /// Builder-style method to set whether line numbers are shown. They are by default.
+    pub fn with_line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Set the font.
+    ///
+    /// If you change this property at runtime, you must ensure that [`update`]
+    /// is called in order to correctly recompute the text.
+    ///
+    /// [`update`]: Widget::update
+    pub fn set_font(&mut self, font: impl Into<KeyOrValue<FontDescriptor>>) {
+        self.font = font.into();
+        self.textbox.widget_mut().set_font(self.font.clone());
+    }
+
+    /// Set whether long lines are wrapped.
+    ///
+    /// If you change this property at runtime, you must request layout.
+    pub fn set_soft_wrap(&mut self, soft_wrap: bool) {
+        self.textbox.widget_mut().set_line_wrapping(soft_wrap);
+    }
+
+    /// Set whether line numbers are shown.
+    ///
+    /// If you change this property at runtime, you must request layout.
+    pub fn set_line_numbers(&mut self, line_numbers: bool) {
+        self.line_numbers = line_numbers;
+    }
+
+    fn highlight(&mut self) {
+        let mut attrs = AttributeSpans::new();
+        if let Some(highlighter) = self.highlighter.as_mut() {
+            for (range, attr) in highlighter.highlight(&self.buffer.text) {
+                attrs.add(range, attr);
+            }
+        }
+        self.buffer.attrs = Arc::new(attrs);
+    }
+
+    /// The offset of the text layout relative to this widget.
+    fn text_origin(&self, env: &Env) -> Vec2 {
+        let insets = env.get(theme::TEXTBOX_INSETS);
+        self.textbox.layout_rect().origin().to_vec2() + Vec2::new(insets.x0, insets.y0)
+            - self.textbox.widget().scroll_offset()
+    }
+
+    fn paint_line_numbers(&self, ctx: &mut PaintCtx, env: &Env) {
+        let gutter = Rect::new(0.0, 0.0, self.gutter_width, ctx.size().height);
+        ctx.fill(gutter, &env.get(theme::BACKGROUND_DARK));
+
+        let text = self.textbox.widget().text().borrow();
+        let layout = match text.layout.layout() {
+            Some(layout) => layout,
+            None => return,
+        };
+        let font = self.font.resolve(env);
+        let color = env.get(theme::PLACEHOLDER_COLOR);
+        let origin = self.text_origin(env);
+        let source = self.buffer.text.as_str();
+
+        ctx.with_save(|ctx| {
+            ctx.clip(gutter);
+            let mut line_number = 0;
+            for metric in (0..layout.line_count()).filter_map(|line| layout.line_metric(line)) {
+                // wrapped lines continue the previous line, and get no number
+                let start = metric.start_offset;
+                if start > 0 && !source[..start].ends_with('\n') {
+                    continue;
+                }
+                line_number += 1;
+                let y = origin.y + metric.y_offset;
+                if y + metric.height < 0.0 || y > gutter.height() {
+                    continue;
+                }
+                let number = ctx
+                    .text()
+                    .new_text_layout(line_number.to_string())
+                    .font(font.family.clone(), font.size)
+                    .text_color(color)
+                    .build()
+                    .unwrap();
+                let baseline = number.line_metric(0).map(|m| m.baseline).unwrap_or(0.0);
+                let x = self.gutter_width - GUTTER_PADDING - number.size().width;
+                ctx.draw_text(&number, (x, y + metric.baseline - baseline));
+            }
+        });
+    }
+
+    fn paint_matching_brackets(&self, ctx: &mut PaintCtx, env: &Env) {
+        let text = self.textbox.widget().text().borrow();
+        let selection = text.selection();
+        if !selection.is_caret() {
+            return;
+        }
+        let (open, close) = match matching_brackets(&self.buffer.text, selection.active) {
+            Some(pair) => pair,
+            None => return,
+        };
+        let origin = self.text_origin(env);
+        let color = env.get(theme::PRIMARY_LIGHT);
+        ctx.with_save(|ctx| {
+            ctx.clip(self.textbox.layout_rect());
+            for offset in [open, close] {
+                for rect in text.layout.rects_for_range(offset..offset + 1) {
+                    ctx.stroke(rect + origin, &color, 1.0);
+                }
+            }
+        });
+    }
+}
+
+/// The offsets of the bracket next to `caret` and the bracket matching it.
+///
+/// The bracket after the caret takes precedence. Brackets in strings and comments
+/// are not told apart from others.
+fn matching_brackets(text: &str, caret: usize) -> Option<(usize, usize)> {
+    let after = text[caret..].chars().next().map(|c| (caret, c));
+    let before = text[..caret]
+        .chars()
+        .next_back()
+        .map(|c| (caret - c.len_utf8(), c));
+    after.into_iter().chain(before).find_map(|(offset, c)| {
+        BRACKETS.iter().find_map(|&(open, close)| {
+            if c == open {
+                let mut depth = 0;
+                text[offset..].char_indices().find_map(|(i, c)| {
+                    if c == open {
+                        depth += 1;
+                    } else if c == close {
+                        depth -= 1;
+                    }
+                    (depth == 0).then_some((offset, offset + i))
+                })
+            } else if c == close {
+                let mut depth = 0;
+                text[..=offset].char_indices().rev().find_map(|(i, c)| {
+                    if c == close {
+                        depth += 1;
+                    } else if c == open {
+                        depth -= 1;
+                    }
+                    (depth == 0).then_some((i, offset))
+                })
+            } else {
+                None
+            }
+        })
+    })
+}
+
+impl Default for CodeEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Findable<String> for CodeEditor {
+    fn find_selection(&self) -> Option<Selection> {
+        self.textbox.widget().selection()
+    }
+
+    fn find_select(&mut self, ctx: &mut EventCtx, data: &String, selection: Selection) {
+        // the buffer catches up with `data` in `update`, but the selection is
+        // made in the new text.
+        if self.buffer.text != *data {
+            self.buffer.text = data.clone();
+        }
+        self.textbox
+            .widget_mut()
+            .set_selection(ctx, &self.buffer, selection);
+    }
+
+    fn set_find_highlights(&mut self, ranges: Vec<Range<usize>>) {
+        self.textbox.widget_mut().set_highlights(ranges);
+    }
+
+    fn find_positions(&self, ranges: &[Range<usize>]) -> Vec<f64> {
+        self.textbox.widget().find_positions(ranges)
+    }
+}
+
+impl Widget<String> for CodeEditor {
+    #[instrument(
+        name = "CodeEditor",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
+        self.textbox.event(ctx, event, &mut self.buffer, env);
+        if self.buffer.text != *data {
+            *data = self.buffer.text.clone();
+        }
+    }
+
+    #[instrument(
+        name = "CodeEditor",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &String, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.buffer.text = data.clone();
+            self.highlight();
+            self.shown = self.buffer.clone();
+        }
+        self.textbox.lifecycle(ctx, event, &self.buffer, env);
+    }
+
+    #[instrument(
+        name = "CodeEditor",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &String, data: &String, env: &Env) {
+        if self.buffer.text != *data {
+            self.buffer.text = data.clone();
+        }
+        if self.buffer.text != self.shown.text {
+            self.highlight();
+        }
+        self.textbox.update(ctx, &self.buffer, env);
+        if self.buffer.text.lines().count() != self.shown.text.lines().count() {
+            // the gutter may need to widen
+            ctx.request_layout();
+        }
+        self.shown = self.buffer.clone();
+    }
+
+    #[instrument(name = "CodeEditor", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &String,
+        env: &Env,
+    ) -> Size {
+        self.gutter_width = if self.line_numbers {
+            let font = self.font.resolve(env);
+            let digits = self.buffer.text.lines().count().to_string().len();
+            let digits = "0".repeat(digits.max(GUTTER_MIN_DIGITS));
+            let layout = ctx
+                .text()
+                .new_text_layout(digits)
+                .font(font.family, font.size)
+                .build()
+                .unwrap();
+            layout.size().width + 2.0 * GUTTER_PADDING
+        } else {
+            0.0
+        };
+
+        let child_bc = bc.shrink((self.gutter_width, 0.0));
+        let child_size = self.textbox.layout(ctx, &child_bc, &self.buffer, env);
+        self.textbox
+            .set_origin(ctx, Point::new(self.gutter_width, 0.0));
+        ctx.set_baseline_offset(self.textbox.baseline_offset());
+
+        let size = Size::new(child_size.width + self.gutter_width, child_size.height);
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "CodeEditor", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &String, env: &Env) {
+        if !self.textbox.widget().text().can_read() {
+            tracing::warn!("Widget::paint called with outstanding IME lock, skipping");
+            return;
+        }
+        if self.line_numbers {
+            self.paint_line_numbers(ctx, env);
+        }
+        self.textbox.paint(ctx, &self.buffer, env);
+        if ctx.has_focus() {
+            self.paint_matching_brackets(ctx, env);
+        }
+    }
+
+    fn debug_state(&self, data: &String) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.clone(),
+            children: vec![self.textbox.widget().debug_state(&self.buffer)],
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for CodeText {
+    fn default() -> Self {
+        CodeText {
+            text: String::new(),
+            attrs: Arc::new(AttributeSpans::new()),
+        }
+    }
+}
+
+impl PietTextStorage for CodeText {
+    fn as_str(&self) -> &str {
+        self.text.as_str()
+    }
+}
+
+impl TextStorage for CodeText {
+    fn add_attributes(
+        &self,
+        mut builder: PietTextLayoutBuilder,
+        env: &Env,
+    ) -> PietTextLayoutBuilder {
+        for (range, attr) in self.attrs.to_piet_attrs(env) {
+            builder = builder.range_attribute(range, attr);
+        }
+        builder
+    }
+
+    fn env_update(&self, ctx: &EnvUpdateCtx) -> bool {
+        self.attrs.env_update(ctx)
+    }
+
+    fn links(&self) -> &[Link] {
+        &[]
+    }
+}
+
+impl EditableText for CodeText {
+    fn cursor(&self, position: usize) -> Option<StringCursor<'_>> {
+        self.text.cursor(position)
+    }
+
+    fn edit(&mut self, range: Range<usize>, new: impl Into<String>) {
+        self.text.edit(range, new);
+        // The spans no longer line up with the text; the editor highlights
+        // the new text in its next update.
+        self.attrs = Arc::new(AttributeSpans::new());
+    }
+
+    fn slice(&self, range: Range<usize>) -> Option<Cow<'_, str>> {
+        self.text.slice(range)
+    }
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    fn prev_word_offset(&self, offset: usize) -> Option<usize> {
+        self.text.prev_word_offset(offset)
+    }
+
+    fn next_word_offset(&self, offset: usize) -> Option<usize> {
+        self.text.next_word_offset(offset)
+    }
+
+    fn prev_grapheme_offset(&self, offset: usize) -> Option<usize> {
+        self.text.prev_grapheme_offset(offset)
+    }
+
+    fn next_grapheme_offset(&self, offset: usize) -> Option<usize> {
+        self.text.next_grapheme_offset(offset)
+    }
+
+    fn prev_codepoint_offset(&self, offset: usize) -> Option<usize> {
+        self.text.prev_codepoint_offset(offset)
+    }
+
+    fn next_codepoint_offset(&self, offset: usize) -> Option<usize> {
+        self.text.next_codepoint_offset(offset)
+    }
+
+    fn preceding_line_break(&self, offset: usize) -> usize {
+        self.text.preceding_line_break(offset)
+    }
+
+    fn next_line_break(&self, offset: usize) -> usize {
+        self.text.next_line_break(offset)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn from_str(s: &str) -> Self {
+        CodeText {
+            text: s.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brackets() {
+        let text = "f(a[0], {b})";
+        // after the caret
+        assert_eq!(matching_brackets(text, 1), Some((1, 11)));
+        assert_eq!(matching_brackets(text, 11), Some((1, 11)));
+        // before the caret
+        assert_eq!(matching_brackets(text, 12), Some((1, 11)));
+        assert_eq!(matching_brackets(text, 6), Some((3, 5)));
+        assert_eq!(matching_brackets(text, 9), Some((8, 10)));
+        assert_eq!(matching_brackets(text, 0), None);
+        assert_eq!(matching_brackets("(()", 0), None);
+    }
+}
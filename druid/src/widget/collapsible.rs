@@ -0,0 +1,478 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Containers that can collapse their content.
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::BezPath;
+use crate::widget::prelude::*;
+use crate::widget::{Label, LabelText};
+use crate::{theme, Affine, Lens, LensExt, Point, Rect, Selector, WidgetPod};
+
+/// The width reserved on the left of the header for the disclosure triangle.
+const HEADER_INDENT: f64 = 20.0;
+/// The size of the disclosure triangle.
+const TRIANGLE_SIZE: f64 = 8.0;
+/// The duration of the expand and collapse animation.
+const EXPAND_ANIMATION: Duration = Duration::from_millis(150);
+
+/// Sent to a [`Collapsible`] to expand or collapse it.
+const SET_EXPANDED: Selector<bool> = Selector::new("druid-builtin.collapsible-set-expanded");
+/// Sent by a [`Collapsible`] when the user expands or collapses it.
+const EXPANSION_CHANGED: Selector<bool> =
+    Selector::new("druid-builtin.collapsible-expansion-changed");
+
+/// Accessors for expansion state that is stored in the data.
+struct Binding<T> {
+    get: Box<dyn Fn(&T) -> bool>,
+    put: Box<dyn Fn(&mut T, bool)>,
+}
+
+/// A container with a header that can be clicked to show or hide its body.
+///
+/// By default the expansion state is kept by the widget itself; use
+/// [`bind_expanded`] to store it in the data instead.
+///
+/// [`bind_expanded`]: Collapsible::bind_expanded
+pub struct Collapsible<T> {
+    header: WidgetPod<T, Box<dyn Widget<T>>>,
+    body: WidgetPod<T, Box<dyn Widget<T>>>,
+    binding: Option<Binding<T>>,
+    expanded: bool,
+    /// How far the body is shown, from `0.0` to `1.0`, before easing.
+    openness: f64,
+    animated: bool,
+    header_height: f64,
+    header_hovered: bool,
+}
+
+/// A vertical group of [`Collapsible`] sections.
+///
+/// By default, expanding one section collapses the others.
+pub struct Accordion<T> {
+    sections: Vec<WidgetPod<T, Collapsible<T>>>,
+    exclusive: bool,
+}
+
+impl<T: Data> Collapsible<T> {
+    /// Create a new collapsed container with a text header.
+    pub fn new(header: impl Into<LabelText<T>>, body: impl Widget<T> + 'static) -> Self {
+        Collapsible::with_header(Label::new(header), body)
+    }
+
+    /// Create a new collapsed container with an arbitrary widget as the header.
+    ///
+    /// Clicks on the header toggle the container, unless they are handled by
+    /// the header widget.
+    pub fn with_header(header: impl Widget<T> + 'static, body: impl Widget<T> + 'static) -> Self {
+        Collapsible {
+            header: WidgetPod::new(Box::new(header)),
+            body: WidgetPod::new(Box::new(body)),
+            binding: None,
+            expanded: false,
+            openness: 0.0,
+            animated: true,
+            header_height: 0.0,
+            header_hovered: false,
+        }
+    }
+
+    /// Builder-style method to set whether the container is initially expanded.
+    ///
+    /// This is ignored if the expansion state is [bound] to the data.
+    ///
+    /// [bound]: Collapsible::bind_expanded
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self.openness = if expanded { 1.0 } else { 0.0 };
+        self
+    }
+
+    /// Builder-style method to store the expansion state in the data, using the provided lens.
+    pub fn bind_expanded(mut self, lens: impl Lens<T, bool> + 'static) -> Self {
+        let lens = Rc::new(lens);
+        let put_lens = lens.clone();
+        self.binding = Some(Binding {
+            get: Box::new(move |data| lens.get(data)),
+            put: Box::new(move |data, expanded| put_lens.put(data, expanded)),
+        });
+        self
+    }
+
+    /// Builder-style method to set whether expanding and collapsing is animated.
+    ///
+    /// The default is `true`.
+    pub fn animated(mut self, animated: bool) -> Self {
+        self.animated = animated;
+        self
+    }
+
+    /// Returns `true` if the container is expanded, or is being expanded.
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// The fraction of the body that is currently shown.
+    fn eased_openness(&self) -> f64 {
+        1.0 - (1.0 - self.openness).powi(3)
+    }
+
+    /// Set the expansion state, returning `true` if it changed.
+    fn set_expanded(&mut self, expanded: bool, data: &mut T) -> bool {
+        if let Some(binding) = &self.binding {
+            (binding.put)(data, expanded);
+        }
+        self.expanded_changed(expanded)
+    }
+
+    /// Start moving towards a new expansion state, returning `true` if it changed.
+    fn expanded_changed(&mut self, expanded: bool) -> bool {
+        if expanded == self.expanded {
+            return false;
+        }
+        trace!("Collapsible expanded: {}", expanded);
+        self.expanded = expanded;
+        if !self.animated {
+            self.openness = if expanded { 1.0 } else { 0.0 };
+        }
+        true
+    }
+
+    fn is_animating(&self) -> bool {
+        let target = if self.expanded { 1.0 } else { 0.0 };
+        self.openness != target
+    }
+
+    fn header_rect(&self, size: Size) -> Rect {
+        Rect::new(0.0, 0.0, size.width, self.header_height)
+    }
+
+    fn paint_triangle(&self, ctx: &mut PaintCtx, env: &Env) {
+        let mut path = BezPath::new();
+        path.move_to((-TRIANGLE_SIZE / 2.0, -TRIANGLE_SIZE / 2.0));
+        path.line_to((TRIANGLE_SIZE / 2.0, 0.0));
+        path.line_to((-TRIANGLE_SIZE / 2.0, TRIANGLE_SIZE / 2.0));
+        path.close_path();
+
+        // Point right when collapsed, and down when expanded.
+        let center = Point::new(HEADER_INDENT / 2.0, self.header_height / 2.0);
+        let transform = Affine::translate(center.to_vec2())
+            * Affine::rotate(self.eased_openness() * std::f64::consts::FRAC_PI_2);
+        ctx.fill(transform * path, &env.get(theme::TEXT_COLOR));
+    }
+}
+
+impl<T: Data> Widget<T> for Collapsible<T> {
+    #[instrument(
+        name = "Collapsible",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(SET_EXPANDED) => {
+                ctx.set_handled();
+                if self.set_expanded(*cmd.get_unchecked(SET_EXPANDED), data) {
+                    ctx.request_anim_frame();
+                    ctx.request_layout();
+                }
+                return;
+            }
+            Event::AnimFrame(interval) if self.is_animating() => {
+                let step = if env.get(theme::REDUCED_MOTION) {
+                    1.0
+                } else {
+                    *interval as f64 / EXPAND_ANIMATION.as_nanos() as f64
+                };
+                self.openness = if self.expanded {
+                    (self.openness + step).min(1.0)
+                } else {
+                    (self.openness - step).max(0.0)
+                };
+                if self.is_animating() {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_layout();
+            }
+            _ => (),
+        }
+
+        self.header.event(ctx, event, data, env);
+        if self.openness > 0.0 || event.should_propagate_to_hidden() {
+            self.body.event(ctx, event, data, env);
+        }
+        if ctx.is_handled() {
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse)
+                if mouse.button.is_left() && self.header_rect(ctx.size()).contains(mouse.pos) =>
+            {
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse) if ctx.is_active() && mouse.button.is_left() => {
+                ctx.set_active(false);
+                if ctx.is_hot() && self.header_rect(ctx.size()).contains(mouse.pos) {
+                    let expanded = !self.expanded;
+                    self.set_expanded(expanded, data);
+                    ctx.submit_notification_without_warning(EXPANSION_CHANGED.with(expanded));
+                    ctx.request_anim_frame();
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+            }
+            Event::MouseMove(mouse) => {
+                let hovered = ctx.is_hot() && self.header_rect(ctx.size()).contains(mouse.pos);
+                if hovered != self.header_hovered {
+                    self.header_hovered = hovered;
+                    ctx.request_paint();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "Collapsible",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                if let Some(binding) = &self.binding {
+                    self.expanded = (binding.get)(data);
+                    self.openness = if self.expanded { 1.0 } else { 0.0 };
+                }
+            }
+            LifeCycle::HotChanged(false) if self.header_hovered => {
+                self.header_hovered = false;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+        self.header.lifecycle(ctx, event, data, env);
+        self.body.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Collapsible",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if let Some(binding) = &self.binding {
+            let expanded = (binding.get)(data);
+            if self.expanded_changed(expanded) {
+                ctx.request_anim_frame();
+                ctx.request_layout();
+            }
+        }
+        self.header.update(ctx, data, env);
+        self.body.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Collapsible", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Collapsible");
+
+        let header_bc = bc.shrink((HEADER_INDENT, 0.0)).loosen();
+        let header_size = self.header.layout(ctx, &header_bc, data, env);
+        self.header_height = header_size.height.max(env.get(theme::BASIC_WIDGET_HEIGHT));
+        let header_y = (self.header_height - header_size.height) / 2.0;
+        self.header
+            .set_origin(ctx, Point::new(HEADER_INDENT, header_y));
+
+        let body_bc = BoxConstraints::new(
+            Size::new(bc.min().width, 0.0),
+            Size::new(
+                bc.max().width,
+                (bc.max().height - self.header_height).max(0.0),
+            ),
+        );
+        let body_size = self.body.layout(ctx, &body_bc, data, env);
+        self.body
+            .set_origin(ctx, Point::new(0.0, self.header_height));
+
+        let width = (header_size.width + HEADER_INDENT).max(body_size.width);
+        let height = self.header_height + body_size.height * self.eased_openness();
+        let size = bc.constrain((width, height));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "Collapsible", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        if self.header_hovered {
+            ctx.fill(self.header_rect(size), &env.get(theme::BACKGROUND_LIGHT));
+        }
+        self.paint_triangle(ctx, env);
+        self.header.paint(ctx, data, env);
+
+        if self.openness > 0.0 {
+            let body_rect = Rect::new(0.0, self.header_height, size.width, size.height);
+            ctx.with_save(|ctx| {
+                ctx.clip(body_rect);
+                self.body.paint(ctx, data, env);
+            });
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: if self.expanded {
+                "expanded"
+            } else {
+                "collapsed"
+            }
+            .to_string(),
+            children: vec![
+                self.header.widget().debug_state(data),
+                self.body.widget().debug_state(data),
+            ],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data> Accordion<T> {
+    /// Create a new, empty accordion.
+    pub fn new() -> Self {
+        Accordion {
+            sections: Vec::new(),
+            exclusive: true,
+        }
+    }
+
+    /// Builder-style method to add a section with a text header.
+    pub fn with_section(
+        self,
+        header: impl Into<LabelText<T>>,
+        body: impl Widget<T> + 'static,
+    ) -> Self {
+        self.with_collapsible(Collapsible::new(header, body))
+    }
+
+    /// Builder-style method to add a section.
+    pub fn with_collapsible(mut self, section: Collapsible<T>) -> Self {
+        self.sections.push(WidgetPod::new(section));
+        self
+    }
+
+    /// Builder-style method to set whether expanding a section collapses the others.
+    ///
+    /// The default is `true`. This only applies to sections expanded by the user; sections
+    /// whose state is [bound] to the data are not collapsed when the data changes.
+    ///
+    /// [bound]: Collapsible::bind_expanded
+    pub fn exclusive(mut self, exclusive: bool) -> Self {
+        self.exclusive = exclusive;
+        self
+    }
+}
+
+impl<T: Data> Default for Accordion<T> {
+    fn default() -> Self {
+        Accordion::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Accordion<T> {
+    #[instrument(name = "Accordion", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Notification(notification) = event {
+            if notification.is(EXPANSION_CHANGED) {
+                ctx.set_handled();
+                let expanded = *notification.get(EXPANSION_CHANGED).unwrap();
+                if expanded && self.exclusive {
+                    for section in &self.sections {
+                        if section.id() != notification.source() && section.widget().is_expanded() {
+                            ctx.submit_command(SET_EXPANDED.with(false).to(section.id()));
+                        }
+                    }
+                }
+            }
+            return;
+        }
+        for section in &mut self.sections {
+            section.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Accordion", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for section in &mut self.sections {
+            section.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Accordion",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for section in &mut self.sections {
+            section.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Accordion", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Accordion");
+        let section_bc = BoxConstraints::new(
+            Size::new(bc.min().width, 0.0),
+            Size::new(bc.max().width, f64::INFINITY),
+        );
+        let mut width: f64 = 0.0;
+        let mut y = 0.0;
+        for section in &mut self.sections {
+            let size = section.layout(ctx, &section_bc, data, env);
+            section.set_origin(ctx, Point::new(0.0, y));
+            width = width.max(size.width);
+            y += size.height;
+        }
+        let size = bc.constrain((width, y));
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "Accordion", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for section in &mut self.sections {
+            section.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: self
+                .sections
+                .iter()
+                .map(|section| section.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,624 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container that positions its children by solving linear constraints.
+
+use std::ops::{Add, Mul, Sub};
+
+use cassowary::{strength, Constraint, Expression, RelationalOperator, Solver, Term, Variable};
+use tracing::{instrument, trace, warn};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, Point, Rect, WidgetPod};
+
+// The preferences used when the constraints leave room, from strongest to weakest.
+// They are all weaker than any `ConstraintPriority`.
+
+/// How strongly the layout prefers to fill the available space.
+const FILL_STRENGTH: f64 = strength::WEAK * 0.75;
+/// How strongly children prefer the size they would have without constraints.
+const NATURAL_SIZE_STRENGTH: f64 = strength::WEAK * 0.5;
+/// How strongly children prefer to be at the top left corner.
+const ORIGIN_STRENGTH: f64 = strength::WEAK * 0.25;
+
+/// A child of a [`ConstraintLayout`], or the layout itself, whose anchors can be
+/// constrained.
+///
+/// Items of children are returned by [`ConstraintLayout::add_child`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayoutItem(Option<usize>);
+
+/// A position or length of a [`LayoutItem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LayoutAnchor {
+    /// The left edge.
+    Left,
+    /// The right edge.
+    Right,
+    /// The top edge.
+    Top,
+    /// The bottom edge.
+    Bottom,
+    /// The width.
+    Width,
+    /// The height.
+    Height,
+    /// The horizontal center.
+    CenterX,
+    /// The vertical center.
+    CenterY,
+}
+
+/// A linear combination of anchors, plus a constant.
+///
+/// These are created from the anchors of a [`LayoutItem`], and can be scaled,
+/// added, and offset:
+///
+/// ```
+/// # use druid::widget::LayoutItem;
+/// let half_width_plus_margin = LayoutItem::PARENT.width() * 0.5 + 8.0;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchorExpr {
+    terms: Vec<(LayoutItem, LayoutAnchor, f64)>,
+    constant: f64,
+}
+
+/// How important it is to satisfy a [`LayoutConstraint`].
+///
+/// Required constraints are always satisfied; if that is impossible, the constraint
+/// is ignored with a warning. The other constraints are satisfied as far as possible,
+/// with stronger ones taking precedence over weaker ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConstraintPriority {
+    /// The constraint must be satisfied.
+    #[default]
+    Required,
+    /// The constraint should be satisfied.
+    Strong,
+    /// The constraint should be satisfied, unless it conflicts with a strong one.
+    Medium,
+    /// The constraint should be satisfied, unless it conflicts with any other.
+    Weak,
+}
+
+/// A linear equation or inequality between anchors, used by a [`ConstraintLayout`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutConstraint {
+    /// The left hand side minus the right hand side.
+    expr: AnchorExpr,
+    relation: Relation,
+    priority: ConstraintPriority,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Relation {
+    Equal,
+    AtLeast,
+    AtMost,
+}
+
+/// A container that positions and sizes its children by solving a set of linear
+/// constraints.
+///
+/// Each child, and the layout itself, has anchors for its edges, center and size.
+/// Constraints relate these anchors to each other, as equations or inequalities
+/// with a [`ConstraintPriority`], for instance to put one child to the right of another, keep
+/// an aspect ratio, or make a sidebar a quarter of the width but at least 200 pixels
+/// wide. The constraints are solved on every layout pass, using the [Cassowary]
+/// algorithm, which allows adaptive layouts that would otherwise need deeply nested
+/// [`Flex`] and [`Split`] widgets.
+///
+/// Children that are not fully constrained keep the size they would choose for
+/// themselves, and are placed at the top left corner where possible. The layout fills the space
+/// available to it, unless constraints say otherwise; if that space is unbounded, it
+/// is as small as the constraints allow.
+///
+/// This widget requires the `constraint-layout` feature.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{ConstraintLayout, Label, LayoutItem, ConstraintPriority};
+///
+/// let mut layout = ConstraintLayout::<()>::new();
+/// let parent = LayoutItem::PARENT;
+/// let sidebar = layout.add_child(Label::new("Sidebar"));
+/// let content = layout.add_child(Label::new("Content"));
+///
+/// layout.add_constraint(sidebar.left().equal_to(parent.left()));
+/// layout.add_constraint(sidebar.top().equal_to(parent.top()));
+/// layout.add_constraint(sidebar.bottom().equal_to(parent.bottom()));
+/// layout.add_constraint(sidebar.width().equal_to(parent.width() * 0.25).priority(ConstraintPriority::Strong));
+/// layout.add_constraint(sidebar.width().at_least(200.0));
+///
+/// layout.add_constraint(content.left().equal_to(sidebar.right() + 8.0));
+/// layout.add_constraint(content.right().equal_to(parent.right()));
+/// layout.add_constraint(content.top().equal_to(parent.top()));
+/// layout.add_constraint(content.bottom().equal_to(parent.bottom()));
+/// ```
+///
+/// [Cassowary]: https://constraints.cs.washington.edu/cassowary/
+/// [`Flex`]: super::Flex
+/// [`Split`]: super::Split
+pub struct ConstraintLayout<T> {
+    children: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+    constraints: Vec<LayoutConstraint>,
+}
+
+/// The variables of a [`LayoutItem`] in the solver.
+#[derive(Clone, Copy)]
+struct ItemVariables {
+    left: Variable,
+    top: Variable,
+    width: Variable,
+    height: Variable,
+}
+
+impl LayoutItem {
+    /// The [`ConstraintLayout`] itself.
+    ///
+    /// Its left and top edges are always at zero.
+    pub const PARENT: LayoutItem = LayoutItem(None);
+
+    /// An expression for the given anchor of this item.
+    pub fn anchor(self, anchor: LayoutAnchor) -> AnchorExpr {
+        AnchorExpr {
+            terms: vec![(self, anchor, 1.0)],
+            constant: 0.0,
+        }
+    }
+
+    /// The left edge of this item.
+    pub fn left(self) -> AnchorExpr {
+        self.anchor(LayoutAnchor::Left)
+    }
+
+    /// The right edge of this item.
+    pub fn right(self) -> AnchorExpr {
+        self.anchor(LayoutAnchor::Right)
+    }
+
+    /// The top edge of this item.
+    pub fn top(self) -> AnchorExpr {
+        self.anchor(LayoutAnchor::Top)
+    }
+
+    /// The bottom edge of this item.
+    pub fn bottom(self) -> AnchorExpr {
+        self.anchor(LayoutAnchor::Bottom)
+    }
+
+    /// The width of this item.
+    pub fn width(self) -> AnchorExpr {
+        self.anchor(LayoutAnchor::Width)
+    }
+
+    /// The height of this item.
+    pub fn height(self) -> AnchorExpr {
+        self.anchor(LayoutAnchor::Height)
+    }
+
+    /// The horizontal center of this item.
+    pub fn center_x(self) -> AnchorExpr {
+        self.anchor(LayoutAnchor::CenterX)
+    }
+
+    /// The vertical center of this item.
+    pub fn center_y(self) -> AnchorExpr {
+        self.anchor(LayoutAnchor::CenterY)
+    }
+}
+
+impl AnchorExpr {
+    /// A constraint that this expression is equal to `other`.
+    pub fn equal_to(self, other: impl Into<AnchorExpr>) -> LayoutConstraint {
+        LayoutConstraint::new(self - other.into(), Relation::Equal)
+    }
+
+    /// A constraint that this expression is at least `other`.
+    pub fn at_least(self, other: impl Into<AnchorExpr>) -> LayoutConstraint {
+        LayoutConstraint::new(self - other.into(), Relation::AtLeast)
+    }
+
+    /// A constraint that this expression is at most `other`.
+    pub fn at_most(self, other: impl Into<AnchorExpr>) -> LayoutConstraint {
+        LayoutConstraint::new(self - other.into(), Relation::AtMost)
+    }
+}
+
+impl From<f64> for AnchorExpr {
+    fn from(constant: f64) -> AnchorExpr {
+        AnchorExpr {
+            terms: Vec::new(),
+            constant,
+        }
+    }
+}
+
+impl Add for AnchorExpr {
+    type Output = AnchorExpr;
+
+    fn add(mut self, other: AnchorExpr) -> AnchorExpr {
+        self.terms.extend(other.terms);
+        self.constant += other.constant;
+        self
+    }
+}
+
+impl Sub for AnchorExpr {
+    type Output = AnchorExpr;
+
+    fn sub(self, other: AnchorExpr) -> AnchorExpr {
+        self + other * -1.0
+    }
+}
+
+impl Add<f64> for AnchorExpr {
+    type Output = AnchorExpr;
+
+    fn add(mut self, constant: f64) -> AnchorExpr {
+        self.constant += constant;
+        self
+    }
+}
+
+impl Sub<f64> for AnchorExpr {
+    type Output = AnchorExpr;
+
+    fn sub(self, constant: f64) -> AnchorExpr {
+        self + -constant
+    }
+}
+
+impl Mul<f64> for AnchorExpr {
+    type Output = AnchorExpr;
+
+    fn mul(mut self, factor: f64) -> AnchorExpr {
+        for (_, _, coefficient) in &mut self.terms {
+            *coefficient *= factor;
+        }
+        self.constant *= factor;
+        self
+    }
+}
+
+impl LayoutConstraint {
+    fn new(expr: AnchorExpr, relation: Relation) -> Self {
+        LayoutConstraint {
+            expr,
+            relation,
+            priority: ConstraintPriority::Required,
+        }
+    }
+
+    /// Builder-style method to set the priority of this constraint.
+    ///
+    /// Constraints are [`ConstraintPriority::Required`] by default.
+    pub fn priority(mut self, priority: ConstraintPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl ConstraintPriority {
+    fn strength(self) -> f64 {
+        match self {
+            ConstraintPriority::Required => strength::REQUIRED,
+            ConstraintPriority::Strong => strength::STRONG,
+            ConstraintPriority::Medium => strength::MEDIUM,
+            ConstraintPriority::Weak => strength::WEAK,
+        }
+    }
+}
+
+impl ItemVariables {
+    fn new() -> Self {
+        ItemVariables {
+            left: Variable::new(),
+            top: Variable::new(),
+            width: Variable::new(),
+            height: Variable::new(),
+        }
+    }
+
+    /// The terms of `anchor`, scaled by `factor`.
+    fn terms(&self, anchor: LayoutAnchor, factor: f64) -> Vec<Term> {
+        let term = |variable, coefficient: f64| Term {
+            variable,
+            coefficient: coefficient * factor,
+        };
+        match anchor {
+            LayoutAnchor::Left => vec![term(self.left, 1.0)],
+            LayoutAnchor::Top => vec![term(self.top, 1.0)],
+            LayoutAnchor::Width => vec![term(self.width, 1.0)],
+            LayoutAnchor::Height => vec![term(self.height, 1.0)],
+            LayoutAnchor::Right => vec![term(self.left, 1.0), term(self.width, 1.0)],
+            LayoutAnchor::Bottom => vec![term(self.top, 1.0), term(self.height, 1.0)],
+            LayoutAnchor::CenterX => vec![term(self.left, 1.0), term(self.width, 0.5)],
+            LayoutAnchor::CenterY => vec![term(self.top, 1.0), term(self.height, 0.5)],
+        }
+    }
+}
+
+impl<T: Data> ConstraintLayout<T> {
+    /// Create a new layout without children.
+    pub fn new() -> Self {
+        ConstraintLayout {
+            children: Vec::new(),
+            constraints: Vec::new(),
+        }
+    }
+
+    /// Add a child, and return the item used to constrain it.
+    ///
+    /// This should be followed by a call to [`children_changed`] if the layout is
+    /// already in use.
+    ///
+    /// [`children_changed`]: crate::EventCtx::children_changed
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static) -> LayoutItem {
+        self.children.push(WidgetPod::new(Box::new(child)));
+        LayoutItem(Some(self.children.len() - 1))
+    }
+
+    /// Add a constraint.
+    ///
+    /// This should be followed by a call to [`request_layout`] if the layout is
+    /// already in use.
+    ///
+    /// [`request_layout`]: crate::EventCtx::request_layout
+    pub fn add_constraint(&mut self, constraint: LayoutConstraint) {
+        self.constraints.push(constraint);
+    }
+
+    /// Builder-style variant of [`add_constraint`].
+    ///
+    /// [`add_constraint`]: ConstraintLayout::add_constraint
+    pub fn with_constraint(mut self, constraint: LayoutConstraint) -> Self {
+        self.add_constraint(constraint);
+        self
+    }
+
+    /// Remove all constraints.
+    pub fn clear_constraints(&mut self) {
+        self.constraints.clear();
+    }
+}
+
+impl<T: Data> Default for ConstraintLayout<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Solves `constraints` for children of the given natural sizes, and returns the size
+/// of the layout and the frame of each child.
+fn solve(
+    constraints: &[LayoutConstraint],
+    bc: &BoxConstraints,
+    natural_sizes: &[Size],
+) -> (Size, Vec<Rect>) {
+    let parent = ItemVariables::new();
+    let children: Vec<_> = natural_sizes.iter().map(|_| ItemVariables::new()).collect();
+    let variables = |item: LayoutItem| match item.0 {
+        None => Some(&parent),
+        Some(index) => children.get(index),
+    };
+
+    let mut solver = Solver::new();
+    let mut add = |expr: Expression, op, strength| {
+        if let Err(err) = solver.add_constraint(Constraint::new(expr, op, strength)) {
+            warn!("ignoring layout constraint: {:?}", err);
+        }
+    };
+    let var = |variable: Variable, constant: f64| {
+        Expression::new(
+            vec![Term {
+                variable,
+                coefficient: 1.0,
+            }],
+            -constant,
+        )
+    };
+    use RelationalOperator::{Equal, GreaterOrEqual, LessOrEqual};
+
+    let (min, max) = (bc.min(), bc.max());
+    add(var(parent.left, 0.0), Equal, strength::REQUIRED);
+    add(var(parent.top, 0.0), Equal, strength::REQUIRED);
+    for (variable, min, max) in [
+        (parent.width, min.width, max.width),
+        (parent.height, min.height, max.height),
+    ] {
+        add(var(variable, min), GreaterOrEqual, strength::REQUIRED);
+        if max.is_finite() {
+            add(var(variable, max), LessOrEqual, strength::REQUIRED);
+        }
+        let preferred = if max.is_finite() { max } else { min };
+        add(var(variable, preferred), Equal, FILL_STRENGTH);
+    }
+    for (child, size) in children.iter().zip(natural_sizes) {
+        add(var(child.width, 0.0), GreaterOrEqual, strength::REQUIRED);
+        add(var(child.height, 0.0), GreaterOrEqual, strength::REQUIRED);
+        add(var(child.width, size.width), Equal, NATURAL_SIZE_STRENGTH);
+        add(var(child.height, size.height), Equal, NATURAL_SIZE_STRENGTH);
+        add(var(child.left, 0.0), Equal, ORIGIN_STRENGTH);
+        add(var(child.top, 0.0), Equal, ORIGIN_STRENGTH);
+    }
+
+    'constraints: for constraint in constraints {
+        let mut terms = Vec::new();
+        for &(item, anchor, factor) in &constraint.expr.terms {
+            match variables(item) {
+                Some(variables) => terms.extend(variables.terms(anchor, factor)),
+                None => {
+                    warn!(
+                        "ignoring layout constraint for a missing child: {:?}",
+                        constraint
+                    );
+                    continue 'constraints;
+                }
+            }
+        }
+        let op = match constraint.relation {
+            Relation::Equal => Equal,
+            Relation::AtLeast => GreaterOrEqual,
+            Relation::AtMost => LessOrEqual,
+        };
+        let expr = Expression::new(terms, constraint.expr.constant);
+        add(expr, op, constraint.priority.strength());
+    }
+
+    let size = bc.constrain(Size::new(
+        solver.get_value(parent.width),
+        solver.get_value(parent.height),
+    ));
+    let frames = children
+        .iter()
+        .map(|child| {
+            let origin = Point::new(solver.get_value(child.left), solver.get_value(child.top));
+            let size = Size::new(
+                solver.get_value(child.width).max(0.0),
+                solver.get_value(child.height).max(0.0),
+            );
+            Rect::from_origin_size(origin, size)
+        })
+        .collect();
+    (size, frames)
+}
+
+impl<T: Data> Widget<T> for ConstraintLayout<T> {
+    #[instrument(
+        name = "ConstraintLayout",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ConstraintLayout",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ConstraintLayout",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ConstraintLayout",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("ConstraintLayout");
+
+        let loose = bc.loosen();
+        let natural_sizes: Vec<_> = self
+            .children
+            .iter_mut()
+            .map(|child| child.layout(ctx, &loose, data, env))
+            .collect();
+        let (size, frames) = solve(&self.constraints, bc, &natural_sizes);
+
+        let mut child_paint_rect = Rect::ZERO;
+        for (child, frame) in self.children.iter_mut().zip(frames) {
+            child.layout(ctx, &BoxConstraints::tight(frame.size()), data, env);
+            child.set_origin(ctx, frame.origin());
+            child_paint_rect = child_paint_rect.union(child.paint_rect());
+        }
+
+        let insets = child_paint_rect - size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "ConstraintLayout", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children_state = self
+            .children
+            .iter()
+            .map(|child| child.widget().debug_state(data))
+            .collect();
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: children_state,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sidebar_layout() {
+        let parent = LayoutItem::PARENT;
+        let sidebar = LayoutItem(Some(0));
+        let content = LayoutItem(Some(1));
+        let constraints = [
+            sidebar.left().equal_to(parent.left()),
+            sidebar.top().equal_to(parent.top()),
+            sidebar.bottom().equal_to(parent.bottom()),
+            sidebar
+                .width()
+                .equal_to(parent.width() * 0.25)
+                .priority(ConstraintPriority::Strong),
+            sidebar.width().at_least(200.0),
+            content.left().equal_to(sidebar.right() + 10.0),
+            content.right().equal_to(parent.right()),
+        ];
+        let natural = [Size::new(50.0, 20.0), Size::new(100.0, 30.0)];
+
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(1000.0, 500.0));
+        let (size, frames) = solve(&constraints, &bc, &natural);
+        assert_eq!(size, Size::new(1000.0, 500.0));
+        assert_eq!(frames[0], Rect::new(0.0, 0.0, 250.0, 500.0));
+        assert_eq!(frames[1], Rect::new(260.0, 0.0, 1000.0, 30.0));
+
+        // The required minimum width wins over the strong proportion.
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(400.0, 500.0));
+        let (_, frames) = solve(&constraints, &bc, &natural);
+        assert_eq!(frames[0].width(), 200.0);
+        assert_eq!(frames[1].x0, 210.0);
+
+        // The unconstrained sidebar keeps its natural width, and stays at the left.
+        let (_, frames) = solve(&constraints[..2], &bc, &natural);
+        assert_eq!(frames[0], Rect::new(0.0, 0.0, 50.0, 20.0));
+    }
+}
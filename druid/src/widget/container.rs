@@ -311,4 +311,24 @@ impl<T: Data> Widget<T> for Container<T> {
         let border_width_on_both_sides = container_width * 2.;
         child_size + border_width_on_both_sides
     }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let container_width = match &self.border {
+            Some(border) => border.width.resolve(env),
+            None => 0.0,
+        };
+        let child_bc = bc.shrink((2.0 * container_width, 2.0 * container_width));
+        let child_size = self
+            .child
+            .widget_mut()
+            .compute_min_intrinsic(axis, ctx, &child_bc, data, env);
+        child_size + container_width * 2.
+    }
 }
@@ -0,0 +1,284 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that shows a context menu for its child.
+
+use std::any::Any;
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::{Menu, MouseButton, MouseEvent, Point, TimerToken};
+
+/// The distance the cursor may move during a long press before it is
+/// treated as a drag instead.
+const LONG_PRESS_SLOP: f64 = 4.0;
+
+/// Information about a context menu request, passed to the closure
+/// that builds the menu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContextMenuTarget {
+    /// The id of the widget the menu was requested for.
+    ///
+    /// Menu items can send commands to this id to dispatch them back
+    /// to the widget.
+    pub widget_id: WidgetId,
+    /// The location of the click, in the widget's coordinate space.
+    pub pos: Point,
+    /// The location of the click, in the window's coordinate space.
+    pub window_pos: Point,
+}
+
+/// A [`Controller`] that shows a platform context menu when its child is
+/// right-clicked, or optionally long-pressed.
+///
+/// The menu is built by a closure each time it is shown, so it can
+/// reflect the current data. Because menus operate on the application's
+/// root data, `R` must be the type passed to [`AppLauncher::launch`].
+/// To act on the data of the widget the menu is attached to instead,
+/// give the menu items a [`command`] targeting
+/// [`ContextMenuTarget::widget_id`] and handle it in the widget tree.
+///
+/// This is more conveniently available via the [`context_menu`] method
+/// on [`WidgetExt`].
+///
+/// The menu is shown with [`EventCtx::show_context_menu`], so it is only
+/// available on platforms that support native context menus (currently
+/// Windows and macOS). Elsewhere the request is logged and ignored; there
+/// is no in-window fallback, because the menu items act on the root data
+/// and need the platform's menu handling to dispatch them.
+///
+/// [`AppLauncher::launch`]: crate::AppLauncher::launch
+/// [`command`]: crate::MenuItem::command
+/// [`context_menu`]: crate::WidgetExt::context_menu
+/// [`WidgetExt`]: crate::WidgetExt
+pub struct ContextMenu<T, R> {
+    make_menu: Box<dyn Fn(&T, &ContextMenuTarget) -> Menu<R>>,
+    long_press: Option<Duration>,
+    pending: Option<(TimerToken, MouseEvent)>,
+    /// Set when a long press showed the menu, so the release that ends
+    /// the press is not also seen as a click by the child.
+    swallow_up: bool,
+}
+
+impl<T: Data, R: Any> ContextMenu<T, R> {
+    /// Create a new context menu controller.
+    ///
+    /// The closure is called with the current data and information about
+    /// where the menu was requested, and returns the menu to show.
+    pub fn new(make_menu: impl Fn(&T, &ContextMenuTarget) -> Menu<R> + 'static) -> Self {
+        ContextMenu {
+            make_menu: Box::new(make_menu),
+            long_press: None,
+            pending: None,
+            swallow_up: false,
+        }
+    }
+
+    /// Builder-style method to also show the menu when the child is held
+    /// down with the primary button for `delay`.
+    pub fn long_press(mut self, delay: Duration) -> Self {
+        self.long_press = Some(delay);
+        self
+    }
+
+    fn show(&self, ctx: &mut EventCtx, mouse: &MouseEvent, data: &T) {
+        let target = ContextMenuTarget {
+            widget_id: ctx.widget_id(),
+            pos: mouse.pos,
+            window_pos: mouse.window_pos,
+        };
+        trace!("Showing context menu for {:?}", target.widget_id);
+        let menu = (self.make_menu)(data, &target);
+        ctx.show_context_menu(menu, mouse.window_pos);
+        ctx.set_handled();
+    }
+}
+
+impl<T: Data, R: Any, W: Widget<T>> Controller<T, W> for ContextMenu<T, R> {
+    #[instrument(
+        name = "ContextMenu",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if !ctx.is_disabled() => {
+                self.swallow_up = false;
+                if mouse.button == MouseButton::Right {
+                    self.pending = None;
+                    self.show(ctx, mouse, data);
+                } else if let (MouseButton::Left, Some(delay)) = (mouse.button, self.long_press) {
+                    self.pending = Some((ctx.request_timer(delay), mouse.clone()));
+                }
+            }
+            Event::MouseMove(mouse) => {
+                if let Some((_, down)) = &self.pending {
+                    if (mouse.pos - down.pos).hypot() > LONG_PRESS_SLOP {
+                        self.pending = None;
+                    }
+                }
+            }
+            Event::MouseUp(_) => {
+                self.pending = None;
+                if self.swallow_up {
+                    self.swallow_up = false;
+                    // The child may have gone active on the press.
+                    ctx.set_active(false);
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+            }
+            Event::Timer(token) => {
+                if matches!(&self.pending, Some((pending, _)) if pending == token) {
+                    let (_, mouse) = self.pending.take().unwrap();
+                    self.show(ctx, &mouse, data);
+                    self.swallow_up = true;
+                }
+            }
+            _ => (),
+        }
+
+        if !ctx.is_handled() {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "ContextMenu",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::HotChanged(false) | LifeCycle::DisabledChanged(true) = event {
+            self.pending = None;
+            self.swallow_up = false;
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{Record, Recording, TestWidgetExt};
+    use crate::widget::{SizedBox, WidgetExt};
+    use crate::MouseButtons;
+
+    const DELAY: Duration = Duration::from_millis(500);
+
+    fn mouse(button: MouseButton, pos: impl Into<Point>) -> MouseEvent {
+        let pos = pos.into();
+        MouseEvent {
+            pos,
+            window_pos: pos,
+            buttons: MouseButtons::default().with(button),
+            mods: Default::default(),
+            count: 1,
+            focus: false,
+            button,
+            wheel_delta: Default::default(),
+        }
+    }
+
+    fn menu_widget(shown: &Rc<Cell<u32>>, recording: &Recording) -> impl Widget<()> {
+        let shown = shown.clone();
+        SizedBox::empty().expand().record(recording).controller(
+            ContextMenu::new(move |_: &(), _| {
+                shown.set(shown.get() + 1);
+                Menu::<()>::empty()
+            })
+            .long_press(DELAY),
+        )
+    }
+
+    fn received_mouse_up(recording: &Recording) -> bool {
+        recording
+            .drain()
+            .any(|rec| matches!(rec, Record::E(Event::MouseUp(_))))
+    }
+
+    #[test]
+    fn right_click_shows_menu() {
+        let shown = Rc::new(Cell::new(0));
+        let recording = Recording::default();
+        Harness::create_simple((), menu_widget(&shown, &recording), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            recording.clear();
+
+            harness.event(Event::MouseDown(mouse(MouseButton::Right, (10., 10.))));
+            assert_eq!(shown.get(), 1);
+            assert!(!recording
+                .drain()
+                .any(|rec| matches!(rec, Record::E(Event::MouseDown(_)))));
+        });
+    }
+
+    #[test]
+    fn long_press_shows_menu_and_swallows_release() {
+        let shown = Rc::new(Cell::new(0));
+        let recording = Recording::default();
+        Harness::create_simple((), menu_widget(&shown, &recording), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(Event::MouseDown(mouse(MouseButton::Left, (10., 10.))));
+            let token = *harness.window().timers.keys().next().unwrap();
+            harness.event(Event::Timer(token));
+            assert_eq!(shown.get(), 1);
+
+            recording.clear();
+            harness.event(Event::MouseUp(mouse(MouseButton::Left, (10., 10.))));
+            assert!(!received_mouse_up(&recording));
+        });
+    }
+
+    #[test]
+    fn long_press_cancelled_by_release_or_drag() {
+        let shown = Rc::new(Cell::new(0));
+        let recording = Recording::default();
+        Harness::create_simple((), menu_widget(&shown, &recording), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            harness.event(Event::MouseDown(mouse(MouseButton::Left, (10., 10.))));
+            let token = *harness.window().timers.keys().next().unwrap();
+            recording.clear();
+            harness.event(Event::MouseUp(mouse(MouseButton::Left, (10., 10.))));
+            assert!(received_mouse_up(&recording));
+            harness.event(Event::Timer(token));
+            assert_eq!(shown.get(), 0);
+
+            harness.event(Event::MouseDown(mouse(MouseButton::Left, (10., 10.))));
+            let token = *harness.window().timers.keys().next().unwrap();
+            harness.event(Event::MouseMove(mouse(MouseButton::Left, (20., 10.))));
+            harness.event(Event::Timer(token));
+            assert_eq!(shown.get(), 0);
+        });
+    }
+}
@@ -142,6 +142,17 @@ impl<T, W: Widget<T>, C: Controller<T, W>> Widget<T> for ControllerHost<W, C> {
     ) -> f64 {
         self.widget.compute_max_intrinsic(axis, ctx, bc, data, env)
     }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.widget.compute_min_intrinsic(axis, ctx, bc, data, env)
+    }
 }
 
 impl<W, C> WidgetWrapper for ControllerHost<W, C> {
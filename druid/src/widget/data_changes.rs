@@ -0,0 +1,74 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A controller that logs changes to its widget's data.
+
+use tracing::{debug, instrument};
+
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::DataDiff;
+
+/// A [`Controller`] that logs which paths of its widget's data changed, each
+/// time the widget is updated with different data.
+///
+/// Changes are logged at the `debug` level. See [`DataDiff`] for how paths
+/// are described, and [`WidgetExt::log_data_changes`] for a shorthand.
+///
+/// [`WidgetExt::log_data_changes`]: crate::WidgetExt::log_data_changes
+pub struct LogDataChanges<T> {
+    diff: DataDiff<T>,
+    label: Option<String>,
+}
+
+impl<T: Data> LogDataChanges<T> {
+    /// Create a new `LogDataChanges` that checks the paths in `diff`.
+    pub fn new(diff: DataDiff<T>) -> Self {
+        LogDataChanges { diff, label: None }
+    }
+
+    /// Builder-style method to set a label that is included in each message,
+    /// to tell several of these apart.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for LogDataChanges<T> {
+    #[instrument(
+        name = "LogDataChanges",
+        level = "trace",
+        skip(self, child, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) {
+            let label = self.label.as_deref().unwrap_or("data");
+            let changed = self.diff.changed(old_data, data);
+            if changed.is_empty() {
+                debug!(
+                    widget = ?ctx.widget_id(),
+                    "{label} changed outside of the diffed paths"
+                );
+            } else {
+                debug!(
+                    widget = ?ctx.widget_id(),
+                    "{label} changed: {}",
+                    changed.join(", ")
+                );
+            }
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
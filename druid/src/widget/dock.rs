@@ -0,0 +1,1174 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A docking container for tool panels.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use tracing::{instrument, trace, warn};
+
+use crate::commands::{CLOSE_WINDOW, DOCK_LAYOUT_CHANGED, SET_DOCK_LAYOUT};
+use crate::debug_state::DebugState;
+use crate::kurbo::Line;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::{theme, Cursor, Point, Rect, Selector, Vec2, WidgetPod, WindowConfig, WindowId};
+
+/// The height of the tab strip above each group of panels.
+const TAB_HEIGHT: f64 = 24.0;
+/// The horizontal padding around the title of each tab.
+const TAB_PADDING: f64 = 8.0;
+/// The distance from the inner edge of a group at which it can be resized.
+const RESIZE_SLOP: f64 = 3.0;
+/// The distance from the edges of the dock at which a dragged panel docks.
+const EDGE_DROP_ZONE: f64 = 48.0;
+/// The distance the mouse has to move before a tab is dragged.
+const DRAG_THRESHOLD: f64 = 4.0;
+/// The smallest size a docked group, and the center, can be resized to.
+const MIN_EXTENT: f64 = 40.0;
+/// The size of a group that is newly docked to an edge.
+const DEFAULT_EXTENT: f64 = 200.0;
+/// The size of a newly floated panel window.
+const DEFAULT_FLOAT_SIZE: Size = Size::new(300.0, 400.0);
+
+/// Sent from a floating panel window to its dock.
+const FLOATING_UPDATE: Selector<FloatingUpdate> = Selector::new("druid-builtin.dock.floating");
+
+/// An edge of a [`Dock`] that panels can be docked to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Data)]
+pub enum DockEdge {
+    /// The left edge.
+    Left,
+    /// The right edge.
+    Right,
+    /// The top edge.
+    Top,
+    /// The bottom edge.
+    Bottom,
+}
+
+/// Where a [`DockGroup`] is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub enum DockPosition {
+    /// Docked to an edge of the dock.
+    Edge(DockEdge),
+    /// In a separate window.
+    Floating {
+        /// The frame of the window, in screen coordinates.
+        rect: Rect,
+        /// The edge the panels return to when the window is closed.
+        home: DockEdge,
+    },
+}
+
+/// A group of panels, shown as tabs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DockGroup {
+    /// Where the group is shown.
+    pub position: DockPosition,
+    /// The ids of the panels in the group, in tab order.
+    pub panels: Vec<String>,
+    /// The index of the selected panel.
+    pub active: usize,
+    /// The width of groups docked to the left or right edge, or the height of groups
+    /// docked to the top or bottom edge.
+    pub extent: f64,
+}
+
+/// The arrangement of the panels of a [`Dock`].
+///
+/// Each edge has at most one group of panels; panels that are docked to an edge
+/// that already has a group are added to it as a tab. Panels that are not part of
+/// the layout are not shown.
+///
+/// The layout can be serialized with [`to_string`] and restored with [`parse`].
+/// For this reason panel ids must not be empty, or contain whitespace or commas;
+/// such panels are not added.
+///
+/// [`to_string`]: ToString::to_string
+/// [`parse`]: str::parse
+#[derive(Debug, Clone, Default, PartialEq, Data)]
+pub struct DockLayout {
+    #[data(eq)]
+    groups: Vec<DockGroup>,
+}
+
+/// An error parsing a [`DockLayout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DockLayoutParseError {
+    line: usize,
+}
+
+/// A panel that can be shown in a [`Dock`].
+struct Panel<T> {
+    id: String,
+    title: TextLayout<String>,
+    make_widget: Box<dyn Fn() -> Box<dyn Widget<T>>>,
+}
+
+/// The state of a tab being dragged.
+struct DockDrag {
+    panel: String,
+    start: Point,
+    pos: Point,
+    moved: bool,
+}
+
+/// Where a dragged panel would be dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DropTarget {
+    Edge(DockEdge),
+    Float(Point),
+}
+
+/// The state of a floating window, reported back to the dock.
+#[derive(Debug, Clone, Copy)]
+struct FloatingUpdate {
+    window: WindowId,
+    rect: Option<Rect>,
+    active: usize,
+    /// Whether the panels should return to the dock, closing the window.
+    redock: bool,
+}
+
+/// A container for tool panels around a central widget, as found in IDEs.
+///
+/// Panels are grouped into tabs on the edges of the dock. A tab can be dragged
+/// to another edge, or out of the dock to float it in its own window. Double-clicking
+/// the tabs of a floating window, or closing it, docks its panels again. Groups can
+/// be resized by dragging their inner edge.
+///
+/// Each panel is identified by a string id and created by a closure, because floating
+/// a panel creates a new instance of its widget in the new window. Any state a panel
+/// needs to keep should therefore be part of the data.
+///
+/// The current layout is sent as a [`DOCK_LAYOUT_CHANGED`] notification whenever the user
+/// changes it, so it can be persisted, and can be replaced with the [`SET_DOCK_LAYOUT`]
+/// command.
+///
+/// [`DOCK_LAYOUT_CHANGED`]: crate::commands::DOCK_LAYOUT_CHANGED
+/// [`SET_DOCK_LAYOUT`]: crate::commands::SET_DOCK_LAYOUT
+pub struct Dock<T> {
+    center: WidgetPod<T, Box<dyn Widget<T>>>,
+    panels: Vec<Panel<T>>,
+    layout: DockLayout,
+    /// The widgets of the docked panels, by id.
+    pods: HashMap<String, WidgetPod<T, Box<dyn Widget<T>>>>,
+    /// The panels shown in each floating window.
+    windows: HashMap<WindowId, Vec<String>>,
+    /// The rects of the docked groups, by index in the layout, as of the last layout.
+    group_rects: Vec<(usize, Rect)>,
+    drag: Option<DockDrag>,
+    resizing: Option<DockEdge>,
+    /// Whether the floating windows need to be synced with the layout.
+    needs_sync: bool,
+}
+
+impl DockEdge {
+    const ALL: [DockEdge; 4] = [
+        DockEdge::Top,
+        DockEdge::Bottom,
+        DockEdge::Left,
+        DockEdge::Right,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            DockEdge::Left => "left",
+            DockEdge::Right => "right",
+            DockEdge::Top => "top",
+            DockEdge::Bottom => "bottom",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<DockEdge> {
+        DockEdge::ALL.into_iter().find(|edge| edge.name() == name)
+    }
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, DockEdge::Left | DockEdge::Right)
+    }
+}
+
+impl DockLayout {
+    /// Create an empty layout.
+    pub fn new() -> DockLayout {
+        DockLayout::default()
+    }
+
+    /// Builder-style method to dock a panel to the provided edge.
+    pub fn with_panel(mut self, panel: impl Into<String>, edge: DockEdge) -> Self {
+        self.dock_panel(panel, edge);
+        self
+    }
+
+    /// The groups of panels in this layout.
+    pub fn groups(&self) -> &[DockGroup] {
+        &self.groups
+    }
+
+    /// Returns `true` if the panel is part of the layout, either docked or floating.
+    pub fn contains(&self, panel: &str) -> bool {
+        self.find(panel).is_some()
+    }
+
+    /// Dock a panel to the provided edge, as the selected tab.
+    ///
+    /// The panel is moved if it is already part of the layout.
+    pub fn dock_panel(&mut self, panel: impl Into<String>, edge: DockEdge) {
+        let panel = panel.into();
+        if !is_valid_panel_id(&panel) {
+            return;
+        }
+        self.remove_panel(&panel);
+        match self.group_at(edge) {
+            Some(idx) => {
+                let group = &mut self.groups[idx];
+                group.panels.push(panel);
+                group.active = group.panels.len() - 1;
+            }
+            None => self.groups.push(DockGroup {
+                position: DockPosition::Edge(edge),
+                panels: vec![panel],
+                active: 0,
+                extent: DEFAULT_EXTENT,
+            }),
+        }
+    }
+
+    /// Float a panel in its own window, with the provided frame in screen coordinates.
+    ///
+    /// The panel is moved if it is already part of the layout.
+    pub fn float_panel(&mut self, panel: impl Into<String>, rect: Rect) {
+        let panel = panel.into();
+        let home = match self.remove_panel(&panel) {
+            Some(DockPosition::Edge(edge)) | Some(DockPosition::Floating { home: edge, .. }) => {
+                edge
+            }
+            None => DockEdge::Right,
+        };
+        self.groups.push(DockGroup {
+            position: DockPosition::Floating { rect, home },
+            panels: vec![panel],
+            active: 0,
+            extent: DEFAULT_EXTENT,
+        });
+    }
+
+    /// Remove a panel from the layout, returning where it was.
+    pub fn remove_panel(&mut self, panel: &str) -> Option<DockPosition> {
+        let (group_idx, panel_idx) = self.find(panel)?;
+        let group = &mut self.groups[group_idx];
+        let position = group.position;
+        group.panels.remove(panel_idx);
+        if group.panels.is_empty() {
+            self.groups.remove(group_idx);
+        } else if group.active > panel_idx || group.active == group.panels.len() {
+            group.active -= 1;
+        }
+        Some(position)
+    }
+
+    /// Set the size of the group docked to `edge`, if there is one.
+    pub fn set_extent(&mut self, edge: DockEdge, extent: f64) {
+        if let Some(idx) = self.group_at(edge) {
+            self.groups[idx].extent = extent.max(MIN_EXTENT);
+        }
+    }
+
+    fn group_at(&self, edge: DockEdge) -> Option<usize> {
+        self.groups
+            .iter()
+            .position(|group| group.position == DockPosition::Edge(edge))
+    }
+
+    fn find(&self, panel: &str) -> Option<(usize, usize)> {
+        self.groups
+            .iter()
+            .enumerate()
+            .find_map(|(group_idx, group)| {
+                let panel_idx = group.panels.iter().position(|p| p == panel)?;
+                Some((group_idx, panel_idx))
+            })
+    }
+
+    /// Dock all panels of a floating group back to its home edge.
+    fn redock(&mut self, group_idx: usize) {
+        let group = self.groups.remove(group_idx);
+        if let DockPosition::Floating { home, .. } = group.position {
+            for panel in group.panels {
+                self.dock_panel(panel, home);
+            }
+        }
+    }
+
+    fn floating_group(&self, panels: &[String]) -> Option<usize> {
+        self.groups.iter().position(|group| {
+            matches!(group.position, DockPosition::Floating { .. }) && group.panels == panels
+        })
+    }
+}
+
+/// Returns `true` if `id` can be written to and read back from a [`DockLayout`] string.
+fn is_valid_panel_id(id: &str) -> bool {
+    let valid = !id.is_empty() && !id.contains(|c: char| c.is_whitespace() || c == ',');
+    if !valid {
+        debug_panic!(
+            "Dock panel id {:?} must not be empty or contain whitespace or commas",
+            id
+        );
+    }
+    valid
+}
+
+/// One group per line, either
+/// `edge <edge> <extent> <active> <panels>` or
+/// `float <x> <y> <width> <height> <home> <active> <panels>`,
+/// where `<panels>` is a comma separated list of ids.
+impl fmt::Display for DockLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for group in &self.groups {
+            match group.position {
+                DockPosition::Edge(edge) => write!(f, "edge {} {}", edge.name(), group.extent)?,
+                DockPosition::Floating { rect, home } => write!(
+                    f,
+                    "float {} {} {} {} {}",
+                    rect.x0,
+                    rect.y0,
+                    rect.width(),
+                    rect.height(),
+                    home.name()
+                )?,
+            }
+            writeln!(f, " {} {}", group.active, group.panels.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DockLayout {
+    type Err = DockLayoutParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut layout = DockLayout::new();
+        for (idx, line) in s.lines().enumerate() {
+            let err = DockLayoutParseError { line: idx + 1 };
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let num = |i: usize| words.get(i).and_then(|w| w.parse::<f64>().ok());
+            let edge = |i: usize| words.get(i).and_then(|w| DockEdge::from_name(w));
+            let (position, extent, rest) = match words.first() {
+                None => continue,
+                Some(&"edge") => (
+                    DockPosition::Edge(edge(1).ok_or(err.clone())?),
+                    num(2).ok_or(err.clone())?,
+                    3,
+                ),
+                Some(&"float") => {
+                    let (x, y) = (num(1).ok_or(err.clone())?, num(2).ok_or(err.clone())?);
+                    let (w, h) = (num(3).ok_or(err.clone())?, num(4).ok_or(err.clone())?);
+                    let rect = Rect::from_origin_size((x, y), (w, h));
+                    let home = edge(5).ok_or(err.clone())?;
+                    (DockPosition::Floating { rect, home }, DEFAULT_EXTENT, 6)
+                }
+                Some(_) => return Err(err),
+            };
+            let active = words
+                .get(rest)
+                .and_then(|w| w.parse::<usize>().ok())
+                .ok_or(err.clone())?;
+            let panels = words
+                .get(rest + 1)
+                .map(|w| w.split(',').map(String::from).collect::<Vec<_>>())
+                .filter(|panels| active < panels.len())
+                .ok_or(err)?;
+            layout.groups.push(DockGroup {
+                position,
+                panels,
+                active,
+                extent,
+            });
+        }
+        Ok(layout)
+    }
+}
+
+impl fmt::Display for DockLayoutParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid dock layout on line {}", self.line)
+    }
+}
+
+impl std::error::Error for DockLayoutParseError {}
+
+impl<T: Data> Dock<T> {
+    /// Create a new dock around the provided central widget.
+    pub fn new(center: impl Widget<T> + 'static) -> Self {
+        Dock {
+            center: WidgetPod::new(center).boxed(),
+            panels: Vec::new(),
+            layout: DockLayout::new(),
+            pods: HashMap::new(),
+            windows: HashMap::new(),
+            group_rects: Vec::new(),
+            drag: None,
+            resizing: None,
+            needs_sync: true,
+        }
+    }
+
+    /// Builder-style method to add a panel that can be shown in the dock.
+    ///
+    /// `make_widget` is called whenever the panel is shown in a new place.
+    /// The panel is only visible if it is part of the [layout]. Like in the
+    /// layout, `id` must not be empty, or contain whitespace or commas.
+    ///
+    /// [layout]: Dock::with_layout
+    pub fn with_panel<W: Widget<T> + 'static>(
+        mut self,
+        id: impl Into<String>,
+        title: impl Into<String>,
+        make_widget: impl Fn() -> W + 'static,
+    ) -> Self {
+        let id = id.into();
+        if !is_valid_panel_id(&id) {
+            return self;
+        }
+        self.panels.push(Panel {
+            id,
+            title: TextLayout::from_text(title.into()),
+            make_widget: Box::new(move || Box::new(make_widget())),
+        });
+        self
+    }
+
+    /// Builder-style method to set the initial layout of the panels.
+    pub fn with_layout(mut self, layout: DockLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// The current layout of the panels.
+    pub fn layout(&self) -> &DockLayout {
+        &self.layout
+    }
+
+    fn panel(&self, id: &str) -> Option<&Panel<T>> {
+        self.panels.iter().find(|panel| panel.id == id)
+    }
+
+    /// The width of the tab for a panel.
+    fn tab_width(&self, id: &str) -> f64 {
+        self.panel(id)
+            .map(|panel| panel.title.size().width)
+            .unwrap_or_default()
+            + 2.0 * TAB_PADDING
+    }
+
+    /// The index of the tab at `x` in the tab strip of a group starting at `x0`.
+    fn tab_at(&self, group: &DockGroup, x0: f64, x: f64) -> Option<usize> {
+        let mut end = x0;
+        group.panels.iter().position(|id| {
+            end += self.tab_width(id);
+            x < end
+        })
+    }
+
+    /// Create the widgets of newly docked panels, and drop those that are no longer docked.
+    ///
+    /// Returns `true` if any widgets were added or removed.
+    fn update_pods(&mut self) -> bool {
+        let docked = self
+            .layout
+            .groups
+            .iter()
+            .filter(|group| matches!(group.position, DockPosition::Edge(_)))
+            .flat_map(|group| group.panels.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        let before = self.pods.len();
+        self.pods.retain(|id, _| docked.contains(id));
+        let mut changed = self.pods.len() != before;
+        for id in docked {
+            if self.pods.contains_key(&id) {
+                continue;
+            }
+            match self.panels.iter().find(|panel| panel.id == id) {
+                Some(panel) => {
+                    self.pods.insert(id, WidgetPod::new((panel.make_widget)()));
+                    changed = true;
+                }
+                None => warn!("Dock layout contains unknown panel {:?}", id),
+            }
+        }
+        changed
+    }
+
+    /// Open and close floating windows to match the layout.
+    fn sync_windows(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        self.needs_sync = false;
+        let mut shown = Vec::new();
+        let layout = &self.layout;
+        self.windows
+            .retain(|window, panels| match layout.floating_group(panels) {
+                Some(idx) => {
+                    shown.push(idx);
+                    true
+                }
+                None => {
+                    ctx.submit_command(CLOSE_WINDOW.to(*window));
+                    false
+                }
+            });
+
+        for (idx, group) in self.layout.groups.iter().enumerate() {
+            let rect = match group.position {
+                DockPosition::Floating { rect, .. } if !shown.contains(&idx) => rect,
+                _ => continue,
+            };
+            let panels = group
+                .panels
+                .iter()
+                .filter_map(|id| self.panels.iter().find(|panel| &panel.id == id))
+                .map(|panel| {
+                    let title = panel.title.text().cloned().unwrap_or_default();
+                    (
+                        TextLayout::from_text(title),
+                        WidgetPod::new((panel.make_widget)()),
+                    )
+                })
+                .collect();
+            let root = FloatingGroup {
+                dock: ctx.widget_id(),
+                panels,
+                active: group.active,
+            };
+            let config = WindowConfig::default()
+                .set_position(rect.origin())
+                .window_size(rect.size());
+            trace!("Floating panels {:?}", group.panels);
+            let window = ctx.new_sub_window(config, root, data.clone(), env.clone());
+            self.windows.insert(window, group.panels.clone());
+        }
+    }
+
+    /// Apply a change to the layout made by the user.
+    fn layout_changed(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        if self.update_pods() {
+            ctx.children_changed();
+        }
+        self.sync_windows(ctx, data, env);
+        ctx.submit_notification_without_warning(DOCK_LAYOUT_CHANGED.with(self.layout.clone()));
+        ctx.request_layout();
+    }
+
+    fn drop_target(&self, size: Size, pos: Point) -> DropTarget {
+        for &(idx, rect) in &self.group_rects {
+            let header = Rect::new(rect.x0, rect.y0, rect.x1, rect.y0 + TAB_HEIGHT);
+            if let (true, DockPosition::Edge(edge)) =
+                (header.contains(pos), self.layout.groups[idx].position)
+            {
+                return DropTarget::Edge(edge);
+            }
+        }
+        if pos.x < EDGE_DROP_ZONE {
+            DropTarget::Edge(DockEdge::Left)
+        } else if pos.x > size.width - EDGE_DROP_ZONE {
+            DropTarget::Edge(DockEdge::Right)
+        } else if pos.y < EDGE_DROP_ZONE {
+            DropTarget::Edge(DockEdge::Top)
+        } else if pos.y > size.height - EDGE_DROP_ZONE {
+            DropTarget::Edge(DockEdge::Bottom)
+        } else {
+            DropTarget::Float(pos)
+        }
+    }
+
+    /// The area that is highlighted while dragging over a drop target.
+    fn drop_rect(&self, size: Size, target: DropTarget) -> Rect {
+        match target {
+            DropTarget::Edge(edge) => {
+                let existing = self.layout.group_at(edge).and_then(|idx| {
+                    self.group_rects
+                        .iter()
+                        .find(|(i, _)| *i == idx)
+                        .map(|(_, rect)| *rect)
+                });
+                existing.unwrap_or_else(|| {
+                    let extent = DEFAULT_EXTENT.min(size.width / 3.0).min(size.height / 3.0);
+                    match edge {
+                        DockEdge::Left => Rect::new(0.0, 0.0, extent, size.height),
+                        DockEdge::Right => {
+                            Rect::new(size.width - extent, 0.0, size.width, size.height)
+                        }
+                        DockEdge::Top => Rect::new(0.0, 0.0, size.width, extent),
+                        DockEdge::Bottom => {
+                            Rect::new(0.0, size.height - extent, size.width, size.height)
+                        }
+                    }
+                })
+            }
+            DropTarget::Float(pos) => Rect::from_origin_size(pos, DEFAULT_FLOAT_SIZE),
+        }
+    }
+
+    /// The docked edge whose inner border is at `pos`, if any.
+    fn resize_handle_at(&self, pos: Point) -> Option<DockEdge> {
+        self.group_rects.iter().find_map(|&(idx, rect)| {
+            let edge = match self.layout.groups[idx].position {
+                DockPosition::Edge(edge) => edge,
+                DockPosition::Floating { .. } => return None,
+            };
+            let (border, coord, in_span) = match edge {
+                DockEdge::Left => (rect.x1, pos.x, (rect.y0..rect.y1).contains(&pos.y)),
+                DockEdge::Right => (rect.x0, pos.x, (rect.y0..rect.y1).contains(&pos.y)),
+                DockEdge::Top => (rect.y1, pos.y, (rect.x0..rect.x1).contains(&pos.x)),
+                DockEdge::Bottom => (rect.y0, pos.y, (rect.x0..rect.x1).contains(&pos.x)),
+            };
+            (in_span && (coord - border).abs() <= RESIZE_SLOP).then_some(edge)
+        })
+    }
+
+    fn set_resize_cursor(ctx: &mut EventCtx, edge: Option<DockEdge>) {
+        match edge {
+            Some(edge) if edge.is_horizontal() => ctx.set_cursor(&Cursor::ResizeLeftRight),
+            Some(_) => ctx.set_cursor(&Cursor::ResizeUpDown),
+            None => ctx.clear_cursor(),
+        }
+    }
+
+    /// Handle the mouse for tab selection, dragging and resizing.
+    ///
+    /// Returns `true` if the event was consumed.
+    fn mouse_event(&mut self, ctx: &mut EventCtx, event: &Event, data: &T, env: &Env) -> bool {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if let Some(edge) = self.resize_handle_at(mouse.pos) {
+                    self.resizing = Some(edge);
+                    ctx.set_active(true);
+                    return true;
+                }
+                for &(idx, rect) in &self.group_rects {
+                    let header = Rect::new(rect.x0, rect.y0, rect.x1, rect.y0 + TAB_HEIGHT);
+                    if !header.contains(mouse.pos) {
+                        continue;
+                    }
+                    let group = &self.layout.groups[idx];
+                    if let Some(tab) = self.tab_at(group, rect.x0, mouse.pos.x) {
+                        let panel = group.panels[tab].clone();
+                        if group.active != tab {
+                            self.layout.groups[idx].active = tab;
+                            ctx.submit_notification_without_warning(
+                                DOCK_LAYOUT_CHANGED.with(self.layout.clone()),
+                            );
+                            ctx.request_layout();
+                        }
+                        self.drag = Some(DockDrag {
+                            panel,
+                            start: mouse.pos,
+                            pos: mouse.pos,
+                            moved: false,
+                        });
+                        ctx.set_active(true);
+                    }
+                    return true;
+                }
+                false
+            }
+            Event::MouseMove(mouse) => {
+                if let Some(edge) = self.resizing {
+                    let size = ctx.size();
+                    let extent = match edge {
+                        DockEdge::Left => mouse.pos.x,
+                        DockEdge::Right => size.width - mouse.pos.x,
+                        DockEdge::Top => mouse.pos.y,
+                        DockEdge::Bottom => size.height - mouse.pos.y,
+                    };
+                    self.layout.set_extent(edge, extent);
+                    ctx.request_layout();
+                    return true;
+                }
+                if let Some(drag) = &mut self.drag {
+                    drag.pos = mouse.pos;
+                    drag.moved |= (mouse.pos - drag.start).hypot() > DRAG_THRESHOLD;
+                    if drag.moved {
+                        ctx.request_paint();
+                    }
+                    return true;
+                }
+                if ctx.is_hot() {
+                    Self::set_resize_cursor(ctx, self.resize_handle_at(mouse.pos));
+                }
+                false
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() => {
+                if self.resizing.take().is_some() {
+                    ctx.set_active(false);
+                    ctx.submit_notification_without_warning(
+                        DOCK_LAYOUT_CHANGED.with(self.layout.clone()),
+                    );
+                    return true;
+                }
+                let drag = match self.drag.take() {
+                    Some(drag) => drag,
+                    None => return false,
+                };
+                ctx.set_active(false);
+                if drag.moved {
+                    match self.drop_target(ctx.size(), mouse.pos) {
+                        DropTarget::Edge(edge) => self.layout.dock_panel(drag.panel, edge),
+                        DropTarget::Float(pos) => {
+                            // Put the window where the tab was dropped.
+                            let origin = ctx.to_screen(pos) - Vec2::new(TAB_PADDING, TAB_HEIGHT);
+                            let rect = Rect::from_origin_size(origin, DEFAULT_FLOAT_SIZE);
+                            self.layout.float_panel(drag.panel, rect);
+                        }
+                    }
+                    self.layout_changed(ctx, data, env);
+                    ctx.request_paint();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn floating_update(
+        &mut self,
+        ctx: &mut EventCtx,
+        update: &FloatingUpdate,
+        data: &T,
+        env: &Env,
+    ) {
+        let idx = match self
+            .windows
+            .get(&update.window)
+            .and_then(|panels| self.layout.floating_group(panels))
+        {
+            Some(idx) => idx,
+            None => return,
+        };
+        if update.redock {
+            self.windows.remove(&update.window);
+            self.layout.redock(idx);
+            ctx.submit_command(CLOSE_WINDOW.to(update.window));
+            self.layout_changed(ctx, data, env);
+            return;
+        }
+        let group = &mut self.layout.groups[idx];
+        group.active = update.active.min(group.panels.len() - 1);
+        if let (DockPosition::Floating { rect, .. }, Some(new_rect)) =
+            (&mut group.position, update.rect)
+        {
+            *rect = new_rect;
+        }
+        ctx.submit_notification_without_warning(DOCK_LAYOUT_CHANGED.with(self.layout.clone()));
+    }
+
+    /// The rects of the docked groups, by index in the layout, and the rect of the center.
+    fn compute_rects(&self, size: Size) -> (Vec<(usize, Rect)>, Rect) {
+        let mut area = size.to_rect();
+        let mut rects = Vec::new();
+        for edge in DockEdge::ALL {
+            let idx = match self.layout.group_at(edge) {
+                Some(idx) => idx,
+                None => continue,
+            };
+            let available = if edge.is_horizontal() {
+                area.width()
+            } else {
+                area.height()
+            };
+            let extent = self.layout.groups[idx]
+                .extent
+                .min(available - MIN_EXTENT)
+                .max(0.0);
+            let rect = match edge {
+                DockEdge::Left => {
+                    area.x0 += extent;
+                    Rect::new(area.x0 - extent, area.y0, area.x0, area.y1)
+                }
+                DockEdge::Right => {
+                    area.x1 -= extent;
+                    Rect::new(area.x1, area.y0, area.x1 + extent, area.y1)
+                }
+                DockEdge::Top => {
+                    area.y0 += extent;
+                    Rect::new(area.x0, area.y0 - extent, area.x1, area.y0)
+                }
+                DockEdge::Bottom => {
+                    area.y1 -= extent;
+                    Rect::new(area.x0, area.y1, area.x1, area.y1 + extent)
+                }
+            };
+            rects.push((idx, rect));
+        }
+        (rects, area)
+    }
+
+    /// The ids of the selected panels of the docked groups.
+    fn active_panels(&self) -> Vec<String> {
+        self.group_rects
+            .iter()
+            .map(|&(idx, _)| {
+                let group = &self.layout.groups[idx];
+                group.panels[group.active].clone()
+            })
+            .collect()
+    }
+
+    fn paint_group(&mut self, ctx: &mut PaintCtx, idx: usize, rect: Rect, data: &T, env: &Env) {
+        let group = &self.layout.groups[idx];
+        let header = Rect::new(rect.x0, rect.y0, rect.x1, rect.y0 + TAB_HEIGHT);
+        ctx.fill(header, &env.get(theme::BACKGROUND_DARK));
+        let mut x = rect.x0;
+        for (tab, id) in group.panels.iter().enumerate() {
+            let width = self.tab_width(id);
+            if tab == group.active {
+                let tab_rect = Rect::new(x, header.y0, x + width, header.y1);
+                ctx.fill(tab_rect, &env.get(theme::BACKGROUND_LIGHT));
+            }
+            if let Some(panel) = self.panel(id) {
+                let y = header.y0 + (TAB_HEIGHT - panel.title.size().height) / 2.0;
+                ctx.with_save(|ctx| {
+                    ctx.clip(header);
+                    panel.title.draw(ctx, (x + TAB_PADDING, y));
+                });
+            }
+            x += width;
+        }
+
+        let active = group.panels[group.active].clone();
+        if let Some(pod) = self.pods.get_mut(&active) {
+            pod.paint(ctx, data, env);
+        }
+
+        if let DockPosition::Edge(edge) = group.position {
+            let border = match edge {
+                DockEdge::Left => Line::new((rect.x1 - 0.5, rect.y0), (rect.x1 - 0.5, rect.y1)),
+                DockEdge::Right => Line::new((rect.x0 + 0.5, rect.y0), (rect.x0 + 0.5, rect.y1)),
+                DockEdge::Top => Line::new((rect.x0, rect.y1 - 0.5), (rect.x1, rect.y1 - 0.5)),
+                DockEdge::Bottom => Line::new((rect.x0, rect.y0 + 0.5), (rect.x1, rect.y0 + 0.5)),
+            };
+            ctx.stroke(border, &env.get(theme::BORDER_DARK), 1.0);
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Dock<T> {
+    #[instrument(name = "Dock", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if self.needs_sync {
+            self.sync_windows(ctx, data, env);
+        }
+
+        match event {
+            Event::Command(cmd) if cmd.is(SET_DOCK_LAYOUT) => {
+                ctx.set_handled();
+                self.layout = cmd.get_unchecked(SET_DOCK_LAYOUT).clone();
+                if self.update_pods() {
+                    ctx.children_changed();
+                }
+                self.sync_windows(ctx, data, env);
+                ctx.request_layout();
+                return;
+            }
+            Event::Command(cmd) if cmd.is(FLOATING_UPDATE) => {
+                ctx.set_handled();
+                let update = *cmd.get_unchecked(FLOATING_UPDATE);
+                self.floating_update(ctx, &update, data, env);
+                return;
+            }
+            _ => (),
+        }
+
+        if self.mouse_event(ctx, event, data, env) {
+            ctx.set_handled();
+            return;
+        }
+
+        self.center.event(ctx, event, data, env);
+        for id in self.active_panels() {
+            if let Some(pod) = self.pods.get_mut(&id) {
+                pod.event(ctx, event, data, env);
+            }
+        }
+    }
+
+    #[instrument(name = "Dock", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.update_pods();
+        }
+        self.center.lifecycle(ctx, event, data, env);
+        if event.should_propagate_to_hidden() {
+            for pod in self.pods.values_mut() {
+                pod.lifecycle(ctx, event, data, env);
+            }
+        } else {
+            for id in self.active_panels() {
+                if let Some(pod) = self.pods.get_mut(&id) {
+                    pod.lifecycle(ctx, event, data, env);
+                }
+            }
+        }
+    }
+
+    #[instrument(name = "Dock", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.center.update(ctx, data, env);
+        for pod in self.pods.values_mut() {
+            pod.update(ctx, data, env);
+        }
+        if ctx.env_changed() {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Dock", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Dock");
+        for panel in &mut self.panels {
+            panel.title.rebuild_if_needed(ctx.text(), env);
+        }
+
+        let size = bc.max();
+        let (group_rects, center) = self.compute_rects(size);
+        self.group_rects = group_rects;
+
+        let center_bc = BoxConstraints::tight(center.size());
+        self.center.layout(ctx, &center_bc, data, env);
+        self.center.set_origin(ctx, center.origin());
+
+        for &(idx, rect) in &self.group_rects {
+            let group = &self.layout.groups[idx];
+            let body = Rect::new(rect.x0, rect.y0 + TAB_HEIGHT, rect.x1, rect.y1);
+            let body_bc = BoxConstraints::tight(body.size().clamp(Size::ZERO, body.size()));
+            if let Some(pod) = self.pods.get_mut(&group.panels[group.active]) {
+                pod.layout(ctx, &body_bc, data, env);
+                pod.set_origin(ctx, body.origin());
+            }
+        }
+
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "Dock", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.center.paint(ctx, data, env);
+        for (idx, rect) in self.group_rects.clone() {
+            self.paint_group(ctx, idx, rect, data, env);
+        }
+
+        if let Some(drag) = self.drag.as_ref().filter(|drag| drag.moved) {
+            let size = ctx.size();
+            let target = self.drop_rect(size, self.drop_target(size, drag.pos));
+            let color = env.get(theme::PRIMARY_LIGHT);
+            ctx.fill(target, &color.with_alpha(0.25));
+            ctx.stroke(target.inset(-1.0), &color, 2.0);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut children = vec![self.center.widget().debug_state(data)];
+        children.extend(self.pods.values().map(|pod| pod.widget().debug_state(data)));
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+/// The root widget of a window with floating panels.
+struct FloatingGroup<T> {
+    dock: WidgetId,
+    panels: Vec<(TextLayout<String>, WidgetPod<T, Box<dyn Widget<T>>>)>,
+    active: usize,
+}
+
+impl<T: Data> FloatingGroup<T> {
+    fn report(&self, ctx: &mut EventCtx, redock: bool) {
+        let window = ctx.window();
+        let rect = Rect::from_origin_size(window.get_position(), window.get_size());
+        let update = FloatingUpdate {
+            window: ctx.window_id(),
+            rect: Some(rect),
+            active: self.active,
+            redock,
+        };
+        ctx.submit_command(FLOATING_UPDATE.with(update).to(self.dock));
+    }
+
+    fn tab_at(&self, x: f64) -> Option<usize> {
+        let mut end = 0.0;
+        self.panels.iter().position(|(title, _)| {
+            end += title.size().width + 2.0 * TAB_PADDING;
+            x < end
+        })
+    }
+}
+
+impl<T: Data> Widget<T> for FloatingGroup<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.pos.y < TAB_HEIGHT => {
+                ctx.set_handled();
+                if mouse.count == 2 {
+                    self.report(ctx, true);
+                } else if let Some(tab) = self.tab_at(mouse.pos.x) {
+                    if tab != self.active {
+                        self.active = tab;
+                        self.report(ctx, false);
+                        ctx.request_layout();
+                    }
+                }
+                return;
+            }
+            Event::WindowSize(_) => self.report(ctx, false),
+            // If the window was closed by the dock this is ignored,
+            // because the dock no longer knows the window.
+            Event::WindowDisconnected => self.report(ctx, true),
+            _ => (),
+        }
+        if let Some((_, pod)) = self.panels.get_mut(self.active) {
+            pod.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for (idx, (_, pod)) in self.panels.iter_mut().enumerate() {
+            if idx == self.active || event.should_propagate_to_hidden() {
+                pod.lifecycle(ctx, event, data, env);
+            }
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for (_, pod) in &mut self.panels {
+            pod.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        for (title, _) in &mut self.panels {
+            title.rebuild_if_needed(ctx.text(), env);
+        }
+        let size = bc.max();
+        let body = Size::new(size.width, (size.height - TAB_HEIGHT).max(0.0));
+        if let Some((_, pod)) = self.panels.get_mut(self.active) {
+            pod.layout(ctx, &BoxConstraints::tight(body), data, env);
+            pod.set_origin(ctx, Point::new(0.0, TAB_HEIGHT));
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let header = Rect::new(0.0, 0.0, ctx.size().width, TAB_HEIGHT);
+        ctx.fill(header, &env.get(theme::BACKGROUND_DARK));
+        let mut x = 0.0;
+        for (idx, (title, _)) in self.panels.iter().enumerate() {
+            let width = title.size().width + 2.0 * TAB_PADDING;
+            if idx == self.active {
+                let tab = Rect::new(x, 0.0, x + width, TAB_HEIGHT);
+                ctx.fill(tab, &env.get(theme::BACKGROUND_LIGHT));
+            }
+            let y = (TAB_HEIGHT - title.size().height) / 2.0;
+            title.draw(ctx, (x + TAB_PADDING, y));
+            x += width;
+        }
+        if let Some((_, pod)) = self.panels.get_mut(self.active) {
+            pod.paint(ctx, data, env);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn docking_merges_groups() {
+        let mut layout = DockLayout::new()
+            .with_panel("files", DockEdge::Left)
+            .with_panel("search", DockEdge::Left)
+            .with_panel("terminal", DockEdge::Bottom);
+        assert_eq!(layout.groups().len(), 2);
+        assert_eq!(layout.groups()[0].panels, ["files", "search"]);
+        assert_eq!(layout.groups()[0].active, 1);
+
+        layout.dock_panel("search", DockEdge::Bottom);
+        assert_eq!(layout.groups()[0].panels, ["files"]);
+        assert_eq!(layout.groups()[0].active, 0);
+        assert_eq!(layout.groups()[1].panels, ["terminal", "search"]);
+
+        layout.dock_panel("files", DockEdge::Right);
+        assert_eq!(layout.groups().len(), 2);
+        assert_eq!(
+            layout.groups()[1].position,
+            DockPosition::Edge(DockEdge::Right)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_ids_that_can_not_be_parsed() {
+        DockLayout::new().with_panel("file list", DockEdge::Left);
+    }
+
+    #[test]
+    fn floating_returns_home() {
+        let mut layout = DockLayout::new().with_panel("files", DockEdge::Left);
+        let rect = Rect::new(10.0, 20.0, 310.0, 420.0);
+        layout.float_panel("files", rect);
+        assert_eq!(
+            layout.groups()[0].position,
+            DockPosition::Floating {
+                rect,
+                home: DockEdge::Left
+            }
+        );
+        let idx = layout.floating_group(&["files".to_string()]).unwrap();
+        layout.redock(idx);
+        assert_eq!(
+            layout,
+            DockLayout::new().with_panel("files", DockEdge::Left)
+        );
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let mut layout = DockLayout::new()
+            .with_panel("files", DockEdge::Left)
+            .with_panel("search", DockEdge::Left)
+            .with_panel("terminal", DockEdge::Bottom)
+            .with_panel("outline", DockEdge::Right);
+        layout.set_extent(DockEdge::Bottom, 120.0);
+        layout.float_panel("outline", Rect::new(10.0, 20.0, 310.0, 420.0));
+
+        let text = layout.to_string();
+        assert_eq!(text.parse::<DockLayout>(), Ok(layout));
+        assert_eq!(
+            "edge left 200 3 files".parse::<DockLayout>(),
+            Err(DockLayoutParseError { line: 1 })
+        );
+        assert_eq!(
+            "\nedge middle 200 0 files".parse::<DockLayout>(),
+            Err(DockLayoutParseError { line: 2 })
+        );
+    }
+}
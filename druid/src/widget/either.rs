@@ -16,15 +16,24 @@
 
 use crate::debug_state::DebugState;
 use crate::widget::prelude::*;
+use crate::widget::switch_transition::{SwitchTransition, TransitionState};
 use crate::{Data, Point, WidgetPod};
 use tracing::instrument;
 
 /// A widget that switches between two possible child views.
+///
+/// Switching can be animated with [`with_transition`].
+///
+/// [`with_transition`]: Either::with_transition
 pub struct Either<T> {
     closure: Box<dyn Fn(&T, &Env) -> bool>,
     true_branch: WidgetPod<T, Box<dyn Widget<T>>>,
     false_branch: WidgetPod<T, Box<dyn Widget<T>>>,
     current: bool,
+    transition: SwitchTransition,
+    transition_state: Option<TransitionState>,
+    /// The data from before the switch, which the previous branch is painted with.
+    previous_data: Option<T>,
 }
 
 impl<T> Either<T> {
@@ -42,8 +51,17 @@ impl<T> Either<T> {
             true_branch: WidgetPod::new(true_branch).boxed(),
             false_branch: WidgetPod::new(false_branch).boxed(),
             current: false,
+            transition: SwitchTransition::Instant,
+            transition_state: None,
+            previous_data: None,
         }
     }
+
+    /// Builder-style method to animate switching between the branches.
+    pub fn with_transition(mut self, transition: SwitchTransition) -> Self {
+        self.transition = transition;
+        self
+    }
 }
 
 impl<T: Data> Widget<T> for Either<T> {
@@ -52,9 +70,19 @@ impl<T: Data> Widget<T> for Either<T> {
         if event.should_propagate_to_hidden() {
             self.true_branch.event(ctx, event, data, env);
             self.false_branch.event(ctx, event, data, env);
-        } else {
+        } else if self.transition_state.is_none() {
             self.current_widget().event(ctx, event, data, env)
         }
+
+        if let (Some(state), Event::AnimFrame(interval)) = (&mut self.transition_state, event) {
+            if state.advance(*interval) {
+                ctx.request_anim_frame();
+            } else {
+                self.transition_state = None;
+                self.previous_data = None;
+            }
+            ctx.request_paint();
+        }
     }
 
     #[instrument(name = "Either", level = "trace", skip(self, ctx, event, data, env), fields(branch = self.current))]
@@ -71,11 +99,13 @@ impl<T: Data> Widget<T> for Either<T> {
         }
     }
 
-    #[instrument(name = "Either", level = "trace", skip(self, ctx, _old_data, data, env), fields(branch = self.current))]
-    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+    #[instrument(name = "Either", level = "trace", skip(self, ctx, old_data, data, env), fields(branch = self.current))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
         let current = (self.closure)(data, env);
         if current != self.current {
             self.current = current;
+            self.transition_state = self.transition.start(ctx, env);
+            self.previous_data = self.transition_state.as_ref().map(|_| old_data.clone());
             ctx.children_changed();
         }
         self.current_widget().update(ctx, data, env)
@@ -87,12 +117,36 @@ impl<T: Data> Widget<T> for Either<T> {
         let size = current_widget.layout(ctx, bc, data, env);
         current_widget.set_origin(ctx, Point::ORIGIN);
         ctx.set_paint_insets(current_widget.paint_insets());
+        ctx.set_baseline_offset(current_widget.baseline_offset());
+
+        if let Some(previous_data) = &self.previous_data {
+            let previous_widget = match self.current {
+                true => &mut self.false_branch,
+                false => &mut self.true_branch,
+            };
+            previous_widget.layout(ctx, bc, previous_data, env);
+            previous_widget.set_origin(ctx, Point::ORIGIN);
+        }
         size
     }
 
     #[instrument(name = "Either", level = "trace", skip(self, ctx, data, env), fields(branch = self.current))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        self.current_widget().paint(ctx, data, env)
+        if let (Some(state), Some(previous_data)) = (&self.transition_state, &self.previous_data) {
+            let current = self.current;
+            let (true_branch, false_branch) = (&mut self.true_branch, &mut self.false_branch);
+            let mut paint_child = |ctx: &mut PaintCtx, new: bool| {
+                let branch_data = if new { data } else { previous_data };
+                if new == current {
+                    true_branch.paint_raw(ctx, branch_data, env);
+                } else {
+                    false_branch.paint_raw(ctx, branch_data, env);
+                }
+            };
+            self.transition.paint(ctx, state, &mut paint_child, env);
+        } else {
+            self.current_widget().paint(ctx, data, env)
+        }
     }
 
     fn debug_state(&self, data: &T) -> DebugState {
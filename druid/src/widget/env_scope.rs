@@ -95,6 +95,7 @@ impl<T: Data, W: Widget<T>> Widget<T> for EnvScope<T, W> {
 
         let size = self.child.layout(ctx, bc, data, &new_env);
         self.child.set_origin(ctx, Point::ORIGIN);
+        ctx.set_baseline_offset(self.child.baseline_offset());
         size
     }
 
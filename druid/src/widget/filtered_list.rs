@@ -0,0 +1,372 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A list that only shows the items matching a query.
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::{List, ListIter};
+use crate::{Data, Lens, LensExt, Point, Selector, WidgetPod};
+
+/// Sent by a `FilteredList` to itself when the number of matches changed, so that it
+/// can report it from `event`.
+const REPORT_MATCHES: Selector = Selector::new("druid-builtin.filtered-list-report-matches");
+
+type Predicate<I> = Box<dyn Fn(&I, &str) -> bool>;
+
+/// The items of a collection that match the query, as seen by the inner [`List`].
+#[derive(Clone)]
+struct Matches<C> {
+    items: C,
+    /// The indices in `items` of the matching items, in ascending order.
+    indices: Arc<Vec<usize>>,
+}
+
+/// A [`List`] that shows only the items of a collection that match a query string.
+///
+/// The query and the collection are both read from the data with lenses, and a
+/// predicate decides whether an item matches the query. The collection itself is
+/// never changed by filtering; items that are edited through the list are written
+/// back to their place in the collection.
+///
+/// When nothing matches, the widget set with [`with_empty`] is shown instead of
+/// the list. Whenever the number of matches changes, the list sends a
+/// [`MATCH_COUNT_CHANGED`] notification.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use druid::widget::{FilteredList, Flex, Label, List, TextBox};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Contacts {
+///     query: String,
+///     names: Arc<Vec<String>>,
+/// }
+///
+/// fn contacts() -> impl Widget<Contacts> {
+///     let list = FilteredList::new(
+///         Contacts::query,
+///         Contacts::names,
+///         List::new(|| Label::dynamic(|name: &String, _| name.clone())),
+///         |name: &String, query| name.to_lowercase().contains(&query.to_lowercase()),
+///     )
+///     .with_empty(Label::new("No contacts found"));
+///
+///     Flex::column()
+///         .with_child(TextBox::new().lens(Contacts::query))
+///         .with_flex_child(list.scroll(), 1.0)
+/// }
+/// ```
+///
+/// [`with_empty`]: FilteredList::with_empty
+/// [`MATCH_COUNT_CHANGED`]: FilteredList::MATCH_COUNT_CHANGED
+pub struct FilteredList<T, I, C, Q, L> {
+    query: Q,
+    items: L,
+    predicate: Predicate<I>,
+    list: WidgetPod<Matches<C>, List<I>>,
+    empty: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+    indices: Arc<Vec<usize>>,
+    /// The number of matches last sent with `MATCH_COUNT_CHANGED`.
+    reported: Option<usize>,
+    phantom: PhantomData<T>,
+}
+
+impl FilteredList<(), (), (), (), ()> {
+    /// Sent by a [`FilteredList`] when the number of items that match the query changes.
+    ///
+    /// This is also sent once the list has been added to the widget tree.
+    pub const MATCH_COUNT_CHANGED: Selector<usize> =
+        Selector::new("druid-builtin.filtered-list-match-count-changed");
+}
+
+impl<T, I, C, Q, L> FilteredList<T, I, C, Q, L>
+where
+    T: Data,
+    I: Data,
+    C: ListIter<I>,
+    Q: Lens<T, String>,
+    L: Lens<T, C>,
+{
+    /// Create a filtered list.
+    ///
+    /// `query` and `items` are lenses to the query string and to the collection, and
+    /// `predicate` is called with each item and the query to decide whether it is shown.
+    pub fn new(
+        query: Q,
+        items: L,
+        list: List<I>,
+        predicate: impl Fn(&I, &str) -> bool + 'static,
+    ) -> Self {
+        FilteredList {
+            query,
+            items,
+            predicate: Box::new(predicate),
+            list: WidgetPod::new(list),
+            empty: None,
+            indices: Arc::new(Vec::new()),
+            reported: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Builder-style method for setting the widget shown when no item matches the query.
+    pub fn with_empty(mut self, empty: impl Widget<T> + 'static) -> Self {
+        self.empty = Some(WidgetPod::new(Box::new(empty)));
+        self
+    }
+
+    /// The number of items that match the query.
+    pub fn match_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Recomputes the matching items, returning `true` if they changed.
+    fn filter(&mut self, data: &T) -> bool {
+        let predicate = &self.predicate;
+        let indices = self.query.with(data, |query| {
+            self.items.with(data, |items| {
+                let mut indices = Vec::new();
+                items.for_each(|item, i| {
+                    if predicate(item, query) {
+                        indices.push(i);
+                    }
+                });
+                indices
+            })
+        });
+        if indices == *self.indices {
+            return false;
+        }
+        self.indices = Arc::new(indices);
+        true
+    }
+
+    fn matches(&self, data: &T) -> Matches<C> {
+        Matches {
+            items: self.items.get(data),
+            indices: self.indices.clone(),
+        }
+    }
+
+    fn showing_empty(&self) -> bool {
+        self.indices.is_empty() && self.empty.is_some()
+    }
+}
+
+impl<C: Data> Data for Matches<C> {
+    fn same(&self, other: &Self) -> bool {
+        self.items.same(&other.items) && self.indices.same(&other.indices)
+    }
+}
+
+impl<I: Data, C: ListIter<I>> ListIter<I> for Matches<C> {
+    fn for_each(&self, mut cb: impl FnMut(&I, usize)) {
+        let mut next = 0;
+        self.items.for_each(|item, i| {
+            if self.indices.get(next) == Some(&i) {
+                cb(item, next);
+                next += 1;
+            }
+        });
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut I, usize)) {
+        let indices = &self.indices;
+        let mut next = 0;
+        self.items.for_each_mut(|item, i| {
+            if indices.get(next) == Some(&i) {
+                cb(item, next);
+                next += 1;
+            }
+        });
+    }
+
+    fn data_len(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+impl<T, I, C, Q, L> Widget<T> for FilteredList<T, I, C, Q, L>
+where
+    T: Data,
+    I: Data,
+    C: ListIter<I>,
+    Q: Lens<T, String>,
+    L: Lens<T, C>,
+{
+    #[instrument(
+        name = "FilteredList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(REPORT_MATCHES) {
+                let count = self.match_count();
+                if self.reported != Some(count) {
+                    self.reported = Some(count);
+                    ctx.submit_notification_without_warning(
+                        FilteredList::MATCH_COUNT_CHANGED.with(count),
+                    );
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        let showing_empty = self.showing_empty();
+        let to_hidden = event.should_propagate_to_hidden();
+        if let Some(empty) = &mut self.empty {
+            if showing_empty || to_hidden {
+                empty.event(ctx, event, data, env);
+            }
+        }
+        if !showing_empty || to_hidden {
+            let indices = self.indices.clone();
+            let list = &mut self.list;
+            self.items.with_mut(data, |items| {
+                let mut matches = Matches {
+                    items: items.clone(),
+                    indices,
+                };
+                list.event(ctx, event, &mut matches, env);
+                if !matches.items.same(items) {
+                    *items = matches.items;
+                }
+            });
+        }
+    }
+
+    #[instrument(
+        name = "FilteredList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.filter(data);
+            ctx.submit_command(REPORT_MATCHES.to(ctx.widget_id()));
+        }
+        if let Some(empty) = &mut self.empty {
+            empty.lifecycle(ctx, event, data, env);
+        }
+        let matches = self.matches(data);
+        self.list.lifecycle(ctx, event, &matches, env);
+    }
+
+    #[instrument(
+        name = "FilteredList",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) {
+            let was_empty = self.showing_empty();
+            if self.filter(data) {
+                ctx.submit_command(REPORT_MATCHES.to(ctx.widget_id()));
+            }
+            if was_empty != self.showing_empty() {
+                ctx.request_layout();
+            }
+        }
+        if let Some(empty) = &mut self.empty {
+            empty.update(ctx, data, env);
+        }
+        let matches = self.matches(data);
+        self.list.update(ctx, &matches, env);
+    }
+
+    #[instrument(name = "FilteredList", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("FilteredList");
+        let matches = self.matches(data);
+        let list_size = self.list.layout(ctx, bc, &matches, env);
+        self.list.set_origin(ctx, Point::ORIGIN);
+
+        let showing_empty = self.showing_empty();
+        let size = match &mut self.empty {
+            Some(empty) => {
+                let empty_size = empty.layout(ctx, bc, data, env);
+                empty.set_origin(ctx, Point::ORIGIN);
+                match showing_empty {
+                    true => empty_size,
+                    false => list_size,
+                }
+            }
+            None => list_size,
+        };
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "FilteredList", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        match &mut self.empty {
+            Some(empty) if self.indices.is_empty() => empty.paint(ctx, data, env),
+            _ => {
+                let matches = self.matches(data);
+                self.list.paint(ctx, &matches, env);
+            }
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let child = match &self.empty {
+            Some(empty) if self.indices.is_empty() => empty.widget().debug_state(data),
+            _ => self.list.widget().debug_state(&self.matches(data)),
+        };
+        DebugState {
+            display_name: "FilteredList".to_string(),
+            main_value: format!("{} matches", self.match_count()),
+            children: vec![child],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_skip_other_items() {
+        let mut matches = Matches {
+            items: Arc::new(vec![10, 11, 12, 13, 14]),
+            indices: Arc::new(vec![1, 3, 4]),
+        };
+        let mut seen = Vec::new();
+        matches.for_each(|item, i| seen.push((*item, i)));
+        assert_eq!(seen, vec![(11, 0), (13, 1), (14, 2)]);
+        assert_eq!(matches.data_len(), 3);
+
+        let items = matches.items.clone();
+        matches.for_each_mut(|item, i| {
+            if i == 1 {
+                *item = 30;
+            }
+        });
+        assert_eq!(*matches.items, vec![10, 11, 12, 30, 14]);
+        assert!(!Arc::ptr_eq(&items, &matches.items));
+    }
+}
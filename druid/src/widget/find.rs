@@ -0,0 +1,306 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Finding and replacing text in text widgets.
+
+use std::ops::Range;
+
+use tracing::instrument;
+
+use crate::text::{EditableText, Selection, TextStorage};
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::Selector;
+
+/// A text widget that a [`FindController`] can search.
+pub trait Findable<T> {
+    /// The current selection, or `None` if it cannot be read right now.
+    fn find_selection(&self) -> Option<Selection>;
+
+    /// Select `selection` of `data`, and scroll it into view.
+    fn find_select(&mut self, ctx: &mut EventCtx, data: &T, selection: Selection);
+
+    /// Highlight `ranges` of the text, replacing any earlier highlights.
+    fn set_find_highlights(&mut self, ranges: Vec<Range<usize>>);
+
+    /// The top of each of `ranges` in the laid-out text, as a fraction of the
+    /// height of the text.
+    fn find_positions(&self, ranges: &[Range<usize>]) -> Vec<f64>;
+}
+
+/// The matches of a [`FindController`], sent with [`FindController::MATCHES_CHANGED`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindMatches {
+    /// The byte ranges of the matches, in order.
+    pub ranges: Vec<Range<usize>>,
+    /// The index in `ranges` of the selected match, if a match is selected.
+    pub current: Option<usize>,
+    /// The top of each match as a fraction of the height of the text, for
+    /// marking the matches alongside a scrollbar.
+    pub positions: Vec<f64>,
+}
+
+/// A [`Controller`] that finds and replaces text in a [`TextBox`] or [`CodeEditor`].
+///
+/// The controller is driven by commands sent to its widget: [`FIND`] sets the
+/// text to look for, and highlights every match; [`FIND_NEXT`] and
+/// [`FIND_PREVIOUS`] select the next or previous match, wrapping around at the
+/// ends; [`REPLACE`] replaces the selected match and selects the next one, and
+/// [`REPLACE_ALL`] replaces every match. Finding an empty string clears the
+/// matches.
+///
+/// Whenever the matches change, or another match is selected, the controller
+/// sends a [`MATCHES_CHANGED`] notification.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{FindController, TextBox};
+/// use druid::{Widget, WidgetExt, WidgetId};
+///
+/// fn searchable_editor(id: WidgetId) -> impl Widget<String> {
+///     TextBox::multiline()
+///         .controller(FindController::new())
+///         .with_id(id)
+/// }
+///
+/// // Later, from a search field:
+/// // ctx.submit_command(FindController::FIND.with("needle".to_string()).to(id));
+/// ```
+///
+/// [`TextBox`]: super::TextBox
+/// [`CodeEditor`]: super::CodeEditor
+/// [`FIND`]: FindController::FIND
+/// [`FIND_NEXT`]: FindController::FIND_NEXT
+/// [`FIND_PREVIOUS`]: FindController::FIND_PREVIOUS
+/// [`REPLACE`]: FindController::REPLACE
+/// [`REPLACE_ALL`]: FindController::REPLACE_ALL
+/// [`MATCHES_CHANGED`]: FindController::MATCHES_CHANGED
+#[derive(Debug, Default)]
+pub struct FindController {
+    query: String,
+    case_sensitive: bool,
+    matches: Vec<Range<usize>>,
+}
+
+impl FindController {
+    /// Find the text in the payload, highlighting every match and selecting
+    /// the first one at or after the selection.
+    pub const FIND: Selector<String> = Selector::new("druid-builtin.find");
+
+    /// Select the next match after the selection.
+    pub const FIND_NEXT: Selector = Selector::new("druid-builtin.find-next");
+
+    /// Select the last match before the selection.
+    pub const FIND_PREVIOUS: Selector = Selector::new("druid-builtin.find-previous");
+
+    /// Replace the selected match with the payload, and select the next match.
+    ///
+    /// If no match is selected, this selects the next one instead.
+    pub const REPLACE: Selector<String> = Selector::new("druid-builtin.find-replace");
+
+    /// Replace every match with the payload.
+    pub const REPLACE_ALL: Selector<String> = Selector::new("druid-builtin.find-replace-all");
+
+    /// Sent by the controller when the matches change, or another is selected.
+    pub const MATCHES_CHANGED: Selector<FindMatches> =
+        Selector::new("druid-builtin.find-matches-changed");
+
+    /// Sent to itself once the text has been laid out after a change.
+    const NOTIFY: Selector = Selector::new("druid-builtin.find-notify");
+
+    /// Create a new `FindController`, which ignores case.
+    pub fn new() -> Self {
+        FindController::default()
+    }
+
+    /// Builder-style method to set whether matches must have the same case
+    /// as the text that is looked for.
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// The byte ranges of the current matches.
+    pub fn matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    fn refresh<T: TextStorage, W: Findable<T>>(&mut self, child: &mut W, data: &T) {
+        self.matches = find_all(data.as_str(), &self.query, self.case_sensitive);
+        child.set_find_highlights(self.matches.clone());
+    }
+
+    /// Select the first match at or after `offset`, or the last before it,
+    /// wrapping around.
+    fn select_match<T, W: Findable<T>>(
+        &self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        data: &T,
+        offset: usize,
+        forward: bool,
+    ) {
+        let found = if forward {
+            self.matches
+                .iter()
+                .find(|m| m.start >= offset)
+                .or_else(|| self.matches.first())
+        } else {
+            self.matches
+                .iter()
+                .rev()
+                .find(|m| m.end <= offset)
+                .or_else(|| self.matches.last())
+        };
+        if let Some(found) = found {
+            child.find_select(ctx, data, Selection::new(found.start, found.end));
+        }
+    }
+
+    /// The index of the match that is selected, if one is.
+    fn current<T, W: Findable<T>>(&self, child: &W) -> Option<usize> {
+        let selection = child.find_selection()?;
+        self.matches.iter().position(|m| *m == selection.range())
+    }
+}
+
+impl<T: TextStorage + EditableText, W: Widget<T> + Findable<T>> Controller<T, W>
+    for FindController
+{
+    #[instrument(
+        name = "FindController",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let cmd = match event {
+            Event::Command(cmd) => cmd,
+            _ => return child.event(ctx, event, data, env),
+        };
+        let selection = child.find_selection();
+        let (start, end) = selection.map_or((0, 0), |s| (s.min(), s.max()));
+        if let Some(query) = cmd.get(Self::FIND) {
+            self.query = query.clone();
+            self.refresh(child, data);
+            self.select_match(child, ctx, data, start, true);
+        } else if cmd.is(Self::FIND_NEXT) {
+            self.select_match(child, ctx, data, end, true);
+        } else if cmd.is(Self::FIND_PREVIOUS) {
+            self.select_match(child, ctx, data, start, false);
+        } else if let Some(replacement) = cmd.get(Self::REPLACE) {
+            match self.current(child) {
+                Some(i) => {
+                    let range = self.matches[i].clone();
+                    data.edit(range.clone(), replacement);
+                    self.refresh(child, data);
+                    let after = range.start + replacement.len();
+                    self.select_match(child, ctx, data, after, true);
+                }
+                None => self.select_match(child, ctx, data, end, true),
+            }
+        } else if let Some(replacement) = cmd.get(Self::REPLACE_ALL) {
+            for range in self.matches.iter().rev() {
+                data.edit(range.clone(), replacement);
+            }
+            self.refresh(child, data);
+        } else if cmd.is(Self::NOTIFY) {
+            let current = self.current(child);
+            let positions = child.find_positions(&self.matches);
+            ctx.submit_notification(Self::MATCHES_CHANGED.with(FindMatches {
+                ranges: self.matches.clone(),
+                current,
+                positions,
+            }));
+            ctx.set_handled();
+            return;
+        } else {
+            return child.event(ctx, event, data, env);
+        }
+        // Positions are only known once the new selection and text are laid out.
+        ctx.submit_command(Self::NOTIFY.to(ctx.widget_id()));
+        ctx.request_paint();
+        ctx.set_handled();
+    }
+
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !self.query.is_empty() && !old_data.same(data) {
+            self.refresh(child, data);
+            ctx.submit_command(Self::NOTIFY.to(ctx.widget_id()));
+            ctx.request_paint();
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+/// The ranges of `text` that match `query`, in order and without overlapping.
+fn find_all(text: &str, query: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    if case_sensitive {
+        return text
+            .match_indices(query)
+            .map(|(i, found)| i..i + found.len())
+            .collect();
+    }
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while let Some(c) = text[start..].chars().next() {
+        match match_len_ignoring_case(&text[start..], query) {
+            Some(len) => {
+                matches.push(start..start + len);
+                start += len;
+            }
+            None => start += c.len_utf8(),
+        }
+    }
+    matches
+}
+
+/// The length of the start of `text` that matches `query`, ignoring case.
+fn match_len_ignoring_case(text: &str, query: &str) -> Option<usize> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    for (i, c) in text.char_indices() {
+        if query.peek().is_none() {
+            return Some(i);
+        }
+        for lower in c.to_lowercase() {
+            if query.next() != Some(lower) {
+                return None;
+            }
+        }
+    }
+    query.peek().is_none().then_some(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_ignoring_case() {
+        let text = "Find the FIND in fInDing; find";
+        assert_eq!(
+            find_all(text, "find", false),
+            vec![0..4, 9..13, 17..21, 26..30]
+        );
+        assert_eq!(find_all(text, "find", true), vec![26..30]);
+        assert!(find_all(text, "", false).is_empty());
+        assert!(find_all(text, "missing", false).is_empty());
+        // a match may not end partway through a character's lowercase form
+        assert_eq!(find_all("\u{130}x", "i", false), Vec::<Range<usize>>::new());
+        assert_eq!(find_all("caf\u{c9}s", "\u{e9}", false), vec![3..5]);
+    }
+}
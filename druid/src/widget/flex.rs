@@ -140,6 +140,7 @@ pub struct Flex<T> {
     cross_alignment: CrossAxisAlignment,
     main_alignment: MainAxisAlignment,
     fill_major_axis: bool,
+    gap: KeyOrValue<f64>,
     children: Vec<Child<T>>,
     old_bc: BoxConstraints,
 }
@@ -375,6 +376,7 @@ impl<T: Data> Flex<T> {
             cross_alignment: CrossAxisAlignment::Center,
             main_alignment: MainAxisAlignment::Start,
             fill_major_axis: false,
+            gap: KeyOrValue::Concrete(0.0),
             old_bc: BoxConstraints::tight(Size::ZERO),
         }
     }
@@ -429,6 +431,18 @@ impl<T: Data> Flex<T> {
         self
     }
 
+    /// Builder-style method for setting the gap between children.
+    ///
+    /// The gap is inserted between each pair of adjacent child widgets, but not
+    /// before the first or after the last one. Spacers are not children for this
+    /// purpose, so a spacer adds to the gap between the children around it.
+    ///
+    /// The default value is `0.0`.
+    pub fn with_gap(mut self, gap: impl Into<KeyOrValue<f64>>) -> Self {
+        self.gap = gap.into();
+        self
+    }
+
     /// Builder-style variant of `add_child`.
     ///
     /// Convenient for assembling a group of widgets in a single expression.
@@ -512,6 +526,15 @@ impl<T: Data> Flex<T> {
         self.main_alignment = alignment;
     }
 
+    /// Set the gap between children.
+    ///
+    /// See [`with_gap`] for more details.
+    ///
+    /// [`with_gap`]: Flex::with_gap
+    pub fn set_gap(&mut self, gap: impl Into<KeyOrValue<f64>>) {
+        self.gap = gap.into();
+    }
+
     /// Set whether the container must expand to fill the available space on
     /// its main axis.
     pub fn set_must_fill_main_axis(&mut self, fill: bool) {
@@ -623,6 +646,24 @@ impl<T: Data> Flex<T> {
         let new_child = Child::FlexedSpacer(flex, 0.0);
         self.children.push(new_child);
     }
+
+    /// The resolved gap between children.
+    fn gap_size(&self, env: &Env) -> f64 {
+        let gap = self.gap.resolve(env);
+        if gap < 0.0 {
+            tracing::warn!("Gap provided to Flex was less than 0");
+        }
+        gap.max(0.0)
+    }
+
+    /// The total size of the gaps between children, if each gap is `gap`.
+    fn total_gap(&self, gap: f64) -> f64 {
+        let widgets = self
+            .children
+            .iter()
+            .filter(|child| child.widget().is_some());
+        gap * widgets.count().saturating_sub(1) as f64
+    }
 }
 
 impl<T: Data> Widget<T> for Flex<T> {
@@ -642,6 +683,9 @@ impl<T: Data> Widget<T> for Flex<T> {
 
     #[instrument(name = "Flex", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if ctx.env_key_changed(&self.gap) {
+            ctx.request_layout();
+        }
         for child in self.children.iter_mut() {
             match child {
                 Child::Fixed { widget, .. } | Child::Flex { widget, .. } => {
@@ -675,7 +719,8 @@ impl<T: Data> Widget<T> for Flex<T> {
         self.old_bc = *bc;
 
         // Measure non-flex children.
-        let mut major_non_flex = 0.0;
+        let gap = self.gap_size(env);
+        let mut major_non_flex = self.total_gap(gap);
         let mut flex_sum = 0.0;
         for child in &mut self.children {
             match child {
@@ -806,6 +851,7 @@ impl<T: Data> Widget<T> for Flex<T> {
 
         let mut major = spacing.next().unwrap_or(0.);
         let mut child_paint_rect = Rect::ZERO;
+        let mut first_widget = true;
 
         for child in &mut self.children {
             match child {
@@ -813,6 +859,9 @@ impl<T: Data> Widget<T> for Flex<T> {
                 | Child::Flex {
                     widget, alignment, ..
                 } => {
+                    if !std::mem::take(&mut first_widget) {
+                        major += gap;
+                    }
                     let child_size = widget.layout_rect().size();
                     let alignment = alignment.unwrap_or(self.cross_alignment);
                     let child_minor_offset = match alignment {
@@ -954,7 +1003,9 @@ impl<T: Data> Widget<T> for Flex<T> {
             // Direction axis and sizing axis are different.
             // We compute max(child dim in cross axis).
             let mut max_size_on_cross_axis: f64 = 0.;
-            let mut available_size_on_main_axis = self.direction.major(bc.max());
+            let gap = self.gap_size(env);
+            let mut available_size_on_main_axis =
+                self.direction.major(bc.max()) - self.total_gap(gap);
             let mut total_flex = 0.;
             for child in self.children.iter_mut() {
                 match child {
@@ -1008,7 +1059,8 @@ impl<T: Data> Widget<T> for Flex<T> {
         } else {
             // Direction axis and sizing axis are same.
             // We compute total(child dim on that axis)
-            let mut total: f64 = 0.;
+            let gap = self.gap_size(env);
+            let mut total: f64 = self.total_gap(gap);
             let mut max_flex_fraction: f64 = 0.;
             let mut total_flex = 0.;
             for child in self.children.iter_mut() {
@@ -1043,13 +1095,53 @@ impl<T: Data> Widget<T> for Flex<T> {
             total + max_flex_fraction * total_flex
         }
     }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        if self.direction != axis {
+            // On the cross axis we need to fit the largest child.
+            let mut max_size_on_cross_axis: f64 = 0.;
+            for child in self.children.iter_mut() {
+                if let Some(widget) = child.widget_mut() {
+                    let s = widget
+                        .widget_mut()
+                        .compute_min_intrinsic(axis, ctx, bc, data, env);
+                    max_size_on_cross_axis = max_size_on_cross_axis.max(s);
+                }
+            }
+            max_size_on_cross_axis
+        } else {
+            // On the main axis every child, flexible or not, needs at least its min
+            // intrinsic size; flexed spacers can shrink to nothing.
+            let gap = self.gap_size(env);
+            let mut total: f64 = self.total_gap(gap);
+            for child in self.children.iter_mut() {
+                match child {
+                    Child::Fixed { widget, .. } | Child::Flex { widget, .. } => {
+                        total += widget
+                            .widget_mut()
+                            .compute_min_intrinsic(axis, ctx, bc, data, env);
+                    }
+                    Child::FixedSpacer(kv, _) => total += kv.resolve(env).max(0.),
+                    Child::FlexedSpacer(..) => (),
+                }
+            }
+            total
+        }
+    }
 }
 
 impl CrossAxisAlignment {
     /// Given the difference between the size of the container and the size
     /// of the child (on their minor axis) return the necessary offset for
     /// this alignment.
-    fn align(self, val: f64) -> f64 {
+    pub(crate) fn align(self, val: f64) -> f64 {
         match self {
             CrossAxisAlignment::Start => 0.0,
             // in vertical layout, baseline is equivalent to center
@@ -1060,7 +1152,7 @@ impl CrossAxisAlignment {
     }
 }
 
-struct Spacing {
+pub(crate) struct Spacing {
     alignment: MainAxisAlignment,
     extra: f64,
     n_children: usize,
@@ -1074,7 +1166,7 @@ impl Spacing {
     /// this returns an iterator of `f64` spacing,
     /// where the first element is the spacing before any children
     /// and all subsequent elements are the spacing after children.
-    fn new(alignment: MainAxisAlignment, extra: f64, n_children: usize) -> Spacing {
+    pub(crate) fn new(alignment: MainAxisAlignment, extra: f64, n_children: usize) -> Spacing {
         let extra = if extra.is_finite() { extra } else { 0. };
         let equal_space = if n_children > 0 {
             match alignment {
@@ -0,0 +1,450 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of the fields of a form.
+
+use std::collections::HashMap;
+use std::thread;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::{Button, WidgetWrapper};
+use crate::{theme, ArcStr, Data, Key, Point, Selector, TextLayout, WidgetExt, WidgetPod};
+
+/// The space between a field and its error message.
+const ERROR_SPACING: f64 = 2.0;
+
+type Validator<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+/// A check that is run on another thread.
+pub type AsyncCheck = Box<dyn FnOnce() -> Result<(), String> + Send>;
+type AsyncValidator<T> = Box<dyn Fn(&T) -> AsyncCheck>;
+type SubmitCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, &Env)>;
+
+/// Sent by a [`FormField`] to itself when it should validate its data.
+const VALIDATE: Selector = Selector::new("druid-builtin.form-field-validate");
+/// Sent by the thread running a [`FormField`]'s asynchronous check, with the
+/// generation of the check and its result.
+const ASYNC_RESULT: Selector<(u64, Result<(), String>)> =
+    Selector::new("druid-builtin.form-field-async-result");
+
+/// Whether the data of a [`FormField`] is valid.
+#[derive(Debug, Clone, PartialEq, Eq, Data)]
+pub enum FieldState {
+    /// Every validator passed.
+    Valid,
+    /// A validator failed with this message.
+    Invalid(String),
+    /// The synchronous validators passed, and an asynchronous check is running.
+    Pending,
+}
+
+/// A widget that contains the [`FormField`]s of a form and tracks whether they
+/// are all valid.
+///
+/// Widgets in a form can read [`Form::IS_VALID`] from the [`Env`], for instance
+/// to enable the submit button, which is what [`Form::submit_button`] does.
+///
+/// The form is submitted with [`Form::SUBMIT`], either as a notification from
+/// inside the form or as a command to it. From then on every field shows its error,
+/// including fields that haven't been edited yet, and if the form is valid the
+/// [`on_submit`] callback is called.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Flex, Form, FormField, TextBox};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Signup {
+///     name: String,
+///     email: String,
+/// }
+///
+/// fn signup() -> impl Widget<Signup> {
+///     let name = FormField::new(TextBox::new().lens(Signup::name))
+///         .validate(|data: &Signup| match data.name.is_empty() {
+///             true => Err("Enter your name".into()),
+///             false => Ok(()),
+///         });
+///     let email = FormField::new(TextBox::new().lens(Signup::email))
+///         .validate(|data: &Signup| match data.email.contains('@') {
+///             true => Ok(()),
+///             false => Err("Enter an email address".into()),
+///         });
+///     let fields = Flex::column()
+///         .with_child(name)
+///         .with_child(email)
+///         .with_child(Form::submit_button("Sign up"));
+///     Form::new(fields).on_submit(|_ctx, data, _env| println!("signing up {}", data.name))
+/// }
+/// ```
+///
+/// [`on_submit`]: Form::on_submit
+pub struct Form<T, W> {
+    child: WidgetPod<T, W>,
+    /// Whether each field that has reported to the form is valid.
+    fields: HashMap<WidgetId, bool>,
+    show_errors: bool,
+    on_submit: Option<SubmitCallback<T>>,
+}
+
+/// A widget that validates the data of a form field, and shows the error below it.
+///
+/// Validators are closures that get the data of the field, which is usually the
+/// data of the whole form, and return an error message if it isn't valid. They run
+/// whenever the data changes, in the order they were added, until one fails. After
+/// that, a check that is too slow to run on the UI thread, such as asking a server
+/// whether a user name is taken, can run on another thread.
+///
+/// The error is shown once the data has been changed, or once the enclosing
+/// [`Form`] has been submitted. Its color and size come from
+/// [`theme::FORM_ERROR_COLOR`] and [`theme::FORM_ERROR_TEXT_SIZE`].
+///
+/// [`theme::FORM_ERROR_COLOR`]: crate::theme::FORM_ERROR_COLOR
+/// [`theme::FORM_ERROR_TEXT_SIZE`]: crate::theme::FORM_ERROR_TEXT_SIZE
+pub struct FormField<T, W> {
+    child: WidgetPod<T, W>,
+    validators: Vec<Validator<T>>,
+    async_validator: Option<AsyncValidator<T>>,
+    state: FieldState,
+    /// Increased on every validation, so that results of outdated checks are ignored.
+    generation: u64,
+    /// Whether the state has been sent to the form.
+    reported: bool,
+    touched: bool,
+    error: TextLayout<ArcStr>,
+}
+
+impl Form<(), ()> {
+    /// Submit the form.
+    pub const SUBMIT: Selector = Selector::new("druid-builtin.form-submit");
+
+    /// Sent by a [`FormField`] to its form when its state changes.
+    pub const FIELD_STATE: Selector<FieldState> = Selector::new("druid-builtin.form-field-state");
+
+    /// Whether every field in the form is valid.
+    pub const IS_VALID: Key<bool> = Key::new("org.linebender.druid.widget.form.is_valid");
+
+    /// Whether fields show their errors before they have been edited, which they do
+    /// once the form has been submitted.
+    pub const SHOW_ERRORS: Key<bool> = Key::new("org.linebender.druid.widget.form.show_errors");
+
+    /// A button that submits the form, and is disabled while the form isn't valid.
+    pub fn submit_button<T: Data>(label: impl Into<crate::widget::LabelText<T>>) -> impl Widget<T> {
+        Button::new(label)
+            .on_click(|ctx, _, _| ctx.submit_notification(Form::SUBMIT))
+            .disabled_if(|_, env| !env.try_get(Form::IS_VALID).unwrap_or(true))
+    }
+}
+
+impl<T: Data, W: Widget<T>> Form<T, W> {
+    /// Create a form around `child`, which contains its fields.
+    pub fn new(child: W) -> Self {
+        Form {
+            child: WidgetPod::new(child),
+            fields: HashMap::new(),
+            show_errors: false,
+            on_submit: None,
+        }
+    }
+
+    /// Builder-style method for a callback that is called when the form is
+    /// submitted while it is valid.
+    pub fn on_submit(mut self, f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        self.on_submit = Some(Box::new(f));
+        self
+    }
+
+    /// Whether every field in the form is valid.
+    pub fn is_valid(&self) -> bool {
+        self.fields.values().all(|valid| *valid)
+    }
+
+    fn child_env(&self, env: &Env) -> Env {
+        env.clone()
+            .adding(Form::IS_VALID, self.is_valid())
+            .adding(Form::SHOW_ERRORS, self.show_errors)
+    }
+
+    fn submit(&mut self, ctx: &mut EventCtx, data: &mut T, env: &Env) {
+        if !self.show_errors {
+            self.show_errors = true;
+            ctx.request_update();
+        }
+        if self.is_valid() {
+            if let Some(on_submit) = &self.on_submit {
+                (on_submit)(ctx, data, env);
+            }
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Form<T, W> {
+    #[instrument(name = "Form", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Notification(note) => {
+                if let Some(state) = note.get(Form::FIELD_STATE) {
+                    let was_valid = self.is_valid();
+                    let valid = matches!(state, FieldState::Valid);
+                    self.fields.insert(note.source(), valid);
+                    if self.is_valid() != was_valid {
+                        trace!("form validity changed to {}", self.is_valid());
+                        ctx.request_update();
+                    }
+                    ctx.set_handled();
+                } else if note.is(Form::SUBMIT) {
+                    self.submit(ctx, data, env);
+                    ctx.set_handled();
+                }
+                return;
+            }
+            Event::Command(cmd) if cmd.is(Form::SUBMIT) => {
+                self.submit(ctx, data, env);
+                ctx.set_handled();
+                return;
+            }
+            _ => {}
+        }
+        let env = self.child_env(env);
+        self.child.event(ctx, event, data, &env);
+    }
+
+    #[instrument(name = "Form", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let env = self.child_env(env);
+        self.child.lifecycle(ctx, event, data, &env);
+    }
+
+    #[instrument(name = "Form", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let env = self.child_env(env);
+        self.child.update(ctx, data, &env);
+    }
+
+    #[instrument(name = "Form", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Form");
+        let env = self.child_env(env);
+        let size = self.child.layout(ctx, bc, data, &env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "Form", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let env = self.child_env(env);
+        self.child.paint(ctx, data, &env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: if self.is_valid() { "valid" } else { "invalid" }.to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, W: Widget<T>> WidgetWrapper for Form<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
+
+impl<T: Data, W: Widget<T>> FormField<T, W> {
+    /// Create a field around `child`, which edits the field's data.
+    pub fn new(child: W) -> Self {
+        let mut error = TextLayout::new();
+        error.set_text_color(theme::FORM_ERROR_COLOR);
+        error.set_text_size(theme::FORM_ERROR_TEXT_SIZE);
+        FormField {
+            child: WidgetPod::new(child),
+            validators: Vec::new(),
+            async_validator: None,
+            state: FieldState::Valid,
+            generation: 0,
+            reported: false,
+            touched: false,
+            error,
+        }
+    }
+
+    /// Builder-style method for adding a validator.
+    pub fn validate(mut self, f: impl Fn(&T) -> Result<(), String> + 'static) -> Self {
+        self.validators.push(Box::new(f));
+        self
+    }
+
+    /// Builder-style method for setting a check that runs on another thread.
+    ///
+    /// `f` is called on the UI thread, after the other validators have passed, and
+    /// returns the check to run. While it runs, the field is [`FieldState::Pending`],
+    /// which the form counts as not valid.
+    pub fn validate_async(mut self, f: impl Fn(&T) -> AsyncCheck + 'static) -> Self {
+        self.async_validator = Some(Box::new(f));
+        self
+    }
+
+    /// Whether the field's data is valid.
+    pub fn state(&self) -> &FieldState {
+        &self.state
+    }
+
+    fn validate_now(&mut self, ctx: &mut EventCtx, data: &T) {
+        self.generation += 1;
+        let failure = self
+            .validators
+            .iter()
+            .find_map(|validator| validator(data).err());
+        let state = match (failure, &self.async_validator) {
+            (Some(message), _) => FieldState::Invalid(message),
+            (None, Some(async_validator)) => {
+                let check = async_validator(data);
+                let sink = ctx.get_external_handle();
+                let id = ctx.widget_id();
+                let generation = self.generation;
+                thread::spawn(move || {
+                    let result = check();
+                    let _ = sink.submit_command(ASYNC_RESULT, (generation, result), id);
+                });
+                FieldState::Pending
+            }
+            (None, None) => FieldState::Valid,
+        };
+        self.set_state(ctx, state);
+    }
+
+    fn set_state(&mut self, ctx: &mut EventCtx, state: FieldState) {
+        if self.reported && state == self.state {
+            return;
+        }
+        self.reported = true;
+        self.state = state.clone();
+        ctx.submit_notification(Form::FIELD_STATE.with(state));
+        ctx.request_layout();
+    }
+
+    fn shown_error(&self, env: &Env) -> Option<&str> {
+        match &self.state {
+            FieldState::Invalid(message)
+                if self.touched || env.try_get(Form::SHOW_ERRORS).unwrap_or(false) =>
+            {
+                Some(message)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for FormField<T, W> {
+    #[instrument(name = "FormField", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(VALIDATE) {
+                self.validate_now(ctx, data);
+                ctx.set_handled();
+                return;
+            }
+            if let Some((generation, result)) = cmd.get(ASYNC_RESULT) {
+                if *generation == self.generation {
+                    let state = match result {
+                        Ok(()) => FieldState::Valid,
+                        Err(message) => FieldState::Invalid(message.clone()),
+                    };
+                    self.set_state(ctx, state);
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "FormField", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            // Validation reports to the form, which can only be done from `event`.
+            ctx.submit_command(VALIDATE.to(ctx.widget_id()));
+        }
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "FormField",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) {
+            self.touched = true;
+            ctx.submit_command(VALIDATE.to(ctx.widget_id()));
+        }
+        if ctx.env_key_changed(&Form::SHOW_ERRORS) || self.error.needs_rebuild_after_update(ctx) {
+            ctx.request_layout();
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "FormField", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("FormField");
+        let error = self.shown_error(env).map(ArcStr::from);
+        let error_size = match error {
+            Some(message) => {
+                self.error.set_text(message);
+                self.error.set_wrap_width(bc.max().width);
+                self.error.rebuild_if_needed(ctx.text(), env);
+                let size = self.error.size();
+                Size::new(size.width, size.height + ERROR_SPACING)
+            }
+            None => Size::ZERO,
+        };
+
+        let child_bc = bc.shrink((0.0, error_size.height));
+        let child_size = self.child.layout(ctx, &child_bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+
+        let size = bc.constrain(Size::new(
+            child_size.width.max(error_size.width),
+            child_size.height + error_size.height,
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "FormField", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if self.shown_error(env).is_some() {
+            let y = self.child.layout_rect().height() + ERROR_SPACING;
+            self.error.draw(ctx, (0.0, y));
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("{:?}", self.state),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, W: Widget<T>> WidgetWrapper for FormField<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
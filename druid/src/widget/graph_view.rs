@@ -0,0 +1,840 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that lays out and draws a graph of nodes and edges.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{Affine, BezPath, Circle, Line, Vec2};
+use crate::widget::prelude::*;
+use crate::{theme, Data, MouseEvent, Point, Rect, Selector, WidgetPod};
+
+/// The vertical space between the layers of a [`GraphLayout::Layered`] layout.
+const LAYER_SPACING: f64 = 48.0;
+/// The horizontal space between nodes in a layer.
+const NODE_SPACING: f64 = 24.0;
+/// The preferred length of edges in a [`GraphLayout::ForceDirected`] layout.
+const EDGE_LENGTH: f64 = 120.0;
+const FORCE_ITERATIONS: usize = 300;
+/// The widest a node's widget can be.
+const MAX_NODE_WIDTH: f64 = 240.0;
+const TOGGLE_RADIUS: f64 = 6.0;
+const MIN_ZOOM: f64 = 0.1;
+const MAX_ZOOM: f64 = 4.0;
+
+/// The nodes and edges shown by a [`GraphView`].
+///
+/// Edges are pairs of indices into `nodes`, going from the first to the second.
+#[derive(Clone, Data)]
+pub struct Graph<N> {
+    /// The nodes.
+    pub nodes: Arc<Vec<N>>,
+    /// The edges, as `(from, to)` indices of nodes.
+    pub edges: Arc<Vec<(usize, usize)>>,
+}
+
+/// How a [`GraphView`] arranges its nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum GraphLayout {
+    /// Nodes are placed in rows, with each node below the nodes it has edges from,
+    /// like an org chart or a tree.
+    Layered,
+    /// Nodes are placed by simulating edges as springs and nodes as repelling
+    /// each other, like a mind map.
+    ForceDirected,
+}
+
+type SelectCallback<N> = Box<dyn Fn(&mut EventCtx, &mut Graph<N>, Option<usize>, &Env)>;
+
+/// A widget that shows a [`Graph`], arranging its nodes automatically.
+///
+/// Each node is shown by its own widget, created by the closure passed to
+/// [`GraphView::new`], and edges are drawn between them. The view can be panned
+/// by dragging the background or with the scroll wheel, and zoomed with the scroll
+/// wheel while holding control.
+///
+/// Nodes with outgoing edges have a toggle below them that collapses them, hiding
+/// the nodes that can only be reached through them. Clicking a node selects it,
+/// and the [`on_select`] callback is called with its index.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Graph, GraphLayout, GraphView, Label};
+/// use druid::{Widget, WidgetExt};
+///
+/// fn org_chart() -> impl Widget<Graph<String>> {
+///     GraphView::new(|| Label::raw().padding(8.0).border(druid::theme::BORDER_LIGHT, 1.0))
+///         .with_layout(GraphLayout::Layered)
+///         .on_select(|_ctx, graph, node, _env| {
+///             if let Some(node) = node {
+///                 println!("selected {}", graph.nodes[node]);
+///             }
+///         })
+/// }
+///
+/// let data = Graph::new(
+///     vec!["CEO".to_string(), "CTO".to_string(), "CFO".to_string()],
+///     vec![(0, 1), (0, 2)],
+/// );
+/// ```
+///
+/// [`on_select`]: GraphView::on_select
+pub struct GraphView<N> {
+    closure: Box<dyn Fn() -> Box<dyn Widget<N>>>,
+    children: Vec<WidgetPod<N, Box<dyn Widget<N>>>>,
+    layout: GraphLayout,
+    collapsed: HashSet<usize>,
+    visible: Vec<bool>,
+    /// The centers of the nodes, in graph coordinates.
+    positions: Vec<Point>,
+    /// The sizes of the nodes when they were last arranged.
+    sizes: Vec<Option<Size>>,
+    selected: Option<usize>,
+    on_select: Option<SelectCallback<N>>,
+    /// The position of the graph's origin in the widget.
+    pan: Vec2,
+    zoom: f64,
+    /// Whether the graph should be centered in the view after its next layout.
+    fit: bool,
+    /// The last mouse position while panning.
+    drag: Option<Point>,
+}
+
+impl<N> Graph<N> {
+    /// Create a graph from its nodes and edges.
+    pub fn new(nodes: impl Into<Arc<Vec<N>>>, edges: impl Into<Arc<Vec<(usize, usize)>>>) -> Self {
+        Graph {
+            nodes: nodes.into(),
+            edges: edges.into(),
+        }
+    }
+}
+
+impl GraphView<()> {
+    /// Center the graph in the view, and reset the zoom.
+    pub const FIT: Selector = Selector::new("druid-builtin.graph-view-fit");
+
+    /// Collapse the node with this index, or expand it if it is collapsed.
+    pub const TOGGLE_COLLAPSED: Selector<usize> =
+        Selector::new("druid-builtin.graph-view-toggle-collapsed");
+}
+
+impl<N: Data> GraphView<N> {
+    /// Create a view that shows each node with a widget created by `closure`.
+    pub fn new<W: Widget<N> + 'static>(closure: impl Fn() -> W + 'static) -> Self {
+        GraphView {
+            closure: Box::new(move || Box::new(closure())),
+            children: Vec::new(),
+            layout: GraphLayout::Layered,
+            collapsed: HashSet::new(),
+            visible: Vec::new(),
+            positions: Vec::new(),
+            sizes: Vec::new(),
+            selected: None,
+            on_select: None,
+            pan: Vec2::ZERO,
+            zoom: 1.0,
+            fit: true,
+            drag: None,
+        }
+    }
+
+    /// Builder-style method for setting how nodes are arranged.
+    pub fn with_layout(mut self, layout: GraphLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Builder-style method for a callback that is called when a node is clicked,
+    /// with its index, or when the background is clicked, with `None`.
+    pub fn on_select(
+        mut self,
+        f: impl Fn(&mut EventCtx, &mut Graph<N>, Option<usize>, &Env) + 'static,
+    ) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    /// The index of the selected node.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Whether the node with this index is collapsed.
+    pub fn is_collapsed(&self, node: usize) -> bool {
+        self.collapsed.contains(&node)
+    }
+
+    fn update_child_count(&mut self, data: &Graph<N>) -> bool {
+        let len = data.nodes.len();
+        if self.children.len() == len {
+            return false;
+        }
+        self.children.truncate(len);
+        while self.children.len() < len {
+            self.children.push(WidgetPod::new((self.closure)()));
+        }
+        self.collapsed.retain(|node| *node < len);
+        if matches!(self.selected, Some(node) if node >= len) {
+            self.selected = None;
+        }
+        true
+    }
+
+    fn update_visible(&mut self, data: &Graph<N>) {
+        self.visible = visible_nodes(data.nodes.len(), &data.edges, &self.collapsed);
+    }
+
+    fn has_children(&self, data: &Graph<N>, node: usize) -> bool {
+        data.edges
+            .iter()
+            .any(|(from, to)| *from == node && *to != node)
+    }
+
+    fn toggle_center(&self, node: usize) -> Point {
+        let rect = self.children[node].layout_rect();
+        Point::new(rect.center().x, rect.y1 + TOGGLE_RADIUS + 2.0)
+    }
+
+    fn to_graph(&self, pos: Point) -> Point {
+        ((pos.to_vec2() - self.pan) / self.zoom).to_point()
+    }
+
+    /// The event in graph coordinates, for the nodes.
+    fn graph_event(&self, event: &Event) -> Event {
+        let transform = |mouse: &MouseEvent| MouseEvent {
+            pos: self.to_graph(mouse.pos),
+            ..mouse.clone()
+        };
+        match event {
+            Event::MouseDown(mouse) => Event::MouseDown(transform(mouse)),
+            Event::MouseUp(mouse) => Event::MouseUp(transform(mouse)),
+            Event::MouseMove(mouse) => Event::MouseMove(transform(mouse)),
+            Event::Wheel(mouse) => Event::Wheel(transform(mouse)),
+            _ => event.clone(),
+        }
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, data: &mut Graph<N>, node: Option<usize>, env: &Env) {
+        if self.selected != node {
+            self.selected = node;
+            ctx.request_paint();
+        }
+        if let Some(on_select) = &self.on_select {
+            on_select(ctx, data, node, env);
+        }
+    }
+
+    fn toggle_collapsed(&mut self, ctx: &mut EventCtx, data: &Graph<N>, node: usize) {
+        if !self.collapsed.remove(&node) {
+            self.collapsed.insert(node);
+        }
+        self.update_visible(data);
+        if matches!(self.selected, Some(node) if !self.visible[node]) {
+            self.selected = None;
+        }
+        self.sizes.clear();
+        ctx.request_layout();
+    }
+
+    fn zoom_around(&mut self, pos: Point, zoom: f64) {
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        let graph_pos = self.to_graph(pos);
+        self.zoom = zoom;
+        self.pan = pos.to_vec2() - graph_pos.to_vec2() * zoom;
+    }
+
+    /// The bounds of the visible nodes, in graph coordinates.
+    fn bounds(&self) -> Option<Rect> {
+        self.children
+            .iter()
+            .zip(&self.visible)
+            .filter(|(_, visible)| **visible)
+            .map(|(child, _)| child.layout_rect())
+            .reduce(|a, b| a.union(b))
+    }
+}
+
+impl<N: Data> Widget<Graph<N>> for GraphView<N> {
+    #[instrument(name = "GraphView", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Graph<N>, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(GraphView::FIT) {
+                self.fit = true;
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+            if let Some(node) = cmd.get(GraphView::TOGGLE_COLLAPSED) {
+                if *node < self.children.len() {
+                    self.toggle_collapsed(ctx, data, *node);
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        let graph_event = self.graph_event(event);
+        let propagate_to_hidden = event.should_propagate_to_hidden();
+        let mut nodes = data.nodes.clone();
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if !propagate_to_hidden && !self.visible.get(i).copied().unwrap_or(false) {
+                continue;
+            }
+            let mut node = nodes[i].clone();
+            child.event(ctx, &graph_event, &mut node, env);
+            if !node.same(&nodes[i]) {
+                Arc::make_mut(&mut nodes)[i] = node;
+            }
+        }
+        if !nodes.same(&data.nodes) {
+            data.nodes = nodes;
+        }
+        // Nodes are drawn scaled, so the areas they invalidate can't be trusted.
+        if matches!(
+            event,
+            Event::MouseMove(_) | Event::MouseDown(_) | Event::MouseUp(_)
+        ) {
+            ctx.request_paint();
+        }
+
+        match event {
+            Event::MouseDown(mouse) => {
+                let pos = self.to_graph(mouse.pos);
+                let toggle = (0..self.children.len()).find(|node| {
+                    self.visible[*node]
+                        && self.has_children(data, *node)
+                        && self.toggle_center(*node).distance(pos) <= TOGGLE_RADIUS
+                });
+                if let Some(node) = toggle {
+                    self.toggle_collapsed(ctx, data, node);
+                    ctx.set_handled();
+                    return;
+                }
+                let hit = (0..self.children.len()).rev().find(|node| {
+                    self.visible[*node] && self.children[*node].layout_rect().contains(pos)
+                });
+                self.select(ctx, data, hit, env);
+                if hit.is_none() && !ctx.is_handled() {
+                    self.drag = Some(mouse.pos);
+                    ctx.set_active(true);
+                    ctx.set_handled();
+                }
+            }
+            Event::MouseMove(mouse) => {
+                if let Some(last) = self.drag {
+                    self.pan += mouse.pos - last;
+                    self.drag = Some(mouse.pos);
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+            }
+            Event::MouseUp(_) if self.drag.is_some() => {
+                self.drag = None;
+                ctx.set_active(false);
+                ctx.set_handled();
+            }
+            Event::Wheel(mouse) if !ctx.is_handled() => {
+                if mouse.mods.ctrl() {
+                    let zoom = self.zoom * (-mouse.wheel_delta.y / 400.0).exp();
+                    self.zoom_around(mouse.pos, zoom);
+                } else {
+                    self.pan -= mouse.wheel_delta;
+                }
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    #[instrument(name = "GraphView", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Graph<N>, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.update_child_count(data);
+            self.update_visible(data);
+        }
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if event.should_propagate_to_hidden() || self.visible[i] {
+                child.lifecycle(ctx, event, &data.nodes[i], env);
+            }
+        }
+    }
+
+    #[instrument(
+        name = "GraphView",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Graph<N>, data: &Graph<N>, env: &Env) {
+        // Update the existing nodes before adding new ones, which haven't been
+        // added to the widget tree yet.
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let (Some(node), true) = (data.nodes.get(i), self.visible[i]) {
+                child.update(ctx, node, env);
+            }
+        }
+        if self.update_child_count(data) {
+            ctx.children_changed();
+        }
+        if !old_data.edges.same(&data.edges) || self.visible.len() != data.nodes.len() {
+            self.update_visible(data);
+            self.sizes.clear();
+            ctx.request_layout();
+        }
+        ctx.request_paint();
+    }
+
+    #[instrument(name = "GraphView", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Graph<N>,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("GraphView");
+        let child_bc = BoxConstraints::new(Size::ZERO, Size::new(MAX_NODE_WIDTH, f64::INFINITY));
+        let mut sizes = Vec::with_capacity(self.children.len());
+        for (i, child) in self.children.iter_mut().enumerate() {
+            sizes.push(match self.visible[i] {
+                true => Some(child.layout(ctx, &child_bc, &data.nodes[i], env)),
+                false => None,
+            });
+        }
+        if sizes != self.sizes {
+            trace!("arranging {} nodes", sizes.len());
+            self.positions = match self.layout {
+                GraphLayout::Layered => layered_layout(&sizes, &data.edges),
+                GraphLayout::ForceDirected => force_directed_layout(&sizes, &data.edges),
+            };
+            self.sizes = sizes;
+        }
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let Some(size) = self.sizes[i] {
+                let origin = self.positions[i] - size.to_vec2() / 2.0;
+                child.set_origin(ctx, origin);
+            }
+        }
+
+        let bounds = self.bounds().unwrap_or(Rect::ZERO);
+        let size = bc.constrain(bounds.size());
+        if self.fit && !self.children.is_empty() {
+            self.fit = false;
+            self.zoom = 1.0;
+            self.pan = size.to_rect().center() - bounds.center();
+        }
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "GraphView", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Graph<N>, env: &Env) {
+        let clip = ctx.size().to_rect();
+        ctx.clip(clip);
+        ctx.transform(Affine::translate(self.pan) * Affine::scale(self.zoom));
+
+        let edge_color = env.get(theme::BORDER_LIGHT);
+        for &(from, to) in data.edges.iter() {
+            let visible = |node: usize| self.visible.get(node).copied().unwrap_or(false);
+            if from == to || !visible(from) || !visible(to) {
+                continue;
+            }
+            let from_rect = self.children[from].layout_rect();
+            let to_rect = self.children[to].layout_rect();
+            match self.layout {
+                GraphLayout::Layered => {
+                    let start = Point::new(from_rect.center().x, from_rect.y1);
+                    let end = Point::new(to_rect.center().x, to_rect.y0);
+                    let middle = (start.y + end.y) / 2.0;
+                    let mut path = BezPath::new();
+                    path.move_to(start);
+                    path.curve_to(Point::new(start.x, middle), Point::new(end.x, middle), end);
+                    ctx.stroke(path, &edge_color, 1.5);
+                }
+                GraphLayout::ForceDirected => {
+                    let line = Line::new(from_rect.center(), to_rect.center());
+                    ctx.stroke(line, &edge_color, 1.5);
+                }
+            }
+        }
+
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if self.visible[i] {
+                child.paint(ctx, &data.nodes[i], env);
+            }
+        }
+
+        if let Some(selected) = self.selected {
+            let rect = self.children[selected].layout_rect().inset(2.0);
+            ctx.paint_selection_decoration(rect.to_rounded_rect(4.0), env);
+        }
+
+        let background = env.get(theme::BACKGROUND_LIGHT);
+        let foreground = env.get(theme::TEXT_COLOR);
+        for node in 0..self.children.len() {
+            if !self.visible[node] || !self.has_children(data, node) {
+                continue;
+            }
+            let center = self.toggle_center(node);
+            let circle = Circle::new(center, TOGGLE_RADIUS);
+            ctx.fill(circle, &background);
+            ctx.stroke(circle, &edge_color, 1.0);
+            let arm = TOGGLE_RADIUS - 2.5;
+            ctx.stroke(
+                Line::new(center - (arm, 0.0), center + (arm, 0.0)),
+                &foreground,
+                1.0,
+            );
+            if self.collapsed.contains(&node) {
+                ctx.stroke(
+                    Line::new(center - (0.0, arm), center + (0.0, arm)),
+                    &foreground,
+                    1.0,
+                );
+            }
+        }
+    }
+
+    fn debug_state(&self, data: &Graph<N>) -> DebugState {
+        let children = self
+            .children
+            .iter()
+            .zip(data.nodes.iter())
+            .map(|(child, node)| child.widget().debug_state(node))
+            .collect();
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+/// Which nodes are shown: the ones that can be reached from a root without going
+/// through a collapsed node.
+///
+/// Roots are nodes without incoming edges, and, for nodes that can't be reached
+/// from those because they are part of a cycle, the first node of the cycle.
+fn visible_nodes(count: usize, edges: &[(usize, usize)], collapsed: &HashSet<usize>) -> Vec<bool> {
+    let edges = valid_edges(count, edges);
+    let mut has_parent = vec![false; count];
+    for (_, to) in &edges {
+        has_parent[*to] = true;
+    }
+    let mut reached = vec![false; count];
+    let mut roots = Vec::new();
+    let candidates = (0..count).filter(|node| !has_parent[*node]).chain(0..count);
+    for node in candidates {
+        if !reached[node] {
+            roots.push(node);
+            traverse(node, &edges, |_| false, &mut reached);
+        }
+    }
+
+    let mut visible = vec![false; count];
+    for root in roots {
+        traverse(root, &edges, |node| collapsed.contains(&node), &mut visible);
+    }
+    visible
+}
+
+/// The edges between existing nodes, without loops.
+fn valid_edges(count: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    edges
+        .iter()
+        .copied()
+        .filter(|(from, to)| *from < count && *to < count && from != to)
+        .collect()
+}
+
+/// Mark the nodes that can be reached from `start`, not following the edges out of
+/// nodes for which `stop` returns `true`.
+fn traverse(
+    start: usize,
+    edges: &[(usize, usize)],
+    stop: impl Fn(usize) -> bool,
+    seen: &mut [bool],
+) {
+    if seen[start] {
+        return;
+    }
+    seen[start] = true;
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        if stop(node) {
+            continue;
+        }
+        for (from, to) in edges {
+            if *from == node && !seen[*to] {
+                seen[*to] = true;
+                stack.push(*to);
+            }
+        }
+    }
+}
+
+/// Arrange nodes in layers, each node below the nodes it has edges from, and
+/// return their centers. Hidden nodes have no size.
+fn layered_layout(sizes: &[Option<Size>], edges: &[(usize, usize)]) -> Vec<Point> {
+    let count = sizes.len();
+    let edges: Vec<_> = valid_edges(count, edges)
+        .into_iter()
+        .filter(|(from, to)| sizes[*from].is_some() && sizes[*to].is_some())
+        .collect();
+
+    // Longest path layering over the edges that don't close a cycle, found by a
+    // depth-first search.
+    let mut layer = vec![0; count];
+    let mut state = vec![0u8; count]; // 0: unvisited, 1: on the stack, 2: done
+    let mut order = Vec::with_capacity(count);
+    for start in 0..count {
+        if state[start] != 0 || sizes[start].is_none() {
+            continue;
+        }
+        let mut stack = vec![(start, 0)];
+        state[start] = 1;
+        while let Some((node, next_edge)) = stack.pop() {
+            match edges[next_edge..]
+                .iter()
+                .position(|(from, _)| *from == node)
+            {
+                Some(offset) => {
+                    let edge = next_edge + offset;
+                    stack.push((node, edge + 1));
+                    let to = edges[edge].1;
+                    if state[to] == 0 {
+                        state[to] = 1;
+                        stack.push((to, 0));
+                    }
+                }
+                None => {
+                    state[node] = 2;
+                    order.push(node);
+                }
+            }
+        }
+    }
+    // `order` is a post-order, so reversing it puts parents first, except across
+    // the edges that close cycles, which are ignored by only moving nodes down.
+    let position: Vec<_> = {
+        let mut position = vec![0; count];
+        for (i, node) in order.iter().rev().enumerate() {
+            position[*node] = i;
+        }
+        position
+    };
+    for &node in order.iter().rev() {
+        for (from, to) in &edges {
+            if *from == node && position[*to] > position[node] {
+                layer[*to] = layer[*to].max(layer[node] + 1);
+            }
+        }
+    }
+
+    let layer_count = order.iter().map(|node| layer[*node] + 1).max().unwrap_or(0);
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for node in 0..count {
+        if sizes[node].is_some() {
+            layers[layer[node]].push(node);
+        }
+    }
+    // Order each layer by the average position of the nodes' parents, to reduce
+    // crossings.
+    let mut rank = vec![0.0; count];
+    for (i, node) in layers.first().into_iter().flatten().enumerate() {
+        rank[*node] = i as f64;
+    }
+    for nodes in layers.iter_mut().skip(1) {
+        for &node in nodes.iter() {
+            let parents: Vec<f64> = edges
+                .iter()
+                .filter(|(from, to)| *to == node && layer[*from] + 1 == layer[node])
+                .map(|(from, _)| rank[*from])
+                .collect();
+            rank[node] = match parents.is_empty() {
+                true => f64::INFINITY,
+                false => parents.iter().sum::<f64>() / parents.len() as f64,
+            };
+        }
+        nodes.sort_by(|a, b| rank[*a].total_cmp(&rank[*b]));
+        for (i, node) in nodes.iter().enumerate() {
+            rank[*node] = i as f64;
+        }
+    }
+
+    // The first layer is centered on zero, and the nodes of the others are placed
+    // below their parents, as far as they can be without overlapping.
+    let mut positions = vec![Point::ZERO; count];
+    let mut y = 0.0;
+    for (l, nodes) in layers.iter().enumerate() {
+        let width = |node: usize| sizes[node].unwrap_or_default().width;
+        let height = nodes
+            .iter()
+            .map(|node| sizes[*node].unwrap_or_default().height)
+            .fold(0.0, f64::max);
+        let total_width: f64 = nodes
+            .iter()
+            .map(|node| width(*node) + NODE_SPACING)
+            .sum::<f64>()
+            - NODE_SPACING;
+        let mut right = -total_width / 2.0 - NODE_SPACING;
+        let mut offset = 0.0;
+        for &node in nodes {
+            let half = width(node) / 2.0;
+            let parents: Vec<f64> = edges
+                .iter()
+                .filter(|(from, to)| *to == node && l > 0 && layer[*from] + 1 == l)
+                .map(|(from, _)| positions[*from].x)
+                .collect();
+            let earliest = right + NODE_SPACING + half;
+            let wanted = match parents.is_empty() {
+                true => earliest,
+                false => parents.iter().sum::<f64>() / parents.len() as f64,
+            };
+            let x = wanted.max(earliest);
+            offset += wanted - x;
+            positions[node] = Point::new(x, y + height / 2.0);
+            right = x + half;
+        }
+        // Pushing nodes apart moves them right, so move the layer back.
+        let offset = offset / nodes.len().max(1) as f64;
+        for &node in nodes {
+            positions[node].x += offset;
+        }
+        y += height + LAYER_SPACING;
+    }
+    positions
+}
+
+/// Arrange nodes by simulating forces between them, and return their centers.
+/// Hidden nodes have no size.
+///
+/// This is the Fruchterman-Reingold algorithm, starting with the nodes on a
+/// circle, so the result only depends on the graph.
+fn force_directed_layout(sizes: &[Option<Size>], edges: &[(usize, usize)]) -> Vec<Point> {
+    let count = sizes.len();
+    let nodes: Vec<_> = (0..count).filter(|node| sizes[*node].is_some()).collect();
+    let edges: Vec<_> = valid_edges(count, edges)
+        .into_iter()
+        .filter(|(from, to)| sizes[*from].is_some() && sizes[*to].is_some())
+        .collect();
+    let radius = |node: usize| sizes[node].unwrap_or_default().to_vec2().hypot() / 2.0;
+
+    let mut positions = vec![Point::ZERO; count];
+    let circle = EDGE_LENGTH * nodes.len() as f64 / std::f64::consts::TAU;
+    for (i, node) in nodes.iter().enumerate() {
+        let angle = std::f64::consts::TAU * i as f64 / nodes.len() as f64;
+        positions[*node] = Point::new(circle * angle.cos(), circle * angle.sin());
+    }
+
+    let k = EDGE_LENGTH;
+    let start_temperature = k * 2.0;
+    for iteration in 0..FORCE_ITERATIONS {
+        let mut moves = vec![Vec2::ZERO; count];
+        for (i, &a) in nodes.iter().enumerate() {
+            for &b in &nodes[i + 1..] {
+                let delta = positions[a] - positions[b];
+                // Repulsion acts on the space between the nodes, so that large
+                // nodes don't overlap.
+                let distance = (delta.hypot() - radius(a) - radius(b)).max(1.0);
+                let direction = match delta.hypot() > 0.0 {
+                    true => delta / delta.hypot(),
+                    false => Vec2::new(1.0, 0.0),
+                };
+                let force = direction * (k * k / distance);
+                moves[a] += force;
+                moves[b] -= force;
+            }
+        }
+        for &(from, to) in &edges {
+            let delta = positions[from] - positions[to];
+            let distance = delta.hypot();
+            if distance > 0.0 {
+                let force = delta / distance * (distance * distance / k);
+                moves[from] -= force;
+                moves[to] += force;
+            }
+        }
+        let temperature = start_temperature * (1.0 - iteration as f64 / FORCE_ITERATIONS as f64);
+        for &node in &nodes {
+            let length = moves[node].hypot();
+            if length > 0.0 {
+                positions[node] += moves[node] / length * length.min(temperature);
+            }
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collapsed_nodes_hide_descendants() {
+        // 0 -> 1 -> 2, 0 -> 3 -> 2, and a cycle 4 -> 5 -> 4.
+        let edges = [(0, 1), (1, 2), (0, 3), (3, 2), (4, 5), (5, 4)];
+        let none = HashSet::new();
+        assert_eq!(visible_nodes(6, &edges, &none), vec![true; 6]);
+
+        // 2 can still be reached through 3.
+        let collapsed = [1].into_iter().collect();
+        assert_eq!(visible_nodes(6, &edges, &collapsed), vec![true; 6]);
+
+        let collapsed = [0, 4].into_iter().collect();
+        assert_eq!(
+            visible_nodes(6, &edges, &collapsed),
+            vec![true, false, false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn layers() {
+        let size = Some(Size::new(40.0, 20.0));
+        // A tree with a cycle back to the root, and a hidden node.
+        let sizes = [size, size, size, size, None];
+        let edges = [(0, 1), (0, 2), (2, 3), (3, 0), (3, 4)];
+        let positions = layered_layout(&sizes, &edges);
+        assert_eq!(positions[0], Point::new(0.0, 10.0));
+        assert_eq!(positions[1].y, positions[2].y);
+        assert_eq!(positions[2].x - positions[1].x, 40.0 + NODE_SPACING);
+        assert_eq!(positions[1].x + positions[2].x, 0.0);
+        assert_eq!(positions[3].y - positions[2].y, 20.0 + LAYER_SPACING);
+        assert_eq!(positions[3].x, positions[2].x);
+    }
+
+    #[test]
+    fn forces_separate_nodes() {
+        let size = Some(Size::new(40.0, 20.0));
+        let sizes = [size; 4];
+        let edges = [(0, 1), (1, 2), (2, 0)];
+        let positions = force_directed_layout(&sizes, &edges);
+        for a in 0..4 {
+            for b in a + 1..4 {
+                assert!(positions[a].distance(positions[b]) > 40.0);
+            }
+        }
+        // The unconnected node is pushed away from the others.
+        let center =
+            (positions[0].to_vec2() + positions[1].to_vec2() + positions[2].to_vec2()) / 3.0;
+        let spread = positions[0].distance(center.to_point());
+        assert!(positions[3].distance(center.to_point()) > spread);
+    }
+}
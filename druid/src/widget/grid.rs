@@ -0,0 +1,533 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container that lays out its children in rows and columns.
+
+use std::collections::HashMap;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::Axis;
+use crate::{Data, Point, Rect, UnitPoint, WidgetPod};
+
+/// How the size of a row or column of a [`Grid`] is determined.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridTrack {
+    /// A track of a fixed size.
+    Fixed(f64),
+    /// A track that takes a share of the space left over by the other tracks,
+    /// in proportion to its flex factor.
+    ///
+    /// If the grid has unbounded constraints on this axis, the track is sized
+    /// like [`GridTrack::Auto`] instead.
+    Flex(f64),
+    /// A track that is as large as the max intrinsic width (for columns) or
+    /// the height (for rows) of the largest child in it.
+    Auto,
+}
+
+/// The rows or the columns of a [`Grid`].
+#[derive(Debug, Clone, Default)]
+struct Tracks {
+    sizes: Vec<GridTrack>,
+    gap: f64,
+    /// The gaps that differ from `gap`, by the index of the track they follow.
+    gaps: HashMap<usize, f64>,
+}
+
+struct GridChild<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    row: usize,
+    column: usize,
+    row_span: usize,
+    column_span: usize,
+}
+
+/// A container that lays out its children in rows and columns.
+///
+/// Each child is placed in a cell at a given row and column, and can span several
+/// rows and columns. Unlike nested [`Flex`] rows, all cells of a column have the same
+/// width, and all cells of a row have the same height, so the contents of a grid
+/// line up in both directions.
+///
+/// The size of each row and column is set with a [`GridTrack`]: it can be fixed,
+/// take a share of the remaining space, or fit the children in it. Rows and columns
+/// that are used by children but were not added to the grid are sized like
+/// [`GridTrack::Auto`]. The gaps between the tracks can be set for the whole grid
+/// and overridden after individual tracks.
+///
+/// Children get the size of their cell as their maximum size, and are aligned
+/// within it with the [`cell_alignment`], the top left corner by default. Use
+/// [`expand`] to have a child fill its cell.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Grid, GridTrack, Label, TextBox};
+/// use druid::WidgetExt;
+///
+/// let form = Grid::<String>::new()
+///     .with_column(GridTrack::Auto)
+///     .with_column(GridTrack::Flex(1.0))
+///     .with_column_gap(8.0)
+///     .with_row_gap(4.0)
+///     .with_child(Label::new("Name"), 0, 0)
+///     .with_child(TextBox::new().expand_width(), 0, 1)
+///     .with_child(Label::new("Address"), 1, 0)
+///     .with_child(TextBox::multiline().expand_width(), 1, 1)
+///     .with_spanning_child(Label::new("All fields are required."), 2, 0, 1, 2);
+/// ```
+///
+/// [`Flex`]: super::Flex
+/// [`cell_alignment`]: Grid::cell_alignment
+/// [`expand`]: super::WidgetExt::expand
+pub struct Grid<T> {
+    rows: Tracks,
+    columns: Tracks,
+    alignment: UnitPoint,
+    children: Vec<GridChild<T>>,
+}
+
+impl<T: Data> Grid<T> {
+    /// Create a new, empty grid.
+    pub fn new() -> Self {
+        Grid {
+            rows: Tracks::default(),
+            columns: Tracks::default(),
+            alignment: UnitPoint::TOP_LEFT,
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a row.
+    pub fn with_row(mut self, row: GridTrack) -> Self {
+        self.add_row(row);
+        self
+    }
+
+    /// Builder-style method to add a column.
+    pub fn with_column(mut self, column: GridTrack) -> Self {
+        self.add_column(column);
+        self
+    }
+
+    /// Builder-style method to set the gap between rows.
+    pub fn with_row_gap(mut self, gap: f64) -> Self {
+        self.set_row_gap(gap);
+        self
+    }
+
+    /// Builder-style method to set the gap between columns.
+    pub fn with_column_gap(mut self, gap: f64) -> Self {
+        self.set_column_gap(gap);
+        self
+    }
+
+    /// Builder-style method to set the gap after the row at `row`, overriding the
+    /// gap between rows.
+    pub fn with_gap_after_row(mut self, row: usize, gap: f64) -> Self {
+        self.set_gap_after_row(row, gap);
+        self
+    }
+
+    /// Builder-style method to set the gap after the column at `column`, overriding
+    /// the gap between columns.
+    pub fn with_gap_after_column(mut self, column: usize, gap: f64) -> Self {
+        self.set_gap_after_column(column, gap);
+        self
+    }
+
+    /// Builder-style method to set how children are aligned within their cells.
+    pub fn cell_alignment(mut self, alignment: UnitPoint) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Builder-style method to add a child in the cell at `row` and `column`.
+    pub fn with_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        row: usize,
+        column: usize,
+    ) -> Self {
+        self.add_child(child, row, column);
+        self
+    }
+
+    /// Builder-style method to add a child that spans `row_span` rows and
+    /// `column_span` columns, starting at `row` and `column`.
+    pub fn with_spanning_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        row: usize,
+        column: usize,
+        row_span: usize,
+        column_span: usize,
+    ) -> Self {
+        self.add_spanning_child(child, row, column, row_span, column_span);
+        self
+    }
+
+    /// Add a row.
+    pub fn add_row(&mut self, row: GridTrack) {
+        self.rows.sizes.push(row);
+    }
+
+    /// Add a column.
+    pub fn add_column(&mut self, column: GridTrack) {
+        self.columns.sizes.push(column);
+    }
+
+    /// Set the gap between rows.
+    pub fn set_row_gap(&mut self, gap: f64) {
+        self.rows.gap = gap;
+    }
+
+    /// Set the gap between columns.
+    pub fn set_column_gap(&mut self, gap: f64) {
+        self.columns.gap = gap;
+    }
+
+    /// Set the gap after the row at `row`, overriding the gap between rows.
+    pub fn set_gap_after_row(&mut self, row: usize, gap: f64) {
+        self.rows.gaps.insert(row, gap);
+    }
+
+    /// Set the gap after the column at `column`, overriding the gap between columns.
+    pub fn set_gap_after_column(&mut self, column: usize, gap: f64) {
+        self.columns.gaps.insert(column, gap);
+    }
+
+    /// Add a child in the cell at `row` and `column`.
+    ///
+    /// This should be followed by a call to [`children_changed`] if the grid is
+    /// already in use.
+    ///
+    /// [`children_changed`]: crate::EventCtx::children_changed
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static, row: usize, column: usize) {
+        self.add_spanning_child(child, row, column, 1, 1);
+    }
+
+    /// Add a child that spans `row_span` rows and `column_span` columns, starting
+    /// at `row` and `column`.
+    ///
+    /// This should be followed by a call to [`children_changed`] if the grid is
+    /// already in use.
+    ///
+    /// [`children_changed`]: crate::EventCtx::children_changed
+    pub fn add_spanning_child(
+        &mut self,
+        child: impl Widget<T> + 'static,
+        row: usize,
+        column: usize,
+        row_span: usize,
+        column_span: usize,
+    ) {
+        if row_span == 0 || column_span == 0 {
+            debug_panic!(
+                "Grid spans should be at least 1. Spans given were: {}, {}",
+                row_span,
+                column_span
+            );
+        }
+        self.children.push(GridChild {
+            widget: WidgetPod::new(Box::new(child)),
+            row,
+            column,
+            row_span: row_span.max(1),
+            column_span: column_span.max(1),
+        });
+    }
+
+    /// The number of rows, including the ones only used by children.
+    fn row_count(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| child.row + child.row_span)
+            .fold(self.rows.sizes.len(), usize::max)
+    }
+
+    /// The number of columns, including the ones only used by children.
+    fn column_count(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| child.column + child.column_span)
+            .fold(self.columns.sizes.len(), usize::max)
+    }
+}
+
+impl<T: Data> Default for Grid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tracks {
+    fn track(&self, index: usize) -> GridTrack {
+        self.sizes.get(index).copied().unwrap_or(GridTrack::Auto)
+    }
+
+    fn gap_after(&self, index: usize) -> f64 {
+        self.gaps.get(&index).copied().unwrap_or(self.gap)
+    }
+
+    /// The total size of the gaps between `count` tracks, starting at `start`.
+    fn gaps_between(&self, start: usize, count: usize) -> f64 {
+        (start..start + count.saturating_sub(1))
+            .map(|index| self.gap_after(index))
+            .sum()
+    }
+
+    /// Computes the sizes of `count` tracks, given the space `available` along
+    /// this axis, and the size of each child along it with the tracks it spans.
+    fn resolve(&self, count: usize, available: f64, content: &[(usize, usize, f64)]) -> Vec<f64> {
+        let flexible = available.is_finite();
+        let fits_content = |index| match self.track(index) {
+            GridTrack::Auto => true,
+            GridTrack::Flex(_) => !flexible,
+            GridTrack::Fixed(_) => false,
+        };
+
+        let mut sizes: Vec<f64> = (0..count)
+            .map(|index| match self.track(index) {
+                GridTrack::Fixed(size) => size,
+                _ => 0.0,
+            })
+            .collect();
+
+        // Children in a single track size it directly; children that span several
+        // tracks then grow the smallest of the tracks they span if those are too small.
+        for &(start, _, size) in content.iter().filter(|(_, span, _)| *span == 1) {
+            if fits_content(start) {
+                sizes[start] = sizes[start].max(size);
+            }
+        }
+        for &(start, span, size) in content.iter().filter(|(_, span, _)| *span > 1) {
+            let range = start..(start + span).min(count);
+            let current: f64 =
+                sizes[range.clone()].iter().sum::<f64>() + self.gaps_between(start, span);
+            let mut growable: Vec<_> = range.filter(|&index| fits_content(index)).collect();
+            if size > current && !growable.is_empty() {
+                // Grow the smallest tracks first, so that they tend towards equal sizes.
+                growable.sort_by(|&a, &b| sizes[a].total_cmp(&sizes[b]));
+                let mut extra = size - current;
+                let mut level = 0.0;
+                for (count, &index) in growable.iter().enumerate() {
+                    extra += sizes[index];
+                    level = extra / (count + 1) as f64;
+                    match growable.get(count + 1) {
+                        Some(&next) if sizes[next] < level => {}
+                        _ => break,
+                    }
+                }
+                for index in growable {
+                    sizes[index] = sizes[index].max(level);
+                }
+            }
+        }
+
+        if flexible {
+            let used: f64 = sizes.iter().sum::<f64>() + self.gaps_between(0, count);
+            let remaining = (available - used).max(0.0);
+            let flex_sum: f64 = (0..count)
+                .filter_map(|index| match self.track(index) {
+                    GridTrack::Flex(flex) => Some(flex),
+                    _ => None,
+                })
+                .sum();
+            if flex_sum > 0.0 {
+                for (index, size) in sizes.iter_mut().enumerate() {
+                    if let GridTrack::Flex(flex) = self.track(index) {
+                        *size = remaining * flex / flex_sum;
+                    }
+                }
+            }
+        }
+        sizes
+    }
+
+    /// The start of each track, and the total size, for the resolved `sizes`.
+    fn offsets(&self, sizes: &[f64]) -> (Vec<f64>, f64) {
+        let mut offsets = Vec::with_capacity(sizes.len());
+        let mut offset = 0.0;
+        for (index, size) in sizes.iter().enumerate() {
+            if index > 0 {
+                offset += self.gap_after(index - 1);
+            }
+            offsets.push(offset);
+            offset += size;
+        }
+        (offsets, offset)
+    }
+}
+
+impl<T: Data> Widget<T> for Grid<T> {
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Grid");
+        let max = bc.max();
+        let row_count = self.row_count();
+        let column_count = self.column_count();
+
+        // Size the columns from the children's max intrinsic widths, then lay the
+        // children out with the width of their columns to size the rows.
+        let loose = BoxConstraints::new(Size::ZERO, max);
+        let mut widths = Vec::with_capacity(self.children.len());
+        for child in &mut self.children {
+            let width = child.widget.widget_mut().compute_max_intrinsic(
+                Axis::Horizontal,
+                ctx,
+                &loose,
+                data,
+                env,
+            );
+            widths.push((child.column, child.column_span, width));
+        }
+        let column_sizes = self.columns.resolve(column_count, max.width, &widths);
+        let (column_offsets, width) = self.columns.offsets(&column_sizes);
+
+        let column_extent = |child: &GridChild<T>| {
+            column_sizes[child.column..child.column + child.column_span]
+                .iter()
+                .sum::<f64>()
+                + self.columns.gaps_between(child.column, child.column_span)
+        };
+        let mut heights = Vec::with_capacity(self.children.len());
+        for child in &mut self.children {
+            let child_bc =
+                BoxConstraints::new(Size::ZERO, Size::new(column_extent(child), max.height));
+            let size = child.widget.layout(ctx, &child_bc, data, env);
+            heights.push((child.row, child.row_span, size.height));
+        }
+        let row_sizes = self.rows.resolve(row_count, max.height, &heights);
+        let (row_offsets, height) = self.rows.offsets(&row_sizes);
+
+        let size = bc.constrain(Size::new(width, height));
+        let mut child_paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            let cell_size = Size::new(
+                column_extent(child),
+                row_sizes[child.row..child.row + child.row_span]
+                    .iter()
+                    .sum::<f64>()
+                    + self.rows.gaps_between(child.row, child.row_span),
+            );
+            let child_size =
+                child
+                    .widget
+                    .layout(ctx, &BoxConstraints::new(Size::ZERO, cell_size), data, env);
+            let extra = cell_size - child_size;
+            let offset = self.alignment.resolve(Rect::new(
+                0.0,
+                0.0,
+                extra.width.max(0.0),
+                extra.height.max(0.0),
+            ));
+            let origin =
+                Point::new(column_offsets[child.column], row_offsets[child.row]) + offset.to_vec2();
+            child.widget.set_origin(ctx, origin);
+            child_paint_rect = child_paint_rect.union(child.widget.paint_rect());
+        }
+
+        let insets = child_paint_rect - size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children_state = self
+            .children
+            .iter()
+            .map(|child| child.widget.widget().debug_state(data))
+            .collect();
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: children_state,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_sizes() {
+        let tracks = Tracks {
+            sizes: vec![
+                GridTrack::Fixed(50.0),
+                GridTrack::Auto,
+                GridTrack::Flex(1.0),
+                GridTrack::Flex(3.0),
+            ],
+            gap: 10.0,
+            gaps: HashMap::from([(0, 0.0)]),
+        };
+        let content = [(1, 1, 40.0), (1, 1, 60.0), (2, 1, 500.0), (4, 1, 20.0)];
+        // 50 + 60 + 20 for the fixed and auto tracks, and 30 for the gaps, leave 240.
+        assert_eq!(
+            tracks.resolve(5, 400.0, &content),
+            vec![50.0, 60.0, 60.0, 180.0, 20.0]
+        );
+        // Without a bound, flexible tracks fit their content.
+        assert_eq!(
+            tracks.resolve(5, f64::INFINITY, &content),
+            vec![50.0, 60.0, 500.0, 0.0, 20.0]
+        );
+        assert_eq!(tracks.offsets(&[50.0, 60.0]), (vec![0.0, 50.0], 110.0));
+    }
+
+    #[test]
+    fn spanning_children_grow_auto_tracks() {
+        let tracks = Tracks {
+            sizes: vec![GridTrack::Fixed(20.0), GridTrack::Auto, GridTrack::Auto],
+            gap: 5.0,
+            gaps: HashMap::new(),
+        };
+        let content = [(1, 1, 10.0), (0, 3, 70.0)];
+        assert_eq!(tracks.resolve(3, 400.0, &content), vec![20.0, 20.0, 20.0]);
+    }
+}
@@ -0,0 +1,329 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that draws a vector icon.
+
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, SvgParseError};
+use crate::piet::{LineCap, LineJoin, StrokeStyle};
+use crate::widget::prelude::*;
+use crate::{theme, Affine, Color, Data, KeyOrValue, Point};
+
+/// How much of an icon is below the baseline of the text next to it, as a fraction
+/// of its height, so that it lines up with capital letters.
+const BELOW_BASELINE: f64 = 0.15;
+
+/// The shape of an icon.
+///
+/// Icons are drawn in a square or rectangular view box, which is scaled to the size
+/// of the [`Icon`]. They are either stroked, like the icons that come with Druid,
+/// or filled.
+#[derive(Debug, Clone)]
+pub struct IconData {
+    path: IconPath,
+    view_box: Size,
+    /// The width of the outline, in view box units; `None` if the icon is filled.
+    stroke_width: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+enum IconPath {
+    /// SVG path data that has been checked by the tests.
+    Static(&'static str),
+    Path(Arc<BezPath>),
+}
+
+/// A widget that draws an [`IconData`], in the color of the text.
+///
+/// The color defaults to [`theme::TEXT_COLOR`], and to
+/// [`theme::DISABLED_TEXT_COLOR`] while the icon is disabled. The size defaults to
+/// [`theme::TEXT_SIZE_NORMAL`], so that an icon fits next to a label.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Flex, Icon, IconData, Label};
+/// use druid::Widget;
+///
+/// fn search_field() -> impl Widget<()> {
+///     Flex::row()
+///         .with_child(Icon::new(IconData::SEARCH))
+///         .with_child(Label::new("Search"))
+/// }
+/// ```
+///
+/// [`theme::TEXT_COLOR`]: crate::theme::TEXT_COLOR
+/// [`theme::DISABLED_TEXT_COLOR`]: crate::theme::DISABLED_TEXT_COLOR
+/// [`theme::TEXT_SIZE_NORMAL`]: crate::theme::TEXT_SIZE_NORMAL
+pub struct Icon {
+    data: IconData,
+    path: BezPath,
+    color: KeyOrValue<Color>,
+    size: KeyOrValue<f64>,
+}
+
+macro_rules! icons {
+    ($($(#[$attr:meta])* $name:ident = $path:literal;)*) => {
+        impl IconData {
+            $(
+                $(#[$attr])*
+                pub const $name: IconData = IconData::stroked($path);
+            )*
+        }
+
+        #[cfg(test)]
+        const ALL_ICONS: &[(&str, IconData)] = &[$((stringify!($name), IconData::$name)),*];
+    };
+}
+
+icons! {
+    /// A check mark.
+    CHECK = "M20 6L9 17L4 12";
+    /// A cross, for closing or removing something.
+    CLOSE = "M18 6L6 18M6 6L18 18";
+    /// A plus sign.
+    ADD = "M12 5V19M5 12H19";
+    /// A minus sign.
+    REMOVE = "M5 12H19";
+    /// A chevron pointing left.
+    CHEVRON_LEFT = "M15 18L9 12L15 6";
+    /// A chevron pointing right.
+    CHEVRON_RIGHT = "M9 18L15 12L9 6";
+    /// A chevron pointing up.
+    CHEVRON_UP = "M18 15L12 9L6 15";
+    /// A chevron pointing down.
+    CHEVRON_DOWN = "M6 9L12 15L18 9";
+    /// An arrow pointing left.
+    ARROW_LEFT = "M19 12H5M12 19L5 12L12 5";
+    /// An arrow pointing right.
+    ARROW_RIGHT = "M5 12H19M12 5L19 12L12 19";
+    /// Three horizontal lines, for opening a menu.
+    MENU = "M3 6H21M3 12H21M3 18H21";
+    /// A magnifying glass.
+    SEARCH = "M19 11A8 8 0 1 1 3 11A8 8 0 1 1 19 11ZM21 21L16.65 16.65";
+    /// A house.
+    HOME = "M3 10L12 3L21 10V20A1 1 0 0 1 20 21H4A1 1 0 0 1 3 20ZM9 21V13H15V21";
+    /// A lowercase i in a circle.
+    INFO = "M22 12A10 10 0 1 1 2 12A10 10 0 1 1 22 12ZM12 16V12M12 8H12.01";
+    /// An exclamation mark in a triangle.
+    WARNING = "M12 3L22 20H2ZM12 9V13M12 17H12.01";
+    /// A triangle pointing right.
+    PLAY = "M6 4L20 12L6 20Z";
+    /// Two vertical bars.
+    PAUSE = "M6 4H10V20H6ZM14 4H18V20H14Z";
+    /// A pencil.
+    EDIT = "M17 3L21 7L8 20H4V16Z";
+    /// A waste basket.
+    DELETE = "M3 6H21M8 6V4H16V6M19 6L18 20A2 2 0 0 1 16 22H8A2 2 0 0 1 6 20L5 6";
+    /// Two overlapping pages.
+    COPY = "M9 9H20V20H9ZM5 15H4V4H15V5";
+    /// An arrow pointing down into a tray.
+    DOWNLOAD = "M12 3V15M7 10L12 15L17 10M4 17V21H20V17";
+    /// A folder.
+    FOLDER = "M3 5H10L12 8H21V19H3Z";
+}
+
+impl IconData {
+    /// The size of the view box of the icons that come with Druid.
+    const DEFAULT_VIEW_BOX: Size = Size::new(24.0, 24.0);
+
+    const fn stroked(path: &'static str) -> Self {
+        IconData {
+            path: IconPath::Static(path),
+            view_box: IconData::DEFAULT_VIEW_BOX,
+            stroke_width: Some(2.0),
+        }
+    }
+
+    /// Create a filled icon from a path, drawn in a view box of `view_box`, which
+    /// starts at the origin.
+    pub fn from_path(path: BezPath, view_box: impl Into<Size>) -> Self {
+        IconData {
+            path: IconPath::Path(Arc::new(path)),
+            view_box: view_box.into(),
+            stroke_width: None,
+        }
+    }
+
+    /// Create a filled icon from the data of an SVG `<path>` element.
+    pub fn from_svg_path(data: &str, view_box: impl Into<Size>) -> Result<Self, SvgParseError> {
+        Ok(IconData::from_path(BezPath::from_svg(data)?, view_box))
+    }
+
+    /// Builder-style method for drawing the outline of the path instead of filling
+    /// it, `width` view box units wide.
+    pub fn stroked_with(mut self, width: f64) -> Self {
+        self.stroke_width = Some(width);
+        self
+    }
+
+    fn to_path(&self) -> BezPath {
+        match &self.path {
+            IconPath::Static(data) => BezPath::from_svg(data).unwrap(),
+            IconPath::Path(path) => (**path).clone(),
+        }
+    }
+}
+
+impl Icon {
+    /// Create a widget that draws `data`.
+    pub fn new(data: IconData) -> Self {
+        Icon {
+            path: data.to_path(),
+            data,
+            color: theme::TEXT_COLOR.into(),
+            size: theme::TEXT_SIZE_NORMAL.into(),
+        }
+    }
+
+    /// Builder-style method for setting the color.
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Builder-style method for setting the height.
+    pub fn with_size(mut self, size: impl Into<KeyOrValue<f64>>) -> Self {
+        self.size = size.into();
+        self
+    }
+
+    /// Set the icon that is drawn.
+    pub fn set_icon(&mut self, data: IconData) {
+        self.path = data.to_path();
+        self.data = data;
+    }
+
+    /// Set the color.
+    pub fn set_color(&mut self, color: impl Into<KeyOrValue<Color>>) {
+        self.color = color.into();
+    }
+
+    /// The size of the icon, for a height of `height`.
+    fn size_for(&self, height: f64) -> Size {
+        let view_box = self.data.view_box;
+        match view_box.height > 0.0 {
+            true => Size::new(view_box.width * height / view_box.height, height),
+            false => Size::ZERO,
+        }
+    }
+
+    /// The size of the icon in `env`.
+    pub(crate) fn resolved_size(&self, env: &Env) -> Size {
+        self.size_for(self.size.resolve(env))
+    }
+
+    /// How far the baseline of text next to the icon should be from its bottom.
+    pub(crate) fn baseline_offset(&self, env: &Env) -> f64 {
+        self.resolved_size(env).height * BELOW_BASELINE
+    }
+
+    /// Draw the icon, with its top left corner at `origin`.
+    pub(crate) fn draw(&self, ctx: &mut PaintCtx, origin: Point, env: &Env) {
+        let size = self.resolved_size(env);
+        let view_box = self.data.view_box;
+        if view_box.is_empty() {
+            return;
+        }
+        let color = match ctx.is_disabled() {
+            true => env.get(theme::DISABLED_TEXT_COLOR),
+            false => self.color.resolve(env),
+        };
+        ctx.with_save(|ctx| {
+            ctx.transform(
+                Affine::translate(origin.to_vec2())
+                    * Affine::scale_non_uniform(
+                        size.width / view_box.width,
+                        size.height / view_box.height,
+                    ),
+            );
+            match self.data.stroke_width {
+                Some(width) => {
+                    let style = StrokeStyle::new()
+                        .line_cap(LineCap::Round)
+                        .line_join(LineJoin::Round);
+                    ctx.stroke_styled(&self.path, &color, width, &style);
+                }
+                None => ctx.fill(&self.path, &color),
+            }
+        });
+    }
+}
+
+impl<T: Data> Widget<T> for Icon {
+    #[instrument(name = "Icon", level = "trace", skip(self, _ctx, _event, _data, _env))]
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+
+    #[instrument(name = "Icon", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        if let LifeCycle::DisabledChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(
+        name = "Icon",
+        level = "trace",
+        skip(self, ctx, _old_data, _data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {
+        if ctx.env_key_changed(&self.size) {
+            ctx.request_layout();
+        }
+        if ctx.env_key_changed(&self.color) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Icon", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("Icon");
+        let size = bc.constrain(self.resolved_size(env));
+        ctx.set_baseline_offset(size.height * BELOW_BASELINE);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Icon", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let size = self.resolved_size(env);
+        let origin = ((ctx.size().to_vec2() - size.to_vec2()) / 2.0).to_point();
+        self.draw(ctx, origin, env);
+    }
+
+    fn debug_state(&self, _data: &T) -> DebugState {
+        DebugState {
+            display_name: "Icon".to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icons_parse() {
+        for (name, icon) in ALL_ICONS {
+            if let IconPath::Static(data) = icon.path {
+                assert!(BezPath::from_svg(data).is_ok(), "{} doesn't parse", name);
+            }
+        }
+    }
+}
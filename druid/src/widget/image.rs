@@ -15,15 +15,25 @@
 //! An Image widget.
 //! Please consider using SVG and the SVG widget as it scales much better.
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use crate::{
     kurbo::Rect,
     piet::{Image as _, ImageBuf, InterpolationMode, PietImage},
+    resources::{ResourceCache, ResourceKind},
+    theme,
     widget::common::FillStrat,
     widget::prelude::*,
-    Data,
+    Data, Selector,
 };
 use tracing::{instrument, trace};
 
+/// Frames that are shown for less than this are shown for [`DEFAULT_FRAME_DELAY`]
+/// instead, like browsers do.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
 /// A widget that renders a bitmap Image.
 ///
 /// Contains data about how to fill the given space and interpolate pixels.
@@ -71,10 +81,29 @@ use tracing::{instrument, trace};
 /// [SVG files]: https://en.wikipedia.org/wiki/Scalable_Vector_Graphics
 pub struct Image {
     image_data: ImageBuf,
-    paint_data: Option<PietImage>,
+    paint_data: ResourceCache<PietImage>,
     fill: FillStrat,
     interpolation: InterpolationMode,
     clip_area: Option<Rect>,
+    animation: Option<Animation>,
+}
+
+/// The frames of an animated image, each with the time it is shown for.
+///
+/// The underlying `ImageBuf`s use `Arc` for their data, making this cheap to clone.
+#[derive(Debug, Clone)]
+pub struct ImageFrames {
+    frames: Arc<[(ImageBuf, Duration)]>,
+}
+
+/// The playback state of an animated [`Image`].
+struct Animation {
+    frames: ImageFrames,
+    current: usize,
+    /// How long the current frame has been shown.
+    elapsed: Duration,
+    playing: bool,
+    looping: bool,
 }
 
 impl Image {
@@ -91,11 +120,49 @@ impl Image {
     pub fn new(image_data: ImageBuf) -> Self {
         Image {
             image_data,
-            paint_data: None,
+            paint_data: ResourceCache::new(ResourceKind::Image),
             fill: FillStrat::default(),
             interpolation: InterpolationMode::Bilinear,
             clip_area: None,
+            animation: None,
+        }
+    }
+
+    /// Create an image drawing widget that plays an animation.
+    ///
+    /// The animation starts playing when the widget is added, unless
+    /// [`REDUCED_MOTION`] is set, and loops forever; see [`Image::looping`].
+    /// It can be controlled with the [`PLAY_ANIMATION`] and [`PAUSE_ANIMATION`]
+    /// commands.
+    ///
+    /// [`REDUCED_MOTION`]: crate::theme::REDUCED_MOTION
+    /// [`PLAY_ANIMATION`]: Image::PLAY_ANIMATION
+    /// [`PAUSE_ANIMATION`]: Image::PAUSE_ANIMATION
+    pub fn animated(frames: ImageFrames) -> Self {
+        let mut image = Image::new(frames.frame(0).cloned().unwrap_or_else(ImageBuf::empty));
+        image.animation = Some(Animation {
+            frames,
+            current: 0,
+            elapsed: Duration::ZERO,
+            playing: true,
+            looping: true,
+        });
+        image
+    }
+
+    /// Builder-style method for whether an animation starts over after its last frame.
+    ///
+    /// Has no effect on images that are not animated.
+    pub fn looping(mut self, looping: bool) -> Self {
+        if let Some(animation) = &mut self.animation {
+            animation.looping = looping;
         }
+        self
+    }
+
+    /// Returns `true` if this is an animated image that is currently playing.
+    pub fn is_playing(&self) -> bool {
+        matches!(&self.animation, Some(animation) if animation.playing)
     }
 
     /// Builder-style method for specifying the fill strategy.
@@ -148,16 +215,64 @@ impl Image {
     }
 
     /// Set new `ImageBuf`.
+    ///
+    /// This stops any animation.
     #[inline]
     pub fn set_image_data(&mut self, image_data: ImageBuf) {
+        self.animation = None;
         self.image_data = image_data;
         self.invalidate();
     }
 
+    /// Start playing the animation, from the start if it had finished.
+    fn play(&mut self) {
+        if let Some(animation) = &mut self.animation {
+            let finished = animation.current + 1 >= animation.frames.len() && !animation.looping;
+            if finished {
+                animation.current = 0;
+                animation.elapsed = Duration::ZERO;
+                if let Some(frame) = animation.frames.frame(0) {
+                    self.image_data = frame.clone();
+                    self.paint_data.clear();
+                }
+            }
+            animation.playing = true;
+        }
+    }
+
+    /// Advance the animation by `interval`, returning `true` if the frame changed.
+    fn advance(&mut self, interval: Duration) -> bool {
+        let animation = match &mut self.animation {
+            Some(animation) if animation.playing => animation,
+            _ => return false,
+        };
+        let start = animation.current;
+        animation.elapsed += interval;
+        while let Some(delay) = animation.frames.delay(animation.current) {
+            if animation.elapsed < delay {
+                break;
+            }
+            if animation.current + 1 == animation.frames.len() && !animation.looping {
+                animation.playing = false;
+                break;
+            }
+            animation.elapsed -= delay;
+            animation.current = (animation.current + 1) % animation.frames.len();
+        }
+        if animation.current == start {
+            return false;
+        }
+        if let Some(frame) = animation.frames.frame(animation.current) {
+            self.image_data = frame.clone();
+            self.invalidate();
+        }
+        true
+    }
+
     /// Invalidate the image cache, forcing it to be recreated.
     #[inline]
     fn invalidate(&mut self) {
-        self.paint_data = None;
+        self.paint_data.clear();
     }
 
     /// The size of the effective image, considering clipping if it's in effect.
@@ -169,12 +284,60 @@ impl Image {
     }
 }
 
+impl Image {
+    /// Start or resume playing an animated image.
+    ///
+    /// If the animation had finished, it starts over.
+    pub const PLAY_ANIMATION: Selector = Selector::new("druid-builtin.image-play-animation");
+
+    /// Pause an animated image on its current frame.
+    pub const PAUSE_ANIMATION: Selector = Selector::new("druid-builtin.image-pause-animation");
+}
+
 impl<T: Data> Widget<T> for Image {
-    #[instrument(name = "Image", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+    #[instrument(name = "Image", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        if self.animation.is_none() {
+            return;
+        }
+        match event {
+            Event::AnimFrame(interval) => {
+                if self.advance(Duration::from_nanos(*interval)) {
+                    ctx.request_paint();
+                }
+                if self.is_playing() {
+                    ctx.request_anim_frame();
+                }
+            }
+            Event::Command(cmd) if cmd.is(Self::PLAY_ANIMATION) => {
+                if !self.is_playing() {
+                    self.play();
+                    ctx.request_paint();
+                    ctx.request_anim_frame();
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(Self::PAUSE_ANIMATION) => {
+                if let Some(animation) = &mut self.animation {
+                    animation.playing = false;
+                }
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
 
-    #[instrument(name = "Image", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+    #[instrument(name = "Image", level = "trace", skip(self, ctx, event, _data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, env: &Env) {
+        if let (LifeCycle::WidgetAdded, Some(animation)) = (event, &mut self.animation) {
+            if env.get(theme::REDUCED_MOTION) {
+                animation.playing = false;
+            }
+            if animation.playing && animation.frames.len() > 1 {
+                ctx.request_anim_frame();
+            }
+        }
+    }
 
     #[instrument(
         name = "Image",
@@ -227,32 +390,139 @@ impl<T: Data> Widget<T> for Image {
             ctx.clip(clip_rect);
         }
 
+        // The cache is held by the resource registry, which drops it if the
+        // application goes over its memory budget.
         let piet_image = {
             let image_data = &self.image_data;
+            let bytes = image_data.raw_pixels().len();
             self.paint_data
-                .get_or_insert_with(|| image_data.to_image(ctx.render_ctx))
+                .get_or_insert_with(bytes, || image_data.to_image(ctx.render_ctx))
         };
         if piet_image.size().is_empty() {
             // zero-sized image = nothing to draw
             return;
         }
         ctx.with_save(|ctx| {
-            // we have to re-do this because the whole struct is moved into the closure.
-            let piet_image = {
-                let image_data = &self.image_data;
-                self.paint_data
-                    .get_or_insert_with(|| image_data.to_image(ctx.render_ctx))
-            };
             ctx.transform(offset_matrix);
             if let Some(area) = self.clip_area {
-                ctx.draw_image_area(piet_image, area, image_size.to_rect(), self.interpolation);
+                ctx.draw_image_area(&*piet_image, area, image_size.to_rect(), self.interpolation);
             } else {
-                ctx.draw_image(piet_image, image_size.to_rect(), self.interpolation);
+                ctx.draw_image(&*piet_image, image_size.to_rect(), self.interpolation);
             }
         });
     }
 }
 
+impl ImageFrames {
+    /// Create an animation from frames and the time each is shown for.
+    pub fn new(frames: impl IntoIterator<Item = (ImageBuf, Duration)>) -> Self {
+        ImageFrames {
+            frames: frames
+                .into_iter()
+                .map(|(image, delay)| match delay < MIN_FRAME_DELAY {
+                    true => (image, DEFAULT_FRAME_DELAY),
+                    false => (image, delay),
+                })
+                .collect(),
+        }
+    }
+
+    /// Decode all frames of an animated GIF, PNG or WebP image.
+    ///
+    /// Only the formats whose features are enabled are decoded as animations;
+    /// other images that the image crate can decode result in a single frame.
+    #[cfg(feature = "image")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+    pub fn from_data(
+        raw_image: &[u8],
+    ) -> Result<ImageFrames, Box<dyn std::error::Error + Send + Sync>> {
+        #[allow(unused_imports)]
+        use crate::image::{AnimationDecoder, ImageFormat};
+        #[allow(unused_imports)]
+        use std::io::Cursor;
+
+        #[allow(unused_variables)]
+        let format = crate::image::guess_format(raw_image)?;
+        #[cfg(feature = "gif")]
+        if format == ImageFormat::Gif {
+            let decoder = crate::image::codecs::gif::GifDecoder::new(Cursor::new(raw_image))?;
+            return decode_frames(decoder.into_frames());
+        }
+        #[cfg(feature = "png")]
+        if format == ImageFormat::Png {
+            let decoder = crate::image::codecs::png::PngDecoder::new(Cursor::new(raw_image))?;
+            if decoder.is_apng() {
+                return decode_frames(decoder.apng().into_frames());
+            }
+        }
+        #[cfg(feature = "webp")]
+        if format == ImageFormat::WebP {
+            let decoder = crate::image::codecs::webp::WebPDecoder::new(Cursor::new(raw_image))?;
+            return decode_frames(decoder.into_frames());
+        }
+        let image = ImageBuf::from_data(raw_image)?;
+        Ok(ImageFrames::new([(image, Duration::ZERO)]))
+    }
+
+    /// Decode all frames of the animated image file at `path`.
+    ///
+    /// See [`ImageFrames::from_data`].
+    #[cfg(feature = "image")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+    pub fn from_file(
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<ImageFrames, Box<dyn std::error::Error + Send + Sync>> {
+        ImageFrames::from_data(&std::fs::read(path)?)
+    }
+
+    /// The number of frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns `true` if there are no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The image of frame `index`.
+    pub fn frame(&self, index: usize) -> Option<&ImageBuf> {
+        self.frames.get(index).map(|(image, _)| image)
+    }
+
+    /// How long frame `index` is shown for.
+    pub fn delay(&self, index: usize) -> Option<Duration> {
+        self.frames.get(index).map(|(_, delay)| *delay)
+    }
+}
+
+#[cfg(all(
+    feature = "image",
+    any(feature = "gif", feature = "png", feature = "webp")
+))]
+fn decode_frames(
+    frames: crate::image::Frames,
+) -> Result<ImageFrames, Box<dyn std::error::Error + Send + Sync>> {
+    use crate::piet::ImageFormat;
+
+    let frames = frames
+        .map(|frame| {
+            let frame = frame?;
+            let delay = Duration::from(frame.delay());
+            let buffer = frame.into_buffer();
+            let (width, height) = buffer.dimensions();
+            let image = ImageBuf::from_raw(
+                buffer.into_raw(),
+                ImageFormat::RgbaSeparate,
+                width as usize,
+                height as usize,
+            );
+            Ok((image, delay))
+        })
+        .collect::<Result<Vec<_>, crate::image::ImageError>>()?;
+    Ok(ImageFrames::new(frames))
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
@@ -260,6 +530,18 @@ mod tests {
     use crate::piet::ImageFormat;
     use test_log::test;
 
+    #[test]
+    fn animation_playback() {
+        let frames = ImageFrames::new(vec![(ImageBuf::empty(), Duration::from_millis(50)); 3]);
+        let mut image = Image::animated(frames).looping(false);
+        assert!(image.advance(Duration::from_millis(60)));
+        assert_eq!(image.animation.as_ref().unwrap().current, 1);
+        assert!(!image.advance(Duration::from_millis(10)));
+        assert!(image.advance(Duration::from_millis(200)));
+        assert_eq!(image.animation.as_ref().unwrap().current, 2);
+        assert!(!image.is_playing());
+    }
+
     /// Painting an empty image shouldn't crash Druid.
     #[test]
     fn empty_paint() {
@@ -0,0 +1,336 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A scrolling list that asks for more items when it is scrolled to the end.
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::{List, ListIter, Scroll};
+use crate::{Data, Point, Selector, WidgetPod};
+
+/// Sent by an `InfiniteList` to itself after layout, to check from `event` whether
+/// the end of the list is in view.
+const CHECK_END: Selector = Selector::new("druid-builtin.infinite-list-check-end");
+
+/// The default distance from the end of the list at which more items are loaded.
+const DEFAULT_THRESHOLD: f64 = 200.0;
+
+type LoadMoreFn<C> = dyn Fn(&mut EventCtx, &mut C, &Env);
+
+/// Whether more items are being loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoadState {
+    Idle,
+    /// More items were requested when the collection had this many.
+    Loading(usize),
+    /// There are no more items to load.
+    Exhausted,
+}
+
+/// The list and the loading footer below it.
+struct Body<T, C> {
+    list: WidgetPod<C, List<T>>,
+    footer: Option<WidgetPod<C, Box<dyn Widget<C>>>>,
+    show_footer: bool,
+}
+
+/// A vertically scrolling [`List`] that loads more items when it is scrolled near
+/// its end.
+///
+/// When the end of the list comes within [`with_threshold`] pixels of the bottom of
+/// the view, the list sends a [`LOAD_MORE`] notification and calls the callback set
+/// with [`on_load_more`]. The app should then start fetching the next page, and
+/// append it to the collection. While it does so, the footer set with
+/// [`with_loading_footer`] is shown below the items.
+///
+/// The list waits for the collection to grow before asking again. If a fetch ends
+/// without adding items, submit [`LOAD_FINISHED`] to the list; its payload is whether
+/// there may be more items to load.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use druid::widget::{InfiniteList, Label, List, Spinner};
+/// use druid::Widget;
+///
+/// fn feed() -> impl Widget<Arc<Vec<String>>> {
+///     InfiniteList::new(List::new(|| Label::dynamic(|item: &String, _| item.clone())))
+///         .with_loading_footer(Spinner::new())
+///         .on_load_more(|_ctx, items: &mut Arc<Vec<String>>, _env| {
+///             let next = items.len();
+///             Arc::make_mut(items).extend((next..next + 20).map(|i| format!("Item {}", i)));
+///         })
+/// }
+/// ```
+///
+/// [`with_threshold`]: InfiniteList::with_threshold
+/// [`on_load_more`]: InfiniteList::on_load_more
+/// [`with_loading_footer`]: InfiniteList::with_loading_footer
+/// [`LOAD_MORE`]: InfiniteList::LOAD_MORE
+/// [`LOAD_FINISHED`]: InfiniteList::LOAD_FINISHED
+pub struct InfiniteList<T, C> {
+    scroll: Scroll<C, Body<T, C>>,
+    threshold: f64,
+    on_load_more: Option<Box<LoadMoreFn<C>>>,
+    state: LoadState,
+}
+
+impl InfiniteList<(), ()> {
+    /// Sent by an [`InfiniteList`] when it is scrolled near its end and more items
+    /// should be loaded.
+    pub const LOAD_MORE: Selector = Selector::new("druid-builtin.infinite-list-load-more");
+
+    /// Tells an [`InfiniteList`] that loading has finished without adding items.
+    ///
+    /// The payload is whether there may be more items; if it is `false`, the list
+    /// stops asking until the collection shrinks.
+    pub const LOAD_FINISHED: Selector<bool> =
+        Selector::new("druid-builtin.infinite-list-load-finished");
+}
+
+impl<T: Data, C: ListIter<T>> InfiniteList<T, C> {
+    /// Create an infinite list that shows its items with `list`.
+    pub fn new(list: List<T>) -> Self {
+        let body = Body {
+            list: WidgetPod::new(list),
+            footer: None,
+            show_footer: false,
+        };
+        InfiniteList {
+            scroll: Scroll::new(body).vertical(),
+            threshold: DEFAULT_THRESHOLD,
+            on_load_more: None,
+            state: LoadState::Idle,
+        }
+    }
+
+    /// Builder-style method for setting how close to the end of the list, in pixels,
+    /// more items are loaded.
+    ///
+    /// The default is `200.0`.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Builder-style method for setting the widget shown below the items while more
+    /// are being loaded.
+    pub fn with_loading_footer(mut self, footer: impl Widget<C> + 'static) -> Self {
+        self.scroll.child_mut().footer = Some(WidgetPod::new(Box::new(footer)));
+        self
+    }
+
+    /// Builder-style method for setting a callback that is called when more items
+    /// should be loaded.
+    ///
+    /// The callback can append items to the collection directly, or start loading them
+    /// in the background.
+    pub fn on_load_more(mut self, f: impl Fn(&mut EventCtx, &mut C, &Env) + 'static) -> Self {
+        self.on_load_more = Some(Box::new(f));
+        self
+    }
+
+    /// Returns `true` while the list is waiting for more items.
+    pub fn is_loading(&self) -> bool {
+        matches!(self.state, LoadState::Loading(_))
+    }
+
+    fn set_state(&mut self, state: LoadState) -> bool {
+        if self.state == state {
+            return false;
+        }
+        self.state = state;
+        let show_footer = self.is_loading();
+        let body = self.scroll.child_mut();
+        let changed = body.show_footer != show_footer && body.footer.is_some();
+        body.show_footer = show_footer;
+        changed
+    }
+
+    fn near_end(&self) -> bool {
+        let viewport = self.scroll.viewport_rect();
+        if viewport.height() <= 0.0 {
+            // Not laid out yet.
+            return false;
+        }
+        let remaining = self.scroll.child_size().height - viewport.y1;
+        remaining <= self.threshold
+    }
+
+    fn load_more_if_needed(&mut self, ctx: &mut EventCtx, data: &mut C, env: &Env) {
+        if self.state != LoadState::Idle || !self.near_end() {
+            return;
+        }
+        if self.set_state(LoadState::Loading(data.data_len())) {
+            ctx.request_layout();
+        }
+        ctx.submit_notification_without_warning(InfiniteList::LOAD_MORE);
+        if let Some(on_load_more) = &self.on_load_more {
+            on_load_more(ctx, data, env);
+        }
+    }
+}
+
+impl<T: Data, C: ListIter<T>> Widget<C> for InfiniteList<T, C> {
+    #[instrument(
+        name = "InfiniteList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut C, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(CHECK_END) {
+                self.load_more_if_needed(ctx, data, env);
+                ctx.set_handled();
+                return;
+            }
+            if let Some(&has_more) = cmd.get(InfiniteList::LOAD_FINISHED) {
+                let state = match has_more {
+                    true => LoadState::Idle,
+                    false => LoadState::Exhausted,
+                };
+                if self.set_state(state) {
+                    ctx.request_layout();
+                }
+                return;
+            }
+        }
+        self.scroll.event(ctx, event, data, env);
+        self.load_more_if_needed(ctx, data, env);
+    }
+
+    #[instrument(
+        name = "InfiniteList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &C, env: &Env) {
+        self.scroll.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "InfiniteList",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &C, data: &C, env: &Env) {
+        let len = data.data_len();
+        let state = match self.state {
+            LoadState::Loading(old_len) if len > old_len => LoadState::Idle,
+            LoadState::Exhausted if len < old_data.data_len() => LoadState::Idle,
+            state => state,
+        };
+        if self.set_state(state) {
+            ctx.request_layout();
+        }
+        self.scroll.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "InfiniteList", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &C, env: &Env) -> Size {
+        bc.debug_check("InfiniteList");
+        let size = self.scroll.layout(ctx, bc, data, env);
+        if self.state == LoadState::Idle && self.near_end() {
+            // The items may not fill the view yet.
+            ctx.submit_command(CHECK_END.to(ctx.widget_id()));
+        }
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "InfiniteList", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &C, env: &Env) {
+        self.scroll.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &C) -> DebugState {
+        DebugState {
+            display_name: "InfiniteList".to_string(),
+            main_value: format!("{:?}", self.state),
+            children: vec![self.scroll.debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data, C: ListIter<T>> Widget<C> for Body<T, C> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut C, env: &Env) {
+        self.list.event(ctx, event, data, env);
+        if let Some(footer) = &mut self.footer {
+            if self.show_footer || event.should_propagate_to_hidden() {
+                footer.event(ctx, event, data, env);
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &C, env: &Env) {
+        self.list.lifecycle(ctx, event, data, env);
+        if let Some(footer) = &mut self.footer {
+            footer.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &C, data: &C, env: &Env) {
+        self.list.update(ctx, data, env);
+        if let Some(footer) = &mut self.footer {
+            footer.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &C, env: &Env) -> Size {
+        let list_size = self.list.layout(ctx, bc, data, env);
+        self.list.set_origin(ctx, Point::ORIGIN);
+        let mut size = list_size;
+        if let Some(footer) = &mut self.footer {
+            let footer_bc = BoxConstraints::new(
+                Size::new(bc.min().width, 0.0),
+                Size::new(bc.max().width, f64::INFINITY),
+            );
+            let footer_size = footer.layout(ctx, &footer_bc, data, env);
+            // Center the footer below the items.
+            let x = ((list_size.width.max(bc.min().width) - footer_size.width) / 2.0).max(0.0);
+            footer.set_origin(ctx, Point::new(x, list_size.height));
+            if self.show_footer {
+                size.width = size.width.max(footer_size.width);
+                size.height += footer_size.height;
+            }
+        }
+        bc.constrain(size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &C, env: &Env) {
+        self.list.paint(ctx, data, env);
+        if let Some(footer) = &mut self.footer {
+            if self.show_footer {
+                footer.paint(ctx, data, env);
+            }
+        }
+    }
+
+    fn debug_state(&self, data: &C) -> DebugState {
+        let mut children = vec![self.list.widget().debug_state(data)];
+        if let (Some(footer), true) = (&self.footer, self.show_footer) {
+            children.push(footer.widget().debug_state(data));
+        }
+        DebugState {
+            display_name: "InfiniteList body".to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
@@ -14,21 +14,26 @@
 
 //! A label widget.
 
+use std::f64::consts::FRAC_PI_2;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 
 use druid_shell::Cursor;
 
 use crate::debug_state::DebugState;
-use crate::kurbo::Vec2;
-use crate::text::TextStorage;
+use crate::kurbo::{Affine, Vec2};
+use crate::piet::{InterpolationMode, PietImage};
+use crate::text::{
+    movement, word_range_for_pos, AttachmentContent, Direction, Movement, ParagraphStyle,
+    Selection, TextStorage, VerticalMovement,
+};
 use crate::widget::prelude::*;
 use crate::widget::Axis;
 use crate::{
-    ArcStr, Color, Data, FontDescriptor, KeyOrValue, LocalizedString, Point, TextAlignment,
-    TextLayout,
+    commands, theme, ArcStr, Color, Data, FontDescriptor, HotKey, KbKey, KeyEvent, KeyOrValue,
+    LocalizedString, Point, SysMods, TextAlignment, TextLayout, WidgetPod,
 };
-use tracing::{instrument, trace, warn};
+use tracing::{instrument, trace};
 
 // added padding between the edges of the widget and the text.
 const LABEL_X_PADDING: f64 = 2.0;
@@ -86,12 +91,38 @@ pub struct Label<T> {
 ///
 /// This requires the `Data` to implement [`TextStorage`]; to handle static, dynamic, or
 /// localized text, use [`Label`].
+///
+/// When [`CARET_BROWSING`] is enabled in the [`Env`], the label can be focused, and
+/// shows a caret that can be moved with the keyboard or the mouse to select and copy
+/// its text.
+///
+/// A label made [selectable] can always be focused, and its text selected by dragging
+/// or double-clicking, and copied, without showing a caret. The selection stays visible,
+/// in [`SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR`], while another widget has focus.
+///
+/// [`CARET_BROWSING`]: crate::theme::CARET_BROWSING
+/// [selectable]: RawLabel::with_selectable
+/// [`SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR`]: crate::theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR
 pub struct RawLabel<T> {
     layout: TextLayout<T>,
     line_break_mode: LineBreaking,
+    rotation: TextRotation,
 
     disabled: bool,
     default_text_color: KeyOrValue<Color>,
+
+    /// Whether this label takes part in caret browsing; labels drawn by other
+    /// widgets, such as the text of a button, do not.
+    browsable: bool,
+    caret_browsing: bool,
+    selectable: bool,
+    /// Whether the label registered for focus when the focus chain was last built.
+    in_focus_chain: bool,
+    selection: Selection,
+    /// Widgets shown in the text's widget attachments, by key.
+    inline_widgets: Vec<(ArcStr, WidgetPod<(), Box<dyn Widget<()>>>)>,
+    /// The text's image attachments, created on first paint.
+    attachment_images: Vec<Option<PietImage>>,
 }
 
 /// Options for handling lines that are too wide for the label.
@@ -105,6 +136,21 @@ pub enum LineBreaking {
     Overflow,
 }
 
+/// The direction a label's text runs in.
+///
+/// No backend lays text out vertically, so text is rotated as a whole;
+/// CJK characters are turned on their side along with everything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data, Default)]
+pub enum TextRotation {
+    /// Left to right, as usual.
+    #[default]
+    None,
+    /// A quarter turn clockwise, so that the text reads from top to bottom.
+    Clockwise,
+    /// A quarter turn counterclockwise, so that the text reads from bottom to top.
+    CounterClockwise,
+}
+
 /// The text for a [`Label`].
 ///
 /// This can be one of three things; either an [`ArcStr`], a [`LocalizedString`],
@@ -147,8 +193,16 @@ impl<T: TextStorage> RawLabel<T> {
         Self {
             layout: TextLayout::new(),
             line_break_mode: LineBreaking::Overflow,
+            rotation: TextRotation::None,
             disabled: false,
             default_text_color: crate::theme::TEXT_COLOR.into(),
+            browsable: true,
+            caret_browsing: false,
+            selectable: false,
+            in_focus_chain: false,
+            selection: Selection::caret(0),
+            inline_widgets: Vec::new(),
+            attachment_images: Vec::new(),
         }
     }
 
@@ -195,6 +249,41 @@ impl<T: TextStorage> RawLabel<T> {
         self
     }
 
+    /// Builder-style method to set whether the text can be selected and copied.
+    pub fn with_selectable(mut self, selectable: bool) -> Self {
+        self.set_selectable(selectable);
+        self
+    }
+
+    /// Builder-style method to set the [`TextRotation`].
+    pub fn with_rotation(mut self, rotation: TextRotation) -> Self {
+        self.set_rotation(rotation);
+        self
+    }
+
+    /// Builder-style method to set the [`ParagraphStyle`].
+    pub fn with_paragraph_style(mut self, style: ParagraphStyle) -> Self {
+        self.set_paragraph_style(style);
+        self
+    }
+
+    /// Builder-style method to add a widget that is shown inline with the text.
+    ///
+    /// The widget is placed in the space made for it with
+    /// [`RichTextBuilder::push_widget`] and the same `key`, and is given
+    /// exactly that size. It is hidden while the text has no such space.
+    ///
+    /// [`RichTextBuilder::push_widget`]: crate::text::RichTextBuilder::push_widget
+    pub fn with_inline_widget(
+        mut self,
+        key: impl Into<ArcStr>,
+        widget: impl Widget<()> + 'static,
+    ) -> Self {
+        self.inline_widgets
+            .push((key.into(), WidgetPod::new(widget).boxed()));
+        self
+    }
+
     /// Set the text color.
     ///
     /// The argument can be either a `Color` or a [`Key<Color>`].
@@ -254,6 +343,46 @@ impl<T: TextStorage> RawLabel<T> {
         self.layout.set_text_alignment(alignment);
     }
 
+    /// Set the [`ParagraphStyle`], the spacing of lines and paragraphs.
+    ///
+    /// Text with its own style, such as [`RichText`] built with one, uses
+    /// that instead.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the label is updated.
+    ///
+    /// [`RichText`]: crate::text::RichText
+    /// [`request_layout`]: EventCtx::request_layout
+    pub fn set_paragraph_style(&mut self, style: ParagraphStyle) {
+        self.layout.set_paragraph_style(style);
+    }
+
+    /// Set the [`TextRotation`].
+    ///
+    /// Rotated text wraps at the label's maximum height rather than its width.
+    /// Inline widgets are not shown while the text is rotated.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the label is updated.
+    ///
+    /// [`request_layout`]: EventCtx::request_layout
+    pub fn set_rotation(&mut self, rotation: TextRotation) {
+        self.rotation = rotation;
+    }
+
+    /// Set whether the text can be selected with the mouse and keyboard, and copied.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_update`] so that the label can join or leave the focus chain.
+    ///
+    /// [`request_update`]: EventCtx::request_update
+    pub fn set_selectable(&mut self, selectable: bool) {
+        self.selectable = selectable;
+        if !selectable {
+            self.selection = Selection::caret(self.selection.active);
+        }
+    }
+
     /// Draw this label's text at the provided `Point`, without internal padding.
     ///
     /// This is a convenience for widgets that want to use Label as a way
@@ -268,6 +397,112 @@ impl<T: TextStorage> RawLabel<T> {
         let text_metrics = self.layout.layout_metrics();
         text_metrics.size.height - text_metrics.first_baseline
     }
+
+    /// The transform from the text's unrotated space to a widget of `size`.
+    fn text_transform(&self, size: Size) -> Affine {
+        match self.rotation {
+            TextRotation::None => Affine::IDENTITY,
+            TextRotation::Clockwise => {
+                Affine::translate((size.width, 0.0)) * Affine::rotate(FRAC_PI_2)
+            }
+            TextRotation::CounterClockwise => {
+                Affine::translate((0.0, size.height)) * Affine::rotate(-FRAC_PI_2)
+            }
+        }
+    }
+
+    /// The point in the text layout under `pos`, in a widget of `size`.
+    fn text_pos(&self, pos: Point, size: Size) -> Point {
+        self.text_transform(size).inverse() * pos - Vec2::new(LABEL_X_PADDING, 0.0)
+    }
+
+    /// Draw the text's image attachments, with the text drawn at `origin`.
+    fn paint_attachments(&mut self, ctx: &mut PaintCtx, origin: Point) {
+        let count = self.layout.attachments().count();
+        self.attachment_images.resize_with(count, || None);
+        for ((attachment, rect), cached) in self
+            .layout
+            .attachments()
+            .zip(self.attachment_images.iter_mut())
+        {
+            if let AttachmentContent::Image(image) = &attachment.content {
+                let image = cached.get_or_insert_with(|| image.to_image(ctx.render_ctx));
+                let rect = rect + origin.to_vec2();
+                ctx.draw_image(image, rect, InterpolationMode::Bilinear);
+            }
+        }
+    }
+
+    /// Exclude this label from caret browsing, for labels that are part of a control.
+    pub(crate) fn set_browsable(&mut self, browsable: bool) {
+        self.browsable = browsable;
+    }
+
+    /// Whether the text can currently be selected, because the label is
+    /// selectable or caret browsing is enabled.
+    fn is_selectable(&self) -> bool {
+        self.selectable || self.caret_browsing
+    }
+
+    fn select_all(&mut self) {
+        let len = self.layout.text().map(|text| text.as_str().len());
+        self.selection = Selection::new(0, len.unwrap_or(0));
+    }
+
+    fn copy_selection(&self) {
+        if let Some(text) = self.layout.text() {
+            if let Some(selected) = text.as_str().get(self.selection.range()) {
+                if !selected.is_empty() {
+                    crate::Application::global()
+                        .clipboard()
+                        .put_string(selected);
+                }
+            }
+        }
+    }
+
+    fn paint_selection(&self, ctx: &mut PaintCtx, origin: Point, env: &Env) {
+        let focused = ctx.is_focused();
+        if !self.selection.is_caret() && (focused || self.selectable) {
+            let color = if focused {
+                env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+            } else {
+                env.get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+            };
+            for rect in self.layout.rects_for_range(self.selection.range()) {
+                ctx.fill(rect + origin.to_vec2(), &color);
+            }
+        }
+        if focused && self.caret_browsing {
+            let line = self
+                .layout
+                .cursor_line_for_text_position(self.selection.active);
+            ctx.stroke(line + origin.to_vec2(), &env.get(theme::CURSOR_COLOR), 1.0);
+        }
+    }
+}
+
+/// The movement of the caret for a key, when caret browsing.
+fn caret_movement(key: &KeyEvent) -> Option<Movement> {
+    let by_word = if cfg!(target_os = "macos") {
+        key.mods.alt()
+    } else {
+        key.mods.ctrl()
+    };
+    let movement = match key.key {
+        KbKey::ArrowLeft if by_word => Movement::Word(Direction::Left),
+        KbKey::ArrowLeft => Movement::Grapheme(Direction::Left),
+        KbKey::ArrowRight if by_word => Movement::Word(Direction::Right),
+        KbKey::ArrowRight => Movement::Grapheme(Direction::Right),
+        KbKey::ArrowUp => Movement::Vertical(VerticalMovement::LineUp),
+        KbKey::ArrowDown => Movement::Vertical(VerticalMovement::LineDown),
+        KbKey::Home if key.mods.ctrl() => Movement::Vertical(VerticalMovement::DocumentStart),
+        KbKey::Home => Movement::Line(Direction::Upstream),
+        KbKey::End if key.mods.ctrl() => Movement::Vertical(VerticalMovement::DocumentEnd),
+        KbKey::End => Movement::Line(Direction::Downstream),
+        _ => return None,
+    };
+    Some(movement)
 }
 
 impl<T: TextStorage> Label<T> {
@@ -395,6 +630,24 @@ impl<T: Data> Label<T> {
         self
     }
 
+    /// Builder-style method to set whether the text can be selected and copied.
+    pub fn with_selectable(mut self, selectable: bool) -> Self {
+        self.label.set_selectable(selectable);
+        self
+    }
+
+    /// Builder-style method to set the [`TextRotation`].
+    pub fn with_rotation(mut self, rotation: TextRotation) -> Self {
+        self.label.set_rotation(rotation);
+        self
+    }
+
+    /// Builder-style method to set the [`ParagraphStyle`].
+    pub fn with_paragraph_style(mut self, style: ParagraphStyle) -> Self {
+        self.label.set_paragraph_style(style);
+        self
+    }
+
     /// Draw this label's text at the provided `Point`, without internal padding.
     ///
     /// This is a convenience for widgets that want to use Label as a way
@@ -462,8 +715,11 @@ impl<T: Data> LabelText<T> {
 }
 
 impl<T: Data> Widget<T> for Label<T> {
-    #[instrument(name = "Label", level = "trace", skip(self, _ctx, _event, _data, _env))]
-    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+    #[instrument(name = "Label", level = "trace", skip(self, ctx, event, _data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        let mut text = self.current_text.clone();
+        self.label.event(ctx, event, &mut text, env)
+    }
 
     #[instrument(name = "Label", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
@@ -521,43 +777,122 @@ impl<T: Data> Widget<T> for Label<T> {
         self.label
             .compute_max_intrinsic(axis, ctx, bc, &self.current_text, env)
     }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.label
+            .compute_min_intrinsic(axis, ctx, bc, &self.current_text, env)
+    }
 }
 
 impl<T: TextStorage> Widget<T> for RawLabel<T> {
-    #[instrument(
-        name = "RawLabel",
-        level = "trace",
-        skip(self, ctx, event, _data, _env)
-    )]
-    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, event, _data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        for (_, child) in &mut self.inline_widgets {
+            child.event(ctx, event, &mut (), env);
+        }
+        if ctx.is_handled() {
+            return;
+        }
         match event {
+            Event::MouseDown(event) if self.is_selectable() && event.button.is_left() => {
+                let pos = self.text_pos(event.pos, ctx.size());
+                let offset = self.layout.text_position_for_point(pos);
+                self.selection = if event.mods.shift() {
+                    Selection::new(self.selection.anchor, offset)
+                } else if event.count == 2 {
+                    let text = self.layout.text().map(|text| text.as_str()).unwrap_or("");
+                    let word = word_range_for_pos(text, offset);
+                    Selection::new(word.start, word.end)
+                } else {
+                    Selection::caret(offset)
+                };
+                ctx.request_focus();
+                ctx.set_active(true);
+                ctx.request_paint();
+            }
             Event::MouseUp(event) => {
-                // Account for the padding
-                let pos = event.pos - Vec2::new(LABEL_X_PADDING, 0.0);
-                if let Some(link) = self.layout.link_for_pos(pos) {
-                    ctx.submit_command(link.command.clone());
+                ctx.set_active(false);
+                // Account for the padding and rotation
+                let pos = self.text_pos(event.pos, ctx.size());
+                // a drag that selected text is not a click on a link
+                if self.selection.is_caret() {
+                    if let Some(link) = self.layout.link_for_pos(pos) {
+                        ctx.submit_command(link.command.clone());
+                    }
                 }
             }
             Event::MouseMove(event) => {
-                // Account for the padding
-                let pos = event.pos - Vec2::new(LABEL_X_PADDING, 0.0);
+                // Account for the padding and rotation
+                let pos = self.text_pos(event.pos, ctx.size());
 
+                if ctx.is_active() && self.is_selectable() {
+                    let offset = self.layout.text_position_for_point(pos);
+                    self.selection = Selection::new(self.selection.anchor, offset);
+                    ctx.request_paint();
+                }
                 if self.layout.link_for_pos(pos).is_some() {
                     ctx.set_cursor(&Cursor::Pointer);
+                } else if self.is_selectable() {
+                    ctx.set_cursor(&Cursor::IBeam);
                 } else {
                     ctx.clear_cursor();
                 }
             }
+            Event::KeyDown(key) if self.is_selectable() && ctx.is_focused() => {
+                if HotKey::new(SysMods::Cmd, "c").matches(key) {
+                    self.copy_selection();
+                } else if HotKey::new(SysMods::Cmd, "a").matches(key) {
+                    self.select_all();
+                } else if let Some(caret_movement) = caret_movement(key) {
+                    let modify = key.mods.shift();
+                    self.selection = movement(caret_movement, self.selection, &self.layout, modify);
+                } else {
+                    return;
+                }
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+            Event::Command(cmd)
+                if self.is_selectable() && ctx.is_focused() && cmd.is(commands::COPY) =>
+            {
+                self.copy_selection();
+                ctx.set_handled();
+            }
+            Event::Command(cmd)
+                if self.is_selectable() && ctx.is_focused() && cmd.is(commands::SELECT_ALL) =>
+            {
+                self.select_all();
+                ctx.request_paint();
+                ctx.set_handled();
+            }
             _ => {}
         }
     }
 
-    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, event, data, _env))]
-    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for (_, child) in &mut self.inline_widgets {
+            child.lifecycle(ctx, event, &(), env);
+        }
         match event {
             LifeCycle::WidgetAdded => {
                 self.layout.set_text(data.to_owned());
+                self.caret_browsing = self.browsable && env.get(theme::CARET_BROWSING);
             }
+            LifeCycle::BuildFocusChain => {
+                self.in_focus_chain = self.is_selectable();
+                if self.in_focus_chain {
+                    ctx.register_for_focus();
+                }
+            }
+            LifeCycle::FocusChanged(_) if self.is_selectable() => ctx.request_paint(),
             LifeCycle::DisabledChanged(disabled) => {
                 let color = if *disabled {
                     KeyOrValue::Key(crate::theme::DISABLED_TEXT_COLOR)
@@ -574,13 +909,29 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
     #[instrument(
         name = "RawLabel",
         level = "trace",
-        skip(self, ctx, old_data, data, _env)
+        skip(self, ctx, old_data, data, env)
     )]
-    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
         if !old_data.same(data) {
             self.layout.set_text(data.clone());
+            self.selection = self.selection.constrained(data.as_str());
+            self.attachment_images.clear();
             ctx.request_layout();
         }
+        for (_, child) in &mut self.inline_widgets {
+            child.update(ctx, &(), env);
+        }
+        let caret_browsing = self.browsable && env.get(theme::CARET_BROWSING);
+        if caret_browsing != self.caret_browsing {
+            self.caret_browsing = caret_browsing;
+            ctx.request_paint();
+        }
+        if self.is_selectable() != self.in_focus_chain {
+            // There is no other way to rebuild the focus chain, which the label
+            // joins or leaves.
+            ctx.children_changed();
+            ctx.request_paint();
+        }
         if self.layout.needs_rebuild_after_update(ctx) {
             ctx.request_layout();
         }
@@ -590,33 +941,75 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
         bc.debug_check("Label");
 
+        let rotated = self.rotation != TextRotation::None;
+        let max_width = if rotated {
+            bc.max().height
+        } else {
+            bc.max().width
+        };
         let width = match self.line_break_mode {
-            LineBreaking::WordWrap => bc.max().width - LABEL_X_PADDING * 2.0,
+            LineBreaking::WordWrap => max_width - LABEL_X_PADDING * 2.0,
             _ => f64::INFINITY,
         };
 
         self.layout.set_wrap_width(width);
         self.layout.rebuild_if_needed(ctx.text(), env);
 
+        for (key, child) in &mut self.inline_widgets {
+            let rect = self.layout.attachments().find_map(|(attachment, rect)| {
+                match &attachment.content {
+                    AttachmentContent::Widget(k) if k == key => Some(rect),
+                    _ => None,
+                }
+            });
+            let rect = rect.filter(|_| !rotated).unwrap_or_default();
+            child.layout(ctx, &BoxConstraints::tight(rect.size()), &(), env);
+            // The bottom of the attachment is on the text's baseline; put the
+            // widget's baseline there instead.
+            let origin = rect.origin() + Vec2::new(LABEL_X_PADDING, child.baseline_offset());
+            child.set_origin(ctx, origin);
+        }
+
         let text_metrics = self.layout.layout_metrics();
-        ctx.set_baseline_offset(text_metrics.size.height - text_metrics.first_baseline);
-        let size = bc.constrain(Size::new(
+        let text_size = Size::new(
             text_metrics.size.width + 2. * LABEL_X_PADDING,
             text_metrics.size.height,
-        ));
+        );
+        let size = if rotated {
+            // Rotated text has no baseline to align with its neighbours.
+            ctx.set_baseline_offset(0.0);
+            bc.constrain(Size::new(text_size.height, text_size.width))
+        } else {
+            ctx.set_baseline_offset(text_metrics.size.height - text_metrics.first_baseline);
+            bc.constrain(text_size)
+        };
         trace!("Computed size: {}", size);
         size
     }
 
-    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, _data, _env))]
-    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, _env: &Env) {
+    #[instrument(name = "RawLabel", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         let origin = Point::new(LABEL_X_PADDING, 0.0);
         let label_size = ctx.size();
+        let text_size = match self.rotation {
+            TextRotation::None => label_size,
+            _ => Size::new(label_size.height, label_size.width),
+        };
 
-        if self.line_break_mode == LineBreaking::Clip {
-            ctx.clip(label_size.to_rect());
+        ctx.with_save(|ctx| {
+            ctx.transform(self.text_transform(label_size));
+            if self.line_break_mode == LineBreaking::Clip {
+                ctx.clip(text_size.to_rect());
+            }
+            if self.is_selectable() {
+                self.paint_selection(ctx, origin, env);
+            }
+            self.draw_at(ctx, origin);
+            self.paint_attachments(ctx, origin);
+        });
+        for (_, child) in &mut self.inline_widgets {
+            child.paint(ctx, &(), env);
         }
-        self.draw_at(ctx, origin)
     }
 
     fn compute_max_intrinsic(
@@ -627,6 +1020,9 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
         data: &T,
         env: &Env,
     ) -> f64 {
+        if self.rotation != TextRotation::None {
+            return axis.major(self.layout(ctx, bc, data, env));
+        }
         match axis {
             Axis::Horizontal => {
                 match self.line_break_mode {
@@ -641,10 +1037,33 @@ impl<T: TextStorage> Widget<T> for RawLabel<T> {
                     _ => self.layout(ctx, bc, data, env).width,
                 }
             }
-            Axis::Vertical => {
-                warn!("Max intrinsic height of a label is not implemented.");
-                0.
+            // The height of a label is the height of its text, wrapped to the available width.
+            Axis::Vertical => self.layout(ctx, bc, data, env).height,
+        }
+    }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        if self.rotation != TextRotation::None {
+            return self.compute_max_intrinsic(axis, ctx, bc, data, env);
+        }
+        match (axis, self.line_break_mode) {
+            (Axis::Horizontal, LineBreaking::WordWrap) => {
+                // Wrapping at every opportunity leaves the longest word as the widest line.
+                self.layout.set_wrap_width(0.0);
+                self.layout.rebuild_if_needed(ctx.text(), env);
+                let width = self.layout.layout_metrics().size.width + 2. * LABEL_X_PADDING;
+                bc.constrain((width, 0.0)).width
             }
+            // Clipped text can be made as narrow as the constraints allow.
+            (Axis::Horizontal, LineBreaking::Clip) => bc.min().width,
+            _ => self.compute_max_intrinsic(axis, ctx, bc, data, env),
         }
     }
 }
@@ -18,7 +18,7 @@ use std::marker::PhantomData;
 
 use crate::debug_state::DebugState;
 use crate::widget::prelude::*;
-use crate::widget::WidgetWrapper;
+use crate::widget::{Axis, WidgetWrapper};
 use crate::{Data, Lens};
 
 use tracing::{instrument, trace};
@@ -129,6 +129,34 @@ where
         self.lens.with(data, |data| child.paint(ctx, data, env));
     }
 
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let child = &mut self.child;
+        self.lens.with(data, |data| {
+            child.compute_max_intrinsic(axis, ctx, bc, data, env)
+        })
+    }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let child = &mut self.child;
+        self.lens.with(data, |data| {
+            child.compute_min_intrinsic(axis, ctx, bc, data, env)
+        })
+    }
+
     fn id(&self) -> Option<WidgetId> {
         self.child.id()
     }
@@ -14,34 +14,117 @@
 
 //! Simple list view widget.
 
+use std::any::Any;
 use std::cmp::Ordering;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::f64;
+use std::hash::Hash;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use tracing::{instrument, trace};
+use tracing::{instrument, trace, warn};
 
 #[cfg(feature = "im")]
-use crate::im::{OrdMap, Vector};
+use crate::im::{HashMap as ImHashMap, OrdMap, Vector};
+#[cfg(feature = "im")]
+use crate::{lens, Lens};
 
 use crate::kurbo::{Point, Rect, Size};
 
 use crate::debug_state::DebugState;
 use crate::{
-    widget::Axis, BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
-    LifeCycleCtx, PaintCtx, UpdateCtx, Widget, WidgetPod,
+    widget::Axis, BoxConstraints, Command, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx,
+    LifeCycle, LifeCycleCtx, Notification, PaintCtx, Selector, SingleUse, UpdateCtx, Widget,
+    WidgetPod,
 };
 
+const INSERT_ITEM: Selector<InsertItem> = Selector::new("druid-builtin.list-insert-item");
+
 /// A list widget for a variable-size collection of items.
+///
+/// By default the children are matched to the items by their position, so
+/// when an item is inserted or removed, the children after it show different
+/// items than before, and keep state (such as focus or a scroll offset) that
+/// belonged to another item. Use [`with_key`] to match children to items by
+/// a key instead.
+///
+/// Children can edit the collection around their own item, without knowing
+/// its index, by submitting [`REMOVE_ITEM`], [`MOVE_ITEM`], or the
+/// notifications made by [`insert_before`] and [`insert_after`].
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use druid::widget::{Button, Flex, Label, List};
+/// # use druid::{Widget, WidgetExt};
+/// fn todos() -> impl Widget<Arc<Vec<String>>> {
+///     List::new(|| {
+///         Flex::row()
+///             .with_child(Label::dynamic(|todo: &String, _| todo.clone()))
+///             .with_child(Button::new("Up").on_click(|ctx, _, _| {
+///                 ctx.submit_notification(List::MOVE_ITEM.with(-1))
+///             }))
+///             .with_child(Button::new("Delete").on_click(|ctx, _, _| {
+///                 ctx.submit_notification(List::REMOVE_ITEM)
+///             }))
+///     })
+/// }
+/// ```
+///
+/// [`with_key`]: List::with_key
+/// [`REMOVE_ITEM`]: List::REMOVE_ITEM
+/// [`MOVE_ITEM`]: List::MOVE_ITEM
+/// [`insert_before`]: List::insert_before
+/// [`insert_after`]: List::insert_after
 pub struct List<T> {
     closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
     children: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+    /// The key of each child, if the list is keyed.
+    keys: Option<Box<dyn ChildKeys<T>>>,
     axis: Axis,
     spacing: KeyOrValue<f64>,
     old_bc: BoxConstraints,
 }
 
+/// The payload of the notifications made by [`List::insert_before`] and
+/// [`List::insert_after`].
+struct InsertItem {
+    after: bool,
+    item: SingleUse<Box<dyn Any>>,
+}
+
+impl List<()> {
+    /// Remove the item of the child that submits this notification.
+    pub const REMOVE_ITEM: Selector = Selector::new("druid-builtin.list-remove-item");
+
+    /// Move the item of the child that submits this notification by the
+    /// given number of places; negative numbers move it towards the start.
+    ///
+    /// The item stops at the start or end of the list.
+    pub const MOVE_ITEM: Selector<isize> = Selector::new("druid-builtin.list-move-item");
+
+    /// Create a notification that inserts `item` before the item of the
+    /// child that submits it.
+    ///
+    /// `item` must be of the list's item type.
+    pub fn insert_before<T: Any>(item: T) -> Command {
+        INSERT_ITEM.with(InsertItem {
+            after: false,
+            item: SingleUse::new(Box::new(item)),
+        })
+    }
+
+    /// Create a notification that inserts `item` after the item of the
+    /// child that submits it.
+    ///
+    /// `item` must be of the list's item type.
+    pub fn insert_after<T: Any>(item: T) -> Command {
+        INSERT_ITEM.with(InsertItem {
+            after: true,
+            item: SingleUse::new(Box::new(item)),
+        })
+    }
+}
+
 impl<T: Data> List<T> {
     /// Create a new list widget. Closure will be called every time when a new child
     /// needs to be constructed.
@@ -49,6 +132,7 @@ impl<T: Data> List<T> {
         List {
             closure: Box::new(move || Box::new(closure())),
             children: Vec::new(),
+            keys: None,
             axis: Axis::Vertical,
             spacing: KeyOrValue::Concrete(0.),
             old_bc: BoxConstraints::tight(Size::ZERO),
@@ -73,10 +157,39 @@ impl<T: Data> List<T> {
         self
     }
 
+    /// Builder-style method to match children to items by the key that
+    /// `key` returns for each item, rather than by position.
+    ///
+    /// When items are inserted, removed or reordered, each child moves along
+    /// with its item, keeping its state. Keys should be unique; children of
+    /// items with the same key are matched up in order.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use druid::widget::{Label, List};
+    /// # use druid::Widget;
+    /// // Each item is an id and a name.
+    /// fn people() -> impl Widget<Arc<Vec<(u64, String)>>> {
+    ///     List::new(|| Label::dynamic(|(_, name): &(u64, String), _| name.clone()))
+    ///         .with_key(|(id, _)| *id)
+    /// }
+    /// ```
+    pub fn with_key<K: Hash + Eq + 'static>(mut self, key: impl Fn(&T) -> K + 'static) -> Self {
+        self.keys = Some(Box::new(Keys {
+            key_fn: Box::new(key),
+            keys: Vec::new(),
+            next: Vec::new(),
+        }));
+        self
+    }
+
     /// When the widget is created or the data changes, create or remove children as needed
     ///
     /// Returns `true` if children were added or removed.
     fn update_child_count(&mut self, data: &impl ListIter<T>, _env: &Env) -> bool {
+        if self.keys.is_some() {
+            return self.update_keyed_children(data).is_some();
+        }
         let len = self.children.len();
         match len.cmp(&data.data_len()) {
             Ordering::Greater => self.children.truncate(data.data_len()),
@@ -90,6 +203,111 @@ impl<T: Data> List<T> {
         }
         len != data.data_len()
     }
+
+    /// Match the children of a keyed list to the items by key, creating and
+    /// removing children as needed.
+    ///
+    /// Returns `None` if nothing changed, or otherwise whether each child was
+    /// newly created.
+    fn update_keyed_children(&mut self, data: &impl ListIter<T>) -> Option<Vec<bool>> {
+        let keys = self.keys.as_mut()?;
+        data.for_each(|item, _| keys.push_item(item));
+        keys.rematch(&mut self.children, &self.closure)
+    }
+
+    /// Apply an edit requested by one of the children.
+    fn edit_item(&self, ctx: &mut EventCtx, note: &Notification, data: &mut impl ListIter<T>) {
+        let index = match self.children.iter().position(|c| c.id() == note.route()) {
+            Some(index) => index,
+            None => return,
+        };
+        let edited = if note.is(List::REMOVE_ITEM) {
+            data.data_remove(index)
+        } else if let Some(offset) = note.get(List::MOVE_ITEM) {
+            let last = data.data_len().saturating_sub(1);
+            data.data_move(index, index.saturating_add_signed(*offset).min(last))
+        } else if let Some(insert) = note.get(INSERT_ITEM) {
+            match insert.item.take().map(|item| item.downcast::<T>()) {
+                Some(Ok(item)) => data.data_insert(index + usize::from(insert.after), *item),
+                _ => {
+                    warn!(
+                        "item to insert into List is not a {}",
+                        std::any::type_name::<T>()
+                    );
+                    true
+                }
+            }
+        } else {
+            return;
+        };
+        ctx.set_handled();
+        if !edited {
+            warn!("List data does not support this edit");
+        }
+    }
+}
+
+/// The keys of the children of a keyed [`List`], whatever their type.
+trait ChildKeys<T> {
+    /// Record the key of the next item.
+    fn push_item(&mut self, item: &T);
+
+    /// Match `children` to the items recorded since the last call, creating and
+    /// removing children as needed.
+    ///
+    /// Returns `None` if nothing changed, or otherwise whether each child was
+    /// newly created.
+    fn rematch(
+        &mut self,
+        children: &mut Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+        new_child: &dyn Fn() -> Box<dyn Widget<T>>,
+    ) -> Option<Vec<bool>>;
+}
+
+struct Keys<T, K> {
+    key_fn: Box<dyn Fn(&T) -> K>,
+    /// The key of each child.
+    keys: Vec<K>,
+    /// The keys of the items, as they are recorded.
+    next: Vec<K>,
+}
+
+impl<T, K: Hash + Eq> ChildKeys<T> for Keys<T, K> {
+    fn push_item(&mut self, item: &T) {
+        self.next.push((self.key_fn)(item));
+    }
+
+    fn rematch(
+        &mut self,
+        children: &mut Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+        new_child: &dyn Fn() -> Box<dyn Widget<T>>,
+    ) -> Option<Vec<bool>> {
+        let keys = std::mem::take(&mut self.next);
+        if keys == self.keys && children.len() == keys.len() {
+            return None;
+        }
+
+        let mut old: HashMap<K, Vec<_>> = HashMap::new();
+        // Reversed, so that popping gives duplicate keys in their original order.
+        for (key, child) in self.keys.drain(..).zip(children.drain(..)).rev() {
+            old.entry(key).or_default().push(child);
+        }
+        let mut fresh = Vec::with_capacity(keys.len());
+        for key in &keys {
+            match old.get_mut(key).and_then(Vec::pop) {
+                Some(child) => {
+                    children.push(child);
+                    fresh.push(false);
+                }
+                None => {
+                    children.push(WidgetPod::new(new_child()));
+                    fresh.push(true);
+                }
+            }
+        }
+        self.keys = keys;
+        Some(fresh)
+    }
 }
 
 /// This iterator enables writing List widget for any `Data`.
@@ -102,6 +320,34 @@ pub trait ListIter<T>: Data {
 
     /// Return data length.
     fn data_len(&self) -> usize;
+
+    /// Remove the item at `index`.
+    ///
+    /// Returns `false` if the item was not removed, for instance because
+    /// removing items is not supported. The default implementation does nothing.
+    fn data_remove(&mut self, index: usize) -> bool {
+        let _ = index;
+        false
+    }
+
+    /// Insert `item` at `index`.
+    ///
+    /// Returns `false` if the item was not inserted, for instance because
+    /// inserting items is not supported. The default implementation does nothing.
+    fn data_insert(&mut self, index: usize, item: T) -> bool {
+        let _ = (index, item);
+        false
+    }
+
+    /// Move the item at `from` to `to`.
+    ///
+    /// Returns `false` if the item was not moved, for instance because the
+    /// order is not under the collection's control. The default
+    /// implementation does nothing.
+    fn data_move(&mut self, from: usize, to: usize) -> bool {
+        let _ = (from, to);
+        false
+    }
 }
 #[cfg(feature = "im")]
 impl<T: Data> ListIter<T> for Vector<T> {
@@ -124,10 +370,33 @@ impl<T: Data> ListIter<T> for Vector<T> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        if index >= self.len() {
+            return false;
+        }
+        self.remove(index);
+        true
+    }
+
+    fn data_insert(&mut self, index: usize, item: T) -> bool {
+        if index > self.len() {
+            return false;
+        }
+        self.insert(index, item);
+        true
+    }
+
+    fn data_move(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.len() || to >= self.len() {
+            return false;
+        }
+        let item = self.remove(from);
+        self.insert(to, item);
+        true
+    }
 }
 
-//An implementation for ListIter<(K, V)> has been omitted due to problems
-//with how the List Widget handles the reordering of its data.
 #[cfg(feature = "im")]
 impl<K, V> ListIter<V> for OrdMap<K, V>
 where
@@ -155,6 +424,158 @@ where
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        match self.keys().nth(index).cloned() {
+            Some(key) => self.remove(&key).is_some(),
+            None => false,
+        }
+    }
+}
+
+/// The entries of a map, which a [`List`] iterates over as `(key, value)`
+/// pairs in key order.
+///
+/// Changes to the keys are discarded. Use this with [`List::with_key`], so
+/// that children stay with their entries when entries are added or removed.
+///
+/// ```
+/// # use druid::im::OrdMap;
+/// # use druid::widget::{Label, List, MapEntries};
+/// # use druid::{Widget, WidgetExt};
+/// fn scores() -> impl Widget<OrdMap<String, u32>> {
+///     List::new(|| Label::dynamic(|(name, score): &(String, u32), _| format!("{name}: {score}")))
+///         .with_key(|(name, _): &(String, u32)| name.clone())
+///         .lens(MapEntries::lens())
+/// }
+/// ```
+#[cfg(feature = "im")]
+#[derive(Clone, Debug)]
+pub struct MapEntries<M>(pub M);
+
+#[cfg(feature = "im")]
+impl<M: Data> MapEntries<M> {
+    /// A lens from a map to its entries.
+    pub fn lens() -> impl Lens<M, MapEntries<M>> {
+        lens::Map::new(
+            |map: &M| MapEntries(map.clone()),
+            |map: &mut M, entries: MapEntries<M>| *map = entries.0,
+        )
+    }
+}
+
+#[cfg(feature = "im")]
+impl<M: Data> Data for MapEntries<M> {
+    fn same(&self, other: &Self) -> bool {
+        self.0.same(&other.0)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<K, V> ListIter<(K, V)> for MapEntries<OrdMap<K, V>>
+where
+    K: Data + Ord,
+    V: Data,
+{
+    fn for_each(&self, mut cb: impl FnMut(&(K, V), usize)) {
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            cb(&(key.clone(), value.clone()), i);
+        }
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut (K, V), usize)) {
+        for (i, (key, value)) in self.0.clone().iter().enumerate() {
+            let mut entry = (key.clone(), value.clone());
+            cb(&mut entry, i);
+            if !value.same(&entry.1) {
+                self.0[key] = entry.1;
+            }
+        }
+    }
+
+    fn data_len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        self.0.data_remove(index)
+    }
+}
+
+/// Iterates over the values in key order.
+#[cfg(feature = "im")]
+impl<K, V> ListIter<V> for ImHashMap<K, V>
+where
+    K: Data + Ord + Hash,
+    V: Data,
+{
+    fn for_each(&self, mut cb: impl FnMut(&V, usize)) {
+        for (i, key) in sorted_keys(self).iter().enumerate() {
+            cb(&self[key], i);
+        }
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut V, usize)) {
+        for (i, key) in sorted_keys(self).into_iter().enumerate() {
+            let value = &self[&key];
+            let mut new_value = value.clone();
+            cb(&mut new_value, i);
+            if !value.same(&new_value) {
+                self.insert(key, new_value);
+            }
+        }
+    }
+
+    fn data_len(&self) -> usize {
+        self.len()
+    }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        match sorted_keys(self).get(index) {
+            Some(key) => self.remove(key).is_some(),
+            None => false,
+        }
+    }
+}
+
+#[cfg(feature = "im")]
+impl<K, V> ListIter<(K, V)> for MapEntries<ImHashMap<K, V>>
+where
+    K: Data + Ord + Hash,
+    V: Data,
+{
+    fn for_each(&self, mut cb: impl FnMut(&(K, V), usize)) {
+        for (i, key) in sorted_keys(&self.0).into_iter().enumerate() {
+            let value = self.0[&key].clone();
+            cb(&(key, value), i);
+        }
+    }
+
+    fn for_each_mut(&mut self, mut cb: impl FnMut(&mut (K, V), usize)) {
+        for (i, key) in sorted_keys(&self.0).into_iter().enumerate() {
+            let value = self.0[&key].clone();
+            let mut entry = (key.clone(), value.clone());
+            cb(&mut entry, i);
+            if !value.same(&entry.1) {
+                self.0.insert(key, entry.1);
+            }
+        }
+    }
+
+    fn data_len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        self.0.data_remove(index)
+    }
+}
+
+#[cfg(feature = "im")]
+fn sorted_keys<K: Clone + Ord + Hash, V: Clone>(map: &ImHashMap<K, V>) -> Vec<K> {
+    let mut keys: Vec<K> = map.keys().cloned().collect();
+    keys.sort();
+    keys
 }
 
 // S == shared data type
@@ -184,6 +605,19 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Vector<T>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        self.1.data_remove(index)
+    }
+
+    /// The shared part of `item` is ignored.
+    fn data_insert(&mut self, index: usize, item: (S, T)) -> bool {
+        self.1.data_insert(index, item.1)
+    }
+
+    fn data_move(&mut self, from: usize, to: usize) -> bool {
+        self.1.data_move(from, to)
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<Vec<T>> {
@@ -221,6 +655,34 @@ impl<T: Data> ListIter<T> for Arc<Vec<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        if index >= self.len() {
+            return false;
+        }
+        Arc::make_mut(self).remove(index);
+        true
+    }
+
+    fn data_insert(&mut self, index: usize, item: T) -> bool {
+        if index > self.len() {
+            return false;
+        }
+        Arc::make_mut(self).insert(index, item);
+        true
+    }
+
+    fn data_move(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.len() || to >= self.len() {
+            return false;
+        }
+        if from != to {
+            let items = Arc::make_mut(self);
+            let item = items.remove(from);
+            items.insert(to, item);
+        }
+        true
+    }
 }
 
 // S == shared data type
@@ -262,6 +724,19 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Arc<Vec<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        self.1.data_remove(index)
+    }
+
+    /// The shared part of `item` is ignored.
+    fn data_insert(&mut self, index: usize, item: (S, T)) -> bool {
+        self.1.data_insert(index, item.1)
+    }
+
+    fn data_move(&mut self, from: usize, to: usize) -> bool {
+        self.1.data_move(from, to)
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<VecDeque<T>> {
@@ -299,6 +774,35 @@ impl<T: Data> ListIter<T> for Arc<VecDeque<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        if index >= self.len() {
+            return false;
+        }
+        Arc::make_mut(self).remove(index);
+        true
+    }
+
+    fn data_insert(&mut self, index: usize, item: T) -> bool {
+        if index > self.len() {
+            return false;
+        }
+        Arc::make_mut(self).insert(index, item);
+        true
+    }
+
+    fn data_move(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.len() || to >= self.len() {
+            return false;
+        }
+        if from != to {
+            let items = Arc::make_mut(self);
+            if let Some(item) = items.remove(from) {
+                items.insert(to, item);
+            }
+        }
+        true
+    }
 }
 
 // S == shared data type
@@ -340,11 +844,28 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Arc<VecDeque<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn data_remove(&mut self, index: usize) -> bool {
+        self.1.data_remove(index)
+    }
+
+    /// The shared part of `item` is ignored.
+    fn data_insert(&mut self, index: usize, item: (S, T)) -> bool {
+        self.1.data_insert(index, item.1)
+    }
+
+    fn data_move(&mut self, from: usize, to: usize) -> bool {
+        self.1.data_move(from, to)
+    }
 }
 
 impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
     #[instrument(name = "List", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Notification(note) = event {
+            self.edit_item(ctx, note, data);
+            return;
+        }
         let mut children = self.children.iter_mut();
         data.for_each_mut(|child_data, _| {
             if let Some(child) = children.next() {
@@ -371,6 +892,27 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
 
     #[instrument(name = "List", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if self.keys.is_some() {
+            // children are matched to their items first, and only children
+            // that existed before are updated.
+            let fresh = self.update_keyed_children(data);
+            let mut children = self.children.iter_mut().enumerate();
+            data.for_each(|child_data, _| {
+                if let Some((i, child)) = children.next() {
+                    if !fresh.as_ref().is_some_and(|fresh| fresh[i]) {
+                        child.update(ctx, child_data, env);
+                    }
+                }
+            });
+            if fresh.is_some() {
+                ctx.children_changed();
+            }
+            if ctx.env_key_changed(&self.spacing) {
+                ctx.request_layout();
+            }
+            return;
+        }
+
         // we send update to children first, before adding or removing children;
         // this way we avoid sending update to newly added children, at the cost
         // of potentially updating children that are going to be removed.
@@ -460,3 +1002,43 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::Label;
+
+    #[test]
+    fn keyed_children_follow_their_items() {
+        let mut list =
+            List::new(|| Label::dynamic(|n: &u32, _| n.to_string())).with_key(|n: &u32| *n);
+        let data = Arc::new(vec![1, 2]);
+        assert_eq!(list.update_keyed_children(&data), Some(vec![true, true]));
+        assert_eq!(list.update_keyed_children(&data), None);
+        let ids: Vec<_> = list.children.iter().map(|child| child.id()).collect();
+
+        let data = Arc::new(vec![0, 1, 2]);
+        assert_eq!(
+            list.update_keyed_children(&data),
+            Some(vec![true, false, false])
+        );
+        assert_eq!(list.children[1].id(), ids[0]);
+        assert_eq!(list.children[2].id(), ids[1]);
+    }
+
+    #[test]
+    fn edit_items() {
+        let mut data = Arc::new(vec![1, 2, 3]);
+        assert!(data.data_move(0, 2));
+        assert_eq!(*data, vec![2, 3, 1]);
+        assert!(data.data_remove(1));
+        assert!(data.data_insert(2, 4));
+        assert_eq!(*data, vec![2, 1, 4]);
+        assert!(!data.data_remove(3));
+        assert!(!data.data_insert(4, 5));
+
+        let mut shared = (0, data);
+        assert!(shared.data_insert(0, (9, 0)));
+        assert_eq!(shared, (0, Arc::new(vec![0, 2, 1, 4])));
+    }
+}
@@ -0,0 +1,347 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that renders Markdown.
+
+use pulldown_cmark::{Event as ParseEvent, Options, Parser, Tag};
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::piet::{FontFamily, FontStyle, FontWeight};
+use crate::text::{AttributesAdder, RichText, RichTextBuilder, TextStorage};
+use crate::widget::prelude::*;
+use crate::widget::{Axis, LineBreaking, RawLabel};
+use crate::{theme, Selector};
+
+/// The indentation of each level of a nested list.
+const LIST_INDENT: &str = "    ";
+
+/// A widget that renders [CommonMark] text.
+///
+/// The data is the Markdown source, which is parsed and displayed as [`RichText`]
+/// whenever it changes. Headings, emphasis, lists, code, block quotes and links are
+/// supported; other constructs are displayed as plain text.
+///
+/// Clicking a link submits [`Markdown::OPEN_LINK`] with the link destination as payload;
+/// the application is responsible for handling it, for instance in an [`AppDelegate`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Markdown;
+/// use druid::Widget;
+///
+/// fn notes() -> impl Widget<String> {
+///     Markdown::new()
+/// }
+/// ```
+///
+/// [CommonMark]: https://commonmark.org
+/// [`AppDelegate`]: crate::AppDelegate
+pub struct Markdown {
+    label: RawLabel<RichText>,
+    rendered: RichText,
+}
+
+impl Markdown {
+    /// The command submitted when a link is clicked; the payload is the link destination.
+    pub const OPEN_LINK: Selector<String> = Selector::new("druid-builtin.markdown-open-link");
+
+    /// Create a new `Markdown` widget.
+    pub fn new() -> Self {
+        Markdown {
+            label: RawLabel::new().with_line_break_mode(LineBreaking::WordWrap),
+            rendered: RichText::new("".into()),
+        }
+    }
+}
+
+impl Default for Markdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Incrementally builds the [`RichText`] for a Markdown document.
+struct Renderer<'a> {
+    builder: RichTextBuilder,
+    len: usize,
+    /// Newlines owed before the next text, so that the document doesn't end with them.
+    pending_newlines: usize,
+    /// The number of newlines at the end of the text so far.
+    trailing_newlines: usize,
+    /// Whether the last thing pushed was a list item's bullet.
+    item_start: bool,
+    tags: Vec<(usize, Tag<'a>)>,
+    /// The next number of each open list, `None` for bulleted lists.
+    lists: Vec<Option<u64>>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new() -> Self {
+        Renderer {
+            builder: RichTextBuilder::new(),
+            len: 0,
+            pending_newlines: 0,
+            trailing_newlines: 0,
+            item_start: false,
+            tags: Vec::new(),
+            lists: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, text: &str) -> AttributesAdder<'_> {
+        self.flush_newlines();
+        self.item_start = false;
+        if !text.is_empty() {
+            self.trailing_newlines = 0;
+        }
+        self.len += text.len();
+        self.builder.push(text)
+    }
+
+    fn flush_newlines(&mut self) {
+        if self.pending_newlines > 0 {
+            self.len += self.pending_newlines;
+            self.builder.push(&"\n".repeat(self.pending_newlines));
+            self.trailing_newlines += self.pending_newlines;
+            self.pending_newlines = 0;
+        }
+    }
+
+    /// Separate the previous block from the next one.
+    ///
+    /// Blocks inside lists are only separated by a line break, so that lists stay compact.
+    fn break_block(&mut self) {
+        if self.len > 0 && !self.item_start {
+            let newlines: usize = if self.lists.is_empty() { 2 } else { 1 };
+            let missing = newlines.saturating_sub(self.trailing_newlines);
+            self.pending_newlines = self.pending_newlines.max(missing);
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'a>) {
+        match &tag {
+            Tag::Paragraph | Tag::Heading(_) | Tag::BlockQuote | Tag::CodeBlock(_) => {
+                self.break_block()
+            }
+            Tag::List(first) => {
+                self.break_block();
+                self.lists.push(*first);
+            }
+            Tag::Item => {
+                self.break_block();
+                let depth = self.lists.len().saturating_sub(1);
+                let bullet = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.push(&format!("{}{}", LIST_INDENT.repeat(depth), bullet));
+                self.item_start = true;
+            }
+            _ => (),
+        }
+        // Newlines owed to the previous block should not take this tag's attributes.
+        self.flush_newlines();
+        self.tags.push((self.len, tag));
+    }
+
+    fn end(&mut self) {
+        let (start, tag) = self
+            .tags
+            .pop()
+            .expect("parser does not return unbalanced tags");
+        let mut attrs = self.builder.add_attributes_for_range(start..self.len);
+        match &tag {
+            Tag::Heading(level) => {
+                let size = match level {
+                    1 => 28.0,
+                    2 => 24.0,
+                    3 => 20.0,
+                    4 => 17.0,
+                    _ => 15.0,
+                };
+                attrs.size(size).weight(FontWeight::BOLD);
+            }
+            Tag::BlockQuote => {
+                attrs
+                    .style(FontStyle::Italic)
+                    .text_color(theme::PLACEHOLDER_COLOR);
+            }
+            Tag::CodeBlock(_) => {
+                attrs.font_family(FontFamily::MONOSPACE);
+            }
+            Tag::Emphasis => {
+                attrs.style(FontStyle::Italic);
+            }
+            Tag::Strong => {
+                attrs.weight(FontWeight::BOLD);
+            }
+            Tag::Strikethrough => {
+                attrs.strikethrough(true);
+            }
+            Tag::Link(_, destination, _) => {
+                attrs.link(Markdown::OPEN_LINK.with(destination.to_string()));
+            }
+            _ => (),
+        }
+        match tag {
+            Tag::List(_) => {
+                self.lists.pop();
+                self.break_block();
+            }
+            Tag::Paragraph | Tag::Heading(_) | Tag::BlockQuote | Tag::CodeBlock(_) => {
+                self.break_block()
+            }
+            Tag::Item if self.trailing_newlines == 0 => {
+                self.pending_newlines = self.pending_newlines.max(1)
+            }
+            _ => (),
+        }
+    }
+
+    fn render(mut self, source: &'a str) -> RichText {
+        for event in Parser::new_ext(source, Options::ENABLE_STRIKETHROUGH) {
+            match event {
+                ParseEvent::Start(tag) => self.start(tag),
+                ParseEvent::End(_) => self.end(),
+                ParseEvent::Text(text) => {
+                    // Lines of code blocks end with a newline, which is deferred so that
+                    // it merges with the one separating the code block from the next block.
+                    let in_code_block = matches!(self.tags.last(), Some((_, Tag::CodeBlock(_))));
+                    match text.strip_suffix('\n') {
+                        Some(line) if in_code_block => {
+                            self.push(line);
+                            self.pending_newlines = 1;
+                        }
+                        _ => {
+                            self.push(&text);
+                        }
+                    }
+                }
+                ParseEvent::Code(text) => {
+                    self.push(&text).font_family(FontFamily::MONOSPACE);
+                }
+                ParseEvent::Html(text) => {
+                    self.push(&text)
+                        .font_family(FontFamily::MONOSPACE)
+                        .text_color(theme::PLACEHOLDER_COLOR);
+                }
+                ParseEvent::SoftBreak => {
+                    self.push(" ");
+                }
+                ParseEvent::HardBreak => {
+                    self.push("\n");
+                }
+                ParseEvent::Rule => {
+                    self.break_block();
+                    self.push("⸻");
+                    self.break_block();
+                }
+                _ => (),
+            }
+        }
+        self.builder.build()
+    }
+}
+
+/// Parse Markdown `source` into [`RichText`].
+fn render(source: &str) -> RichText {
+    Renderer::new().render(source)
+}
+
+impl<T: TextStorage> Widget<T> for Markdown {
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, event, _data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        let mut rendered = self.rendered.clone();
+        self.label.event(ctx, event, &mut rendered, env)
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.rendered = render(data.as_str());
+        }
+        self.label.lifecycle(ctx, event, &self.rendered, env)
+    }
+
+    #[instrument(
+        name = "Markdown",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) {
+            let rendered = render(data.as_str());
+            self.label.update(ctx, &self.rendered, &rendered, env);
+            self.rendered = rendered;
+        } else {
+            self.label.update(ctx, &self.rendered, &self.rendered, env);
+        }
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        self.label.layout(ctx, bc, &self.rendered, env)
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        self.label.paint(ctx, &self.rendered, env)
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Markdown".to_string(),
+            main_value: data.as_str().to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.label
+            .compute_max_intrinsic(axis, ctx, bc, &self.rendered, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piet::TextStorage as _;
+
+    #[test]
+    fn blocks_are_separated() {
+        let rendered = render("# Title\n\nSome *text*.\n\n```\nlet x = 1;\n```\n");
+        assert_eq!(rendered.as_str(), "Title\n\nSome text.\n\nlet x = 1;");
+    }
+
+    #[test]
+    fn lists() {
+        let rendered = render("Items:\n\n- one\n- two\n  1. first\n  2. second\n\nDone");
+        assert_eq!(
+            rendered.as_str(),
+            "Items:\n\n• one\n• two\n    1. first\n    2. second\n\nDone"
+        );
+    }
+}
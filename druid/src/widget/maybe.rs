@@ -126,11 +126,13 @@ impl<T: Data> Widget<Option<T>> for Maybe<T> {
             Some(d) => self.widget.with_some(|w| {
                 let size = w.layout(ctx, bc, d, env);
                 w.set_origin(ctx, Point::ORIGIN);
+                ctx.set_baseline_offset(w.baseline_offset());
                 size
             }),
             None => self.widget.with_none(|w| {
                 let size = w.layout(ctx, bc, &(), env);
                 w.set_origin(ctx, Point::ORIGIN);
+                ctx.set_baseline_offset(w.baseline_offset());
                 size
             }),
         }
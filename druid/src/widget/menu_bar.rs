@@ -0,0 +1,925 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A menu bar drawn inside the window.
+
+use std::ops::Range;
+
+use tracing::{instrument, trace};
+
+use crate::core::CommandQueue;
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Line};
+use crate::menu::{MenuItemId, MenuManager, MenuNode};
+use crate::widget::prelude::*;
+use crate::{
+    theme, ArcStr, Color, Data, HotKey, KbKey, KeyEvent, Menu, Modifiers, Point, Rect, TextLayout,
+    WidgetPod, WindowId,
+};
+
+/// The horizontal padding around the title of a menu in the bar.
+const TITLE_PADDING: f64 = 8.0;
+const ITEM_HEIGHT: f64 = 24.0;
+const SEPARATOR_HEIGHT: f64 = 9.0;
+/// The space to the left of item labels, which holds the check mark of selected items.
+const ITEM_INSET: f64 = 24.0;
+/// The space between the label and the shortcut of an item.
+const SHORTCUT_GAP: f64 = 24.0;
+const DROPDOWN_PADDING: f64 = 4.0;
+const DROPDOWN_MIN_WIDTH: f64 = 160.0;
+
+/// A label whose text may mark a mnemonic with `&`, like the titles of platform menus.
+struct MnemonicLabel {
+    layout: TextLayout<ArcStr>,
+    mnemonic: Option<(char, Range<usize>)>,
+}
+
+/// The title of a menu in the bar.
+struct Title {
+    label: MnemonicLabel,
+    rect: Rect,
+}
+
+/// An open dropdown, showing the entries of a submenu.
+struct Dropdown {
+    /// The indices of the submenus leading to this one, starting in the bar.
+    path: Vec<usize>,
+    rect: Rect,
+    rows: Vec<Row>,
+    /// The row that is highlighted.
+    hot: Option<usize>,
+}
+
+/// A row of a dropdown.
+struct Row {
+    rect: Rect,
+    label: Option<MnemonicLabel>,
+    shortcut: Option<TextLayout<ArcStr>>,
+}
+
+/// A menu bar that is drawn by Druid, above its child.
+///
+/// This shows a [`Menu`] as a bar of titles with dropdowns, for platforms without a
+/// menu bar of their own, or for a look that matches the rest of the app on every
+/// platform. Its items call the same callbacks, and send the same commands, as they
+/// would from a platform menu set with [`WindowDesc::menu`]. To avoid showing the
+/// menu twice, don't use both for the same window.
+///
+/// The menus are opened by clicking their titles. With the keyboard, `F10` or
+/// pressing and releasing `Alt` activates the bar; the arrow keys then move between
+/// menus and items, `Enter` chooses an item, and `Escape` closes menus. A character
+/// that follows `&` in a title, as in `"&File"`, is its mnemonic: pressing it with
+/// `Alt`, or by itself while the bar is active, opens that menu or chooses that item.
+/// The hotkeys of items work while no menu is open.
+///
+/// Menu callbacks get the data of the menu bar, so to use the same menu as the
+/// window, the menu bar should be the root widget of the window.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, MenuBar};
+/// use druid::{commands, Data, Menu, MenuItem, SysMods, Widget};
+///
+/// fn window<T: Data>() -> impl Widget<T> {
+///     MenuBar::new(
+///         |_window, _data, _env| {
+///             Menu::empty().entry(
+///                 Menu::new("&File")
+///                     .entry(MenuItem::new("&Open…").command(commands::SHOW_OPEN_PANEL.with(Default::default())).hotkey(SysMods::Cmd, "o"))
+///                     .separator()
+///                     .entry(MenuItem::new("&Quit").command(commands::QUIT_APP)),
+///             )
+///         },
+///         Label::new("Hello"),
+///     )
+/// }
+/// ```
+///
+/// [`WindowDesc::menu`]: crate::WindowDesc::menu
+pub struct MenuBar<T, W> {
+    manager: MenuManager<T>,
+    window: Option<WindowId>,
+    nodes: Vec<MenuNode>,
+    titles: Vec<Title>,
+    bar_height: f64,
+    /// The menu whose title is highlighted, while the bar is in use.
+    active: Option<usize>,
+    dropdowns: Vec<Dropdown>,
+    /// The title under the mouse, while the bar is not in use.
+    hover: Option<usize>,
+    /// Whether the bar was activated with the keyboard, which shows mnemonics.
+    keyboard: bool,
+    /// Whether `Alt` was pressed without another key, so releasing it activates the bar.
+    alt_pending: bool,
+    child: WidgetPod<T, W>,
+}
+
+impl<T: Data, W: Widget<T>> MenuBar<T, W> {
+    /// Create a menu bar above `child`.
+    ///
+    /// The closure builds the menu, like the one passed to [`WindowDesc::menu`]; the
+    /// entries of the top-level menu are shown in the bar. It is called again when the
+    /// menu asks to be rebuilt with [`Menu::rebuild_on`].
+    ///
+    /// [`WindowDesc::menu`]: crate::WindowDesc::menu
+    pub fn new(
+        build: impl FnMut(Option<WindowId>, &T, &Env) -> Menu<T> + 'static,
+        child: W,
+    ) -> Self {
+        MenuBar {
+            manager: MenuManager::new(build),
+            window: None,
+            nodes: Vec::new(),
+            titles: Vec::new(),
+            bar_height: 0.0,
+            active: None,
+            dropdowns: Vec::new(),
+            hover: None,
+            keyboard: false,
+            alt_pending: false,
+            child: WidgetPod::new(child),
+        }
+    }
+
+    fn set_nodes(&mut self, nodes: Vec<MenuNode>) {
+        self.titles = nodes
+            .iter()
+            .map(|node| Title {
+                label: MnemonicLabel::new(node_title(node)),
+                rect: Rect::ZERO,
+            })
+            .collect();
+        self.nodes = nodes;
+        if matches!(self.active, Some(index) if index >= self.nodes.len()) {
+            self.active = None;
+        }
+        // The structure may have changed, so reopen the dropdowns from scratch.
+        self.dropdowns.clear();
+    }
+
+    /// The entries shown by the dropdown at `path`.
+    fn entries(&self, path: &[usize]) -> &[MenuNode] {
+        let mut entries = &self.nodes[..];
+        for &index in path {
+            entries = match entries.get(index) {
+                Some(MenuNode::Submenu { children, .. }) => children,
+                _ => return &[],
+            };
+        }
+        entries
+    }
+
+    fn open(
+        &mut self,
+        ctx: &mut EventCtx,
+        index: usize,
+        select_first: bool,
+        data: &mut T,
+        env: &Env,
+    ) {
+        self.active = Some(index);
+        self.dropdowns.clear();
+        match self.nodes.get(index) {
+            Some(MenuNode::Submenu { enabled: true, .. }) => {
+                self.open_dropdown(vec![index], select_first)
+            }
+            Some(MenuNode::Item {
+                id, enabled: true, ..
+            }) => {
+                let id = *id;
+                self.activate(ctx, id, data, env);
+                return;
+            }
+            _ => (),
+        }
+        ctx.request_focus();
+        ctx.request_layout();
+        ctx.request_paint();
+    }
+
+    fn open_dropdown(&mut self, path: Vec<usize>, select_first: bool) {
+        let mut dropdown = Dropdown {
+            path,
+            rect: Rect::ZERO,
+            rows: Vec::new(),
+            hot: None,
+        };
+        if select_first {
+            dropdown.hot = self.step(&dropdown, None, 1);
+        }
+        self.dropdowns.push(dropdown);
+    }
+
+    /// Opens the submenu highlighted in the last dropdown, if there is one.
+    fn open_hot_submenu(&mut self, select_first: bool) -> bool {
+        let Some(last) = self.dropdowns.last() else {
+            return false;
+        };
+        let Some(hot) = last.hot else {
+            return false;
+        };
+        if let Some(MenuNode::Submenu { enabled: true, .. }) = self.entries(&last.path).get(hot) {
+            let mut path = last.path.clone();
+            path.push(hot);
+            self.open_dropdown(path, select_first);
+            return true;
+        }
+        false
+    }
+
+    /// The next row from `from` in `direction` that can be highlighted.
+    fn step(&self, dropdown: &Dropdown, from: Option<usize>, direction: isize) -> Option<usize> {
+        let entries = self.entries(&dropdown.path);
+        let len = entries.len() as isize;
+        let mut index = from.map_or(if direction > 0 { -1 } else { len }, |i| i as isize);
+        for _ in 0..len {
+            index = (index + direction).rem_euclid(len);
+            if !matches!(entries[index as usize], MenuNode::Separator) {
+                return Some(index as usize);
+            }
+        }
+        None
+    }
+
+    fn close(&mut self, ctx: &mut EventCtx) {
+        self.active = None;
+        self.dropdowns.clear();
+        self.keyboard = false;
+        ctx.request_layout();
+        ctx.request_paint();
+    }
+
+    fn activate(&mut self, ctx: &mut EventCtx, id: MenuItemId, data: &mut T, env: &Env) {
+        self.close(ctx);
+        let mut queue = CommandQueue::new();
+        self.manager.event(&mut queue, self.window, id, data, env);
+        for cmd in queue {
+            ctx.submit_command(cmd);
+        }
+    }
+
+    /// Chooses the highlighted row of the last dropdown.
+    fn choose_hot(&mut self, ctx: &mut EventCtx, data: &mut T, env: &Env) {
+        let Some(last) = self.dropdowns.last() else {
+            return;
+        };
+        let node = last.hot.and_then(|hot| self.entries(&last.path).get(hot));
+        if let Some(MenuNode::Item {
+            id, enabled: true, ..
+        }) = node
+        {
+            let id = *id;
+            self.activate(ctx, id, data, env);
+        } else if matches!(node, Some(MenuNode::Submenu { .. })) && self.open_hot_submenu(true) {
+            ctx.request_layout();
+        }
+    }
+
+    /// The title at `pos`.
+    fn title_at(&self, pos: Point) -> Option<usize> {
+        self.titles
+            .iter()
+            .position(|title| title.rect.contains(pos))
+    }
+
+    /// The dropdown and row at `pos`, starting with the last dropdown.
+    fn row_at(&self, pos: Point) -> Option<(usize, Option<usize>)> {
+        self.dropdowns
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, dropdown)| dropdown.rect.contains(pos))
+            .map(|(level, dropdown)| {
+                let row = dropdown.rows.iter().position(|row| row.rect.contains(pos));
+                (level, row)
+            })
+    }
+
+    fn mouse_event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) => match self.title_at(mouse.pos) {
+                Some(index) if self.active == Some(index) && !self.dropdowns.is_empty() => {
+                    self.close(ctx)
+                }
+                Some(index) => {
+                    self.keyboard = false;
+                    self.open(ctx, index, false, data, env);
+                }
+                None if self.row_at(mouse.pos).is_none() => self.close(ctx),
+                None => (),
+            },
+            Event::MouseMove(mouse) => {
+                if let Some(index) = self.title_at(mouse.pos) {
+                    if self.active != Some(index) && !self.dropdowns.is_empty() {
+                        self.open(ctx, index, false, data, env);
+                    }
+                } else if let Some((level, Some(row))) = self.row_at(mouse.pos) {
+                    let dropdown = &mut self.dropdowns[level];
+                    if dropdown.hot != Some(row) || self.dropdowns.len() > level + 1 {
+                        self.dropdowns.truncate(level + 1);
+                        self.dropdowns[level].hot = Some(row);
+                        self.open_hot_submenu(false);
+                        ctx.request_layout();
+                        ctx.request_paint();
+                    }
+                }
+            }
+            Event::MouseUp(mouse) => {
+                if let Some((level, Some(row))) = self.row_at(mouse.pos) {
+                    let path = &self.dropdowns[level].path;
+                    if let Some(MenuNode::Item {
+                        id, enabled: true, ..
+                    }) = self.entries(path).get(row)
+                    {
+                        let id = *id;
+                        self.activate(ctx, id, data, env);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn key_event(&mut self, ctx: &mut EventCtx, key: &KeyEvent, data: &mut T, env: &Env) {
+        let Some(active) = self.active else {
+            return;
+        };
+        let count = self.titles.len();
+        match &key.key {
+            KbKey::Escape => {
+                if self.dropdowns.len() > 1 {
+                    self.dropdowns.pop();
+                } else if !self.dropdowns.is_empty() && self.keyboard {
+                    self.dropdowns.clear();
+                } else {
+                    self.close(ctx);
+                }
+            }
+            KbKey::F10 => self.close(ctx),
+            KbKey::ArrowLeft if self.dropdowns.len() > 1 => {
+                self.dropdowns.pop();
+            }
+            KbKey::ArrowRight if self.open_hot_submenu(true) => (),
+            KbKey::ArrowLeft | KbKey::ArrowRight if count > 0 => {
+                let next = match key.key {
+                    KbKey::ArrowLeft => (active + count - 1) % count,
+                    _ => (active + 1) % count,
+                };
+                if self.dropdowns.is_empty() {
+                    self.active = Some(next);
+                } else {
+                    self.open(ctx, next, true, data, env);
+                }
+            }
+            KbKey::ArrowDown | KbKey::ArrowUp => {
+                let direction = if key.key == KbKey::ArrowDown { 1 } else { -1 };
+                match self.dropdowns.last() {
+                    Some(last) => {
+                        let hot = self.step(last, last.hot, direction);
+                        if let Some(last) = self.dropdowns.last_mut() {
+                            last.hot = hot;
+                        }
+                    }
+                    None => self.open(ctx, active, true, data, env),
+                }
+            }
+            KbKey::Enter if self.dropdowns.is_empty() => self.open(ctx, active, true, data, env),
+            KbKey::Enter => self.choose_hot(ctx, data, env),
+            KbKey::Character(c) if c == " " => self.choose_hot(ctx, data, env),
+            KbKey::Character(c) => {
+                let Some(c) = c.chars().next() else {
+                    return;
+                };
+                match self.dropdowns.last() {
+                    None => {
+                        if let Some(index) = self.titles.iter().position(|t| t.label.is_mnemonic(c))
+                        {
+                            self.open(ctx, index, true, data, env);
+                        }
+                    }
+                    Some(last) => {
+                        if let Some(row) = last.rows.iter().position(
+                            |row| matches!(&row.label, Some(label) if label.is_mnemonic(c)),
+                        ) {
+                            if let Some(last) = self.dropdowns.last_mut() {
+                                last.hot = Some(row);
+                            }
+                            self.choose_hot(ctx, data, env);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+        ctx.request_layout();
+        ctx.request_paint();
+    }
+
+    /// Activates the enabled item with a hotkey matching `key`.
+    fn hotkey_event(&mut self, ctx: &mut EventCtx, key: &KeyEvent, data: &mut T, env: &Env) {
+        if let Some(id) = find_hotkey(&self.nodes, key) {
+            self.activate(ctx, id, data, env);
+            ctx.set_handled();
+        }
+    }
+
+    fn layout_dropdowns(&mut self, ctx: &mut LayoutCtx, size: Size, env: &Env) {
+        for level in 0..self.dropdowns.len() {
+            let origin = match level {
+                0 => {
+                    let title = self.dropdowns[0].path[0];
+                    Point::new(self.titles[title].rect.x0, self.bar_height)
+                }
+                _ => {
+                    let parent = &self.dropdowns[level - 1];
+                    let row = parent.hot.and_then(|hot| parent.rows.get(hot));
+                    let y = row.map_or(parent.rect.y0, |row| row.rect.y0 - DROPDOWN_PADDING);
+                    Point::new(parent.rect.x1, y)
+                }
+            };
+
+            let path = self.dropdowns[level].path.clone();
+            let mut rows = Vec::new();
+            let mut label_width: f64 = 0.0;
+            let mut shortcut_width: f64 = 0.0;
+            for entry in self.entries(&path) {
+                let (title, hotkey, enabled) = match entry {
+                    MenuNode::Separator => {
+                        rows.push((SEPARATOR_HEIGHT, None, None));
+                        continue;
+                    }
+                    MenuNode::Item {
+                        title,
+                        hotkey,
+                        enabled,
+                        ..
+                    } => (title, hotkey.as_ref(), *enabled),
+                    MenuNode::Submenu { title, enabled, .. } => (title, None, *enabled),
+                };
+                let mut label = MnemonicLabel::new(title);
+                if !enabled {
+                    label.layout.set_text_color(theme::DISABLED_TEXT_COLOR);
+                }
+                label.layout.rebuild_if_needed(ctx.text(), env);
+                label_width = label_width.max(label.layout.size().width);
+                let shortcut = hotkey.map(|hotkey| {
+                    let mut layout = TextLayout::from_text(hotkey_text(hotkey));
+                    layout.set_text_color(theme::DISABLED_TEXT_COLOR);
+                    layout.rebuild_if_needed(ctx.text(), env);
+                    shortcut_width = shortcut_width.max(layout.size().width);
+                    layout
+                });
+                rows.push((ITEM_HEIGHT, Some(label), shortcut));
+            }
+
+            let shortcut_space = match shortcut_width > 0.0 {
+                true => SHORTCUT_GAP + shortcut_width,
+                false => 0.0,
+            };
+            let width = (ITEM_INSET * 2.0 + label_width + shortcut_space).max(DROPDOWN_MIN_WIDTH);
+            let height: f64 =
+                rows.iter().map(|(height, _, _)| height).sum::<f64>() + DROPDOWN_PADDING * 2.0;
+            // Keep the dropdown inside the window where possible.
+            let x = origin.x.min(size.width - width).max(0.0);
+            let y = origin.y.min(size.height - height).max(0.0);
+            let rect = Rect::from_origin_size((x, y), (width, height));
+
+            let mut row_y = rect.y0 + DROPDOWN_PADDING;
+            let dropdown = &mut self.dropdowns[level];
+            dropdown.rect = rect;
+            dropdown.rows = rows
+                .into_iter()
+                .map(|(height, label, shortcut)| {
+                    let row_rect = Rect::new(rect.x0, row_y, rect.x1, row_y + height);
+                    row_y += height;
+                    Row {
+                        rect: row_rect,
+                        label,
+                        shortcut,
+                    }
+                })
+                .collect();
+        }
+    }
+
+    fn paint_dropdown(&self, ctx: &mut PaintCtx, dropdown: &Dropdown, env: &Env) {
+        let entries = self.entries(&dropdown.path);
+        ctx.fill(
+            dropdown
+                .rect
+                .with_origin(dropdown.rect.origin() + (1.0, 2.0)),
+            &Color::BLACK.with_alpha(0.15),
+        );
+        ctx.fill(dropdown.rect, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(dropdown.rect.inset(-0.5), &env.get(theme::BORDER_DARK), 1.0);
+
+        let text_color = env.get(theme::TEXT_COLOR);
+        for (index, (row, entry)) in dropdown.rows.iter().zip(entries).enumerate() {
+            let rect = row.rect;
+            if let MenuNode::Separator = entry {
+                let y = rect.center().y.round() + 0.5;
+                let line = Line::new((rect.x0 + 1.0, y), (rect.x1 - 1.0, y));
+                ctx.stroke(line, &env.get(theme::BORDER_LIGHT), 1.0);
+                continue;
+            }
+            if dropdown.hot == Some(index) && node_enabled(entry) {
+                ctx.fill(
+                    rect.inset((-2.0, 0.0)),
+                    &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR),
+                );
+            }
+            if let Some(label) = &row.label {
+                let y = rect.center().y - label.layout.size().height / 2.0;
+                label.draw(ctx, Point::new(rect.x0 + ITEM_INSET, y), self.keyboard, env);
+            }
+            if let Some(shortcut) = &row.shortcut {
+                let size = shortcut.size();
+                let origin = Point::new(
+                    rect.x1 - ITEM_INSET - size.width,
+                    rect.center().y - size.height / 2.0,
+                );
+                shortcut.draw(ctx, origin);
+            }
+            match entry {
+                MenuNode::Item {
+                    selected: Some(true),
+                    ..
+                } => {
+                    let center = Point::new(rect.x0 + ITEM_INSET / 2.0, rect.center().y);
+                    let mut check = BezPath::new();
+                    check.move_to(center + (-4.0, 0.0));
+                    check.line_to(center + (-1.0, 3.0));
+                    check.line_to(center + (4.0, -3.0));
+                    ctx.stroke(check, &text_color, 1.5);
+                }
+                MenuNode::Submenu { .. } => {
+                    let center = Point::new(rect.x1 - ITEM_INSET / 2.0, rect.center().y);
+                    let mut arrow = BezPath::new();
+                    arrow.move_to(center + (-2.0, -4.0));
+                    arrow.line_to(center + (2.0, 0.0));
+                    arrow.line_to(center + (-2.0, 4.0));
+                    arrow.close_path();
+                    ctx.fill(arrow, &text_color);
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl MnemonicLabel {
+    fn new(text: &str) -> Self {
+        let (text, mnemonic) = parse_mnemonic(text);
+        MnemonicLabel {
+            layout: TextLayout::from_text(text),
+            mnemonic,
+        }
+    }
+
+    fn is_mnemonic(&self, c: char) -> bool {
+        matches!(&self.mnemonic, Some((mnemonic, _)) if mnemonic.to_lowercase().eq(c.to_lowercase()))
+    }
+
+    fn draw(&self, ctx: &mut PaintCtx, origin: Point, show_mnemonic: bool, env: &Env) {
+        self.layout.draw(ctx, origin);
+        if let (true, Some((_, range))) = (show_mnemonic, &self.mnemonic) {
+            let line = self.layout.underline_for_range(range.clone());
+            ctx.stroke(line + origin.to_vec2(), &env.get(theme::TEXT_COLOR), 1.0);
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for MenuBar<T, W> {
+    #[instrument(name = "MenuBar", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::WindowConnected => {
+                self.child.event(ctx, event, data, env);
+                // Key events only reach widgets that have focus, or contain it.
+                if !ctx.has_focus() {
+                    ctx.request_focus();
+                }
+                return;
+            }
+            // with no dropdown open, wheel events go to the child
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::MouseMove(_) | Event::Wheel(_)
+                if self.active.is_some()
+                    && (!self.dropdowns.is_empty() || !matches!(event, Event::Wheel(_))) =>
+            {
+                self.mouse_event(ctx, event, data, env);
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseDown(mouse) | Event::MouseMove(mouse) if mouse.pos.y < self.bar_height => {
+                let hover = self.title_at(mouse.pos);
+                if hover != self.hover {
+                    self.hover = hover;
+                    ctx.request_paint();
+                }
+                if let (Event::MouseDown(_), Some(index)) = (event, hover) {
+                    self.open(ctx, index, false, data, env);
+                    ctx.set_handled();
+                }
+                return;
+            }
+            Event::MouseMove(_) if self.hover.is_some() => {
+                self.hover = None;
+                ctx.request_paint();
+            }
+            Event::KeyDown(key) => {
+                if self.active.is_some() {
+                    self.key_event(ctx, key, data, env);
+                    ctx.set_handled();
+                    return;
+                }
+                let alt = key.mods.alt() && !key.mods.ctrl() && !key.mods.meta();
+                self.alt_pending = key.key == KbKey::Alt && !key.repeat;
+                if key.key == KbKey::F10 && key.mods.is_empty() && !self.titles.is_empty() {
+                    self.active = Some(0);
+                    self.keyboard = true;
+                    ctx.request_focus();
+                    ctx.request_paint();
+                    ctx.set_handled();
+                    return;
+                }
+                if let (true, KbKey::Character(c)) = (alt, &key.key) {
+                    let index = c.chars().next().and_then(|c| {
+                        self.titles
+                            .iter()
+                            .position(|title| title.label.is_mnemonic(c))
+                    });
+                    if let Some(index) = index {
+                        self.keyboard = true;
+                        self.open(ctx, index, true, data, env);
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+            }
+            Event::KeyUp(key) if key.key == KbKey::Alt && std::mem::take(&mut self.alt_pending) => {
+                if self.active.is_some() {
+                    self.close(ctx);
+                } else if !self.titles.is_empty() {
+                    self.active = Some(0);
+                    self.keyboard = true;
+                    ctx.request_focus();
+                    ctx.request_paint();
+                }
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+
+        self.child.event(ctx, event, data, env);
+
+        if let Event::KeyDown(key) = event {
+            if !ctx.is_handled() && self.active.is_none() {
+                self.hotkey_event(ctx, key, data, env);
+            }
+        }
+    }
+
+    #[instrument(name = "MenuBar", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.window = Some(ctx.window_id());
+                let nodes = self.manager.initialize_nodes(self.window, data, env);
+                self.set_nodes(nodes);
+            }
+            LifeCycle::FocusChanged(false) if self.active.is_some() => {
+                self.active = None;
+                self.dropdowns.clear();
+                self.keyboard = false;
+                ctx.request_layout();
+                ctx.request_paint();
+            }
+            LifeCycle::HotChanged(false) if self.hover.is_some() => {
+                self.hover = None;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "MenuBar",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if let Some(nodes) = self.manager.update_nodes(self.window, data, env) {
+            self.set_nodes(nodes);
+            ctx.request_layout();
+        }
+        if self
+            .titles
+            .iter_mut()
+            .any(|title| title.label.layout.needs_rebuild_after_update(ctx))
+        {
+            ctx.request_layout();
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "MenuBar", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("MenuBar");
+
+        let mut text_height: f64 = 0.0;
+        for title in &mut self.titles {
+            title.label.layout.rebuild_if_needed(ctx.text(), env);
+            text_height = text_height.max(title.label.layout.size().height);
+        }
+        self.bar_height = env
+            .get(theme::BORDERED_WIDGET_HEIGHT)
+            .max(text_height + 4.0);
+        let mut x = 0.0;
+        for title in &mut self.titles {
+            let width = title.label.layout.size().width + TITLE_PADDING * 2.0;
+            title.rect = Rect::new(x, 0.0, x + width, self.bar_height);
+            x += width;
+        }
+
+        let child_bc = bc.shrink((0.0, self.bar_height));
+        let child_size = self.child.layout(ctx, &child_bc, data, env);
+        self.child.set_origin(ctx, Point::new(0.0, self.bar_height));
+        let size = bc.constrain(Size::new(
+            child_size.width.max(x),
+            child_size.height + self.bar_height,
+        ));
+
+        // The dropdowns are painted over the child, and kept inside our bounds.
+        self.layout_dropdowns(ctx, size, env);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "MenuBar", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+
+        let size = ctx.size();
+        let bar = Rect::new(0.0, 0.0, size.width, self.bar_height);
+        ctx.fill(bar, &env.get(theme::WINDOW_BACKGROUND_COLOR));
+        let y = self.bar_height - 0.5;
+        ctx.stroke(
+            Line::new((0.0, y), (size.width, y)),
+            &env.get(theme::BORDER_DARK),
+            1.0,
+        );
+
+        let highlighted = self.active.or(self.hover);
+        for (index, title) in self.titles.iter().enumerate() {
+            if highlighted == Some(index) {
+                ctx.fill(title.rect, &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR));
+            }
+            let origin = Point::new(
+                title.rect.x0 + TITLE_PADDING,
+                (self.bar_height - title.label.layout.size().height) / 2.0,
+            );
+            title.label.draw(ctx, origin, self.keyboard, env);
+        }
+
+        for dropdown in &self.dropdowns {
+            self.paint_dropdown(ctx, dropdown, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let titles: Vec<_> = self
+            .titles
+            .iter()
+            .filter_map(|title| title.label.layout.text().map(|text| text.to_string()))
+            .collect();
+        DebugState {
+            display_name: "MenuBar".to_string(),
+            main_value: titles.join(" "),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+fn node_title(node: &MenuNode) -> &str {
+    match node {
+        MenuNode::Item { title, .. } | MenuNode::Submenu { title, .. } => title,
+        MenuNode::Separator => "",
+    }
+}
+
+fn node_enabled(node: &MenuNode) -> bool {
+    match node {
+        MenuNode::Item { enabled, .. } | MenuNode::Submenu { enabled, .. } => *enabled,
+        MenuNode::Separator => false,
+    }
+}
+
+fn find_hotkey(nodes: &[MenuNode], key: &KeyEvent) -> Option<MenuItemId> {
+    nodes.iter().find_map(|node| match node {
+        MenuNode::Item {
+            id,
+            hotkey: Some(hotkey),
+            enabled: true,
+            ..
+        } if hotkey.matches(key) => Some(*id),
+        MenuNode::Submenu {
+            children,
+            enabled: true,
+            ..
+        } => find_hotkey(children, key),
+        _ => None,
+    })
+}
+
+/// Removes the `&` that marks the mnemonic of a menu title, as platform menus do,
+/// and returns the mnemonic with its range in the remaining text.
+///
+/// `&&` stands for a literal `&`.
+fn parse_mnemonic(raw: &str) -> (ArcStr, Option<(char, Range<usize>)>) {
+    let mut text = String::with_capacity(raw.len());
+    let mut mnemonic = None;
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('&') => text.push('&'),
+            Some(c) => {
+                if mnemonic.is_none() {
+                    mnemonic = Some((c, text.len()..text.len() + c.len_utf8()));
+                }
+                text.push(c);
+            }
+            None => (),
+        }
+    }
+    (text.into(), mnemonic)
+}
+
+/// The text that describes a hotkey, such as "Ctrl+Shift+S".
+fn hotkey_text(hotkey: &HotKey) -> ArcStr {
+    let mods: Modifiers = hotkey.mods().into();
+    let mut text = String::new();
+    if mods.ctrl() {
+        text.push_str("Ctrl+");
+    }
+    if mods.alt() {
+        text.push_str("Alt+");
+    }
+    if mods.shift() {
+        text.push_str("Shift+");
+    }
+    if mods.meta() {
+        text.push_str(if cfg!(target_os = "macos") {
+            "Cmd+"
+        } else {
+            "Super+"
+        });
+    }
+    match hotkey.key() {
+        KbKey::Character(c) if c == " " => text.push_str("Space"),
+        KbKey::Character(c) => text.extend(c.chars().flat_map(char::to_uppercase)),
+        KbKey::ArrowLeft => text.push_str("Left"),
+        KbKey::ArrowRight => text.push_str("Right"),
+        KbKey::ArrowUp => text.push_str("Up"),
+        KbKey::ArrowDown => text.push_str("Down"),
+        key => text.push_str(&key.to_string()),
+    }
+    text.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mnemonics() {
+        assert_eq!(parse_mnemonic("&File"), ("File".into(), Some(('F', 0..1))));
+        assert_eq!(
+            parse_mnemonic("Save &As…"),
+            ("Save As…".into(), Some(('A', 5..6)))
+        );
+        assert_eq!(
+            parse_mnemonic("Fish && Chips"),
+            ("Fish & Chips".into(), None)
+        );
+        assert_eq!(parse_mnemonic("Plain"), ("Plain".into(), None));
+    }
+}
@@ -20,95 +20,214 @@ mod widget_wrapper;
 
 mod added;
 mod align;
+mod anchor;
 mod aspect_ratio_box;
+mod async_image;
+mod badged;
+mod binding;
+mod breadcrumbs;
 mod button;
+mod canvas;
+mod carousel;
+pub mod chart;
 mod checkbox;
 mod click;
 mod clip_box;
+mod code_editor;
+mod collapsible;
 mod common;
+#[cfg(feature = "constraint-layout")]
+#[cfg_attr(docsrs, doc(cfg(feature = "constraint-layout")))]
+mod constraint_layout;
 mod container;
+mod context_menu;
 mod controller;
+mod data_changes;
 mod disable_if;
+mod dock;
 mod either;
 mod env_scope;
+mod filtered_list;
+mod find;
 mod flex;
+mod form;
+mod graph_view;
+mod grid;
+mod icon;
 mod identity_wrapper;
 mod image;
+mod infinite_list;
 mod intrinsic_width;
 mod invalidation;
 mod label;
 mod lens_wrap;
 mod list;
+#[cfg(feature = "markdown")]
+#[cfg_attr(docsrs, doc(cfg(feature = "markdown")))]
+mod markdown;
 mod maybe;
+mod menu_bar;
+mod navigation_stack;
 mod padding;
 mod painter;
 mod parse;
+mod password_box;
+mod pdf_view;
+#[cfg(feature = "im")]
+#[cfg_attr(docsrs, doc(cfg(feature = "im")))]
+mod piano_roll;
+mod print_preview;
+mod profiled;
 mod progress_bar;
 mod radio;
+mod rating;
+mod reducer;
+mod responsive;
+mod rich_text_box;
 mod scope;
 mod scroll;
+mod segmented_control;
+mod sheet;
 mod sized_box;
 mod slider;
 mod spinner;
 mod split;
+mod state_machine;
 mod stepper;
+mod styled;
 #[cfg(feature = "svg")]
 #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
 mod svg;
 mod switch;
+mod switch_transition;
 mod tabs;
+#[cfg(feature = "im")]
+#[cfg_attr(docsrs, doc(cfg(feature = "im")))]
+mod tag_input;
 mod textbox;
+mod theme_scope;
+mod tooltip;
+mod undo;
 mod value_textbox;
 mod view_switcher;
+mod waveform;
+#[cfg(feature = "raw-win-handle")]
+#[cfg_attr(docsrs, doc(cfg(feature = "raw-win-handle")))]
+mod web_view;
 #[allow(clippy::module_inception)]
 mod widget;
 mod widget_ext;
+mod wizard;
+mod workspace;
+mod wrap;
 mod z_stack;
 
-pub use self::image::Image;
+pub use self::image::{Image, ImageFrames};
 pub use added::Added;
 pub use align::Align;
-pub use aspect_ratio_box::AspectRatioBox;
+pub use anchor::Anchor;
+pub use aspect_ratio_box::{AspectRatioBox, AspectRatioFit};
+pub use async_image::AsyncImage;
+pub use badged::{Badge, Badged};
+pub use binding::Binding;
+pub use breadcrumbs::Breadcrumbs;
 pub use button::Button;
+pub use canvas::{Canvas, Scene, SceneItem};
+pub use carousel::Carousel;
 pub use checkbox::Checkbox;
 pub use click::Click;
 pub use clip_box::{ClipBox, Viewport};
+pub use code_editor::{CodeEditor, Highlighter};
+pub use collapsible::{Accordion, Collapsible};
 pub use common::FillStrat;
+#[cfg(feature = "constraint-layout")]
+pub use constraint_layout::{
+    AnchorExpr, ConstraintLayout, ConstraintPriority, LayoutAnchor, LayoutConstraint, LayoutItem,
+};
 pub use container::Container;
+pub use context_menu::{ContextMenu, ContextMenuTarget};
 pub use controller::{Controller, ControllerHost};
+pub use data_changes::LogDataChanges;
 pub use disable_if::DisabledIf;
+pub use dock::{Dock, DockEdge, DockGroup, DockLayout, DockLayoutParseError, DockPosition};
 pub use either::Either;
 pub use env_scope::EnvScope;
+pub use filtered_list::FilteredList;
+pub use find::{FindController, FindMatches, Findable};
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use form::{AsyncCheck, FieldState, Form, FormField};
+pub use graph_view::{Graph, GraphLayout, GraphView};
+pub use grid::{Grid, GridTrack};
+pub use icon::{Icon, IconData};
 pub use identity_wrapper::IdentityWrapper;
+pub use infinite_list::InfiniteList;
 pub use intrinsic_width::IntrinsicWidth;
-pub use label::{Label, LabelText, LineBreaking, RawLabel};
+pub use label::{Label, LabelText, LineBreaking, RawLabel, TextRotation};
 pub use lens_wrap::LensWrap;
+#[cfg(feature = "im")]
+pub use list::MapEntries;
 pub use list::{List, ListIter};
+#[cfg(feature = "markdown")]
+pub use markdown::Markdown;
 pub use maybe::Maybe;
+pub use menu_bar::MenuBar;
+pub use navigation_stack::{NavRoute, NavigationStack};
 pub use padding::Padding;
-pub use painter::{BackgroundBrush, Painter};
+pub use painter::{BackgroundBrush, InteractivePainter, Painter};
 #[allow(deprecated)]
 pub use parse::Parse;
+pub use password_box::PasswordBox;
+pub use pdf_view::{PdfChar, PdfDocument, PdfView, PdfViewMode};
+#[cfg(feature = "im")]
+pub use piano_roll::{Note, PianoRoll};
+pub use print_preview::{PageOrientation, PageSetup, PrintPreview};
+pub use profiled::Profiled;
 pub use progress_bar::ProgressBar;
 pub use radio::{Radio, RadioGroup};
-pub use scope::{DefaultScopePolicy, LensScopeTransfer, Scope, ScopePolicy, ScopeTransfer};
+pub use rating::{Rating, RatingValue};
+pub use reducer::{Effects, Reducer};
+pub use responsive::Responsive;
+pub use rich_text_box::{RichTextBox, RichTextDocument, TextStyle};
+pub use scope::{
+    DefaultScopePolicy, LensScopeTransfer, LocalState, LocalStatePolicy, Scope, ScopePolicy,
+    ScopeTransfer,
+};
 pub use scroll::Scroll;
+#[cfg(feature = "im")]
+pub use segmented_control::MultiSegmentedControl;
+pub use segmented_control::SegmentedControl;
+pub use sheet::{BasicEngine, CellRef, CellValue, FormulaEngine, Sheet, SheetData};
 pub use sized_box::SizedBox;
 pub use slider::{KnobStyle, RangeSlider, Slider};
 pub use spinner::Spinner;
-pub use split::Split;
+pub use split::{Split, SplitPane};
+pub use state_machine::{Interaction, InteractionState, StateMachine, Trigger};
 pub use stepper::Stepper;
+pub use styled::{Style, Styled};
 #[cfg(feature = "svg")]
 pub use svg::{Svg, SvgData};
 pub use switch::Switch;
-pub use tabs::{AddTab, TabInfo, Tabs, TabsEdge, TabsPolicy, TabsState, TabsTransition};
+pub use switch_transition::{SwitchTransition, TransitionFrame, TransitionPainter};
+pub use tabs::{
+    AddTab, TabInfo, Tabs, TabsEdge, TabsLoading, TabsPolicy, TabsState, TabsTransition,
+};
+#[cfg(feature = "im")]
+pub use tag_input::TagInput;
 pub use textbox::TextBox;
+pub use theme_scope::ThemeScope;
+pub use tooltip::Tooltip;
+pub use undo::UndoManager;
 pub use value_textbox::{TextBoxEvent, ValidationDelegate, ValueTextBox};
 pub use view_switcher::ViewSwitcher;
+pub use waveform::Waveform;
+#[cfg(feature = "raw-win-handle")]
+pub use web_view::{WebView, WebViewBackend, WebViewEvents};
 pub use widget::{Widget, WidgetId};
 pub use widget_ext::WidgetExt;
 pub use widget_wrapper::WidgetWrapper;
+pub use wizard::Wizard;
+pub use workspace::{TabData, Workspace, WorkspaceSession, WorkspaceSessionParseError};
+pub use wrap::Wrap;
 pub use z_stack::ZStack;
 
 /// The types required to implement a [`Widget`].
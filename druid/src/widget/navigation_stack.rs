@@ -0,0 +1,525 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A stack of pages with back and forward navigation.
+
+use std::any::Any;
+use std::time::Duration;
+
+use tracing::{instrument, trace, warn};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{
+    theme, Affine, Color, Data, KbKey, MouseButton, Point, Selector, TimerToken, WidgetExt,
+    WidgetPod,
+};
+
+/// How long the stack waits after the last scroll event before it ends a swipe.
+const SWIPE_END_DELAY: Duration = Duration::from_millis(150);
+/// The fraction of the width a swipe has to cover to go back.
+const SWIPE_BACK_FRACTION: f64 = 0.35;
+/// How far the page below moves, relative to the page on top.
+const PARALLAX: f64 = 0.3;
+/// How dark the page below is while it's covered.
+const SHADE: f64 = 0.2;
+
+type Page<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+type RouteBuilder<T> = Box<dyn Fn(&dyn Any, &T) -> Option<Box<dyn Widget<T>>>>;
+
+/// The payload of [`NavigationStack::NAV_PUSH`].
+///
+/// It wraps a value of any type; the stack builds the new page with the route
+/// that was registered for that type with [`NavigationStack::with_route`].
+pub struct NavRoute {
+    payload: Box<dyn Any>,
+}
+
+impl NavRoute {
+    /// Create a route to the page for `payload`.
+    pub fn new(payload: impl Any) -> Self {
+        NavRoute {
+            payload: Box::new(payload),
+        }
+    }
+
+    /// The payload, if it has type `P`.
+    pub fn get<P: Any>(&self) -> Option<&P> {
+        self.payload.downcast_ref()
+    }
+}
+
+/// A widget that shows the top page of a stack of pages.
+///
+/// New pages are pushed with [`NavigationStack::NAV_PUSH`], whose payload is
+/// a [`NavRoute`]; the stack builds the page with the route registered for the
+/// payload's type. [`NavigationStack::NAV_POP`] goes back to the previous page,
+/// and [`NavigationStack::NAV_FORWARD`] goes forward again to a page that was
+/// popped, until a new page is pushed.
+///
+/// Pages can send these as notifications, which go to the closest stack that can
+/// handle them, or they can be sent to the stack as commands. The back and forward
+/// mouse buttons, the browser back and forward keys, and a horizontal two-finger
+/// swipe on a trackpad navigate as well.
+///
+/// Pages slide in and out, unless [`theme::REDUCED_MOTION`] is set. Input is not
+/// passed to the pages while they move.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Label, NavRoute, NavigationStack};
+/// use druid::{Widget, WidgetExt};
+///
+/// struct Detail(usize);
+///
+/// fn pages() -> impl Widget<()> {
+///     let list = Button::new("Show item 3").on_click(|ctx, _, _| {
+///         ctx.submit_notification(NavigationStack::NAV_PUSH.with(NavRoute::new(Detail(3))))
+///     });
+///     NavigationStack::new(list).with_route(|detail: &Detail, _data: &()| {
+///         Label::new(format!("Item {}", detail.0))
+///     })
+/// }
+/// ```
+///
+/// [`theme::REDUCED_MOTION`]: crate::theme::REDUCED_MOTION
+pub struct NavigationStack<T> {
+    pages: Vec<Page<T>>,
+    /// Pages that were popped, with the next one to go forward to last.
+    forward: Vec<Page<T>>,
+    routes: Vec<RouteBuilder<T>>,
+    duration: Duration,
+    transition: Option<Transition>,
+    /// How far the top page has been swiped, as a fraction of the width.
+    swipe: f64,
+    swipe_timer: TimerToken,
+}
+
+/// A page sliding in or out.
+struct Transition {
+    /// Whether the page slides out, instead of into its place.
+    leaving: bool,
+    /// The offset the page starts at, as a fraction of the width.
+    start: f64,
+    elapsed: u64,
+    duration: u64,
+}
+
+enum NavRequest<'a> {
+    Push(&'a NavRoute),
+    Pop,
+    Forward,
+}
+
+impl NavigationStack<()> {
+    /// Push the page for a [`NavRoute`].
+    pub const NAV_PUSH: Selector<NavRoute> = Selector::new("druid-builtin.navigation-push");
+
+    /// Go back to the previous page.
+    pub const NAV_POP: Selector = Selector::new("druid-builtin.navigation-pop");
+
+    /// Go forward to the page that was popped last.
+    pub const NAV_FORWARD: Selector = Selector::new("druid-builtin.navigation-forward");
+}
+
+impl<T: Data> NavigationStack<T> {
+    /// Create a stack with `root` as its first page.
+    ///
+    /// The root page can't be popped.
+    pub fn new(root: impl Widget<T> + 'static) -> Self {
+        NavigationStack {
+            pages: vec![WidgetPod::new(root.boxed())],
+            forward: Vec::new(),
+            routes: Vec::new(),
+            duration: Duration::from_millis(250),
+            transition: None,
+            swipe: 0.0,
+            swipe_timer: TimerToken::INVALID,
+        }
+    }
+
+    /// Builder-style method to add a route, which builds the page for
+    /// a [`NavRoute`] with a payload of type `P`.
+    pub fn with_route<P: Any, W: Widget<T> + 'static>(
+        mut self,
+        route: impl Fn(&P, &T) -> W + 'static,
+    ) -> Self {
+        self.routes.push(Box::new(move |payload, data| {
+            payload
+                .downcast_ref()
+                .map(|payload| route(payload, data).boxed())
+        }));
+        self
+    }
+
+    /// Builder-style method to set how long the pages slide.
+    ///
+    /// The default is 250 milliseconds.
+    pub fn with_transition_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// The number of pages on the stack, including the root page.
+    pub fn depth(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn navigate(&mut self, ctx: &mut EventCtx, request: NavRequest, data: &T, env: &Env) -> bool {
+        match request {
+            NavRequest::Push(route) => {
+                let Some(page) = self
+                    .routes
+                    .iter()
+                    .find_map(|build| build(&*route.payload, data))
+                else {
+                    warn!(
+                        "NavigationStack {:?} has no route for the pushed payload",
+                        ctx.widget_id()
+                    );
+                    return false;
+                };
+                self.forward.clear();
+                self.pages.push(WidgetPod::new(page));
+                self.start_transition(ctx, false, 1.0, env);
+            }
+            NavRequest::Pop => {
+                if self.pages.len() < 2 {
+                    return false;
+                }
+                self.back(ctx, 0.0, env);
+            }
+            NavRequest::Forward => {
+                let Some(page) = self.forward.pop() else {
+                    return false;
+                };
+                self.pages.push(page);
+                self.start_transition(ctx, false, 1.0, env);
+            }
+        }
+        trace!(
+            "NavigationStack {:?} moved to page {}",
+            ctx.widget_id(),
+            self.pages.len()
+        );
+        true
+    }
+
+    /// Pop the top page, which starts at `start`.
+    fn back(&mut self, ctx: &mut EventCtx, start: f64, env: &Env) {
+        if let Some(page) = self.pages.pop() {
+            self.forward.push(page);
+        }
+        self.start_transition(ctx, true, start, env);
+    }
+
+    fn start_transition(&mut self, ctx: &mut EventCtx, leaving: bool, start: f64, env: &Env) {
+        let distance = if leaving { 1.0 - start } else { start };
+        let duration = (self.duration.as_nanos() as f64 * distance) as u64;
+        self.transition = if env.get(theme::REDUCED_MOTION) || duration == 0 {
+            None
+        } else {
+            ctx.request_anim_frame();
+            Some(Transition {
+                leaving,
+                start,
+                elapsed: 0,
+                duration,
+            })
+        };
+        self.swipe = 0.0;
+        self.swipe_timer = TimerToken::INVALID;
+        ctx.children_changed();
+    }
+
+    fn is_moving(&self) -> bool {
+        self.transition.is_some() || self.swipe > 0.0
+    }
+
+    /// The offset of the page on top, as a fraction of the width.
+    fn offset(&self) -> f64 {
+        self.transition
+            .as_ref()
+            .map_or(self.swipe, Transition::offset)
+    }
+
+    /// The page below and the page on top, while a page is moving.
+    fn layers(&mut self) -> (Option<&mut Page<T>>, Option<&mut Page<T>>) {
+        match &self.transition {
+            Some(transition) if transition.leaving => {
+                (self.pages.last_mut(), self.forward.last_mut())
+            }
+            _ if self.is_moving() => match self.pages.split_last_mut() {
+                Some((top, below)) => (below.last_mut(), Some(top)),
+                None => (None, None),
+            },
+            _ => (None, self.pages.last_mut()),
+        }
+    }
+
+    fn all_pages(&mut self) -> impl Iterator<Item = &mut Page<T>> {
+        self.pages.iter_mut().chain(self.forward.iter_mut())
+    }
+}
+
+impl<T: Data> Widget<T> for NavigationStack<T> {
+    #[instrument(
+        name = "NavigationStack",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Notification(_) => {
+                if let Some(request) = nav_request(event) {
+                    if self.navigate(ctx, request, data, env) {
+                        ctx.set_handled();
+                    }
+                }
+                return;
+            }
+            Event::Timer(token) if *token == self.swipe_timer => {
+                let swipe = self.swipe;
+                if swipe >= SWIPE_BACK_FRACTION {
+                    self.back(ctx, swipe, env);
+                } else {
+                    self.start_transition(ctx, false, swipe, env);
+                }
+                return;
+            }
+            _ => (),
+        }
+
+        if event.should_propagate_to_hidden() {
+            for page in self.all_pages() {
+                page.event(ctx, event, data, env);
+            }
+        } else if !self.is_moving() {
+            if let Some(page) = self.pages.last_mut() {
+                page.event(ctx, event, data, env);
+            }
+        }
+
+        if let (Some(transition), Event::AnimFrame(interval)) = (&mut self.transition, event) {
+            // We can get a high interval on the first frame due to other widgets or old animations.
+            transition.elapsed += if transition.elapsed == 0 {
+                1
+            } else {
+                *interval
+            };
+            if transition.elapsed < transition.duration {
+                ctx.request_anim_frame();
+            } else {
+                self.transition = None;
+                ctx.children_changed();
+            }
+            ctx.request_paint();
+        }
+
+        if ctx.is_handled() {
+            return;
+        }
+        let request = match event {
+            Event::Command(_) => nav_request(event),
+            Event::MouseDown(mouse) if mouse.button == MouseButton::X1 => Some(NavRequest::Pop),
+            Event::MouseDown(mouse) if mouse.button == MouseButton::X2 => Some(NavRequest::Forward),
+            Event::KeyDown(key) if key.key == KbKey::BrowserBack => Some(NavRequest::Pop),
+            Event::KeyDown(key) if key.key == KbKey::BrowserForward => Some(NavRequest::Forward),
+            Event::Wheel(mouse) if self.pages.len() > 1 && self.transition.is_none() => {
+                let delta = mouse.wheel_delta;
+                if self.swipe > 0.0 || delta.x.abs() > delta.y.abs() {
+                    let was_swiping = self.swipe > 0.0;
+                    self.swipe = (self.swipe - delta.x / ctx.size().width.max(1.0)).clamp(0.0, 1.0);
+                    self.swipe_timer = ctx.request_timer(SWIPE_END_DELAY);
+                    if was_swiping != (self.swipe > 0.0) {
+                        ctx.children_changed();
+                    }
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+                None
+            }
+            _ => None,
+        };
+        if let Some(request) = request {
+            if self.navigate(ctx, request, data, env) {
+                ctx.set_handled();
+            }
+        }
+    }
+
+    #[instrument(
+        name = "NavigationStack",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if event.should_propagate_to_hidden() {
+            for page in self.all_pages() {
+                page.lifecycle(ctx, event, data, env);
+            }
+        } else if let Some(page) = self.pages.last_mut() {
+            page.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "NavigationStack",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        // Hidden pages are updated too, so they are current when they are shown again.
+        for page in self.all_pages() {
+            page.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "NavigationStack",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("NavigationStack");
+
+        let (below, top) = self.layers();
+        for page in below.into_iter().chain(top) {
+            page.layout(ctx, bc, data, env);
+            page.set_origin(ctx, Point::ORIGIN);
+        }
+
+        let size = self
+            .pages
+            .last()
+            .map_or(Size::ZERO, |page| page.layout_rect().size());
+        let size = bc.constrain(size);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "NavigationStack", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let offset = self.offset();
+        let size = ctx.size();
+        let (below, top) = self.layers();
+
+        if let Some(below) = below {
+            ctx.clip(size.to_rect());
+            ctx.with_save(|ctx| {
+                ctx.transform(Affine::translate((
+                    -(1.0 - offset) * size.width * PARALLAX,
+                    0.0,
+                )));
+                below.paint_raw(ctx, data, env);
+            });
+            ctx.fill(
+                size.to_rect(),
+                &Color::BLACK.with_alpha(SHADE * (1.0 - offset)),
+            );
+        }
+        if let Some(top) = top {
+            ctx.with_save(|ctx| {
+                ctx.transform(Affine::translate((offset * size.width, 0.0)));
+                top.paint_raw(ctx, data, env);
+            });
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!(
+                "page {} of {}",
+                self.pages.len(),
+                self.pages.len() + self.forward.len()
+            ),
+            children: self
+                .pages
+                .last()
+                .map(|page| page.widget().debug_state(data))
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Transition {
+    fn offset(&self) -> f64 {
+        let t = (self.elapsed as f64 / self.duration as f64).min(1.0);
+        let eased = 1.0 - (1.0 - t).powi(3);
+        if self.leaving {
+            self.start + (1.0 - self.start) * eased
+        } else {
+            self.start * (1.0 - eased)
+        }
+    }
+}
+
+fn nav_request(event: &Event) -> Option<NavRequest<'_>> {
+    let (route, pop, forward) = match event {
+        Event::Notification(note) => (
+            note.get(NavigationStack::NAV_PUSH),
+            note.is(NavigationStack::NAV_POP),
+            note.is(NavigationStack::NAV_FORWARD),
+        ),
+        Event::Command(cmd) => (
+            cmd.get(NavigationStack::NAV_PUSH),
+            cmd.is(NavigationStack::NAV_POP),
+            cmd.is(NavigationStack::NAV_FORWARD),
+        ),
+        _ => return None,
+    };
+    match (route, pop, forward) {
+        (Some(route), _, _) => Some(NavRequest::Push(route)),
+        (None, true, _) => Some(NavRequest::Pop),
+        (None, false, true) => Some(NavRequest::Forward),
+        (None, false, false) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_offset() {
+        let mut transition = Transition {
+            leaving: true,
+            start: 0.5,
+            elapsed: 0,
+            duration: 100,
+        };
+        assert_eq!(transition.offset(), 0.5);
+        transition.elapsed = 50;
+        assert!(transition.offset() > 0.5 && transition.offset() < 1.0);
+        transition.elapsed = 150;
+        assert_eq!(transition.offset(), 1.0);
+
+        transition.leaving = false;
+        transition.elapsed = 0;
+        assert_eq!(transition.offset(), 0.5);
+        transition.elapsed = 100;
+        assert_eq!(transition.offset(), 0.0);
+    }
+
+    #[test]
+    fn route_payload() {
+        let route = NavRoute::new(3usize);
+        assert_eq!(route.get::<usize>(), Some(&3));
+        assert_eq!(route.get::<u32>(), None);
+    }
+}
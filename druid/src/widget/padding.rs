@@ -65,6 +65,11 @@ impl<T, W: Widget<T>> Padding<T, W> {
             child: WidgetPod::new(child),
         }
     }
+
+    /// Set the insets; the caller is responsible for requesting layout.
+    pub(crate) fn set_insets(&mut self, insets: impl Into<KeyOrValue<Insets>>) {
+        self.insets = insets.into();
+    }
 }
 
 impl<T, W> WidgetWrapper for Padding<T, W> {
@@ -143,4 +148,21 @@ impl<T: Data, W: Widget<T>> Widget<T> for Padding<T, W> {
             .compute_max_intrinsic(axis, ctx, &child_bc, data, env);
         child_max_intrinsic_width + axis.major(inset_size)
     }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let inset_size = self.insets.resolve(env).size();
+        let child_bc = bc.shrink(inset_size);
+        let child_min_intrinsic = self
+            .child
+            .widget_mut()
+            .compute_min_intrinsic(axis, ctx, &child_bc, data, env);
+        child_min_intrinsic + axis.major(inset_size)
+    }
 }
@@ -14,7 +14,7 @@
 
 use crate::piet::{FixedGradient, LinearGradient, PaintBrush, RadialGradient};
 use crate::widget::prelude::*;
-use crate::{Color, Data, Key};
+use crate::{Color, Data, Key, Point};
 use tracing::instrument;
 
 /// A widget that only handles painting.
@@ -77,6 +77,57 @@ use tracing::instrument;
 /// [`Controller`]: super::Controller
 pub struct Painter<T>(Box<dyn FnMut(&mut PaintCtx, &T, &Env)>);
 
+type HitTest = Box<dyn Fn(Size, Point) -> bool>;
+type EventHandler<T> = Box<dyn FnMut(&mut EventCtx, &Event, &mut T, &Env)>;
+
+/// A widget that paints with a closure, like [`Painter`], and handles events with
+/// another closure.
+///
+/// This is for small custom widgets, such as a draggable handle or a mini-map,
+/// that don't need the whole [`Widget`] trait. Unlike a [`Painter`], it repaints
+/// when it becomes hot or active or gains focus, so the paint closure can check
+/// [`is_hot`] and [`is_active`].
+///
+/// A hit test can limit which mouse events are handled to a shape within the
+/// widget, such as a circle. Mouse events that miss it are ignored, except while
+/// the widget is active, so that drags that started on the shape continue.
+///
+/// # Examples
+///
+/// A knob that can be dragged up and down to change a value:
+///
+/// ```
+/// use druid::kurbo::Circle;
+/// use druid::widget::InteractivePainter;
+/// use druid::{Event, RenderContext};
+///
+/// let knob = InteractivePainter::new(|ctx, value: &f64, env| {
+///     let size = ctx.size();
+///     let center = (size.width / 2.0, size.height * (1.0 - value));
+///     let color = match ctx.is_hot() || ctx.is_active() {
+///         true => env.get(druid::theme::PRIMARY_LIGHT),
+///         false => env.get(druid::theme::PRIMARY_DARK),
+///     };
+///     ctx.fill(Circle::new(center, 8.0), &color);
+/// })
+/// .on_event(|ctx, event, value, _env| match event {
+///     Event::MouseDown(_) => ctx.set_active(true),
+///     Event::MouseMove(mouse) if ctx.is_active() => {
+///         *value = (1.0 - mouse.pos.y / ctx.size().height).clamp(0.0, 1.0);
+///     }
+///     Event::MouseUp(_) => ctx.set_active(false),
+///     _ => {}
+/// });
+/// ```
+///
+/// [`is_hot`]: PaintCtx::is_hot
+/// [`is_active`]: PaintCtx::is_active
+pub struct InteractivePainter<T> {
+    painter: Painter<T>,
+    hit_test: Option<HitTest>,
+    on_event: Option<EventHandler<T>>,
+}
+
 /// Something that can be used as the background for a widget.
 ///
 /// This represents anything that can be painted inside a widgets [`paint`]
@@ -103,6 +154,51 @@ impl<T> Painter<T> {
     }
 }
 
+impl<T> InteractivePainter<T> {
+    /// Create a new `InteractivePainter` with the provided [`paint`] fn.
+    ///
+    /// [`paint`]: Widget::paint
+    pub fn new(f: impl FnMut(&mut PaintCtx, &T, &Env) + 'static) -> Self {
+        InteractivePainter {
+            painter: Painter::new(f),
+            hit_test: None,
+            on_event: None,
+        }
+    }
+
+    /// Builder-style method for limiting mouse events to the points for which `f`
+    /// returns `true`.
+    ///
+    /// `f` is called with the size of the widget and the position of the mouse.
+    pub fn with_hit_test(mut self, f: impl Fn(Size, Point) -> bool + 'static) -> Self {
+        self.hit_test = Some(Box::new(f));
+        self
+    }
+
+    /// Builder-style method for handling events.
+    pub fn on_event(
+        mut self,
+        f: impl FnMut(&mut EventCtx, &Event, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_event = Some(Box::new(f));
+        self
+    }
+
+    fn hits(&self, size: Size, event: &Event) -> bool {
+        let pos = match event {
+            Event::MouseDown(mouse)
+            | Event::MouseUp(mouse)
+            | Event::MouseMove(mouse)
+            | Event::Wheel(mouse) => mouse.pos,
+            _ => return true,
+        };
+        match &self.hit_test {
+            Some(hit_test) => hit_test(size, pos),
+            None => true,
+        }
+    }
+}
+
 impl<T: Data> BackgroundBrush<T> {
     /// Request paint if the BackgroundBrush changed.
     pub fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
@@ -148,6 +244,61 @@ impl<T: Data> Widget<T> for Painter<T> {
     }
 }
 
+impl<T: Data> Widget<T> for InteractivePainter<T> {
+    #[instrument(
+        name = "InteractivePainter",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if !ctx.is_active() && !self.hits(ctx.size(), event) {
+            return;
+        }
+        if let Some(on_event) = &mut self.on_event {
+            let was_active = ctx.is_active();
+            on_event(ctx, event, data, env);
+            if ctx.is_active() != was_active {
+                ctx.request_paint();
+            }
+        }
+    }
+
+    #[instrument(name = "InteractivePainter", level = "trace", skip(self, ctx, event))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _: &T, _: &Env) {
+        if let LifeCycle::HotChanged(_)
+        | LifeCycle::FocusChanged(_)
+        | LifeCycle::DisabledChanged(_) = event
+        {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(
+        name = "InteractivePainter",
+        level = "trace",
+        skip(self, ctx, old_data, data)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _: &Env) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "InteractivePainter", level = "trace", skip(self, _ctx, bc))]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _: &T, _: &Env) -> Size {
+        bc.max()
+    }
+
+    #[instrument(
+        name = "InteractivePainter",
+        level = "trace",
+        skip(self, ctx, data, env)
+    )]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        (self.painter.0)(ctx, data, env)
+    }
+}
+
 impl<T> From<Color> for BackgroundBrush<T> {
     fn from(src: Color) -> BackgroundBrush<T> {
         BackgroundBrush::Color(src)
@@ -0,0 +1,496 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A textbox for passwords and other secrets.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use tracing::instrument;
+
+use super::{Axis, TextBox};
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Circle, Line};
+use crate::text::Selection;
+use crate::widget::prelude::*;
+use crate::{theme, Point, Rect, SecureInputToken, TimerToken};
+
+/// The width and height of the reveal toggle.
+const REVEAL_TOGGLE_SIZE: f64 = 16.0;
+/// How long a newly typed character stays visible, if that is enabled.
+const LAST_CHAR_REVEAL_DURATION: Duration = Duration::from_millis(1000);
+
+/// A [`TextBox`] that masks its text, for entering passwords and other secrets.
+///
+/// Every character of the text is shown as the [`PASSWORD_MASK_CHAR`] from the
+/// [`Env`]. A toggle at the end of the box lets the user reveal the text and
+/// hide it again; it can be turned off with [`with_reveal_toggle`]. As with
+/// touch screen keyboards, each character can also be shown briefly as it is
+/// typed, with [`with_last_char_reveal`].
+///
+/// While the box has focus, the window is put into secure text entry where the
+/// platform supports it, so that other applications can't observe the keys
+/// being typed. The text is never included in [`debug_state`], and no undo
+/// history is kept.
+///
+/// This is created from a `TextBox` with [`TextBox::password`], so that it can
+/// be configured like any other textbox first.
+///
+/// [`PASSWORD_MASK_CHAR`]: crate::theme::PASSWORD_MASK_CHAR
+/// [`with_reveal_toggle`]: PasswordBox::with_reveal_toggle
+/// [`with_last_char_reveal`]: PasswordBox::with_last_char_reveal
+/// [`debug_state`]: Widget::debug_state
+pub struct PasswordBox {
+    child: TextBox<String>,
+    /// The text the child shows and edits: the data, masked.
+    buffer: String,
+    /// The buffer the child last saw in `update`.
+    old_buffer: String,
+    revealed: bool,
+    reveal_toggle: bool,
+    toggle_hot: bool,
+    last_char_reveal: bool,
+    /// The index, in chars, of a character that was just typed and is shown unmasked.
+    revealed_char: Option<usize>,
+    reveal_timer: TimerToken,
+    force_selection: Option<Selection>,
+    secure_input: Option<SecureInputToken>,
+}
+
+impl TextBox<String> {
+    /// Turn this `TextBox` into a [`PasswordBox`], which masks its text.
+    pub fn password(self) -> PasswordBox {
+        PasswordBox::new(self)
+    }
+}
+
+impl PasswordBox {
+    /// Create a new `PasswordBox` from a normal [`TextBox`].
+    pub fn new(child: TextBox<String>) -> Self {
+        PasswordBox {
+            child,
+            buffer: String::new(),
+            old_buffer: String::new(),
+            revealed: false,
+            reveal_toggle: true,
+            toggle_hot: false,
+            last_char_reveal: false,
+            revealed_char: None,
+            reveal_timer: TimerToken::INVALID,
+            force_selection: None,
+            secure_input: None,
+        }
+    }
+
+    /// Builder-style method to set whether the toggle for revealing the text
+    /// is shown. It is shown by default.
+    pub fn with_reveal_toggle(mut self, show: bool) -> Self {
+        self.set_reveal_toggle(show);
+        self
+    }
+
+    /// Set whether the toggle for revealing the text is shown.
+    ///
+    /// The text is hidden again if the toggle is removed. The caller is
+    /// responsible for requesting layout.
+    pub fn set_reveal_toggle(&mut self, show: bool) {
+        self.reveal_toggle = show;
+        if !show {
+            self.revealed = false;
+        }
+    }
+
+    /// Builder-style method to set whether each character is shown briefly
+    /// as it is typed. This is off by default.
+    pub fn with_last_char_reveal(mut self, reveal: bool) -> Self {
+        self.set_last_char_reveal(reveal);
+        self
+    }
+
+    /// Set whether each character is shown briefly as it is typed.
+    pub fn set_last_char_reveal(&mut self, reveal: bool) {
+        self.last_char_reveal = reveal;
+    }
+
+    /// Returns `true` if the user has revealed the text.
+    pub fn is_revealed(&self) -> bool {
+        self.revealed
+    }
+
+    /// The text to show for `secret`.
+    fn display_text(&self, secret: &str, env: &Env) -> String {
+        if self.revealed {
+            return secret.to_owned();
+        }
+        let mask = env
+            .get(theme::PASSWORD_MASK_CHAR)
+            .chars()
+            .next()
+            .unwrap_or('\u{2022}');
+        secret
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                if Some(i) == self.revealed_char {
+                    c
+                } else {
+                    mask
+                }
+            })
+            .collect()
+    }
+
+    /// Replace the buffer, keeping the selection on the same characters.
+    fn set_buffer(&mut self, text: String) {
+        if self.child.text().can_read() {
+            let sel = self.child.text().borrow().selection();
+            let remap = |offset| byte_offset(&text, char_offset(&self.buffer, offset));
+            self.force_selection = Some(Selection::new(remap(sel.anchor), remap(sel.active)));
+        }
+        self.buffer = text;
+    }
+
+    /// If the child edited the buffer, make the same edit to the data.
+    fn apply_child_edit(&mut self, ctx: &mut EventCtx, data: &mut String, env: &Env) {
+        // the text being composed is applied when the composition ends
+        if !self.child.text().can_read() || self.child.text().is_composing() {
+            return;
+        }
+        let shown = self.display_text(data, env);
+        if shown == self.buffer {
+            return;
+        }
+        let caret = self.child.text().borrow().selection().active;
+        let (secret, inserted) = apply_edit(data, &shown, &self.buffer, caret);
+        *data = secret;
+
+        self.revealed_char = None;
+        if self.last_char_reveal && !self.revealed && inserted.len() == 1 {
+            self.revealed_char = Some(inserted.start);
+            self.reveal_timer = ctx.request_timer(LAST_CHAR_REVEAL_DURATION);
+        }
+        let shown = self.display_text(data, env);
+        self.set_buffer(shown);
+        ctx.request_update();
+    }
+
+    /// The `Env` for the child, with room at the end for the reveal toggle.
+    fn child_env(&self, env: &Env) -> Env {
+        if !self.reveal_toggle {
+            return env.clone();
+        }
+        let mut insets = env.get(theme::TEXTBOX_INSETS);
+        // the toggle is as far from the text as from the border
+        insets.x1 = 2.0 * insets.x1 + REVEAL_TOGGLE_SIZE;
+        env.clone().adding(theme::TEXTBOX_INSETS, insets)
+    }
+
+    /// The rect of the reveal toggle, at the end of a box of `size`.
+    fn toggle_rect(size: Size, env: &Env) -> Rect {
+        let x1 = size.width - env.get(theme::TEXTBOX_INSETS).x1;
+        let center = Point::new(x1 - REVEAL_TOGGLE_SIZE / 2.0, size.height / 2.0);
+        Rect::from_center_size(center, (REVEAL_TOGGLE_SIZE, REVEAL_TOGGLE_SIZE))
+    }
+
+    /// The area that toggles the reveal when clicked; the full height of the
+    /// box, from the end of the text.
+    fn toggle_hit_rect(size: Size, env: &Env) -> Rect {
+        let rect = Self::toggle_rect(size, env);
+        Rect::new(
+            rect.x0 - env.get(theme::TEXTBOX_INSETS).x1,
+            0.0,
+            size.width,
+            size.height,
+        )
+    }
+
+    fn paint_toggle(&self, ctx: &mut PaintCtx, env: &Env) {
+        let rect = Self::toggle_rect(ctx.size(), env);
+        let color = if self.toggle_hot {
+            env.get(theme::TEXT_COLOR)
+        } else {
+            env.get(theme::PLACEHOLDER_COLOR)
+        };
+        let center = rect.center();
+
+        // an eye, with a pupil
+        let mut eye = BezPath::new();
+        eye.move_to((rect.x0, center.y));
+        eye.quad_to(
+            (center.x, center.y - rect.height() * 0.6),
+            (rect.x1, center.y),
+        );
+        eye.quad_to(
+            (center.x, center.y + rect.height() * 0.6),
+            (rect.x0, center.y),
+        );
+        eye.close_path();
+        ctx.stroke(eye, &color, 1.5);
+        ctx.fill(Circle::new(center, rect.height() * 0.15), &color);
+
+        // struck through while the text is revealed, since clicking hides it
+        if self.revealed {
+            let slash = Line::new(
+                (rect.x0 + 2.0, rect.y1 - 1.0),
+                (rect.x1 - 2.0, rect.y0 + 1.0),
+            );
+            ctx.stroke(slash, &color, 1.5);
+        }
+    }
+}
+
+impl Widget<String> for PasswordBox {
+    #[instrument(
+        name = "PasswordBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.reveal_timer => {
+                self.reveal_timer = TimerToken::INVALID;
+                self.revealed_char = None;
+                let shown = self.display_text(data, env);
+                self.set_buffer(shown);
+                ctx.request_update();
+                return;
+            }
+            Event::MouseDown(mouse)
+                if self.reveal_toggle
+                    && Self::toggle_hit_rect(ctx.size(), env).contains(mouse.pos) =>
+            {
+                self.revealed = !self.revealed;
+                self.revealed_char = None;
+                let shown = self.display_text(data, env);
+                self.set_buffer(shown);
+                ctx.request_update();
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+            // the selection is in bytes of the data, which the buffer doesn't share
+            Event::Command(cmd) if cmd.is(TextBox::SET_SELECTION) => {
+                let sel = *cmd.get_unchecked(TextBox::SET_SELECTION);
+                let remap = |offset| byte_offset(&self.buffer, char_offset(data, offset));
+                let sel = Selection::new(remap(sel.anchor), remap(sel.active));
+                self.child.set_selection(ctx, &self.buffer, sel);
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(mouse) => {
+                let hot = self.reveal_toggle
+                    && ctx.is_hot()
+                    && !ctx.is_active()
+                    && Self::toggle_hit_rect(ctx.size(), env).contains(mouse.pos);
+                if hot != self.toggle_hot {
+                    self.toggle_hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            _ => (),
+        }
+        let child_env = self.child_env(env);
+        self.child.event(ctx, event, &mut self.buffer, &child_env);
+        self.apply_child_edit(ctx, data, env);
+    }
+
+    #[instrument(
+        name = "PasswordBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &String, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.buffer = self.display_text(data, env);
+                self.old_buffer = self.buffer.clone();
+            }
+            LifeCycle::FocusChanged(true) => {
+                self.secure_input = Some(ctx.window().begin_secure_input());
+            }
+            LifeCycle::FocusChanged(false) => {
+                self.secure_input = None;
+            }
+            LifeCycle::HotChanged(false) if self.toggle_hot => {
+                self.toggle_hot = false;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+        let child_env = self.child_env(env);
+        self.child.lifecycle(ctx, event, &self.buffer, &child_env);
+    }
+
+    #[instrument(
+        name = "PasswordBox",
+        level = "trace",
+        skip(self, ctx, _old, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &String, data: &String, env: &Env) {
+        if self.child.text().can_read() && !self.child.text().is_composing() {
+            let shown = self.display_text(data, env);
+            if shown != self.buffer {
+                self.set_buffer(shown);
+            }
+        }
+
+        let child_env = self.child_env(env);
+        if self.old_buffer != self.buffer {
+            self.child
+                .update(ctx, &self.old_buffer, &self.buffer, &child_env);
+            self.old_buffer = self.buffer.clone();
+            // the history would only hold the masked text, which can't be restored
+            if self.child.text().can_write() {
+                self.child.text_mut().borrow_mut().clear_history();
+            }
+        } else if ctx.env_changed() {
+            self.child
+                .update(ctx, &self.buffer, &self.buffer, &child_env);
+        }
+
+        if let Some(sel) = self.force_selection.take() {
+            if self.child.text().can_write() {
+                if let Some(change) = self.child.text_mut().borrow_mut().set_selection(sel) {
+                    ctx.invalidate_text_input(change);
+                }
+            }
+        }
+    }
+
+    #[instrument(name = "PasswordBox", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &String,
+        env: &Env,
+    ) -> Size {
+        let child_env = self.child_env(env);
+        self.child.layout(ctx, bc, &self.buffer, &child_env)
+    }
+
+    #[instrument(name = "PasswordBox", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &String, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.paint(ctx, &self.buffer, &child_env);
+        if self.reveal_toggle {
+            self.paint_toggle(ctx, env);
+        }
+    }
+
+    fn debug_state(&self, data: &String) -> DebugState {
+        // never the secret, even while it is revealed on screen
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: "\u{2022}".repeat(data.chars().count()),
+            ..Default::default()
+        }
+    }
+
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &String,
+        env: &Env,
+    ) -> f64 {
+        let child_env = self.child_env(env);
+        self.child
+            .compute_max_intrinsic(axis, ctx, bc, &self.buffer, &child_env)
+    }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &String,
+        env: &Env,
+    ) -> f64 {
+        let child_env = self.child_env(env);
+        self.child
+            .compute_min_intrinsic(axis, ctx, bc, &self.buffer, &child_env)
+    }
+}
+
+/// Make an edit of the displayed text to the secret it stands for.
+///
+/// The displayed text has a char for each char of `secret`, so the edit is
+/// found by comparing the `old` and `new` displayed text. A typed mask
+/// character can't be told apart from the mask, so the `caret`, which follows
+/// inserted text, decides where the edit was.
+///
+/// Returns the new secret, and the range of chars that were inserted.
+fn apply_edit(secret: &str, old: &str, new: &str, caret: usize) -> (String, Range<usize>) {
+    let old_len = old.chars().count();
+    let new_len = new.chars().count();
+    let caret = char_offset(new, caret);
+    // everything after the caret is left over from before the edit
+    let suffix = (new_len - caret).min(old_len);
+    let common = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let prefix = common.min(caret).min(old_len - suffix);
+
+    let secret = secret
+        .chars()
+        .take(prefix)
+        .chain(new.chars().take(new_len - suffix).skip(prefix))
+        .chain(secret.chars().skip(old_len - suffix))
+        .collect();
+    (secret, prefix..new_len - suffix)
+}
+
+/// The number of chars before the byte `offset` of `text`.
+fn char_offset(text: &str, offset: usize) -> usize {
+    text.char_indices().take_while(|(i, _)| *i < offset).count()
+}
+
+/// The byte offset of the char at index `n` of `text`, or the length of `text`.
+fn byte_offset(text: &str, n: usize) -> usize {
+    text.char_indices()
+        .nth(n)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_of_masked_text() {
+        // typing at the end
+        let (secret, inserted) = apply_edit("abc", "•••", "•••d", "•••d".len());
+        assert_eq!(secret, "abcd");
+        assert_eq!(inserted, 3..4);
+
+        // typing the mask character itself, in the middle
+        let (secret, inserted) = apply_edit("abc", "•••", "••••", "••".len());
+        assert_eq!(secret, "a•bc");
+        assert_eq!(inserted, 1..2);
+
+        // backspace in the middle
+        let (secret, inserted) = apply_edit("abcd", "••••", "•••", "•".len());
+        assert_eq!(secret, "acd");
+        assert!(inserted.is_empty());
+
+        // replacing a selection, next to a revealed character
+        let (secret, _) = apply_edit("abcd", "•b••", "•x•", "•x".len());
+        assert_eq!(secret, "axd");
+    }
+}
@@ -0,0 +1,618 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget for reading PDF documents.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::piet::{ImageBuf, InterpolationMode};
+use crate::widget::prelude::*;
+use crate::{
+    commands, theme, Application, HotKey, KbKey, Point, Rect, ScaledArea, Selector, SysMods,
+};
+
+/// The space between pages, and around the edges.
+const PAGE_GAP: f64 = 12.0;
+const MIN_ZOOM: f64 = 0.1;
+const MAX_ZOOM: f64 = 8.0;
+/// How much each step of the mouse wheel changes the zoom by.
+const ZOOM_STEP: f64 = 1.1;
+/// The number of rendered pages kept around for scrolling back to.
+const MAX_CACHED_PAGES: usize = 8;
+
+/// A character on a page of a [`PdfDocument`], used for selecting and searching text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfChar {
+    /// The character.
+    pub ch: char,
+    /// The box the character occupies, in points from the top left of the page.
+    pub bounds: Rect,
+}
+
+/// A PDF document that can be shown in a [`PdfView`].
+///
+/// Druid doesn't parse or render PDF itself. This is implemented by the
+/// application on top of a PDF library, for instance pdfium or a pure-Rust
+/// renderer.
+pub trait PdfDocument {
+    /// The number of pages in the document.
+    fn page_count(&self) -> usize;
+
+    /// The size of the page with this index, in points.
+    fn page_size(&self, page: usize) -> Size;
+
+    /// Render the page with this index to an image of `size_px` pixels.
+    fn render_page(&self, page: usize, size_px: Size) -> Option<ImageBuf>;
+
+    /// The characters on the page with this index, in reading order.
+    ///
+    /// Without this, text on the page can't be selected or searched.
+    fn page_text(&self, _page: usize) -> Vec<PdfChar> {
+        Vec::new()
+    }
+}
+
+/// How a [`PdfView`] lays out the pages of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfViewMode {
+    /// All pages, one below another.
+    Continuous,
+    /// Only the current page.
+    SinglePage,
+}
+
+/// A widget that shows a [`PdfDocument`].
+///
+/// In [`Continuous`] mode the view grows to fit every page and is meant to be
+/// put in a [`Scroll`]; only pages that are visible are rendered, and a few
+/// recently rendered pages are kept for scrolling back. Pages are rendered at
+/// the resolution of the window.
+///
+/// Text can be selected by dragging within a page and copied with the usual
+/// shortcut or [`COPY`] command, and searched for with the [`FIND`] command.
+/// The view is controlled with these commands:
+///
+/// - [`GO_TO_PAGE`], [`NEXT_PAGE`] and [`PREVIOUS_PAGE`] change the current page,
+///   which is scrolled into view. Page up, page down, home and end do the same.
+/// - [`FIND`] highlights every match for some text, ignoring case, and shows the
+///   first one from the current page on. [`FIND_NEXT`] and [`FIND_PREVIOUS`] move
+///   between the matches.
+/// - Ctrl and the mouse wheel zoom.
+///
+/// [`Continuous`]: PdfViewMode::Continuous
+/// [`Scroll`]: super::Scroll
+/// [`COPY`]: crate::commands::COPY
+/// [`GO_TO_PAGE`]: PdfView::GO_TO_PAGE
+/// [`NEXT_PAGE`]: PdfView::NEXT_PAGE
+/// [`PREVIOUS_PAGE`]: PdfView::PREVIOUS_PAGE
+/// [`FIND`]: PdfView::FIND
+/// [`FIND_NEXT`]: PdfView::FIND_NEXT
+/// [`FIND_PREVIOUS`]: PdfView::FIND_PREVIOUS
+pub struct PdfView {
+    mode: PdfViewMode,
+    zoom: f64,
+    page: usize,
+    /// The rectangle of each page; empty for pages that aren't shown.
+    page_rects: Vec<Rect>,
+    /// Rendered pages, least recently used first.
+    images: Vec<(usize, ImageBuf)>,
+    text: HashMap<usize, Vec<PdfChar>>,
+    selection: Option<TextSelection>,
+    matches: Vec<(usize, Range<usize>)>,
+    current_match: usize,
+}
+
+/// A range of characters on one page, between where a drag started and where it is now.
+#[derive(Debug, Clone, Copy)]
+struct TextSelection {
+    page: usize,
+    anchor: usize,
+    active: usize,
+}
+
+impl TextSelection {
+    fn range(&self) -> Range<usize> {
+        self.anchor.min(self.active)..self.anchor.max(self.active) + 1
+    }
+}
+
+impl PdfView {
+    /// Make the page with this index the current page.
+    pub const GO_TO_PAGE: Selector<usize> = Selector::new("druid-builtin.pdf-view-go-to-page");
+
+    /// Go to the next page.
+    pub const NEXT_PAGE: Selector = Selector::new("druid-builtin.pdf-view-next-page");
+
+    /// Go to the previous page.
+    pub const PREVIOUS_PAGE: Selector = Selector::new("druid-builtin.pdf-view-previous-page");
+
+    /// Search for this text; an empty string clears the search.
+    pub const FIND: Selector<String> = Selector::new("druid-builtin.pdf-view-find");
+
+    /// Show the next match of the current search.
+    pub const FIND_NEXT: Selector = Selector::new("druid-builtin.pdf-view-find-next");
+
+    /// Show the previous match of the current search.
+    pub const FIND_PREVIOUS: Selector = Selector::new("druid-builtin.pdf-view-find-previous");
+
+    /// Create a new `PdfView`, showing pages continuously at their actual size.
+    pub fn new() -> Self {
+        PdfView {
+            mode: PdfViewMode::Continuous,
+            zoom: 1.0,
+            page: 0,
+            page_rects: Vec::new(),
+            images: Vec::new(),
+            text: HashMap::new(),
+            selection: None,
+            matches: Vec::new(),
+            current_match: 0,
+        }
+    }
+
+    /// Builder-style method for setting how pages are laid out.
+    pub fn with_mode(mut self, mode: PdfViewMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Builder-style method for setting the initial zoom, the size in display
+    /// points of one point on the page.
+    pub fn with_zoom(mut self, zoom: f64) -> Self {
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        self
+    }
+
+    /// The index of the current page.
+    pub fn current_page(&self) -> usize {
+        self.page
+    }
+
+    /// The current zoom.
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// The number of matches for the last [`FIND`].
+    ///
+    /// [`FIND`]: PdfView::FIND
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// The selected text, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        let selection = self.selection?;
+        let chars = self.text.get(&selection.page)?;
+        let text: String = chars.get(selection.range())?.iter().map(|c| c.ch).collect();
+        Some(text)
+    }
+
+    fn page_text(&mut self, doc: &dyn PdfDocument, page: usize) -> &[PdfChar] {
+        self.text.entry(page).or_insert_with(|| doc.page_text(page))
+    }
+
+    fn reset(&mut self) {
+        self.page = 0;
+        self.images.clear();
+        self.text.clear();
+        self.selection = None;
+        self.matches.clear();
+        self.current_match = 0;
+    }
+
+    /// The page and the position on it, in points, of a point in the widget.
+    fn page_at(&self, pos: Point) -> Option<(usize, Point)> {
+        let page = self.page_rects.iter().position(|rect| rect.contains(pos))?;
+        let origin = self.page_rects[page].origin();
+        Some((page, ((pos - origin) / self.zoom).to_point()))
+    }
+
+    /// Make `page` the current page, and scroll it into view.
+    fn go_to(&mut self, ctx: &mut EventCtx, page: usize, count: usize) {
+        let page = page.min(count.saturating_sub(1));
+        let changed = page != self.page;
+        self.page = page;
+        match self.mode {
+            PdfViewMode::Continuous => {
+                if let Some(rect) = self.page_rects.get(page) {
+                    ctx.scroll_area_to_view(rect.inset(PAGE_GAP));
+                }
+            }
+            PdfViewMode::SinglePage if changed => ctx.request_layout(),
+            PdfViewMode::SinglePage => {}
+        }
+    }
+
+    /// Find every match for `query` in the document, ignoring case.
+    fn find(&mut self, doc: &dyn PdfDocument, query: &str) {
+        self.matches.clear();
+        self.current_match = 0;
+        let query: Vec<char> = query.chars().map(fold_case).collect();
+        if query.is_empty() {
+            return;
+        }
+        for page in 0..doc.page_count() {
+            let text: Vec<char> = self
+                .page_text(doc, page)
+                .iter()
+                .map(|c| fold_case(c.ch))
+                .collect();
+            for start in 0..text.len().saturating_sub(query.len() - 1) {
+                if text[start..start + query.len()] == query[..] {
+                    self.matches.push((page, start..start + query.len()));
+                }
+            }
+        }
+        self.current_match = self
+            .matches
+            .iter()
+            .position(|(page, _)| *page >= self.page)
+            .unwrap_or(0);
+    }
+
+    fn show_match(&mut self, ctx: &mut EventCtx, count: usize) {
+        if let Some((page, _)) = self.matches.get(self.current_match) {
+            let page = *page;
+            self.go_to(ctx, page, count);
+            ctx.request_paint();
+        }
+    }
+
+    /// The rectangles of some characters on a page, in the widget's coordinate space.
+    fn char_rects(&self, page: usize, range: Range<usize>) -> Vec<Rect> {
+        let origin = self.page_rects[page].origin().to_vec2();
+        self.text
+            .get(&page)
+            .and_then(|chars| chars.get(range))
+            .unwrap_or_default()
+            .iter()
+            .map(|c| c.bounds.scale_from_origin(self.zoom) + origin)
+            .collect()
+    }
+
+    /// Get the rendered image of a page, rendering it if needed.
+    fn page_image(
+        &mut self,
+        doc: &dyn PdfDocument,
+        page: usize,
+        size_px: Size,
+    ) -> Option<&ImageBuf> {
+        let cached = self
+            .images
+            .iter()
+            .position(|(p, image)| *p == page && image.size() == size_px);
+        let entry = match cached {
+            Some(idx) => self.images.remove(idx),
+            None => {
+                self.images.retain(|(p, _)| *p != page);
+                if self.images.len() >= MAX_CACHED_PAGES {
+                    self.images.remove(0);
+                }
+                (page, doc.render_page(page, size_px)?)
+            }
+        };
+        self.images.push(entry);
+        self.images.last().map(|(_, image)| image)
+    }
+}
+
+impl Default for PdfView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn fold_case(ch: char) -> char {
+    ch.to_lowercase().next().unwrap_or(ch)
+}
+
+impl Widget<Arc<dyn PdfDocument>> for PdfView {
+    #[instrument(name = "PdfView", level = "trace", skip(self, ctx, event, data, _env))]
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut Arc<dyn PdfDocument>,
+        _env: &Env,
+    ) {
+        let count = data.page_count();
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.request_focus();
+                self.selection = None;
+                if let Some((page, pos)) = self.page_at(mouse.pos) {
+                    self.page = page;
+                    let hit = self
+                        .page_text(&**data, page)
+                        .iter()
+                        .position(|c| c.bounds.contains(pos));
+                    if let Some(idx) = hit {
+                        self.selection = Some(TextSelection {
+                            page,
+                            anchor: idx,
+                            active: idx,
+                        });
+                        ctx.set_active(true);
+                    }
+                }
+                ctx.request_paint();
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some(mut selection) = self.selection {
+                    let origin = self.page_rects[selection.page].origin();
+                    let pos = ((mouse.pos - origin) / self.zoom).to_point();
+                    let nearest = self
+                        .page_text(&**data, selection.page)
+                        .iter()
+                        .enumerate()
+                        .min_by(|(_, a), (_, b)| {
+                            let a = a.bounds.center().distance(pos);
+                            let b = b.bounds.center().distance(pos);
+                            a.total_cmp(&b)
+                        });
+                    if let Some((idx, _)) = nearest {
+                        if idx != selection.active {
+                            selection.active = idx;
+                            self.selection = Some(selection);
+                            ctx.request_paint();
+                        }
+                    }
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => ctx.set_active(false),
+            Event::Wheel(mouse) if mouse.mods.ctrl() => {
+                let steps = -mouse.wheel_delta.y.signum();
+                let zoom = (self.zoom * ZOOM_STEP.powf(steps)).clamp(MIN_ZOOM, MAX_ZOOM);
+                if zoom != self.zoom {
+                    self.zoom = zoom;
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                if HotKey::new(SysMods::Cmd, "c").matches(key) {
+                    ctx.submit_command(commands::COPY.to(ctx.widget_id()));
+                } else {
+                    let page = match key.key {
+                        KbKey::PageDown => self.page + 1,
+                        KbKey::PageUp => self.page.saturating_sub(1),
+                        KbKey::Home => 0,
+                        KbKey::End => count.saturating_sub(1),
+                        _ => return,
+                    };
+                    self.go_to(ctx, page, count);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if ctx.is_focused() && cmd.is(commands::COPY) => {
+                if let Some(text) = self.selected_text().filter(|text| !text.is_empty()) {
+                    Application::global().clipboard().put_string(text);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) => {
+                if let Some(page) = cmd.get(PdfView::GO_TO_PAGE) {
+                    self.go_to(ctx, *page, count);
+                } else if cmd.is(PdfView::NEXT_PAGE) {
+                    self.go_to(ctx, self.page + 1, count);
+                } else if cmd.is(PdfView::PREVIOUS_PAGE) {
+                    self.go_to(ctx, self.page.saturating_sub(1), count);
+                } else if let Some(query) = cmd.get(PdfView::FIND) {
+                    self.find(&**data, query);
+                    self.show_match(ctx, count);
+                    ctx.request_paint();
+                } else if cmd.is(PdfView::FIND_NEXT) && !self.matches.is_empty() {
+                    self.current_match = (self.current_match + 1) % self.matches.len();
+                    self.show_match(ctx, count);
+                } else if cmd.is(PdfView::FIND_PREVIOUS) && !self.matches.is_empty() {
+                    let len = self.matches.len();
+                    self.current_match = (self.current_match + len - 1) % len;
+                    self.show_match(ctx, count);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[instrument(name = "PdfView", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &Arc<dyn PdfDocument>,
+        _env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+    }
+
+    #[instrument(
+        name = "PdfView",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &Arc<dyn PdfDocument>,
+        data: &Arc<dyn PdfDocument>,
+        _env: &Env,
+    ) {
+        if !old_data.same(data) {
+            self.reset();
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "PdfView", level = "trace", skip(self, _ctx, bc, data, _env))]
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Arc<dyn PdfDocument>,
+        _env: &Env,
+    ) -> Size {
+        bc.debug_check("PdfView");
+        let count = data.page_count();
+        self.page = self.page.min(count.saturating_sub(1));
+        let shown = match self.mode {
+            PdfViewMode::Continuous => 0..count,
+            PdfViewMode::SinglePage => self.page..(self.page + 1).min(count),
+        };
+
+        let sizes: Vec<Size> = shown
+            .clone()
+            .map(|page| data.page_size(page) * self.zoom)
+            .collect();
+        let widest = sizes.iter().map(|size| size.width).fold(0.0, f64::max);
+        let height = sizes.iter().map(|size| size.height + PAGE_GAP).sum::<f64>() + PAGE_GAP;
+        let size = bc.constrain(Size::new(widest + 2.0 * PAGE_GAP, height));
+
+        self.page_rects = vec![Rect::ZERO; count];
+        let mut y = PAGE_GAP;
+        for (page, page_size) in shown.zip(sizes) {
+            let x = ((size.width - page_size.width) / 2.0).max(PAGE_GAP);
+            self.page_rects[page] = Rect::from_origin_size((x, y), page_size);
+            y += page_size.height + PAGE_GAP;
+        }
+
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "PdfView", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Arc<dyn PdfDocument>, env: &Env) {
+        let background = ctx.size().to_rect();
+        ctx.fill(background, &env.get(theme::BACKGROUND_DARK));
+
+        let visible = ctx.region().bounding_box();
+        for page in 0..self.page_rects.len() {
+            let rect = self.page_rects[page];
+            if rect.area() <= 0.0 || rect.intersect(visible).area() <= 0.0 {
+                continue;
+            }
+            let size_px = ScaledArea::from_dp(rect.size(), ctx.scale()).size_px();
+            match self.page_image(&**data, page, size_px) {
+                Some(image) => {
+                    let image = image.to_image(ctx.render_ctx);
+                    ctx.draw_image(&image, rect, InterpolationMode::Bilinear);
+                }
+                None => {
+                    tracing::error!("unable to render page {} of PDF", page);
+                    ctx.fill(rect, &crate::Color::WHITE);
+                }
+            }
+            ctx.stroke(rect.inset(0.5), &env.get(theme::BORDER_DARK), 1.0);
+        }
+
+        let match_color = env
+            .get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+            .with_alpha(0.5);
+        let current_match_color = env.get(theme::PRIMARY_LIGHT).with_alpha(0.5);
+        for (idx, (page, range)) in self.matches.iter().enumerate() {
+            let rect = self.page_rects[*page];
+            if rect.area() <= 0.0 || rect.intersect(visible).area() <= 0.0 {
+                continue;
+            }
+            let color = if idx == self.current_match {
+                &current_match_color
+            } else {
+                &match_color
+            };
+            for char_rect in self.char_rects(*page, range.clone()) {
+                ctx.fill(char_rect, color);
+            }
+        }
+
+        if let Some(selection) = self.selection {
+            let color = if ctx.is_focused() {
+                env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+            } else {
+                env.get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+            }
+            .with_alpha(0.5);
+            for char_rect in self.char_rects(selection.page, selection.range()) {
+                ctx.fill(char_rect, &color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two pages, each with one line of text in 10pt wide characters.
+    struct Document;
+
+    impl PdfDocument for Document {
+        fn page_count(&self) -> usize {
+            2
+        }
+
+        fn page_size(&self, _page: usize) -> Size {
+            Size::new(200.0, 100.0)
+        }
+
+        fn render_page(&self, _page: usize, _size_px: Size) -> Option<ImageBuf> {
+            None
+        }
+
+        fn page_text(&self, page: usize) -> Vec<PdfChar> {
+            let text = ["Hello world", "Goodbye WORLD"][page];
+            text.chars()
+                .enumerate()
+                .map(|(i, ch)| PdfChar {
+                    ch,
+                    bounds: Rect::new(i as f64 * 10.0, 0.0, i as f64 * 10.0 + 10.0, 12.0),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn find_text() {
+        let mut view = PdfView::new();
+        view.find(&Document, "world");
+        assert_eq!(view.matches, vec![(0, 6..11), (1, 8..13)]);
+
+        view.page = 1;
+        view.find(&Document, "O");
+        assert_eq!(view.match_count(), 5);
+        assert_eq!(view.matches[view.current_match], (1, 1..2));
+
+        view.find(&Document, "");
+        assert_eq!(view.match_count(), 0);
+    }
+
+    #[test]
+    fn selected_text() {
+        let mut view = PdfView::new();
+        view.page_text(&Document, 1);
+        view.selection = Some(TextSelection {
+            page: 1,
+            anchor: 6,
+            active: 2,
+        });
+        assert_eq!(view.selected_text().as_deref(), Some("odbye"));
+    }
+}
@@ -0,0 +1,573 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An editable grid of notes, like the piano roll of a sequencer.
+
+use std::collections::BTreeSet;
+
+use tracing::{instrument, trace};
+
+use crate::im::Vector;
+use crate::kurbo::Line;
+use crate::widget::prelude::*;
+use crate::{theme, Cursor, Data, KbKey, Point, Rect};
+
+/// How close to the right edge of a note the mouse has to be to resize it.
+const RESIZE_HANDLE: f64 = 5.0;
+/// How much each step of the mouse wheel changes the zoom by.
+const ZOOM_STEP: f64 = 1.1;
+const MIN_BEAT_WIDTH: f64 = 4.0;
+const MAX_BEAT_WIDTH: f64 = 400.0;
+/// The number of empty beats shown after the last note.
+const TRAILING_BEATS: f64 = 8.0;
+
+/// A note in a [`PianoRoll`].
+#[derive(Debug, Clone, PartialEq, Data)]
+pub struct Note {
+    /// When the note starts, in beats.
+    pub start: f64,
+    /// How long the note lasts, in beats.
+    pub length: f64,
+    /// The row of the note, counting up from the bottom row.
+    pub row: usize,
+}
+
+/// An editable grid of [`Note`]s, with time from left to right and rows, such as
+/// pitches, from bottom to top.
+///
+/// - Notes are dragged to move them, and dragged by their right edge to resize them.
+///   Moving and resizing snap to the [`snap`] interval.
+/// - Clicking a note selects it, and shift-clicking adds it to the selection.
+///   Dragging across empty space selects the notes in a rectangle. Moving a selected
+///   note moves the whole selection.
+/// - Double-clicking empty space adds a note, and delete or backspace removes the
+///   selected notes.
+/// - Ctrl and the mouse wheel zoom in time.
+///
+/// The grid grows to fit all the notes, so it is normally put in a [`Scroll`].
+///
+/// [`snap`]: PianoRoll::snap
+/// [`Scroll`]: super::Scroll
+pub struct PianoRoll {
+    rows: usize,
+    row_height: f64,
+    beat_width: f64,
+    beats_per_bar: usize,
+    snap: f64,
+    min_beats: f64,
+    selection: BTreeSet<usize>,
+    drag: Option<Drag>,
+}
+
+/// What a drag started on a [`PianoRoll`] is doing.
+#[derive(Debug, Clone)]
+enum Drag {
+    /// Moving the selected notes, which started at these positions.
+    Move {
+        origin: Point,
+        notes: Vec<(usize, Note)>,
+    },
+    /// Changing the length of a note, which started with this length.
+    Resize {
+        origin: Point,
+        note: usize,
+        length: f64,
+    },
+    /// Selecting the notes in a rectangle, keeping the notes that were selected before.
+    Marquee {
+        origin: Point,
+        current: Point,
+        previous: BTreeSet<usize>,
+    },
+}
+
+impl PianoRoll {
+    /// Create a piano roll with 128 rows, the number of MIDI pitches.
+    pub fn new() -> Self {
+        PianoRoll {
+            rows: 128,
+            row_height: 12.0,
+            beat_width: 40.0,
+            beats_per_bar: 4,
+            snap: 0.25,
+            min_beats: 16.0,
+            selection: BTreeSet::new(),
+            drag: None,
+        }
+    }
+
+    /// Builder-style method for setting the number of rows.
+    pub fn with_rows(mut self, rows: usize) -> Self {
+        self.rows = rows.max(1);
+        self
+    }
+
+    /// Builder-style method for setting the height of each row.
+    pub fn with_row_height(mut self, height: f64) -> Self {
+        self.row_height = height;
+        self
+    }
+
+    /// Builder-style method for setting the initial width of a beat.
+    pub fn with_beat_width(mut self, width: f64) -> Self {
+        self.beat_width = width.clamp(MIN_BEAT_WIDTH, MAX_BEAT_WIDTH);
+        self
+    }
+
+    /// Builder-style method for setting how many beats there are in a bar, which
+    /// is where heavier grid lines are drawn.
+    pub fn with_beats_per_bar(mut self, beats: usize) -> Self {
+        self.beats_per_bar = beats.max(1);
+        self
+    }
+
+    /// Builder-style method for setting the interval, in beats, that notes snap to.
+    ///
+    /// Zero turns snapping off.
+    pub fn snap(mut self, beats: f64) -> Self {
+        self.snap = beats.max(0.0);
+        self
+    }
+
+    /// Builder-style method for setting the smallest number of beats shown.
+    pub fn with_min_beats(mut self, beats: f64) -> Self {
+        self.min_beats = beats;
+        self
+    }
+
+    /// The indices of the selected notes.
+    pub fn selection(&self) -> impl Iterator<Item = usize> + '_ {
+        self.selection.iter().copied()
+    }
+
+    /// The length of a new note, and the shortest a note can be resized to.
+    fn default_length(&self) -> f64 {
+        if self.snap > 0.0 {
+            self.snap
+        } else {
+            1.0
+        }
+    }
+
+    fn row_top(&self, row: usize) -> f64 {
+        (self.rows - 1 - row.min(self.rows - 1)) as f64 * self.row_height
+    }
+
+    fn row_at(&self, y: f64) -> usize {
+        let from_top = (y / self.row_height)
+            .floor()
+            .clamp(0.0, (self.rows - 1) as f64);
+        self.rows - 1 - from_top as usize
+    }
+
+    fn note_rect(&self, note: &Note) -> Rect {
+        let x = note.start * self.beat_width;
+        let y = self.row_top(note.row);
+        Rect::new(x, y, x + note.length * self.beat_width, y + self.row_height)
+    }
+
+    /// The topmost note at `pos`, and whether `pos` is on its resize handle.
+    fn note_at(&self, notes: &Vector<Note>, pos: Point) -> Option<(usize, bool)> {
+        notes.iter().enumerate().rev().find_map(|(idx, note)| {
+            let rect = self.note_rect(note);
+            rect.contains(pos).then(|| {
+                let handle = RESIZE_HANDLE.min(rect.width() / 3.0);
+                (idx, pos.x >= rect.x1 - handle)
+            })
+        })
+    }
+
+    fn marquee_selection(
+        &self,
+        notes: &Vector<Note>,
+        rect: Rect,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let rect = rect.abs();
+        notes
+            .iter()
+            .enumerate()
+            .filter(|(_, note)| self.note_rect(note).intersect(rect).area() > 0.0)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn drag_to(&mut self, data: &mut Vector<Note>, pos: Point) {
+        let snap = self.snap;
+        let snap_beats = |beats: f64| match snap > 0.0 {
+            true => (beats / snap).round() * snap,
+            false => beats,
+        };
+        let min_length = self.default_length();
+        match &mut self.drag {
+            Some(Drag::Move { origin, notes }) => {
+                let delta = pos - *origin;
+                let beats = delta.x / self.beat_width;
+                let rows = -(delta.y / self.row_height).round() as isize;
+                // Keep the whole selection inside the grid, and snap by the first note.
+                let lowest = notes.iter().map(|(_, note)| note.row).min().unwrap_or(0) as isize;
+                let highest = notes.iter().map(|(_, note)| note.row).max().unwrap_or(0) as isize;
+                let rows = rows.clamp(-lowest, self.rows as isize - 1 - highest);
+                let earliest = notes
+                    .iter()
+                    .map(|(_, note)| note.start)
+                    .fold(f64::INFINITY, f64::min);
+                let shift = snap_beats((earliest + beats).max(0.0)) - earliest;
+                for (idx, note) in notes.iter() {
+                    if let Some(target) = data.get_mut(*idx) {
+                        target.start = note.start + shift;
+                        target.row = (note.row as isize + rows) as usize;
+                    }
+                }
+            }
+            Some(Drag::Resize {
+                origin,
+                note,
+                length,
+            }) => {
+                let beats = (pos.x - origin.x) / self.beat_width;
+                if let Some(target) = data.get_mut(*note) {
+                    let end = snap_beats(target.start + *length + beats);
+                    target.length = (end - target.start).max(min_length);
+                }
+            }
+            Some(Drag::Marquee { current, .. }) => *current = pos,
+            None => {}
+        }
+        if let Some(Drag::Marquee {
+            origin,
+            current,
+            previous,
+        }) = &self.drag
+        {
+            let rect = Rect::from_points(*origin, *current);
+            let mut selection = previous.clone();
+            selection.extend(self.marquee_selection(data, rect));
+            self.selection = selection;
+        }
+    }
+}
+
+impl Default for PianoRoll {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<Vector<Note>> for PianoRoll {
+    #[instrument(
+        name = "PianoRoll",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Vector<Note>, _env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                ctx.request_focus();
+                ctx.set_active(true);
+                let pos = mouse.pos;
+                match self.note_at(data, pos) {
+                    Some((idx, true)) => {
+                        self.selection = BTreeSet::from([idx]);
+                        self.drag = Some(Drag::Resize {
+                            origin: pos,
+                            note: idx,
+                            length: data[idx].length,
+                        });
+                    }
+                    Some((idx, false)) => {
+                        if mouse.mods.shift() {
+                            if !self.selection.remove(&idx) {
+                                self.selection.insert(idx);
+                            }
+                        } else if !self.selection.contains(&idx) {
+                            self.selection = BTreeSet::from([idx]);
+                        }
+                        let notes = self
+                            .selection
+                            .iter()
+                            .map(|&idx| (idx, data[idx].clone()))
+                            .collect();
+                        self.drag = Some(Drag::Move { origin: pos, notes });
+                    }
+                    None if mouse.count == 2 => {
+                        let beats = pos.x / self.beat_width;
+                        let start = match self.snap > 0.0 {
+                            true => (beats / self.snap).floor() * self.snap,
+                            false => beats,
+                        };
+                        let note = Note {
+                            start: start.max(0.0),
+                            length: self.default_length(),
+                            row: self.row_at(pos.y),
+                        };
+                        trace!("adding {:?}", note);
+                        data.push_back(note);
+                        self.selection = BTreeSet::from([data.len() - 1]);
+                        self.drag = None;
+                    }
+                    None => {
+                        let previous = match mouse.mods.shift() {
+                            true => self.selection.clone(),
+                            false => BTreeSet::new(),
+                        };
+                        self.selection = previous.clone();
+                        self.drag = Some(Drag::Marquee {
+                            origin: pos,
+                            current: pos,
+                            previous,
+                        });
+                    }
+                }
+                ctx.request_paint();
+            }
+            Event::MouseMove(mouse) => {
+                if ctx.is_active() {
+                    self.drag_to(data, mouse.pos);
+                    ctx.request_paint();
+                } else {
+                    let cursor = match self.note_at(data, mouse.pos) {
+                        Some((_, true)) => &Cursor::ResizeLeftRight,
+                        _ => &Cursor::Arrow,
+                    };
+                    ctx.set_cursor(cursor);
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                self.drag = None;
+                ctx.request_layout();
+                ctx.request_paint();
+            }
+            Event::Wheel(mouse) if mouse.mods.ctrl() => {
+                let steps = -mouse.wheel_delta.y.signum();
+                let width =
+                    (self.beat_width * ZOOM_STEP.powf(steps)).clamp(MIN_BEAT_WIDTH, MAX_BEAT_WIDTH);
+                if width != self.beat_width {
+                    self.beat_width = width;
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.is_focused() => match key.key {
+                KbKey::Delete | KbKey::Backspace if !self.selection.is_empty() => {
+                    for idx in self.selection.iter().rev() {
+                        if *idx < data.len() {
+                            data.remove(*idx);
+                        }
+                    }
+                    self.selection.clear();
+                    ctx.request_layout();
+                    ctx.set_handled();
+                }
+                KbKey::Escape => {
+                    self.selection.clear();
+                    ctx.request_paint();
+                    ctx.set_handled();
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    #[instrument(
+        name = "PianoRoll",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &Vector<Note>,
+        _env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+    }
+
+    #[instrument(
+        name = "PianoRoll",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &Vector<Note>,
+        data: &Vector<Note>,
+        _env: &Env,
+    ) {
+        if !old_data.same(data) {
+            if old_data.len() != data.len() && self.drag.is_none() {
+                self.selection.retain(|idx| *idx < data.len());
+            }
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "PianoRoll", level = "trace", skip(self, _ctx, bc, data, _env))]
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Vector<Note>,
+        _env: &Env,
+    ) -> Size {
+        bc.debug_check("PianoRoll");
+        let last = data
+            .iter()
+            .map(|note| note.start + note.length)
+            .fold(0.0, f64::max);
+        let beats = (last + TRAILING_BEATS).max(self.min_beats);
+        let size = bc.constrain(Size::new(
+            beats * self.beat_width,
+            self.rows as f64 * self.row_height,
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "PianoRoll", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Vector<Note>, env: &Env) {
+        let size = ctx.size();
+        let visible = ctx.region().bounding_box().intersect(size.to_rect());
+        ctx.fill(visible, &env.get(theme::BACKGROUND_DARK));
+
+        // Shade every other row.
+        let first_row = (visible.y0 / self.row_height).floor() as usize;
+        let last_row = ((visible.y1 / self.row_height).ceil() as usize).min(self.rows);
+        let shade = env.get(theme::BACKGROUND_LIGHT).with_alpha(0.3);
+        for from_top in first_row..last_row {
+            if (self.rows - 1 - from_top) % 2 == 1 {
+                let y = from_top as f64 * self.row_height;
+                ctx.fill(
+                    Rect::new(visible.x0, y, visible.x1, y + self.row_height),
+                    &shade,
+                );
+            }
+        }
+
+        // Beat and bar lines.
+        let light = env.get(theme::BORDER_DARK);
+        let heavy = env.get(theme::BORDER_LIGHT);
+        let first_beat = (visible.x0 / self.beat_width).floor() as usize;
+        let last_beat = (visible.x1 / self.beat_width).ceil() as usize;
+        for beat in first_beat..=last_beat {
+            let x = beat as f64 * self.beat_width + 0.5;
+            let color = if beat % self.beats_per_bar == 0 {
+                &heavy
+            } else {
+                &light
+            };
+            ctx.stroke(Line::new((x, visible.y0), (x, visible.y1)), color, 1.0);
+        }
+
+        let fill = env.get(theme::PRIMARY_DARK);
+        let selected_fill = env.get(theme::PRIMARY_LIGHT);
+        let border = env.get(theme::BORDER_DARK);
+        for (idx, note) in data.iter().enumerate() {
+            let rect = self.note_rect(note);
+            if rect.intersect(visible).area() <= 0.0 {
+                continue;
+            }
+            let rect = rect.inset(-0.5).to_rounded_rect(2.0);
+            let color = if self.selection.contains(&idx) {
+                &selected_fill
+            } else {
+                &fill
+            };
+            ctx.fill(rect, color);
+            ctx.stroke(rect, &border, 1.0);
+        }
+
+        if let Some(Drag::Marquee {
+            origin, current, ..
+        }) = &self.drag
+        {
+            let rect = Rect::from_points(*origin, *current);
+            ctx.fill(
+                rect,
+                &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+                    .with_alpha(0.3),
+            );
+            ctx.stroke(rect, &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR), 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(start: f64, length: f64, row: usize) -> Note {
+        Note { start, length, row }
+    }
+
+    #[test]
+    fn hit_testing() {
+        let roll = PianoRoll::new()
+            .with_rows(4)
+            .with_row_height(10.0)
+            .with_beat_width(20.0);
+        let notes = Vector::from(vec![note(0.0, 1.0, 0), note(1.0, 2.0, 3)]);
+        assert_eq!(roll.note_rect(&notes[0]), Rect::new(0.0, 30.0, 20.0, 40.0));
+        assert_eq!(
+            roll.note_at(&notes, Point::new(5.0, 35.0)),
+            Some((0, false))
+        );
+        assert_eq!(roll.note_at(&notes, Point::new(58.0, 5.0)), Some((1, true)));
+        assert_eq!(roll.note_at(&notes, Point::new(5.0, 5.0)), None);
+        assert_eq!(roll.row_at(35.0), 0);
+        assert_eq!(roll.row_at(100.0), 0);
+    }
+
+    #[test]
+    fn drag_snaps() {
+        let mut roll = PianoRoll::new()
+            .with_rows(4)
+            .with_row_height(10.0)
+            .with_beat_width(20.0);
+        let mut notes = Vector::from(vec![note(0.0, 1.0, 0), note(1.0, 2.0, 1)]);
+
+        roll.drag = Some(Drag::Move {
+            origin: Point::new(5.0, 35.0),
+            notes: vec![(0, notes[0].clone()), (1, notes[1].clone())],
+        });
+        // A bit over a beat right, and far enough up to leave the grid.
+        roll.drag_to(&mut notes, Point::new(27.0, -100.0));
+        assert_eq!(notes[0], note(1.0, 1.0, 2));
+        assert_eq!(notes[1], note(2.0, 2.0, 3));
+
+        roll.drag = Some(Drag::Resize {
+            origin: Point::new(60.0, 5.0),
+            note: 1,
+            length: 2.0,
+        });
+        roll.drag_to(&mut notes, Point::new(0.0, 5.0));
+        assert_eq!(notes[1].length, 0.25);
+
+        roll.drag = Some(Drag::Marquee {
+            origin: Point::new(0.0, 0.0),
+            current: Point::ZERO,
+            previous: BTreeSet::new(),
+        });
+        roll.drag_to(&mut notes, Point::new(30.0, 15.0));
+        assert_eq!(roll.selection().collect::<Vec<_>>(), vec![0]);
+    }
+}
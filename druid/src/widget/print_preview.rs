@@ -0,0 +1,454 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that previews how a document will be printed.
+
+use tracing::{instrument, trace};
+
+use crate::kurbo::Insets;
+use crate::widget::prelude::*;
+use crate::{theme, Affine, Color, Data, KbKey, Point, Rect, Selector};
+
+/// The space between pages, and around the edges.
+const PAGE_GAP: f64 = 16.0;
+const MIN_ZOOM: f64 = 0.05;
+const MAX_ZOOM: f64 = 4.0;
+/// How much each step of the mouse wheel changes the zoom by.
+const ZOOM_STEP: f64 = 1.1;
+
+type PageCount<T> = Box<dyn Fn(&T, Size, &Env) -> usize>;
+type PagePainter<T> = Box<dyn Fn(&mut PaintCtx, &T, usize, Size, &Env)>;
+
+/// Whether a page is taller than it is wide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum PageOrientation {
+    /// The paper is used upright.
+    Portrait,
+    /// The paper is turned on its side.
+    Landscape,
+}
+
+/// The paper, orientation and margins pages are laid out with.
+///
+/// All sizes are in points (1/72 inch).
+#[derive(Debug, Clone, Copy, PartialEq, Data)]
+pub struct PageSetup {
+    /// The size of the paper, when held upright.
+    pub paper: Size,
+    /// How the paper is turned.
+    pub orientation: PageOrientation,
+    /// The space left blank around the content of each page.
+    pub margins: Insets,
+}
+
+/// A widget that shows the pages of a document as they will be printed.
+///
+/// The document is split into pages by the application: the `PrintPreview`
+/// asks how many pages the data needs for the printable area of the current
+/// [`PageSetup`], then asks for each visible page to be painted. Pages are
+/// painted in points, with the origin at the top left of the printable area.
+///
+/// Pages are shown side by side as thumbnails, as many to a row as fit. The
+/// thumbnails can be zoomed with ctrl and the mouse wheel, and a page can be
+/// selected by clicking it or with the arrow, page up, page down, home and end
+/// keys. The preview grows to fit all the pages, so it is normally put in a
+/// [`Scroll`], which is kept scrolled to the selected page.
+///
+/// Controls for the page setup and for changing pages are submitted to the
+/// preview as the [`SET_PAGE_SETUP`] and [`SHOW_PAGE`] commands.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::{PrintPreview, Scroll};
+/// use druid::{Color, Rect, RenderContext, Widget};
+///
+/// const LINE_HEIGHT: f64 = 14.0;
+///
+/// fn preview() -> impl Widget<Arc<Vec<String>>> {
+///     let preview = PrintPreview::new(
+///         |lines: &Arc<Vec<String>>, printable, _env| {
+///             let per_page = (printable.height / LINE_HEIGHT).floor().max(1.0) as usize;
+///             lines.len().div_ceil(per_page)
+///         },
+///         |ctx, lines, page, printable, _env| {
+///             let per_page = (printable.height / LINE_HEIGHT).floor().max(1.0) as usize;
+///             let first = page * per_page;
+///             for (i, line) in lines.iter().skip(first).take(per_page).enumerate() {
+///                 let y = i as f64 * LINE_HEIGHT;
+///                 let width = line.len() as f64 * 6.0;
+///                 ctx.fill(Rect::new(0.0, y + 2.0, width, y + 12.0), &Color::grey(0.3));
+///             }
+///         },
+///     );
+///     Scroll::new(preview).vertical()
+/// }
+/// ```
+///
+/// [`Scroll`]: super::Scroll
+/// [`SET_PAGE_SETUP`]: PrintPreview::SET_PAGE_SETUP
+/// [`SHOW_PAGE`]: PrintPreview::SHOW_PAGE
+pub struct PrintPreview<T> {
+    page_count: PageCount<T>,
+    paint_page: PagePainter<T>,
+    setup: PageSetup,
+    zoom: f64,
+    show_margins: bool,
+    count: usize,
+    selected: usize,
+    columns: usize,
+}
+
+impl PageSetup {
+    /// The size of ISO A4 paper.
+    pub const A4: Size = Size::new(595.0, 842.0);
+    /// The size of US Letter paper.
+    pub const LETTER: Size = Size::new(612.0, 792.0);
+
+    /// A portrait page setup for `paper`, with one inch margins.
+    pub fn new(paper: Size) -> Self {
+        PageSetup {
+            paper,
+            orientation: PageOrientation::Portrait,
+            margins: Insets::uniform(72.0),
+        }
+    }
+
+    /// Builder-style method for setting the orientation.
+    pub fn with_orientation(mut self, orientation: PageOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Builder-style method for setting the margins.
+    pub fn with_margins(mut self, margins: impl Into<Insets>) -> Self {
+        self.margins = margins.into();
+        self
+    }
+
+    /// The size of a page, taking the orientation into account.
+    pub fn page_size(&self) -> Size {
+        let Size { width, height } = self.paper;
+        match self.orientation {
+            PageOrientation::Portrait => Size::new(width.min(height), width.max(height)),
+            PageOrientation::Landscape => Size::new(width.max(height), width.min(height)),
+        }
+    }
+
+    /// The area of a page that content is printed in, inside the margins.
+    pub fn printable_rect(&self) -> Rect {
+        let rect = self.page_size().to_rect() - self.margins;
+        rect.with_size(Size::new(rect.width().max(0.0), rect.height().max(0.0)))
+    }
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        PageSetup::new(PageSetup::A4)
+    }
+}
+
+impl PrintPreview<()> {
+    /// Change the [`PageSetup`] of the preview; the pages are counted again.
+    pub const SET_PAGE_SETUP: Selector<PageSetup> =
+        Selector::new("druid-builtin.print-preview-set-page-setup");
+
+    /// Select the page with this index and scroll it into view.
+    pub const SHOW_PAGE: Selector<usize> = Selector::new("druid-builtin.print-preview-show-page");
+}
+
+impl<T> PrintPreview<T> {
+    /// Create a print preview.
+    ///
+    /// `page_count` returns how many pages the data needs, given the size of the
+    /// printable area of a page. `paint_page` paints the page with the given index
+    /// into the printable area, which has been clipped.
+    pub fn new(
+        page_count: impl Fn(&T, Size, &Env) -> usize + 'static,
+        paint_page: impl Fn(&mut PaintCtx, &T, usize, Size, &Env) + 'static,
+    ) -> Self {
+        PrintPreview {
+            page_count: Box::new(page_count),
+            paint_page: Box::new(paint_page),
+            setup: PageSetup::default(),
+            zoom: 0.25,
+            show_margins: false,
+            count: 0,
+            selected: 0,
+            columns: 1,
+        }
+    }
+
+    /// Builder-style method for setting the initial [`PageSetup`].
+    pub fn with_page_setup(mut self, setup: PageSetup) -> Self {
+        self.setup = setup;
+        self
+    }
+
+    /// Builder-style method for setting the initial zoom, the size in
+    /// display points of one point on the page.
+    pub fn with_zoom(mut self, zoom: f64) -> Self {
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        self
+    }
+
+    /// Builder-style method for outlining the printable area of each page.
+    pub fn show_margins(mut self, show: bool) -> Self {
+        self.show_margins = show;
+        self
+    }
+
+    /// The current page setup.
+    pub fn page_setup(&self) -> &PageSetup {
+        &self.setup
+    }
+
+    /// The current zoom.
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// The number of pages, as of the last time the data changed.
+    pub fn page_count(&self) -> usize {
+        self.count
+    }
+
+    /// The index of the selected page.
+    pub fn selected_page(&self) -> usize {
+        self.selected
+    }
+
+    fn count_pages(&mut self, data: &T, env: &Env) {
+        self.count = (self.page_count)(data, self.setup.printable_rect().size(), env);
+        self.selected = self.selected.min(self.count.saturating_sub(1));
+    }
+
+    /// The size of a page on screen.
+    fn thumbnail_size(&self) -> Size {
+        self.setup.page_size() * self.zoom
+    }
+
+    /// The rectangle of the page with this index, in the widget's coordinate space.
+    fn page_rect(&self, page: usize) -> Rect {
+        let size = self.thumbnail_size();
+        let column = (page % self.columns) as f64;
+        let row = (page / self.columns) as f64;
+        let origin = Point::new(
+            PAGE_GAP + column * (size.width + PAGE_GAP),
+            PAGE_GAP + row * (size.height + PAGE_GAP),
+        );
+        Rect::from_origin_size(origin, size)
+    }
+
+    fn page_at(&self, pos: Point) -> Option<usize> {
+        let size = self.thumbnail_size();
+        let column = ((pos.x - PAGE_GAP) / (size.width + PAGE_GAP)).floor();
+        let row = ((pos.y - PAGE_GAP) / (size.height + PAGE_GAP)).floor();
+        if column < 0.0 || row < 0.0 || column as usize >= self.columns {
+            return None;
+        }
+        let page = row as usize * self.columns + column as usize;
+        (page < self.count && self.page_rect(page).contains(pos)).then_some(page)
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, page: usize) {
+        let page = page.min(self.count.saturating_sub(1));
+        if page != self.selected {
+            self.selected = page;
+            ctx.request_paint();
+        }
+        ctx.scroll_area_to_view(self.page_rect(page).inset(PAGE_GAP / 2.0));
+    }
+}
+
+impl<T: Data> Widget<T> for PrintPreview<T> {
+    #[instrument(
+        name = "PrintPreview",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) => {
+                if let Some(page) = self.page_at(mouse.pos) {
+                    self.select(ctx, page);
+                }
+                ctx.request_focus();
+            }
+            Event::Wheel(mouse) if mouse.mods.ctrl() => {
+                let steps = -mouse.wheel_delta.y.signum();
+                let zoom = (self.zoom * ZOOM_STEP.powf(steps)).clamp(MIN_ZOOM, MAX_ZOOM);
+                if zoom != self.zoom {
+                    self.zoom = zoom;
+                    ctx.request_layout();
+                }
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.is_focused() && self.count > 0 => {
+                let last = self.count - 1;
+                let page = match key.key {
+                    KbKey::ArrowRight | KbKey::PageDown => (self.selected + 1).min(last),
+                    KbKey::ArrowLeft | KbKey::PageUp => self.selected.saturating_sub(1),
+                    KbKey::ArrowDown => (self.selected + self.columns).min(last),
+                    KbKey::ArrowUp => self.selected.saturating_sub(self.columns),
+                    KbKey::Home => 0,
+                    KbKey::End => last,
+                    _ => return,
+                };
+                self.select(ctx, page);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) => {
+                if let Some(setup) = cmd.get(PrintPreview::SET_PAGE_SETUP) {
+                    self.setup = *setup;
+                    self.count_pages(data, env);
+                    ctx.request_layout();
+                } else if let Some(page) = cmd.get(PrintPreview::SHOW_PAGE) {
+                    self.select(ctx, *page);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[instrument(
+        name = "PrintPreview",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.count_pages(data, env);
+                ctx.register_for_focus();
+            }
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+    }
+
+    #[instrument(
+        name = "PrintPreview",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) || ctx.env_changed() {
+            let count = self.count;
+            self.count_pages(data, env);
+            if count != self.count {
+                ctx.request_layout();
+            }
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(
+        name = "PrintPreview",
+        level = "trace",
+        skip(self, _ctx, bc, _data, _env)
+    )]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.debug_check("PrintPreview");
+        let page = self.thumbnail_size();
+        let max_columns = if bc.is_width_bounded() {
+            ((bc.max().width - PAGE_GAP) / (page.width + PAGE_GAP)).floor() as usize
+        } else {
+            self.count
+        };
+        self.columns = max_columns.min(self.count).max(1);
+        let rows = self.count.div_ceil(self.columns);
+
+        let size = bc.constrain(Size::new(
+            PAGE_GAP + self.columns as f64 * (page.width + PAGE_GAP),
+            PAGE_GAP + rows as f64 * (page.height + PAGE_GAP),
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "PrintPreview", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let background = ctx.size().to_rect();
+        ctx.fill(background, &env.get(theme::BACKGROUND_DARK));
+
+        let visible = ctx.region().bounding_box();
+        let printable = self.setup.printable_rect();
+        for page in 0..self.count {
+            let rect = self.page_rect(page);
+            if rect.intersect(visible).area() <= 0.0 {
+                continue;
+            }
+
+            ctx.fill(rect, &Color::WHITE);
+            ctx.with_save(|ctx| {
+                ctx.clip(rect);
+                ctx.transform(
+                    Affine::translate(rect.origin().to_vec2())
+                        * Affine::scale(self.zoom)
+                        * Affine::translate(printable.origin().to_vec2()),
+                );
+                ctx.clip(printable.size().to_rect());
+                (self.paint_page)(ctx, data, page, printable.size(), env);
+            });
+
+            if self.show_margins {
+                let margins = printable.scale_from_origin(self.zoom) + rect.origin().to_vec2();
+                ctx.stroke(margins, &env.get(theme::PRIMARY_LIGHT).with_alpha(0.5), 1.0);
+            }
+            if page == self.selected && ctx.is_focused() {
+                ctx.paint_focus_decoration(rect.inset(1.0), env);
+            } else {
+                ctx.stroke(rect.inset(0.5), &env.get(theme::BORDER_DARK), 1.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_setup() {
+        let setup = PageSetup::new(Size::new(600.0, 800.0)).with_margins((50.0, 100.0));
+        assert_eq!(setup.page_size(), Size::new(600.0, 800.0));
+        assert_eq!(setup.printable_rect(), Rect::new(50.0, 100.0, 550.0, 700.0));
+
+        let landscape = setup.with_orientation(PageOrientation::Landscape);
+        assert_eq!(landscape.page_size(), Size::new(800.0, 600.0));
+        assert_eq!(
+            landscape.printable_rect(),
+            Rect::new(50.0, 100.0, 750.0, 500.0)
+        );
+
+        let cramped = setup.with_margins(400.0);
+        assert_eq!(cramped.printable_rect().size(), Size::ZERO);
+    }
+
+    #[test]
+    fn page_grid() {
+        let mut preview = PrintPreview::new(|_: &(), _, _| 5, |_, _, _, _, _| {})
+            .with_page_setup(PageSetup::new(Size::new(100.0, 200.0)))
+            .with_zoom(1.0);
+        preview.count = 5;
+        preview.columns = 2;
+        assert_eq!(preview.page_rect(3), Rect::new(132.0, 232.0, 232.0, 432.0));
+        assert_eq!(preview.page_at(Point::new(140.0, 240.0)), Some(3));
+        assert_eq!(preview.page_at(Point::new(240.0, 240.0)), None);
+        assert_eq!(preview.page_at(Point::new(140.0, 460.0)), None);
+        assert_eq!(preview.page_at(Point::new(20.0, 460.0)), Some(4));
+    }
+}
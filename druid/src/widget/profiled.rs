@@ -0,0 +1,118 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that reports its timings under a name.
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::profiling::{self, Pass};
+use crate::widget::prelude::*;
+use crate::widget::Axis;
+use crate::{ArcStr, Data};
+
+/// A widget that reports the time spent in its child, children included, under a name.
+///
+/// The timings are only collected while [`profiling`] is enabled. This is usually
+/// created with [`WidgetExt::profiled`].
+///
+/// [`profiling`]: crate::profiling
+/// [`WidgetExt::profiled`]: crate::WidgetExt::profiled
+pub struct Profiled<T, W> {
+    child: W,
+    name: ArcStr,
+    marker: std::marker::PhantomData<T>,
+}
+
+impl<T: Data, W: Widget<T>> Profiled<T, W> {
+    /// Wraps a widget in a `Profiled` with the given name.
+    pub fn new(child: W, name: impl Into<ArcStr>) -> Self {
+        Self {
+            child,
+            name: name.into(),
+            marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Profiled<T, W> {
+    #[instrument(name = "Profiled", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let _timer = profiling::time_named(Pass::Event, &self.name);
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Profiled", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let _timer = profiling::time_named(Pass::Lifecycle, &self.name);
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    #[instrument(
+        name = "Profiled",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let _timer = profiling::time_named(Pass::Update, &self.name);
+        self.child.update(ctx, old_data, data, env);
+    }
+
+    #[instrument(name = "Profiled", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let _timer = profiling::time_named(Pass::Layout, &self.name);
+        self.child.layout(ctx, bc, data, env)
+    }
+
+    #[instrument(name = "Profiled", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let _timer = profiling::time_named(Pass::Paint, &self.name);
+        self.child.paint(ctx, data, env);
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.child.id()
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: self.name.to_string(),
+            children: vec![self.child.debug_state(data)],
+            ..Default::default()
+        }
+    }
+
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.child.compute_max_intrinsic(axis, ctx, bc, data, env)
+    }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.child.compute_min_intrinsic(axis, ctx, bc, data, env)
+    }
+}
@@ -73,9 +73,11 @@ pub struct Radio<T> {
 impl<T: Data> Radio<T> {
     /// Create a lone Radio button from label text and an enum variant
     pub fn new(label: impl Into<LabelText<T>>, variant: T) -> Radio<T> {
+        let mut child_label = Label::new(label);
+        child_label.set_browsable(false);
         Radio {
             variant,
-            child_label: Label::new(label),
+            child_label,
         }
     }
 }
@@ -134,7 +136,9 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
             radio_diam.max(label_size.height),
         );
         let size = bc.constrain(desired_size);
-        trace!("Computed size: {}", size);
+        let baseline = self.child_label.baseline_offset() + (size.height - label_size.height);
+        ctx.set_baseline_offset(baseline);
+        trace!("Computed layout: size={}, baseline={}", size, baseline);
         size
     }
 
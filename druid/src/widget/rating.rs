@@ -0,0 +1,355 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A row of stars for picking a rating.
+
+use std::f64::consts::PI;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::BezPath;
+use crate::widget::prelude::*;
+use crate::{theme, Color, Data, KbKey, KeyOrValue, Point, Rect};
+
+/// The default size of a star.
+const DEFAULT_STAR_SIZE: f64 = 20.0;
+/// The space between two stars.
+const STAR_SPACING: f64 = 4.0;
+/// The radius of the inner points of a star, as a fraction of its outer radius.
+const INNER_RADIUS: f64 = 0.4;
+
+/// A value that can be edited with a [`Rating`].
+///
+/// This is implemented for `f64`, which can hold half values, and `u32`, which
+/// can only hold whole values.
+pub trait RatingValue: Data {
+    /// The value as a number of stars.
+    fn to_stars(&self) -> f64;
+
+    /// The value closest to the given number of stars.
+    fn from_stars(stars: f64) -> Self;
+}
+
+impl RatingValue for f64 {
+    fn to_stars(&self) -> f64 {
+        *self
+    }
+
+    fn from_stars(stars: f64) -> Self {
+        stars
+    }
+}
+
+impl RatingValue for u32 {
+    fn to_stars(&self) -> f64 {
+        *self as f64
+    }
+
+    fn from_stars(stars: f64) -> Self {
+        stars.round().max(0.0) as u32
+    }
+}
+
+/// A row of stars that shows and edits a rating.
+///
+/// The rating is set by clicking or dragging over the stars, or with the arrow keys
+/// while the widget has focus; `Home` and `End` set it to zero and to the maximum.
+/// While the mouse is over the widget, the rating that a click would set is
+/// previewed.
+///
+/// By default only whole values can be picked; use [`with_half_steps`] to allow
+/// half stars. Half values need `f64` data, a `u32` rating is always rounded.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Rating;
+/// use druid::Widget;
+///
+/// fn review_score() -> impl Widget<f64> {
+///     Rating::new().with_max(10).with_half_steps(true)
+/// }
+/// ```
+///
+/// [`with_half_steps`]: Rating::with_half_steps
+pub struct Rating {
+    max: usize,
+    half_steps: bool,
+    read_only: bool,
+    star_size: f64,
+    color: KeyOrValue<Color>,
+    /// The rating that clicking at the mouse position would set.
+    hover: Option<f64>,
+}
+
+impl Rating {
+    /// Create a rating of five stars.
+    pub fn new() -> Self {
+        Rating {
+            max: 5,
+            half_steps: false,
+            read_only: false,
+            star_size: DEFAULT_STAR_SIZE,
+            color: theme::PRIMARY_LIGHT.into(),
+            hover: None,
+        }
+    }
+
+    /// Builder-style method for setting the number of stars.
+    pub fn with_max(mut self, max: usize) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Builder-style method for setting whether half stars can be picked.
+    ///
+    /// The default is `false`.
+    pub fn with_half_steps(mut self, half_steps: bool) -> Self {
+        self.half_steps = half_steps;
+        self
+    }
+
+    /// Builder-style method for making the rating display-only.
+    ///
+    /// A read-only rating ignores the mouse and keyboard, and does not take focus.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Builder-style method for setting the size of each star.
+    pub fn with_star_size(mut self, size: f64) -> Self {
+        self.star_size = size;
+        self
+    }
+
+    /// Builder-style method for setting the color of filled stars.
+    ///
+    /// The default is [`theme::PRIMARY_LIGHT`].
+    ///
+    /// [`theme::PRIMARY_LIGHT`]: crate::theme::PRIMARY_LIGHT
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    fn step(&self) -> f64 {
+        if self.half_steps {
+            0.5
+        } else {
+            1.0
+        }
+    }
+
+    /// The rating that a click at `x` sets.
+    fn stars_at(&self, x: f64) -> f64 {
+        if x <= 0.0 {
+            return 0.0;
+        }
+        let pitch = self.star_size + STAR_SPACING;
+        let index = (x / pitch).floor();
+        let within = (x - index * pitch) / self.star_size;
+        let stars = match self.half_steps && within < 0.5 {
+            true => index + 0.5,
+            false => index + 1.0,
+        };
+        stars.min(self.max as f64)
+    }
+
+    fn set<T: RatingValue>(&self, ctx: &mut EventCtx, data: &mut T, stars: f64) {
+        let stars = stars.clamp(0.0, self.max as f64);
+        let value = T::from_stars(stars);
+        if !value.same(data) {
+            *data = value;
+            ctx.request_paint();
+        }
+    }
+
+    fn set_hover<T: RatingValue>(&mut self, ctx: &mut EventCtx, hover: Option<f64>) {
+        // Round the preview the same way the data would be.
+        let hover = hover.map(|stars| T::from_stars(stars).to_stars());
+        if hover != self.hover {
+            self.hover = hover;
+            ctx.request_paint();
+        }
+    }
+
+    fn star_path(&self, origin: Point) -> BezPath {
+        let radius = self.star_size / 2.0;
+        let center = origin + (radius, radius);
+        let mut path = BezPath::new();
+        for i in 0..10 {
+            let r = match i % 2 {
+                0 => radius,
+                _ => radius * INNER_RADIUS,
+            };
+            let angle = -PI / 2.0 + i as f64 * PI / 5.0;
+            let point = center + (r * angle.cos(), r * angle.sin());
+            if i == 0 {
+                path.move_to(point);
+            } else {
+                path.line_to(point);
+            }
+        }
+        path.close_path();
+        path
+    }
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: RatingValue> Widget<T> for Rating {
+    #[instrument(name = "Rating", level = "trace", skip(self, ctx, event, data, _env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, _env: &Env) {
+        if self.read_only || ctx.is_disabled() {
+            return;
+        }
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                ctx.set_active(true);
+                ctx.request_focus();
+                self.set(ctx, data, self.stars_at(mouse.pos.x));
+            }
+            Event::MouseMove(mouse) => {
+                if ctx.is_active() {
+                    self.set(ctx, data, self.stars_at(mouse.pos.x));
+                }
+                let hover = ctx.is_hot().then(|| self.stars_at(mouse.pos.x));
+                self.set_hover::<T>(ctx, hover);
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let stars = data.to_stars();
+                let stars = match key.key {
+                    KbKey::ArrowLeft | KbKey::ArrowDown => stars - self.step(),
+                    KbKey::ArrowRight | KbKey::ArrowUp => stars + self.step(),
+                    KbKey::Home => 0.0,
+                    KbKey::End => self.max as f64,
+                    _ => return,
+                };
+                self.set(ctx, data, stars);
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    #[instrument(name = "Rating", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded if !self.read_only => ctx.register_for_focus(),
+            LifeCycle::HotChanged(false) => {
+                self.hover = None;
+                ctx.request_paint();
+            }
+            LifeCycle::FocusChanged(_) | LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+    }
+
+    #[instrument(
+        name = "Rating",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) || ctx.env_key_changed(&self.color) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Rating", level = "trace", skip(self, _ctx, bc, _data, _env))]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.debug_check("Rating");
+        let count = self.max as f64;
+        let width = count * self.star_size + (count - 1.0).max(0.0) * STAR_SPACING;
+        let size = bc.constrain(Size::new(width, self.star_size));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Rating", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let stars = self.hover.unwrap_or_else(|| data.to_stars());
+        let color = self.color.resolve(env);
+        let color = match ctx.is_disabled() {
+            true => color.with_alpha(0.4),
+            false => color,
+        };
+        let empty = env.get(theme::BACKGROUND_LIGHT);
+        let border = env.get(theme::BORDER_DARK);
+
+        for i in 0..self.max {
+            let origin = Point::new(i as f64 * (self.star_size + STAR_SPACING), 0.0);
+            let star = self.star_path(origin);
+            ctx.fill(&star, &empty);
+            let fill = (stars - i as f64).clamp(0.0, 1.0);
+            if fill > 0.0 {
+                let filled =
+                    Rect::from_origin_size(origin, (self.star_size * fill, self.star_size));
+                ctx.with_save(|ctx| {
+                    ctx.clip(filled);
+                    ctx.fill(&star, &color);
+                });
+            }
+            ctx.stroke(&star, &border, 1.0);
+        }
+
+        if ctx.is_focused() {
+            let rect = ctx.size().to_rect().inset(2.0);
+            ctx.paint_focus_decoration(rect.to_rounded_rect(2.0), env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Rating".to_string(),
+            main_value: format!("{}/{}", data.to_stars(), self.max),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stars_at_position() {
+        let rating = Rating::new().with_star_size(20.0);
+        assert_eq!(rating.stars_at(-5.0), 0.0);
+        assert_eq!(rating.stars_at(5.0), 1.0);
+        assert_eq!(rating.stars_at(30.0), 2.0);
+        assert_eq!(rating.stars_at(500.0), 5.0);
+
+        let rating = rating.with_half_steps(true);
+        assert_eq!(rating.stars_at(5.0), 0.5);
+        assert_eq!(rating.stars_at(15.0), 1.0);
+        assert_eq!(rating.stars_at(28.0), 1.5);
+    }
+
+    #[test]
+    fn whole_values_are_rounded() {
+        assert_eq!(u32::from_stars(2.5), 3);
+        assert_eq!(u32::from_stars(-1.0), 0);
+        assert_eq!(f64::from_stars(2.5), 2.5);
+    }
+}
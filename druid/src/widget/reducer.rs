@@ -0,0 +1,250 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Updating application data from typed messages.
+
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use tracing::{instrument, warn};
+
+use crate::task::TaskPool;
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::{Command, Selector};
+
+type Reduce<T, M> = Box<dyn FnMut(&mut T, M) -> Effects<M>>;
+type Effect<M> = Pin<Box<dyn Future<Output = M> + Send>>;
+
+const DISPATCH: Selector<Message> = Selector::new("druid-builtin.reducer-dispatch");
+
+/// A [`Controller`] that updates its widget's data by folding messages into
+/// it with a single function.
+///
+/// Instead of changing the data directly, widgets below the reducer send
+/// messages with [`Reducer::dispatch`]. The reducer function applies each
+/// message to the data, and can return [`Effects`]: futures that run on a
+/// [`TaskPool`] and whose output is dispatched as another message. Keeping
+/// every change in one function makes it easy to test, with [`reduce`], and
+/// widgets bound with lenses keep working alongside it.
+///
+/// Messages of a different type than `M` are left for reducers further up
+/// the tree, so reducers for parts of the data can be nested.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Flex, Label, Reducer};
+/// use druid::{Widget, WidgetExt};
+///
+/// enum Msg {
+///     Increment,
+///     Reset,
+/// }
+///
+/// fn build_root() -> impl Widget<u32> {
+///     Flex::row()
+///         .with_child(Label::dynamic(|count: &u32, _| count.to_string()))
+///         .with_child(Button::new("+").on_click(|ctx, _, _| {
+///             ctx.submit_notification(Reducer::dispatch(Msg::Increment))
+///         }))
+///         .with_child(Button::new("Reset").on_click(|ctx, _, _| {
+///             ctx.submit_notification(Reducer::dispatch(Msg::Reset))
+///         }))
+///         .controller(Reducer::new(|count: &mut u32, msg| match msg {
+///             Msg::Increment => *count += 1,
+///             Msg::Reset => *count = 0,
+///         }))
+/// }
+/// ```
+///
+/// [`reduce`]: Reducer::reduce
+pub struct Reducer<T, M> {
+    reduce: Reduce<T, M>,
+    tasks: Option<TaskPool>,
+}
+
+/// Work started by a [`Reducer`] in response to a message.
+///
+/// Each effect is a future whose output is dispatched back to the reducer
+/// that returned it. Reducer functions that return `()` have no effects.
+pub struct Effects<M> {
+    futures: Vec<Effect<M>>,
+}
+
+/// The payload of a dispatched message.
+struct Message(Mutex<Option<Box<dyn Any + Send>>>);
+
+impl Reducer<(), ()> {
+    /// Create a [`Notification`] that sends `msg` to the nearest [`Reducer`]
+    /// above the widget that submits it, and whose messages are of type `M`.
+    ///
+    /// Submit it with [`EventCtx::submit_notification`].
+    ///
+    /// [`Notification`]: crate::Notification
+    pub fn dispatch<M: Any + Send>(msg: M) -> Command {
+        DISPATCH.with(Message(Mutex::new(Some(Box::new(msg)))))
+    }
+}
+
+impl<T: Data, M: Any + Send> Reducer<T, M> {
+    /// Create a new `Reducer` with the function that applies messages to the data.
+    ///
+    /// The function returns [`Effects`], or `()` if it has none.
+    pub fn new<E: Into<Effects<M>>>(mut reduce: impl FnMut(&mut T, M) -> E + 'static) -> Self {
+        Reducer {
+            reduce: Box::new(move |data, msg| reduce(data, msg).into()),
+            tasks: None,
+        }
+    }
+
+    /// Apply `msg` to `data`, returning the effects without running them.
+    pub fn reduce(&mut self, data: &mut T, msg: M) -> Effects<M> {
+        (self.reduce)(data, msg)
+    }
+
+    fn handle(&mut self, ctx: &mut EventCtx, message: &Message, data: &mut T) {
+        let Some(msg) = message.take::<M>() else {
+            return;
+        };
+        ctx.set_handled();
+        let effects = self.reduce(data, msg);
+        if effects.is_empty() {
+            return;
+        }
+        let target = ctx.widget_id();
+        let tasks = self
+            .tasks
+            .get_or_insert_with(|| TaskPool::new(ctx.get_external_handle()));
+        for future in effects.futures {
+            tasks.spawn_async(DISPATCH, target, move |_| async move {
+                Message(Mutex::new(Some(Box::new(future.await))))
+            });
+        }
+    }
+}
+
+impl<M> Effects<M> {
+    /// No effects.
+    pub fn none() -> Self {
+        Effects {
+            futures: Vec::new(),
+        }
+    }
+
+    /// A single effect that dispatches the output of `future`.
+    pub fn future(future: impl Future<Output = M> + Send + 'static) -> Self {
+        Effects::none().with_future(future)
+    }
+
+    /// Builder-style method to add another effect.
+    pub fn with_future(mut self, future: impl Future<Output = M> + Send + 'static) -> Self {
+        self.futures.push(Box::pin(future));
+        self
+    }
+
+    /// Whether there are no effects.
+    pub fn is_empty(&self) -> bool {
+        self.futures.is_empty()
+    }
+
+    /// The number of effects.
+    pub fn len(&self) -> usize {
+        self.futures.len()
+    }
+}
+
+impl<M> Default for Effects<M> {
+    fn default() -> Self {
+        Effects::none()
+    }
+}
+
+impl<M> From<()> for Effects<M> {
+    fn from(_: ()) -> Self {
+        Effects::none()
+    }
+}
+
+impl Message {
+    /// Take the message out, if it is an `M`.
+    fn take<M: Any>(&self) -> Option<M> {
+        let mut msg = self.0.lock().unwrap();
+        if !msg.as_ref().is_some_and(|msg| msg.is::<M>()) {
+            return None;
+        }
+        match msg.take()?.downcast() {
+            Ok(msg) => Some(*msg),
+            Err(_) => None,
+        }
+    }
+}
+
+impl<T: Data, M: Any + Send, W: Widget<T>> Controller<T, W> for Reducer<T, M> {
+    #[instrument(
+        name = "Reducer",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Notification(note) if note.is(DISPATCH) => {
+                if let Some(message) = note.get(DISPATCH) {
+                    self.handle(ctx, message, data);
+                }
+            }
+            Event::Command(cmd) if cmd.is(DISPATCH) => {
+                if let Some(message) = cmd.get(DISPATCH) {
+                    self.handle(ctx, message, data);
+                }
+                if !ctx.is_handled() {
+                    warn!("effect output was not a message for this reducer");
+                    ctx.set_handled();
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_of_other_types_are_left() {
+        let message = Message(Mutex::new(Some(Box::new(5u32))));
+        assert_eq!(message.take::<String>(), None);
+        assert_eq!(message.take::<u32>(), Some(5));
+        assert_eq!(message.take::<u32>(), None);
+    }
+
+    #[test]
+    fn reduce_returns_effects() {
+        let mut reducer = Reducer::new(|count: &mut u32, step: u32| {
+            *count += step;
+            if *count > 2 {
+                Effects::future(async { 0 })
+            } else {
+                Effects::none()
+            }
+        });
+        let mut count = 0;
+        assert!(reducer.reduce(&mut count, 2).is_empty());
+        assert_eq!(reducer.reduce(&mut count, 1).len(), 1);
+        assert_eq!(count, 3);
+    }
+}
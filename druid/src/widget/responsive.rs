@@ -0,0 +1,214 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that switches between layouts depending on its width.
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{Data, Point, Selector, WidgetPod};
+
+/// Sent by a [`Responsive`] to itself when its width crosses a breakpoint during layout,
+/// because children can only be replaced outside of layout.
+const SWITCH_BREAKPOINT: Selector = Selector::new("druid-builtin.responsive.switch");
+
+type ChildBuilder<T> = dyn Fn() -> Box<dyn Widget<T>>;
+
+/// A widget that switches between alternative children depending on its width.
+///
+/// Each child is created by a builder closure, and used from a minimum width, called a
+/// breakpoint, up to the next breakpoint. This lets an app use a compact layout in
+/// narrow windows and a wider one elsewhere, without passing the window size through
+/// its data.
+///
+/// The width is the maximum width allowed by the parent during layout. When it crosses
+/// a breakpoint, the child for the new range is built and replaces the old one, so any
+/// state that should be kept when switching should be part of the data. The switch
+/// happens right after the layout pass in which the width changed.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Flex, Label, Responsive};
+///
+/// let layout = Responsive::<()>::new(|| {
+///     Flex::column()
+///         .with_child(Label::new("Sidebar"))
+///         .with_child(Label::new("Content"))
+/// })
+/// .with_breakpoint(600.0, || {
+///     Flex::row()
+///         .with_child(Label::new("Sidebar"))
+///         .with_child(Label::new("Content"))
+/// });
+/// ```
+pub struct Responsive<T> {
+    /// The breakpoints and the builders of their children, sorted by width.
+    breakpoints: Vec<(f64, Box<ChildBuilder<T>>)>,
+    /// The index of the breakpoint of the current child.
+    active: usize,
+    /// The index of the breakpoint that applies to the width of the last layout.
+    wanted: usize,
+    /// Whether a switch to `wanted` has been requested.
+    switch_requested: bool,
+    child: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+}
+
+impl<T: Data> Responsive<T> {
+    /// Create a new `Responsive` widget, with the child used at any width
+    /// below the first breakpoint.
+    pub fn new<W: Widget<T> + 'static>(builder: impl Fn() -> W + 'static) -> Self {
+        Responsive {
+            breakpoints: vec![(0.0, Box::new(move || Box::new(builder())))],
+            active: 0,
+            wanted: 0,
+            switch_requested: false,
+            child: None,
+        }
+    }
+
+    /// Builder-style method to add a child that is used from `min_width` up to the
+    /// next breakpoint.
+    pub fn with_breakpoint<W: Widget<T> + 'static>(
+        mut self,
+        min_width: f64,
+        builder: impl Fn() -> W + 'static,
+    ) -> Self {
+        let index = self
+            .breakpoints
+            .partition_point(|(width, _)| *width <= min_width);
+        self.breakpoints
+            .insert(index, (min_width, Box::new(move || Box::new(builder()))));
+        self
+    }
+
+    /// The index of the breakpoint that applies to `width`.
+    fn breakpoint_for(&self, width: f64) -> usize {
+        self.breakpoints
+            .partition_point(|(min_width, _)| *min_width <= width)
+            .saturating_sub(1)
+    }
+}
+
+impl<T: Data> Widget<T> for Responsive<T> {
+    #[instrument(
+        name = "Responsive",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if cmd.is(SWITCH_BREAKPOINT) {
+                self.switch_requested = false;
+                if self.wanted != self.active {
+                    self.active = self.wanted;
+                    self.child = Some(WidgetPod::new((self.breakpoints[self.active].1)()));
+                    ctx.children_changed();
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        if let Some(child) = &mut self.child {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Responsive",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.child = Some(WidgetPod::new((self.breakpoints[self.active].1)()));
+        }
+        if let Some(child) = &mut self.child {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Responsive",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if let Some(child) = &mut self.child {
+            child.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Responsive", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Responsive");
+
+        self.wanted = self.breakpoint_for(bc.max().width);
+        if self.wanted != self.active && !self.switch_requested {
+            self.switch_requested = true;
+            ctx.submit_command(SWITCH_BREAKPOINT.to(ctx.widget_id()));
+        }
+
+        let size = match &mut self.child {
+            Some(child) => {
+                let size = child.layout(ctx, bc, data, env);
+                child.set_origin(ctx, Point::ORIGIN);
+                ctx.set_baseline_offset(child.baseline_offset());
+                size
+            }
+            None => bc.min(),
+        };
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Responsive", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if let Some(child) = &mut self.child {
+            child.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("breakpoint {}", self.breakpoints[self.active].0),
+            children: self
+                .child
+                .iter()
+                .map(|child| child.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::Label;
+
+    #[test]
+    fn breakpoints() {
+        let responsive = Responsive::<()>::new(|| Label::new("compact"))
+            .with_breakpoint(1000.0, || Label::new("wide"))
+            .with_breakpoint(600.0, || Label::new("medium"));
+        assert_eq!(responsive.breakpoint_for(0.0), 0);
+        assert_eq!(responsive.breakpoint_for(599.0), 0);
+        assert_eq!(responsive.breakpoint_for(600.0), 1);
+        assert_eq!(responsive.breakpoint_for(999.0), 1);
+        assert_eq!(responsive.breakpoint_for(f64::INFINITY), 2);
+    }
+}
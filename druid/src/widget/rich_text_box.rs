@@ -0,0 +1,368 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A text box for editing rich text.
+
+use std::ops::Range;
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::piet::TextStorage as _;
+use crate::text::{Attribute, EditableText, RichText, Selection};
+use crate::widget::prelude::*;
+use crate::widget::TextBox;
+use crate::{
+    theme, Color, FontStyle, FontWeight, HotKey, KeyEvent, KeyOrValue, Lens, Selector, SysMods,
+};
+
+/// Sent by a [`RichTextBox`] to itself when the selection style was changed in
+/// `update`, where the text cannot be changed.
+const APPLY_STYLE: Selector = Selector::new("druid-builtin.rich-text-box-apply-style");
+
+/// The style of a run of rich text.
+///
+/// Fields that are `None` use the text box's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Data, Lens)]
+pub struct TextStyle {
+    /// Whether the text is bold.
+    pub bold: bool,
+    /// Whether the text is italic.
+    pub italic: bool,
+    /// Whether the text is underlined.
+    pub underline: bool,
+    /// Whether the text is struck through.
+    pub strikethrough: bool,
+    /// The color of the text.
+    pub color: Option<Color>,
+    /// The size of the text, in points.
+    pub size: Option<f64>,
+}
+
+/// The data of a [`RichTextBox`].
+#[derive(Debug, Clone, Data, Lens)]
+pub struct RichTextDocument {
+    /// The text and its attributes.
+    pub text: RichText,
+    /// The style of the selection, or of the text before the caret.
+    ///
+    /// This is kept up to date by the [`RichTextBox`] as the selection moves, so that
+    /// toolbars can display it; when it is changed, the change is applied to the
+    /// selected text, or to the text typed next if the selection is a caret.
+    pub selection_style: TextStyle,
+}
+
+/// A multi-line text box for editing [`RichText`].
+///
+/// The style of the selection is exposed through [`RichTextDocument::selection_style`],
+/// which can be bound to a toolbar. Bold, italic and underline can also be toggled with
+/// the usual shortcuts.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Checkbox, Flex, RichTextBox, RichTextDocument, TextStyle};
+/// use druid::{LensExt, Widget, WidgetExt};
+///
+/// fn editor() -> impl Widget<RichTextDocument> {
+///     let bold = Checkbox::new("Bold")
+///         .lens(RichTextDocument::selection_style.then(TextStyle::bold));
+///     Flex::column()
+///         .with_child(bold)
+///         .with_flex_child(RichTextBox::new(), 1.0)
+/// }
+/// ```
+pub struct RichTextBox {
+    textbox: TextBox<RichText>,
+    /// The style as last synchronized with the data; a difference with the data
+    /// is a change made by the user.
+    style: TextStyle,
+    selection: Selection,
+}
+
+impl RichTextDocument {
+    /// Create a new `RichTextDocument`.
+    pub fn new(text: RichText) -> Self {
+        let selection_style = TextStyle::at(&text, Selection::caret(0));
+        RichTextDocument {
+            text,
+            selection_style,
+        }
+    }
+}
+
+impl TextStyle {
+    /// The style of the text at `selection`.
+    ///
+    /// This is the style of the start of a selected range, or of the character
+    /// before a caret.
+    fn at(text: &RichText, selection: Selection) -> TextStyle {
+        let offset = if selection.is_caret() {
+            match text.prev_codepoint_offset(selection.active) {
+                Some(offset) => offset,
+                None => selection.active,
+            }
+        } else {
+            selection.min()
+        };
+        let mut style = TextStyle::default();
+        for attr in text.attributes_at(offset) {
+            match attr {
+                Attribute::Weight(weight) => style.bold = weight.to_raw() >= 600,
+                Attribute::Style(font_style) => style.italic = font_style == FontStyle::Italic,
+                Attribute::Underline(underline) => style.underline = underline,
+                Attribute::Strikethrough(strikethrough) => style.strikethrough = strikethrough,
+                Attribute::TextColor(KeyOrValue::Concrete(color)) => style.color = Some(color),
+                Attribute::FontSize(KeyOrValue::Concrete(size)) => style.size = Some(size),
+                _ => (),
+            }
+        }
+        style
+    }
+
+    /// Apply the fields that differ from `old` to `range`.
+    fn apply_changes(&self, old: &TextStyle, text: &mut RichText, range: Range<usize>) {
+        let mut set = |attr: Attribute, on: bool| {
+            if on {
+                text.add_attribute(range.clone(), attr);
+            } else {
+                text.remove_attributes(range.clone(), &attr);
+            }
+        };
+        if self.bold != old.bold {
+            set(Attribute::weight(FontWeight::BOLD), self.bold);
+        }
+        if self.italic != old.italic {
+            set(Attribute::style(FontStyle::Italic), self.italic);
+        }
+        if self.underline != old.underline {
+            set(Attribute::underline(true), self.underline);
+        }
+        if self.strikethrough != old.strikethrough {
+            set(Attribute::strikethrough(true), self.strikethrough);
+        }
+        if self.color != old.color {
+            let color = self.color.map_or(theme::TEXT_COLOR.into(), Into::into);
+            set(Attribute::TextColor(color), self.color.is_some());
+        }
+        if self.size != old.size {
+            let size = self.size.map_or(theme::TEXT_SIZE_NORMAL.into(), Into::into);
+            set(Attribute::FontSize(size), self.size.is_some());
+        }
+    }
+}
+
+impl RichTextBox {
+    /// Create a new `RichTextBox`.
+    pub fn new() -> Self {
+        RichTextBox {
+            textbox: TextBox::multiline(),
+            style: TextStyle::default(),
+            selection: Selection::caret(0),
+        }
+    }
+
+    /// Apply the style in the data to the selection.
+    fn apply_style(&mut self, data: &mut RichTextDocument) {
+        let range = self.selection.range();
+        if !range.is_empty() {
+            data.selection_style
+                .apply_changes(&self.style, &mut data.text, range);
+        }
+        self.style = data.selection_style.clone();
+    }
+
+    /// Handle the shortcuts that toggle bold, italic and underline.
+    fn toggle_for_key(&mut self, key: &KeyEvent, data: &mut RichTextDocument) -> bool {
+        let style = &mut data.selection_style;
+        if HotKey::new(SysMods::Cmd, "b").matches(key) {
+            style.bold = !style.bold;
+        } else if HotKey::new(SysMods::Cmd, "i").matches(key) {
+            style.italic = !style.italic;
+        } else if HotKey::new(SysMods::Cmd, "u").matches(key) {
+            style.underline = !style.underline;
+        } else {
+            return false;
+        }
+        self.apply_style(data);
+        true
+    }
+}
+
+/// The range of `new` that was inserted in place of some range of `old`, if any.
+fn inserted_range(old: &str, new: &str) -> Option<Range<usize>> {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| old.len().min(new.len()));
+    let suffix = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+    let end = new.len() - suffix;
+    (end > prefix).then_some(prefix..end)
+}
+
+impl Default for RichTextBox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<RichTextDocument> for RichTextBox {
+    #[instrument(
+        name = "RichTextBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut RichTextDocument, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(APPLY_STYLE) => {
+                self.apply_style(data);
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key)
+                if ctx.has_focus()
+                    && !self.textbox.text().is_composing()
+                    && self.toggle_for_key(key, data) =>
+            {
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+
+        let old_text = data.text.clone();
+        self.textbox.event(ctx, event, &mut data.text, env);
+        if !self.textbox.text().can_read() {
+            return;
+        }
+
+        let text_changed = !old_text.same(&data.text);
+        if text_changed {
+            // Typed text continues the style of the text before it, or the style
+            // picked for it while the selection was a caret.
+            if let Some(range) = inserted_range(old_text.as_str(), data.text.as_str()) {
+                self.style
+                    .apply_changes(&TextStyle::default(), &mut data.text, range);
+            }
+        }
+        let selection = self.textbox.text().borrow().selection();
+        if text_changed || selection != self.selection {
+            self.selection = selection;
+            self.style = TextStyle::at(&data.text, selection);
+            if self.style != data.selection_style {
+                data.selection_style = self.style.clone();
+            }
+        }
+    }
+
+    #[instrument(
+        name = "RichTextBox",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &RichTextDocument,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.style = data.selection_style.clone();
+        }
+        self.textbox.lifecycle(ctx, event, &data.text, env)
+    }
+
+    #[instrument(
+        name = "RichTextBox",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &RichTextDocument,
+        data: &RichTextDocument,
+        env: &Env,
+    ) {
+        if data.selection_style != self.style {
+            ctx.submit_command(APPLY_STYLE.to(ctx.widget_id()));
+        }
+        self.textbox.update(ctx, &old_data.text, &data.text, env)
+    }
+
+    #[instrument(name = "RichTextBox", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &RichTextDocument,
+        env: &Env,
+    ) -> Size {
+        self.textbox.layout(ctx, bc, &data.text, env)
+    }
+
+    #[instrument(name = "RichTextBox", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &RichTextDocument, env: &Env) {
+        self.textbox.paint(ctx, &data.text, env)
+    }
+
+    fn debug_state(&self, data: &RichTextDocument) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.text.as_str().to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserted() {
+        assert_eq!(inserted_range("hello", "helXlo"), Some(3..4));
+        assert_eq!(inserted_range("hello", "hello!"), Some(5..6));
+        assert_eq!(inserted_range("hello", "hi"), Some(1..2));
+        assert_eq!(inserted_range("hello", "hllo"), None);
+        assert_eq!(inserted_range("aa", "aaa"), Some(2..3));
+    }
+
+    #[test]
+    fn style_follows_attributes() {
+        let mut text = RichText::new("plain bold".into());
+        text.add_attribute(6..10, Attribute::weight(FontWeight::BOLD));
+        assert!(!TextStyle::at(&text, Selection::caret(6)).bold);
+        assert!(TextStyle::at(&text, Selection::caret(8)).bold);
+        assert!(TextStyle::at(&text, Selection::new(6, 9)).bold);
+
+        let bold = TextStyle {
+            bold: true,
+            ..Default::default()
+        };
+        TextStyle::default().apply_changes(&bold, &mut text, 7..9);
+        assert!(TextStyle::at(&text, Selection::caret(7)).bold);
+        assert!(!TextStyle::at(&text, Selection::caret(8)).bold);
+        assert!(TextStyle::at(&text, Selection::caret(10)).bold);
+    }
+}
@@ -17,6 +17,7 @@ use std::marker::PhantomData;
 use crate::widget::prelude::*;
 use crate::widget::WidgetWrapper;
 use crate::{Data, Lens, Point, WidgetPod};
+use local_state_derived_lenses::{outer, state};
 use tracing::instrument;
 
 /// A policy that controls how a [`Scope`] will interact with its surrounding
@@ -144,6 +145,63 @@ impl<L: Lens<State, In>, In: Data, State: Data> ScopeTransfer for LensScopeTrans
     }
 }
 
+/// The data of a [`Scope`] made with [`Scope::with_state`]: the data from
+/// outside the scope, and the scope's own state.
+#[derive(Clone, Data, Lens)]
+pub struct LocalState<T, S> {
+    /// The data from outside the scope.
+    pub outer: T,
+    /// The state kept by the scope.
+    pub state: S,
+}
+
+type MakeState<T, S> = Box<dyn Fn(&T) -> S>;
+
+/// The [`ScopePolicy`] of a [`Scope`] made with [`Scope::with_state`].
+///
+/// The outer data is copied into the [`LocalState`] whenever it changes, and
+/// copied back out when a child changes it. The state is created from the
+/// outer data when the scope is first used, and is kept for as long as the
+/// scope is, unless the scope was built with [`Scope::reset_on_change`].
+pub struct LocalStatePolicy<T, S> {
+    make_state: MakeState<T, S>,
+    reset_on_change: bool,
+}
+
+impl<T: Data, S: Data> ScopePolicy for LocalStatePolicy<T, S> {
+    type In = T;
+    type State = LocalState<T, S>;
+    type Transfer = Self;
+
+    fn create(self, input: &T) -> (LocalState<T, S>, Self) {
+        let state = LocalState {
+            outer: input.clone(),
+            state: (self.make_state)(input),
+        };
+        (state, self)
+    }
+}
+
+impl<T: Data, S: Data> ScopeTransfer for LocalStatePolicy<T, S> {
+    type In = T;
+    type State = LocalState<T, S>;
+
+    fn read_input(&self, state: &mut LocalState<T, S>, input: &T) {
+        if !state.outer.same(input) {
+            state.outer = input.clone();
+            if self.reset_on_change {
+                state.state = (self.make_state)(input);
+            }
+        }
+    }
+
+    fn write_back_input(&self, state: &LocalState<T, S>, input: &mut T) {
+        if !state.outer.same(input) {
+            *input = state.outer.clone();
+        }
+    }
+}
+
 enum ScopeContent<SP: ScopePolicy> {
     Policy {
         policy: Option<SP>,
@@ -257,7 +315,7 @@ impl<SP: ScopePolicy, W: Widget<SP::State>> Scope<SP, W> {
         }
     }
 
-    fn with_state<V>(
+    fn with_current_state<V>(
         &mut self,
         data: &SP::In,
         mut f: impl FnMut(&mut SP::State, &mut WidgetPod<SP::State, W>) -> V,
@@ -312,22 +370,82 @@ impl<In: Data, State: Data, F: Fn(In) -> State, L: Lens<State, In>, W: Widget<St
     }
 }
 
+impl<T: Data, S: Data, W: Widget<LocalState<T, S>>> Scope<LocalStatePolicy<T, S>, W> {
+    /// Create a scope that keeps `initial` as local state beside the outer data.
+    ///
+    /// `build` is called with a lens to the outer data and a lens to the
+    /// state, and returns the child widget. Changes the child makes to the
+    /// outer data are written back out of the scope; the state stays inside.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use druid::widget::{Button, Flex, Label, Scope};
+    /// use druid::{Widget, WidgetExt};
+    ///
+    /// fn item() -> impl Widget<u32> {
+    ///     Scope::with_state(false, |outer, expanded| {
+    ///         Flex::column()
+    ///             .with_child(Button::new("Details").on_click(|_, data: &mut bool, _| {
+    ///                 *data = !*data
+    ///             }).lens(expanded))
+    ///             .with_child(Label::dynamic(|n: &u32, _| n.to_string()).lens(outer))
+    ///     })
+    /// }
+    /// ```
+    pub fn with_state(initial: S, build: impl FnOnce(outer<T, S>, state<T, S>) -> W) -> Self {
+        Self::with_state_from(move |_| initial.clone(), build)
+    }
+
+    /// Create a scope with local state made from the outer data.
+    ///
+    /// This is like [`with_state`], but the initial state is computed
+    /// from the outer data when the scope is first used.
+    ///
+    /// [`with_state`]: Scope::with_state
+    pub fn with_state_from(
+        make_state: impl Fn(&T) -> S + 'static,
+        build: impl FnOnce(outer<T, S>, state<T, S>) -> W,
+    ) -> Self {
+        let policy = LocalStatePolicy {
+            make_state: Box::new(make_state),
+            reset_on_change: false,
+        };
+        Self::new(policy, build(LocalState::outer, LocalState::state))
+    }
+
+    /// Builder-style method to recreate the local state whenever the outer
+    /// data is changed from outside the scope.
+    ///
+    /// This suits state that belongs to a particular value, such as an edit
+    /// buffer for the selected item.
+    pub fn reset_on_change(mut self) -> Self {
+        if let ScopeContent::Policy {
+            policy: Some(policy),
+        } = &mut self.content
+        {
+            policy.reset_on_change = true;
+        }
+        self
+    }
+}
+
 impl<SP: ScopePolicy, W: Widget<SP::State>> Widget<SP::In> for Scope<SP, W> {
     #[instrument(name = "Scope", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut SP::In, env: &Env) {
-        self.with_state(data, |state, inner| inner.event(ctx, event, state, env));
+        self.with_current_state(data, |state, inner| inner.event(ctx, event, state, env));
         self.write_back_input(data);
         ctx.request_update()
     }
 
     #[instrument(name = "Scope", level = "trace", skip(self, ctx, event, data, env))]
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &SP::In, env: &Env) {
-        self.with_state(data, |state, inner| inner.lifecycle(ctx, event, state, env));
+        self.with_current_state(data, |state, inner| inner.lifecycle(ctx, event, state, env));
     }
 
     #[instrument(name = "Scope", level = "trace", skip(self, ctx, _old_data, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &SP::In, data: &SP::In, env: &Env) {
-        self.with_state(data, |state, inner| inner.update(ctx, state, env));
+        self.with_current_state(data, |state, inner| inner.update(ctx, state, env));
     }
 
     #[instrument(name = "Scope", level = "trace", skip(self, ctx, bc, data, env))]
@@ -338,7 +456,7 @@ impl<SP: ScopePolicy, W: Widget<SP::State>> Widget<SP::In> for Scope<SP, W> {
         data: &SP::In,
         env: &Env,
     ) -> Size {
-        self.with_state(data, |state, inner| {
+        self.with_current_state(data, |state, inner| {
             let size = inner.layout(ctx, bc, state, env);
             inner.set_origin(ctx, Point::ORIGIN);
             size
@@ -347,7 +465,7 @@ impl<SP: ScopePolicy, W: Widget<SP::State>> Widget<SP::In> for Scope<SP, W> {
 
     #[instrument(name = "Scope", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &SP::In, env: &Env) {
-        self.with_state(data, |state, inner| inner.paint_raw(ctx, state, env));
+        self.with_current_state(data, |state, inner| inner.paint_raw(ctx, state, env));
     }
 
     // TODO
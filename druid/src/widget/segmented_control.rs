@@ -0,0 +1,519 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A row of mutually exclusive toggle buttons.
+
+#[cfg(feature = "im")]
+use std::hash::Hash;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::Line;
+use crate::widget::prelude::*;
+use crate::{theme, ArcStr, Color, Data, KbKey, KeyOrValue, Point, Rect, TextLayout};
+
+/// The space between a segment's label and its edges.
+const LABEL_PADDING: f64 = 12.0;
+
+/// A segment: its label, and the value it stands for.
+struct Segment<T> {
+    label: TextLayout<ArcStr>,
+    value: T,
+}
+
+/// What the user did to a segment.
+enum Action {
+    /// The segment was clicked, or space or return was pressed while it had focus.
+    Activate(usize),
+    /// The focus moved to the segment with an arrow key.
+    Move(usize),
+}
+
+/// The segments of a [`SegmentedControl`] or [`MultiSegmentedControl`], and how
+/// they are drawn and interacted with.
+struct Segments<T> {
+    segments: Vec<Segment<T>>,
+    /// The segment under the mouse.
+    hot: Option<usize>,
+    /// The segment the mouse was pressed on.
+    pressed: Option<usize>,
+    /// The segment that arrow keys move from, and that space toggles.
+    focused: usize,
+    width: f64,
+    selected_color: KeyOrValue<Color>,
+    hover_color: KeyOrValue<Color>,
+}
+
+/// A row of toggle buttons, of which exactly one is selected, that edits a value
+/// such as an enum.
+///
+/// Segments are selected by clicking them, or with the arrow keys while the control
+/// has focus. The colors of selected segments and of the segment under the mouse
+/// come from [`theme::SEGMENT_SELECTED_COLOR`] and [`theme::SEGMENT_HOVER_COLOR`],
+/// unless they are set with [`with_selected_color`] and [`with_hover_color`].
+///
+/// For a control where any number of segments can be selected, see
+/// [`MultiSegmentedControl`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::SegmentedControl;
+/// use druid::{Data, Widget};
+///
+/// #[derive(Clone, Copy, PartialEq, Data)]
+/// enum View {
+///     Day,
+///     Week,
+///     Month,
+/// }
+///
+/// fn view_picker() -> impl Widget<View> {
+///     SegmentedControl::new([("Day", View::Day), ("Week", View::Week), ("Month", View::Month)])
+/// }
+/// ```
+///
+/// [`theme::SEGMENT_SELECTED_COLOR`]: crate::theme::SEGMENT_SELECTED_COLOR
+/// [`theme::SEGMENT_HOVER_COLOR`]: crate::theme::SEGMENT_HOVER_COLOR
+/// [`with_selected_color`]: SegmentedControl::with_selected_color
+/// [`with_hover_color`]: SegmentedControl::with_hover_color
+pub struct SegmentedControl<T> {
+    segments: Segments<T>,
+}
+
+/// A row of toggle buttons, any number of which can be selected, that edits a set.
+///
+/// Clicking a segment adds its value to the set, or removes it. With the keyboard,
+/// the arrow keys move between segments and space or return toggles one.
+///
+/// This is styled like a [`SegmentedControl`].
+#[cfg(feature = "im")]
+pub struct MultiSegmentedControl<T> {
+    segments: Segments<T>,
+}
+
+impl<T: Data> Segments<T> {
+    fn new(variants: impl IntoIterator<Item = (impl Into<ArcStr>, T)>) -> Self {
+        let segments = variants
+            .into_iter()
+            .map(|(label, value)| Segment {
+                label: TextLayout::from_text(label.into()),
+                value,
+            })
+            .collect();
+        Segments {
+            segments,
+            hot: None,
+            pressed: None,
+            focused: 0,
+            width: 0.0,
+            selected_color: theme::SEGMENT_SELECTED_COLOR.into(),
+            hover_color: theme::SEGMENT_HOVER_COLOR.into(),
+        }
+    }
+
+    fn rect(&self, index: usize, height: f64) -> Rect {
+        let x = index as f64 * self.width;
+        Rect::new(x, 0.0, x + self.width, height)
+    }
+
+    fn hit(&self, pos: Point, size: Size) -> Option<usize> {
+        if !size.to_rect().contains(pos) || self.width <= 0.0 {
+            return None;
+        }
+        let index = (pos.x / self.width) as usize;
+        (index < self.segments.len()).then_some(index)
+    }
+
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event) -> Option<Action> {
+        if ctx.is_disabled() {
+            return None;
+        }
+        match event {
+            Event::MouseDown(mouse) => {
+                self.pressed = self.hit(mouse.pos, ctx.size());
+                if let Some(index) = self.pressed {
+                    self.focused = index;
+                    ctx.set_active(true);
+                    ctx.request_focus();
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseMove(mouse) => {
+                let hot = self.hit(mouse.pos, ctx.size());
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.request_paint();
+                let pressed = self.pressed.take();
+                if pressed.is_some() && pressed == self.hit(mouse.pos, ctx.size()) {
+                    return pressed.map(Action::Activate);
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let last = self.segments.len().saturating_sub(1);
+                let action = match key.key {
+                    KbKey::ArrowLeft | KbKey::ArrowUp => {
+                        Action::Move(self.focused.saturating_sub(1))
+                    }
+                    KbKey::ArrowRight | KbKey::ArrowDown => {
+                        Action::Move((self.focused + 1).min(last))
+                    }
+                    KbKey::Home => Action::Move(0),
+                    KbKey::End => Action::Move(last),
+                    KbKey::Enter => Action::Activate(self.focused),
+                    KbKey::Character(ref c) if c == " " => Action::Activate(self.focused),
+                    _ => return None,
+                };
+                if let Action::Move(index) = action {
+                    self.focused = index;
+                }
+                ctx.request_paint();
+                ctx.set_handled();
+                return Some(action);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_for_focus(),
+            LifeCycle::HotChanged(false) => {
+                self.hot = None;
+                ctx.request_paint();
+            }
+            LifeCycle::FocusChanged(_) | LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx) {
+        for segment in &mut self.segments {
+            if segment.label.needs_rebuild_after_update(ctx) {
+                ctx.request_layout();
+            }
+        }
+        if ctx.env_key_changed(&self.selected_color) || ctx.env_key_changed(&self.hover_color) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, env: &Env) -> Size {
+        let mut label_size = Size::ZERO;
+        for segment in &mut self.segments {
+            segment.label.rebuild_if_needed(ctx.text(), env);
+            let size = segment.label.size();
+            label_size = Size::new(
+                label_size.width.max(size.width),
+                label_size.height.max(size.height),
+            );
+        }
+        let count = self.segments.len().max(1) as f64;
+        let height = env
+            .get(theme::BORDERED_WIDGET_HEIGHT)
+            .max(label_size.height + 4.0);
+        let size = bc.constrain(Size::new(
+            (label_size.width + 2.0 * LABEL_PADDING) * count,
+            height,
+        ));
+        self.width = size.width / count;
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, env: &Env, is_selected: impl Fn(&T) -> bool) {
+        let size = ctx.size();
+        let radius = env.get(theme::BUTTON_BORDER_RADIUS);
+        let border_width = env.get(theme::BUTTON_BORDER_WIDTH);
+        let outline = size
+            .to_rect()
+            .inset(-border_width / 2.0)
+            .to_rounded_rect(radius);
+        let disabled = ctx.is_disabled();
+
+        ctx.with_save(|ctx| {
+            ctx.clip(size.to_rect().to_rounded_rect(radius));
+            ctx.fill(size.to_rect(), &env.get(theme::BACKGROUND_LIGHT));
+            for (i, segment) in self.segments.iter().enumerate() {
+                let rect = self.rect(i, size.height);
+                if is_selected(&segment.value) {
+                    let color = self.selected_color.resolve(env);
+                    let color = match disabled {
+                        true => color.with_alpha(0.4),
+                        false => color,
+                    };
+                    ctx.fill(rect, &color);
+                } else if self.hot == Some(i) && !disabled {
+                    ctx.fill(rect, &self.hover_color.resolve(env));
+                }
+            }
+        });
+
+        let border_color = env.get(theme::BORDER_DARK);
+        for i in 1..self.segments.len() {
+            let x = (i as f64 * self.width).round() + 0.5;
+            ctx.stroke(Line::new((x, 0.0), (x, size.height)), &border_color, 1.0);
+        }
+        ctx.stroke(outline, &border_color, border_width);
+
+        let width = self.width;
+        for (i, segment) in self.segments.iter_mut().enumerate() {
+            let rect = Rect::new(i as f64 * width, 0.0, (i + 1) as f64 * width, size.height);
+            let label_size = segment.label.size();
+            segment
+                .label
+                .draw(ctx, rect.center() - label_size.to_vec2() / 2.0);
+        }
+
+        if ctx.is_focused() {
+            let rect = self.rect(self.focused, size.height).inset(-2.0);
+            ctx.paint_focus_decoration(rect.to_rounded_rect(radius), env);
+        }
+    }
+
+    fn debug_state(&self, name: &str, selected: impl Fn(&T) -> bool) -> DebugState {
+        let selected: Vec<_> = self
+            .segments
+            .iter()
+            .filter(|segment| selected(&segment.value))
+            .map(|segment| {
+                segment
+                    .label
+                    .text()
+                    .map(|text| text.to_string())
+                    .unwrap_or_default()
+            })
+            .collect();
+        DebugState {
+            display_name: name.to_string(),
+            main_value: selected.join(", "),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data + PartialEq> SegmentedControl<T> {
+    /// Create a control from `(label, value)` pairs.
+    pub fn new(variants: impl IntoIterator<Item = (impl Into<ArcStr>, T)>) -> Self {
+        SegmentedControl {
+            segments: Segments::new(variants),
+        }
+    }
+
+    /// Builder-style method for setting the background of the selected segment.
+    pub fn with_selected_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.segments.selected_color = color.into();
+        self
+    }
+
+    /// Builder-style method for setting the background of the segment under the
+    /// mouse.
+    pub fn with_hover_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.segments.hover_color = color.into();
+        self
+    }
+
+    fn focus_selected(&mut self, data: &T) {
+        if let Some(index) = self
+            .segments
+            .segments
+            .iter()
+            .position(|segment| segment.value == *data)
+        {
+            self.segments.focused = index;
+        }
+    }
+}
+
+impl<T: Data + PartialEq> Widget<T> for SegmentedControl<T> {
+    #[instrument(
+        name = "SegmentedControl",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, _env: &Env) {
+        if let Some(Action::Activate(index) | Action::Move(index)) = self.segments.event(ctx, event)
+        {
+            let value = &self.segments.segments[index].value;
+            if *data != *value {
+                trace!(
+                    "SegmentedControl {:?} selected segment {}",
+                    ctx.widget_id(),
+                    index
+                );
+                *data = value.clone();
+            }
+        }
+    }
+
+    #[instrument(
+        name = "SegmentedControl",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.focus_selected(data);
+        }
+        self.segments.lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "SegmentedControl",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) {
+            self.focus_selected(data);
+            ctx.request_paint();
+        }
+        self.segments.update(ctx);
+    }
+
+    #[instrument(
+        name = "SegmentedControl",
+        level = "trace",
+        skip(self, ctx, bc, _data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("SegmentedControl");
+        self.segments.layout(ctx, bc, env)
+    }
+
+    #[instrument(name = "SegmentedControl", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.segments.paint(ctx, env, |value| value == data);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.segments
+            .debug_state(self.short_type_name(), |value| value == data)
+    }
+}
+
+#[cfg(feature = "im")]
+impl<T: Data + Hash + Eq> MultiSegmentedControl<T> {
+    /// Create a control from `(label, value)` pairs.
+    pub fn new(variants: impl IntoIterator<Item = (impl Into<ArcStr>, T)>) -> Self {
+        MultiSegmentedControl {
+            segments: Segments::new(variants),
+        }
+    }
+
+    /// Builder-style method for setting the background of selected segments.
+    pub fn with_selected_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.segments.selected_color = color.into();
+        self
+    }
+
+    /// Builder-style method for setting the background of the segment under the
+    /// mouse.
+    pub fn with_hover_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.segments.hover_color = color.into();
+        self
+    }
+}
+
+#[cfg(feature = "im")]
+impl<T: Data + Hash + Eq> Widget<im::HashSet<T>> for MultiSegmentedControl<T> {
+    #[instrument(
+        name = "MultiSegmentedControl",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut im::HashSet<T>, _env: &Env) {
+        if let Some(Action::Activate(index)) = self.segments.event(ctx, event) {
+            let value = &self.segments.segments[index].value;
+            if data.remove(value).is_none() {
+                data.insert(value.clone());
+            }
+            trace!(
+                "MultiSegmentedControl {:?} toggled segment {}",
+                ctx.widget_id(),
+                index
+            );
+        }
+    }
+
+    #[instrument(
+        name = "MultiSegmentedControl",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &im::HashSet<T>,
+        _env: &Env,
+    ) {
+        self.segments.lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "MultiSegmentedControl",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &im::HashSet<T>,
+        data: &im::HashSet<T>,
+        _env: &Env,
+    ) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+        self.segments.update(ctx);
+    }
+
+    #[instrument(
+        name = "MultiSegmentedControl",
+        level = "trace",
+        skip(self, ctx, bc, _data, env)
+    )]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &im::HashSet<T>,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("MultiSegmentedControl");
+        self.segments.layout(ctx, bc, env)
+    }
+
+    #[instrument(
+        name = "MultiSegmentedControl",
+        level = "trace",
+        skip(self, ctx, data, env)
+    )]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &im::HashSet<T>, env: &Env) {
+        self.segments.paint(ctx, env, |value| data.contains(value));
+    }
+
+    fn debug_state(&self, data: &im::HashSet<T>) -> DebugState {
+        self.segments
+            .debug_state(self.short_type_name(), |value| data.contains(value))
+    }
+}
@@ -0,0 +1,955 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A spreadsheet widget.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::kurbo::Line;
+use crate::text::TextComponent;
+use crate::widget::prelude::*;
+use crate::widget::{IdentityWrapper, Scroll, TextBox};
+use crate::{theme, ArcStr, Data, KbKey, Point, Rect, Selector, TextLayout, WidgetExt, WidgetPod};
+
+const COLUMN_WIDTH: f64 = 80.0;
+const ROW_HEIGHT: f64 = 22.0;
+/// The width of the row numbers on the left of the grid.
+const ROW_HEADER_WIDTH: f64 = 40.0;
+/// The width of the name of the selected cell, left of the formula bar.
+const CELL_NAME_WIDTH: f64 = 60.0;
+const CELL_PADDING: f64 = 4.0;
+
+/// The position of a cell in a [`Sheet`].
+///
+/// Cells are written as a column name and a row number, like `B3`, and are
+/// counted from zero, so `B3` is row 2 of column 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Data)]
+pub struct CellRef {
+    /// The row, counting from zero.
+    pub row: usize,
+    /// The column, counting from zero.
+    pub col: usize,
+}
+
+/// The value of a cell, after evaluating its formula.
+#[derive(Debug, Clone, PartialEq, Data)]
+pub enum CellValue {
+    /// The cell is empty.
+    Empty,
+    /// A number.
+    Number(f64),
+    /// Text.
+    Text(ArcStr),
+    /// The formula could not be evaluated; the message is shown in the cell.
+    Error(ArcStr),
+}
+
+/// The contents of the cells of a [`Sheet`].
+///
+/// Each cell has a source: a number or text, or a formula starting with `=`.
+#[derive(Debug, Clone, Default, Data)]
+pub struct SheetData {
+    cells: Arc<BTreeMap<CellRef, String>>,
+}
+
+/// Evaluates the formulas of a [`Sheet`].
+///
+/// [`BasicEngine`] is used unless the sheet is given another engine.
+pub trait FormulaEngine {
+    /// Evaluate `formula`, without the leading `=`.
+    ///
+    /// `lookup` returns the value of another cell.
+    fn evaluate(&self, formula: &str, lookup: &dyn Fn(CellRef) -> CellValue) -> CellValue;
+}
+
+/// A [`FormulaEngine`] for arithmetic over numbers and cells.
+///
+/// Formulas can use numbers, cell references like `A1`, `+`, `-`, `*`, `/`,
+/// parentheses, and the functions `SUM`, `MIN`, `MAX`, `AVERAGE` and `COUNT`,
+/// which take numbers, cells and ranges like `A1:B3`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BasicEngine;
+
+/// A spreadsheet: a grid of cells whose values are computed from formulas, with a
+/// formula bar for editing the selected cell.
+///
+/// Cells are selected with the mouse or the arrow keys. Typing in the formula bar
+/// and pressing return sets the source of the selected cell, and escape discards
+/// the edit. Double-clicking a cell, or pressing return or F2, moves to the formula
+/// bar, and delete clears the selected cell.
+///
+/// Only the visible cells are evaluated and drawn, so the sheet can have many rows
+/// and columns.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{CellRef, Sheet, SheetData};
+///
+/// let mut data = SheetData::new();
+/// data.set(CellRef::new(0, 0), "2");
+/// data.set(CellRef::new(1, 0), "3");
+/// data.set("A3".parse().unwrap(), "=SUM(A1:A2) * 2");
+/// let sheet = Sheet::new().with_size(100, 10);
+/// ```
+pub struct Sheet {
+    rows: usize,
+    cols: usize,
+    engine: Rc<dyn FormulaEngine>,
+    grid_id: WidgetId,
+    grid: WidgetPod<SheetData, Scroll<SheetData, IdentityWrapper<SheetGrid>>>,
+    formula: WidgetPod<String, TextBox<String>>,
+    /// The text being edited in the formula bar.
+    edit: String,
+    selected: CellRef,
+    cell_name: TextLayout<ArcStr>,
+}
+
+/// The grid of cells of a [`Sheet`], with row and column headers that stay in view.
+struct SheetGrid {
+    rows: usize,
+    cols: usize,
+    engine: Rc<dyn FormulaEngine>,
+    values: RefCell<HashMap<CellRef, CellValue>>,
+    layouts: HashMap<CellRef, TextLayout<ArcStr>>,
+    selected: CellRef,
+    /// The visible part of the grid.
+    clip: Rect,
+}
+
+impl CellRef {
+    /// The cell at `row` and `col`, counting from zero.
+    pub fn new(row: usize, col: usize) -> Self {
+        CellRef { row, col }
+    }
+
+    /// The name of a column: `A` to `Z`, then `AA`, `AB`, and so on.
+    pub fn column_name(col: usize) -> String {
+        let mut name = Vec::new();
+        let mut n = col + 1;
+        while n > 0 {
+            name.push(b'A' + ((n - 1) % 26) as u8);
+            n = (n - 1) / 26;
+        }
+        name.reverse();
+        String::from_utf8(name).unwrap()
+    }
+
+    /// Every cell in the rectangle between two corners.
+    fn range(a: CellRef, b: CellRef) -> impl Iterator<Item = CellRef> {
+        let cols = a.col.min(b.col)..=a.col.max(b.col);
+        (a.row.min(b.row)..=a.row.max(b.row))
+            .flat_map(move |row| cols.clone().map(move |col| CellRef::new(row, col)))
+    }
+}
+
+impl fmt::Display for CellRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", CellRef::column_name(self.col), self.row + 1)
+    }
+}
+
+impl FromStr for CellRef {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let split = s.find(|c: char| !c.is_ascii_alphabetic()).ok_or(())?;
+        let (letters, digits) = s.split_at(split);
+        if letters.is_empty() || letters.len() > 6 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(());
+        }
+        let col = letters.bytes().fold(0, |col, b| {
+            col * 26 + (b.to_ascii_uppercase() - b'A') as usize + 1
+        });
+        let row: usize = digits.parse().map_err(|_| ())?;
+        if row == 0 {
+            return Err(());
+        }
+        Ok(CellRef::new(row - 1, col - 1))
+    }
+}
+
+impl CellValue {
+    /// The value of a cell that doesn't contain a formula.
+    fn from_literal(source: &str) -> Self {
+        let trimmed = source.trim();
+        if trimmed.is_empty() {
+            CellValue::Empty
+        } else if let Ok(number) = trimmed.parse() {
+            CellValue::Number(number)
+        } else {
+            CellValue::Text(source.into())
+        }
+    }
+
+    /// The value as a number, treating empty cells as zero.
+    pub fn as_number(&self) -> Result<f64, ArcStr> {
+        match self {
+            CellValue::Empty => Ok(0.0),
+            CellValue::Number(number) => Ok(*number),
+            CellValue::Text(text) => text.trim().parse().map_err(|_| "#VALUE!".into()),
+            CellValue::Error(error) => Err(error.clone()),
+        }
+    }
+}
+
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellValue::Empty => Ok(()),
+            CellValue::Number(number) => write!(f, "{}", number),
+            CellValue::Text(text) | CellValue::Error(text) => f.write_str(text),
+        }
+    }
+}
+
+impl SheetData {
+    /// An empty sheet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The source of a cell; empty if the cell has never been set.
+    pub fn get(&self, cell: CellRef) -> &str {
+        self.cells.get(&cell).map(String::as_str).unwrap_or("")
+    }
+
+    /// Set the source of a cell; setting it to an empty string clears it.
+    pub fn set(&mut self, cell: CellRef, source: impl Into<String>) {
+        let source = source.into();
+        let cells = Arc::make_mut(&mut self.cells);
+        if source.is_empty() {
+            cells.remove(&cell);
+        } else {
+            cells.insert(cell, source);
+        }
+    }
+
+    /// The cells that are not empty, with their sources, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (CellRef, &str)> {
+        self.cells
+            .iter()
+            .map(|(cell, source)| (*cell, source.as_str()))
+    }
+
+    /// The value of a cell, evaluating formulas with `engine`.
+    pub fn evaluate(&self, cell: CellRef, engine: &dyn FormulaEngine) -> CellValue {
+        let cache = RefCell::new(HashMap::new());
+        Evaluator::new(self, engine, &cache).value(cell)
+    }
+}
+
+/// Evaluates cells, remembering their values and catching circular references.
+struct Evaluator<'a> {
+    data: &'a SheetData,
+    engine: &'a dyn FormulaEngine,
+    values: &'a RefCell<HashMap<CellRef, CellValue>>,
+    evaluating: RefCell<HashSet<CellRef>>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(
+        data: &'a SheetData,
+        engine: &'a dyn FormulaEngine,
+        values: &'a RefCell<HashMap<CellRef, CellValue>>,
+    ) -> Self {
+        Evaluator {
+            data,
+            engine,
+            values,
+            evaluating: RefCell::new(HashSet::new()),
+        }
+    }
+
+    fn value(&self, cell: CellRef) -> CellValue {
+        if let Some(value) = self.values.borrow().get(&cell) {
+            return value.clone();
+        }
+        let source = self.data.get(cell);
+        let value = match source.strip_prefix('=') {
+            Some(formula) => {
+                if !self.evaluating.borrow_mut().insert(cell) {
+                    return CellValue::Error("#CYCLE!".into());
+                }
+                let value = self.engine.evaluate(formula, &|cell| self.value(cell));
+                self.evaluating.borrow_mut().remove(&cell);
+                value
+            }
+            None => CellValue::from_literal(source),
+        };
+        self.values.borrow_mut().insert(cell, value.clone());
+        value
+    }
+}
+
+impl FormulaEngine for BasicEngine {
+    fn evaluate(&self, formula: &str, lookup: &dyn Fn(CellRef) -> CellValue) -> CellValue {
+        let mut parser = Parser {
+            input: formula.as_bytes(),
+            pos: 0,
+            lookup,
+        };
+        let result = parser.expression().and_then(|value| {
+            parser.skip_whitespace();
+            match parser.pos == parser.input.len() {
+                true => Ok(value),
+                false => Err("#ERROR!".into()),
+            }
+        });
+        result.unwrap_or_else(CellValue::Error)
+    }
+}
+
+/// A recursive descent parser and evaluator for [`BasicEngine`] formulas.
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+    lookup: &'a dyn Fn(CellRef) -> CellValue,
+}
+
+type ParseResult<T> = Result<T, ArcStr>;
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.input.get(self.pos), Some(b) if b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consume `byte` if it is next.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.input.get(self.pos) == Some(&byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expression(&mut self) -> ParseResult<CellValue> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat(b'+') {
+                let rhs = self.term()?;
+                value = CellValue::Number(value.as_number()? + rhs.as_number()?);
+            } else if self.eat(b'-') {
+                let rhs = self.term()?;
+                value = CellValue::Number(value.as_number()? - rhs.as_number()?);
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> ParseResult<CellValue> {
+        let mut value = self.factor()?;
+        loop {
+            if self.eat(b'*') {
+                let rhs = self.factor()?;
+                value = CellValue::Number(value.as_number()? * rhs.as_number()?);
+            } else if self.eat(b'/') {
+                let rhs = self.factor()?.as_number()?;
+                if rhs == 0.0 {
+                    return Err("#DIV/0!".into());
+                }
+                value = CellValue::Number(value.as_number()? / rhs);
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn factor(&mut self) -> ParseResult<CellValue> {
+        self.skip_whitespace();
+        if self.eat(b'-') {
+            return Ok(CellValue::Number(-self.factor()?.as_number()?));
+        }
+        if self.eat(b'(') {
+            let value = self.expression()?;
+            return match self.eat(b')') {
+                true => Ok(value),
+                false => Err("#ERROR!".into()),
+            };
+        }
+        match self.input.get(self.pos) {
+            Some(b) if b.is_ascii_digit() || *b == b'.' => self.number(),
+            Some(b) if b.is_ascii_alphabetic() => {
+                let name = self.word();
+                if self.eat(b'(') {
+                    self.function(&name)
+                } else {
+                    let cell = name.parse().map_err(|_| ArcStr::from("#NAME?"))?;
+                    Ok((self.lookup)(cell))
+                }
+            }
+            _ => Err("#ERROR!".into()),
+        }
+    }
+
+    fn number(&mut self) -> ParseResult<CellValue> {
+        let start = self.pos;
+        while matches!(self.input.get(self.pos), Some(b) if b.is_ascii_digit() || *b == b'.') {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.input[start..self.pos]).unwrap();
+        text.parse()
+            .map(CellValue::Number)
+            .map_err(|_| "#ERROR!".into())
+    }
+
+    /// A function name or cell reference.
+    fn word(&mut self) -> String {
+        let start = self.pos;
+        while matches!(self.input.get(self.pos), Some(b) if b.is_ascii_alphanumeric()) {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.input[start..self.pos]).to_ascii_uppercase()
+    }
+
+    /// The arguments of a function, after the opening parenthesis, as numbers.
+    ///
+    /// Text and empty cells in ranges are skipped.
+    fn arguments(&mut self) -> ParseResult<Vec<f64>> {
+        let mut numbers = Vec::new();
+        if self.eat(b')') {
+            return Ok(numbers);
+        }
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let range = match self.input.get(self.pos) {
+                Some(b) if b.is_ascii_alphabetic() => {
+                    let first = self.word().parse::<CellRef>();
+                    match (first, self.eat(b':')) {
+                        (Ok(first), true) => {
+                            self.skip_whitespace();
+                            let last = self.word().parse().map_err(|_| ArcStr::from("#NAME?"))?;
+                            Some((first, last))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            match range {
+                Some((first, last)) => {
+                    for cell in CellRef::range(first, last) {
+                        match (self.lookup)(cell) {
+                            CellValue::Number(number) => numbers.push(number),
+                            CellValue::Error(error) => return Err(error),
+                            CellValue::Empty | CellValue::Text(_) => {}
+                        }
+                    }
+                }
+                None => {
+                    self.pos = start;
+                    numbers.push(self.expression()?.as_number()?);
+                }
+            }
+            if self.eat(b')') {
+                return Ok(numbers);
+            }
+            if !self.eat(b',') {
+                return Err("#ERROR!".into());
+            }
+        }
+    }
+
+    fn function(&mut self, name: &str) -> ParseResult<CellValue> {
+        let args = self.arguments()?;
+        let value = match name {
+            "SUM" => args.iter().sum(),
+            "MIN" => args.iter().copied().reduce(f64::min).unwrap_or(0.0),
+            "MAX" => args.iter().copied().reduce(f64::max).unwrap_or(0.0),
+            "COUNT" => args.len() as f64,
+            "AVERAGE" if args.is_empty() => return Err("#DIV/0!".into()),
+            "AVERAGE" => args.iter().sum::<f64>() / args.len() as f64,
+            _ => return Err("#NAME?".into()),
+        };
+        Ok(CellValue::Number(value))
+    }
+}
+
+impl SheetGrid {
+    /// Sent by the grid to the [`Sheet`] when a cell is selected.
+    const SELECTED: Selector<CellRef> = Selector::new("druid-builtin.sheet-selected");
+    /// Sent by the grid to the [`Sheet`] to start editing the selected cell.
+    const EDIT: Selector = Selector::new("druid-builtin.sheet-edit");
+    /// Sent by the [`Sheet`] to the grid to select a cell.
+    const SELECT: Selector<CellRef> = Selector::new("druid-builtin.sheet-select");
+
+    fn placeholder() -> Self {
+        SheetGrid {
+            rows: 1,
+            cols: 1,
+            engine: Rc::new(BasicEngine),
+            values: RefCell::new(HashMap::new()),
+            layouts: HashMap::new(),
+            selected: CellRef::new(0, 0),
+            clip: Rect::ZERO,
+        }
+    }
+
+    fn cell_rect(&self, cell: CellRef) -> Rect {
+        let origin = Point::new(
+            ROW_HEADER_WIDTH + cell.col as f64 * COLUMN_WIDTH,
+            ROW_HEIGHT + cell.row as f64 * ROW_HEIGHT,
+        );
+        Rect::from_origin_size(origin, (COLUMN_WIDTH, ROW_HEIGHT))
+    }
+
+    fn cell_at(&self, pos: Point) -> Option<CellRef> {
+        let view = self.clip;
+        if pos.x < view.x0 + ROW_HEADER_WIDTH || pos.y < view.y0 + ROW_HEIGHT {
+            return None;
+        }
+        let col = ((pos.x - ROW_HEADER_WIDTH) / COLUMN_WIDTH).floor() as usize;
+        let row = ((pos.y - ROW_HEIGHT) / ROW_HEIGHT).floor() as usize;
+        (row < self.rows && col < self.cols).then(|| CellRef::new(row, col))
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, cell: CellRef) {
+        let cell = CellRef::new(cell.row.min(self.rows - 1), cell.col.min(self.cols - 1));
+        self.selected = cell;
+        // Leave room for the headers, which are drawn over the cells.
+        let rect = self.cell_rect(cell);
+        let rect = Rect::new(
+            rect.x0 - ROW_HEADER_WIDTH,
+            rect.y0 - ROW_HEIGHT,
+            rect.x1,
+            rect.y1,
+        );
+        ctx.scroll_area_to_view(rect);
+        ctx.submit_notification(SheetGrid::SELECTED.with(cell));
+        ctx.request_paint();
+    }
+}
+
+impl Widget<SheetData> for SheetGrid {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut SheetData, _env: &Env) {
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.request_focus();
+                if let Some(cell) = self.cell_at(mouse.pos) {
+                    self.select(ctx, cell);
+                    if mouse.count == 2 {
+                        ctx.submit_notification(SheetGrid::EDIT);
+                    }
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let CellRef { row, col } = self.selected;
+                let cell = match key.key {
+                    KbKey::ArrowUp => CellRef::new(row.saturating_sub(1), col),
+                    KbKey::ArrowDown => CellRef::new(row + 1, col),
+                    KbKey::ArrowLeft => CellRef::new(row, col.saturating_sub(1)),
+                    KbKey::ArrowRight | KbKey::Tab => CellRef::new(row, col + 1),
+                    KbKey::Enter | KbKey::F2 => {
+                        ctx.submit_notification(SheetGrid::EDIT);
+                        ctx.set_handled();
+                        return;
+                    }
+                    KbKey::Delete | KbKey::Backspace => {
+                        data.set(self.selected, "");
+                        ctx.submit_notification(SheetGrid::SELECTED.with(self.selected));
+                        ctx.set_handled();
+                        return;
+                    }
+                    _ => return,
+                };
+                self.select(ctx, cell);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) => {
+                if let Some(cell) = cmd.get(SheetGrid::SELECT) {
+                    self.select(ctx, *cell);
+                    ctx.set_handled();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &SheetData,
+        _env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_for_focus(),
+            LifeCycle::ViewContextChanged(view_context) => {
+                self.clip = view_context.clip;
+                ctx.request_paint();
+            }
+            LifeCycle::Size(size) if self.clip.area() <= 0.0 => self.clip = size.to_rect(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &SheetData, data: &SheetData, _env: &Env) {
+        if !old_data.same(data) {
+            self.values.borrow_mut().clear();
+            self.layouts.clear();
+            ctx.request_paint();
+        }
+        if ctx.env_changed() {
+            self.layouts.clear();
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &SheetData,
+        _env: &Env,
+    ) -> Size {
+        bc.debug_check("SheetGrid");
+        bc.constrain(Size::new(
+            ROW_HEADER_WIDTH + self.cols as f64 * COLUMN_WIDTH,
+            ROW_HEIGHT + self.rows as f64 * ROW_HEIGHT,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &SheetData, env: &Env) {
+        let view = self.clip;
+        let visible = ctx.region().bounding_box().intersect(view);
+        ctx.fill(visible, &env.get(theme::BACKGROUND_DARK));
+
+        let first_row = ((visible.y0 / ROW_HEIGHT).floor() as usize).saturating_sub(1);
+        let last_row = ((visible.y1 / ROW_HEIGHT).ceil() as usize).min(self.rows);
+        let first_col = (((visible.x0 - ROW_HEADER_WIDTH) / COLUMN_WIDTH)
+            .floor()
+            .max(0.0)) as usize;
+        let last_col = ((((visible.x1 - ROW_HEADER_WIDTH) / COLUMN_WIDTH)
+            .ceil()
+            .max(0.0)) as usize)
+            .min(self.cols);
+
+        // Cell values.
+        let evaluator = Evaluator::new(data, &*self.engine, &self.values);
+        let mut visible_layouts = HashMap::new();
+        for row in first_row..last_row {
+            for col in first_col..last_col {
+                let cell = CellRef::new(row, col);
+                let value = evaluator.value(cell);
+                if value == CellValue::Empty {
+                    continue;
+                }
+                let mut layout = self.layouts.remove(&cell).unwrap_or_else(|| {
+                    let mut layout = TextLayout::from_text(value.to_string());
+                    if let CellValue::Error(_) = value {
+                        layout.set_text_color(theme::FORM_ERROR_COLOR);
+                    }
+                    layout
+                });
+                layout.rebuild_if_needed(ctx.text(), env);
+                let rect = self.cell_rect(cell);
+                let size = layout.size();
+                let x = match value {
+                    CellValue::Number(_) => rect.x1 - CELL_PADDING - size.width,
+                    _ => rect.x0 + CELL_PADDING,
+                };
+                let y = rect.y0 + (ROW_HEIGHT - size.height) / 2.0;
+                ctx.with_save(|ctx| {
+                    ctx.clip(rect);
+                    layout.draw(ctx, (x, y));
+                });
+                visible_layouts.insert(cell, layout);
+            }
+        }
+        self.layouts = visible_layouts;
+
+        // Grid lines.
+        let line_color = env.get(theme::BORDER_DARK);
+        for row in first_row..=last_row {
+            let y = ROW_HEIGHT + row as f64 * ROW_HEIGHT + 0.5;
+            ctx.stroke(
+                Line::new((visible.x0, y), (visible.x1, y)),
+                &line_color,
+                1.0,
+            );
+        }
+        for col in first_col..=last_col {
+            let x = ROW_HEADER_WIDTH + col as f64 * COLUMN_WIDTH + 0.5;
+            ctx.stroke(
+                Line::new((x, visible.y0), (x, visible.y1)),
+                &line_color,
+                1.0,
+            );
+        }
+
+        let selected = self.cell_rect(self.selected).inset(-1.0);
+        let selected_color = match ctx.is_focused() {
+            true => env.get(theme::FOCUS_COLOR),
+            false => env.get(theme::BORDER_LIGHT),
+        };
+        ctx.stroke(selected, &selected_color, 2.0);
+
+        // Headers, which stay at the top and left of the view.
+        let header_color = env.get(theme::BACKGROUND_LIGHT);
+        let columns = Rect::new(visible.x0, view.y0, visible.x1, view.y0 + ROW_HEIGHT);
+        let rows = Rect::new(view.x0, visible.y0, view.x0 + ROW_HEADER_WIDTH, visible.y1);
+        ctx.fill(rows, &header_color);
+        for row in first_row..last_row {
+            let y = ROW_HEIGHT + row as f64 * ROW_HEIGHT;
+            let rect = Rect::new(view.x0, y, view.x0 + ROW_HEADER_WIDTH, y + ROW_HEIGHT);
+            draw_header(ctx, env, (row + 1).to_string(), rect);
+        }
+        ctx.fill(columns, &header_color);
+        for col in first_col..last_col {
+            let x = ROW_HEADER_WIDTH + col as f64 * COLUMN_WIDTH;
+            let rect = Rect::new(x, view.y0, x + COLUMN_WIDTH, view.y0 + ROW_HEIGHT);
+            draw_header(ctx, env, CellRef::column_name(col), rect);
+        }
+        let corner = Rect::from_origin_size(view.origin(), (ROW_HEADER_WIDTH, ROW_HEIGHT));
+        ctx.fill(corner, &header_color);
+    }
+}
+
+/// Draw the name of a row or column, centered in `rect`.
+fn draw_header(ctx: &mut PaintCtx, env: &Env, name: String, rect: Rect) {
+    let mut layout = TextLayout::<ArcStr>::from_text(name);
+    layout.set_text_color(theme::DISABLED_TEXT_COLOR);
+    layout.rebuild_if_needed(ctx.text(), env);
+    let size = layout.size();
+    let origin = rect.center() - size.to_vec2() / 2.0;
+    layout.draw(ctx, origin);
+    ctx.stroke(rect.inset(-0.5), &env.get(theme::BORDER_DARK), 1.0);
+}
+
+impl Sheet {
+    /// Create a sheet of 1000 rows and 26 columns, using the [`BasicEngine`].
+    pub fn new() -> Self {
+        let mut formula = TextBox::new();
+        formula.text_mut().borrow_mut().send_notification_on_return = true;
+        formula.text_mut().borrow_mut().send_notification_on_cancel = true;
+        let mut sheet = Sheet {
+            rows: 1000,
+            cols: 26,
+            engine: Rc::new(BasicEngine),
+            grid_id: WidgetId::next(),
+            grid: WidgetPod::new(Scroll::new(
+                SheetGrid::placeholder().with_id(WidgetId::next()),
+            )),
+            formula: WidgetPod::new(formula),
+            edit: String::new(),
+            selected: CellRef::new(0, 0),
+            cell_name: TextLayout::new(),
+        };
+        sheet.rebuild_grid();
+        sheet
+    }
+
+    /// Builder-style method for setting the number of rows and columns.
+    pub fn with_size(mut self, rows: usize, cols: usize) -> Self {
+        self.rows = rows.max(1);
+        self.cols = cols.max(1);
+        self.rebuild_grid();
+        self
+    }
+
+    /// Builder-style method for evaluating formulas with another engine.
+    pub fn with_engine(mut self, engine: impl FormulaEngine + 'static) -> Self {
+        self.engine = Rc::new(engine);
+        self.rebuild_grid();
+        self
+    }
+
+    /// The selected cell.
+    pub fn selected(&self) -> CellRef {
+        self.selected
+    }
+
+    fn rebuild_grid(&mut self) {
+        let grid = SheetGrid {
+            rows: self.rows,
+            cols: self.cols,
+            engine: self.engine.clone(),
+            ..SheetGrid::placeholder()
+        };
+        self.grid = WidgetPod::new(Scroll::new(grid.with_id(self.grid_id)));
+    }
+
+    fn show_selected(&mut self, ctx: &mut EventCtx, data: &SheetData) {
+        self.edit = data.get(self.selected).to_owned();
+        self.cell_name.set_text(self.selected.to_string().into());
+        ctx.request_update();
+        ctx.request_layout();
+    }
+}
+
+impl Default for Sheet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<SheetData> for Sheet {
+    #[instrument(name = "Sheet", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut SheetData, env: &Env) {
+        if let Event::Notification(note) = event {
+            if let Some(cell) = note.get(SheetGrid::SELECTED) {
+                self.selected = *cell;
+                self.show_selected(ctx, data);
+            } else if note.is(SheetGrid::EDIT) {
+                ctx.set_focus(self.formula.id());
+            } else if note.is(TextComponent::RETURN) {
+                trace!("setting {} to {:?}", self.selected, self.edit);
+                data.set(self.selected, self.edit.clone());
+                let below = CellRef::new(self.selected.row + 1, self.selected.col);
+                ctx.submit_command(SheetGrid::SELECT.with(below).to(self.grid_id));
+                ctx.set_focus(self.grid_id);
+            } else if note.is(TextComponent::CANCEL) {
+                self.show_selected(ctx, data);
+                ctx.set_focus(self.grid_id);
+            } else {
+                return;
+            }
+            ctx.set_handled();
+            return;
+        }
+
+        let edit = self.edit.clone();
+        self.formula.event(ctx, event, &mut self.edit, env);
+        if self.edit != edit {
+            ctx.request_update();
+        }
+        self.grid.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Sheet", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &SheetData,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.edit = data.get(self.selected).to_owned();
+            self.cell_name.set_text(self.selected.to_string().into());
+        }
+        self.formula.lifecycle(ctx, event, &self.edit, env);
+        self.grid.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Sheet", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &SheetData, data: &SheetData, env: &Env) {
+        if self.cell_name.needs_rebuild_after_update(ctx) {
+            ctx.request_layout();
+        }
+        self.formula.update(ctx, &self.edit, env);
+        self.grid.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Sheet", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &SheetData,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("Sheet");
+        let width = bc.max().width;
+        let formula_bc = BoxConstraints::new(
+            Size::new((width - CELL_NAME_WIDTH).max(0.0), 0.0),
+            Size::new((width - CELL_NAME_WIDTH).max(0.0), f64::INFINITY),
+        );
+        let bar_height = self
+            .formula
+            .layout(ctx, &formula_bc, &self.edit, env)
+            .height;
+        self.formula
+            .set_origin(ctx, Point::new(CELL_NAME_WIDTH, 0.0));
+        self.cell_name.rebuild_if_needed(ctx.text(), env);
+
+        let grid_bc = bc.shrink((0.0, bar_height)).loosen();
+        let grid_size = self.grid.layout(ctx, &grid_bc, data, env);
+        self.grid.set_origin(ctx, Point::new(0.0, bar_height));
+
+        let size = bc.constrain(Size::new(grid_size.width, bar_height + grid_size.height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Sheet", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &SheetData, env: &Env) {
+        let bar_height = self.formula.layout_rect().height();
+        let name_rect = Rect::new(0.0, 0.0, CELL_NAME_WIDTH, bar_height);
+        ctx.fill(name_rect, &env.get(theme::BACKGROUND_LIGHT));
+        let size = self.cell_name.size();
+        self.cell_name
+            .draw(ctx, name_rect.center() - size.to_vec2() / 2.0);
+        self.formula.paint(ctx, &self.edit, env);
+        self.grid.paint(ctx, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_names() {
+        assert_eq!(CellRef::new(2, 1).to_string(), "B3");
+        assert_eq!(CellRef::new(0, 26).to_string(), "AA1");
+        assert_eq!(CellRef::new(9, 701).to_string(), "ZZ10");
+        assert_eq!("aa1".parse(), Ok(CellRef::new(0, 26)));
+        assert_eq!("ZZ10".parse(), Ok(CellRef::new(9, 701)));
+        assert_eq!("A0".parse::<CellRef>(), Err(()));
+        assert_eq!("12".parse::<CellRef>(), Err(()));
+        assert_eq!("B".parse::<CellRef>(), Err(()));
+    }
+
+    #[test]
+    fn formulas() {
+        let mut data = SheetData::new();
+        data.set(CellRef::new(0, 0), "2");
+        data.set(CellRef::new(1, 0), "3");
+        data.set(CellRef::new(2, 0), "apples");
+        data.set(CellRef::new(0, 1), "=A1 + A2 * (1 + 1)");
+        data.set(CellRef::new(1, 1), "=SUM(A1:A3, 10) / -2");
+        data.set(CellRef::new(2, 1), "=AVERAGE(A1:A2) + MAX(A1, B1)");
+        data.set(CellRef::new(3, 1), "=A3");
+        data.set(CellRef::new(4, 1), "=A3 + 1");
+        data.set(CellRef::new(5, 1), "=A1 / (A2 - 3)");
+        data.set(CellRef::new(6, 1), "=B8");
+        data.set(CellRef::new(7, 1), "=B7 + 1");
+        data.set(CellRef::new(8, 1), "=FOO(1)");
+
+        let value = |name: &str| data.evaluate(name.parse().unwrap(), &BasicEngine);
+        assert_eq!(value("B1"), CellValue::Number(8.0));
+        assert_eq!(value("B2"), CellValue::Number(-7.5));
+        assert_eq!(value("B3"), CellValue::Number(10.5));
+        assert_eq!(value("B4"), CellValue::Text("apples".into()));
+        assert_eq!(value("B5"), CellValue::Error("#VALUE!".into()));
+        assert_eq!(value("B6"), CellValue::Error("#DIV/0!".into()));
+        assert_eq!(value("B7"), CellValue::Error("#CYCLE!".into()));
+        assert_eq!(value("B9"), CellValue::Error("#NAME?".into()));
+        assert_eq!(value("C1"), CellValue::Empty);
+    }
+}
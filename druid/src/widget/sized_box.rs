@@ -31,10 +31,23 @@ use crate::{Data, KeyOrValue};
 /// If not given a child, SizedBox will try to size itself as close to the specified height
 /// and width as possible given the parent's constraints. If height or width is not set,
 /// it will be treated as zero.
+///
+/// The width and height can also be a percentage of the maximum size allowed by the
+/// parent, see [`width_pct`] and [`height_pct`].
+///
+/// [`width_pct`]: SizedBox::width_pct
+/// [`height_pct`]: SizedBox::height_pct
 pub struct SizedBox<T> {
     child: Option<Box<dyn Widget<T>>>,
-    width: Option<KeyOrValue<f64>>,
-    height: Option<KeyOrValue<f64>>,
+    width: Option<Length>,
+    height: Option<Length>,
+}
+
+/// The width or height of a [`SizedBox`].
+enum Length {
+    Fixed(KeyOrValue<f64>),
+    /// A fraction of the maximum size allowed by the incoming constraints.
+    Fraction(f64),
 }
 
 impl<T> SizedBox<T> {
@@ -63,13 +76,31 @@ impl<T> SizedBox<T> {
 
     /// Set container's width.
     pub fn width(mut self, width: impl Into<KeyOrValue<f64>>) -> Self {
-        self.width = Some(width.into());
+        self.width = Some(Length::Fixed(width.into()));
         self
     }
 
     /// Set container's height.
     pub fn height(mut self, height: impl Into<KeyOrValue<f64>>) -> Self {
-        self.height = Some(height.into());
+        self.height = Some(Length::Fixed(height.into()));
+        self
+    }
+
+    /// Set container's width to a percentage of the maximum width allowed by its parent.
+    ///
+    /// For example, `width_pct(50.0)` makes the container half as wide as it can be.
+    /// If the parent allows any width, this has no effect.
+    pub fn width_pct(mut self, percent: f64) -> Self {
+        self.width = Some(Length::from_percent(percent));
+        self
+    }
+
+    /// Set container's height to a percentage of the maximum height allowed by its parent.
+    ///
+    /// For example, `height_pct(50.0)` makes the container half as high as it can be.
+    /// If the parent allows any height, this has no effect.
+    pub fn height_pct(mut self, percent: f64) -> Self {
+        self.height = Some(Length::from_percent(percent));
         self
     }
 
@@ -82,8 +113,8 @@ impl<T> SizedBox<T> {
     /// [`expand_height`]: #method.expand_height
     /// [`expand_width`]: #method.expand_width
     pub fn expand(mut self) -> Self {
-        self.width = Some(KeyOrValue::Concrete(INFINITY));
-        self.height = Some(KeyOrValue::Concrete(INFINITY));
+        self.width = Some(Length::Fixed(KeyOrValue::Concrete(INFINITY)));
+        self.height = Some(Length::Fixed(KeyOrValue::Concrete(INFINITY)));
         self
     }
 
@@ -91,7 +122,7 @@ impl<T> SizedBox<T> {
     ///
     /// This will force the child to have maximum width.
     pub fn expand_width(mut self) -> Self {
-        self.width = Some(KeyOrValue::Concrete(INFINITY));
+        self.width = Some(Length::Fixed(KeyOrValue::Concrete(INFINITY)));
         self
     }
 
@@ -99,25 +130,24 @@ impl<T> SizedBox<T> {
     ///
     /// This will force the child to have maximum height.
     pub fn expand_height(mut self) -> Self {
-        self.height = Some(KeyOrValue::Concrete(INFINITY));
+        self.height = Some(Length::Fixed(KeyOrValue::Concrete(INFINITY)));
         self
     }
 
     fn child_constraints(&self, bc: &BoxConstraints, env: &Env) -> BoxConstraints {
         // if we don't have a width/height, we don't change that axis.
         // if we have a width/height, we clamp it on that axis.
-        let (min_width, max_width) = match &self.width {
+        let max = bc.max();
+        let (min_width, max_width) = match resolve(&self.width, max.width, env) {
             Some(width) => {
-                let width = width.resolve(env);
                 let w = width.clamp(bc.min().width, bc.max().width);
                 (w, w)
             }
             None => (bc.min().width, bc.max().width),
         };
 
-        let (min_height, max_height) = match &self.height {
+        let (min_height, max_height) = match resolve(&self.height, max.height, env) {
             Some(height) => {
-                let height = height.resolve(env);
                 let h = height.clamp(bc.min().height, bc.max().height);
                 (h, h)
             }
@@ -133,12 +163,35 @@ impl<T> SizedBox<T> {
     #[cfg(test)]
     pub(crate) fn width_and_height(&self, env: &Env) -> (Option<f64>, Option<f64>) {
         (
-            self.width.as_ref().map(|w| w.resolve(env)),
-            self.height.as_ref().map(|h| h.resolve(env)),
+            resolve(&self.width, INFINITY, env),
+            resolve(&self.height, INFINITY, env),
         )
     }
 }
 
+impl Length {
+    fn from_percent(percent: f64) -> Self {
+        if !(0.0..=100.0).contains(&percent) {
+            warn!(
+                "SizedBox percentage should be between 0 and 100, got {}",
+                percent
+            );
+        }
+        Length::Fraction(percent.clamp(0.0, 100.0) / 100.0)
+    }
+}
+
+/// Resolves a length, given the maximum size allowed on its axis.
+///
+/// Fractions of an unbounded size are treated as if no length was set.
+fn resolve(length: &Option<Length>, max: f64, env: &Env) -> Option<f64> {
+    match length.as_ref()? {
+        Length::Fixed(length) => Some(length.resolve(env)),
+        Length::Fraction(fraction) if max.is_finite() => Some(max * fraction),
+        Length::Fraction(_) => None,
+    }
+}
+
 impl<T: Data> Widget<T> for SizedBox<T> {
     #[instrument(name = "SizedBox", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
@@ -173,14 +226,8 @@ impl<T: Data> Widget<T> for SizedBox<T> {
         let size = match self.child.as_mut() {
             Some(child) => child.layout(ctx, &child_bc, data, env),
             None => bc.constrain((
-                self.width
-                    .as_ref()
-                    .unwrap_or(&KeyOrValue::Concrete(0.0))
-                    .resolve(env),
-                self.height
-                    .as_ref()
-                    .unwrap_or(&KeyOrValue::Concrete(0.0))
-                    .resolve(env),
+                resolve(&self.width, bc.max().width, env).unwrap_or(0.0),
+                resolve(&self.height, bc.max().height, env).unwrap_or(0.0),
             )),
         };
 
@@ -228,13 +275,12 @@ impl<T: Data> Widget<T> for SizedBox<T> {
         data: &T,
         env: &Env,
     ) -> f64 {
-        let kv = match axis {
-            Axis::Horizontal => self.width.as_ref(),
-            Axis::Vertical => self.height.as_ref(),
+        let length = match axis {
+            Axis::Horizontal => resolve(&self.width, bc.max().width, env),
+            Axis::Vertical => resolve(&self.height, bc.max().height, env),
         };
-        match (self.child.as_mut(), kv) {
+        match (self.child.as_mut(), length) {
             (Some(c), Some(v)) => {
-                let v = v.resolve(env);
                 if v == f64::INFINITY {
                     c.compute_max_intrinsic(axis, ctx, bc, data, env)
                 } else {
@@ -243,7 +289,6 @@ impl<T: Data> Widget<T> for SizedBox<T> {
             }
             (Some(c), None) => c.compute_max_intrinsic(axis, ctx, bc, data, env),
             (None, Some(v)) => {
-                let v = v.resolve(env);
                 if v == f64::INFINITY {
                     // If v infinite, we can only warn.
                     warn!("SizedBox is without a child and its dim is infinite. Either give SizedBox a child or make its dim finite. ")
@@ -253,6 +298,25 @@ impl<T: Data> Widget<T> for SizedBox<T> {
             (None, None) => 0.,
         }
     }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let length = match axis {
+            Axis::Horizontal => resolve(&self.width, bc.max().width, env),
+            Axis::Vertical => resolve(&self.height, bc.max().height, env),
+        };
+        match (self.child.as_mut(), length) {
+            (_, Some(v)) if v.is_finite() => v,
+            (Some(c), _) => c.compute_min_intrinsic(axis, ctx, bc, data, env),
+            (None, _) => 0.,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -287,4 +351,23 @@ mod tests {
         assert_eq!(child_bc.min(), Size::new(0., 200.,));
         assert_eq!(child_bc.max(), Size::new(400., 200.,));
     }
+
+    #[test]
+    fn percentage() {
+        let env = Env::empty();
+        let half = SizedBox::<()>::new(Label::new("hello!")).width_pct(50.);
+        let bc = BoxConstraints::new(Size::new(100., 0.), Size::new(400., 300.));
+        let child_bc = half.child_constraints(&bc, &env);
+        assert_eq!(child_bc.min(), Size::new(200., 0.));
+        assert_eq!(child_bc.max(), Size::new(200., 300.));
+
+        // The size is still clamped to the constraints.
+        let small = SizedBox::<()>::new(Label::new("hello!")).width_pct(10.);
+        assert_eq!(small.child_constraints(&bc, &env).max().width, 100.);
+
+        // Percentages of an unbounded size are ignored.
+        let unbounded = BoxConstraints::new(Size::ZERO, Size::new(400., INFINITY));
+        let tall = SizedBox::<()>::new(Label::new("hello!")).height_pct(50.);
+        assert_eq!(tall.child_constraints(&unbounded, &env), unbounded);
+    }
 }
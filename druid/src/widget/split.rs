@@ -14,6 +14,11 @@
 
 //! A widget which splits an area in two, with a settable ratio, and optional draggable resizing.
 
+use std::time::Duration;
+
+use crate::commands::{
+    COLLAPSE_SPLIT_PANE, SET_SPLIT_POINT, SPLIT_PANE_COLLAPSED, SPLIT_POINT_CHANGED,
+};
 use crate::debug_state::DebugState;
 use crate::kurbo::Line;
 use crate::widget::flex::Axis;
@@ -21,6 +26,32 @@ use crate::widget::prelude::*;
 use crate::{theme, Color, Cursor, Data, Point, Rect, WidgetPod};
 use tracing::{instrument, trace, warn};
 
+/// The duration of the animation when a pane is collapsed or expanded.
+const COLLAPSE_ANIMATION: Duration = Duration::from_millis(150);
+
+/// One of the two panes of a [`Split`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum SplitPane {
+    /// The left pane of columns, or the upper pane of rows.
+    First,
+    /// The right pane of columns, or the lower pane of rows.
+    Second,
+}
+
+/// An in-progress animation of the split point, towards the current target.
+struct SplitAnimation {
+    from: f64,
+    elapsed: Duration,
+}
+
+impl SplitAnimation {
+    /// The eased progress of the animation, from `0.0` to `1.0`.
+    fn progress(&self) -> f64 {
+        let t = (self.elapsed.as_secs_f64() / COLLAPSE_ANIMATION.as_secs_f64()).min(1.0);
+        1.0 - (1.0 - t).powi(3)
+    }
+}
+
 /// A container containing two other widgets, splitting the area either horizontally or vertically.
 pub struct Split<T> {
     split_axis: Axis,
@@ -31,6 +62,14 @@ pub struct Split<T> {
     min_bar_area: f64,    // Integers only
     solid: bool,
     draggable: bool,
+    collapsible: bool,
+    collapsed: Option<SplitPane>,
+    /// Split points the bar snaps to while dragging, and the distance at which it snaps.
+    snap_points: Vec<f64>,
+    snap_distance: f64,
+    animation: Option<SplitAnimation>,
+    /// Whether the split point was changed by the current drag.
+    drag_moved: bool,
     /// The split bar is hovered by the mouse. This state is locked to `true` if the
     /// widget is active (the bar is being dragged) to avoid cursor and painting jitter
     /// if the mouse moves faster than the layout and temporarily gets outside of the
@@ -65,6 +104,12 @@ impl<T> Split<T> {
             min_bar_area: 6.0,
             solid: false,
             draggable: false,
+            collapsible: false,
+            collapsed: None,
+            snap_points: Vec::new(),
+            snap_distance: 0.0,
+            animation: None,
+            drag_moved: false,
             is_bar_hover: false,
             click_offset: 0.0,
             child1: WidgetPod::new(child1).boxed(),
@@ -157,6 +202,77 @@ impl<T> Split<T> {
         self
     }
 
+    /// Builder-style method to set whether the panes can be collapsed entirely.
+    ///
+    /// A collapsible pane is collapsed by double-clicking the splitter bar, by dragging
+    /// the bar to less than half of the pane's [minimum size], or by sending a
+    /// [`COLLAPSE_SPLIT_PANE`] command to the widget. Double-clicking the bar again
+    /// restores the previous split point.
+    ///
+    /// [minimum size]: Split::min_size
+    pub fn collapsible(mut self, collapsible: bool) -> Self {
+        self.collapsible = collapsible;
+        self
+    }
+
+    /// Builder-style method to start with the specified pane collapsed.
+    pub fn collapsed(mut self, pane: impl Into<Option<SplitPane>>) -> Self {
+        self.collapsed = pane.into();
+        self
+    }
+
+    /// Builder-style method to make the splitter bar snap to the provided split points
+    /// while it is dragged within `distance` of them.
+    ///
+    /// The split points are fractions of the split axis, like [`split_point`].
+    ///
+    /// [`split_point`]: Split::split_point
+    pub fn snap_points(mut self, points: impl Into<Vec<f64>>, distance: f64) -> Self {
+        self.snap_points = points.into();
+        self.snap_distance = distance.max(0.0);
+        self
+    }
+
+    /// The chosen split point, as a fraction of the split axis.
+    ///
+    /// This is the split point the user last dragged to, or the one that was set with
+    /// [`split_point`] or [`SET_SPLIT_POINT`]. It is not affected by the minimum sizes of
+    /// the panes, nor by collapsing a pane, so it can be persisted and restored later.
+    ///
+    /// [`split_point`]: Split::split_point
+    pub fn current_split_point(&self) -> f64 {
+        self.split_point_chosen
+    }
+
+    /// The currently collapsed pane, if any.
+    pub fn collapsed_pane(&self) -> Option<SplitPane> {
+        self.collapsed
+    }
+
+    /// Collapse the provided pane, or expand both panes with `None`, animating the change.
+    fn set_collapsed(&mut self, ctx: &mut EventCtx, pane: Option<SplitPane>) {
+        if self.collapsed == pane {
+            return;
+        }
+        self.collapsed = pane;
+        self.animation = Some(SplitAnimation {
+            from: self.split_point_effective,
+            elapsed: Duration::ZERO,
+        });
+        ctx.submit_notification_without_warning(SPLIT_PANE_COLLAPSED.with(pane));
+        ctx.request_anim_frame();
+        ctx.request_layout();
+    }
+
+    /// Returns the pane a double-click on the bar collapses: the one that is already smaller.
+    fn pane_to_collapse(&self) -> SplitPane {
+        if self.split_point_chosen <= 0.5 {
+            SplitPane::First
+        } else {
+            SplitPane::Second
+        }
+    }
+
     /// Returns the size of the splitter bar area.
     #[inline]
     fn bar_area(&self) -> f64 {
@@ -230,13 +346,37 @@ impl<T> Split<T> {
         (min_limit, max_limit)
     }
 
-    /// Set a new chosen split point.
+    /// Set a new chosen split point, and collapse or expand a collapsible pane
+    /// depending on how far the bar was dragged past its minimum size.
     fn update_split_point(&mut self, size: Size, mouse_pos: Point) {
-        let (min_limit, max_limit) = self.split_side_limits(size);
-        self.split_point_chosen = match self.split_axis {
-            Axis::Horizontal => mouse_pos.x.clamp(min_limit, max_limit) / size.width,
-            Axis::Vertical => mouse_pos.y.clamp(min_limit, max_limit) / size.height,
+        let axis_size = self.split_axis.major(size);
+        let mut pos = self.split_axis.major_pos(mouse_pos);
+
+        let (min_first, min_second) = self.min_size;
+        self.collapsed = if !self.collapsible {
+            None
+        } else if pos < min_first / 2.0 {
+            Some(SplitPane::First)
+        } else if pos > axis_size - min_second / 2.0 {
+            Some(SplitPane::Second)
+        } else {
+            None
+        };
+        if self.collapsed.is_some() {
+            return;
         }
+
+        if let Some(snap) = self
+            .snap_points
+            .iter()
+            .map(|point| point * axis_size)
+            .find(|snap| (snap - pos).abs() <= self.snap_distance)
+        {
+            pos = snap;
+        }
+
+        let (min_limit, max_limit) = self.split_side_limits(size);
+        self.split_point_chosen = pos.clamp(min_limit, max_limit) / axis_size;
     }
 
     /// Returns the color of the splitter bar.
@@ -317,12 +457,54 @@ impl<T: Data> Widget<T> for Split<T> {
                 return;
             }
         }
+        match event {
+            Event::AnimFrame(interval) => {
+                if let Some(animation) = &mut self.animation {
+                    animation.elapsed += Duration::from_nanos(*interval);
+                    if animation.elapsed >= COLLAPSE_ANIMATION || env.get(theme::REDUCED_MOTION) {
+                        self.animation = None;
+                    } else {
+                        ctx.request_anim_frame();
+                    }
+                    ctx.request_layout();
+                }
+            }
+            Event::Command(cmd) => {
+                if let Some(pane) = cmd.get(COLLAPSE_SPLIT_PANE) {
+                    ctx.set_handled();
+                    self.set_collapsed(ctx, *pane);
+                } else if let Some(split_point) = cmd.get(SET_SPLIT_POINT) {
+                    ctx.set_handled();
+                    self.split_point_chosen = split_point.clamp(0.0, 1.0);
+                    self.collapsed = None;
+                    self.animation = None;
+                    ctx.request_layout();
+                }
+            }
+            Event::MouseDown(mouse)
+                if self.collapsible
+                    && mouse.button.is_left()
+                    && mouse.count == 2
+                    && self.bar_hit_test(ctx.size(), mouse.pos) =>
+            {
+                ctx.set_handled();
+                let pane = match self.collapsed {
+                    Some(_) => None,
+                    None => Some(self.pane_to_collapse()),
+                };
+                self.set_collapsed(ctx, pane);
+                return;
+            }
+            _ => (),
+        }
         if self.draggable {
             match event {
                 Event::MouseDown(mouse) => {
                     if mouse.button.is_left() && self.bar_hit_test(ctx.size(), mouse.pos) {
                         ctx.set_handled();
                         ctx.set_active(true);
+                        self.animation = None;
+                        self.drag_moved = false;
                         // Save the delta between the mouse click position and the split point
                         self.click_offset = match self.split_axis {
                             Axis::Horizontal => mouse.pos.x,
@@ -342,6 +524,11 @@ impl<T: Data> Widget<T> for Split<T> {
                     if mouse.button.is_left() && ctx.is_active() {
                         ctx.set_handled();
                         ctx.set_active(false);
+                        if self.drag_moved {
+                            ctx.submit_notification_without_warning(
+                                SPLIT_POINT_CHANGED.with(self.split_point_chosen),
+                            );
+                        }
                         // Dependending on where the mouse cursor is when the button is released,
                         // the cursor might or might not need to be changed
                         self.is_bar_hover =
@@ -362,7 +549,14 @@ impl<T: Data> Widget<T> for Split<T> {
                                 Point::new(mouse.pos.x, mouse.pos.y - self.click_offset)
                             }
                         };
+                        let collapsed = self.collapsed;
                         self.update_split_point(ctx.size(), effective_pos);
+                        if self.collapsed != collapsed {
+                            ctx.submit_notification_without_warning(
+                                SPLIT_PANE_COLLAPSED.with(self.collapsed),
+                            );
+                        }
+                        self.drag_moved = true;
                         ctx.request_layout();
                     } else {
                         // If not active, set cursor when hovering state changes
@@ -428,16 +622,22 @@ impl<T: Data> Widget<T> for Split<T> {
         );
 
         // Update our effective split point to respect our constraints
-        self.split_point_effective = {
+        let target = {
             let (min_limit, max_limit) = self.split_side_limits(reduced_size);
             let reduced_axis_size = self.split_axis.major(reduced_size);
-            if reduced_axis_size.is_infinite() || reduced_axis_size <= std::f64::EPSILON {
-                0.5
-            } else {
-                self.split_point_chosen
-                    .clamp(min_limit / reduced_axis_size, max_limit / reduced_axis_size)
+            match self.collapsed {
+                Some(SplitPane::First) => 0.0,
+                Some(SplitPane::Second) => 1.0,
+                None if reduced_axis_size.is_infinite() || reduced_axis_size <= f64::EPSILON => 0.5,
+                None => self
+                    .split_point_chosen
+                    .clamp(min_limit / reduced_axis_size, max_limit / reduced_axis_size),
             }
         };
+        self.split_point_effective = match &self.animation {
+            Some(animation) => animation.from + (target - animation.from) * animation.progress(),
+            None => target,
+        };
 
         let (child1_bc, child2_bc) = match self.split_axis {
             Axis::Horizontal => {
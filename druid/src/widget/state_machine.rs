@@ -0,0 +1,452 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that drives its child's interaction logic with a state machine.
+
+use std::fmt::Debug;
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::WidgetWrapper;
+use crate::{Command, Data, Point, Selector, TimerToken, WidgetPod};
+
+/// How far the mouse has to move while pressed before
+/// [`InteractionState::Dragging`] is entered.
+const DRAG_THRESHOLD: f64 = 4.0;
+
+type Guard<T> = Box<dyn Fn(&Interaction, &T, &Env) -> bool>;
+type Action<T> = Box<dyn Fn(&mut EventCtx, &mut T, &Env)>;
+type EnvFn<T> = Box<dyn Fn(&mut Env, &T)>;
+
+/// Something that can cause a [`StateMachine`] to change state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trigger {
+    /// The mouse moved onto the widget.
+    MouseEnter,
+    /// The mouse moved off the widget.
+    MouseLeave,
+    /// A mouse button was pressed over the widget.
+    MouseDown,
+    /// A mouse button was released, while the widget is hot or active.
+    MouseUp,
+    /// The mouse moved, while the widget is hot or active.
+    MouseMove,
+    /// The current state has lasted this long.
+    After(Duration),
+    /// The widget received this command.
+    Command(Selector),
+}
+
+/// What is known about the interaction with the widget when a transition is
+/// considered. This is passed to guards.
+#[derive(Debug, Clone, Copy)]
+pub struct Interaction {
+    /// The position of the mouse, if the trigger was a mouse event.
+    pub pos: Option<Point>,
+    /// Where the mouse button was last pressed, if it is still down.
+    pub press_pos: Option<Point>,
+    /// Whether the mouse is over the widget.
+    pub is_hot: bool,
+    /// How long the machine has been in its current state.
+    pub time_in_state: Duration,
+}
+
+/// The states of the machine created by [`StateMachine::interaction`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteractionState {
+    /// The mouse is elsewhere.
+    Idle,
+    /// The mouse is over the widget.
+    Hover,
+    /// The mouse was pressed over the widget and has not moved much.
+    Pressed,
+    /// The mouse was pressed over the widget and then moved.
+    Dragging,
+}
+
+struct Transition<S, T> {
+    from: S,
+    trigger: Trigger,
+    guard: Option<Guard<T>>,
+    to: S,
+}
+
+/// A widget whose interaction logic is described by a state machine.
+///
+/// Rather than keeping track of hot, active and timer state by hand in a
+/// custom widget's [`event`] method, the states of the interaction and the
+/// transitions between them are declared up front. Transitions are caused by
+/// a [`Trigger`] and can be guarded by a condition. Entering a state can run
+/// actions, such as submitting a command, and each state can change the
+/// [`Env`] of the child, to style it.
+///
+/// For the common idle, hover, pressed and dragging states, see
+/// [`StateMachine::interaction`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::widget::{Label, StateMachine, Trigger};
+/// use druid::{theme, Color, Selector, Widget};
+///
+/// const LONG_PRESS: Selector = Selector::new("my-app.long-press");
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Press {
+///     Up,
+///     Down,
+///     Held,
+/// }
+///
+/// fn long_press_label() -> impl Widget<()> {
+///     StateMachine::new(Press::Up, Label::new("Press and hold"))
+///         .transition(Press::Up, Trigger::MouseDown, Press::Down)
+///         .transition(Press::Down, Trigger::After(Duration::from_millis(500)), Press::Held)
+///         .transition(Press::Down, Trigger::MouseUp, Press::Up)
+///         .transition(Press::Held, Trigger::MouseUp, Press::Up)
+///         .on_enter(Press::Down, |ctx, _, _| ctx.set_active(true))
+///         .on_enter(Press::Up, |ctx, _, _| ctx.set_active(false))
+///         .submit_on_enter(Press::Held, LONG_PRESS)
+///         .with_env(Press::Held, |env, _| env.set(theme::TEXT_COLOR, Color::RED))
+/// }
+/// ```
+///
+/// [`event`]: Widget::event
+pub struct StateMachine<S, T, W> {
+    state: S,
+    entered: Instant,
+    press_pos: Option<Point>,
+    was_hot: bool,
+    timers: Vec<(TimerToken, Duration)>,
+    transitions: Vec<Transition<S, T>>,
+    on_enter: Vec<(S, Action<T>)>,
+    env: Vec<(S, EnvFn<T>)>,
+    child: WidgetPod<T, W>,
+}
+
+impl<S: Clone + PartialEq + Debug + 'static, T: Data, W: Widget<T>> StateMachine<S, T, W> {
+    /// Create a state machine around `child`, starting in the `initial` state.
+    pub fn new(initial: S, child: W) -> Self {
+        StateMachine {
+            state: initial,
+            entered: Instant::now(),
+            press_pos: None,
+            was_hot: false,
+            timers: Vec::new(),
+            transitions: Vec::new(),
+            on_enter: Vec::new(),
+            env: Vec::new(),
+            child: WidgetPod::new(child),
+        }
+    }
+
+    /// Builder-style method to add a transition from `from` to `to`, when `trigger` happens.
+    ///
+    /// If several transitions match, the one that was added first is taken.
+    pub fn transition(mut self, from: S, trigger: Trigger, to: S) -> Self {
+        self.transitions.push(Transition {
+            from,
+            trigger,
+            guard: None,
+            to,
+        });
+        self
+    }
+
+    /// Builder-style method to add a transition that is only taken if `guard` returns `true`.
+    pub fn transition_if(
+        mut self,
+        from: S,
+        trigger: Trigger,
+        to: S,
+        guard: impl Fn(&Interaction, &T, &Env) -> bool + 'static,
+    ) -> Self {
+        self.transitions.push(Transition {
+            from,
+            trigger,
+            guard: Some(Box::new(guard)),
+            to,
+        });
+        self
+    }
+
+    /// Builder-style method to run `action` whenever `state` is entered.
+    pub fn on_enter(
+        mut self,
+        state: S,
+        action: impl Fn(&mut EventCtx, &mut T, &Env) + 'static,
+    ) -> Self {
+        self.on_enter.push((state, Box::new(action)));
+        self
+    }
+
+    /// Builder-style method to submit `command` whenever `state` is entered.
+    pub fn submit_on_enter(self, state: S, command: impl Into<Command>) -> Self {
+        let command = command.into();
+        self.on_enter(state, move |ctx, _, _| ctx.submit_command(command.clone()))
+    }
+
+    /// Builder-style method to change the [`Env`] of the child while in `state`.
+    pub fn with_env(mut self, state: S, f: impl Fn(&mut Env, &T) + 'static) -> Self {
+        self.env.push((state, Box::new(f)));
+        self
+    }
+
+    /// The current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    fn child_env(&self, data: &T, env: &Env) -> Env {
+        let mut env = env.clone();
+        for (_, f) in self.env.iter().filter(|(state, _)| *state == self.state) {
+            f(&mut env, data);
+        }
+        env
+    }
+
+    /// Take the first transition from the current state that matches `trigger`.
+    fn fire(
+        &mut self,
+        ctx: &mut EventCtx,
+        trigger: Trigger,
+        pos: Option<Point>,
+        data: &mut T,
+        env: &Env,
+    ) {
+        let interaction = Interaction {
+            pos,
+            press_pos: self.press_pos,
+            is_hot: ctx.is_hot(),
+            time_in_state: self.entered.elapsed(),
+        };
+        let next = self
+            .transitions
+            .iter()
+            .filter(|t| t.from == self.state && t.trigger == trigger)
+            .find(|t| match &t.guard {
+                Some(guard) => guard(&interaction, data, env),
+                None => true,
+            })
+            .map(|t| t.to.clone());
+        if let Some(next) = next {
+            self.enter(ctx, next, data, env);
+        }
+    }
+
+    fn enter(&mut self, ctx: &mut EventCtx, state: S, data: &mut T, env: &Env) {
+        trace!("{:?} -> {:?}", self.state, state);
+        self.state = state;
+        self.entered = Instant::now();
+        self.timers.clear();
+        for transition in self.transitions.iter().filter(|t| t.from == self.state) {
+            if let Trigger::After(duration) = transition.trigger {
+                self.timers.push((ctx.request_timer(duration), duration));
+            }
+        }
+        for (_, action) in self.on_enter.iter().filter(|(s, _)| *s == self.state) {
+            action(ctx, data, env);
+        }
+        ctx.request_update();
+        ctx.request_paint();
+    }
+}
+
+impl<T: Data, W: Widget<T>> StateMachine<InteractionState, T, W> {
+    /// Create a state machine with the usual pointer interaction states.
+    ///
+    /// The machine goes from [`Idle`] to [`Hover`] when the mouse enters the
+    /// widget, to [`Pressed`] when a button is pressed, and to [`Dragging`]
+    /// when the mouse moves a few pixels while pressed. The widget is
+    /// [active] while pressed or dragging.
+    ///
+    /// More transitions and actions can be added with the builder methods.
+    ///
+    /// [`Idle`]: InteractionState::Idle
+    /// [`Hover`]: InteractionState::Hover
+    /// [`Pressed`]: InteractionState::Pressed
+    /// [`Dragging`]: InteractionState::Dragging
+    /// [active]: EventCtx::set_active
+    pub fn interaction(child: W) -> Self {
+        use InteractionState::*;
+        StateMachine::new(Idle, child)
+            .transition(Idle, Trigger::MouseEnter, Hover)
+            .transition(Hover, Trigger::MouseLeave, Idle)
+            .transition(Hover, Trigger::MouseDown, Pressed)
+            .transition_if(
+                Pressed,
+                Trigger::MouseMove,
+                Dragging,
+                |interaction, _, _| match (interaction.pos, interaction.press_pos) {
+                    (Some(pos), Some(press_pos)) => pos.distance(press_pos) > DRAG_THRESHOLD,
+                    _ => false,
+                },
+            )
+            .transition_if(Pressed, Trigger::MouseUp, Hover, |i, _, _| i.is_hot)
+            .transition(Pressed, Trigger::MouseUp, Idle)
+            .transition_if(Dragging, Trigger::MouseUp, Hover, |i, _, _| i.is_hot)
+            .transition(Dragging, Trigger::MouseUp, Idle)
+            .on_enter(Pressed, |ctx, _, _| ctx.set_active(true))
+            .on_enter(Hover, |ctx, _, _| ctx.set_active(false))
+            .on_enter(Idle, |ctx, _, _| ctx.set_active(false))
+    }
+}
+
+impl<S: Clone + PartialEq + Debug + 'static, T: Data, W: Widget<T>> Widget<T>
+    for StateMachine<S, T, W>
+{
+    #[instrument(
+        name = "StateMachine",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(mouse) => {
+                let pos = Some(mouse.pos);
+                if ctx.is_hot() != self.was_hot {
+                    self.was_hot = ctx.is_hot();
+                    let trigger = match self.was_hot {
+                        true => Trigger::MouseEnter,
+                        false => Trigger::MouseLeave,
+                    };
+                    self.fire(ctx, trigger, pos, data, env);
+                }
+                self.fire(ctx, Trigger::MouseMove, pos, data, env);
+            }
+            Event::MouseDown(mouse) => {
+                self.press_pos = Some(mouse.pos);
+                self.fire(ctx, Trigger::MouseDown, Some(mouse.pos), data, env);
+            }
+            Event::MouseUp(mouse) => {
+                self.fire(ctx, Trigger::MouseUp, Some(mouse.pos), data, env);
+                self.press_pos = None;
+            }
+            Event::Timer(token) => {
+                if let Some(&(_, duration)) = self.timers.iter().find(|(t, _)| t == token) {
+                    self.fire(ctx, Trigger::After(duration), None, data, env);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::Command(cmd) => {
+                let selector = self.transitions.iter().find_map(|t| match t.trigger {
+                    Trigger::Command(selector) if t.from == self.state && cmd.is(selector) => {
+                        Some(selector)
+                    }
+                    _ => None,
+                });
+                if let Some(selector) = selector {
+                    self.fire(ctx, Trigger::Command(selector), None, data, env);
+                }
+            }
+            _ => {}
+        }
+        let env = self.child_env(data, env);
+        self.child.event(ctx, event, data, &env);
+    }
+
+    #[instrument(
+        name = "StateMachine",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let env = self.child_env(data, env);
+        self.child.lifecycle(ctx, event, data, &env);
+    }
+
+    #[instrument(
+        name = "StateMachine",
+        level = "trace",
+        skip(self, ctx, _old, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old: &T, data: &T, env: &Env) {
+        let env = self.child_env(data, env);
+        self.child.update(ctx, data, &env);
+    }
+
+    #[instrument(name = "StateMachine", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("StateMachine");
+        let env = self.child_env(data, env);
+        let size = self.child.layout(ctx, bc, data, &env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "StateMachine", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let env = self.child_env(data, env);
+        self.child.paint(ctx, data, &env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("{:?}", self.state),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<S, T, W: Widget<T>> WidgetWrapper for StateMachine<S, T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::MouseButton;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn pointer_interaction() {
+        use InteractionState::*;
+        let states = Rc::new(RefCell::new(Vec::new()));
+        let mut machine = StateMachine::interaction(SizedBox::empty());
+        for state in [Idle, Hover, Pressed, Dragging] {
+            let states = states.clone();
+            machine = machine.on_enter(state, move |_, _, _| states.borrow_mut().push(state));
+        }
+
+        Harness::create_simple((), machine, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.event(Event::MouseMove(move_mouse((10., 10.))));
+            let mut down = move_mouse((10., 10.));
+            down.button = MouseButton::Left;
+            harness.event(Event::MouseDown(down));
+            harness.event(Event::MouseMove(move_mouse((20., 10.))));
+            // Release outside of the widget, which covers the whole window.
+            harness.event(Event::MouseMove(move_mouse((500., 10.))));
+            let mut up = move_mouse((500., 10.));
+            up.button = MouseButton::Left;
+            harness.event(Event::MouseUp(up));
+        });
+
+        assert_eq!(*states.borrow(), [Hover, Pressed, Dragging, Idle]);
+    }
+}
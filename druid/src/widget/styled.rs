@@ -0,0 +1,344 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that overrides common theme values for its child.
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::kurbo::RoundedRectRadii;
+use crate::theme::{self, Theme};
+use crate::widget::prelude::*;
+use crate::widget::WidgetWrapper;
+use crate::{Color, Data, Insets, Key, Point, ValueType, WidgetPod};
+
+/// Overrides of common theme values, applied by a [`Styled`] widget.
+///
+/// Each method sets the [`Env`] keys that the builtin widgets use for one
+/// property; for example [`background`] sets the colors of buttons and text
+/// boxes. The [`hover`], [`active`] and [`disabled`] variants are applied on
+/// top of the base style while the child is in that state.
+///
+/// This is usually created with [`WidgetExt::styled`].
+///
+/// [`background`]: Style::background
+/// [`hover`]: Style::hover
+/// [`active`]: Style::active
+/// [`disabled`]: Style::disabled
+/// [`WidgetExt::styled`]: super::WidgetExt::styled
+#[derive(Clone, Debug, Default)]
+pub struct Style {
+    values: Theme,
+    hover: Theme,
+    active: Theme,
+    disabled: Theme,
+    /// Whether this is the variant for the disabled state, which sets the
+    /// `DISABLED_*` keys instead.
+    is_disabled_variant: bool,
+}
+
+/// The interaction state of the child of a [`Styled`] widget.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct State {
+    hot: bool,
+    active: bool,
+    disabled: bool,
+}
+
+impl Style {
+    /// Create a style that overrides nothing.
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    /// Builder-style method to set the background color of buttons and text
+    /// boxes.
+    pub fn background(self, color: impl Into<Color>) -> Self {
+        let keys = if self.is_disabled_variant {
+            [
+                theme::DISABLED_BUTTON_LIGHT,
+                theme::DISABLED_BUTTON_DARK,
+                theme::BACKGROUND_LIGHT,
+                theme::BACKGROUND_DARK,
+            ]
+        } else {
+            [
+                theme::BUTTON_LIGHT,
+                theme::BUTTON_DARK,
+                theme::BACKGROUND_LIGHT,
+                theme::BACKGROUND_DARK,
+            ]
+        };
+        self.with_colors(&keys, color.into())
+    }
+
+    /// Builder-style method to set the color of text.
+    pub fn text_color(self, color: impl Into<Color>) -> Self {
+        let key = if self.is_disabled_variant {
+            theme::DISABLED_TEXT_COLOR
+        } else {
+            theme::TEXT_COLOR
+        };
+        self.with_colors(&[key], color.into())
+    }
+
+    /// Builder-style method to set the color and width of borders.
+    pub fn border(self, color: impl Into<Color>, width: f64) -> Self {
+        self.with_colors(&[theme::BORDER_LIGHT, theme::BORDER_DARK], color.into())
+            .set(theme::BUTTON_BORDER_WIDTH, width)
+            .set(theme::TEXTBOX_BORDER_WIDTH, width)
+    }
+
+    /// Builder-style method to set the corner radius of buttons and text boxes.
+    pub fn corner_radius(self, radius: impl Into<RoundedRectRadii>) -> Self {
+        let radius = radius.into();
+        self.set(theme::BUTTON_BORDER_RADIUS, radius)
+            .set(theme::TEXTBOX_BORDER_RADIUS, radius)
+    }
+
+    /// Builder-style method to set the space between the border of buttons and
+    /// text boxes and their content.
+    pub fn padding(self, insets: impl Into<Insets>) -> Self {
+        let insets = insets.into();
+        self.set(theme::BUTTON_INSETS, insets)
+            .set(theme::TEXTBOX_INSETS, insets)
+    }
+
+    /// Builder-style method to set any other `Env` value.
+    pub fn set<V: ValueType + 'static>(mut self, key: Key<V>, value: impl Into<V>) -> Self {
+        self.values = self.values.with_value(key, value);
+        self
+    }
+
+    /// Builder-style method to set the style for when the pointer is over the
+    /// child.
+    ///
+    /// The variants of the returned style are ignored.
+    pub fn hover(mut self, f: impl FnOnce(Style) -> Style) -> Self {
+        self.hover = f(Style::new()).values;
+        self
+    }
+
+    /// Builder-style method to set the style for when the child, or one of its
+    /// descendants, is active; for example while a button is pressed.
+    ///
+    /// This is applied on top of the [`hover`] style. The variants of the
+    /// returned style are ignored.
+    ///
+    /// [`hover`]: Style::hover
+    pub fn active(mut self, f: impl FnOnce(Style) -> Style) -> Self {
+        self.active = f(Style::new()).values;
+        self
+    }
+
+    /// Builder-style method to set the style for when the child is disabled.
+    ///
+    /// Here [`background`] and [`text_color`] set the keys for disabled
+    /// widgets, such as [`DISABLED_TEXT_COLOR`]. The variants of the returned
+    /// style are ignored.
+    ///
+    /// [`background`]: Style::background
+    /// [`text_color`]: Style::text_color
+    /// [`DISABLED_TEXT_COLOR`]: theme::DISABLED_TEXT_COLOR
+    pub fn disabled(mut self, f: impl FnOnce(Style) -> Style) -> Self {
+        let variant = Style {
+            is_disabled_variant: true,
+            ..Style::new()
+        };
+        self.disabled = f(variant).values;
+        self
+    }
+
+    fn with_colors(mut self, keys: &[Key<Color>], color: Color) -> Self {
+        for key in keys {
+            self.values = self.values.with_color(key.clone(), color);
+        }
+        self
+    }
+
+    /// Set the values of the style for a child in `state`.
+    fn apply(&self, env: &mut Env, state: State) {
+        self.values.apply(env);
+        if state.disabled {
+            self.disabled.apply(env);
+            return;
+        }
+        if state.hot {
+            self.hover.apply(env);
+        }
+        if state.active {
+            self.active.apply(env);
+        }
+    }
+}
+
+/// A widget that applies a [`Style`] to its child, changing with the
+/// child's hot, active and disabled state.
+///
+/// This is available as [`WidgetExt::styled`] for convenience.
+///
+/// [`WidgetExt::styled`]: super::WidgetExt::styled
+pub struct Styled<T, W> {
+    child: WidgetPod<T, W>,
+    style: Style,
+    state: State,
+    /// The env from the parent, and the env made from it for the child.
+    cached_env: Option<(Env, State, Env)>,
+}
+
+impl<T, W: Widget<T>> Styled<T, W> {
+    /// Create a widget that applies `style` to `child`.
+    pub fn new(style: Style, child: W) -> Self {
+        Styled {
+            child: WidgetPod::new(child),
+            style,
+            state: State::default(),
+            cached_env: None,
+        }
+    }
+
+    /// The style of the widget.
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    fn child_env(&mut self, env: &Env) -> Env {
+        match &self.cached_env {
+            Some((parent, state, child)) if parent.same(env) && *state == self.state => {
+                child.clone()
+            }
+            _ => {
+                let mut child = env.clone();
+                self.style.apply(&mut child, self.state);
+                self.cached_env = Some((env.clone(), self.state, child.clone()));
+                child
+            }
+        }
+    }
+
+    /// Read the state of the child, returning `true` if it changed.
+    fn update_state(&mut self, disabled: bool) -> bool {
+        let state = State {
+            hot: self.child.is_hot(),
+            active: self.child.has_active(),
+            disabled,
+        };
+        let changed = state != self.state;
+        self.state = state;
+        changed
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Styled<T, W> {
+    #[instrument(name = "Styled", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.event(ctx, event, data, &child_env);
+        if self.update_state(ctx.is_disabled()) {
+            ctx.request_update();
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Styled", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.lifecycle(ctx, event, data, &child_env);
+        if self.update_state(ctx.is_disabled()) {
+            // there is no `request_update` on `LifeCycleCtx`
+            ctx.widget_state.request_update = true;
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(
+        name = "Styled",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.update(ctx, data, &child_env);
+    }
+
+    #[instrument(name = "Styled", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Styled");
+        let child_env = self.child_env(env);
+        let size = self.child.layout(ctx, bc, data, &child_env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "Styled", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.paint(ctx, data, &child_env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, W: Widget<T>> WidgetWrapper for Styled<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_follow_state() {
+        let red = Color::rgb8(0xff, 0, 0);
+        let green = Color::rgb8(0, 0xff, 0);
+        let blue = Color::rgb8(0, 0, 0xff);
+        let style = Style::new()
+            .background(red)
+            .hover(|style| style.background(green))
+            .active(|style| style.text_color(green))
+            .disabled(|style| style.background(blue));
+        let base = theme::add_to_env(Env::empty());
+
+        let mut env = base.clone();
+        style.apply(&mut env, State::default());
+        assert_eq!(env.get(theme::BUTTON_LIGHT), red);
+
+        let mut env = base.clone();
+        let pressed = State {
+            hot: true,
+            active: true,
+            disabled: false,
+        };
+        style.apply(&mut env, pressed);
+        assert_eq!(env.get(theme::BUTTON_LIGHT), green);
+        assert_eq!(env.get(theme::TEXT_COLOR), green);
+
+        let mut env = base;
+        let disabled = State {
+            disabled: true,
+            ..pressed
+        };
+        style.apply(&mut env, disabled);
+        assert_eq!(env.get(theme::DISABLED_BUTTON_LIGHT), blue);
+        assert_eq!(env.get(theme::BUTTON_LIGHT), red);
+        assert_ne!(env.get(theme::TEXT_COLOR), green);
+    }
+}
@@ -17,17 +17,55 @@
 use std::sync::Arc;
 
 use resvg;
-use usvg::Tree;
+use usvg::{roxmltree, NodeKind, Paint, Tree};
 
 use crate::piet::{ImageBuf, ImageFormat, InterpolationMode};
 use crate::widget::prelude::*;
-use crate::{Rect, ScaledArea};
+use crate::{Color, KeyOrValue, Rect, ScaledArea};
+
+/// The color `currentColor` resolves to in [`SvgData`], so that [`Svg`] can find it.
+///
+/// It is close enough to black, the default for `currentColor`, that it looks the
+/// same when the tree is drawn without recoloring.
+const CURRENT_COLOR: usvg::Color = usvg::Color {
+    red: 1,
+    green: 0,
+    blue: 1,
+};
 
 /// A widget that renders a SVG
+///
+/// The SVG is rasterized at the resolution of the window, and the result is
+/// cached until the widget's size or color changes.
+///
+/// # Recoloring
+///
+/// Fills and strokes that use `currentColor` can be drawn in a color from the
+/// [`Env`] with [`with_color`], so that a single icon works with both light
+/// and dark themes:
+///
+/// ```
+/// use druid::widget::{Svg, SvgData};
+/// use druid::theme;
+///
+/// let icon: SvgData = r#"
+///     <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+///         <circle cx="5" cy="5" r="4" fill="currentColor"/>
+///     </svg>
+/// "#.parse().unwrap();
+/// let widget = Svg::new(icon).with_color(theme::TEXT_COLOR);
+/// ```
+///
+/// This only applies to [`SvgData`] that doesn't set a `color` on its root element.
+///
+/// [`with_color`]: Svg::with_color
 pub struct Svg {
     tree: Arc<Tree>,
     default_size: Size,
+    color: Option<KeyOrValue<Color>>,
     cached: Option<ImageBuf>,
+    /// The color `cached` was rendered with.
+    cached_color: Option<Color>,
 }
 
 impl Svg {
@@ -38,19 +76,46 @@ impl Svg {
         let tree = tree.into();
         Svg {
             default_size: Size::new(tree.size.width(), tree.size.height()),
+            color: None,
             cached: None,
+            cached_color: None,
             tree,
         }
     }
 
+    /// Builder-style method for setting the color used for `currentColor`.
+    ///
+    /// The argument can be either a `Color` or a [`Key<Color>`].
+    ///
+    /// [`Key<Color>`]: crate::Key
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    /// Set the color used for `currentColor`.
+    ///
+    /// The argument can be either a `Color` or a [`Key<Color>`].
+    ///
+    /// [`Key<Color>`]: crate::Key
+    pub fn set_color(&mut self, color: impl Into<KeyOrValue<Color>>) {
+        self.color = Some(color.into());
+    }
+
+    /// Clear the color, drawing `currentColor` as black.
+    pub fn clear_color(&mut self) {
+        self.color = None;
+    }
+
     /// Rasterize the SVG into the specified size in pixels.
-    fn render(&self, size_px: Size) -> Option<ImageBuf> {
+    fn render(&self, size_px: Size, color: Option<Color>) -> Option<ImageBuf> {
         let fit = usvg::FitTo::Size(size_px.width as u32, size_px.height as u32);
         let mut pixmap =
             tiny_skia::Pixmap::new(size_px.width as u32, size_px.height as u32).unwrap();
 
+        let recolored = color.map(|color| recolor(&self.tree, color));
         if resvg::render(
-            &self.tree,
+            recolored.as_ref().unwrap_or(&self.tree),
             fit,
             tiny_skia::Transform::identity(),
             pixmap.as_mut(),
@@ -70,12 +135,48 @@ impl Svg {
     }
 }
 
+/// Return a copy of `tree` with `currentColor` fills and strokes replaced by `color`.
+fn recolor(tree: &Tree, color: Color) -> Tree {
+    let (red, green, blue, alpha) = color.as_rgba8();
+    let replacement = usvg::Color { red, green, blue };
+    let alpha = alpha as f64 / 255.0;
+    let replace = |paint: &mut Paint, opacity: &mut usvg::Opacity| {
+        if matches!(paint, Paint::Color(c) if *c == CURRENT_COLOR) {
+            *paint = Paint::Color(replacement);
+            *opacity = usvg::Opacity::new(opacity.get() * alpha).unwrap_or(*opacity);
+        }
+    };
+
+    let root = tree.root.make_deep_copy();
+    for node in root.descendants() {
+        if let NodeKind::Path(ref mut path) = *node.borrow_mut() {
+            if let Some(fill) = &mut path.fill {
+                replace(&mut fill.paint, &mut fill.opacity);
+            }
+            if let Some(stroke) = &mut path.stroke {
+                replace(&mut stroke.paint, &mut stroke.opacity);
+            }
+        }
+    }
+    Tree {
+        size: tree.size,
+        view_box: tree.view_box,
+        root,
+    }
+}
+
 impl<T: Data> Widget<T> for Svg {
     fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
 
     fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
 
-    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {}
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {
+        if let Some(color) = &self.color {
+            if ctx.env_key_changed(color) {
+                ctx.request_paint();
+            }
+        }
+    }
 
     fn layout(
         &mut self,
@@ -89,19 +190,22 @@ impl<T: Data> Widget<T> for Svg {
         bc.constrain_aspect_ratio(size.height / size.width, size.width)
     }
 
-    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, _env: &Env) {
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         let size = ctx.size();
         let area = ScaledArea::from_dp(size, ctx.scale());
         let size_px = area.size_px();
+        let color = self.color.as_ref().map(|color| color.resolve(env));
 
         let needs_render = self
             .cached
             .as_ref()
             .filter(|image_buf| image_buf.size() == size_px)
-            .is_none();
+            .is_none()
+            || self.cached_color != color;
 
         if needs_render {
-            self.cached = self.render(size_px);
+            self.cached = self.render(size_px, color);
+            self.cached_color = color;
         }
 
         if self.cached.is_none() {
@@ -152,13 +256,40 @@ impl std::str::FromStr for SvgData {
             ..usvg::Options::default()
         };
 
-        match Tree::from_str(svg_str, &re_opt.to_ref()) {
+        let svg_str = with_current_color(svg_str)?;
+        match Tree::from_str(&svg_str, &re_opt.to_ref()) {
             Ok(tree) => Ok(SvgData::new(Arc::new(tree))),
             Err(err) => Err(err.into()),
         }
     }
 }
 
+/// Give the root element of `svg_str` a `color` of [`CURRENT_COLOR`], unless it already
+/// has one.
+fn with_current_color(svg_str: &str) -> Result<String, roxmltree::Error> {
+    let options = roxmltree::ParsingOptions { allow_dtd: true };
+    let doc = roxmltree::Document::parse_with_options(svg_str, options)?;
+    let root = doc.root_element();
+    if root.has_attribute("color") {
+        return Ok(svg_str.to_owned());
+    }
+
+    // Insert the attribute right after the tag name.
+    let start = root.range().start;
+    let name_end = svg_str[start..]
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .map(|offset| start + offset)
+        .unwrap_or(svg_str.len());
+    Ok(format!(
+        "{} color=\"#{:02x}{:02x}{:02x}\"{}",
+        &svg_str[..name_end],
+        CURRENT_COLOR.red,
+        CURRENT_COLOR.green,
+        CURRENT_COLOR.blue,
+        &svg_str[name_end..]
+    ))
+}
+
 impl From<SvgData> for Arc<Tree> {
     fn from(d: SvgData) -> Self {
         d.tree
@@ -170,3 +301,41 @@ impl Default for SvgData {
         SvgData::empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill_color(tree: &Tree) -> Option<usvg::Color> {
+        tree.root
+            .descendants()
+            .find_map(|node| match &*node.borrow() {
+                NodeKind::Path(path) => match path.fill.as_ref()?.paint {
+                    Paint::Color(color) => Some(color),
+                    _ => None,
+                },
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn recolor_current_color() {
+        let svg: SvgData = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+                <rect width="10" height="10" fill="currentColor"/>
+            </svg>"#
+            .parse()
+            .unwrap();
+        let tree = &svg.tree;
+        assert_eq!(fill_color(tree), Some(CURRENT_COLOR));
+
+        let recolored = recolor(tree, Color::rgb8(0x20, 0x40, 0x60));
+        let expected = usvg::Color {
+            red: 0x20,
+            green: 0x40,
+            blue: 0x60,
+        };
+        assert_eq!(fill_color(&recolored), Some(expected));
+        // The original tree is left alone.
+        assert_eq!(fill_color(tree), Some(CURRENT_COLOR));
+    }
+}
@@ -142,7 +142,11 @@ impl Widget<bool> for Switch {
                 let delta = Duration::from_nanos(*interval).as_secs_f64();
 
                 // move knob to right position depending on the value
-                if self.animation_in_progress {
+                if self.animation_in_progress && env.get(theme::REDUCED_MOTION) {
+                    self.knob_pos.x = if *data { on_pos } else { off_pos };
+                    self.animation_in_progress = false;
+                    ctx.request_paint();
+                } else if self.animation_in_progress {
                     let change_time = if *data {
                         SWITCH_CHANGE_TIME
                     } else {
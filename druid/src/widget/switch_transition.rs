@@ -0,0 +1,202 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Animated transitions between the children of switching widgets.
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::widget::prelude::*;
+use crate::{theme, Affine};
+
+/// A closure that paints a frame of a [`SwitchTransition::Custom`] transition.
+pub type TransitionPainter = dyn Fn(&mut PaintCtx, &mut TransitionFrame, &Env);
+
+/// How [`Either`] and [`ViewSwitcher`] animate from one child to the next.
+///
+/// While a transition runs, the children get no mouse or keyboard input.
+/// Transitions are skipped when [`theme::REDUCED_MOTION`] is set.
+///
+/// [`Either`]: crate::widget::Either
+/// [`ViewSwitcher`]: crate::widget::ViewSwitcher
+/// [`theme::REDUCED_MOTION`]: crate::theme::REDUCED_MOTION
+#[derive(Clone, Default)]
+pub enum SwitchTransition {
+    /// Switch instantly, without an animation.
+    #[default]
+    Instant,
+    /// Fade the old child out and the new child in, through
+    /// [`theme::WINDOW_BACKGROUND_COLOR`].
+    ///
+    /// [`theme::WINDOW_BACKGROUND_COLOR`]: crate::theme::WINDOW_BACKGROUND_COLOR
+    Crossfade(Duration),
+    /// Slide the old child out to the left, and the new child in from the right.
+    SlideLeft(Duration),
+    /// Slide the old child out to the right, and the new child in from the left.
+    SlideRight(Duration),
+    /// Paint every frame of the transition with a closure.
+    Custom(Duration, Rc<TransitionPainter>),
+}
+
+/// A frame of a running [`SwitchTransition`].
+pub struct TransitionFrame<'a> {
+    progress: f64,
+    paint_child: &'a mut dyn FnMut(&mut PaintCtx, bool),
+}
+
+/// The animation state of a running [`SwitchTransition`].
+pub(crate) struct TransitionState {
+    elapsed: u64,
+    duration: u64,
+}
+
+impl SwitchTransition {
+    /// A transition that paints every frame with `painter`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use druid::widget::SwitchTransition;
+    /// use druid::{Affine, RenderContext};
+    ///
+    /// // The new child drops in from the top, over the old one.
+    /// let drop_in = SwitchTransition::custom(Duration::from_millis(200), |ctx, frame, _env| {
+    ///     let height = ctx.size().height;
+    ///     frame.paint_old(ctx);
+    ///     let offset = (frame.progress() - 1.0) * height;
+    ///     ctx.with_save(|ctx| {
+    ///         ctx.transform(Affine::translate((0.0, offset)));
+    ///         frame.paint_new(ctx);
+    ///     });
+    /// });
+    /// ```
+    pub fn custom(
+        duration: Duration,
+        painter: impl Fn(&mut PaintCtx, &mut TransitionFrame, &Env) + 'static,
+    ) -> Self {
+        SwitchTransition::Custom(duration, Rc::new(painter))
+    }
+
+    /// How long the transition runs.
+    pub fn duration(&self) -> Duration {
+        match self {
+            SwitchTransition::Instant => Duration::ZERO,
+            SwitchTransition::Crossfade(duration)
+            | SwitchTransition::SlideLeft(duration)
+            | SwitchTransition::SlideRight(duration)
+            | SwitchTransition::Custom(duration, _) => *duration,
+        }
+    }
+
+    /// Start the transition, unless it's instant or motion is reduced.
+    pub(crate) fn start(&self, ctx: &mut UpdateCtx, env: &Env) -> Option<TransitionState> {
+        let duration = self.duration().as_nanos() as u64;
+        if duration == 0 || env.get(theme::REDUCED_MOTION) {
+            return None;
+        }
+        ctx.request_anim_frame();
+        Some(TransitionState {
+            elapsed: 0,
+            duration,
+        })
+    }
+
+    /// Paint a frame of the transition.
+    ///
+    /// `paint_child` paints the new child if its argument is `true`, and the old child otherwise.
+    pub(crate) fn paint(
+        &self,
+        ctx: &mut PaintCtx,
+        state: &TransitionState,
+        paint_child: &mut dyn FnMut(&mut PaintCtx, bool),
+        env: &Env,
+    ) {
+        let mut frame = TransitionFrame {
+            progress: state.progress(),
+            paint_child,
+        };
+        let size = ctx.size();
+        let t = frame.eased_progress();
+        match self {
+            SwitchTransition::Instant => frame.paint_new(ctx),
+            SwitchTransition::Crossfade(_) => {
+                let fade = if t < 0.5 {
+                    frame.paint_old(ctx);
+                    t * 2.0
+                } else {
+                    frame.paint_new(ctx);
+                    (1.0 - t) * 2.0
+                };
+                let background = env.get(theme::WINDOW_BACKGROUND_COLOR);
+                ctx.fill(size.to_rect(), &background.with_alpha(fade));
+            }
+            SwitchTransition::SlideLeft(_) | SwitchTransition::SlideRight(_) => {
+                let direction = match self {
+                    SwitchTransition::SlideLeft(_) => -1.0,
+                    _ => 1.0,
+                };
+                ctx.clip(size.to_rect());
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate((direction * t * size.width, 0.0)));
+                    frame.paint_old(ctx);
+                });
+                ctx.with_save(|ctx| {
+                    ctx.transform(Affine::translate((
+                        -direction * (1.0 - t) * size.width,
+                        0.0,
+                    )));
+                    frame.paint_new(ctx);
+                });
+            }
+            SwitchTransition::Custom(_, painter) => painter(ctx, &mut frame, env),
+        }
+    }
+}
+
+impl TransitionFrame<'_> {
+    /// How far the transition is, from `0.0` at the start to `1.0` at the end.
+    pub fn progress(&self) -> f64 {
+        self.progress
+    }
+
+    /// The progress, eased in and out.
+    pub fn eased_progress(&self) -> f64 {
+        let t = self.progress;
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    /// Paint the child that is being switched away from.
+    pub fn paint_old(&mut self, ctx: &mut PaintCtx) {
+        (self.paint_child)(ctx, false);
+    }
+
+    /// Paint the child that is being switched to.
+    pub fn paint_new(&mut self, ctx: &mut PaintCtx) {
+        (self.paint_child)(ctx, true);
+    }
+}
+
+impl TransitionState {
+    /// Advance the transition on an [`Event::AnimFrame`], and return whether it's still running.
+    pub(crate) fn advance(&mut self, interval: u64) -> bool {
+        // We can get a high interval on the first frame due to other widgets or old animations.
+        self.elapsed += if self.elapsed == 0 { 1 } else { interval };
+        self.elapsed < self.duration
+    }
+
+    fn progress(&self) -> f64 {
+        (self.elapsed as f64 / self.duration as f64).min(1.0)
+    }
+}
@@ -34,6 +34,11 @@ type TabBarPod<TP> = WidgetPod<TabsState<TP>, Box<dyn Widget<TabsState<TP>>>>;
 type TabIndex = usize;
 type Nanos = u64;
 
+/// How far a tab label has to be dragged before it starts moving.
+const TAB_DRAG_THRESHOLD: f64 = 4.0;
+/// Roughly how long it takes tab labels to move out of the way of a dragged tab.
+const TAB_SHIFT_NANOS: f64 = 100_000_000.;
+
 /// Information about a tab that may be used by the TabPolicy to
 /// drive the visual presentation and behaviour of its label
 pub struct TabInfo<Input> {
@@ -91,6 +96,16 @@ pub trait TabsPolicy: Data {
     /// For this tab key, return the body widget
     fn tab_body(&self, key: Self::Key, data: &Self::Input) -> Self::BodyWidget;
 
+    /// Can [`tab_body`] be called more than once for the same key?
+    ///
+    /// Policies returning `false` here can not drop and rebuild bodies, so
+    /// [`TabsLoading::Unload`] falls back to [`TabsLoading::Lazy`] for them.
+    ///
+    /// [`tab_body`]: TabsPolicy::tab_body
+    fn can_rebuild_body(&self) -> bool {
+        true
+    }
+
     /// Label widget for the tab.
     /// Usually implemented with a call to default_make_label ( can't default here because Self::LabelWidget isn't determined)
     fn tab_label(
@@ -104,6 +119,21 @@ pub trait TabsPolicy: Data {
     #[allow(unused_variables)]
     fn close_tab(&self, key: Self::Key, data: &mut Self::Input) {}
 
+    /// Can the user reorder this tab by dragging its label?
+    ///
+    /// Policies returning `true` here must also implement [`move_tab`].
+    ///
+    /// [`move_tab`]: TabsPolicy::move_tab
+    #[allow(unused_variables)]
+    fn can_move_tab(&self, key: Self::Key, data: &Self::Input) -> bool {
+        false
+    }
+
+    /// Change the data to reflect the user dragging a tab to the (zero-based)
+    /// index `to`, where `to` is the position of the tab once it has been moved.
+    #[allow(unused_variables)]
+    fn move_tab(&self, key: Self::Key, to: usize, data: &mut Self::Input) {}
+
     #[allow(unused_variables)]
     /// Construct an instance of this TabsFromData from its Build type.
     /// The main use case for this is StaticTabs, where the tabs are provided by the app developer up front.
@@ -177,6 +207,10 @@ impl<T: Data> TabsPolicy for StaticTabs<T> {
             .expect("StaticTabs body widget can only be retrieved once")
     }
 
+    fn can_rebuild_body(&self) -> bool {
+        false
+    }
+
     fn tab_label(
         &self,
         _key: Self::Key,
@@ -234,12 +268,27 @@ impl<TP: TabsPolicy> TabsState<TP> {
     }
 }
 
+/// A tab label that is being dragged in the tab bar.
+struct TabDrag {
+    idx: TabIndex,
+    /// Where the drag started, along the major axis.
+    start: f64,
+    /// How far the label has been dragged along the major axis.
+    offset: f64,
+    /// Whether the drag has moved past the threshold.
+    moved: bool,
+}
+
 /// This widget is the tab bar. It contains widgets that when pressed switch the active tab.
 struct TabBar<TP: TabsPolicy> {
     axis: Axis,
     edge: TabsEdge,
     tabs: Vec<(TP::Key, TabBarPod<TP>)>,
     hot: Option<TabIndex>,
+    drag: Option<TabDrag>,
+    /// The current offset of each label along the major axis, while they
+    /// are moving out of the way of a dragged label.
+    shifts: Vec<f64>,
     phantom_tp: PhantomData<TP>,
 }
 
@@ -251,6 +300,8 @@ impl<TP: TabsPolicy> TabBar<TP> {
             edge,
             tabs: vec![],
             hot: None,
+            drag: None,
+            shifts: vec![],
             phantom_tp: Default::default(),
         }
     }
@@ -272,7 +323,49 @@ impl<TP: TabsPolicy> TabBar<TP> {
         }
     }
 
+    /// The index the dragged tab would be moved to if it was dropped now.
+    fn drag_target(&self, drag: &TabDrag) -> TabIndex {
+        let axis = self.axis;
+        let center = |tab: &TabBarPod<TP>| axis.major_pos(tab.layout_rect().center());
+        let dragged = center(&self.tabs[drag.idx].1) + drag.offset;
+        self.tabs
+            .iter()
+            .enumerate()
+            .filter(|(idx, (_, tab))| *idx != drag.idx && center(tab) < dragged)
+            .count()
+    }
+
+    /// Where each label should be while the current drag is in progress.
+    fn target_shifts(&self) -> Vec<f64> {
+        let mut shifts = vec![0.; self.tabs.len()];
+        if let Some(drag) = self.drag.as_ref().filter(|drag| drag.moved) {
+            let target = self.drag_target(drag);
+            let width = self.axis.major(self.tabs[drag.idx].1.layout_rect().size());
+            for (idx, shift) in shifts.iter_mut().enumerate() {
+                if drag.idx < idx && idx <= target {
+                    *shift = -width;
+                } else if target <= idx && idx < drag.idx {
+                    *shift = width;
+                }
+            }
+        }
+        shifts
+    }
+
+    fn finish_drag(&mut self, data: &mut TabsState<TP>) {
+        if let Some(drag) = self.drag.take() {
+            let target = self.drag_target(&drag);
+            if drag.moved && target != drag.idx {
+                let key = self.tabs[drag.idx].0.clone();
+                data.policy.move_tab(key, target, &mut data.inner);
+                data.selected = target;
+            }
+        }
+        self.shifts = vec![0.; self.tabs.len()];
+    }
+
     fn ensure_tabs(&mut self, data: &TabsState<TP>) {
+        self.drag = None;
         ensure_for_tabs(&mut self.tabs, &data.policy, &data.inner, |policy, key| {
             let info = policy.tab_info(key.clone(), &data.inner);
 
@@ -325,7 +418,14 @@ impl<TP: TabsPolicy> TabBar<TP> {
                     .with_child(label)
                     .with_child(close_button.on_click(
                         move |_ctx, data: &mut TabsState<TP>, _env| {
+                            // Keep the same tab selected, if it is still there.
+                            let selected =
+                                data.policy.tabs(&data.inner).get(data.selected).cloned();
                             data.policy.close_tab(key.clone(), &mut data.inner);
+                            let tabs = data.policy.tabs(&data.inner);
+                            data.selected = selected
+                                .and_then(|selected| tabs.iter().position(|k| *k == selected))
+                                .unwrap_or_else(|| data.selected.min(tabs.len().saturating_sub(1)));
                         },
                     ));
                 WidgetPod::new(Box::new(row))
@@ -333,6 +433,7 @@ impl<TP: TabsPolicy> TabBar<TP> {
                 WidgetPod::new(Box::new(label))
             }
         });
+        self.shifts = vec![0.; self.tabs.len()];
     }
 }
 
@@ -343,10 +444,30 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
             Event::MouseDown(e) => {
                 if let Some(idx) = self.find_idx(e.pos) {
                     data.selected = idx;
+                    if data
+                        .policy
+                        .can_move_tab(self.tabs[idx].0.clone(), &data.inner)
+                    {
+                        self.drag = Some(TabDrag {
+                            idx,
+                            start: self.axis.major_pos(e.pos),
+                            offset: 0.,
+                            moved: false,
+                        });
+                        ctx.set_active(true);
+                    }
                 }
             }
             Event::MouseMove(e) => {
-                let new_hot = if ctx.is_hot() {
+                if let Some(drag) = &mut self.drag {
+                    drag.offset = self.axis.major_pos(e.pos) - drag.start;
+                    if drag.offset.abs() > TAB_DRAG_THRESHOLD {
+                        drag.moved = true;
+                    }
+                    ctx.request_anim_frame();
+                    ctx.request_paint();
+                }
+                let new_hot = if ctx.is_hot() && self.drag.is_none() {
                     self.find_idx(e.pos)
                 } else {
                     None
@@ -356,6 +477,28 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
                     ctx.request_paint();
                 }
             }
+            Event::MouseUp(_) if self.drag.is_some() => {
+                ctx.set_active(false);
+                self.finish_drag(data);
+                ctx.request_paint();
+            }
+            Event::AnimFrame(interval) => {
+                let step = (*interval as f64 / TAB_SHIFT_NANOS).min(1.);
+                let mut moving = false;
+                let targets = self.target_shifts();
+                for (shift, target) in self.shifts.iter_mut().zip(targets) {
+                    *shift += (target - *shift) * step;
+                    if (target - *shift).abs() < 0.5 {
+                        *shift = target;
+                    } else {
+                        moving = true;
+                    }
+                }
+                if moving {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+            }
             _ => {}
         }
 
@@ -428,39 +571,57 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabBar<TP> {
     fn paint(&mut self, ctx: &mut PaintCtx, data: &TabsState<TP>, env: &Env) {
         let hl_thickness = 2.;
         let highlight = env.get(theme::PRIMARY_LIGHT);
-        for (idx, (_, tab)) in self.tabs.iter_mut().enumerate() {
-            let layout_rect = tab.layout_rect();
-            let expanded_size = self.axis.pack(
-                self.axis.major(layout_rect.size()),
-                self.axis.minor(ctx.size()),
-            );
-            let rect = layout_rect.with_size(expanded_size);
-            let bg = match (idx == data.selected, Some(idx) == self.hot) {
-                (_, true) => env.get(theme::BUTTON_DARK),
-                (true, false) => env.get(theme::BACKGROUND_LIGHT),
-                _ => env.get(theme::BACKGROUND_DARK),
+        let axis = self.axis;
+        let edge = self.edge;
+        let hot = self.hot;
+
+        // The dragged tab is painted last, so that it is on top.
+        let dragged = self.drag.as_ref().filter(|drag| drag.moved);
+        let order = (0..self.tabs.len())
+            .filter(|idx| dragged.map(|drag| drag.idx) != Some(*idx))
+            .chain(dragged.map(|drag| drag.idx))
+            .collect::<Vec<_>>();
+        let dragged = dragged.map(|drag| (drag.idx, drag.offset));
+
+        for idx in order {
+            let offset = match dragged {
+                Some((dragged_idx, offset)) if dragged_idx == idx => offset,
+                _ => self.shifts.get(idx).copied().unwrap_or(0.),
             };
-            ctx.fill(rect, &bg);
-
-            tab.paint(ctx, data, env);
-            if idx == data.selected {
-                let (maj_near, maj_far) = self.axis.major_span(rect);
-                let (min_near, min_far) = self.axis.minor_span(rect);
-                let minor_pos = if let TabsEdge::Trailing = self.edge {
-                    min_near + (hl_thickness / 2.)
-                } else {
-                    min_far - (hl_thickness / 2.)
+            let tab = &mut self.tabs[idx].1;
+            ctx.with_save(|ctx| {
+                ctx.transform(Affine::translate(axis.pack(offset, 0.)));
+                let layout_rect = tab.layout_rect();
+                let expanded_size =
+                    axis.pack(axis.major(layout_rect.size()), axis.minor(ctx.size()));
+                let rect = layout_rect.with_size(expanded_size);
+                let bg = match (idx == data.selected, Some(idx) == hot) {
+                    (_, true) => env.get(theme::BUTTON_DARK),
+                    (true, false) => env.get(theme::BACKGROUND_LIGHT),
+                    _ => env.get(theme::BACKGROUND_DARK),
                 };
+                ctx.fill(rect, &bg);
+
+                tab.paint(ctx, data, env);
+                if idx == data.selected {
+                    let (maj_near, maj_far) = axis.major_span(rect);
+                    let (min_near, min_far) = axis.minor_span(rect);
+                    let minor_pos = if let TabsEdge::Trailing = edge {
+                        min_near + (hl_thickness / 2.)
+                    } else {
+                        min_far - (hl_thickness / 2.)
+                    };
 
-                ctx.stroke(
-                    Line::new(
-                        self.axis.pack(maj_near, minor_pos),
-                        self.axis.pack(maj_far, minor_pos),
-                    ),
-                    &highlight,
-                    hl_thickness,
-                )
-            }
+                    ctx.stroke(
+                        Line::new(
+                            axis.pack(maj_near, minor_pos),
+                            axis.pack(maj_far, minor_pos),
+                        ),
+                        &highlight,
+                        hl_thickness,
+                    )
+                }
+            });
         }
     }
 }
@@ -533,47 +694,84 @@ fn ensure_for_tabs<Content, TP: TabsPolicy + ?Sized>(
 /// This widget is the tabs body. It shows the active tab, keeps other tabs hidden, and can
 /// animate transitions between them.
 struct TabsBody<TP: TabsPolicy> {
-    children: Vec<(TP::Key, TabBodyPod<TP>)>,
+    /// The body of each tab; `None` if it has not been built yet, or was unloaded.
+    children: Vec<(TP::Key, Option<TabBodyPod<TP>>)>,
     axis: Axis,
     transition: TabsTransition,
     transition_state: Option<TabsTransitionState>,
+    loading: TabsLoading,
     phantom_tp: PhantomData<TP>,
 }
 
 impl<TP: TabsPolicy> TabsBody<TP> {
-    fn new(axis: Axis, transition: TabsTransition) -> TabsBody<TP> {
+    fn new(axis: Axis, transition: TabsTransition, loading: TabsLoading) -> TabsBody<TP> {
         TabsBody {
             children: vec![],
             axis,
             transition,
             transition_state: None,
+            loading,
             phantom_tp: Default::default(),
         }
     }
 
     fn make_tabs(&mut self, data: &TabsState<TP>) -> Vec<usize> {
+        let eager = self.loading == TabsLoading::Eager;
         ensure_for_tabs(
             &mut self.children,
             &data.policy,
             &data.inner,
-            |policy, key| WidgetPod::new(policy.tab_body(key, &data.inner)),
+            |policy, key| eager.then(|| WidgetPod::new(policy.tab_body(key, &data.inner))),
         )
     }
 
+    /// Build the body of the selected tab, if it doesn't exist yet.
+    ///
+    /// Returns `true` if a new body was built.
+    fn load_selected(&mut self, data: &TabsState<TP>) -> bool {
+        match self.children.get_mut(data.selected) {
+            Some((key, body @ None)) => {
+                trace!("Building body for tab {}", data.selected);
+                *body = Some(WidgetPod::new(
+                    data.policy.tab_body(key.clone(), &data.inner),
+                ));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Drop the bodies of all tabs but the selected one, if configured to do so.
+    ///
+    /// Returns `true` if any bodies were dropped.
+    fn unload_hidden(&mut self, selected: TabIndex) -> bool {
+        if self.loading != TabsLoading::Unload || self.transition_state.is_some() {
+            return false;
+        }
+        let mut unloaded = false;
+        for (idx, (_, body)) in self.children.iter_mut().enumerate() {
+            if idx != selected && body.is_some() {
+                *body = None;
+                unloaded = true;
+            }
+        }
+        unloaded
+    }
+
     fn active_child(&mut self, state: &TabsState<TP>) -> Option<&mut TabBodyPod<TP>> {
         Self::child(&mut self.children, state.selected)
     }
 
     // Doesn't take self to allow separate borrowing
     fn child(
-        children: &mut [(TP::Key, TabBodyPod<TP>)],
+        children: &mut [(TP::Key, Option<TabBodyPod<TP>>)],
         idx: usize,
     ) -> Option<&mut TabBodyPod<TP>> {
-        children.get_mut(idx).map(|x| &mut x.1)
+        children.get_mut(idx).and_then(|x| x.1.as_mut())
     }
 
     fn child_pods(&mut self) -> impl Iterator<Item = &mut TabBodyPod<TP>> {
-        self.children.iter_mut().map(|x| &mut x.1)
+        self.children.iter_mut().filter_map(|x| x.1.as_mut())
     }
 }
 
@@ -608,6 +806,9 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabsBody<TP> {
                 ctx.request_anim_frame();
             } else {
                 self.transition_state = None;
+                if self.unload_hidden(data.selected) {
+                    ctx.children_changed();
+                }
             }
             ctx.request_paint();
         }
@@ -623,6 +824,7 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabsBody<TP> {
     ) {
         if let LifeCycle::WidgetAdded = event {
             self.make_tabs(data);
+            self.load_selected(data);
             ctx.children_changed();
         }
 
@@ -656,9 +858,19 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabsBody<TP> {
         };
 
         if old_data.selected != data.selected {
-            self.transition_state = self
-                .transition
-                .tab_changed(old_data.selected, data.selected);
+            // Moving the selected tab changes its index, but should not animate.
+            let old_key = old_data
+                .policy
+                .tabs(&old_data.inner)
+                .get(old_data.selected)
+                .cloned();
+            let key = data.policy.tabs(&data.inner).get(data.selected).cloned();
+            self.transition_state = if old_key == key || env.get(theme::REDUCED_MOTION) {
+                None
+            } else {
+                self.transition
+                    .tab_changed(old_data.selected, data.selected)
+            };
             ctx.children_changed();
 
             if self.transition_state.is_some() {
@@ -666,16 +878,27 @@ impl<TP: TabsPolicy> Widget<TabsState<TP>> for TabsBody<TP> {
             }
         }
 
+        // Bodies built here haven't been added yet, so they must not be updated.
+        let loaded = self.load_selected(data).then_some(data.selected);
+        if loaded.is_some() || self.unload_hidden(data.selected) {
+            ctx.children_changed();
+        }
+
         // Make sure to only pass events to initialised children
         if let Some(init) = init {
             for idx in init {
+                if Some(idx) == loaded {
+                    continue;
+                }
                 if let Some(child) = Self::child(&mut self.children, idx) {
                     child.update(ctx, &data.inner, env)
                 }
             }
         } else {
-            for child in self.child_pods() {
-                child.update(ctx, &data.inner, env);
+            for (idx, (_, child)) in self.children.iter_mut().enumerate() {
+                if let (Some(child), false) = (child, Some(idx) == loaded) {
+                    child.update(ctx, &data.inner, env);
+                }
             }
         }
     }
@@ -777,6 +1000,24 @@ impl TabsTransition {
     }
 }
 
+/// Determines when the body widgets of the tabs are built.
+#[derive(Data, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TabsLoading {
+    /// Build the bodies of all tabs up front.
+    #[default]
+    Eager,
+    /// Build the body of each tab the first time it is selected, and keep it afterwards.
+    Lazy,
+    /// Build the body of each tab when it is selected, and drop it again when another
+    /// tab is selected.
+    ///
+    /// This requires a [`TabsPolicy`] that can build a body more than once; for other
+    /// policies, such as tabs added through [`Tabs::with_tab`], this behaves like [`Lazy`].
+    ///
+    /// [`Lazy`]: TabsLoading::Lazy
+    Unload,
+}
+
 /// Determines where the tab bar should be placed relative to the cross axis
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Data)]
 pub enum TabsEdge {
@@ -850,6 +1091,7 @@ pub struct Tabs<TP: TabsPolicy> {
     axis: Axis,
     edge: TabsEdge,
     transition: TabsTransition,
+    loading: TabsLoading,
     content: TabsContent<TP>,
 }
 
@@ -873,6 +1115,7 @@ impl<TP: TabsPolicy> Tabs<TP> {
             axis: Axis::Horizontal,
             edge: Default::default(),
             transition: Default::default(),
+            loading: Default::default(),
             content,
         }
     }
@@ -914,6 +1157,15 @@ impl<TP: TabsPolicy> Tabs<TP> {
         self
     }
 
+    /// Control when the body widgets of the tabs are built.
+    ///
+    /// By default all bodies are built up front; deferring this can reduce startup time
+    /// and memory use when there are many tabs, or tabs with expensive content.
+    pub fn with_loading(mut self, loading: TabsLoading) -> Self {
+        self.loading = loading;
+        self
+    }
+
     /// Available when the policy implements AddTab - e.g StaticTabs.
     /// Return this Tabs widget with the named tab added.
     pub fn with_tab(
@@ -982,7 +1234,13 @@ impl<TP: TabsPolicy> Tabs<TP> {
 
     fn make_scope(&self, tabs_from_data: TP, idx: TabIndex) -> WidgetPod<TP::Input, TabsScope<TP>> {
         let tabs_bar = TabBar::new(self.axis, self.edge);
-        let tabs_body = TabsBody::new(self.axis, self.transition)
+        let loading = if self.loading == TabsLoading::Unload && !tabs_from_data.can_rebuild_body() {
+            tracing::warn!("Tabs policy can not rebuild bodies, using TabsLoading::Lazy");
+            TabsLoading::Lazy
+        } else {
+            self.loading
+        };
+        let tabs_body = TabsBody::new(self.axis, self.transition, loading)
             .padding(5.)
             .border(theme::BORDER_DARK, 0.5);
         let mut layout: Flex<TabsState<TP>> = Flex::for_axis(self.axis.cross());
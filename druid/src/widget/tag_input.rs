@@ -0,0 +1,677 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A text entry that collects a list of tags.
+
+use tracing::{instrument, trace};
+
+use crate::commands::CLOSE_WINDOW;
+use crate::debug_state::DebugState;
+use crate::im::Vector;
+use crate::kurbo::Line;
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::widget::{LabelText, TextBox, WidgetExt};
+use crate::{
+    theme, Color, InternalLifeCycle, KbKey, Point, Rect, Selector, WidgetPod, WindowConfig,
+    WindowId, WindowLevel, WindowSizePolicy,
+};
+
+/// The padding between the border and the chips.
+const INSET: f64 = 3.0;
+/// The space between chips, both horizontally and between rows.
+const CHIP_SPACING: f64 = 4.0;
+/// The horizontal padding inside a chip.
+const CHIP_PADDING: f64 = 6.0;
+/// The vertical padding inside a chip.
+const CHIP_PADDING_VERTICAL: f64 = 2.0;
+/// The width and height of the cross that removes a chip.
+const REMOVE_SIZE: f64 = 7.0;
+/// The entry moves to a row of its own when less than this is left for it.
+const MIN_ENTRY_WIDTH: f64 = 60.0;
+/// The color of the border while the entered text is rejected.
+const INVALID_COLOR: Color = Color::rgb8(0xE0, 0x4B, 0x4B);
+
+/// Sent to the suggestion popup when the suggestions or the highlighted one change.
+const SET_SUGGESTIONS: Selector<Suggestions> =
+    Selector::new("druid-builtin.tag-input-set-suggestions");
+/// Sent by the suggestion popup with the suggestion that was clicked.
+const SUGGESTION_CHOSEN: Selector<usize> =
+    Selector::new("druid-builtin.tag-input-suggestion-chosen");
+
+type Validator = Box<dyn Fn(&str, &Vector<String>) -> bool>;
+type SuggestionProvider = Box<dyn Fn(&str, &Vector<String>) -> Vec<String>>;
+
+/// A text entry that collects a list of tags, shown as removable chips.
+///
+/// Typing Enter or a comma turns the entered text into a tag, and Backspace in an empty
+/// entry removes the last tag. Tags can also be removed by clicking the cross on their
+/// chip. Leading and trailing whitespace is trimmed, and empty tags are ignored.
+///
+/// Tags can be checked with [`validate`], and completions offered with [`suggestions`];
+/// the suggestions are shown in a popup below the widget, and can be chosen with the
+/// mouse or the arrow keys and Enter.
+///
+/// # Examples
+///
+/// ```
+/// use druid::im::Vector;
+/// use druid::widget::TagInput;
+///
+/// const LANGUAGES: &[&str] = &["C", "C++", "Haskell", "Python", "Rust", "Zig"];
+///
+/// let tags = TagInput::new()
+///     .with_placeholder("Add a language")
+///     .validate(|tag, tags: &Vector<String>| !tags.iter().any(|t| t == tag))
+///     .suggestions(|text, _tags| {
+///         let text = text.to_lowercase();
+///         LANGUAGES
+///             .iter()
+///             .filter(|lang| lang.to_lowercase().starts_with(&text))
+///             .map(|lang| lang.to_string())
+///             .collect()
+///     });
+/// ```
+///
+/// [`validate`]: TagInput::validate
+/// [`suggestions`]: TagInput::suggestions
+pub struct TagInput {
+    entry: WidgetPod<String, TextBox<String>>,
+    /// The environment of the entry, which draws neither background nor border.
+    entry_env: Option<Env>,
+    /// The text of the tag being entered.
+    draft: String,
+    chips: Vec<Chip>,
+    validator: Option<Validator>,
+    suggest: Option<SuggestionProvider>,
+    suggestions: Suggestions,
+    /// The suggestion popup's window and the id of its root widget.
+    popup: Option<(WindowId, WidgetId)>,
+    hot_remove: Option<usize>,
+    pressed_remove: Option<usize>,
+    invalid: bool,
+}
+
+struct Chip {
+    text: TextLayout<String>,
+    rect: Rect,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Suggestions {
+    items: Vec<String>,
+    highlighted: Option<usize>,
+}
+
+/// The root widget of the popup listing the suggestions.
+struct SuggestionPopup {
+    tag_input: WidgetId,
+    items: Vec<TextLayout<String>>,
+    highlighted: Option<usize>,
+    hot: Option<usize>,
+    item_height: f64,
+    min_width: f64,
+}
+
+impl TagInput {
+    /// Create a new, empty `TagInput`.
+    pub fn new() -> Self {
+        TagInput {
+            entry: WidgetPod::new(TextBox::new()),
+            entry_env: None,
+            draft: String::new(),
+            chips: Vec::new(),
+            validator: None,
+            suggest: None,
+            suggestions: Suggestions::default(),
+            popup: None,
+            hot_remove: None,
+            pressed_remove: None,
+            invalid: false,
+        }
+    }
+
+    /// Builder-style method to set the placeholder text of the entry.
+    pub fn with_placeholder(mut self, placeholder: impl Into<LabelText<String>>) -> Self {
+        self.entry.widget_mut().set_placeholder(placeholder);
+        self
+    }
+
+    /// Builder-style method to provide a closure that decides whether a tag may be added.
+    ///
+    /// The closure is called with the trimmed text of the new tag and the current tags.
+    /// If it returns `false`, the text stays in the entry and the border is highlighted
+    /// until the text is edited.
+    pub fn validate(mut self, validate: impl Fn(&str, &Vector<String>) -> bool + 'static) -> Self {
+        self.validator = Some(Box::new(validate));
+        self
+    }
+
+    /// Builder-style method to provide a closure that suggests tags for the entered text.
+    ///
+    /// The closure is called with the trimmed text whenever it changes and is not empty,
+    /// along with the current tags.
+    pub fn suggestions(
+        mut self,
+        suggest: impl Fn(&str, &Vector<String>) -> Vec<String> + 'static,
+    ) -> Self {
+        self.suggest = Some(Box::new(suggest));
+        self
+    }
+
+    fn entry_env(&mut self, env: &Env) -> Env {
+        self.entry_env
+            .get_or_insert_with(|| {
+                env.clone()
+                    .adding(theme::BACKGROUND_LIGHT, Color::TRANSPARENT)
+                    .adding(theme::BORDER_DARK, Color::TRANSPARENT)
+                    .adding(theme::PRIMARY_LIGHT, Color::TRANSPARENT)
+                    .adding(theme::TEXTBOX_BORDER_WIDTH, 0.0)
+            })
+            .clone()
+    }
+
+    fn rebuild_chips(&mut self, data: &Vector<String>) {
+        self.chips.truncate(data.len());
+        for (idx, tag) in data.iter().enumerate() {
+            match self.chips.get_mut(idx) {
+                Some(chip) => chip.text.set_text(tag.clone()),
+                None => self.chips.push(Chip {
+                    text: TextLayout::from_text(tag.clone()),
+                    rect: Rect::ZERO,
+                }),
+            }
+        }
+        self.hot_remove = None;
+        self.pressed_remove = None;
+    }
+
+    fn remove_at(&self, pos: Point) -> Option<usize> {
+        self.chips
+            .iter()
+            .position(|chip| chip.remove_rect().inflate(2.0, 2.0).contains(pos))
+    }
+
+    /// Add `text` as a tag, if it passes validation.
+    fn commit(&mut self, ctx: &mut EventCtx, data: &mut Vector<String>, text: &str) {
+        let tag = text.trim();
+        if tag.is_empty() {
+            return;
+        }
+        if let Some(validator) = &self.validator {
+            if !validator(tag, data) {
+                trace!("Tag {:?} rejected", tag);
+                self.invalid = true;
+                ctx.request_paint();
+                return;
+            }
+        }
+        data.push_back(tag.to_string());
+        self.draft.clear();
+        self.invalid = false;
+        ctx.request_update();
+        self.close_popup(ctx);
+    }
+
+    fn refresh_suggestions(&mut self, ctx: &mut EventCtx, data: &Vector<String>, env: &Env) {
+        let text = self.draft.trim();
+        let items = match &self.suggest {
+            Some(suggest) if !text.is_empty() => suggest(text, data),
+            _ => Vec::new(),
+        };
+        self.suggestions = Suggestions {
+            items,
+            highlighted: None,
+        };
+        if self.suggestions.items.is_empty() {
+            self.close_popup(ctx);
+            return;
+        }
+        match self.popup {
+            Some((_, popup)) => {
+                ctx.submit_command(SET_SUGGESTIONS.with(self.suggestions.clone()).to(popup))
+            }
+            None => self.show_popup(ctx, data, env),
+        }
+    }
+
+    fn move_highlight(&mut self, ctx: &mut EventCtx, forward: bool) {
+        let len = self.suggestions.items.len();
+        let next = match (self.suggestions.highlighted, forward) {
+            (None, true) => 0,
+            (None, false) => len - 1,
+            (Some(idx), true) => (idx + 1) % len,
+            (Some(idx), false) => (idx + len - 1) % len,
+        };
+        self.suggestions.highlighted = Some(next);
+        if let Some((_, popup)) = self.popup {
+            ctx.submit_command(SET_SUGGESTIONS.with(self.suggestions.clone()).to(popup));
+        }
+    }
+
+    fn show_popup(&mut self, ctx: &mut EventCtx, data: &Vector<String>, env: &Env) {
+        let popup_id = WidgetId::next();
+        let mut popup = SuggestionPopup {
+            tag_input: ctx.widget_id(),
+            items: Vec::new(),
+            highlighted: None,
+            hot: None,
+            item_height: 0.0,
+            min_width: ctx.size().width,
+        };
+        popup.set_suggestions(&self.suggestions);
+        let position = ctx.to_window(Point::new(0.0, ctx.size().height));
+        let window = ctx.new_sub_window(
+            WindowConfig::default()
+                .show_titlebar(false)
+                .window_size_policy(WindowSizePolicy::Content)
+                .set_level(WindowLevel::DropDown(ctx.window().clone()))
+                .set_position(position),
+            WidgetExt::<Vector<String>>::with_id(popup, popup_id),
+            data.clone(),
+            env.clone(),
+        );
+        self.popup = Some((window, popup_id));
+    }
+
+    fn close_popup(&mut self, ctx: &mut EventCtx) {
+        self.suggestions = Suggestions::default();
+        if let Some((window, _)) = self.popup.take() {
+            ctx.submit_command(CLOSE_WINDOW.to(window));
+        }
+    }
+
+    fn choose_suggestion(&mut self, ctx: &mut EventCtx, data: &mut Vector<String>, idx: usize) {
+        if let Some(text) = self.suggestions.items.get(idx).cloned() {
+            self.commit(ctx, data, &text);
+        }
+    }
+}
+
+impl Default for TagInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chip {
+    fn width(text: &TextLayout<String>) -> f64 {
+        text.size().width + REMOVE_SIZE + 3.0 * CHIP_PADDING
+    }
+
+    fn remove_rect(&self) -> Rect {
+        let x1 = self.rect.x1 - CHIP_PADDING;
+        let y = self.rect.center().y;
+        Rect::new(
+            x1 - REMOVE_SIZE,
+            y - REMOVE_SIZE / 2.0,
+            x1,
+            y + REMOVE_SIZE / 2.0,
+        )
+    }
+}
+
+impl Widget<Vector<String>> for TagInput {
+    #[instrument(name = "TagInput", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Vector<String>, env: &Env) {
+        match event {
+            Event::KeyDown(key) if ctx.has_focus() => {
+                let handled = match &key.key {
+                    KbKey::Enter => {
+                        match self.suggestions.highlighted {
+                            Some(idx) => self.choose_suggestion(ctx, data, idx),
+                            None => self.commit(ctx, data, &self.draft.clone()),
+                        }
+                        true
+                    }
+                    KbKey::Character(c) if c == "," => {
+                        self.commit(ctx, data, &self.draft.clone());
+                        true
+                    }
+                    KbKey::Backspace if self.draft.is_empty() && !data.is_empty() => {
+                        data.pop_back();
+                        true
+                    }
+                    KbKey::ArrowDown | KbKey::ArrowUp if !self.suggestions.items.is_empty() => {
+                        self.move_highlight(ctx, key.key == KbKey::ArrowDown);
+                        true
+                    }
+                    KbKey::Escape if self.popup.is_some() => {
+                        self.close_popup(ctx);
+                        true
+                    }
+                    _ => false,
+                };
+                if handled {
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::Command(cmd) if cmd.is(SUGGESTION_CHOSEN) => {
+                ctx.set_handled();
+                let idx = *cmd.get_unchecked(SUGGESTION_CHOSEN);
+                self.choose_suggestion(ctx, data, idx);
+                return;
+            }
+            Event::MouseMove(mouse) => {
+                let hot = if ctx.is_hot() {
+                    self.remove_at(mouse.pos)
+                } else {
+                    None
+                };
+                if hot != self.hot_remove {
+                    self.hot_remove = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if let Some(idx) = self.remove_at(mouse.pos) {
+                    self.pressed_remove = Some(idx);
+                    ctx.set_active(true);
+                    ctx.request_paint();
+                    return;
+                }
+                if !self.entry.layout_rect().contains(mouse.pos) {
+                    ctx.set_focus(self.entry.id());
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() && ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.request_paint();
+                if let Some(idx) = self.pressed_remove.take() {
+                    if self.remove_at(mouse.pos) == Some(idx) && idx < data.len() {
+                        trace!("Tag {} removed", idx);
+                        data.remove(idx);
+                    }
+                }
+                return;
+            }
+            _ => (),
+        }
+
+        let entry_env = self.entry_env(env);
+        let old_draft = self.draft.clone();
+        self.entry.event(ctx, event, &mut self.draft, &entry_env);
+        if self.draft != old_draft {
+            self.invalid = false;
+            ctx.request_update();
+            ctx.request_paint();
+            self.refresh_suggestions(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "TagInput", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Vector<String>,
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => self.rebuild_chips(data),
+            LifeCycle::Internal(InternalLifeCycle::RouteFocusChanged { old, .. })
+                if *old == Some(self.entry.id()) =>
+            {
+                self.suggestions = Suggestions::default();
+                if let Some((window, _)) = self.popup.take() {
+                    ctx.submit_command(CLOSE_WINDOW.to(window));
+                }
+            }
+            LifeCycle::HotChanged(false) if self.hot_remove.is_some() => {
+                self.hot_remove = None;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+        let entry_env = self.entry_env(env);
+        self.entry.lifecycle(ctx, event, &self.draft, &entry_env);
+    }
+
+    #[instrument(
+        name = "TagInput",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &Vector<String>,
+        data: &Vector<String>,
+        env: &Env,
+    ) {
+        if !old_data.same(data) {
+            self.rebuild_chips(data);
+            ctx.request_layout();
+        }
+        if ctx.env_changed() {
+            self.entry_env = None;
+            if self
+                .chips
+                .iter_mut()
+                .any(|chip| chip.text.needs_rebuild_after_update(ctx))
+            {
+                ctx.request_layout();
+            }
+        }
+        let entry_env = self.entry_env(env);
+        self.entry.update(ctx, &self.draft, &entry_env);
+    }
+
+    #[instrument(name = "TagInput", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Vector<String>,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("TagInput");
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            env.get(theme::WIDE_WIDGET_WIDTH)
+        };
+        let inner_width = (width - 2.0 * INSET).max(0.0);
+
+        let mut chip_height: f64 = 0.0;
+        for chip in &mut self.chips {
+            chip.text.rebuild_if_needed(ctx.text(), env);
+            chip_height = chip_height.max(chip.text.size().height + 2.0 * CHIP_PADDING_VERTICAL);
+        }
+
+        // Place the chips in rows, starting a new row when the next one does not fit.
+        let mut row = 0;
+        let mut x = 0.0;
+        let mut positions = Vec::with_capacity(self.chips.len());
+        for chip in &self.chips {
+            let chip_width = Chip::width(&chip.text);
+            if x > 0.0 && x + chip_width > inner_width {
+                row += 1;
+                x = 0.0;
+            }
+            positions.push((row, x, chip_width));
+            x += chip_width + CHIP_SPACING;
+        }
+        // The entry takes the rest of the last row, or a row of its own.
+        if x > 0.0 && inner_width - x < MIN_ENTRY_WIDTH {
+            row += 1;
+            x = 0.0;
+        }
+
+        let entry_width = inner_width - x;
+        let entry_bc = BoxConstraints::new(
+            Size::new(entry_width, 0.0),
+            Size::new(entry_width, f64::INFINITY),
+        );
+        let entry_env = self.entry_env(env);
+        let entry_size = self.entry.layout(ctx, &entry_bc, &self.draft, &entry_env);
+        let row_height = chip_height.max(entry_size.height);
+        let row_y = |row: usize| INSET + row as f64 * (row_height + CHIP_SPACING);
+
+        for (chip, (chip_row, chip_x, chip_width)) in self.chips.iter_mut().zip(positions) {
+            let y = row_y(chip_row) + (row_height - chip_height) / 2.0;
+            chip.rect = Rect::new(
+                INSET + chip_x,
+                y,
+                INSET + chip_x + chip_width,
+                y + chip_height,
+            );
+        }
+        let entry_y = row_y(row) + (row_height - entry_size.height) / 2.0;
+        self.entry.set_origin(ctx, Point::new(INSET + x, entry_y));
+
+        let height = row_y(row) + row_height + INSET;
+        let size = bc.constrain((width, height));
+        let entry_bottom = entry_y + entry_size.height;
+        ctx.set_baseline_offset(size.height - entry_bottom + self.entry.baseline_offset());
+        trace!("Computed layout: size={}", size);
+        size
+    }
+
+    #[instrument(name = "TagInput", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &Vector<String>, env: &Env) {
+        let border_width = env.get(theme::TEXTBOX_BORDER_WIDTH);
+        let background = ctx
+            .size()
+            .to_rect()
+            .inset(-border_width / 2.0)
+            .to_rounded_rect(env.get(theme::TEXTBOX_BORDER_RADIUS));
+        ctx.fill(background, &env.get(theme::BACKGROUND_LIGHT));
+        let border_color = if self.invalid {
+            INVALID_COLOR
+        } else if ctx.has_focus() {
+            env.get(theme::FOCUS_COLOR)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.stroke(background, &border_color, border_width);
+
+        for (idx, chip) in self.chips.iter().enumerate() {
+            let rect = chip.rect;
+            ctx.fill(
+                rect.to_rounded_rect(rect.height() / 2.0),
+                &env.get(theme::BACKGROUND_DARK),
+            );
+            let text_y = rect.y0 + (rect.height() - chip.text.size().height) / 2.0;
+            chip.text.draw(ctx, (rect.x0 + CHIP_PADDING, text_y));
+
+            let cross = chip.remove_rect();
+            let color = if self.hot_remove == Some(idx) {
+                env.get(theme::TEXT_COLOR)
+            } else {
+                env.get(theme::PLACEHOLDER_COLOR)
+            };
+            ctx.stroke(
+                Line::new((cross.x0, cross.y0), (cross.x1, cross.y1)),
+                &color,
+                1.5,
+            );
+            ctx.stroke(
+                Line::new((cross.x0, cross.y1), (cross.x1, cross.y0)),
+                &color,
+                1.5,
+            );
+        }
+
+        let entry_env = self.entry_env(env);
+        self.entry.paint(ctx, &self.draft, &entry_env);
+    }
+
+    fn debug_state(&self, data: &Vector<String>) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.iter().cloned().collect::<Vec<_>>().join(", "),
+            children: vec![self.entry.widget().debug_state(&self.draft)],
+            ..Default::default()
+        }
+    }
+}
+
+impl SuggestionPopup {
+    fn set_suggestions(&mut self, suggestions: &Suggestions) {
+        self.items = suggestions
+            .items
+            .iter()
+            .map(|item| TextLayout::from_text(item.clone()))
+            .collect();
+        self.highlighted = suggestions.highlighted;
+        self.hot = None;
+    }
+
+    fn item_at(&self, pos: Point) -> Option<usize> {
+        let row = (pos.y / self.item_height).floor();
+        (row >= 0.0 && (row as usize) < self.items.len()).then_some(row as usize)
+    }
+}
+
+impl<T: Data> Widget<T> for SuggestionPopup {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(SET_SUGGESTIONS) => {
+                ctx.set_handled();
+                self.set_suggestions(cmd.get_unchecked(SET_SUGGESTIONS));
+                ctx.request_layout();
+            }
+            Event::MouseMove(mouse) => {
+                let hot = self.item_at(mouse.pos);
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() => {
+                if let Some(row) = self.item_at(mouse.pos) {
+                    ctx.submit_command(SUGGESTION_CHOSEN.with(row).to(self.tag_input));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        if let LifeCycle::HotChanged(false) = event {
+            self.hot = None;
+            ctx.request_paint();
+        }
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        let mut width = self.min_width - 2.0 * CHIP_PADDING;
+        let mut text_height: f64 = 0.0;
+        for text in &mut self.items {
+            text.rebuild_if_needed(ctx.text(), env);
+            width = width.max(text.size().width);
+            text_height = text_height.max(text.size().height);
+        }
+        self.item_height = text_height + 2.0 * CHIP_PADDING_VERTICAL;
+        let height = self.item_height * self.items.len() as f64;
+        bc.constrain((width + 2.0 * CHIP_PADDING, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let size = ctx.size();
+        ctx.fill(size.to_rect(), &env.get(theme::BACKGROUND_DARK));
+        for (row, text) in self.items.iter().enumerate() {
+            let y = row as f64 * self.item_height;
+            if self.hot == Some(row) || self.highlighted == Some(row) {
+                let rect = Rect::new(0.0, y, size.width, y + self.item_height);
+                ctx.fill(rect, &env.get(theme::PRIMARY_DARK));
+            }
+            text.draw(ctx, (CHIP_PADDING, y + CHIP_PADDING_VERTICAL));
+        }
+    }
+}
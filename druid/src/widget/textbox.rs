@@ -14,21 +14,23 @@
 
 //! A textbox widget.
 
+use std::ops::Range;
 use std::time::Duration;
 use tracing::{instrument, trace};
 
-use crate::contexts::ChangeCtx;
+use crate::contexts::{ChangeCtx, RequestCtx};
 use crate::debug_state::DebugState;
-use crate::kurbo::Insets;
+use crate::kurbo::{Affine, Insets};
 use crate::piet::TextLayout as _;
 use crate::text::{
-    EditableText, ImeInvalidation, Selection, TextComponent, TextLayout, TextStorage,
+    CandidateAnchor, EditableText, ImeInvalidation, InputFilter, RichText, Selection,
+    TextComponent, TextLayout, TextStorage,
 };
 use crate::widget::prelude::*;
-use crate::widget::{Padding, Scroll, WidgetWrapper};
+use crate::widget::{Axis, Findable, Padding, Scroll, WidgetWrapper};
 use crate::{
     theme, ArcStr, Color, Command, FontDescriptor, HotKey, KeyEvent, KeyOrValue, Point, Rect,
-    SysMods, TextAlignment, TimerToken, Vec2,
+    Selector, SysMods, TextAlignment, TimerToken, Vec2,
 };
 
 use super::LabelText;
@@ -44,6 +46,11 @@ const MAC_OR_LINUX_OR_BSD: bool = cfg!(any(
 /// When we scroll after editing or movement, we show a little extra of the document.
 const SCROLL_TO_INSETS: Insets = Insets::uniform_xy(40.0, 0.0);
 
+/// The size of a floating label, relative to the placeholder.
+const FLOATING_LABEL_SCALE: f64 = 0.75;
+/// The time it takes a floating label to move above the text, in seconds.
+const FLOATING_LABEL_DURATION: f64 = 0.15;
+
 /// A widget that allows user text input.
 ///
 /// # Editing values
@@ -53,11 +60,48 @@ const SCROLL_TO_INSETS: Insets = Insets::uniform_xy(40.0, 0.0);
 /// [`Formatter`]. You can create a [`ValueTextBox`] by passing the appropriate
 /// [`Formatter`] to [`TextBox::with_formatter`].
 ///
+/// # Placeholders
+///
+/// The placeholder is shown while the textbox is empty. It can be plain or
+/// [`RichText`], and its color can be set per textbox. In floating label mode,
+/// set with [`with_floating_label`], the placeholder stays visible as a small
+/// caption above the text once the textbox has focus or content, as in
+/// Material-style forms.
+///
+/// # Undo and redo
+///
+/// Edits can be undone and redone with the platform's usual key bindings, or
+/// with the [`UNDO`] and [`REDO`] commands, for instance from the Edit menu.
+/// Sending these commands to the textbox's id undoes or redoes programmatically,
+/// as long as the textbox has focus; the history can also be driven directly
+/// through the [`EditSession`] returned by [`TextComponent::borrow_mut`].
+///
+/// # Selection
+///
+/// The selection, whose `active` end is the caret, can be read with
+/// [`selection`] and changed with [`set_selection`], for instance from a
+/// [`Controller`] that selects everything on focus, or that restores the
+/// selection after rewriting the data. From elsewhere, the selection can be
+/// changed by sending [`TextBox::SET_SELECTION`] to the textbox's id.
+///
 /// [`Formatter`]: crate::text::Formatter
 /// [`ValueTextBox`]: super::ValueTextBox
+/// [`UNDO`]: crate::commands::UNDO
+/// [`REDO`]: crate::commands::REDO
+/// [`EditSession`]: crate::text::EditSession
+/// [`with_floating_label`]: TextBox::with_floating_label
+/// [`selection`]: TextBox::selection
+/// [`set_selection`]: TextBox::set_selection
+/// [`Controller`]: super::Controller
 pub struct TextBox<T> {
     placeholder_text: LabelText<T>,
-    placeholder_layout: TextLayout<ArcStr>,
+    /// Shown instead of `placeholder_text`, if set.
+    rich_placeholder: Option<RichText>,
+    placeholder_layout: TextLayout<RichText>,
+    floating_label: bool,
+    /// How far the floating label has moved, from 0.0 (in place of the text)
+    /// to 1.0 (above the text).
+    float_progress: f64,
     inner: Scroll<T, Padding<T, TextComponent<T>>>,
     scroll_to_selection_after_layout: bool,
     multiline: bool,
@@ -77,6 +121,15 @@ pub struct TextBox<T> {
     text_pos: Point,
 }
 
+impl TextBox<()> {
+    /// Set the selection of the textbox this is sent to.
+    ///
+    /// This works whether or not the textbox has focus; see
+    /// [`TextBox::set_selection`] for how the selection is interpreted.
+    pub const SET_SELECTION: Selector<Selection> =
+        Selector::new("druid-builtin.textbox-set-selection");
+}
+
 impl<T: EditableText + TextStorage> TextBox<T> {
     /// Create a new TextBox widget.
     ///
@@ -99,7 +152,7 @@ impl<T: EditableText + TextStorage> TextBox<T> {
         let placeholder_text = ArcStr::from("");
         let mut placeholder_layout = TextLayout::new();
         placeholder_layout.set_text_color(theme::PLACEHOLDER_COLOR);
-        placeholder_layout.set_text(placeholder_text.clone());
+        placeholder_layout.set_text(RichText::new(placeholder_text.clone()));
 
         let mut scroll = Scroll::new(Padding::new(
             theme::TEXTBOX_INSETS,
@@ -111,7 +164,10 @@ impl<T: EditableText + TextStorage> TextBox<T> {
             inner: scroll,
             scroll_to_selection_after_layout: false,
             placeholder_text: placeholder_text.into(),
+            rich_placeholder: None,
             placeholder_layout,
+            floating_label: false,
+            float_progress: 0.0,
             multiline: false,
             was_focused_from_click: false,
             cursor_on: false,
@@ -185,9 +241,16 @@ impl<T: EditableText + TextStorage> TextBox<T> {
     /// ```
     /// [`multiline`]: TextBox::multiline
     pub fn with_line_wrapping(mut self, wrap_lines: bool) -> Self {
-        self.inner.set_horizontal_scroll_enabled(!wrap_lines);
+        self.set_line_wrapping(wrap_lines);
         self
     }
+
+    /// Set whether lines are wrapped at the maximum layout width.
+    ///
+    /// See [`with_line_wrapping`](TextBox::with_line_wrapping).
+    pub(crate) fn set_line_wrapping(&mut self, wrap_lines: bool) {
+        self.inner.set_horizontal_scroll_enabled(!wrap_lines);
+    }
 }
 
 impl<T> TextBox<T> {
@@ -270,6 +333,51 @@ impl<T> TextBox<T> {
         self
     }
 
+    /// Builder-style method to set where the IME candidate window is placed
+    /// while text is being composed.
+    ///
+    /// By default it is placed against the composed text; with
+    /// [`CandidateAnchor::Caret`] it follows the caret instead.
+    pub fn with_candidate_anchor(mut self, anchor: CandidateAnchor) -> Self {
+        self.set_candidate_anchor(anchor);
+        self
+    }
+
+    /// Builder-style method for setting the placeholder color.
+    ///
+    /// The argument can be either a `Color` or a [`Key<Color>`]. The default
+    /// is [`theme::PLACEHOLDER_COLOR`].
+    ///
+    /// [`Key<Color>`]: crate::Key
+    pub fn with_placeholder_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.set_placeholder_color(color);
+        self
+    }
+
+    /// Builder-style method to turn the placeholder into a floating label.
+    ///
+    /// A floating label moves above the text, shrinking into a caption, when
+    /// the textbox gains focus or content, and moves back when it is empty
+    /// and loses focus. The textbox is made taller to leave room for it.
+    pub fn with_floating_label(mut self, floating: bool) -> Self {
+        self.floating_label = floating;
+        self
+    }
+
+    /// Builder-style method to restrict the text that can be entered.
+    ///
+    /// See [`InputFilter`] for more details. To show the text in a fixed
+    /// pattern, such as a date, use an [`InputMask`] with [`with_formatter`]
+    /// instead.
+    ///
+    /// [`InputFilter`]: crate::text::InputFilter
+    /// [`InputMask`]: crate::text::InputMask
+    /// [`with_formatter`]: TextBox::with_formatter
+    pub fn with_input_filter(mut self, filter: InputFilter) -> Self {
+        self.set_input_filter(filter);
+        self
+    }
+
     /// Builder-style method for setting the font.
     ///
     /// The argument can be a [`FontDescriptor`] or a [`Key<FontDescriptor>`]
@@ -396,6 +504,67 @@ impl<T> TextBox<T> {
         self.text_mut().borrow_mut().set_text_alignment(alignment);
     }
 
+    /// Highlight `ranges` of the text, such as the matches of a search,
+    /// replacing any earlier highlights.
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_paint`] to ensure the text box is updated.
+    ///
+    /// [`request_paint`]: EventCtx::request_paint
+    pub fn set_highlights(&mut self, ranges: Vec<Range<usize>>) {
+        if !self.text().can_write() {
+            tracing::warn!("set_highlights called with IME lock held.");
+            return;
+        }
+        self.text_mut().borrow_mut().set_highlights(ranges);
+    }
+
+    /// Set where the IME candidate window is placed while text is being composed.
+    pub fn set_candidate_anchor(&mut self, anchor: CandidateAnchor) {
+        if !self.text().can_write() {
+            tracing::warn!("set_candidate_anchor called with IME lock held.");
+            return;
+        }
+        self.text_mut().borrow_mut().set_candidate_anchor(anchor);
+    }
+
+    /// Set the placeholder color.
+    ///
+    /// The argument can be either a `Color` or a [`Key<Color>`].
+    ///
+    /// If you change this property, you are responsible for calling
+    /// [`request_layout`] to ensure the placeholder is updated.
+    ///
+    /// [`request_layout`]: EventCtx::request_layout
+    /// [`Key<Color>`]: crate::Key
+    pub fn set_placeholder_color(&mut self, color: impl Into<KeyOrValue<Color>>) {
+        self.placeholder_layout.set_text_color(color);
+    }
+
+    /// Set whether the placeholder is a floating label.
+    ///
+    /// See [`with_floating_label`] for more details. If you change this
+    /// property, you are responsible for calling [`request_layout`].
+    ///
+    /// [`with_floating_label`]: TextBox::with_floating_label
+    /// [`request_layout`]: EventCtx::request_layout
+    pub fn set_floating_label(&mut self, floating: bool) {
+        self.floating_label = floating;
+    }
+
+    /// Set the [`InputFilter`] restricting the text that can be entered.
+    ///
+    /// This does not affect the current text.
+    ///
+    /// [`InputFilter`]: crate::text::InputFilter
+    pub fn set_input_filter(&mut self, filter: InputFilter) {
+        if !self.text().can_write() {
+            tracing::warn!("set_input_filter called with IME lock held.");
+            return;
+        }
+        self.text_mut().borrow_mut().set_input_filter(filter);
+    }
+
     /// Set the text color.
     ///
     /// The argument can be either a `Color` or a [`Key<Color>`].
@@ -435,8 +604,25 @@ impl<T: Data> TextBox<T> {
     /// Set the `TextBox`'s placeholder text.
     pub fn set_placeholder(&mut self, placeholder: impl Into<LabelText<T>>) {
         self.placeholder_text = placeholder.into();
+        self.rich_placeholder = None;
         self.placeholder_layout
-            .set_text(self.placeholder_text.display_text());
+            .set_text(RichText::new(self.placeholder_text.display_text()));
+    }
+
+    /// Builder-style method to set a [`RichText`] placeholder.
+    pub fn with_rich_placeholder(mut self, placeholder: RichText) -> Self {
+        self.set_rich_placeholder(placeholder);
+        self
+    }
+
+    /// Set a [`RichText`] placeholder, replacing any plain placeholder text.
+    ///
+    /// The placeholder color applies to the parts of the text that don't
+    /// have a color attribute.
+    pub fn set_rich_placeholder(&mut self, placeholder: RichText) {
+        self.placeholder_text = ArcStr::from("").into();
+        self.placeholder_layout.set_text(placeholder.clone());
+        self.rich_placeholder = Some(placeholder);
     }
 }
 
@@ -457,6 +643,27 @@ impl<T> TextBox<T> {
         self.inner.child_mut().wrapped_mut()
     }
 
+    /// The offset of the text's scroll view.
+    pub(crate) fn scroll_offset(&self) -> Vec2 {
+        self.inner.offset()
+    }
+
+    /// The height of the floating label, if there is one.
+    fn floating_label_height(&self) -> f64 {
+        if self.floating_label {
+            self.placeholder_layout.layout_metrics().size.height * FLOATING_LABEL_SCALE
+        } else {
+            0.0
+        }
+    }
+
+    /// The insets around the text, leaving room for the floating label.
+    fn text_insets(&self, env: &Env) -> Insets {
+        let mut insets = env.get(theme::TEXTBOX_INSETS);
+        insets.y0 += self.floating_label_height();
+        insets
+    }
+
     fn reset_cursor_blink(&mut self, token: TimerToken) {
         self.cursor_on = true;
         self.cursor_timer = token;
@@ -485,6 +692,37 @@ impl<T: TextStorage + EditableText> TextBox<T> {
         Rect::new(x, y0, x, y1)
     }
 
+    /// The current selection, or `None` if the text is locked by the IME.
+    ///
+    /// The `active` end of the selection is the caret.
+    pub fn selection(&self) -> Option<Selection> {
+        if self.text().can_read() {
+            Some(self.text().borrow().selection())
+        } else {
+            None
+        }
+    }
+
+    /// Set the selection, and scroll the caret into view.
+    ///
+    /// The selection is in bytes of `data`, and is moved forward to the nearest
+    /// character boundaries inside it; `Selection::new(0, usize::MAX)` selects
+    /// all of the text.
+    pub fn set_selection(&mut self, ctx: &mut impl RequestCtx, data: &T, selection: Selection) {
+        if !self.text().can_write() {
+            tracing::warn!("set_selection called with IME lock held.");
+            return;
+        }
+        let selection = selection.constrained(data.as_str());
+        if let Some(inval) = self.text_mut().borrow_mut().set_selection(selection) {
+            ctx.invalidate_text_input(inval);
+            ctx.request_paint();
+        }
+        // the layout may not have caught up with the data yet
+        self.scroll_to_selection_after_layout = true;
+        ctx.request_layout();
+    }
+
     fn scroll_to_selection_end<C: ChangeCtx>(&mut self, ctx: &mut C) {
         let rect = self.rect_for_selection_end();
         let view_rect = self.inner.viewport_rect();
@@ -495,6 +733,25 @@ impl<T: TextStorage + EditableText> TextBox<T> {
         }
     }
 
+    /// The width of the text, or of the placeholder if there is no text,
+    /// laid out on a single line and including the textbox insets.
+    fn unwrapped_text_width(&mut self, ctx: &mut LayoutCtx, data: &T, env: &Env) -> f64 {
+        let text_width = if data.is_empty() {
+            self.placeholder_layout.rebuild_if_needed(ctx.text(), env);
+            self.placeholder_layout.layout_metrics().size.width
+        } else if self.text().can_write() {
+            // The wrap width is reset the next time the text component is laid out.
+            let mut session = self.text_mut().borrow_mut();
+            session.layout.set_wrap_width(f64::INFINITY);
+            session.layout.rebuild_if_needed(ctx.text(), env);
+            session.layout.layout_metrics().trailing_whitespace_width
+        } else {
+            tracing::warn!("Widget::compute_max_intrinsic called with outstanding IME lock.");
+            0.0
+        };
+        text_width + env.get(theme::TEXTBOX_INSETS).x_value()
+    }
+
     /// These commands may be supplied by menus; but if they aren't, we
     /// inject them again, here.
     fn fallback_do_builtin_command(
@@ -525,6 +782,39 @@ impl<T: TextStorage + EditableText> TextBox<T> {
     }
 }
 
+impl<T: TextStorage + EditableText> Findable<T> for TextBox<T> {
+    fn find_selection(&self) -> Option<Selection> {
+        self.selection()
+    }
+
+    fn find_select(&mut self, ctx: &mut EventCtx, data: &T, selection: Selection) {
+        self.set_selection(ctx, data, selection);
+    }
+
+    fn set_find_highlights(&mut self, ranges: Vec<Range<usize>>) {
+        self.set_highlights(ranges);
+    }
+
+    fn find_positions(&self, ranges: &[Range<usize>]) -> Vec<f64> {
+        if !self.text().can_read() {
+            return Vec::new();
+        }
+        let text = self.text().borrow();
+        let height = text.layout.size().height;
+        let len = text.layout.text().map_or(0, |text| text.as_str().len());
+        ranges
+            .iter()
+            .map(|range| {
+                if height <= 0.0 || range.start > len {
+                    return 0.0;
+                }
+                let top = text.layout.cursor_line_for_text_position(range.start).p0.y;
+                (top / height).clamp(0.0, 1.0)
+            })
+            .collect()
+    }
+}
+
 impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
     #[instrument(name = "TextBox", level = "trace", skip(self, ctx, event, data, env))]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
@@ -590,6 +880,32 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             Event::ImeStateChange => {
                 self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
             }
+            Event::AnimFrame(interval) if self.floating_label => {
+                let target = if ctx.is_focused() || !data.is_empty() {
+                    1.0
+                } else {
+                    0.0
+                };
+                let step = if env.get(theme::REDUCED_MOTION) {
+                    1.0
+                } else {
+                    Duration::from_nanos(*interval).as_secs_f64() / FLOATING_LABEL_DURATION
+                };
+                self.float_progress = if target > self.float_progress {
+                    (self.float_progress + step).min(target)
+                } else {
+                    (self.float_progress - step).max(target)
+                };
+                if self.float_progress != target {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+            }
+            Event::Command(cmd) if self.text().can_write() && cmd.is(TextBox::SET_SELECTION) => {
+                let selection = *cmd.get_unchecked(TextBox::SET_SELECTION);
+                self.set_selection(ctx, data, selection);
+                ctx.set_handled();
+            }
             Event::Command(ref cmd)
                 if !self.text().is_composing()
                     && ctx.is_focused()
@@ -609,6 +925,26 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                 }
                 ctx.set_handled();
             }
+            Event::Command(cmd)
+                if !self.text().is_composing()
+                    && ctx.is_focused()
+                    && cmd.is(crate::commands::UNDO) =>
+            {
+                if let Some(inval) = self.text_mut().borrow_mut().undo(data) {
+                    ctx.invalidate_text_input(inval);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd)
+                if !self.text().is_composing()
+                    && ctx.is_focused()
+                    && cmd.is(crate::commands::REDO) =>
+            {
+                if let Some(inval) = self.text_mut().borrow_mut().redo(data) {
+                    ctx.invalidate_text_input(inval);
+                }
+                ctx.set_handled();
+            }
             Event::Command(cmd)
                 if !self.text().is_composing()
                     && ctx.is_focused()
@@ -649,6 +985,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                 if matches!(event, LifeCycle::WidgetAdded) {
                     self.placeholder_text.resolve(data, env);
                 }
+                self.float_progress = if data.is_empty() { 0.0 } else { 1.0 };
                 ctx.register_text_input(self.text().input_handler());
             }
             LifeCycle::BuildFocusChain => {
@@ -684,15 +1021,21 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             }
             _ => (),
         }
+        if matches!(event, LifeCycle::FocusChanged(_)) && self.floating_label {
+            ctx.request_anim_frame();
+        }
         self.inner.lifecycle(ctx, event, data, env);
     }
 
     #[instrument(name = "TextBox", level = "trace", skip(self, ctx, old, data, env))]
     fn update(&mut self, ctx: &mut UpdateCtx, old: &T, data: &T, env: &Env) {
         let placeholder_changed = self.placeholder_text.resolve(data, env);
-        if placeholder_changed {
+        if placeholder_changed && self.rich_placeholder.is_none() {
             let new_text = self.placeholder_text.display_text();
-            self.placeholder_layout.set_text(new_text);
+            self.placeholder_layout.set_text(RichText::new(new_text));
+        }
+        if self.floating_label && old.is_empty() != data.is_empty() {
+            ctx.request_anim_frame();
         }
 
         self.inner.update(ctx, old, data, env);
@@ -718,6 +1061,8 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
         let textbox_insets = env.get(theme::TEXTBOX_INSETS);
 
         self.placeholder_layout.rebuild_if_needed(ctx.text(), env);
+        let text_insets = self.text_insets(env);
+        self.inner.child_mut().set_insets(text_insets);
         let min_size = bc.constrain((min_width, 0.0));
         let child_bc = BoxConstraints::new(min_size, bc.max());
 
@@ -762,7 +1107,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
         let is_focused = ctx.is_focused();
 
         let border_color = if is_focused {
-            env.get(theme::PRIMARY_LIGHT)
+            env.get(theme::FOCUS_COLOR)
         } else {
             env.get(theme::BORDER_DARK)
         };
@@ -775,9 +1120,11 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
 
         ctx.fill(clip_rect, &background_color);
 
+        let label_height = self.floating_label_height();
         if !data.is_empty() {
             self.inner.paint(ctx, data, env);
-        } else {
+        }
+        if data.is_empty() || self.floating_label {
             let text_width = self.placeholder_layout.layout_metrics().size.width;
             let extra_width = (size.width - text_width - textbox_insets.x_value()).max(0.);
             let alignment = self.text().borrow().text_alignment();
@@ -788,11 +1135,27 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                 x_offset_for_extra_width(alignment, extra_width)
             };
 
+            // A floating label moves from where the text goes to above it.
+            let resting = Point::new(
+                textbox_insets.x0 + x_offset,
+                textbox_insets.y0 + label_height,
+            );
+            let floating = Point::new(textbox_insets.x0, textbox_insets.y0);
+            let t = if self.floating_label {
+                // ease in and out
+                let p = self.float_progress;
+                p * p * (3.0 - 2.0 * p)
+            } else {
+                0.0
+            };
+            let origin = resting.lerp(floating, t);
+            let scale = 1.0 - (1.0 - FLOATING_LABEL_SCALE) * t;
+
             // clip when we draw the placeholder, since it isn't in a clipbox
             ctx.with_save(|ctx| {
                 ctx.clip(clip_rect);
-                self.placeholder_layout
-                    .draw(ctx, (textbox_insets.x0 + x_offset, textbox_insets.y0));
+                ctx.transform(Affine::translate(origin.to_vec2()) * Affine::scale(scale));
+                self.placeholder_layout.draw(ctx, Point::ORIGIN);
             })
         }
 
@@ -806,7 +1169,7 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
                 .borrow()
                 .cursor_line_for_text_position(cursor_pos);
 
-            let padding_offset = Vec2::new(textbox_insets.x0, textbox_insets.y0);
+            let padding_offset = Vec2::new(textbox_insets.x0, textbox_insets.y0 + label_height);
 
             let mut cursor = if data.is_empty() {
                 cursor_line + padding_offset
@@ -836,6 +1199,41 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
             ..Default::default()
         }
     }
+
+    fn compute_max_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        match axis {
+            // A textbox grows to fit its text, but is never narrower than the default width.
+            Axis::Horizontal => {
+                let width = self
+                    .unwrapped_text_width(ctx, data, env)
+                    .max(env.get(theme::WIDE_WIDGET_WIDTH));
+                bc.constrain((width, 0.0)).width
+            }
+            Axis::Vertical => self.layout(ctx, bc, data, env).height,
+        }
+    }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        match axis {
+            // Text that doesn't fit is scrolled, so the default width is always enough.
+            Axis::Horizontal => bc.constrain((env.get(theme::WIDE_WIDGET_WIDTH), 0.0)).width,
+            Axis::Vertical => self.layout(ctx, bc, data, env).height,
+        }
+    }
 }
 
 impl<T: TextStorage + EditableText> Default for TextBox<T> {
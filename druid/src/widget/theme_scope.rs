@@ -0,0 +1,220 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that applies a theme that can be switched at runtime.
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::theme::{self, Theme, SET_THEME};
+use crate::widget::prelude::*;
+use crate::widget::WidgetWrapper;
+use crate::{Color, Data, Point, WidgetPod};
+
+/// A widget that applies a [`Theme`] to its child, and switches to a new
+/// theme when it receives [`SET_THEME`].
+///
+/// With [`with_transition`], colors fade from the old theme to the new one.
+/// There is no fade when [`REDUCED_MOTION`] is set.
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::theme::{ThemePreset, SET_THEME};
+/// use druid::widget::{Button, ThemeScope};
+/// use druid::{Target, Widget};
+///
+/// fn ui() -> impl Widget<()> {
+///     let button = Button::new("Lights on").on_click(|ctx, _, _| {
+///         ctx.submit_command(SET_THEME.with(ThemePreset::Light.into()).to(Target::Global))
+///     });
+///     ThemeScope::new(ThemePreset::Dark, button).with_transition(Duration::from_millis(250))
+/// }
+/// ```
+///
+/// [`with_transition`]: ThemeScope::with_transition
+/// [`REDUCED_MOTION`]: crate::theme::REDUCED_MOTION
+pub struct ThemeScope<T, W> {
+    child: WidgetPod<T, W>,
+    theme: Theme,
+    transition: Duration,
+    /// The child's env when the theme was switched, and the time since.
+    fade: Option<(Env, Duration)>,
+    /// The env from the parent, and the env made from it for the child.
+    cached_env: Option<(Env, Env)>,
+}
+
+impl<T, W: Widget<T>> ThemeScope<T, W> {
+    /// Create a widget that applies `theme` to `child`.
+    pub fn new(theme: impl Into<Theme>, child: W) -> Self {
+        ThemeScope {
+            child: WidgetPod::new(child),
+            theme: theme.into(),
+            transition: Duration::ZERO,
+            fade: None,
+            cached_env: None,
+        }
+    }
+
+    /// Builder-style method to set how long colors take to fade to a new theme.
+    pub fn with_transition(mut self, duration: Duration) -> Self {
+        self.transition = duration;
+        self
+    }
+
+    /// The current theme.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    fn child_env(&mut self, env: &Env) -> Env {
+        if let Some((parent_env, child_env)) = &self.cached_env {
+            if parent_env.same(env) {
+                return child_env.clone();
+            }
+        }
+        let mut child_env = env.clone();
+        self.theme.apply(&mut child_env);
+        if let Some((from, elapsed)) = &self.fade {
+            let t = elapsed.as_secs_f64() / self.transition.as_secs_f64();
+            // smoothstep
+            let t = t * t * (3.0 - 2.0 * t);
+            for (key, to) in self.theme.colors() {
+                if let Ok(from) = from.try_get(key) {
+                    child_env.set(key.clone(), mix(from, *to, t));
+                }
+            }
+        }
+        self.cached_env = Some((env.clone(), child_env.clone()));
+        child_env
+    }
+
+    fn set_theme(&mut self, ctx: &mut EventCtx, theme: Theme, env: &Env) {
+        let from = self.child_env(env);
+        self.theme = theme;
+        self.fade = (!self.transition.is_zero() && !env.get(theme::REDUCED_MOTION))
+            .then_some((from, Duration::ZERO));
+        if self.fade.is_some() {
+            ctx.request_anim_frame();
+        }
+        self.cached_env = None;
+        ctx.request_update();
+        ctx.request_layout();
+    }
+}
+
+/// Interpolate between two colors.
+fn mix(from: Color, to: Color, t: f64) -> Color {
+    let (r0, g0, b0, a0) = from.as_rgba();
+    let (r1, g1, b1, a1) = to.as_rgba();
+    let lerp = |a: f64, b: f64| a + (b - a) * t;
+    Color::rgba(lerp(r0, r1), lerp(g0, g1), lerp(b0, b1), lerp(a0, a1))
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for ThemeScope<T, W> {
+    #[instrument(
+        name = "ThemeScope",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(SET_THEME) => {
+                let theme = cmd.get_unchecked(SET_THEME).clone();
+                self.set_theme(ctx, theme, env);
+                // not handled, so that every scope and window gets the theme
+            }
+            Event::AnimFrame(interval) => {
+                if let Some((_, elapsed)) = &mut self.fade {
+                    *elapsed += Duration::from_nanos(*interval);
+                    if *elapsed >= self.transition {
+                        self.fade = None;
+                    } else {
+                        ctx.request_anim_frame();
+                    }
+                    self.cached_env = None;
+                    ctx.request_update();
+                    ctx.request_paint();
+                }
+            }
+            _ => (),
+        }
+        let child_env = self.child_env(env);
+        self.child.event(ctx, event, data, &child_env)
+    }
+
+    #[instrument(
+        name = "ThemeScope",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.lifecycle(ctx, event, data, &child_env)
+    }
+
+    #[instrument(
+        name = "ThemeScope",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.update(ctx, data, &child_env);
+    }
+
+    #[instrument(name = "ThemeScope", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("ThemeScope");
+
+        let child_env = self.child_env(env);
+        let size = self.child.layout(ctx, bc, data, &child_env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "ThemeScope", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.paint(ctx, data, &child_env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, W: Widget<T>> WidgetWrapper for ThemeScope<T, W> {
+    widget_wrapper_pod_body!(W, child);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_colors() {
+        let from = Color::rgba8(0, 0x80, 0xff, 0xff);
+        let to = Color::rgba8(0xff, 0x80, 0, 0);
+        assert_eq!(mix(from, to, 0.0).as_rgba8(), from.as_rgba8());
+        assert_eq!(mix(from, to, 1.0).as_rgba8(), to.as_rgba8());
+        assert_eq!(mix(from, to, 0.5).as_rgba8(), (0x80, 0x80, 0x80, 0x80));
+    }
+}
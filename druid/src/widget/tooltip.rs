@@ -0,0 +1,356 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that shows a tooltip when its child is hovered.
+
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::commands::CLOSE_WINDOW;
+use crate::widget::prelude::*;
+use crate::widget::{Controller, Label, LabelText, WidgetExt};
+use crate::{
+    theme, Point, Rect, Screen, Selector, TimerToken, Vec2, WidgetPod, WindowConfig, WindowId,
+    WindowLevel, WindowSizePolicy,
+};
+
+/// The default delay before a tooltip is shown.
+const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+
+/// The default distance between the cursor and the tooltip.
+const DEFAULT_OFFSET: Vec2 = Vec2::new(15.0, 15.0);
+
+/// Sent to the tooltip window when the cursor moves while the tooltip
+/// follows the cursor. The payload is the new anchor in the coordinate space
+/// of the parent window.
+const MOVE_TOOLTIP: Selector<Point> = Selector::new("druid-builtin.tooltip.move");
+
+/// A [`Controller`] that shows a popup near the cursor after the child
+/// has been hovered for a while.
+///
+/// The popup can contain any widget, and shares the data of the widget
+/// it is attached to. It is hidden as soon as the cursor leaves the child or
+/// the child is clicked, and is moved so that it stays on screen.
+///
+/// This is more conveniently available via the [`tooltip`] and
+/// [`tooltip_widget`] methods on [`WidgetExt`].
+///
+/// [`tooltip`]: WidgetExt::tooltip
+/// [`tooltip_widget`]: WidgetExt::tooltip_widget
+pub struct Tooltip<T> {
+    content: Box<dyn Fn() -> Box<dyn Widget<T>>>,
+    delay: Duration,
+    offset: Vec2,
+    follow_cursor: bool,
+    state: TooltipState,
+}
+
+enum TooltipState {
+    /// The cursor is not over the widget.
+    Idle,
+    /// The cursor is over the widget and we are waiting for it to settle.
+    Waiting { token: TimerToken, anchor: Point },
+    /// The tooltip window is open.
+    Showing(WindowId),
+    /// The tooltip was dismissed; it won't be shown again until the cursor
+    /// leaves the widget.
+    Dismissed,
+}
+
+impl<T: Data> Tooltip<T> {
+    /// Create a tooltip that displays the provided text.
+    ///
+    /// The text is displayed in a small bordered box, styled using the
+    /// current theme.
+    pub fn new(text: impl Into<LabelText<T>>) -> Self {
+        let text = text.into();
+        Tooltip::with_widget(move || {
+            Label::new(text.clone())
+                .with_text_size(theme::TEXT_SIZE_NORMAL)
+                .padding((8.0, 4.0))
+                .background(theme::BACKGROUND_LIGHT)
+                .border(theme::BORDER_DARK, 1.0)
+        })
+    }
+
+    /// Create a tooltip that displays an arbitrary widget.
+    ///
+    /// The closure is called to build the content each time the tooltip is
+    /// shown.
+    pub fn with_widget<W: Widget<T> + 'static>(content: impl Fn() -> W + 'static) -> Self {
+        Tooltip {
+            content: Box::new(move || content().boxed()),
+            delay: DEFAULT_DELAY,
+            offset: DEFAULT_OFFSET,
+            follow_cursor: false,
+            state: TooltipState::Idle,
+        }
+    }
+
+    /// Builder-style method to set how long the cursor has to rest over the
+    /// widget before the tooltip is shown.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Builder-style method to set the distance between the cursor and the
+    /// top left corner of the tooltip.
+    pub fn offset(mut self, offset: impl Into<Vec2>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
+    /// Builder-style method to set whether the tooltip follows the cursor
+    /// while it is shown.
+    ///
+    /// By default the tooltip stays where it was first shown.
+    pub fn follow_cursor(mut self, follow: bool) -> Self {
+        self.follow_cursor = follow;
+        self
+    }
+
+    fn show(&mut self, ctx: &mut EventCtx, anchor: Point, data: &T, env: &Env) -> WindowId {
+        let parent_origin = ctx.window().get_position();
+        let frame = TooltipFrame {
+            child: WidgetPod::new((self.content)()),
+            anchor,
+            offset: self.offset,
+            parent_origin,
+            placed: false,
+        };
+        ctx.new_sub_window(
+            WindowConfig::default()
+                .show_titlebar(false)
+                .window_size_policy(WindowSizePolicy::Content)
+                .set_level(WindowLevel::Tooltip(ctx.window().clone()))
+                .set_position(anchor + self.offset),
+            frame,
+            data.clone(),
+            env.clone(),
+        )
+    }
+
+    /// Move to the `next` state, returning the id of the tooltip window
+    /// if it was open and should now be closed.
+    fn hide(&mut self, next: TooltipState) -> Option<WindowId> {
+        match std::mem::replace(&mut self.state, next) {
+            TooltipState::Showing(id) => {
+                trace!("Closing tooltip window {:?}", id);
+                Some(id)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for Tooltip<T> {
+    #[instrument(
+        name = "Tooltip",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(mouse) if ctx.is_hot() => match self.state {
+                TooltipState::Idle | TooltipState::Waiting { .. } => {
+                    self.state = TooltipState::Waiting {
+                        token: ctx.request_timer(self.delay),
+                        anchor: mouse.window_pos,
+                    };
+                }
+                TooltipState::Showing(id) if self.follow_cursor => {
+                    ctx.submit_command(MOVE_TOOLTIP.with(mouse.window_pos).to(id));
+                }
+                _ => (),
+            },
+            Event::MouseDown(_) | Event::Wheel(_) | Event::KeyDown(_) => {
+                if let Some(id) = self.hide(TooltipState::Dismissed) {
+                    ctx.submit_command(CLOSE_WINDOW.to(id));
+                }
+            }
+            Event::Timer(token) => {
+                if let TooltipState::Waiting {
+                    token: waiting,
+                    anchor,
+                } = self.state
+                {
+                    if *token == waiting {
+                        ctx.set_handled();
+                        let id = self.show(ctx, anchor, data, env);
+                        self.state = TooltipState::Showing(id);
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        if !ctx.is_handled() {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Tooltip",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::HotChanged(false)
+        | LifeCycle::DisabledChanged(true)
+        | LifeCycle::ViewContextChanged(_) = event
+        {
+            if let Some(id) = self.hide(TooltipState::Idle) {
+                ctx.submit_command(CLOSE_WINDOW.to(id));
+            }
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
+
+/// The root of a tooltip window.
+///
+/// Once the size of the content is known, this moves the window so that
+/// it stays within the work area of the monitor the cursor is on.
+struct TooltipFrame<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    /// The cursor position, in the coordinate space of the parent window.
+    anchor: Point,
+    offset: Vec2,
+    /// The position of the parent window on the virtual screen.
+    parent_origin: Point,
+    placed: bool,
+}
+
+impl<T: Data> TooltipFrame<T> {
+    fn place(&mut self, ctx: &mut LayoutCtx, size: Size) {
+        let screen_anchor = self.parent_origin + self.anchor.to_vec2();
+        let bounds = Screen::get_monitors()
+            .iter()
+            .map(|monitor| monitor.virtual_work_rect())
+            .find(|rect| rect.contains(screen_anchor))
+            .unwrap_or_else(Screen::get_display_rect);
+        let origin = place_tooltip(screen_anchor, size, self.offset, bounds);
+        ctx.window()
+            .set_position(origin - self.parent_origin.to_vec2());
+        self.placed = true;
+    }
+}
+
+impl<T: Data> Widget<T> for TooltipFrame<T> {
+    #[instrument(
+        name = "TooltipFrame",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(anchor) = cmd.get(MOVE_TOOLTIP) {
+                self.anchor = *anchor;
+                self.placed = false;
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "TooltipFrame",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "TooltipFrame",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "TooltipFrame", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        if !self.placed {
+            self.place(ctx, size);
+        }
+        size
+    }
+
+    #[instrument(name = "TooltipFrame", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+}
+
+/// Compute the origin of a tooltip of `size`, shown for a cursor at `anchor`.
+///
+/// The tooltip is placed below and to the right of the cursor. If that
+/// would take it outside of `bounds` it is flipped to the other side of
+/// the cursor, and finally clamped so that as much as possible is visible.
+fn place_tooltip(anchor: Point, size: Size, offset: Vec2, bounds: Rect) -> Point {
+    let mut x = anchor.x + offset.x;
+    if x + size.width > bounds.x1 {
+        x = anchor.x - offset.x - size.width;
+    }
+    let mut y = anchor.y + offset.y;
+    if y + size.height > bounds.y1 {
+        y = anchor.y - offset.y - size.height;
+    }
+    let x = x.min(bounds.x1 - size.width).max(bounds.x0);
+    let y = y.min(bounds.y1 - size.height).max(bounds.y0);
+    Point::new(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: Rect = Rect::new(0.0, 0.0, 800.0, 600.0);
+    const SIZE: Size = Size::new(100.0, 20.0);
+
+    #[test]
+    fn placed_below_right_of_cursor() {
+        let origin = place_tooltip(Point::new(100.0, 100.0), SIZE, DEFAULT_OFFSET, BOUNDS);
+        assert_eq!(origin, Point::new(115.0, 115.0));
+    }
+
+    #[test]
+    fn flipped_near_edges() {
+        let origin = place_tooltip(Point::new(750.0, 590.0), SIZE, DEFAULT_OFFSET, BOUNDS);
+        assert_eq!(origin, Point::new(635.0, 555.0));
+    }
+
+    #[test]
+    fn clamped_when_too_large() {
+        let size = Size::new(900.0, 20.0);
+        let origin = place_tooltip(Point::new(10.0, 10.0), size, DEFAULT_OFFSET, BOUNDS);
+        assert_eq!(origin, Point::new(0.0, 25.0));
+    }
+}
@@ -0,0 +1,267 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Undo and redo for application data.
+
+use std::collections::VecDeque;
+
+use tracing::instrument;
+
+use crate::commands;
+use crate::widget::prelude::*;
+use crate::widget::Controller;
+use crate::{Lens, Selector};
+
+type Keep<T> = Box<dyn Fn(&mut T, &T)>;
+
+/// A [`Controller`] that records changes to its widget's data, so that they
+/// can be undone and redone.
+///
+/// Before each change the previous data is kept as a snapshot; because
+/// [`Data`] is cheap to clone, this usually shares most of its contents with
+/// the current data. Changes are undone and redone with the [`UNDO`] and
+/// [`REDO`] commands, which are sent by the platform Edit menu items in
+/// [`platform_menus::common`]. These are only handled if no child widget
+/// handled them first, so a focused [`TextBox`] still undoes its own edits.
+///
+/// Changes made between [`BEGIN_GROUP`] and [`END_GROUP`] are undone
+/// together. Fields that should not be undone, such as scroll positions or
+/// selections, can be excluded with [`ignore`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Flex, UndoManager};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Doc {
+///     text: String,
+///     scroll: f64,
+/// }
+///
+/// fn build_root() -> impl Widget<Doc> {
+///     Flex::column().controller(UndoManager::new().ignore(Doc::scroll))
+/// }
+/// ```
+///
+/// [`UNDO`]: crate::commands::UNDO
+/// [`REDO`]: crate::commands::REDO
+/// [`platform_menus::common`]: crate::platform_menus::common
+/// [`TextBox`]: crate::widget::TextBox
+/// [`BEGIN_GROUP`]: UndoManager::BEGIN_GROUP
+/// [`END_GROUP`]: UndoManager::END_GROUP
+/// [`ignore`]: UndoManager::ignore
+pub struct UndoManager<T> {
+    undo_stack: VecDeque<T>,
+    redo_stack: Vec<T>,
+    limit: usize,
+    ignored: Vec<Keep<T>>,
+    group_depth: usize,
+    group_recorded: bool,
+    restored: Option<T>,
+}
+
+impl UndoManager<()> {
+    /// Start a group of changes that are undone together.
+    ///
+    /// Groups can be nested; the changes are grouped until the outermost
+    /// group ends.
+    pub const BEGIN_GROUP: Selector = Selector::new("druid-builtin.undo-begin-group");
+
+    /// End a group started with [`BEGIN_GROUP`].
+    ///
+    /// [`BEGIN_GROUP`]: UndoManager::BEGIN_GROUP
+    pub const END_GROUP: Selector = Selector::new("druid-builtin.undo-end-group");
+
+    /// Forget every recorded change.
+    pub const CLEAR_HISTORY: Selector = Selector::new("druid-builtin.undo-clear-history");
+}
+
+impl<T: Data> UndoManager<T> {
+    /// Create a new `UndoManager` that keeps the last 100 changes.
+    pub fn new() -> Self {
+        UndoManager {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            limit: 100,
+            ignored: Vec::new(),
+            group_depth: 0,
+            group_recorded: false,
+            restored: None,
+        }
+    }
+
+    /// Builder-style method to set how many changes are kept.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    /// Builder-style method to exclude the field selected by `lens` from
+    /// undo and redo.
+    ///
+    /// Changes to only this field are not recorded, and undoing or redoing
+    /// another change leaves this field as it is.
+    pub fn ignore<U: Data>(mut self, lens: impl Lens<T, U> + 'static) -> Self {
+        self.ignored.push(Box::new(move |data, current| {
+            let value = lens.with(current, U::clone);
+            lens.with_mut(data, |field| *field = value);
+        }));
+        self
+    }
+
+    /// Whether there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Whether there is a change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// `snapshot`, with the ignored fields taken from `current`.
+    fn keep_ignored(&self, snapshot: &T, current: &T) -> T {
+        let mut snapshot = snapshot.clone();
+        for keep in &self.ignored {
+            keep(&mut snapshot, current);
+        }
+        snapshot
+    }
+
+    fn record(&mut self, old_data: &T, data: &T) {
+        if self.keep_ignored(old_data, data).same(data) {
+            return;
+        }
+        self.redo_stack.clear();
+        if self.group_depth > 0 {
+            if self.group_recorded {
+                return;
+            }
+            self.group_recorded = true;
+        }
+        if self.undo_stack.len() == self.limit {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(old_data.clone());
+    }
+
+    fn undo(&mut self, data: &mut T) {
+        if let Some(snapshot) = self.undo_stack.pop_back() {
+            let restored = self.keep_ignored(&snapshot, data);
+            self.redo_stack
+                .push(std::mem::replace(data, restored.clone()));
+            self.restored = Some(restored);
+        }
+    }
+
+    fn redo(&mut self, data: &mut T) {
+        if let Some(snapshot) = self.redo_stack.pop() {
+            let restored = self.keep_ignored(&snapshot, data);
+            self.undo_stack
+                .push_back(std::mem::replace(data, restored.clone()));
+            self.restored = Some(restored);
+        }
+    }
+}
+
+impl<T: Data> Default for UndoManager<T> {
+    fn default() -> Self {
+        UndoManager::new()
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for UndoManager<T> {
+    #[instrument(
+        name = "UndoManager",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(UndoManager::BEGIN_GROUP) => {
+                if self.group_depth == 0 {
+                    self.group_recorded = false;
+                }
+                self.group_depth += 1;
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(UndoManager::END_GROUP) => {
+                self.group_depth = self.group_depth.saturating_sub(1);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(UndoManager::CLEAR_HISTORY) => {
+                self.undo_stack.clear();
+                self.redo_stack.clear();
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(commands::UNDO) || cmd.is(commands::REDO) => {
+                child.event(ctx, event, data, env);
+                if !ctx.is_handled() {
+                    if cmd.is(commands::UNDO) {
+                        self.undo(data);
+                    } else {
+                        self.redo(data);
+                    }
+                    ctx.set_handled();
+                }
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    #[instrument(
+        name = "UndoManager",
+        level = "trace",
+        skip(self, child, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        match self.restored.take() {
+            Some(restored) if restored.same(data) => (),
+            _ if !old_data.same(data) => self.record(old_data, data),
+            _ => (),
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_restores_all_but_ignored_fields() {
+        let mut undo = UndoManager::new().ignore(lens!((u32, u32), 1));
+        let mut data = (1, 0);
+
+        // Changes to the ignored field alone are not recorded.
+        undo.record(&data, &(1, 5));
+        assert!(!undo.can_undo());
+
+        undo.record(&(1, 5), &(2, 5));
+        data = (2, 7);
+        undo.undo(&mut data);
+        assert_eq!(data, (1, 7));
+        assert!(undo.can_redo());
+
+        undo.redo(&mut data);
+        assert_eq!(data, (2, 7));
+
+        // Any new change clears the redo history.
+        undo.undo(&mut data);
+        undo.record(&data, &(3, 7));
+        assert!(!undo.can_redo());
+    }
+}
@@ -15,6 +15,7 @@
 //! A widget that can dynamically switch between one of many views.
 
 use crate::widget::prelude::*;
+use crate::widget::switch_transition::{SwitchTransition, TransitionState};
 use crate::{Data, Point, WidgetPod};
 use tracing::instrument;
 
@@ -22,11 +23,19 @@ type ChildPicker<T, U> = dyn Fn(&T, &Env) -> U;
 type ChildBuilder<T, U> = dyn Fn(&U, &T, &Env) -> Box<dyn Widget<T>>;
 
 /// A widget that switches dynamically between multiple children.
+///
+/// Switching can be animated with [`with_transition`].
+///
+/// [`with_transition`]: ViewSwitcher::with_transition
 pub struct ViewSwitcher<T, U> {
     child_picker: Box<ChildPicker<T, U>>,
     child_builder: Box<ChildBuilder<T, U>>,
     active_child: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
     active_child_id: Option<U>,
+    transition: SwitchTransition,
+    transition_state: Option<TransitionState>,
+    /// The child being switched away from, with the data it's painted with.
+    previous_child: Option<(WidgetPod<T, Box<dyn Widget<T>>>, T)>,
 }
 
 impl<T: Data, U: Data> ViewSwitcher<T, U> {
@@ -73,8 +82,19 @@ impl<T: Data, U: Data> ViewSwitcher<T, U> {
             child_builder: Box::new(child_builder),
             active_child: None,
             active_child_id: None,
+            transition: SwitchTransition::Instant,
+            transition_state: None,
+            previous_child: None,
         }
     }
+
+    /// Builder-style method to animate switching between children.
+    ///
+    /// The previous child is kept while the transition runs, but it is only painted.
+    pub fn with_transition(mut self, transition: SwitchTransition) -> Self {
+        self.transition = transition;
+        self
+    }
 }
 
 impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
@@ -85,7 +105,19 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
     )]
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         if let Some(child) = self.active_child.as_mut() {
-            child.event(ctx, event, data, env);
+            if self.transition_state.is_none() || event.should_propagate_to_hidden() {
+                child.event(ctx, event, data, env);
+            }
+        }
+
+        if let (Some(state), Event::AnimFrame(interval)) = (&mut self.transition_state, event) {
+            if state.advance(*interval) {
+                ctx.request_anim_frame();
+            } else {
+                self.transition_state = None;
+                self.previous_child = None;
+            }
+            ctx.request_paint();
         }
     }
 
@@ -108,13 +140,20 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
     #[instrument(
         name = "ViewSwitcher",
         level = "trace",
-        skip(self, ctx, _old_data, data, env)
+        skip(self, ctx, old_data, data, env)
     )]
-    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
         let child_id = (self.child_picker)(data, env);
         // Safe to unwrap because self.active_child_id should not be empty
         if !child_id.same(self.active_child_id.as_ref().unwrap()) {
-            self.active_child = Some(WidgetPod::new((self.child_builder)(&child_id, data, env)));
+            let previous_child = self
+                .active_child
+                .replace(WidgetPod::new((self.child_builder)(&child_id, data, env)));
+            self.transition_state = self.transition.start(ctx, env);
+            self.previous_child = match (&self.transition_state, previous_child) {
+                (Some(_), Some(child)) => Some((child, old_data.clone())),
+                _ => None,
+            };
             self.active_child_id = Some(child_id);
             ctx.children_changed();
         // Because the new child has not yet been initialized, we have to skip the update after switching.
@@ -137,7 +176,17 @@ impl<T: Data, U: Data> Widget<T> for ViewSwitcher<T, U> {
 
     #[instrument(name = "ViewSwitcher", level = "trace", skip(self, ctx, data, env))]
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        if let Some(ref mut child) = self.active_child {
+        if let (Some(state), Some((previous_child, previous_data))) =
+            (&self.transition_state, &mut self.previous_child)
+        {
+            let active_child = &mut self.active_child;
+            let mut paint_child = |ctx: &mut PaintCtx, new: bool| match (new, &mut *active_child) {
+                (true, Some(child)) => child.paint_raw(ctx, data, env),
+                (true, None) => (),
+                (false, _) => previous_child.paint_raw(ctx, previous_data, env),
+            };
+            self.transition.paint(ctx, state, &mut paint_child, env);
+        } else if let Some(ref mut child) = self.active_child {
             child.paint_raw(ctx, data, env);
         }
     }
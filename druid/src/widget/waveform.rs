@@ -0,0 +1,412 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that displays audio samples.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::kurbo::{BezPath, Line};
+use crate::widget::prelude::*;
+use crate::{theme, Color, KeyOrValue, Lens, LensExt, Rect};
+
+/// The number of samples summarized by each precomputed peak.
+const BLOCK_SIZE: usize = 256;
+/// The furthest the view can zoom in, in samples per display point.
+const MIN_SAMPLES_PER_POINT: f64 = 1.0 / 16.0;
+/// How much each step of the mouse wheel changes the zoom by.
+const ZOOM_STEP: f64 = 1.25;
+/// How far the mouse has to move before a press becomes a selection.
+const DRAG_THRESHOLD: f64 = 3.0;
+const DEFAULT_HEIGHT: f64 = 64.0;
+
+type SelectCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, Range<usize>, &Env)>;
+
+/// The smallest and largest sample in each block of a buffer.
+struct Peaks {
+    samples: Arc<[f32]>,
+    blocks: Vec<(f32, f32)>,
+}
+
+/// A widget that draws audio samples as a waveform.
+///
+/// The samples and the position of the playhead, in samples, are read from the
+/// data through two lenses. Clicking the waveform moves the playhead, and dragging
+/// selects a region, which is reported to the [`on_select`] callback.
+///
+/// The whole buffer is shown until the view is zoomed, with ctrl and the mouse
+/// wheel; the mouse wheel alone then scrolls. While zoomed, the view follows the
+/// playhead when it moves out of sight.
+///
+/// When zoomed out, each column of the waveform shows the smallest and largest
+/// sample it covers. These are summarized ahead of time, so even long buffers
+/// are cheap to draw.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::Waveform;
+/// use druid::{Data, Lens, Widget};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Track {
+///     samples: Arc<[f32]>,
+///     position: f64,
+/// }
+///
+/// fn track() -> impl Widget<Track> {
+///     Waveform::new(Track::samples, Track::position).on_select(|_ctx, _track, region, _env| {
+///         println!("selected samples {:?}", region);
+///     })
+/// }
+/// ```
+///
+/// [`on_select`]: Waveform::on_select
+pub struct Waveform<T, S, P> {
+    samples: S,
+    playhead: P,
+    peaks: Peaks,
+    color: KeyOrValue<Color>,
+    /// How many samples each display point covers.
+    samples_per_point: f64,
+    /// The sample at the left edge of the widget.
+    offset: f64,
+    zoomed: bool,
+    /// Where the mouse was pressed, and whether it has moved enough to be a drag.
+    press: Option<(f64, bool)>,
+    /// The sample the selection started at, and the one it extends to.
+    selection: Option<(f64, f64)>,
+    on_select: Option<SelectCallback<T>>,
+}
+
+impl Peaks {
+    fn new(samples: Arc<[f32]>) -> Self {
+        let blocks = samples
+            .chunks(BLOCK_SIZE)
+            .map(|block| min_max(block).unwrap_or_default())
+            .collect();
+        Peaks { samples, blocks }
+    }
+
+    /// The smallest and largest sample in `range`.
+    fn range(&self, range: Range<usize>) -> Option<(f32, f32)> {
+        let Range { start, end } = range;
+        let end = end.min(self.samples.len());
+        if start >= end {
+            return None;
+        }
+        if end - start < 2 * BLOCK_SIZE {
+            return min_max(&self.samples[start..end]);
+        }
+
+        let first_block = start.div_ceil(BLOCK_SIZE);
+        let last_block = end / BLOCK_SIZE;
+        let head = min_max(&self.samples[start..first_block * BLOCK_SIZE]);
+        let tail = min_max(&self.samples[last_block * BLOCK_SIZE..end]);
+        self.blocks[first_block..last_block]
+            .iter()
+            .copied()
+            .chain(head)
+            .chain(tail)
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    }
+}
+
+fn min_max(samples: &[f32]) -> Option<(f32, f32)> {
+    samples.iter().fold(None, |acc, &sample| match acc {
+        None => Some((sample, sample)),
+        Some((min, max)) => Some((min.min(sample), max.max(sample))),
+    })
+}
+
+impl<T, S: Lens<T, Arc<[f32]>>, P: Lens<T, f64>> Waveform<T, S, P> {
+    /// Create a waveform showing the samples from one lens, with a playhead at
+    /// the sample position from another.
+    pub fn new(samples: S, playhead: P) -> Self {
+        Waveform {
+            samples,
+            playhead,
+            peaks: Peaks::new(Arc::new([])),
+            color: theme::PRIMARY_LIGHT.into(),
+            samples_per_point: 1.0,
+            offset: 0.0,
+            zoomed: false,
+            press: None,
+            selection: None,
+            on_select: None,
+        }
+    }
+
+    /// Builder-style method for setting the color of the waveform.
+    ///
+    /// The argument can be either a `Color` or a [`Key<Color>`].
+    ///
+    /// [`Key<Color>`]: crate::Key
+    pub fn with_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.color = color.into();
+        self
+    }
+
+    /// Builder-style method for a callback that is called when a region of samples
+    /// has been selected.
+    pub fn on_select(
+        mut self,
+        f: impl Fn(&mut EventCtx, &mut T, Range<usize>, &Env) + 'static,
+    ) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    /// The selected range of samples, if any.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let (a, b) = self.selection?;
+        let len = self.peaks.samples.len() as f64;
+        let start = a.min(b).clamp(0.0, len).round() as usize;
+        let end = a.max(b).clamp(0.0, len).round() as usize;
+        Some(start..end)
+    }
+
+    fn sample_at(&self, x: f64) -> f64 {
+        (self.offset + x * self.samples_per_point).clamp(0.0, self.peaks.samples.len() as f64)
+    }
+
+    fn x_of(&self, sample: f64) -> f64 {
+        (sample - self.offset) / self.samples_per_point
+    }
+
+    /// Show the whole buffer if the view isn't zoomed, and keep the view inside it.
+    fn clamp_view(&mut self, width: f64) {
+        let len = self.peaks.samples.len() as f64;
+        let fit = (len / width.max(1.0)).max(MIN_SAMPLES_PER_POINT);
+        if !self.zoomed || self.samples_per_point >= fit {
+            self.samples_per_point = fit;
+            self.zoomed = false;
+        }
+        let max_offset = (len - width * self.samples_per_point).max(0.0);
+        self.offset = self.offset.clamp(0.0, max_offset);
+    }
+
+    fn set_samples(&mut self, samples: Arc<[f32]>) {
+        self.peaks = Peaks::new(samples);
+        self.zoomed = false;
+        self.offset = 0.0;
+        self.selection = None;
+    }
+}
+
+impl<T: Data, S: Lens<T, Arc<[f32]>>, P: Lens<T, f64>> Widget<T> for Waveform<T, S, P> {
+    #[instrument(name = "Waveform", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let width = ctx.size().width;
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                ctx.set_active(true);
+                self.press = Some((mouse.pos.x, false));
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some((press_x, dragging)) = self.press {
+                    if dragging || (mouse.pos.x - press_x).abs() >= DRAG_THRESHOLD {
+                        self.press = Some((press_x, true));
+                        let anchor = self.sample_at(press_x);
+                        self.selection = Some((anchor, self.sample_at(mouse.pos.x)));
+                        ctx.request_paint();
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                match self.press.take() {
+                    Some((_, true)) => {
+                        if let (Some(region), Some(on_select)) = (self.selection(), &self.on_select)
+                        {
+                            (on_select)(ctx, data, region, env);
+                        }
+                    }
+                    Some((_, false)) => {
+                        self.selection = None;
+                        let sample = self.sample_at(mouse.pos.x);
+                        self.playhead.put(data, sample);
+                    }
+                    None => {}
+                }
+                ctx.request_paint();
+            }
+            Event::Wheel(mouse) => {
+                if mouse.mods.ctrl() {
+                    let steps = mouse.wheel_delta.y.signum();
+                    let anchor = self.sample_at(mouse.pos.x);
+                    self.samples_per_point =
+                        (self.samples_per_point * ZOOM_STEP.powf(steps)).max(MIN_SAMPLES_PER_POINT);
+                    self.zoomed = true;
+                    self.offset = anchor - mouse.pos.x * self.samples_per_point;
+                } else if self.zoomed {
+                    let delta = match mouse.wheel_delta.x {
+                        x if x != 0.0 => x,
+                        _ => mouse.wheel_delta.y,
+                    };
+                    self.offset += delta * self.samples_per_point;
+                } else {
+                    return;
+                }
+                self.clamp_view(width);
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+    }
+
+    #[instrument(
+        name = "Waveform",
+        level = "trace",
+        skip(self, _ctx, event, data, _env)
+    )]
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let samples = self.samples.get(data);
+            self.set_samples(samples);
+        }
+    }
+
+    #[instrument(
+        name = "Waveform",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        let samples = self.samples.get(data);
+        if !samples.same(&self.peaks.samples) {
+            self.set_samples(samples);
+            self.clamp_view(ctx.size().width);
+            ctx.request_paint();
+        }
+
+        let playhead = self.playhead.get(data);
+        if !playhead.same(&self.playhead.get(old_data)) {
+            let width = ctx.size().width;
+            let x = self.x_of(playhead);
+            if self.zoomed && !(0.0..width).contains(&x) {
+                self.offset = playhead;
+                self.clamp_view(width);
+            }
+            ctx.request_paint();
+        }
+        if ctx.env_key_changed(&self.color) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Waveform", level = "trace", skip(self, _ctx, bc, _data, env))]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("Waveform");
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            env.get(theme::WIDE_WIDGET_WIDTH)
+        };
+        let size = bc.constrain(Size::new(width, DEFAULT_HEIGHT));
+        self.clamp_view(size.width);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Waveform", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        ctx.fill(size.to_rect(), &env.get(theme::BACKGROUND_DARK));
+
+        if let Some((a, b)) = self.selection {
+            let (a, b) = (self.x_of(a), self.x_of(b));
+            let rect = Rect::new(a.min(b), 0.0, a.max(b), size.height);
+            ctx.fill(rect, &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR));
+        }
+
+        let mid = size.height / 2.0;
+        let amplitude = (mid - 1.0).max(0.0);
+        let y = |sample: f32| mid - f64::from(sample).clamp(-1.0, 1.0) * amplitude;
+
+        let visible = ctx.region().bounding_box();
+        let first = visible.x0.floor().max(0.0) as usize;
+        let last = visible.x1.ceil().min(size.width) as usize;
+        let color = self.color.resolve(env);
+        if self.samples_per_point <= 1.0 {
+            // Zoomed in far enough to join up the individual samples.
+            let start = self.sample_at(first as f64).floor() as usize;
+            let end =
+                (self.sample_at(last as f64).ceil() as usize + 1).min(self.peaks.samples.len());
+            let mut path = BezPath::new();
+            for (i, &sample) in self.peaks.samples[start.min(end)..end].iter().enumerate() {
+                let point = (self.x_of((start + i) as f64), y(sample));
+                if i == 0 {
+                    path.move_to(point);
+                } else {
+                    path.line_to(point);
+                }
+            }
+            ctx.stroke(path, &color, 1.0);
+        } else {
+            // Fill the area between the largest and smallest sample of each column.
+            let mut tops = Vec::with_capacity(last.saturating_sub(first));
+            let mut bottoms = Vec::with_capacity(last.saturating_sub(first));
+            for x in first..last {
+                let start = self.sample_at(x as f64) as usize;
+                let end = self.sample_at(x as f64 + 1.0).ceil() as usize;
+                if let Some((min, max)) = self.peaks.range(start..end) {
+                    let (top, bottom) = (y(max), y(min));
+                    // Keep silent stretches visible as a line.
+                    let extra = (1.0 - (bottom - top)).max(0.0) / 2.0;
+                    tops.push((x as f64, top - extra));
+                    bottoms.push((x as f64 + 1.0, bottom + extra));
+                }
+            }
+            let mut path = BezPath::new();
+            for (i, &point) in tops.iter().chain(bottoms.iter().rev()).enumerate() {
+                if i == 0 {
+                    path.move_to(point);
+                } else {
+                    path.line_to(point);
+                }
+            }
+            path.close_path();
+            ctx.fill(path, &color);
+        }
+
+        let playhead = self.x_of(self.playhead.get(data));
+        if (0.0..=size.width).contains(&playhead) {
+            let line = Line::new((playhead, 0.0), (playhead, size.height));
+            ctx.stroke(line, &env.get(theme::CURSOR_COLOR), 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peaks() {
+        let samples: Arc<[f32]> = (0..2000).map(|i| i as f32).collect();
+        let peaks = Peaks::new(samples);
+        assert_eq!(peaks.blocks.len(), 8);
+        assert_eq!(peaks.blocks[1], (256.0, 511.0));
+        assert_eq!(peaks.range(0..1), Some((0.0, 0.0)));
+        assert_eq!(peaks.range(10..20), Some((10.0, 19.0)));
+        assert_eq!(peaks.range(100..1900), Some((100.0, 1899.0)));
+        assert_eq!(peaks.range(1990..5000), Some((1990.0, 1999.0)));
+        assert_eq!(peaks.range(2000..2001), None);
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that embeds a native web view.
+
+use tracing::{instrument, trace};
+
+use crate::widget::prelude::*;
+use crate::{ExtEventError, ExtEventSink, Point, Rect, Selector, WindowHandle};
+
+type MessageCallback = Box<dyn Fn(&mut EventCtx, &mut String, &str, &Env)>;
+
+/// A native web view that can be embedded with a [`WebView`].
+///
+/// This is implemented on top of a web view library, such as wry, which creates
+/// a child surface of the window using its [raw window handle].
+///
+/// [raw window handle]: crate::HasRawWindowHandle
+pub trait WebViewBackend {
+    /// Create the web view as a child of `window`.
+    ///
+    /// `events` is used to tell the [`WebView`] about things that happen in the page.
+    fn attach(&mut self, window: &WindowHandle, events: WebViewEvents);
+
+    /// Move the web view.
+    ///
+    /// `frame` is where the web view is laid out, and `clip` is the part of it that is
+    /// visible, both in window coordinates. If `clip` is empty the web view should be
+    /// hidden.
+    fn set_bounds(&mut self, frame: Rect, clip: Rect);
+
+    /// Load the page at `url`.
+    fn navigate(&mut self, url: &str);
+
+    /// Show `html` as the content of the page.
+    fn load_html(&mut self, html: &str);
+
+    /// Go back in the history.
+    fn go_back(&mut self);
+
+    /// Go forward in the history.
+    fn go_forward(&mut self);
+
+    /// Load the current page again.
+    fn reload(&mut self);
+
+    /// Run a script in the page.
+    fn evaluate_script(&mut self, script: &str);
+
+    /// Send a message to the page's scripts.
+    fn post_message(&mut self, message: &str);
+}
+
+/// A handle that a [`WebViewBackend`] uses to report to its [`WebView`].
+///
+/// It can be sent to other threads.
+#[derive(Clone)]
+pub struct WebViewEvents {
+    sink: ExtEventSink,
+    widget: WidgetId,
+}
+
+impl WebViewEvents {
+    /// Report a message sent by the page's scripts.
+    pub fn message(&self, message: impl Into<String>) -> Result<(), ExtEventError> {
+        self.sink
+            .submit_command(WebView::MESSAGE_RECEIVED, message.into(), self.widget)
+    }
+
+    /// Report that the page navigated to `url`.
+    pub fn navigated(&self, url: impl Into<String>) -> Result<(), ExtEventError> {
+        self.sink
+            .submit_command(WebView::URL_CHANGED, url.into(), self.widget)
+    }
+}
+
+/// A widget that embeds a native web view, showing the URL in its data.
+///
+/// The web view itself is provided by a [`WebViewBackend`]. It is positioned where
+/// the widget is laid out, and clipped to the part of the widget that is visible,
+/// for instance inside a [`Scroll`].
+///
+/// Changing the data loads a new page, and when the page navigates by itself the
+/// data is updated. The web view is controlled with the [`LOAD_HTML`], [`GO_BACK`],
+/// [`GO_FORWARD`], [`RELOAD`], [`EVALUATE_SCRIPT`] and [`POST_MESSAGE`] commands,
+/// and messages from the page are passed to the [`on_message`] callback.
+///
+/// The native view is drawn above the window's content, so widgets can't be drawn
+/// on top of it.
+///
+/// [`Scroll`]: super::Scroll
+/// [`LOAD_HTML`]: WebView::LOAD_HTML
+/// [`GO_BACK`]: WebView::GO_BACK
+/// [`GO_FORWARD`]: WebView::GO_FORWARD
+/// [`RELOAD`]: WebView::RELOAD
+/// [`EVALUATE_SCRIPT`]: WebView::EVALUATE_SCRIPT
+/// [`POST_MESSAGE`]: WebView::POST_MESSAGE
+/// [`on_message`]: WebView::on_message
+pub struct WebView {
+    backend: Box<dyn WebViewBackend>,
+    /// The URL the web view is showing, to tell changes to the data apart from
+    /// navigation in the page.
+    url: String,
+    /// The visible part of the widget, in its own coordinate space.
+    clip: Rect,
+    on_message: Option<MessageCallback>,
+}
+
+impl WebView {
+    /// Show this HTML in the web view.
+    pub const LOAD_HTML: Selector<String> = Selector::new("druid-builtin.web-view-load-html");
+
+    /// Go back in the web view's history.
+    pub const GO_BACK: Selector = Selector::new("druid-builtin.web-view-go-back");
+
+    /// Go forward in the web view's history.
+    pub const GO_FORWARD: Selector = Selector::new("druid-builtin.web-view-go-forward");
+
+    /// Load the current page again.
+    pub const RELOAD: Selector = Selector::new("druid-builtin.web-view-reload");
+
+    /// Run this script in the page.
+    pub const EVALUATE_SCRIPT: Selector<String> =
+        Selector::new("druid-builtin.web-view-evaluate-script");
+
+    /// Send this message to the page's scripts.
+    pub const POST_MESSAGE: Selector<String> = Selector::new("druid-builtin.web-view-post-message");
+
+    /// Sent by the backend with a message from the page's scripts.
+    pub const MESSAGE_RECEIVED: Selector<String> =
+        Selector::new("druid-builtin.web-view-message-received");
+
+    /// Sent by the backend with the new URL when the page navigates.
+    pub const URL_CHANGED: Selector<String> = Selector::new("druid-builtin.web-view-url-changed");
+
+    /// Create a web view using `backend`.
+    pub fn new(backend: impl WebViewBackend + 'static) -> Self {
+        WebView {
+            backend: Box::new(backend),
+            url: String::new(),
+            clip: Rect::ZERO,
+            on_message: None,
+        }
+    }
+
+    /// Builder-style method for a callback that is called with each message
+    /// from the page's scripts.
+    pub fn on_message(
+        mut self,
+        f: impl Fn(&mut EventCtx, &mut String, &str, &Env) + 'static,
+    ) -> Self {
+        self.on_message = Some(Box::new(f));
+        self
+    }
+
+    fn navigate(&mut self, url: &str) {
+        self.url = url.to_owned();
+        if !url.is_empty() {
+            self.backend.navigate(url);
+        }
+    }
+
+    fn update_bounds(&mut self, window_origin: Point, size: Size) {
+        let offset = window_origin.to_vec2();
+        let frame = size.to_rect();
+        let clip = frame.intersect(self.clip);
+        trace!(
+            "web view at {:?}, clipped to {:?}",
+            frame + offset,
+            clip + offset
+        );
+        self.backend.set_bounds(frame + offset, clip + offset);
+    }
+}
+
+impl Widget<String> for WebView {
+    #[instrument(name = "WebView", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(url) = cmd.get(WebView::URL_CHANGED) {
+                self.url = url.clone();
+                *data = url.clone();
+            } else if let Some(message) = cmd.get(WebView::MESSAGE_RECEIVED) {
+                if let Some(on_message) = &self.on_message {
+                    (on_message)(ctx, data, message, env);
+                }
+            } else if let Some(html) = cmd.get(WebView::LOAD_HTML) {
+                self.backend.load_html(html);
+            } else if cmd.is(WebView::GO_BACK) {
+                self.backend.go_back();
+            } else if cmd.is(WebView::GO_FORWARD) {
+                self.backend.go_forward();
+            } else if cmd.is(WebView::RELOAD) {
+                self.backend.reload();
+            } else if let Some(script) = cmd.get(WebView::EVALUATE_SCRIPT) {
+                self.backend.evaluate_script(script);
+            } else if let Some(message) = cmd.get(WebView::POST_MESSAGE) {
+                self.backend.post_message(message);
+            } else {
+                return;
+            }
+            ctx.set_handled();
+        }
+    }
+
+    #[instrument(name = "WebView", level = "trace", skip(self, ctx, event, data, _env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &String, _env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                let events = WebViewEvents {
+                    sink: ctx.get_external_handle(),
+                    widget: ctx.widget_id(),
+                };
+                self.backend.attach(ctx.window(), events);
+                self.navigate(data);
+            }
+            LifeCycle::ViewContextChanged(view_context) => {
+                self.clip = view_context.clip;
+                self.update_bounds(view_context.window_origin, ctx.size());
+            }
+            LifeCycle::Size(size) => self.update_bounds(ctx.window_origin(), *size),
+            _ => {}
+        }
+    }
+
+    #[instrument(
+        name = "WebView",
+        level = "trace",
+        skip(self, _ctx, _old_data, data, _env)
+    )]
+    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &String, data: &String, _env: &Env) {
+        if *data != self.url {
+            self.navigate(data);
+        }
+    }
+
+    #[instrument(name = "WebView", level = "trace", skip(self, _ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &String,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("WebView");
+        let width = env.get(crate::theme::WIDE_WIDGET_WIDTH);
+        let size = bc.constrain(Size::new(width, width));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _data: &String, _env: &Env) {}
+}
@@ -240,6 +240,30 @@ pub trait Widget<T> {
             Axis::Vertical => self.layout(ctx, bc, data, env).height,
         }
     }
+
+    /// Computes min intrinsic dimension of a widget on the provided axis.
+    ///
+    /// Min intrinsic dimension is the smallest dimension the widget could take on that axis
+    /// without its content overflowing. For a word-wrapping label this is the width of its
+    /// longest word. Containers like Grid can use it, together with the max intrinsic, to size
+    /// a column to its widest cell.
+    ///
+    /// Box constraints must be honored, the same way as in `compute_max_intrinsic`.
+    ///
+    /// The default implementation returns the max intrinsic dimension, which assumes the widget
+    /// can't be smaller than its preferred size.
+    ///
+    /// This method **must** return a finite value.
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.compute_max_intrinsic(axis, ctx, bc, data, env)
+    }
 }
 
 impl WidgetId {
@@ -321,4 +345,16 @@ impl<T> Widget<T> for Box<dyn Widget<T>> {
         self.deref_mut()
             .compute_max_intrinsic(axis, ctx, bc, data, env)
     }
+
+    fn compute_min_intrinsic(
+        &mut self,
+        axis: Axis,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.deref_mut()
+            .compute_min_intrinsic(axis, ctx, bc, data, env)
+    }
 }
@@ -18,13 +18,18 @@ use super::invalidation::DebugInvalidation;
 #[allow(deprecated)]
 use super::Parse;
 use super::{
-    Added, Align, BackgroundBrush, Click, Container, Controller, ControllerHost, EnvScope,
-    IdentityWrapper, LensWrap, Padding, SizedBox, WidgetId,
+    Added, Align, Anchor, BackgroundBrush, Badge, Badged, Click, Container, ContextMenu,
+    Controller, ControllerHost, EnvScope, IdentityWrapper, LabelText, LensWrap, LogDataChanges,
+    Padding, Profiled, SizedBox, Style, Styled, Tooltip, WidgetId,
 };
+use crate::lens::MakeMut;
 use crate::widget::{DisabledIf, Scroll};
 use crate::{
-    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, UnitPoint, Widget,
+    ArcStr, Color, Data, DataDiff, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, Menu,
+    UnitPoint, Widget,
 };
+use std::any::Any;
+use std::sync::Arc;
 
 /// A trait that provides extra methods for combining `Widget`s.
 pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
@@ -142,6 +147,27 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         EnvScope::new(f, self)
     }
 
+    /// Wrap this widget in a [`Styled`] widget, overriding common theme values
+    /// such as the background, border and text color for this subtree.
+    ///
+    /// ```
+    /// # use druid::widget::{Button, WidgetExt};
+    /// # use druid::{Color, Widget};
+    /// fn delete_button() -> impl Widget<()> {
+    ///     Button::new("Delete").styled(|style| {
+    ///         style
+    ///             .background(Color::rgb8(0xb0, 0x20, 0x20))
+    ///             .corner_radius(8.0)
+    ///             .hover(|style| style.background(Color::rgb8(0xd0, 0x30, 0x30)))
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// See [`Style`] for the values that can be set.
+    fn styled(self, f: impl FnOnce(Style) -> Style) -> Styled<T, Self> {
+        Styled::new(f(Style::new()), self)
+    }
+
     /// Wrap this widget with the provided [`Controller`].
     fn controller<C: Controller<T, Self>>(self, controller: C) -> ControllerHost<Self, C> {
         ControllerHost::new(self, controller)
@@ -178,6 +204,41 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         ControllerHost::new(self, Click::new(f))
     }
 
+    /// Show a tooltip with the provided text when this widget is hovered.
+    ///
+    /// See [`Tooltip`] for more information; to customize the delay or
+    /// make the tooltip follow the cursor, construct a [`Tooltip`] directly
+    /// and pass it to [`controller`].
+    ///
+    /// [`controller`]: WidgetExt::controller
+    fn tooltip(self, text: impl Into<LabelText<T>>) -> ControllerHost<Self, Tooltip<T>> {
+        ControllerHost::new(self, Tooltip::new(text))
+    }
+
+    /// Show a tooltip containing an arbitrary widget when this widget is hovered.
+    ///
+    /// The closure is called to build the tooltip content each time it is shown.
+    /// The content has access to the same data as this widget.
+    fn tooltip_widget<W: Widget<T> + 'static>(
+        self,
+        content: impl Fn() -> W + 'static,
+    ) -> ControllerHost<Self, Tooltip<T>> {
+        ControllerHost::new(self, Tooltip::with_widget(content))
+    }
+
+    /// Show a context menu when this widget is right-clicked.
+    ///
+    /// The closure is called with the current data to build the menu each
+    /// time it is shown. As with all menus, `R` must be the application's
+    /// root data type; see [`ContextMenu`] for how to route menu commands
+    /// back to this widget, and for long-press support.
+    fn context_menu<R: Any>(
+        self,
+        make_menu: impl Fn(&T) -> Menu<R> + 'static,
+    ) -> ControllerHost<Self, ContextMenu<T, R>> {
+        ControllerHost::new(self, ContextMenu::new(move |data, _| make_menu(data)))
+    }
+
     /// Draw the [`layout`] `Rect`s of  this widget and its children.
     ///
     /// [`layout`]: Widget::layout
@@ -202,6 +263,14 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         DebugInvalidation::new(self)
     }
 
+    /// Log which of the paths in `diff` changed, each time this widget is
+    /// updated with different data.
+    ///
+    /// See [`LogDataChanges`] for more details.
+    fn log_data_changes(self, diff: DataDiff<T>) -> ControllerHost<Self, LogDataChanges<T>> {
+        ControllerHost::new(self, LogDataChanges::new(diff))
+    }
+
     /// Set the [`DEBUG_WIDGET`] env variable for this widget (and its descendants).
     ///
     /// This does nothing by default, but you can use this variable while
@@ -212,11 +281,46 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         EnvScope::new(|env, _| env.set(Env::DEBUG_WIDGET, true), self)
     }
 
+    /// Mark this widget as a named place that [`NAVIGATE_TO_ANCHOR`] can scroll to.
+    ///
+    /// See [`Anchor`] for details.
+    ///
+    /// [`NAVIGATE_TO_ANCHOR`]: crate::commands::NAVIGATE_TO_ANCHOR
+    fn anchor(self, name: impl Into<ArcStr>) -> Anchor<T, Self> {
+        Anchor::new(name, self)
+    }
+
+    /// Draw a badge on a corner of this widget, which shows what `badge` returns
+    /// for the data.
+    ///
+    /// See [`Badged`] for details.
+    fn badge(self, badge: impl Fn(&T, &Env) -> Badge + 'static) -> Badged<T, Self> {
+        Badged::new(self, badge)
+    }
+
+    /// Report the time spent in this widget and its children under `name`, while
+    /// [`profiling`] is enabled.
+    ///
+    /// [`profiling`]: crate::profiling
+    fn profiled(self, name: impl Into<ArcStr>) -> Profiled<T, Self> {
+        Profiled::new(self, name)
+    }
+
     /// Wrap this widget in a [`LensWrap`] widget for the provided [`Lens`].
     fn lens<S: Data, L: Lens<S, T>>(self, lens: L) -> LensWrap<S, T, L, Self> {
         LensWrap::new(self, lens)
     }
 
+    /// Wrap this widget so that it can be used with data in an `Arc`.
+    ///
+    /// Edits made by the widget copy the data on write, and only if the data
+    /// actually changed. See [`LensExt::make_mut`].
+    ///
+    /// [`LensExt::make_mut`]: crate::lens::LensExt::make_mut
+    fn in_arc(self) -> LensWrap<Arc<T>, T, MakeMut, Self> {
+        LensWrap::new(self, MakeMut)
+    }
+
     /// Parse a `Widget<String>`'s contents
     #[doc(hidden)]
     #[deprecated(since = "0.7.0", note = "Use TextBox::with_formatter instead")]
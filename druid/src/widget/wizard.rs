@@ -0,0 +1,489 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A sequence of steps, such as a setup assistant.
+
+use std::rc::Rc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{Circle, Line};
+use crate::widget::prelude::*;
+use crate::widget::{Button, Flex};
+use crate::{
+    theme, ArcStr, Data, Key, Lens, LensExt, Point, Selector, TextLayout, WidgetExt, WidgetPod,
+};
+
+/// The height of the progress header.
+const HEADER_HEIGHT: f64 = 56.0;
+const STEP_RADIUS: f64 = 11.0;
+/// The space around the step's content.
+const CONTENT_PADDING: f64 = 12.0;
+
+type StepValidator<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+type WizardCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, &Env)>;
+
+/// A step of a [`Wizard`].
+struct Step<T> {
+    title: TextLayout<ArcStr>,
+    number: TextLayout<ArcStr>,
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    validator: Option<StepValidator<T>>,
+}
+
+/// A widget that leads the user through a sequence of steps, one at a time.
+///
+/// A header shows the title of every step and how far along the user is, and
+/// buttons below the current step move back, forward, or cancel. Each step can have
+/// a validator, which has to pass before the wizard moves past it; its error is
+/// shown below the step.
+///
+/// The buttons send [`Wizard::NEXT`], [`Wizard::BACK`] and [`Wizard::CANCEL`] as
+/// notifications, and steps can do the same, or they can be sent to the wizard as
+/// commands. Moving forward from the last step calls the [`on_finish`] callback.
+///
+/// The index of the current step can be kept in the data with
+/// [`with_step_lens`], so that the app can see and change it.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, TextBox, Wizard};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Setup {
+///     step: usize,
+///     name: String,
+/// }
+///
+/// fn setup() -> impl Widget<Setup> {
+///     Wizard::new()
+///         .with_step("Welcome", Label::new("Let's get you set up."))
+///         .with_validated_step("Name", TextBox::new().lens(Setup::name), |data: &Setup| {
+///             match data.name.is_empty() {
+///                 true => Err("Enter your name".into()),
+///                 false => Ok(()),
+///             }
+///         })
+///         .with_step_lens(Setup::step)
+///         .on_finish(|_ctx, data, _env| println!("welcome, {}", data.name))
+/// }
+/// ```
+///
+/// [`on_finish`]: Wizard::on_finish
+/// [`with_step_lens`]: Wizard::with_step_lens
+pub struct Wizard<T> {
+    steps: Vec<Step<T>>,
+    current: usize,
+    buttons: WidgetPod<T, Box<dyn Widget<T>>>,
+    error: Option<TextLayout<ArcStr>>,
+    get_step: Option<Box<dyn Fn(&T) -> usize>>,
+    put_step: Option<Box<dyn Fn(&mut T, usize)>>,
+    on_finish: Option<WizardCallback<T>>,
+    on_cancel: Option<WizardCallback<T>>,
+}
+
+impl Wizard<()> {
+    /// Move to the next step, if the current one is valid, or finish.
+    pub const NEXT: Selector = Selector::new("druid-builtin.wizard-next");
+
+    /// Move to the previous step.
+    pub const BACK: Selector = Selector::new("druid-builtin.wizard-back");
+
+    /// Cancel the wizard.
+    pub const CANCEL: Selector = Selector::new("druid-builtin.wizard-cancel");
+
+    /// Whether the current step is the first one.
+    pub const IS_FIRST_STEP: Key<bool> =
+        Key::new("org.linebender.druid.widget.wizard.is_first_step");
+
+    /// Whether the current step is the last one.
+    pub const IS_LAST_STEP: Key<bool> = Key::new("org.linebender.druid.widget.wizard.is_last_step");
+}
+
+impl<T: Data> Wizard<T> {
+    /// Create a wizard without any steps.
+    pub fn new() -> Self {
+        let buttons = Flex::row()
+            .with_child(Button::new("Cancel").on_click(|ctx, _, _| {
+                ctx.submit_notification(Wizard::CANCEL);
+            }))
+            .with_flex_spacer(1.0)
+            .with_child(
+                Button::new("Back")
+                    .on_click(|ctx, _, _| ctx.submit_notification(Wizard::BACK))
+                    .disabled_if(|_, env| env.get(Wizard::IS_FIRST_STEP)),
+            )
+            .with_default_spacer()
+            .with_child(
+                Button::dynamic(|_, env| match env.get(Wizard::IS_LAST_STEP) {
+                    true => "Finish".into(),
+                    false => "Next".into(),
+                })
+                .on_click(|ctx, _, _| ctx.submit_notification(Wizard::NEXT)),
+            );
+        Wizard {
+            steps: Vec::new(),
+            current: 0,
+            buttons: WidgetPod::new(Box::new(buttons)),
+            error: None,
+            get_step: None,
+            put_step: None,
+            on_finish: None,
+            on_cancel: None,
+        }
+    }
+
+    /// Builder-style method for adding a step.
+    pub fn with_step(mut self, title: impl Into<ArcStr>, widget: impl Widget<T> + 'static) -> Self {
+        self.add_step(title, widget, None);
+        self
+    }
+
+    /// Builder-style method for adding a step that has to be valid before the
+    /// wizard moves past it.
+    pub fn with_validated_step(
+        mut self,
+        title: impl Into<ArcStr>,
+        widget: impl Widget<T> + 'static,
+        validator: impl Fn(&T) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.add_step(title, widget, Some(Box::new(validator)));
+        self
+    }
+
+    /// Builder-style method for keeping the index of the current step in the data.
+    ///
+    /// Setting the index in the data moves to that step without validating the
+    /// steps in between.
+    pub fn with_step_lens(mut self, lens: impl Lens<T, usize> + 'static) -> Self {
+        let lens = Rc::new(lens);
+        let put_lens = lens.clone();
+        self.get_step = Some(Box::new(move |data| lens.get(data)));
+        self.put_step = Some(Box::new(move |data, step| put_lens.put(data, step)));
+        self
+    }
+
+    /// Builder-style method for a callback that is called when the user moves
+    /// forward from the last step.
+    pub fn on_finish(mut self, f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        self.on_finish = Some(Box::new(f));
+        self
+    }
+
+    /// Builder-style method for a callback that is called when the wizard is
+    /// cancelled.
+    pub fn on_cancel(mut self, f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        self.on_cancel = Some(Box::new(f));
+        self
+    }
+
+    fn add_step(
+        &mut self,
+        title: impl Into<ArcStr>,
+        widget: impl Widget<T> + 'static,
+        validator: Option<StepValidator<T>>,
+    ) {
+        let mut number = TextLayout::from_text((self.steps.len() + 1).to_string());
+        number.set_text_size(theme::TEXT_SIZE_NORMAL);
+        self.steps.push(Step {
+            title: TextLayout::from_text(title.into()),
+            number,
+            widget: WidgetPod::new(Box::new(widget)),
+            validator,
+        });
+    }
+
+    /// The index of the current step.
+    pub fn current_step(&self) -> usize {
+        self.current
+    }
+
+    /// The number of steps.
+    pub fn step_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    fn child_env(&self, env: &Env) -> Env {
+        env.clone()
+            .adding(Wizard::IS_FIRST_STEP, self.current == 0)
+            .adding(Wizard::IS_LAST_STEP, self.current + 1 >= self.steps.len())
+    }
+
+    fn go_to(&mut self, ctx: &mut EventCtx, data: &mut T, step: usize) {
+        trace!("moving to step {}", step);
+        self.current = step;
+        self.error = None;
+        if let Some(put_step) = &self.put_step {
+            put_step(data, step);
+        }
+        ctx.children_changed();
+        ctx.request_update();
+    }
+
+    fn next(&mut self, ctx: &mut EventCtx, data: &mut T, env: &Env) {
+        let Some(step) = self.steps.get(self.current) else {
+            return;
+        };
+        if let Some(Err(message)) = step.validator.as_ref().map(|validator| validator(data)) {
+            let mut error = TextLayout::from_text(message);
+            error.set_text_color(theme::FORM_ERROR_COLOR);
+            error.set_text_size(theme::FORM_ERROR_TEXT_SIZE);
+            self.error = Some(error);
+            ctx.request_layout();
+            return;
+        }
+        if self.current + 1 < self.steps.len() {
+            self.go_to(ctx, data, self.current + 1);
+        } else if let Some(on_finish) = &self.on_finish {
+            on_finish(ctx, data, env);
+        }
+    }
+
+    fn handle(&mut self, ctx: &mut EventCtx, selector: Selector, data: &mut T, env: &Env) {
+        if selector == Wizard::NEXT {
+            self.next(ctx, data, env);
+        } else if selector == Wizard::BACK {
+            if self.current > 0 {
+                self.go_to(ctx, data, self.current - 1);
+            }
+        } else if selector == Wizard::CANCEL {
+            if let Some(on_cancel) = &self.on_cancel {
+                on_cancel(ctx, data, env);
+            }
+        }
+        ctx.set_handled();
+    }
+
+    /// The center of the circle of a step in the header, for a wizard of `width`.
+    fn step_center(&self, step: usize, width: f64) -> Point {
+        let spacing = width / self.steps.len().max(1) as f64;
+        Point::new(spacing * (step as f64 + 0.5), STEP_RADIUS + 6.0)
+    }
+}
+
+impl<T: Data> Default for Wizard<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Wizard<T> {
+    #[instrument(name = "Wizard", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let selector = match event {
+            Event::Notification(note) => [Wizard::NEXT, Wizard::BACK, Wizard::CANCEL]
+                .into_iter()
+                .find(|selector| note.is(*selector)),
+            Event::Command(cmd) => [Wizard::NEXT, Wizard::BACK, Wizard::CANCEL]
+                .into_iter()
+                .find(|selector| cmd.is(*selector)),
+            _ => None,
+        };
+        if let Some(selector) = selector {
+            self.handle(ctx, selector, data, env);
+            return;
+        }
+
+        // Clicking a step that has been completed goes back to it.
+        if let Event::MouseDown(mouse) = event {
+            if mouse.pos.y < HEADER_HEIGHT {
+                let width = ctx.size().width;
+                let clicked = (0..self.current)
+                    .find(|step| self.step_center(*step, width).distance(mouse.pos) <= STEP_RADIUS);
+                if let Some(step) = clicked {
+                    self.go_to(ctx, data, step);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+        }
+
+        let env = self.child_env(env);
+        if event.should_propagate_to_hidden() {
+            for step in &mut self.steps {
+                step.widget.event(ctx, event, data, &env);
+            }
+        } else if let Some(step) = self.steps.get_mut(self.current) {
+            step.widget.event(ctx, event, data, &env);
+        }
+        self.buttons.event(ctx, event, data, &env);
+    }
+
+    #[instrument(name = "Wizard", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            if let Some(get_step) = &self.get_step {
+                self.current = get_step(data).min(self.steps.len().saturating_sub(1));
+            }
+        }
+        let env = self.child_env(env);
+        if event.should_propagate_to_hidden() {
+            for step in &mut self.steps {
+                step.widget.lifecycle(ctx, event, data, &env);
+            }
+        } else if let Some(step) = self.steps.get_mut(self.current) {
+            step.widget.lifecycle(ctx, event, data, &env);
+        }
+        self.buttons.lifecycle(ctx, event, data, &env);
+    }
+
+    #[instrument(
+        name = "Wizard",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if let Some(get_step) = &self.get_step {
+            let step = get_step(data).min(self.steps.len().saturating_sub(1));
+            if step != self.current {
+                self.current = step;
+                self.error = None;
+                ctx.children_changed();
+            }
+        }
+        let env = self.child_env(env);
+        for step in &mut self.steps {
+            if step.title.needs_rebuild_after_update(ctx) {
+                ctx.request_layout();
+            }
+        }
+        if let Some(step) = self.steps.get_mut(self.current) {
+            step.widget.update(ctx, data, &env);
+        }
+        self.buttons.update(ctx, data, &env);
+        ctx.request_paint();
+    }
+
+    #[instrument(name = "Wizard", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Wizard");
+        let env = self.child_env(env);
+        let width = match bc.is_width_bounded() {
+            true => bc.max().width,
+            false => bc.min().width.max(400.0),
+        };
+        let title_width = width / self.steps.len().max(1) as f64;
+        for step in &mut self.steps {
+            step.title.set_wrap_width(title_width);
+            step.title.rebuild_if_needed(ctx.text(), &env);
+            step.number.rebuild_if_needed(ctx.text(), &env);
+        }
+
+        let inner_width = (width - 2.0 * CONTENT_PADDING).max(0.0);
+        let buttons_bc = BoxConstraints::new(
+            Size::new(inner_width, 0.0),
+            Size::new(inner_width, f64::INFINITY),
+        );
+        let buttons_size = self.buttons.layout(ctx, &buttons_bc, data, &env);
+
+        let error_height = match &mut self.error {
+            Some(error) => {
+                error.set_wrap_width(inner_width);
+                error.rebuild_if_needed(ctx.text(), &env);
+                error.size().height + CONTENT_PADDING / 2.0
+            }
+            None => 0.0,
+        };
+
+        let chrome_height =
+            HEADER_HEIGHT + error_height + buttons_size.height + 3.0 * CONTENT_PADDING;
+        let content_bc = BoxConstraints::new(
+            Size::new(inner_width, (bc.min().height - chrome_height).max(0.0)),
+            Size::new(inner_width, (bc.max().height - chrome_height).max(0.0)),
+        );
+        let content_height = match self.steps.get_mut(self.current) {
+            Some(step) => {
+                let size = step.widget.layout(ctx, &content_bc, data, &env);
+                step.widget.set_origin(
+                    ctx,
+                    Point::new(CONTENT_PADDING, HEADER_HEIGHT + CONTENT_PADDING),
+                );
+                size.height
+            }
+            None => 0.0,
+        };
+
+        let buttons_y = HEADER_HEIGHT + content_height + error_height + 2.0 * CONTENT_PADDING;
+        self.buttons
+            .set_origin(ctx, Point::new(CONTENT_PADDING, buttons_y));
+
+        let size = bc.constrain(Size::new(width, content_height + chrome_height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Wizard", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let env = self.child_env(env);
+        let width = ctx.size().width;
+        let done_color = env.get(theme::PRIMARY_DARK);
+        let current_color = env.get(theme::PRIMARY_LIGHT);
+        let pending_color = env.get(theme::BORDER_LIGHT);
+
+        for step in 1..self.steps.len() {
+            let from = self.step_center(step - 1, width) + (STEP_RADIUS, 0.0);
+            let to = self.step_center(step, width) - (STEP_RADIUS, 0.0);
+            let color = match step <= self.current {
+                true => &done_color,
+                false => &pending_color,
+            };
+            ctx.stroke(Line::new(from, to), color, 2.0);
+        }
+        let centers: Vec<_> = (0..self.steps.len())
+            .map(|step| self.step_center(step, width))
+            .collect();
+        for (i, (step, center)) in self.steps.iter_mut().zip(centers).enumerate() {
+            let circle = Circle::new(center, STEP_RADIUS);
+            match i.cmp(&self.current) {
+                std::cmp::Ordering::Less => ctx.fill(circle, &done_color),
+                std::cmp::Ordering::Equal => ctx.fill(circle, &current_color),
+                std::cmp::Ordering::Greater => ctx.stroke(circle, &pending_color, 2.0),
+            }
+            let size = step.number.size();
+            step.number.draw(ctx, center - size.to_vec2() / 2.0);
+            let size = step.title.size();
+            let title_origin =
+                Point::new(center.x - size.width / 2.0, center.y + STEP_RADIUS + 4.0);
+            step.title.draw(ctx, title_origin);
+        }
+        let divider = Line::new((0.0, HEADER_HEIGHT - 0.5), (width, HEADER_HEIGHT - 0.5));
+        ctx.stroke(divider, &env.get(theme::BORDER_DARK), 1.0);
+
+        if let Some(step) = self.steps.get_mut(self.current) {
+            step.widget.paint(ctx, data, &env);
+            if let Some(error) = &self.error {
+                let rect = step.widget.layout_rect();
+                error.draw(ctx, Point::new(rect.x0, rect.y1 + CONTENT_PADDING / 2.0));
+            }
+        }
+        self.buttons.paint(ctx, data, &env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("step {} of {}", self.current + 1, self.steps.len()),
+            children: self
+                .steps
+                .get(self.current)
+                .map(|step| step.widget.widget().debug_state(data))
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
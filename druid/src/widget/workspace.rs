@@ -0,0 +1,679 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A tabbed top-level shell for browser-like and editor-like apps.
+
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{Circle, Line};
+use crate::text::TextLayout;
+use crate::widget::prelude::*;
+use crate::widget::Scope;
+use crate::{
+    theme, HotKey, KbKey, Lens, MouseButton, Point, RawMods, Rect, Selector, SysMods, WidgetExt,
+    WidgetPod,
+};
+
+/// The height of the tab strip.
+const TAB_HEIGHT: f64 = 28.0;
+/// The horizontal padding around the title of each tab.
+const TAB_PADDING: f64 = 8.0;
+/// The size of the close button of each tab.
+const CLOSE_SIZE: f64 = 14.0;
+/// The number of closed tabs that are remembered by default.
+const DEFAULT_MAX_CLOSED: usize = 20;
+
+type StateFn<T, S> = Rc<dyn Fn(&str, &T) -> S>;
+type BodyFn<T, S> = Box<dyn Fn(&str) -> Box<dyn Widget<TabData<T, S>>>>;
+type TitleFn<T> = Box<dyn Fn(&str, &T) -> String>;
+
+/// The data of a tab in a [`Workspace`]: the app data, and the tab's own state.
+#[derive(Clone, Data, Lens)]
+pub struct TabData<T, S> {
+    /// The data of the app, shared by all tabs.
+    pub app: T,
+    /// The state of this tab.
+    pub tab: S,
+}
+
+/// The open and recently closed tabs of a [`Workspace`].
+///
+/// The session can be serialized with [`to_string`] and restored with [`parse`].
+/// Tab ids must not contain line breaks for this to work.
+///
+/// [`to_string`]: ToString::to_string
+/// [`parse`]: str::parse
+#[derive(Debug, Clone, Default, PartialEq, Eq, Data)]
+pub struct WorkspaceSession {
+    /// The ids of the open tabs, in order.
+    #[data(eq)]
+    pub tabs: Vec<String>,
+    /// The index of the selected tab.
+    pub active: usize,
+    /// The ids of the closed tabs, with the most recently closed last.
+    #[data(eq)]
+    pub closed: Vec<String>,
+}
+
+/// An error parsing a [`WorkspaceSession`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceSessionParseError {
+    line: usize,
+}
+
+struct OpenTab<T> {
+    id: String,
+    title: TextLayout<String>,
+    body: WidgetPod<T, Box<dyn Widget<T>>>,
+}
+
+enum WorkspaceRequest<'a> {
+    Open(&'a String),
+    Close,
+    Reopen,
+    SetSession(&'a WorkspaceSession),
+}
+
+/// A part of the tab strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StripHit {
+    Tab(usize),
+    Close(usize),
+    NewTab,
+}
+
+/// A top-level shell of tabs, as found in browsers and editors.
+///
+/// Each tab is identified by a string id, such as a path or a URL, and has its own
+/// state of type `S`, created when the tab is opened. The body of a tab sees both
+/// the app data and its state, as [`TabData`]; the state is kept in a [`Scope`], so
+/// it's independent of every other tab.
+///
+/// Tabs are opened with [`Workspace::OPEN_TAB`] and closed with
+/// [`Workspace::CLOSE_TAB`], which can be sent as commands or as notifications
+/// from the tabs. Closed tabs are remembered, and [`Workspace::REOPEN_CLOSED_TAB`]
+/// opens the last one again. The tab strip and the usual shortcuts do the same:
+/// <kbd>Ctrl</kbd>+<kbd>W</kbd> closes the selected tab,
+/// <kbd>Ctrl</kbd>+<kbd>Shift</kbd>+<kbd>T</kbd> reopens a closed tab,
+/// <kbd>Ctrl</kbd>+<kbd>T</kbd> opens a [new tab], and
+/// <kbd>Ctrl</kbd>+<kbd>Tab</kbd> moves between tabs.
+///
+/// The current [`WorkspaceSession`] is sent as a [`Workspace::SESSION_CHANGED`]
+/// notification whenever it changes, so it can be persisted, and it can be restored
+/// with [`with_session`] or [`Workspace::SET_SESSION`].
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{TabData, TextBox, Workspace};
+/// use druid::{Widget, WidgetExt};
+///
+/// fn editor() -> impl Widget<()> {
+///     Workspace::new(
+///         |_id, _data| String::new(),
+///         |_id| TextBox::multiline().lens(TabData::tab).expand(),
+///     )
+///     .with_new_tab("untitled")
+/// }
+/// ```
+///
+/// [new tab]: Workspace::with_new_tab
+/// [`with_session`]: Workspace::with_session
+pub struct Workspace<T, S> {
+    make_state: StateFn<T, S>,
+    make_body: BodyFn<T, S>,
+    make_title: TitleFn<T>,
+    new_tab: Option<String>,
+    tabs: Vec<OpenTab<T>>,
+    active: usize,
+    closed: Vec<String>,
+    max_closed: usize,
+    hot: Option<StripHit>,
+}
+
+impl Workspace<(), ()> {
+    /// Open the tab with the given id after the selected tab, or select it if it's
+    /// already open.
+    pub const OPEN_TAB: Selector<String> = Selector::new("druid-builtin.workspace-open-tab");
+
+    /// Close the selected tab.
+    pub const CLOSE_TAB: Selector = Selector::new("druid-builtin.workspace-close-tab");
+
+    /// Open the most recently closed tab again.
+    pub const REOPEN_CLOSED_TAB: Selector =
+        Selector::new("druid-builtin.workspace-reopen-closed-tab");
+
+    /// Replace the open tabs with those of a session.
+    pub const SET_SESSION: Selector<WorkspaceSession> =
+        Selector::new("druid-builtin.workspace-set-session");
+
+    /// Sent as a notification with the current session whenever it changes.
+    pub const SESSION_CHANGED: Selector<WorkspaceSession> =
+        Selector::new("druid-builtin.workspace-session-changed");
+}
+
+impl<T: Data, S: Data> Workspace<T, S> {
+    /// Create a workspace without tabs.
+    ///
+    /// `make_state` creates the state of a tab when it is opened, and `make_body`
+    /// creates its widget.
+    pub fn new<W: Widget<TabData<T, S>> + 'static>(
+        make_state: impl Fn(&str, &T) -> S + 'static,
+        make_body: impl Fn(&str) -> W + 'static,
+    ) -> Self {
+        Workspace {
+            make_state: Rc::new(make_state),
+            make_body: Box::new(move |id| make_body(id).boxed()),
+            make_title: Box::new(|id, _| id.to_string()),
+            new_tab: None,
+            tabs: Vec::new(),
+            active: 0,
+            closed: Vec::new(),
+            max_closed: DEFAULT_MAX_CLOSED,
+            hot: None,
+        }
+    }
+
+    /// Builder-style method to set the title of each tab.
+    ///
+    /// By default, the title is the id of the tab.
+    pub fn with_tab_title(mut self, title: impl Fn(&str, &T) -> String + 'static) -> Self {
+        self.make_title = Box::new(title);
+        self
+    }
+
+    /// Builder-style method to set the id of the tab opened by the new tab button
+    /// and <kbd>Ctrl</kbd>+<kbd>T</kbd>.
+    ///
+    /// Without it, there is no new tab button.
+    pub fn with_new_tab(mut self, id: impl Into<String>) -> Self {
+        self.new_tab = Some(id.into());
+        self
+    }
+
+    /// Builder-style method to set how many closed tabs are remembered.
+    ///
+    /// The default is 20.
+    pub fn with_max_closed(mut self, max_closed: usize) -> Self {
+        self.max_closed = max_closed;
+        self
+    }
+
+    /// Builder-style method to open the tabs of a session.
+    pub fn with_session(mut self, session: WorkspaceSession) -> Self {
+        self.restore(session);
+        self
+    }
+
+    /// The current session.
+    pub fn session(&self) -> WorkspaceSession {
+        WorkspaceSession {
+            tabs: self.tabs.iter().map(|tab| tab.id.clone()).collect(),
+            active: self.active,
+            closed: self.closed.clone(),
+        }
+    }
+
+    fn make_tab(&self, id: String) -> OpenTab<T> {
+        let make_state = self.make_state.clone();
+        let state_id = id.clone();
+        let body = Scope::from_lens(
+            move |app: T| {
+                let tab = make_state(&state_id, &app);
+                TabData { app, tab }
+            },
+            TabData::app,
+            (self.make_body)(&id),
+        );
+        OpenTab {
+            id,
+            title: TextLayout::new(),
+            body: WidgetPod::new(body.boxed()),
+        }
+    }
+
+    fn restore(&mut self, session: WorkspaceSession) {
+        self.tabs = Vec::new();
+        for id in session.tabs {
+            let tab = self.make_tab(id);
+            self.tabs.push(tab);
+        }
+        self.active = session.active.min(self.tabs.len().saturating_sub(1));
+        self.closed = session.closed;
+        self.hot = None;
+    }
+
+    fn open(&mut self, ctx: &mut EventCtx, id: String, data: &T) {
+        if let Some(idx) = self.tabs.iter().position(|tab| tab.id == id) {
+            self.select(ctx, idx);
+            return;
+        }
+        trace!("Workspace {:?} opened tab '{}'", ctx.widget_id(), id);
+        let mut tab = self.make_tab(id);
+        set_title(&self.make_title, &mut tab, data);
+        let idx = match self.tabs.is_empty() {
+            true => 0,
+            false => self.active + 1,
+        };
+        self.tabs.insert(idx, tab);
+        self.active = idx;
+        self.changed(ctx);
+    }
+
+    fn close(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if idx >= self.tabs.len() {
+            return;
+        }
+        let tab = self.tabs.remove(idx);
+        trace!("Workspace {:?} closed tab '{}'", ctx.widget_id(), tab.id);
+        self.closed.retain(|id| *id != tab.id);
+        self.closed.push(tab.id);
+        let excess = self.closed.len().saturating_sub(self.max_closed);
+        self.closed.drain(..excess);
+        if idx < self.active || self.active >= self.tabs.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+        self.changed(ctx);
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, idx: usize) {
+        if idx < self.tabs.len() && idx != self.active {
+            self.active = idx;
+            self.changed(ctx);
+        }
+    }
+
+    fn changed(&mut self, ctx: &mut EventCtx) {
+        self.hot = None;
+        ctx.children_changed();
+        ctx.submit_notification_without_warning(Workspace::SESSION_CHANGED.with(self.session()));
+    }
+
+    fn handle(&mut self, ctx: &mut EventCtx, request: WorkspaceRequest, data: &T) {
+        match request {
+            WorkspaceRequest::Open(id) => self.open(ctx, id.clone(), data),
+            WorkspaceRequest::Close => self.close(ctx, self.active),
+            WorkspaceRequest::Reopen => {
+                if let Some(id) = self.closed.pop() {
+                    self.open(ctx, id, data);
+                }
+            }
+            WorkspaceRequest::SetSession(session) => {
+                self.restore(session.clone());
+                for tab in &mut self.tabs {
+                    set_title(&self.make_title, tab, data);
+                }
+                ctx.children_changed();
+            }
+        }
+    }
+
+    /// The rects of the tabs in the strip, and of the new tab button.
+    fn strip_rects(&self) -> (Vec<Rect>, Rect) {
+        let mut x = 0.0;
+        let tabs = self
+            .tabs
+            .iter()
+            .map(|tab| {
+                let width = tab.title.size().width + 2.5 * TAB_PADDING + CLOSE_SIZE;
+                let rect = Rect::new(x, 0.0, x + width, TAB_HEIGHT);
+                x += width;
+                rect
+            })
+            .collect();
+        (tabs, Rect::new(x, 0.0, x + TAB_HEIGHT, TAB_HEIGHT))
+    }
+
+    fn strip_hit(&self, pos: Point) -> Option<StripHit> {
+        if pos.y >= TAB_HEIGHT {
+            return None;
+        }
+        let (tabs, new_tab) = self.strip_rects();
+        if let Some(idx) = tabs.iter().position(|rect| rect.contains(pos)) {
+            return match close_rect(tabs[idx]).contains(pos) {
+                true => Some(StripHit::Close(idx)),
+                false => Some(StripHit::Tab(idx)),
+            };
+        }
+        (self.new_tab.is_some() && new_tab.contains(pos)).then_some(StripHit::NewTab)
+    }
+}
+
+impl<T: Data, S: Data> Widget<T> for Workspace<T, S> {
+    #[instrument(name = "Workspace", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Notification(_) = event {
+            if let Some(request) = workspace_request(event) {
+                self.handle(ctx, request, data);
+                ctx.set_handled();
+            }
+            return;
+        }
+
+        match event {
+            Event::MouseDown(mouse) => match self.strip_hit(mouse.pos) {
+                Some(StripHit::Tab(idx)) if mouse.button == MouseButton::Middle => {
+                    self.close(ctx, idx)
+                }
+                Some(StripHit::Tab(idx)) => self.select(ctx, idx),
+                Some(StripHit::Close(idx)) => self.close(ctx, idx),
+                Some(StripHit::NewTab) => {
+                    if let Some(id) = self.new_tab.clone() {
+                        self.open(ctx, id, data);
+                    }
+                }
+                None => (),
+            },
+            Event::MouseMove(mouse) => {
+                let hot = self.strip_hit(mouse.pos);
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            _ => (),
+        }
+
+        if event.should_propagate_to_hidden() {
+            for tab in &mut self.tabs {
+                tab.body.event(ctx, event, data, env);
+            }
+        } else if let Some(tab) = self.tabs.get_mut(self.active) {
+            tab.body.event(ctx, event, data, env);
+        }
+
+        if ctx.is_handled() {
+            return;
+        }
+        match event {
+            Event::Command(_) => {
+                if let Some(request) = workspace_request(event) {
+                    self.handle(ctx, request, data);
+                    ctx.set_handled();
+                }
+            }
+            Event::KeyDown(key) => {
+                let next = (self.active + 1) % self.tabs.len().max(1);
+                let previous = (self.active + self.tabs.len().max(1) - 1) % self.tabs.len().max(1);
+                if HotKey::new(SysMods::Cmd, "w").matches(key) {
+                    self.close(ctx, self.active);
+                } else if HotKey::new(SysMods::CmdShift, "T").matches(key) {
+                    self.handle(ctx, WorkspaceRequest::Reopen, data);
+                } else if HotKey::new(SysMods::Cmd, "t").matches(key) && self.new_tab.is_some() {
+                    let id = self.new_tab.clone().unwrap_or_default();
+                    self.open(ctx, id, data);
+                } else if HotKey::new(RawMods::Ctrl, KbKey::Tab).matches(key) {
+                    self.select(ctx, next);
+                } else if HotKey::new(RawMods::CtrlShift, KbKey::Tab).matches(key) {
+                    self.select(ctx, previous);
+                } else {
+                    return;
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(name = "Workspace", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            for tab in &mut self.tabs {
+                set_title(&self.make_title, tab, data);
+            }
+        }
+        if let LifeCycle::HotChanged(false) = event {
+            self.hot = None;
+            ctx.request_paint();
+        }
+
+        if event.should_propagate_to_hidden() {
+            for tab in &mut self.tabs {
+                tab.body.lifecycle(ctx, event, data, env);
+            }
+        } else if let Some(tab) = self.tabs.get_mut(self.active) {
+            tab.body.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(
+        name = "Workspace",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for tab in &mut self.tabs {
+            if set_title(&self.make_title, tab, data) || tab.title.needs_rebuild_after_update(ctx) {
+                ctx.request_layout();
+            }
+            tab.body.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Workspace", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Workspace");
+        for tab in &mut self.tabs {
+            tab.title.rebuild_if_needed(ctx.text(), env);
+        }
+
+        let size = bc.max();
+        let body = Size::new(size.width, (size.height - TAB_HEIGHT).max(0.0));
+        if let Some(tab) = self.tabs.get_mut(self.active) {
+            tab.body
+                .layout(ctx, &BoxConstraints::tight(body), data, env);
+            tab.body.set_origin(ctx, Point::new(0.0, TAB_HEIGHT));
+        }
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Workspace", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let header = Rect::new(0.0, 0.0, ctx.size().width, TAB_HEIGHT);
+        ctx.fill(header, &env.get(theme::BACKGROUND_DARK));
+        let (rects, new_tab) = self.strip_rects();
+        let text_color = env.get(theme::TEXT_COLOR);
+        let hover_color = env.get(theme::BUTTON_LIGHT);
+
+        ctx.with_save(|ctx| {
+            ctx.clip(header);
+            for (idx, (tab, rect)) in self.tabs.iter().zip(&rects).enumerate() {
+                if idx == self.active {
+                    ctx.fill(*rect, &env.get(theme::BACKGROUND_LIGHT));
+                } else if self.hot == Some(StripHit::Tab(idx)) {
+                    ctx.fill(*rect, &hover_color.with_alpha(0.3));
+                }
+                let y = (TAB_HEIGHT - tab.title.size().height) / 2.0;
+                tab.title.draw(ctx, (rect.x0 + TAB_PADDING, y));
+
+                let close = close_rect(*rect);
+                if self.hot == Some(StripHit::Close(idx)) {
+                    let circle = Circle::new(close.center(), CLOSE_SIZE / 2.0);
+                    ctx.fill(circle, &hover_color);
+                }
+                let cross = close.inset(-CLOSE_SIZE / 4.0);
+                ctx.stroke(
+                    Line::new(cross.origin(), (cross.x1, cross.y1)),
+                    &text_color,
+                    1.5,
+                );
+                ctx.stroke(
+                    Line::new((cross.x0, cross.y1), (cross.x1, cross.y0)),
+                    &text_color,
+                    1.5,
+                );
+            }
+
+            if self.new_tab.is_some() {
+                if self.hot == Some(StripHit::NewTab) {
+                    ctx.fill(new_tab, &hover_color.with_alpha(0.3));
+                }
+                let plus = Rect::from_center_size(new_tab.center(), (CLOSE_SIZE, CLOSE_SIZE));
+                let center = plus.center();
+                ctx.stroke(
+                    Line::new((plus.x0, center.y), (plus.x1, center.y)),
+                    &text_color,
+                    1.5,
+                );
+                ctx.stroke(
+                    Line::new((center.x, plus.y0), (center.x, plus.y1)),
+                    &text_color,
+                    1.5,
+                );
+            }
+        });
+
+        if let Some(tab) = self.tabs.get_mut(self.active) {
+            tab.body.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: self
+                .tabs
+                .get(self.active)
+                .map(|tab| tab.id.clone())
+                .unwrap_or_default(),
+            children: self
+                .tabs
+                .get(self.active)
+                .map(|tab| tab.body.widget().debug_state(data))
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Update the title of a tab, and return whether it changed.
+fn set_title<T>(make_title: &TitleFn<T>, tab: &mut OpenTab<T>, data: &T) -> bool {
+    let title = make_title(&tab.id, data);
+    if tab.title.text() == Some(&title) {
+        return false;
+    }
+    tab.title.set_text(title);
+    true
+}
+
+fn workspace_request(event: &Event) -> Option<WorkspaceRequest<'_>> {
+    let (open, close, reopen, session) = match event {
+        Event::Notification(note) => (
+            note.get(Workspace::OPEN_TAB),
+            note.is(Workspace::CLOSE_TAB),
+            note.is(Workspace::REOPEN_CLOSED_TAB),
+            note.get(Workspace::SET_SESSION),
+        ),
+        Event::Command(cmd) => (
+            cmd.get(Workspace::OPEN_TAB),
+            cmd.is(Workspace::CLOSE_TAB),
+            cmd.is(Workspace::REOPEN_CLOSED_TAB),
+            cmd.get(Workspace::SET_SESSION),
+        ),
+        _ => return None,
+    };
+    match (open, session) {
+        (Some(id), _) => Some(WorkspaceRequest::Open(id)),
+        (None, Some(session)) => Some(WorkspaceRequest::SetSession(session)),
+        (None, None) if close => Some(WorkspaceRequest::Close),
+        (None, None) if reopen => Some(WorkspaceRequest::Reopen),
+        (None, None) => None,
+    }
+}
+
+/// The close button of the tab with the given rect.
+fn close_rect(tab: Rect) -> Rect {
+    Rect::from_center_size(
+        (
+            tab.x1 - TAB_PADDING / 2.0 - CLOSE_SIZE / 2.0,
+            tab.center().y,
+        ),
+        (CLOSE_SIZE, CLOSE_SIZE),
+    )
+}
+
+/// One line per tab, as `tab <id>`, one line `active <index>`, and one line per
+/// closed tab, as `closed <id>`.
+impl fmt::Display for WorkspaceSession {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "active {}", self.active)?;
+        for id in &self.tabs {
+            writeln!(f, "tab {}", id)?;
+        }
+        for id in &self.closed {
+            writeln!(f, "closed {}", id)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for WorkspaceSession {
+    type Err = WorkspaceSessionParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut session = WorkspaceSession::default();
+        for (idx, line) in s.lines().enumerate() {
+            let err = WorkspaceSessionParseError { line: idx + 1 };
+            match line.split_once(' ') {
+                Some(("tab", id)) => session.tabs.push(id.to_string()),
+                Some(("closed", id)) => session.closed.push(id.to_string()),
+                Some(("active", active)) => session.active = active.parse().map_err(|_| err)?,
+                None if line.trim().is_empty() => (),
+                _ => return Err(err),
+            }
+        }
+        if session.active >= session.tabs.len().max(1) {
+            return Err(WorkspaceSessionParseError { line: 1 });
+        }
+        Ok(session)
+    }
+}
+
+impl fmt::Display for WorkspaceSessionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid workspace session on line {}", self.line)
+    }
+}
+
+impl std::error::Error for WorkspaceSessionParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_round_trip() {
+        let session = WorkspaceSession {
+            tabs: vec!["src/main.rs".into(), "notes with spaces.txt".into()],
+            active: 1,
+            closed: vec!["README.md".into()],
+        };
+        let parsed: WorkspaceSession = session.to_string().parse().unwrap();
+        assert_eq!(parsed, session);
+
+        assert!("tab a\nactive 3\n".parse::<WorkspaceSession>().is_err());
+        assert!("window a\n".parse::<WorkspaceSession>().is_err());
+        assert_eq!(
+            "".parse::<WorkspaceSession>(),
+            Ok(WorkspaceSession::default())
+        );
+    }
+}
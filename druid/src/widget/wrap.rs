@@ -0,0 +1,332 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container that lays out its children in lines, wrapping when they run out of space.
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::flex::Spacing;
+use crate::widget::prelude::*;
+use crate::widget::{Axis, CrossAxisAlignment, MainAxisAlignment};
+use crate::{Data, Point, Rect, WidgetPod};
+
+/// A line of children in a [`Wrap`].
+struct Run {
+    /// The index of the first child after this line.
+    end: usize,
+    /// The size of the children and the spacing between them, on the main axis.
+    major: f64,
+    minor: f64,
+    /// The largest distance from a child's baseline to its top, for baseline alignment.
+    above_baseline: f64,
+}
+
+/// A container that lays out its children one after the other, starting a new line
+/// when the next child doesn't fit.
+///
+/// This is like a [`Flex`] that wraps its children onto as many lines as needed,
+/// for things like tag clouds, toolbars and lists of chips. A `Wrap` row fills lines
+/// from left to right and adds lines below; a column fills them from top to bottom,
+/// and adds them to the right.
+///
+/// The spacing between children on a line and between lines can be set separately.
+/// The [`MainAxisAlignment`] aligns the children within each line, relative to the
+/// longest line or to the minimum size of the container, whichever is larger. The
+/// [`CrossAxisAlignment`] aligns children of different sizes within a line, and the
+/// run alignment arranges the lines when the container is larger than they are.
+///
+/// Children get the size of the container on the main axis as their maximum, so a
+/// child that is larger is put on its own line.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Wrap};
+///
+/// let toolbar = Wrap::<()>::row()
+///     .main_axis_spacing(4.0)
+///     .cross_axis_spacing(4.0)
+///     .with_child(Button::new("Cut"))
+///     .with_child(Button::new("Copy"))
+///     .with_child(Button::new("Paste"));
+/// ```
+///
+/// [`Flex`]: super::Flex
+pub struct Wrap<T> {
+    direction: Axis,
+    main_spacing: f64,
+    cross_spacing: f64,
+    main_alignment: MainAxisAlignment,
+    cross_alignment: CrossAxisAlignment,
+    run_alignment: MainAxisAlignment,
+    children: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+}
+
+impl<T: Data> Wrap<T> {
+    /// Create a new `Wrap` that fills lines along the provided axis.
+    pub fn for_axis(axis: Axis) -> Self {
+        Wrap {
+            direction: axis,
+            main_spacing: 0.0,
+            cross_spacing: 0.0,
+            main_alignment: MainAxisAlignment::Start,
+            cross_alignment: CrossAxisAlignment::Center,
+            run_alignment: MainAxisAlignment::Start,
+            children: Vec::new(),
+        }
+    }
+
+    /// Create a new `Wrap` with horizontal lines.
+    pub fn row() -> Self {
+        Self::for_axis(Axis::Horizontal)
+    }
+
+    /// Create a new `Wrap` with vertical lines.
+    pub fn column() -> Self {
+        Self::for_axis(Axis::Vertical)
+    }
+
+    /// Builder-style method to set the spacing between children on a line.
+    pub fn main_axis_spacing(mut self, spacing: f64) -> Self {
+        self.set_main_axis_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method to set the spacing between lines.
+    pub fn cross_axis_spacing(mut self, spacing: f64) -> Self {
+        self.set_cross_axis_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method to set how children are aligned within each line.
+    pub fn main_axis_alignment(mut self, alignment: MainAxisAlignment) -> Self {
+        self.set_main_axis_alignment(alignment);
+        self
+    }
+
+    /// Builder-style method to set how children of different sizes are aligned
+    /// across a line.
+    pub fn cross_axis_alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.set_cross_axis_alignment(alignment);
+        self
+    }
+
+    /// Builder-style method to set how the lines are arranged when there is space
+    /// left over on the cross axis.
+    pub fn run_alignment(mut self, alignment: MainAxisAlignment) -> Self {
+        self.set_run_alignment(alignment);
+        self
+    }
+
+    /// Builder-style variant of [`add_child`].
+    ///
+    /// [`add_child`]: Wrap::add_child
+    pub fn with_child(mut self, child: impl Widget<T> + 'static) -> Self {
+        self.add_child(child);
+        self
+    }
+
+    /// Set the spacing between children on a line.
+    pub fn set_main_axis_spacing(&mut self, spacing: f64) {
+        self.main_spacing = spacing;
+    }
+
+    /// Set the spacing between lines.
+    pub fn set_cross_axis_spacing(&mut self, spacing: f64) {
+        self.cross_spacing = spacing;
+    }
+
+    /// Set how children are aligned within each line.
+    pub fn set_main_axis_alignment(&mut self, alignment: MainAxisAlignment) {
+        self.main_alignment = alignment;
+    }
+
+    /// Set how children of different sizes are aligned across a line.
+    pub fn set_cross_axis_alignment(&mut self, alignment: CrossAxisAlignment) {
+        self.cross_alignment = alignment;
+    }
+
+    /// Set how the lines are arranged when there is space left over on the cross axis.
+    pub fn set_run_alignment(&mut self, alignment: MainAxisAlignment) {
+        self.run_alignment = alignment;
+    }
+
+    /// Add a child widget.
+    ///
+    /// This should be followed by a call to [`children_changed`] if the `Wrap` is
+    /// already in use.
+    ///
+    /// [`children_changed`]: crate::EventCtx::children_changed
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static) {
+        self.children.push(WidgetPod::new(Box::new(child)));
+    }
+
+    /// Whether children are aligned on their baselines.
+    ///
+    /// As with [`Flex`](super::Flex), this only applies to horizontal lines.
+    fn uses_baseline(&self) -> bool {
+        self.cross_alignment == CrossAxisAlignment::Baseline && self.direction == Axis::Horizontal
+    }
+}
+
+impl<T: Data> Widget<T> for Wrap<T> {
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.update(ctx, data, env);
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Wrap");
+        let axis = self.direction;
+        let max_major = axis.major(bc.max());
+        let child_bc = bc.loosen();
+        let uses_baseline = self.uses_baseline();
+
+        // Measure the children and break them into lines.
+        let mut sizes = Vec::with_capacity(self.children.len());
+        let mut runs: Vec<Run> = Vec::new();
+        let mut run: Option<Run> = None;
+        for (index, child) in self.children.iter_mut().enumerate() {
+            let size = child.layout(ctx, &child_bc, data, env);
+            let major = axis.major(size);
+            let above_baseline = match uses_baseline {
+                true => size.height - child.baseline_offset(),
+                false => 0.0,
+            };
+            sizes.push(size);
+            match &mut run {
+                Some(current) if current.major + self.main_spacing + major <= max_major => {
+                    current.end = index + 1;
+                    current.major += self.main_spacing + major;
+                    current.minor = current.minor.max(axis.minor(size));
+                    current.above_baseline = current.above_baseline.max(above_baseline);
+                }
+                _ => {
+                    runs.extend(run.take());
+                    run = Some(Run {
+                        end: index + 1,
+                        major,
+                        minor: axis.minor(size),
+                        above_baseline,
+                    });
+                }
+            }
+        }
+        runs.extend(run);
+
+        if uses_baseline {
+            // Lines are tall enough for the children on either side of the baseline.
+            let mut start = 0;
+            for run in &mut runs {
+                let below_baseline = self.children[start..run.end]
+                    .iter()
+                    .map(|child| child.baseline_offset())
+                    .fold(0.0, f64::max);
+                run.minor = run.minor.max(run.above_baseline + below_baseline);
+                start = run.end;
+            }
+        }
+
+        let longest = runs.iter().map(|run| run.major).fold(0.0, f64::max);
+        let major = longest.max(axis.major(bc.min()));
+        let total_minor = runs.iter().map(|run| run.minor).sum::<f64>()
+            + self.cross_spacing * runs.len().saturating_sub(1) as f64;
+        let minor = total_minor.max(axis.minor(bc.min()));
+        let size = bc.constrain(Size::from(axis.pack(major, minor)));
+
+        let mut run_spacing = Spacing::new(
+            self.run_alignment,
+            axis.minor(size) - total_minor,
+            runs.len(),
+        );
+        let mut minor_offset = run_spacing.next().unwrap_or(0.0);
+        let mut child_paint_rect = Rect::ZERO;
+        let mut start = 0;
+        for run in &runs {
+            let count = run.end - start;
+            let mut spacing = Spacing::new(self.main_alignment, major - run.major, count);
+            let mut major_offset = spacing.next().unwrap_or(0.0);
+            for (child, size) in self.children[start..run.end]
+                .iter_mut()
+                .zip(&sizes[start..run.end])
+            {
+                let mut child_size = *size;
+                if self.cross_alignment == CrossAxisAlignment::Fill {
+                    let fill_bc = BoxConstraints::tight(Size::from(
+                        axis.pack(axis.major(child_size), run.minor),
+                    ));
+                    child_size = child.layout(ctx, &fill_bc, data, env);
+                }
+                let cross = match uses_baseline {
+                    true => run.above_baseline - (child_size.height - child.baseline_offset()),
+                    false => self
+                        .cross_alignment
+                        .align(run.minor - axis.minor(child_size)),
+                };
+                child.set_origin(
+                    ctx,
+                    Point::from(axis.pack(major_offset, minor_offset + cross)),
+                );
+                child_paint_rect = child_paint_rect.union(child.paint_rect());
+                major_offset +=
+                    axis.major(child_size) + self.main_spacing + spacing.next().unwrap_or(0.0);
+            }
+            minor_offset += run.minor + self.cross_spacing + run_spacing.next().unwrap_or(0.0);
+            start = run.end;
+        }
+
+        let insets = child_paint_rect - size.to_rect();
+        ctx.set_paint_insets(insets);
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children_state = self
+            .children
+            .iter()
+            .map(|child| child.widget().debug_state(data))
+            .collect();
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: children_state,
+            ..Default::default()
+        }
+    }
+}
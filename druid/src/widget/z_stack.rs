@@ -22,9 +22,15 @@ use crate::{
 /// A container that stacks its children on top of each other.
 ///
 /// The container has a baselayer which has the lowest z-index and determines the size of the
-/// container.
+/// container, unless it is set to [`size_to_largest`] child.
+///
+/// Mouse events go to the topmost layers first; layers below a layer that is hot don't
+/// become hot themselves.
+///
+/// [`size_to_largest`]: ZStack::size_to_largest
 pub struct ZStack<T> {
     layers: Vec<ZChild<T>>,
+    size_to_largest: bool,
 }
 
 struct ZChild<T> {
@@ -49,9 +55,20 @@ impl<T: Data> ZStack<T> {
                 position: UnitPoint::CENTER,
                 offset: Vec2::ZERO,
             }],
+            size_to_largest: false,
         }
     }
 
+    /// Builder-style method to size the ZStack to its largest layer, instead of the base
+    /// layer.
+    ///
+    /// The `relative_size` of the other layers is then relative to the size of the ZStack,
+    /// and the base layer is aligned in the center.
+    pub fn size_to_largest(mut self, size_to_largest: bool) -> Self {
+        self.size_to_largest = size_to_largest;
+        self
+    }
+
     /// Builder-style method to add a new child to the Z-Stack.
     ///
     /// The child is added directly above the base layer.
@@ -148,7 +165,26 @@ impl<T: Data> Widget<T> for ZStack<T> {
         //Layout base layer
 
         let base_layer = self.layers.last_mut().unwrap();
-        let base_size = base_layer.child.layout(ctx, bc, data, env);
+        let mut base_size = base_layer.child.layout(ctx, bc, data, env);
+
+        if self.size_to_largest {
+            // Measure the other layers with the space we have, then lay them out again
+            // relative to the size of the largest layer.
+            let loose = bc.loosen();
+            let largest = self
+                .layers
+                .iter_mut()
+                .rev()
+                .skip(1)
+                .fold(base_size, |size, layer| {
+                    let layer_size = layer.child.layout(ctx, &loose, data, env);
+                    Size::new(
+                        size.width.max(layer_size.width),
+                        size.height.max(layer_size.height),
+                    )
+                });
+            base_size = bc.constrain(largest);
+        }
 
         //Layout other layers
         let other_layers = self.layers.len() - 1;
@@ -160,8 +196,9 @@ impl<T: Data> Widget<T> for ZStack<T> {
                 .layout(ctx, &BoxConstraints::new(Size::ZERO, max_size), data, env);
         }
 
-        //Set origin for all Layers and calculate paint insets
+        //Set origin for all Layers and calculate paint and hit insets
         let mut paint_rect = Rect::ZERO;
+        let mut hit_rect = Rect::ZERO;
 
         for layer in self.layers.iter_mut() {
             let remaining = base_size - layer.child.layout_rect().size();
@@ -169,9 +206,11 @@ impl<T: Data> Widget<T> for ZStack<T> {
             layer.child.set_origin(ctx, origin);
 
             paint_rect = paint_rect.union(layer.child.paint_rect());
+            hit_rect = hit_rect.union(layer.child.hit_rect());
         }
 
         ctx.set_paint_insets(paint_rect - base_size.to_rect());
+        ctx.set_hit_insets(hit_rect - base_size.to_rect());
         ctx.set_baseline_offset(self.layers.last().unwrap().child.baseline_offset());
 
         base_size
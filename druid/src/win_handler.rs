@@ -18,9 +18,10 @@ use std::any::{Any, TypeId};
 use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::kurbo::Size;
-use crate::piet::Piet;
+use crate::piet::{Piet, Text as _};
 use crate::shell::{
     text::InputHandler, Application, FileDialogToken, FileInfo, IdleToken, MouseEvent, Region,
     Scale, TextFieldToken, WinHandler, WindowHandle,
@@ -30,10 +31,14 @@ use crate::app_delegate::{AppDelegate, DelegateCtx};
 use crate::core::CommandQueue;
 use crate::ext_event::{ExtEventHost, ExtEventSink};
 use crate::menu::{ContextMenu, MenuItemId, MenuManager};
+use crate::subscription::Subscriber;
+use crate::theme::{SystemThemes, Theme};
+#[cfg(feature = "theme-file")]
+use crate::theme_file::{ThemeFile, THEME_FILE_CHANGED};
 use crate::window::{ImeUpdateFn, Window};
 use crate::{
-    Command, Data, Env, Event, Handled, InternalEvent, KeyEvent, PlatformError, Selector, Target,
-    TimerToken, WidgetId, WindowDesc, WindowId,
+    AuxControls, ColorBlindness, ColorScheme, Command, Data, Env, Event, Handled, InternalEvent,
+    KeyEvent, PlatformError, Selector, Target, TimerToken, WidgetId, WindowDesc, WindowId,
 };
 
 use crate::app::{PendingWindow, WindowConfig};
@@ -45,6 +50,9 @@ pub(crate) const RUN_COMMANDS_TOKEN: IdleToken = IdleToken::new(1);
 /// A token we are called back with if an external event was submitted.
 pub(crate) const EXT_EVENT_IDLE_TOKEN: IdleToken = IdleToken::new(2);
 
+/// A token we are called back with when cached resources are over budget.
+const RESOURCE_GC_IDLE_TOKEN: IdleToken = IdleToken::new(3);
+
 /// The struct implements the `druid-shell` `WinHandler` trait.
 ///
 /// One `DruidHandler` exists per window.
@@ -101,8 +109,19 @@ struct InnerAppState<T> {
     #[allow(unused)]
     menu_window: Option<WindowId>,
     pub(crate) env: Env,
+    /// The simulated color blindness, and the `Env` before it was applied.
+    unfiltered_env: Option<(ColorBlindness, Env)>,
+    /// The themes to switch between when the system color scheme changes.
+    system_themes: Option<SystemThemes>,
+    /// The theme file applied on top of the system theme.
+    #[cfg(feature = "theme-file")]
+    theme_file: Option<ThemeFile>,
     pub(crate) data: T,
     ime_focus_change: Option<Box<dyn Fn()>>,
+    /// Fonts loaded into each window as it is connected.
+    fonts: Vec<Arc<[u8]>>,
+    /// Told about changes to the data after each update.
+    subscribers: Vec<Box<dyn Subscriber<T>>>,
 }
 
 /// All active windows.
@@ -153,13 +172,20 @@ impl<T> AppHandler<T> {
 }
 
 impl<T> AppState<T> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         app: Application,
         data: T,
         env: Env,
         delegate: Option<Box<dyn AppDelegate<T>>>,
         ext_event_host: ExtEventHost,
+        fonts: Vec<Arc<[u8]>>,
+        mut subscribers: Vec<Box<dyn Subscriber<T>>>,
+        system_themes: Option<SystemThemes>,
     ) -> Self {
+        for subscriber in &mut subscribers {
+            subscriber.init(&data);
+        }
         let inner = Rc::new(RefCell::new(InnerAppState {
             app,
             delegate,
@@ -170,8 +196,14 @@ impl<T> AppState<T> {
             ext_event_host,
             data,
             env,
+            unfiltered_env: None,
+            system_themes,
+            #[cfg(feature = "theme-file")]
+            theme_file: None,
             windows: Windows::default(),
             ime_focus_change: None,
+            fonts,
+            subscribers,
         }));
 
         AppState { inner }
@@ -243,6 +275,13 @@ impl<T: Data> InnerAppState<T> {
     }
 
     fn connect(&mut self, id: WindowId, handle: WindowHandle) {
+        let mut text = handle.text();
+        for font in &self.fonts {
+            if let Err(e) = text.load_font(font) {
+                tracing::warn!("failed to load font: {}", e);
+            }
+        }
+
         self.windows
             .connect(id, handle.clone(), self.ext_event_host.make_sink());
 
@@ -336,6 +375,70 @@ impl<T: Data> InnerAppState<T> {
         }
     }
 
+    fn set_aux_controls(&mut self, controls: &AuxControls, id: WindowId) {
+        if let Some(win) = self.windows.get_mut(id) {
+            win.handle.set_aux_controls(controls.platform_controls());
+            win.aux_controls = Some(controls.clone());
+        }
+    }
+
+    fn simulate_color_blindness(&mut self, kind: Option<ColorBlindness>) {
+        if let Some((_, env)) = self.unfiltered_env.take() {
+            self.env = env;
+        }
+        if let Some(kind) = kind {
+            let filtered = kind.filter_env(&self.env);
+            self.unfiltered_env = Some((kind, std::mem::replace(&mut self.env, filtered)));
+        }
+        for win in self.windows.iter_mut() {
+            win.handle.invalidate();
+        }
+    }
+
+    /// Apply the system theme for `scheme`, if the app follows the system theme.
+    fn follow_color_scheme(&mut self, scheme: ColorScheme) {
+        let theme = match &self.system_themes {
+            Some(themes) => themes.for_scheme(scheme).clone(),
+            None => return,
+        };
+        self.apply_theme(&theme);
+        #[cfg(feature = "theme-file")]
+        if let Some(file) = &self.theme_file {
+            let theme = file.theme().clone();
+            self.apply_theme(&theme);
+        }
+    }
+
+    /// Load the theme file again if it was modified, and apply it.
+    #[cfg(feature = "theme-file")]
+    fn reload_theme_file(&mut self, modified: Option<std::time::SystemTime>) {
+        let file = match &mut self.theme_file {
+            Some(file) => file,
+            None => return,
+        };
+        if !file.reload(modified) {
+            return;
+        }
+        let theme = file.theme().clone();
+        self.apply_theme(&theme);
+        self.do_update();
+    }
+
+    fn apply_theme(&mut self, theme: &Theme) {
+        // the theme goes below any color blindness simulation
+        let simulated = self.unfiltered_env.as_ref().map(|(kind, _)| *kind);
+        self.simulate_color_blindness(None);
+        theme.apply(&mut self.env);
+        self.simulate_color_blindness(simulated);
+    }
+
+    /// The command for an auxiliary control of a window that was used.
+    fn aux_control_cmd(&self, window_id: WindowId, id: u32, value: Option<f64>) -> Option<Command> {
+        let controls = self.windows.get(window_id)?.aux_controls.as_ref()?;
+        let cmd = controls.command(id, value)?;
+        Some(cmd.default_to(Target::Window(window_id)))
+    }
+
     fn prepare_paint(&mut self, window_id: WindowId) {
         if let Some(win) = self.windows.get_mut(window_id) {
             win.prepare_paint(&mut self.command_queue, &mut self.data, &self.env);
@@ -352,6 +455,11 @@ impl<T: Data> InnerAppState<T> {
                 &self.data,
                 &self.env,
             );
+            if crate::resources::over_budget() {
+                if let Some(mut idle) = win.handle.get_idle_handle() {
+                    idle.schedule_idle(RESOURCE_GC_IDLE_TOKEN);
+                }
+            }
         }
     }
 
@@ -494,9 +602,28 @@ impl<T: Data> InnerAppState<T> {
                 }
             }
         }
+        self.notify_subscribers();
         self.invalidate_and_finalize();
     }
 
+    fn notify_subscribers(&mut self) {
+        let InnerAppState {
+            ref mut subscribers,
+            ref mut command_queue,
+            ref data,
+            ref ext_event_host,
+            ..
+        } = self;
+        let mut ctx = DelegateCtx {
+            command_queue,
+            app_data_type: TypeId::of::<T>(),
+            ext_event_host,
+        };
+        for subscriber in subscribers {
+            subscriber.check(data, &mut ctx);
+        }
+    }
+
     /// invalidate any window handles that need it.
     ///
     /// This should always be called at the end of an event update cycle,
@@ -565,6 +692,22 @@ impl<T: Data> AppState<T> {
         self.inner.borrow().env.clone()
     }
 
+    #[cfg(feature = "theme-file")]
+    pub(crate) fn set_theme_file(&self, file: ThemeFile) {
+        self.inner.borrow_mut().theme_file = Some(file);
+    }
+
+    /// Returns `true` if the window is in kiosk mode and ignores system shortcuts.
+    fn suppresses_shortcuts(&self, window_id: WindowId) -> bool {
+        let inner = self.inner.borrow();
+        inner
+            .windows
+            .get(window_id)
+            .and_then(|win| win.kiosk.as_ref())
+            .map(|kiosk| kiosk.suppress_shortcuts)
+            .unwrap_or(false)
+    }
+
     pub(crate) fn add_window(&self, id: WindowId, window: PendingWindow<T>) {
         self.inner.borrow_mut().windows.add(id, window);
     }
@@ -617,6 +760,9 @@ impl<T: Data> AppState<T> {
                 self.process_commands();
                 self.inner.borrow_mut().do_update();
             }
+            RESOURCE_GC_IDLE_TOKEN => {
+                crate::resources::collect_garbage();
+            }
             other => tracing::warn!("unexpected idle token {:?}", other),
         }
     }
@@ -668,6 +814,10 @@ impl<T: Data> AppState<T> {
         match cmd.target() {
             // these are handled the same no matter where they come from
             _ if cmd.is(sys_cmd::QUIT_APP) => self.quit(),
+            _ if cmd.is(sys_cmd::OPEN_URL) => {
+                let url: &String = cmd.get_unchecked(sys_cmd::OPEN_URL);
+                Application::open_url(url)
+            }
             #[cfg(target_os = "macos")]
             _ if cmd.is(sys_cmd::HIDE_APPLICATION) => self.hide_app(),
             #[cfg(target_os = "macos")]
@@ -683,12 +833,31 @@ impl<T: Data> AppState<T> {
                 }
             }
             _ if cmd.is(sys_cmd::CLOSE_ALL_WINDOWS) => self.request_close_all_windows(),
+            _ if cmd.is(sys_cmd::SIMULATE_COLOR_BLINDNESS) => {
+                let kind = *cmd.get_unchecked(sys_cmd::SIMULATE_COLOR_BLINDNESS);
+                self.inner.borrow_mut().simulate_color_blindness(kind);
+            }
+            _ if cmd.is(sys_cmd::COLOR_SCHEME_CHANGED) => {
+                let scheme = *cmd.get_unchecked(sys_cmd::COLOR_SCHEME_CHANGED);
+                let mut inner = self.inner.borrow_mut();
+                inner.follow_color_scheme(scheme);
+                inner.dispatch_cmd(cmd);
+            }
+            #[cfg(feature = "theme-file")]
+            _ if cmd.is(THEME_FILE_CHANGED) => {
+                let modified = *cmd.get_unchecked(THEME_FILE_CHANGED);
+                self.inner.borrow_mut().reload_theme_file(modified);
+            }
             T::Window(id) if cmd.is(sys_cmd::INVALIDATE_IME) => self.invalidate_ime(cmd, id),
             // these should come from a window
             // FIXME: we need to be able to open a file without a window handle
             T::Window(id) if cmd.is(sys_cmd::SHOW_OPEN_PANEL) => self.show_open_panel(cmd, id),
             T::Window(id) if cmd.is(sys_cmd::SHOW_SAVE_PANEL) => self.show_save_panel(cmd, id),
             T::Window(id) if cmd.is(sys_cmd::CONFIGURE_WINDOW) => self.configure_window(cmd, id),
+            T::Window(id) if cmd.is(sys_cmd::SET_AUX_CONTROLS) => {
+                let controls = cmd.get_unchecked(sys_cmd::SET_AUX_CONTROLS);
+                self.inner.borrow_mut().set_aux_controls(controls, id);
+            }
             T::Window(id) if cmd.is(sys_cmd::CLOSE_WINDOW) => {
                 if !self.inner.borrow_mut().dispatch_cmd(cmd).is_handled() {
                     self.request_close_window(id);
@@ -868,6 +1037,15 @@ impl<T: Data> AppState<T> {
         }
     }
 
+    fn handle_aux_control(&mut self, window_id: WindowId, id: u32, value: Option<f64>) {
+        let cmd = self.inner.borrow().aux_control_cmd(window_id, id, value);
+        if let Some(cmd) = cmd {
+            self.inner.borrow_mut().append_command(cmd);
+            self.process_commands();
+            self.inner.borrow_mut().do_update();
+        }
+    }
+
     fn do_paste(&mut self, window_id: WindowId) {
         let event = Event::Paste(self.inner.borrow().app.clipboard());
         self.inner.borrow_mut().do_window_event(window_id, event);
@@ -917,6 +1095,13 @@ impl<T: Data> AppState<T> {
         let data = self.data();
         let env = self.env();
 
+        // Monitors are only known once the application is running, so the
+        // kiosk geometry is resolved here rather than in `WindowDesc::kiosk`.
+        if let Some(rect) = pending.kiosk.as_ref().and_then(|k| k.monitor_rect()) {
+            builder.set_position(rect.origin());
+            builder.set_size(rect.size());
+        }
+
         pending.size_policy = config.size_policy;
         pending.title.resolve(&data, &env);
         builder.set_title(pending.title.display_text().to_string());
@@ -932,8 +1117,20 @@ impl<T: Data> AppState<T> {
         let handler = DruidHandler::new_shared((*self).clone(), id);
         builder.set_handler(Box::new(handler));
 
+        let aux_controls = pending
+            .aux_controls
+            .as_ref()
+            .map(AuxControls::platform_controls);
         self.add_window(id, pending);
-        builder.build()
+        let handle = builder.build()?;
+        if let Some(controls) = aux_controls {
+            handle.set_aux_controls(controls);
+        }
+        // The builder has no notion of input regions, so we apply it afterwards.
+        if let Some(region) = &config.input_region {
+            handle.set_input_region(Some(region.clone()));
+        }
+        Ok(handle)
     }
 }
 
@@ -974,6 +1171,10 @@ impl<T: Data> WinHandler for DruidHandler<T> {
         self.app_state.handle_system_cmd(id, Some(self.window_id));
     }
 
+    fn aux_control(&mut self, id: u32, value: Option<f64>) {
+        self.app_state.handle_aux_control(self.window_id, id, value);
+    }
+
     fn save_as(&mut self, token: FileDialogToken, file_info: Option<FileInfo>) {
         self.app_state.handle_dialog_response(token, file_info);
     }
@@ -1062,6 +1263,9 @@ impl<T: Data> WinHandler for DruidHandler<T> {
     }
 
     fn request_close(&mut self) {
+        if self.app_state.suppresses_shortcuts(self.window_id) {
+            return;
+        }
         self.app_state
             .handle_cmd(sys_cmd::CLOSE_WINDOW.to(self.window_id));
         self.app_state.process_commands();
@@ -21,10 +21,12 @@ use tracing::{error, info, trace_span};
 // Automatically defaults to std::time::Instant on non Wasm platforms
 use instant::Instant;
 
-use crate::piet::{Color, Piet, RenderContext};
-use crate::shell::{text::InputHandler, Counter, Cursor, Region, TextFieldToken, WindowHandle};
+use crate::piet::{Color, ImageFormat, Piet, RenderContext};
+use crate::shell::{
+    text::InputHandler, Counter, Cursor, CursorDesc, Region, TextFieldToken, WindowHandle,
+};
 
-use crate::app::{PendingWindow, WindowSizePolicy};
+use crate::app::{KioskMode, PendingWindow, WindowSizePolicy};
 use crate::contexts::ContextState;
 use crate::core::{CommandQueue, FocusChange, WidgetState};
 use crate::debug_state::DebugState;
@@ -33,9 +35,9 @@ use crate::text::TextFieldRegistration;
 use crate::widget::LabelText;
 use crate::win_handler::RUN_COMMANDS_TOKEN;
 use crate::{
-    BoxConstraints, Data, Env, Event, EventCtx, ExtEventSink, Handled, InternalEvent,
-    InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, PaintCtx, Point, Size, TimerToken,
-    UpdateCtx, ViewContext, Widget, WidgetId, WidgetPod,
+    AuxControls, BoxConstraints, Data, Env, Event, EventCtx, ExtEventSink, Handled, ImageBuf,
+    InternalEvent, InternalLifeCycle, LayoutCtx, LifeCycle, LifeCycleCtx, Menu, PaintCtx, Point,
+    Size, TimerToken, UpdateCtx, ViewContext, Widget, WidgetId, WidgetPod,
 };
 
 pub type ImeUpdateFn = dyn FnOnce(crate::shell::text::Event);
@@ -65,6 +67,14 @@ pub struct Window<T> {
     pub(crate) ime_handlers: Vec<(TextFieldToken, TextFieldRegistration)>,
     ext_handle: ExtEventSink,
     pub(crate) ime_focus_change: Option<Option<TextFieldToken>>,
+    pub(crate) kiosk: Option<KioskMode>,
+    pub(crate) aux_controls: Option<AuxControls>,
+    // The timer for hiding the cursor in kiosk mode, and whether it is currently hidden.
+    /// The pending timer for hiding the cursor in kiosk mode, if any.
+    cursor_timer: TimerToken,
+    /// The last time the mouse was used, for hiding the cursor in kiosk mode.
+    cursor_activity: Instant,
+    cursor_hidden: bool,
 }
 
 impl<T> Window<T> {
@@ -93,11 +103,57 @@ impl<T> Window<T> {
             ime_handlers: Vec::new(),
             ime_focus_change: None,
             pending_text_registrations: Vec::new(),
+            kiosk: pending.kiosk,
+            aux_controls: pending.aux_controls,
+            cursor_timer: TimerToken::INVALID,
+            cursor_activity: Instant::now(),
+            cursor_hidden: false,
         }
     }
 }
 
 impl<T: Data> Window<T> {
+    /// Show the cursor, and start the timer for hiding it, if the window is in kiosk mode.
+    ///
+    /// Only one timer is pending at a time, so that moving the mouse does not
+    /// request a timer per event; it is re-armed when it fires if the mouse
+    /// was used in the meantime.
+    fn reset_cursor_timer(&mut self) {
+        if let Some(delay) = self.kiosk.as_ref().and_then(|k| k.hide_cursor_after) {
+            self.cursor_hidden = false;
+            self.cursor_activity = Instant::now();
+            if self.cursor_timer == TimerToken::INVALID {
+                self.cursor_timer = self.handle.request_timer(delay);
+            }
+        }
+    }
+
+    /// Hide the cursor if the mouse was not used for long enough, or wait for the rest
+    /// of the delay otherwise.
+    fn cursor_timer_fired(&mut self) {
+        self.cursor_timer = TimerToken::INVALID;
+        if let Some(delay) = self.kiosk.as_ref().and_then(|k| k.hide_cursor_after) {
+            let idle = self.cursor_activity.elapsed();
+            if idle >= delay {
+                self.hide_cursor();
+            } else {
+                self.cursor_timer = self.handle.request_timer(delay - idle);
+            }
+        }
+    }
+
+    fn hide_cursor(&mut self) {
+        // There is no platform independent way of hiding the cursor, so we use a transparent one.
+        let image = ImageBuf::from_raw(vec![0; 4], ImageFormat::RgbaSeparate, 1, 1);
+        if let Some(cursor) = self
+            .handle
+            .make_cursor(&CursorDesc::new(image, Point::ZERO))
+        {
+            self.handle.set_cursor(&cursor);
+            self.cursor_hidden = true;
+        }
+    }
+
     /// `true` iff any child requested an animation frame since the last `AnimFrame` event.
     pub(crate) fn wants_animation_frame(&self) -> bool {
         self.root.state().request_anim
@@ -247,6 +303,19 @@ impl<T: Data> Window<T> {
             _ => (),
         }
 
+        match &event {
+            Event::WindowConnected
+            | Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::Wheel(_) => self.reset_cursor_timer(),
+            Event::Timer(token) if *token == self.cursor_timer => {
+                self.cursor_timer_fired();
+                return Handled::Yes;
+            }
+            _ => (),
+        }
+
         let event = match event {
             Event::Timer(token) => {
                 if let Some(widget_id) = self.timers.remove(&token) {
@@ -308,7 +377,9 @@ impl<T: Data> Window<T> {
             Handled::from(ctx.is_handled)
         };
 
-        if let Some(cursor) = &widget_state.cursor {
+        if self.cursor_hidden {
+            // Keep the cursor hidden until the mouse is used again.
+        } else if let Some(cursor) = &widget_state.cursor {
             self.handle.set_cursor(cursor);
         } else if matches!(
             event,